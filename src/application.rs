@@ -19,6 +19,7 @@
  */
 
 use std::cell::{BorrowError, Cell, Ref, RefCell};
+use std::collections::HashSet;
 
 use adw::{prelude::*, subclass::prelude::*};
 use gtk::{
@@ -26,11 +27,39 @@ use gtk::{
     glib::{self, g_critical, property::PropertySet},
 };
 
-use crate::{config::VERSION, i18n::i18n, magpie_client::Readings};
+use crate::{
+    config::VERSION,
+    dbus_interface::DBusInterface,
+    i18n::i18n,
+    magpie_client::{
+        Collector, CollectorIntervals, MagpieClient, Readings, Recording, RemoteHost, StreamConfig,
+        StreamFormat,
+    },
+    status_icon::StatusIcon,
+};
 
 pub const INTERVAL_STEP: f64 = 0.05;
 pub const BASE_INTERVAL: f64 = 1f64;
 
+/// A location in the UI requested from outside the app, either on the command line or through
+/// one of the exported actions
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct DeepLink {
+    /// A top level page (`performance`, `apps`, `services`) or a Performance page device
+    /// (`cpu`, `memory`, `disk`, `network`, `gpu`, `fan` or a full name like `gpu-0`)
+    pub page: Option<String>,
+    /// A process that should be selected in the Apps page
+    pub pid: Option<u32>,
+    /// The name of an app or process to search the Apps page for
+    pub process: Option<String>,
+}
+
+impl DeepLink {
+    pub fn is_empty(&self) -> bool {
+        self.page.is_none() && self.pid.is_none() && self.process.is_none()
+    }
+}
+
 #[macro_export]
 macro_rules! app {
     () => {{
@@ -55,6 +84,21 @@ mod imp {
         pub settings: Cell<Option<gio::Settings>>,
         pub sys_info: RefCell<Option<crate::magpie_client::MagpieClient>>,
         pub window: RefCell<Option<crate::MissionCenterWindow>>,
+
+        pub deep_link: RefCell<DeepLink>,
+
+        // The machine readings come from, `None` being this one
+        pub remote_host: RefCell<Option<RemoteHost>>,
+        // Whether the readings are saved to a file, or played back from one
+        pub recording: RefCell<Option<Recording>>,
+        // Set when switching hosts, Mission Center is started again with these arguments once it quits
+        pub relaunch_args: RefCell<Option<Vec<String>>>,
+
+        // Start, stop and restart entries for each favorite service, kept in sync with the settings
+        pub favorite_services_menu: gio::Menu,
+
+        pub status_icon: RefCell<Option<StatusIcon>>,
+        pub dbus_interface: RefCell<Option<DBusInterface>>,
     }
 
     impl Default for MissionCenterApplication {
@@ -63,6 +107,17 @@ mod imp {
                 settings: Cell::new(None),
                 sys_info: RefCell::new(None),
                 window: RefCell::new(None),
+
+                deep_link: RefCell::new(DeepLink::default()),
+
+                remote_host: RefCell::new(None),
+                recording: RefCell::new(None),
+                relaunch_args: RefCell::new(None),
+
+                favorite_services_menu: gio::Menu::new(),
+
+                status_icon: RefCell::new(None),
+                dbus_interface: RefCell::new(None),
             }
         }
     }
@@ -86,10 +141,167 @@ mod imp {
 
             obj.setup_gactions();
             obj.set_accels_for_action("app.quit", &["<primary>q"]);
+
+            obj.update_favorite_services_menu();
+            obj.settings()
+                .connect_changed(Some("services-page-favorites"), |_, _| {
+                    app!().update_favorite_services_menu();
+                });
+
+            obj.add_main_option(
+                "page",
+                glib::Char::from(b'p'),
+                glib::OptionFlags::NONE,
+                glib::OptionArg::String,
+                &i18n("Open a page: performance, apps, services, or a device such as cpu, memory, disk, network, gpu, fan"),
+                Some("PAGE"),
+            );
+            obj.add_main_option(
+                "pid",
+                glib::Char::from(b'\0'),
+                glib::OptionFlags::NONE,
+                glib::OptionArg::Int,
+                &i18n("Open the Apps page with the given process selected"),
+                Some("PID"),
+            );
+            obj.add_main_option(
+                "find-process",
+                glib::Char::from(b'\0'),
+                glib::OptionFlags::NONE,
+                glib::OptionArg::String,
+                &i18n("Open the Apps page searching for an app or process by name"),
+                Some("NAME"),
+            );
+            obj.add_main_option(
+                "host",
+                glib::Char::from(b'\0'),
+                glib::OptionFlags::NONE,
+                glib::OptionArg::String,
+                &i18n("Monitor a host from the Remote Hosts list in Preferences instead of this computer"),
+                Some("NAME"),
+            );
+            obj.add_main_option(
+                "record",
+                glib::Char::from(b'\0'),
+                glib::OptionFlags::NONE,
+                glib::OptionArg::Filename,
+                &i18n("Save the readings to a file, to play them back later"),
+                Some("FILE"),
+            );
+            obj.add_main_option(
+                "replay",
+                glib::Char::from(b'\0'),
+                glib::OptionFlags::NONE,
+                glib::OptionArg::Filename,
+                &i18n("Play back readings saved with --record instead of monitoring a computer"),
+                Some("FILE"),
+            );
+            obj.add_main_option(
+                "replay-speed",
+                glib::Char::from(b'\0'),
+                glib::OptionFlags::NONE,
+                glib::OptionArg::Double,
+                &i18n("How many times faster than recorded to play the readings back"),
+                Some("FACTOR"),
+            );
         }
     }
 
     impl ApplicationImpl for MissionCenterApplication {
+        fn dbus_register(
+            &self,
+            connection: &gio::DBusConnection,
+            object_path: &str,
+        ) -> Result<(), glib::Error> {
+            self.parent_dbus_register(connection, object_path)?;
+
+            self.dbus_interface
+                .replace(Some(DBusInterface::new(connection, object_path)?));
+
+            Ok(())
+        }
+
+        fn dbus_unregister(&self, connection: &gio::DBusConnection, object_path: &str) {
+            self.dbus_interface.take();
+
+            self.parent_dbus_unregister(connection, object_path);
+        }
+
+        fn handle_local_options(
+            &self,
+            options: &glib::VariantDict,
+        ) -> std::ops::ControlFlow<glib::ExitCode> {
+            // Validate the arguments in the launching process, so errors are reported where the
+            // user can see them, before they get forwarded to an already running instance
+            match super::deep_link_from_options(options)
+                .and_then(|_| super::remote_host_from_options(options, &self.obj().remote_hosts()))
+                .and_then(|_| super::recording_from_options(options))
+            {
+                Ok(_) => std::ops::ControlFlow::Continue(()),
+                Err(e) => {
+                    eprintln!("{}", e);
+                    std::ops::ControlFlow::Break(glib::ExitCode::FAILURE)
+                }
+            }
+        }
+
+        fn shutdown(&self) {
+            // `main` leaves through `std::process::exit`, which runs no destructors; stop the
+            // gatherer here so it gets to hand the fans and CPU weights it changed back
+            drop(self.sys_info.take());
+
+            self.parent_shutdown();
+        }
+
+        fn command_line(&self, command_line: &gio::ApplicationCommandLine) -> glib::ExitCode {
+            let deep_link = match super::deep_link_from_options(&command_line.options_dict()) {
+                Ok(deep_link) => deep_link,
+                Err(e) => {
+                    g_critical!("MissionCenter::Application", "{}", e);
+                    return glib::ExitCode::FAILURE;
+                }
+            };
+
+            let application = self.obj();
+
+            let remote_host = match super::remote_host_from_options(
+                &command_line.options_dict(),
+                &application.remote_hosts(),
+            ) {
+                Ok(remote_host) => remote_host,
+                Err(e) => {
+                    g_critical!("MissionCenter::Application", "{}", e);
+                    return glib::ExitCode::FAILURE;
+                }
+            };
+            let recording = match super::recording_from_options(&command_line.options_dict()) {
+                Ok(recording) => recording,
+                Err(e) => {
+                    g_critical!("MissionCenter::Application", "{}", e);
+                    return glib::ExitCode::FAILURE;
+                }
+            };
+            // The gatherer is started along with the main window, and stays connected to the same
+            // host for as long as Mission Center runs
+            if application.window().is_none() {
+                self.remote_host.replace(remote_host);
+                self.recording.replace(recording);
+            } else if remote_host.is_some() && remote_host != *self.remote_host.borrow() {
+                glib::g_warning!(
+                    "MissionCenter::Application",
+                    "Mission Center is already running, use the host switcher in the sidebar to change hosts"
+                );
+            }
+
+            // Either creates the main window or brings the existing one to the front
+            application.activate();
+            if !deep_link.is_empty() {
+                application.open_deep_link(deep_link);
+            }
+
+            glib::ExitCode::SUCCESS
+        }
+
         fn activate(&self) {
             use gtk::glib::*;
 
@@ -101,7 +313,11 @@ mod imp {
                 let settings = unsafe { self.settings.take().unwrap_unchecked() };
                 self.settings.set(Some(settings.clone()));
 
-                let sys_info = crate::magpie_client::MagpieClient::new();
+                let sys_info = crate::magpie_client::MagpieClient::new(
+                    self.remote_host.borrow().clone(),
+                    self.recording.borrow().clone(),
+                    settings.boolean("gatherer-idle-priority"),
+                );
 
                 let window = crate::MissionCenterWindow::new(&*application, &settings, &sys_info);
 
@@ -154,6 +370,21 @@ mod imp {
 
                 window.set_maximized(settings.boolean("is-maximized"));
 
+                // Running in the background, closing the window only hides it and the readings
+                // keep coming in; the status icon, or opening Mission Center again, brings it back
+                window.connect_close_request(|window| {
+                    if app!().runs_in_background() {
+                        window.set_visible(false);
+                        return Propagation::Stop;
+                    }
+
+                    Propagation::Proceed
+                });
+                window.connect_visible_notify(|_| app!().update_background_interval());
+                settings.connect_changed(Some("background-update-interval"), |_, _| {
+                    app!().update_background_interval();
+                });
+
                 sys_info.set_core_count_affects_percentages(
                     settings.boolean("apps-page-core-count-affects-percentages"),
                 );
@@ -179,8 +410,107 @@ mod imp {
                     },
                 );
 
+                sys_info.set_smooth_cpu_usage(settings.boolean("apps-page-smooth-cpu-usage"));
+                settings.connect_changed(Some("apps-page-smooth-cpu-usage"), |settings, _| {
+                    match app!().sys_info() {
+                        Ok(sys_info) => {
+                            sys_info.set_smooth_cpu_usage(
+                                settings.boolean("apps-page-smooth-cpu-usage"),
+                            );
+                        }
+                        Err(e) => {
+                            g_critical!(
+                                "MissionCenter",
+                                "Failed to get sys_info from MissionCenterApplication: {}",
+                                e
+                            );
+                        }
+                    };
+                });
+
+                sys_info.set_foreground_boost(settings.boolean("apps-page-foreground-boost"));
+                settings.connect_changed(Some("apps-page-foreground-boost"), |settings, _| {
+                    match app!().sys_info() {
+                        Ok(sys_info) => {
+                            sys_info.set_foreground_boost(
+                                settings.boolean("apps-page-foreground-boost"),
+                            );
+                        }
+                        Err(e) => {
+                            g_critical!(
+                                "MissionCenter",
+                                "Failed to get sys_info from MissionCenterApplication: {}",
+                                e
+                            );
+                        }
+                    };
+                });
+
+                sys_info.set_metrics_stream(metrics_stream_config(&settings));
+                for key in [
+                    "metrics-stream-enabled",
+                    "metrics-stream-format",
+                    "metrics-stream-target",
+                    "metrics-stream-interval",
+                ] {
+                    settings.connect_changed(Some(key), |settings, _| match app!().sys_info() {
+                        Ok(sys_info) => {
+                            sys_info.set_metrics_stream(metrics_stream_config(settings));
+                        }
+                        Err(e) => {
+                            g_critical!(
+                                "MissionCenter",
+                                "Failed to get sys_info from MissionCenterApplication: {}",
+                                e
+                            );
+                        }
+                    });
+                }
+
+                sys_info.set_disabled_collectors(disabled_collectors(&settings));
+                settings.connect_changed(Some("disabled-collectors"), |settings, _| {
+                    match app!().sys_info() {
+                        Ok(sys_info) => {
+                            sys_info.set_disabled_collectors(disabled_collectors(settings));
+                        }
+                        Err(e) => {
+                            g_critical!(
+                                "MissionCenter",
+                                "Failed to get sys_info from MissionCenterApplication: {}",
+                                e
+                            );
+                        }
+                    };
+                });
+
+                sys_info.set_collector_intervals(collector_intervals(&settings));
+                settings.connect_changed(Some("collector-intervals"), |settings, _| {
+                    match app!().sys_info() {
+                        Ok(sys_info) => {
+                            sys_info.set_collector_intervals(collector_intervals(settings));
+                        }
+                        Err(e) => {
+                            g_critical!(
+                                "MissionCenter",
+                                "Failed to get sys_info from MissionCenterApplication: {}",
+                                e
+                            );
+                        }
+                    };
+                });
+
                 self.sys_info.set(Some(sys_info));
 
+                application.update_status_icon();
+                settings.connect_changed(Some("status-icon-enabled"), |_, _| {
+                    app!().update_status_icon();
+                });
+
+                apply_reduce_motion(&settings);
+                settings.connect_changed(Some("window-reduce-motion"), |settings, _| {
+                    apply_reduce_motion(settings);
+                });
+
                 let provider = gtk::CssProvider::new();
                 provider.load_from_bytes(&Bytes::from_static(include_bytes!(
                     "../resources/ui/style.css"
@@ -199,6 +529,11 @@ mod imp {
 
             self.window
                 .set(window.downcast_ref::<crate::MissionCenterWindow>().cloned());
+
+            let deep_link = self.deep_link.take();
+            if !deep_link.is_empty() {
+                application.open_deep_link(deep_link);
+            }
         }
     }
 
@@ -242,6 +577,13 @@ impl MissionCenterApplication {
             return;
         };
 
+        if let Some(status_icon) = self.imp().status_icon.borrow().as_ref() {
+            status_icon.update(&readings);
+        }
+        if let Some(dbus_interface) = self.imp().dbus_interface.borrow().as_ref() {
+            dbus_interface.update(&readings);
+        }
+
         window.set_initial_readings(readings)
     }
 
@@ -270,6 +612,13 @@ impl MissionCenterApplication {
             return false;
         };
 
+        if let Some(status_icon) = self.imp().status_icon.borrow().as_ref() {
+            status_icon.update(readings);
+        }
+        if let Some(dbus_interface) = self.imp().dbus_interface.borrow().as_ref() {
+            dbus_interface.update(readings);
+        }
+
         window.update_readings(readings)
     }
 
@@ -303,10 +652,230 @@ impl MissionCenterApplication {
         }
     }
 
+    pub fn remote_host(&self) -> Option<RemoteHost> {
+        self.imp().remote_host.borrow().clone()
+    }
+
+    pub fn remote_hosts(&self) -> Vec<RemoteHost> {
+        RemoteHost::parse_list(&self.settings().string("remote-hosts"))
+    }
+
+    pub fn set_remote_hosts(&self, hosts: &[RemoteHost]) {
+        let hosts = hosts
+            .iter()
+            .map(|host| host.to_string())
+            .collect::<Vec<_>>()
+            .join(";");
+        if let Err(e) = self.settings().set_string("remote-hosts", &hosts) {
+            g_critical!(
+                "MissionCenter::Application",
+                "Failed to save remote hosts: {}",
+                e
+            );
+        }
+    }
+
+    /// Every page is built around the devices of the machine being monitored, so instead of
+    /// tearing all of them down Mission Center quits and starts again, connected to `host`
+    pub fn switch_remote_host(&self, host: Option<&RemoteHost>) {
+        let mut args = vec![];
+        if let Some(host) = host {
+            args.push("--host".to_owned());
+            args.push(host.name.clone());
+        }
+
+        self.imp().relaunch_args.replace(Some(args));
+        self.quit();
+    }
+
+    /// The arguments to start Mission Center again with, if a host switch was requested; also stops
+    /// the gatherer, so the new instance doesn't race the old one for its socket
+    pub fn take_relaunch_args(&self) -> Option<Vec<String>> {
+        let args = self.imp().relaunch_args.take()?;
+        drop(self.imp().sys_info.take());
+
+        Some(args)
+    }
+
+    /// The services picked for quick actions, in the order they were added
+    pub fn favorite_services(&self) -> Vec<String> {
+        self.settings()
+            .string("services-page-favorites")
+            .split(';')
+            .filter(|service| !service.is_empty())
+            .map(|service| service.to_owned())
+            .collect()
+    }
+
+    pub fn set_favorite_service(&self, service: &str, favorite: bool) {
+        let mut favorites = self.favorite_services();
+        let is_favorite = favorites.iter().any(|s| s == service);
+        if favorite == is_favorite {
+            return;
+        }
+
+        if favorite {
+            favorites.push(service.to_owned());
+        } else {
+            favorites.retain(|s| s != service);
+        }
+
+        if let Err(e) = self
+            .settings()
+            .set_string("services-page-favorites", &favorites.join(";"))
+        {
+            g_critical!(
+                "MissionCenter::Application",
+                "Failed to save favorite services: {}",
+                e
+            );
+        }
+    }
+
+    /// A submenu for each favorite service, with entries activating `app.service-start`,
+    /// `app.service-stop` and `app.service-restart`
+    pub fn favorite_services_menu(&self) -> gio::MenuModel {
+        self.imp().favorite_services_menu.clone().upcast()
+    }
+
+    fn update_favorite_services_menu(&self) {
+        let menu = &self.imp().favorite_services_menu;
+        menu.remove_all();
+
+        for service in self.favorite_services() {
+            let target = service.to_variant();
+
+            let actions = gio::Menu::new();
+            for (label, action) in [
+                (i18n("Start"), "app.service-start"),
+                (i18n("Stop"), "app.service-stop"),
+                (i18n("Restart"), "app.service-restart"),
+            ] {
+                let item = gio::MenuItem::new(Some(&label), None);
+                item.set_action_and_target_value(Some(action), Some(&target));
+                actions.append_item(&item);
+            }
+
+            menu.append_submenu(Some(&service), &actions);
+        }
+
+        if let Some(status_icon) = self.imp().status_icon.borrow().as_ref() {
+            status_icon.update_menu();
+        }
+    }
+
+    /// Whether closing the main window leaves Mission Center running
+    pub fn runs_in_background(&self) -> bool {
+        self.settings().boolean("run-in-background")
+            || self
+                .imp()
+                .status_icon
+                .borrow()
+                .as_ref()
+                .is_some_and(|status_icon| status_icon.is_registered())
+    }
+
+    /// Gather less, and less often, while nobody is looking
+    fn update_background_interval(&self) {
+        let interval = self
+            .window()
+            .filter(|window| !window.is_visible())
+            .map(|_| {
+                std::time::Duration::from_secs(
+                    self.settings().int("background-update-interval").max(1) as u64,
+                )
+            });
+
+        match self.sys_info() {
+            Ok(sys_info) => sys_info.set_background_interval(interval),
+            Err(e) => {
+                g_critical!(
+                    "MissionCenter::Application",
+                    "Failed to get sys_info from MissionCenterApplication: {}",
+                    e
+                );
+            }
+        }
+    }
+
+    fn update_status_icon(&self) {
+        let imp = self.imp();
+
+        if !self.settings().boolean("status-icon-enabled") {
+            if imp.status_icon.take().is_some() && !self.runs_in_background() {
+                // Without the icon there is no way back to a hidden window
+                if let Some(window) = self.window().filter(|window| !window.is_visible()) {
+                    window.present();
+                }
+            }
+            return;
+        }
+
+        if imp.status_icon.borrow().is_some() {
+            return;
+        }
+
+        match StatusIcon::new(self.status_icon_menu().upcast()) {
+            Ok(status_icon) => {
+                imp.status_icon.replace(Some(status_icon));
+            }
+            Err(e) => {
+                g_critical!(
+                    "MissionCenter::Application",
+                    "Failed to create the status icon: {}",
+                    e
+                );
+            }
+        }
+    }
+
+    fn status_icon_menu(&self) -> gio::Menu {
+        let menu = gio::Menu::new();
+
+        let window = gio::Menu::new();
+        window.append(Some(&i18n("Show Mission Center")), Some("app.show-window"));
+        menu.append_section(None, &window);
+
+        let pages = gio::Menu::new();
+        for (label, page) in [
+            (i18n("Performance"), "performance"),
+            (i18n("Apps"), "apps"),
+            (i18n("Services"), "services"),
+            (i18n("Users"), "users"),
+        ] {
+            let item = gio::MenuItem::new(Some(&label), None);
+            item.set_action_and_target_value(Some("app.show-page"), Some(&page.to_variant()));
+            pages.append_item(&item);
+        }
+        menu.append_section(None, &pages);
+
+        let services = gio::Menu::new();
+        services.append_submenu(
+            Some(&i18n("Favorite Services")),
+            &self.favorite_services_menu(),
+        );
+        menu.append_section(None, &services);
+
+        let quit = gio::Menu::new();
+        quit.append(Some(&i18n("Quit")), Some("app.quit"));
+        menu.append_section(None, &quit);
+
+        menu
+    }
+
     pub fn window(&self) -> Option<crate::MissionCenterWindow> {
         unsafe { &*self.imp().window.as_ptr() }.clone()
     }
 
+    pub fn open_deep_link(&self, deep_link: DeepLink) {
+        let Some(window) = self.window() else {
+            self.imp().deep_link.replace(deep_link);
+            return;
+        };
+
+        window.open_deep_link(deep_link);
+    }
+
     fn setup_gactions(&self) {
         let quit_action = gio::ActionEntry::builder("quit")
             .activate(move |app: &Self, _, _| app.quit())
@@ -316,19 +885,98 @@ impl MissionCenterApplication {
                 app.show_preferences();
             })
             .build();
+        let show_window_action = gio::ActionEntry::builder("show-window")
+            .activate(move |app: &Self, _, _| app.activate())
+            .build();
         let about_action = gio::ActionEntry::builder("about")
             .activate(move |app: &Self, _, _| app.show_about())
             .build();
         let keyboard_shortcuts_action = gio::ActionEntry::builder("keyboard-shortcuts")
             .activate(move |app: &Self, _, _| app.show_keyboard_shortcuts())
             .build();
+        let show_page_action = gio::ActionEntry::builder("show-page")
+            .parameter_type(Some(glib::VariantTy::STRING))
+            .activate(move |app: &Self, _, param| {
+                let Some(page) = param.and_then(|p| p.get::<String>()) else {
+                    return;
+                };
+                app.activate();
+                app.open_deep_link(DeepLink {
+                    page: Some(page),
+                    ..Default::default()
+                });
+            })
+            .build();
+        let show_process_action = gio::ActionEntry::builder("show-process")
+            .parameter_type(Some(glib::VariantTy::UINT32))
+            .activate(move |app: &Self, _, param| {
+                let Some(pid) = param.and_then(|p| p.get::<u32>()) else {
+                    return;
+                };
+                app.activate();
+                app.open_deep_link(DeepLink {
+                    pid: Some(pid),
+                    ..Default::default()
+                });
+            })
+            .build();
+        let find_process_action = gio::ActionEntry::builder("find-process")
+            .parameter_type(Some(glib::VariantTy::STRING))
+            .activate(move |app: &Self, _, param| {
+                let Some(name) = param.and_then(|p| p.get::<String>()) else {
+                    return;
+                };
+                if name.trim().is_empty() {
+                    return;
+                }
+                app.activate();
+                app.open_deep_link(DeepLink {
+                    process: Some(name.trim().to_owned()),
+                    ..Default::default()
+                });
+            })
+            .build();
+
+        let service_actions = [
+            (
+                "service-start",
+                MagpieClient::start_service as fn(&MagpieClient, String),
+            ),
+            ("service-stop", MagpieClient::stop_service),
+            ("service-restart", MagpieClient::restart_service),
+        ]
+        .map(|(name, request)| {
+            gio::ActionEntry::builder(name)
+                .parameter_type(Some(glib::VariantTy::STRING))
+                .activate(move |app: &Self, _, param| {
+                    let Some(service) = param.and_then(|p| p.get::<String>()) else {
+                        return;
+                    };
+                    match app.sys_info() {
+                        Ok(sys_info) => request(&sys_info, service),
+                        Err(e) => {
+                            g_critical!(
+                                "MissionCenter::Application",
+                                "Failed to get sys_info from MissionCenterApplication: {}",
+                                e
+                            );
+                        }
+                    }
+                })
+                .build()
+        });
 
         self.add_action_entries([
             quit_action,
             preferences_action,
             about_action,
+            show_window_action,
             keyboard_shortcuts_action,
+            show_page_action,
+            show_process_action,
+            find_process_action,
         ]);
+        self.add_action_entries(service_actions);
 
         self.set_accels_for_action("app.preferences", &["<Control>comma"]);
         self.set_accels_for_action("app.keyboard-shortcuts", &["<Control>question"]);
@@ -436,3 +1084,154 @@ impl MissionCenterApplication {
         about.present(Some(&window));
     }
 }
+
+// Turning off GTK's animations covers everything, from stack transitions to drag-and-drop; going
+// back to the system value, instead of forcing them on, keeps the system preference respected
+fn apply_reduce_motion(settings: &gio::Settings) {
+    let Some(gtk_settings) = gtk::Settings::default() else {
+        return;
+    };
+
+    if settings.boolean("window-reduce-motion") {
+        gtk_settings.set_gtk_enable_animations(false);
+    } else {
+        gtk_settings.reset_property("gtk-enable-animations");
+    }
+}
+
+fn metrics_stream_config(settings: &gio::Settings) -> Option<StreamConfig> {
+    let target = settings.string("metrics-stream-target");
+    if !settings.boolean("metrics-stream-enabled") || target.trim().is_empty() {
+        return None;
+    }
+
+    Some(StreamConfig {
+        format: StreamFormat::from_id(&settings.string("metrics-stream-format"))?,
+        target: target.trim().to_owned(),
+        interval: std::time::Duration::from_secs(
+            settings.int("metrics-stream-interval").max(1) as u64
+        ),
+    })
+}
+
+// Intervals are stored as `collector=seconds` entries separated by `;`, collectors read on every
+// update are left out
+pub fn collector_intervals(settings: &gio::Settings) -> CollectorIntervals {
+    settings
+        .string("collector-intervals")
+        .split(';')
+        .filter_map(|entry| entry.split_once('='))
+        .filter_map(|(collector, seconds)| {
+            Some((
+                Collector::from_id(collector)?,
+                std::time::Duration::from_secs(seconds.parse::<u64>().ok()?),
+            ))
+        })
+        .filter(|(_, interval)| !interval.is_zero())
+        .collect()
+}
+
+pub fn disabled_collectors(settings: &gio::Settings) -> HashSet<Collector> {
+    settings
+        .string("disabled-collectors")
+        .split(';')
+        .filter_map(Collector::from_id)
+        .collect()
+}
+
+pub fn set_disabled_collectors(
+    settings: &gio::Settings,
+    disabled: &HashSet<Collector>,
+) -> Result<(), glib::BoolError> {
+    // In a stable order, so the setting doesn't change when the collectors don't
+    let ids = Collector::ALL
+        .iter()
+        .filter(|collector| disabled.contains(collector))
+        .map(|collector| collector.id())
+        .collect::<Vec<_>>();
+    settings.set_string("disabled-collectors", &ids.join(";"))
+}
+
+fn remote_host_from_options(
+    options: &glib::VariantDict,
+    hosts: &[RemoteHost],
+) -> Result<Option<RemoteHost>, String> {
+    let Some(name) = options
+        .lookup::<String>("host")
+        .map_err(|e| format!("Invalid value for --host: {}", e))?
+        .filter(|name| !name.trim().is_empty())
+    else {
+        return Ok(None);
+    };
+
+    hosts
+        .iter()
+        .find(|host| host.name == name.trim())
+        .cloned()
+        .map(Some)
+        .ok_or_else(|| format!("Unknown remote host: {}", name))
+}
+
+fn recording_from_options(options: &glib::VariantDict) -> Result<Option<Recording>, String> {
+    let record = options
+        .lookup::<std::path::PathBuf>("record")
+        .map_err(|e| format!("Invalid value for --record: {}", e))?;
+    let replay = options
+        .lookup::<std::path::PathBuf>("replay")
+        .map_err(|e| format!("Invalid value for --replay: {}", e))?;
+    let speed = options
+        .lookup::<f64>("replay-speed")
+        .map_err(|e| format!("Invalid value for --replay-speed: {}", e))?;
+
+    match (record, replay) {
+        (Some(_), Some(_)) => Err("--record and --replay can't be used together".to_owned()),
+        (Some(path), None) => {
+            if speed.is_some() {
+                return Err("--replay-speed only applies to --replay".to_owned());
+            }
+            Ok(Some(Recording::Record(path)))
+        }
+        (None, Some(path)) => {
+            let speed = speed.unwrap_or(1.);
+            if !speed.is_finite() || speed <= 0. {
+                return Err(format!("Invalid value for --replay-speed: {}", speed));
+            }
+            if !path.is_file() {
+                return Err(format!("No such recording: {}", path.display()));
+            }
+            Ok(Some(Recording::Replay {
+                path,
+                speed: speed as f32,
+            }))
+        }
+        (None, None) if speed.is_some() => {
+            Err("--replay-speed only applies to --replay".to_owned())
+        }
+        (None, None) => Ok(None),
+    }
+}
+
+fn deep_link_from_options(options: &glib::VariantDict) -> Result<DeepLink, String> {
+    let page = options
+        .lookup::<String>("page")
+        .map_err(|e| format!("Invalid value for --page: {}", e))?
+        .map(|page| page.trim().to_lowercase())
+        .filter(|page| !page.is_empty());
+
+    let pid = match options
+        .lookup::<i32>("pid")
+        .map_err(|e| format!("Invalid value for --pid: {}", e))?
+    {
+        Some(pid) if pid > 0 => Some(pid as u32),
+        Some(pid) => return Err(format!("Invalid value for --pid: {}", pid)),
+        None => None,
+    };
+
+    let process = options
+        .lookup::<String>("find-process")
+        .map_err(|e| format!("Invalid value for --find-process: {}", e))?
+        .map(|name| name.trim().to_owned())
+        .filter(|name| !name.is_empty());
+
+    Ok(DeepLink { page, pid, process })
+}
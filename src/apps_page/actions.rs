@@ -27,9 +27,13 @@ use gtk::prelude::*;
 use gtk::subclass::prelude::*;
 
 use crate::app;
+use crate::window::RecentItem;
 
+use super::batch_stop;
 use super::details_dialog::DetailsDialog;
 use super::imp::AppsPage as AppsPageImp;
+use super::memory_dump;
+use super::process_properties;
 use super::row_model::{ContentType, RowModel};
 use super::AppsPage;
 use super::{select_item, upgrade_weak_ptr};
@@ -85,9 +89,16 @@ pub fn configure(imp: &AppsPageImp) {
             let Some(this) = this.upgrade() else {
                 return;
             };
-            let this = this.imp();
+            let imp = this.imp();
 
-            let selected_item = this.selected_item.borrow();
+            // Several apps and processes are stopped together, after asking once for all of them
+            if imp.selected_items.borrow().len() > 1 {
+                let selected_items = imp.selected_items.borrow().clone();
+                batch_stop::stop(&this, &selected_items, false);
+                return;
+            }
+
+            let selected_item = imp.selected_item.borrow();
             if selected_item.content_type() == ContentType::SectionHeader {
                 return;
             }
@@ -110,9 +121,15 @@ pub fn configure(imp: &AppsPageImp) {
             let Some(this) = this.upgrade() else {
                 return;
             };
-            let this = this.imp();
+            let imp = this.imp();
 
-            let selected_item = this.selected_item.borrow();
+            if imp.selected_items.borrow().len() > 1 {
+                let selected_items = imp.selected_items.borrow().clone();
+                batch_stop::stop(&this, &selected_items, true);
+                return;
+            }
+
+            let selected_item = imp.selected_item.borrow();
             if selected_item.content_type() == ContentType::SectionHeader {
                 return;
             }
@@ -292,12 +309,82 @@ pub fn configure(imp: &AppsPageImp) {
                 return;
             }
 
-            let details_dialog = DetailsDialog::new(imp.selected_item.borrow().clone());
+            if let Some(window) = app!().window() {
+                window.add_recent_item(RecentItem::Process(selected_item.name().to_string()));
+            }
+
+            let app_sandboxes = imp.app_sandboxes.borrow();
+            let sandbox = match selected_item.content_type() {
+                ContentType::App => app_sandboxes.get(selected_item.id().as_str()),
+                _ => None,
+            };
+            let details_dialog = DetailsDialog::new(
+                imp.selected_item.borrow().clone(),
+                &imp.service_states.borrow(),
+                sandbox,
+            );
             details_dialog.present(Some(&this));
         }
     });
     actions.add_action(&imp.action_details);
 
+    imp.action_memory_dump.set_enabled(false);
+    imp.action_memory_dump.connect_activate({
+        let this = this.downgrade();
+        move |_action, _| {
+            let Some(this) = this.upgrade() else {
+                return;
+            };
+            let imp = this.imp();
+
+            let selected_item = imp.selected_item.borrow().clone();
+            if selected_item.content_type() != ContentType::Process {
+                return;
+            }
+
+            memory_dump::capture(&this, &selected_item);
+        }
+    });
+    actions.add_action(&imp.action_memory_dump);
+
+    imp.action_open_location.set_enabled(false);
+    imp.action_open_location.connect_activate({
+        let this = this.downgrade();
+        move |_action, _| {
+            let Some(this) = this.upgrade() else {
+                return;
+            };
+            let imp = this.imp();
+
+            let selected_item = imp.selected_item.borrow().clone();
+            if selected_item.content_type() != ContentType::Process {
+                return;
+            }
+
+            process_properties::open_location(&this, &selected_item);
+        }
+    });
+    actions.add_action(&imp.action_open_location);
+
+    imp.action_properties.set_enabled(false);
+    imp.action_properties.connect_activate({
+        let this = this.downgrade();
+        move |_action, _| {
+            let Some(this) = this.upgrade() else {
+                return;
+            };
+            let imp = this.imp();
+
+            let selected_item = imp.selected_item.borrow().clone();
+            if selected_item.content_type() != ContentType::Process {
+                return;
+            }
+
+            process_properties::show(&this, &selected_item);
+        }
+    });
+    actions.add_action(&imp.action_properties);
+
     let action = gio::SimpleAction::new("collapse-all", None);
     action.connect_activate({
         let this = this.downgrade();
@@ -310,7 +397,7 @@ pub fn configure(imp: &AppsPageImp) {
             let Some(selection_model) = imp
                 .column_view
                 .model()
-                .and_then(|model| model.downcast::<gtk::SingleSelection>().ok())
+                .and_then(|model| model.downcast::<gtk::MultiSelection>().ok())
             else {
                 g_critical!(
                     "MissionCenter::AppsPage",
@@ -398,7 +485,7 @@ fn calculate_anchor_point(
     }
 }
 
-fn app_pids(row_model: &RowModel) -> Vec<u32> {
+pub(super) fn app_pids(row_model: &RowModel) -> Vec<u32> {
     let children = row_model.children();
     let mut result = Vec::with_capacity(children.n_items() as usize);
 
@@ -0,0 +1,248 @@
+/* apps_page/app_history.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::time::Duration;
+
+use adw::prelude::*;
+use gtk::{gdk, glib, glib::g_warning, subclass::prelude::*};
+
+use crate::{
+    app,
+    i18n::*,
+    magpie_client::{AppUsage, AppUsageHistory},
+    settings, DataType,
+};
+
+/// The totals change slowly, there is no point in rebuilding the list with every reading
+const REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum SortBy {
+    CpuTime,
+    Drive,
+    Network,
+}
+
+impl SortBy {
+    fn from_index(index: u32) -> Self {
+        match index {
+            1 => SortBy::Drive,
+            2 => SortBy::Network,
+            _ => SortBy::CpuTime,
+        }
+    }
+
+    fn value(&self, usage: &AppUsage) -> f64 {
+        match self {
+            SortBy::CpuTime => usage.cpu_seconds,
+            SortBy::Drive => usage.disk_bytes,
+            SortBy::Network => usage.network_bytes,
+        }
+    }
+}
+
+mod imp {
+    use super::*;
+
+    #[derive(gtk::CompositeTemplate)]
+    #[template(resource = "/io/missioncenter/MissionCenter/ui/apps_page/app_history.ui")]
+    pub struct AppHistory {
+        #[template_child]
+        pub since: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub sort_by: TemplateChild<gtk::DropDown>,
+        #[template_child]
+        pub app_list: TemplateChild<gtk::ListBox>,
+    }
+
+    impl Default for AppHistory {
+        fn default() -> Self {
+            Self {
+                since: TemplateChild::default(),
+                sort_by: TemplateChild::default(),
+                app_list: TemplateChild::default(),
+            }
+        }
+    }
+
+    impl AppHistory {
+        pub fn refresh(&self) {
+            let history = match app!().sys_info() {
+                Ok(sys_info) => sys_info.app_usage(),
+                Err(e) => {
+                    g_warning!("MissionCenter::AppsPage", "Failed to get `sys_info`: {}", e);
+                    return;
+                }
+            };
+
+            if let Some(history) = history {
+                self.show_history(history);
+            }
+        }
+
+        fn show_history(&self, mut history: AppUsageHistory) {
+            let since = glib::DateTime::from_unix_local(history.since)
+                .and_then(|time| time.format("%x %R"))
+                .map(|time| time.to_string())
+                .unwrap_or_default();
+            self.since.set_text(&i18n_f(
+                "Used since {}, when Mission Center started; apps are only counted while it runs, with or without its window",
+                &[&since],
+            ));
+
+            let sort_by = SortBy::from_index(self.sort_by.selected());
+            history.apps.sort_by(|a, b| {
+                sort_by
+                    .value(b)
+                    .total_cmp(&sort_by.value(a))
+                    .then_with(|| a.name.cmp(&b.name))
+            });
+
+            self.app_list.remove_all();
+            for usage in &history.apps {
+                self.app_list.append(&row(usage, sort_by));
+            }
+        }
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for AppHistory {
+        const NAME: &'static str = "AppHistory";
+        type Type = super::AppHistory;
+        type ParentType = gtk::Box;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for AppHistory {
+        fn constructed(&self) {
+            self.parent_constructed();
+
+            let placeholder = gtk::Label::new(Some(&i18n("No apps have run yet")));
+            placeholder.add_css_class("dim-label");
+            placeholder.set_margin_top(20);
+            placeholder.set_margin_bottom(20);
+            self.app_list.set_placeholder(Some(&placeholder));
+
+            self.sort_by.connect_selected_notify({
+                let this = self.obj().downgrade();
+                move |_| {
+                    if let Some(this) = this.upgrade() {
+                        this.imp().refresh();
+                    }
+                }
+            });
+
+            // Only asked for while the tab is shown, right away when it is switched to
+            self.obj().connect_map(|this| this.imp().refresh());
+            glib::timeout_add_local(REFRESH_INTERVAL, {
+                let this = self.obj().downgrade();
+                move || {
+                    let Some(this) = this.upgrade() else {
+                        return glib::ControlFlow::Break;
+                    };
+                    if this.is_mapped() {
+                        this.imp().refresh();
+                    }
+
+                    glib::ControlFlow::Continue
+                }
+            });
+        }
+    }
+
+    impl WidgetImpl for AppHistory {}
+
+    impl BoxImpl for AppHistory {}
+}
+
+fn row(usage: &AppUsage, sort_by: SortBy) -> adw::ActionRow {
+    let cpu_time = format_cpu_time(usage.cpu_seconds);
+    let drive =
+        crate::to_human_readable_nice(usage.disk_bytes as f32, &DataType::DriveBytes, &settings!());
+    let network = crate::to_human_readable_nice(
+        usage.network_bytes as f32,
+        &DataType::NetworkBytes,
+        &settings!(),
+    );
+
+    let mut parts = vec![];
+    if !usage.running {
+        parts.push(i18n("Not running"));
+    }
+    parts.push(i18n_f("CPU {}", &[&cpu_time]));
+    parts.push(i18n_f("Drive {}", &[&drive]));
+    parts.push(i18n_f("Network {}", &[&network]));
+
+    let row = adw::ActionRow::builder()
+        .title(&usage.name)
+        .subtitle(parts.join(" · "))
+        .use_markup(false)
+        .build();
+    row.add_prefix(&icon(usage.icon.as_deref()));
+
+    let total = gtk::Label::new(Some(&match sort_by {
+        SortBy::CpuTime => cpu_time,
+        SortBy::Drive => drive,
+        SortBy::Network => network,
+    }));
+    total.add_css_class("numeric");
+    row.add_suffix(&total);
+
+    row
+}
+
+fn icon(icon: Option<&str>) -> gtk::Image {
+    let image = match icon {
+        Some(path) if path.starts_with('/') => gtk::Image::from_file(path),
+        Some(name)
+            if gdk::Display::default()
+                .is_some_and(|display| gtk::IconTheme::for_display(&display).has_icon(name)) =>
+        {
+            gtk::Image::from_icon_name(name)
+        }
+        _ => gtk::Image::from_icon_name("application-x-executable"),
+    };
+    image.set_pixel_size(32);
+
+    image
+}
+
+fn format_cpu_time(seconds: f64) -> String {
+    let seconds = seconds.round() as u64;
+    format!(
+        "{}:{:02}:{:02}",
+        seconds / 3600,
+        (seconds / 60) % 60,
+        seconds % 60
+    )
+}
+
+glib::wrapper! {
+    pub struct AppHistory(ObjectSubclass<imp::AppHistory>)
+        @extends gtk::Box, gtk::Widget,
+        @implements gtk::Buildable;
+}
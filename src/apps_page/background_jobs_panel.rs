@@ -0,0 +1,184 @@
+/* apps_page/background_jobs_panel.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::cell::RefCell;
+
+use adw::prelude::*;
+use gtk::{glib, subclass::prelude::*};
+
+use super::AppsPage;
+use crate::{
+    i18n::*,
+    magpie_client::{BackgroundJob, JobKind, Pid},
+    settings, DataType,
+};
+
+/// More jobs than this and the panel pushes the process list out of view
+const MAX_VISIBLE_JOBS: usize = 5;
+
+mod imp {
+    use super::*;
+
+    #[derive(gtk::CompositeTemplate)]
+    #[template(resource = "/io/missioncenter/MissionCenter/ui/apps_page/background_jobs_panel.ui")]
+    pub struct BackgroundJobsPanel {
+        #[template_child]
+        pub jobs_list: TemplateChild<gtk::ListBox>,
+
+        pub rows: RefCell<Vec<adw::ActionRow>>,
+        pub pids: RefCell<Vec<Pid>>,
+    }
+
+    impl Default for BackgroundJobsPanel {
+        fn default() -> Self {
+            Self {
+                jobs_list: TemplateChild::default(),
+
+                rows: RefCell::new(vec![]),
+                pids: RefCell::new(vec![]),
+            }
+        }
+    }
+
+    impl BackgroundJobsPanel {
+        fn rebuild_rows(&self, jobs: &[BackgroundJob]) {
+            self.jobs_list.remove_all();
+
+            let mut rows = Vec::with_capacity(jobs.len());
+            for job in jobs {
+                let row = adw::ActionRow::new();
+                row.set_title(&glib::markup_escape_text(&job.name));
+                row.set_tooltip_text(Some(&job.command_line));
+                row.add_prefix(&gtk::Image::from_icon_name(kind_icon(job.kind)));
+                row.set_activatable(true);
+                row.connect_activated({
+                    let pid = job.pid;
+                    move |row| {
+                        if let Some(apps_page) = row
+                            .ancestor(AppsPage::static_type())
+                            .and_downcast::<AppsPage>()
+                        {
+                            apps_page.select_process(pid);
+                        }
+                    }
+                });
+
+                self.jobs_list.append(&row);
+                rows.push(row);
+            }
+
+            self.rows.replace(rows);
+        }
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for BackgroundJobsPanel {
+        const NAME: &'static str = "BackgroundJobsPanel";
+        type Type = super::BackgroundJobsPanel;
+        type ParentType = gtk::Box;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for BackgroundJobsPanel {}
+
+    impl WidgetImpl for BackgroundJobsPanel {}
+
+    impl BoxImpl for BackgroundJobsPanel {}
+}
+
+fn kind_icon(kind: JobKind) -> &'static str {
+    match kind {
+        JobKind::Copy => "edit-copy-symbolic",
+        JobKind::Build => "applications-engineering-symbolic",
+        JobKind::Backup => "document-save-symbolic",
+        JobKind::Archive => "package-x-generic-symbolic",
+        JobKind::Transcode => "video-x-generic-symbolic",
+        JobKind::PackageManager => "system-software-install-symbolic",
+        JobKind::Other => "system-run-symbolic",
+    }
+}
+
+fn kind_label(kind: JobKind) -> String {
+    match kind {
+        JobKind::Copy => i18n("Copying"),
+        JobKind::Build => i18n("Building"),
+        JobKind::Backup => i18n("Backing up"),
+        JobKind::Archive => i18n("Compressing"),
+        JobKind::Transcode => i18n("Transcoding"),
+        JobKind::PackageManager => i18n("Installing software"),
+        JobKind::Other => i18n("Busy"),
+    }
+}
+
+fn job_description(job: &BackgroundJob) -> String {
+    let elapsed = job.elapsed_seconds;
+    let mut parts = vec![
+        kind_label(job.kind),
+        format!(
+            "{}:{:02}:{:02}",
+            elapsed / 3600,
+            (elapsed / 60) % 60,
+            elapsed % 60
+        ),
+        i18n_f("CPU {}%", &[&format!("{:.1}", job.cpu_usage)]),
+    ];
+
+    if job.disk_usage > 0. {
+        parts.push(crate::to_human_readable_nice(
+            job.disk_usage,
+            &DataType::DriveBytesPerSecond,
+            &settings!(),
+        ));
+    }
+
+    parts.join(" · ")
+}
+
+glib::wrapper! {
+    pub struct BackgroundJobsPanel(ObjectSubclass<imp::BackgroundJobsPanel>)
+        @extends gtk::Box, gtk::Widget,
+        @implements gtk::Buildable;
+}
+
+impl BackgroundJobsPanel {
+    pub fn update(&self, jobs: &[BackgroundJob]) {
+        let imp = self.imp();
+
+        let jobs = &jobs[..jobs.len().min(MAX_VISIBLE_JOBS)];
+        self.set_visible(!jobs.is_empty());
+
+        let pids = jobs.iter().map(|job| job.pid).collect::<Vec<_>>();
+        if *imp.pids.borrow() != pids {
+            imp.rebuild_rows(jobs);
+            imp.pids.replace(pids);
+        }
+
+        for (job, row) in jobs.iter().zip(imp.rows.borrow().iter()) {
+            row.set_subtitle(&glib::markup_escape_text(&job_description(job)));
+        }
+    }
+}
@@ -0,0 +1,262 @@
+/* apps_page/batch_stop.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::HashMap;
+
+use adw::prelude::*;
+use gtk::glib::{self, g_warning};
+
+use super::actions::app_pids;
+use super::row_model::{ContentType, RowModel};
+use super::AppsPage;
+use crate::app;
+use crate::i18n::*;
+use crate::magpie_client::{Pid, StopOutcome};
+
+/// Keeps the dialogs from growing past the window with a long selection
+const LIST_MAX_HEIGHT: i32 = 300;
+
+/// An app or process that was selected, with everything that has to exit for it to be stopped
+#[derive(Clone)]
+struct Item {
+    name: String,
+    pids: Vec<Pid>,
+}
+
+/// Ask once for all of `rows`, then stop them and show how that went for each one
+pub fn stop(page: &AppsPage, rows: &[RowModel], force: bool) {
+    let items = rows
+        .iter()
+        .filter_map(|row| {
+            let pids = match row.content_type() {
+                ContentType::App => app_pids(row),
+                ContentType::Process => vec![row.pid()],
+                ContentType::SectionHeader => return None,
+            };
+            (!pids.is_empty()).then(|| Item {
+                name: row.name().to_string(),
+                pids,
+            })
+        })
+        .collect::<Vec<_>>();
+    if items.is_empty() {
+        return;
+    }
+
+    let count = items.len() as u32;
+    let heading = if force {
+        ni18n_f(
+            "Force Stop {} Item?",
+            "Force Stop {} Items?",
+            count,
+            &[&count.to_string()],
+        )
+    } else {
+        ni18n_f(
+            "Stop {} Item?",
+            "Stop {} Items?",
+            count,
+            &[&count.to_string()],
+        )
+    };
+    let body = if force {
+        i18n("These are killed right away, without a chance to save their work.")
+    } else {
+        i18n("These are asked to quit, unsaved work in them may be lost.")
+    };
+
+    let dialog = adw::AlertDialog::new(Some(&heading), Some(&body));
+    dialog.set_extra_child(Some(&item_list(
+        items.iter().map(|item| (item.name.as_str(), None, None)),
+    )));
+    dialog.add_responses(&[
+        ("cancel", &i18n("_Cancel")),
+        (
+            "stop",
+            &if force {
+                i18n("_Force Stop")
+            } else {
+                i18n("_Stop")
+            },
+        ),
+    ]);
+    dialog.set_response_appearance("stop", adw::ResponseAppearance::Destructive);
+    dialog.set_default_response(Some("cancel"));
+    dialog.set_close_response("cancel");
+    dialog.connect_response(Some("stop"), {
+        let page = page.downgrade();
+        move |_, _| {
+            if let Some(page) = page.upgrade() {
+                run(&page, items.clone(), force);
+            }
+        }
+    });
+    dialog.present(Some(page));
+}
+
+fn run(page: &AppsPage, items: Vec<Item>, force: bool) {
+    let mut pids = items
+        .iter()
+        .flat_map(|item| item.pids.iter().copied())
+        .collect::<Vec<_>>();
+    pids.sort_unstable();
+    pids.dedup();
+
+    let sys_info = match app!().sys_info() {
+        Ok(sys_info) => sys_info,
+        Err(e) => {
+            g_warning!("MissionCenter::AppsPage", "Failed to get `sys_info`: {}", e);
+            return;
+        }
+    };
+
+    // Waiting for authorization and for everything to exit can take a while, the results are
+    // shown once it's done
+    let page = glib::SendWeakRef::from(page.downgrade());
+    sys_info.stop_processes(pids, force, move |outcomes| {
+        if let Some(page) = page.upgrade() {
+            show_results(&page, &items, force, outcomes.into_iter().collect());
+        }
+    });
+}
+
+fn show_results(page: &AppsPage, items: &[Item], force: bool, outcomes: HashMap<Pid, StopOutcome>) {
+    // An app counts as stopped once all of its processes are
+    let results = items
+        .iter()
+        .map(|item| {
+            let outcomes = item
+                .pids
+                .iter()
+                .map(|pid| {
+                    outcomes
+                        .get(pid)
+                        .cloned()
+                        .unwrap_or(StopOutcome::Failed(i18n("No answer from the gatherer")))
+                })
+                .collect::<Vec<_>>();
+
+            let outcome = outcomes
+                .iter()
+                .find(|outcome| matches!(outcome, StopOutcome::Failed(_)))
+                .or_else(|| outcomes.iter().find(|o| **o == StopOutcome::StillRunning))
+                .or_else(|| outcomes.iter().find(|o| **o == StopOutcome::Signalled))
+                .cloned()
+                .unwrap_or(StopOutcome::Stopped);
+
+            (item, outcome)
+        })
+        .collect::<Vec<_>>();
+
+    let still_running = results
+        .iter()
+        .filter(|(_, outcome)| *outcome == StopOutcome::StillRunning)
+        .map(|(item, _)| (*item).clone())
+        .collect::<Vec<_>>();
+    let unsuccessful = results
+        .iter()
+        .filter(|(_, outcome)| {
+            matches!(outcome, StopOutcome::StillRunning | StopOutcome::Failed(_))
+        })
+        .count() as u32;
+
+    let heading = if unsuccessful == 0 {
+        ni18n_f(
+            "Stopped {} Item",
+            "Stopped {} Items",
+            items.len() as u32,
+            &[&items.len().to_string()],
+        )
+    } else {
+        ni18n_f(
+            "{} Item Could Not Be Stopped",
+            "{} Items Could Not Be Stopped",
+            unsuccessful,
+            &[&unsuccessful.to_string()],
+        )
+    };
+
+    let dialog = adw::AlertDialog::new(Some(&heading), None);
+    dialog.set_extra_child(Some(&item_list(results.iter().map(|(item, outcome)| {
+        let (status, icon) = describe(outcome);
+        (item.name.as_str(), Some(status), Some(icon))
+    }))));
+    dialog.add_responses(&[("close", &i18n("_Close"))]);
+    dialog.set_default_response(Some("close"));
+    dialog.set_close_response("close");
+
+    // Whatever ignored being asked nicely can still be killed
+    if !force && !still_running.is_empty() {
+        dialog.add_response("force-stop", &i18n("_Force Stop"));
+        dialog.set_response_appearance("force-stop", adw::ResponseAppearance::Destructive);
+        dialog.connect_response(Some("force-stop"), {
+            let page = page.downgrade();
+            move |_, _| {
+                if let Some(page) = page.upgrade() {
+                    run(&page, still_running.clone(), true);
+                }
+            }
+        });
+    }
+
+    dialog.present(Some(page));
+}
+
+fn describe(outcome: &StopOutcome) -> (String, &'static str) {
+    match outcome {
+        StopOutcome::Stopped => (i18n("Stopped"), "object-select-symbolic"),
+        StopOutcome::Signalled => (
+            i18n("Asked to stop, whether it did can't be checked from here"),
+            "object-select-symbolic",
+        ),
+        StopOutcome::StillRunning => (i18n("Still running"), "dialog-warning-symbolic"),
+        StopOutcome::Failed(e) => (e.clone(), "dialog-error-symbolic"),
+    }
+}
+
+fn item_list<'a>(
+    items: impl Iterator<Item = (&'a str, Option<String>, Option<&'static str>)>,
+) -> gtk::ScrolledWindow {
+    let list = gtk::ListBox::builder()
+        .selection_mode(gtk::SelectionMode::None)
+        .css_classes(["boxed-list"])
+        .build();
+
+    for (name, status, icon) in items {
+        let row = adw::ActionRow::builder()
+            .title(name)
+            .use_markup(false)
+            .build();
+        if let Some(status) = status {
+            row.set_subtitle(&status);
+        }
+        if let Some(icon) = icon {
+            row.add_suffix(&gtk::Image::from_icon_name(icon));
+        }
+        list.append(&row);
+    }
+
+    gtk::ScrolledWindow::builder()
+        .hscrollbar_policy(gtk::PolicyType::Never)
+        .propagate_natural_height(true)
+        .max_content_height(LIST_MAX_HEIGHT)
+        .child(&list)
+        .build()
+}
@@ -0,0 +1,250 @@
+/* apps_page/columns/chooser.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use gtk::glib::VariantTy;
+use gtk::{gio, prelude::*};
+
+use crate::i18n::i18n;
+use crate::settings;
+
+/// Hiding this would leave nothing to tell the rows apart by
+const NAME_COLUMN: &str = "name";
+
+/// Add a menu to each column header, for hiding columns and moving them around. Has to be
+/// called before the saved column order is restored, which is what "Reset Columns" goes back to
+pub fn configure(column_view: &gtk::ColumnView) {
+    let settings = settings!();
+
+    let columns = all_columns(column_view);
+    let default_order = columns
+        .iter()
+        .filter_map(|column| column.id())
+        .collect::<Vec<_>>();
+
+    let hidden = settings.string("apps-page-hidden-columns");
+    let hidden = hidden
+        .split(';')
+        .filter(|id| !id.is_empty())
+        .collect::<Vec<_>>();
+
+    let actions = gio::SimpleActionGroup::new();
+    column_view.insert_action_group("columns", Some(&actions));
+
+    let visibility_section = gio::Menu::new();
+    for column in &columns {
+        let Some(id) = column.id() else {
+            continue;
+        };
+        if id == NAME_COLUMN {
+            continue;
+        }
+
+        let visible = !hidden.contains(&id.as_str());
+        column.set_visible(visible);
+
+        let action_name = visibility_action_name(&id);
+        let action = gio::SimpleAction::new_stateful(&action_name, None, &visible.to_variant());
+        action.connect_change_state({
+            let column_view = column_view.downgrade();
+            let column = column.downgrade();
+            let actions = actions.downgrade();
+            move |action, state| {
+                let (Some(column_view), Some(column), Some(actions), Some(visible)) = (
+                    column_view.upgrade(),
+                    column.upgrade(),
+                    actions.upgrade(),
+                    state.and_then(|state| state.get::<bool>()),
+                ) else {
+                    return;
+                };
+
+                action.set_state(&visible.to_variant());
+                column.set_visible(visible);
+                save_hidden_columns(&column_view, &actions);
+            }
+        });
+        actions.add_action(&action);
+
+        visibility_section.append(
+            Some(&column_name(&id)),
+            Some(&format!("columns.{}", action_name)),
+        );
+    }
+
+    let action = gio::SimpleAction::new("move-left", Some(VariantTy::STRING));
+    action.connect_activate({
+        let column_view = column_view.downgrade();
+        move |_, id| {
+            if let (Some(column_view), Some(id)) =
+                (column_view.upgrade(), id.and_then(|id| id.get::<String>()))
+            {
+                move_column(&column_view, &id, -1);
+            }
+        }
+    });
+    actions.add_action(&action);
+
+    let action = gio::SimpleAction::new("move-right", Some(VariantTy::STRING));
+    action.connect_activate({
+        let column_view = column_view.downgrade();
+        move |_, id| {
+            if let (Some(column_view), Some(id)) =
+                (column_view.upgrade(), id.and_then(|id| id.get::<String>()))
+            {
+                move_column(&column_view, &id, 1);
+            }
+        }
+    });
+    actions.add_action(&action);
+
+    let action = gio::SimpleAction::new("reset", None);
+    action.connect_activate({
+        let column_view = column_view.downgrade();
+        let actions = actions.downgrade();
+        move |_, _| {
+            let (Some(column_view), Some(actions)) = (column_view.upgrade(), actions.upgrade())
+            else {
+                return;
+            };
+
+            for column in all_columns(&column_view) {
+                let Some(id) = column.id() else {
+                    continue;
+                };
+                if id != NAME_COLUMN {
+                    actions.change_action_state(&visibility_action_name(&id), &true.to_variant());
+                }
+            }
+
+            let columns = all_columns(&column_view);
+            for (position, id) in default_order.iter().enumerate() {
+                if let Some(column) = columns.iter().find(|c| c.id().as_ref() == Some(id)) {
+                    column_view.insert_column(position as u32, column);
+                }
+            }
+        }
+    });
+    actions.add_action(&action);
+
+    let reset_section = gio::Menu::new();
+    reset_section.append(Some(&i18n("Reset Columns")), Some("columns.reset"));
+
+    for column in &columns {
+        let Some(id) = column.id() else {
+            continue;
+        };
+
+        let move_section = gio::Menu::new();
+        move_section.append_item(&gio::MenuItem::new(
+            Some(&i18n("Move Left")),
+            Some(&gio::Action::print_detailed_name(
+                "columns.move-left",
+                Some(&id.to_variant()),
+            )),
+        ));
+        move_section.append_item(&gio::MenuItem::new(
+            Some(&i18n("Move Right")),
+            Some(&gio::Action::print_detailed_name(
+                "columns.move-right",
+                Some(&id.to_variant()),
+            )),
+        ));
+
+        let menu = gio::Menu::new();
+        menu.append_section(None, &move_section);
+        menu.append_section(Some(&i18n("Columns")), &visibility_section);
+        menu.append_section(None, &reset_section);
+        column.set_header_menu(Some(&menu));
+    }
+}
+
+fn all_columns(column_view: &gtk::ColumnView) -> Vec<gtk::ColumnViewColumn> {
+    let columns = column_view.columns();
+    (0..columns.n_items())
+        .filter_map(|i| {
+            columns
+                .item(i)
+                .and_then(|c| c.downcast::<gtk::ColumnViewColumn>().ok())
+        })
+        .collect()
+}
+
+/// Swap the column with the closest visible one in `direction`, hidden columns are skipped
+/// over so that every move can be seen
+fn move_column(column_view: &gtk::ColumnView, id: &str, direction: i32) {
+    let columns = all_columns(column_view);
+    let Some(position) = columns
+        .iter()
+        .position(|column| column.id().is_some_and(|c| c == id))
+    else {
+        return;
+    };
+
+    let mut target = position as i32;
+    loop {
+        target += direction;
+        if target < 0 || target >= columns.len() as i32 {
+            return;
+        }
+        if columns[target as usize].is_visible() {
+            break;
+        }
+    }
+
+    column_view.insert_column(target as u32, &columns[position]);
+}
+
+/// Goes by what was picked in the menu, columns can also be hidden for lack of data
+fn save_hidden_columns(column_view: &gtk::ColumnView, actions: &gio::SimpleActionGroup) {
+    let hidden = all_columns(column_view)
+        .iter()
+        .filter_map(|column| column.id())
+        .filter(|id| {
+            actions
+                .action_state(&visibility_action_name(id))
+                .and_then(|state| state.get::<bool>())
+                == Some(false)
+        })
+        .collect::<Vec<_>>()
+        .join(";");
+
+    let _ = settings!().set_string("apps-page-hidden-columns", &hidden);
+}
+
+/// Action names can't have underscores, which some column ids do
+fn visibility_action_name(id: &str) -> String {
+    format!("show-{}", id.replace('_', "-"))
+}
+
+/// Column titles include the current usage, so the menu has names of its own
+fn column_name(id: &str) -> String {
+    match id {
+        "pid" => i18n("PID"),
+        "user" => i18n("User"),
+        "cpu" => i18n("CPU"),
+        "memory" => i18n("Memory"),
+        "shared_memory" => i18n("Shared Memory"),
+        "drive" => i18n("Drive"),
+        "network" => i18n("Network"),
+        "gpu" => i18n("GPU"),
+        "gpu_memory" => i18n("GPU Memory"),
+        _ => id.to_owned(),
+    }
+}
@@ -26,10 +26,11 @@ use gtk::glib;
 use gtk::prelude::*;
 
 use super::{compare_column_entries_by, sort_order, LabelCell};
+use crate::i18n::*;
 use crate::label_cell_factory;
 
 pub fn list_item_factory() -> gtk::SignalListItemFactory {
-    label_cell_factory!("cpu-usage", label_formatter)
+    label_cell_factory!("cpu-usage", split_label_formatter)
 }
 
 pub fn sorter(column_view: &gtk::ColumnView) -> impl IsA<gtk::Sorter> {
@@ -55,3 +56,39 @@ pub fn label_formatter(label: &LabelCell, value: glib::Value) {
     let _ = write!(&mut buffer, "{}%", cpu_usage.round() as u32);
     label.set_label(buffer.as_str());
 }
+
+/// Along with the usage, draw how it splits between user space and the kernel
+fn split_label_formatter(label: &LabelCell, value: glib::Value) {
+    let cpu_usage: f32 = value.get().unwrap();
+    label_formatter(label, value);
+
+    // The split is set on the row ahead of the usage, see `models::processes::set_cpu_split`
+    let Some(model) = label.model() else {
+        return;
+    };
+    let system_usage = model.cpu_system_usage();
+    if system_usage < 0. {
+        label.set_usage_bar(None);
+        label.set_tooltip_text(None);
+        return;
+    }
+
+    let system_usage = system_usage.min(cpu_usage);
+    let user_usage = cpu_usage - system_usage;
+    label.set_usage_bar(Some((user_usage / 100., system_usage / 100.)));
+
+    let mut tooltip = format!(
+        "{}\n{}",
+        i18n_f("User: {}%", &[&format!("{:.1}", user_usage)]),
+        i18n_f("System: {}%", &[&format!("{:.1}", system_usage)])
+    );
+    let io_wait = model.io_wait();
+    if io_wait >= 0. {
+        tooltip.push('\n');
+        tooltip.push_str(&i18n_f(
+            "Waiting for I/O: {}%",
+            &[&format!("{:.1}", io_wait)],
+        ));
+    }
+    label.set_tooltip_text(Some(&tooltip));
+}
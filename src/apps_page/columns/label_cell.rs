@@ -21,16 +21,23 @@
 use std::cell::Cell;
 
 use adw::subclass::prelude::*;
-use gtk::{glib, prelude::*};
+use gtk::{gdk, glib, graphene, prelude::*};
 
 use crate::apps_page::row_model::RowModel;
 
+/// Height of the bar drawn under the label, in pixels
+const USAGE_BAR_HEIGHT: f32 = 3.;
+/// Kernel time is told apart from the accent colored user time in red, as other tools do
+const SYSTEM_USAGE_COLOR: gdk::RGBA = gdk::RGBA::new(0.878, 0.106, 0.141, 1.);
+
 mod imp {
     use super::*;
     use adw::prelude::BinExt;
 
     pub struct LabelCell {
         pub label: gtk::Label,
+        /// Fractions of the width taken by the first and the second segment of the bar
+        pub usage_bar: Cell<Option<(f32, f32)>>,
 
         sig_handler: Cell<Option<glib::SignalHandlerId>>,
         model: Cell<glib::WeakRef<RowModel>>,
@@ -40,6 +47,7 @@ mod imp {
         fn default() -> Self {
             Self {
                 label: gtk::Label::new(None),
+                usage_bar: Cell::new(None),
 
                 sig_handler: Cell::new(None),
                 model: Cell::new(glib::WeakRef::default()),
@@ -70,7 +78,19 @@ mod imp {
             self.sig_handler.set(Some(sig_handler));
         }
 
+        pub fn model(&self) -> Option<RowModel> {
+            let model = self.model.take();
+            let result = model.upgrade();
+            self.model.set(model);
+
+            result
+        }
+
         pub fn unbind(&self) {
+            // Cells are recycled, what was shown for the last row goes with it
+            self.usage_bar.set(None);
+            self.obj().set_tooltip_text(None);
+
             let Some(model) = self.model.take().upgrade() else {
                 return;
             };
@@ -108,6 +128,35 @@ mod imp {
         fn realize(&self) {
             self.parent_realize();
         }
+
+        fn snapshot(&self, snapshot: &gtk::Snapshot) {
+            if let Some((first, second)) = self.usage_bar.get() {
+                let this = self.obj();
+                let width = this.width() as f32;
+                let y = this.height() as f32 - USAGE_BAR_HEIGHT;
+
+                let first_width = (first.clamp(0., 1.) * width).round();
+                let second_width = (second.clamp(0., 1.) * width)
+                    .round()
+                    .min(width - first_width);
+
+                snapshot.append_color(
+                    &adw::StyleManager::default().accent_color_rgba(),
+                    &graphene::Rect::new(width - first_width, y, first_width, USAGE_BAR_HEIGHT),
+                );
+                snapshot.append_color(
+                    &SYSTEM_USAGE_COLOR,
+                    &graphene::Rect::new(
+                        width - first_width - second_width,
+                        y,
+                        second_width,
+                        USAGE_BAR_HEIGHT,
+                    ),
+                );
+            }
+
+            self.parent_snapshot(snapshot);
+        }
     }
 
     impl BinImpl for LabelCell {}
@@ -128,6 +177,23 @@ impl LabelCell {
         self.imp().label.set_label(label);
     }
 
+    /// The row the cell is bound to
+    pub fn model(&self) -> Option<RowModel> {
+        self.imp().model()
+    }
+
+    /// Draw a bar of two segments under the label, growing from its end like the label does;
+    /// `None` removes it
+    pub fn set_usage_bar(&self, usage_bar: Option<(f32, f32)>) {
+        let imp = self.imp();
+        if imp.usage_bar.get() == usage_bar {
+            return;
+        }
+
+        imp.usage_bar.set(usage_bar);
+        self.queue_draw();
+    }
+
     pub fn bind(
         &self,
         model: &RowModel,
@@ -27,6 +27,7 @@ use std::fmt::Write;
 use crate::apps_page::row_model::{ContentType, RowModel, SectionType};
 use crate::i18n::i18n;
 
+pub use chooser::configure as configure_column_chooser;
 pub use cpu::label_formatter as cpu_label_formatter;
 pub use cpu::list_item_factory as cpu_list_item_factory;
 pub use cpu::sorter as cpu_sorter;
@@ -54,9 +55,12 @@ pub use pid::sorter as pid_sorter;
 pub use shared_memory::label_formatter as shared_memory_label_formatter;
 pub use shared_memory::list_item_factory as shared_memory_list_item_factory;
 pub use shared_memory::sorter as shared_memory_sorter;
+pub use user::list_item_factory as user_list_item_factory;
+pub use user::sorter as user_sorter;
 
 use crate::{settings, DataType};
 
+mod chooser;
 mod cpu;
 mod drive;
 mod gpu;
@@ -68,6 +72,7 @@ mod name_cell;
 mod network;
 mod pid;
 mod shared_memory;
+mod user;
 
 #[macro_export]
 macro_rules! label_cell_factory {
@@ -145,10 +150,11 @@ macro_rules! label_cell_factory {
                 return;
             }
 
+            // Bound first, so the setter can look at the rest of the row
+            label.bind(&model, $property, $setter);
+
             let value = model.property_value($property);
             ($setter)(&label, value);
-
-            label.bind(&model, $property, $setter);
         });
 
         factory.connect_unbind(|_, list_item| {
@@ -26,6 +26,7 @@ use gtk::glib::FileError;
 use gtk::{gdk, glib, prelude::*, subclass::prelude::*};
 
 use crate::apps_page::row_model::{ContentType, RowModel};
+use crate::i18n::*;
 use crate::widgets::ListCell;
 
 mod icon_cache {
@@ -61,11 +62,15 @@ mod imp {
     pub struct NameCell {
         icon: gtk::Image,
         name: gtk::Label,
+        game_mode_badge: gtk::Label,
+        sandbox_badge: gtk::Label,
 
         sig_id: Cell<Option<glib::SignalHandlerId>>,
         sig_icon: Cell<Option<glib::SignalHandlerId>>,
         sig_name: Cell<Option<glib::SignalHandlerId>>,
         sig_content_type: Cell<Option<glib::SignalHandlerId>>,
+        sig_game_mode: Cell<Option<glib::SignalHandlerId>>,
+        sig_sandbox: Cell<Option<glib::SignalHandlerId>>,
         sig_children_changed: Cell<Option<glib::SignalHandlerId>>,
 
         model: Cell<glib::WeakRef<RowModel>>,
@@ -77,11 +82,15 @@ mod imp {
             Self {
                 icon: gtk::Image::new(),
                 name: gtk::Label::new(None),
+                game_mode_badge: gtk::Label::new(Some(&i18n("GameMode"))),
+                sandbox_badge: gtk::Label::new(None),
 
                 sig_id: Cell::new(None),
                 sig_icon: Cell::new(None),
                 sig_name: Cell::new(None),
                 sig_content_type: Cell::new(None),
+                sig_game_mode: Cell::new(None),
+                sig_sandbox: Cell::new(None),
                 sig_children_changed: Cell::new(None),
 
                 model: Cell::new(glib::WeakRef::default()),
@@ -148,6 +157,32 @@ mod imp {
             self.sig_content_type.set(Some(sig_content_type));
             self.set_content_type(model.content_type());
 
+            let sig_game_mode = model.connect_game_mode_notify({
+                let this = this.clone();
+                move |model| {
+                    let Some(this) = this.upgrade() else {
+                        return;
+                    };
+                    let this = this.imp();
+                    this.game_mode_badge.set_visible(model.game_mode());
+                }
+            });
+            self.sig_game_mode.set(Some(sig_game_mode));
+            self.game_mode_badge.set_visible(model.game_mode());
+
+            let sig_sandbox = model.connect_sandbox_notify({
+                let this = this.clone();
+                move |model| {
+                    let Some(this) = this.upgrade() else {
+                        return;
+                    };
+                    let this = this.imp();
+                    this.set_sandbox(&model.sandbox());
+                }
+            });
+            self.sig_sandbox.set(Some(sig_sandbox));
+            self.set_sandbox(&model.sandbox());
+
             let sig_children_changed = model.children().connect_items_changed({
                 let expander = expander.downgrade();
                 move |children, _, _, _| {
@@ -183,6 +218,14 @@ mod imp {
                 model.disconnect(sig_id);
             }
 
+            if let Some(sig_id) = self.sig_game_mode.take() {
+                model.disconnect(sig_id);
+            }
+
+            if let Some(sig_id) = self.sig_sandbox.take() {
+                model.disconnect(sig_id);
+            }
+
             if let Some(sig_id) = self.sig_children_changed.take() {
                 model.children().disconnect(sig_id);
             }
@@ -220,6 +263,11 @@ mod imp {
             }
         }
 
+        fn set_sandbox(&self, sandbox: &str) {
+            self.sandbox_badge.set_label(sandbox);
+            self.sandbox_badge.set_visible(!sandbox.is_empty());
+        }
+
         fn set_content_type(&self, content_type: ContentType) {
             match content_type {
                 ContentType::SectionHeader => {
@@ -300,8 +348,26 @@ mod imp {
 
             self.name.set_ellipsize(EllipsizeMode::Middle);
 
+            self.game_mode_badge.add_css_class("caption-heading");
+            self.game_mode_badge.add_css_class("accent");
+            self.game_mode_badge.set_margin_start(6);
+            self.game_mode_badge.set_visible(false);
+            self.game_mode_badge.set_tooltip_text(Some(&i18n(
+                "This process asked GameMode to optimize the system for it",
+            )));
+
+            self.sandbox_badge.add_css_class("caption-heading");
+            self.sandbox_badge.add_css_class("dim-label");
+            self.sandbox_badge.set_margin_start(6);
+            self.sandbox_badge.set_visible(false);
+            self.sandbox_badge.set_tooltip_text(Some(&i18n(
+                "Runs in a sandbox, see its details for what it has access to",
+            )));
+
             let _ = self.obj().append(&self.icon);
             let _ = self.obj().append(&self.name);
+            let _ = self.obj().append(&self.sandbox_badge);
+            let _ = self.obj().append(&self.game_mode_badge);
         }
     }
 
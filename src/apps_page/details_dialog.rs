@@ -18,19 +18,31 @@
  * SPDX-License-Identifier: GPL-3.0-or-later
  */
 
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::Rc;
 
+use adw::prelude::{ActionRowExt, ExpanderRowExt, PreferencesRowExt};
 use adw::subclass::prelude::*;
-use gtk::glib::{self};
-use gtk::prelude::StaticTypeExt;
+use gtk::glib::{self, g_critical, g_warning};
+use gtk::prelude::{CastNone, ListModelExt, StaticTypeExt, WidgetExt};
 
 use super::columns::*;
 use super::row_model::{ContentType, RowModel};
+use crate::app;
+use crate::i18n::*;
+use crate::magpie_client::{
+    autostart_entries, cgroup_weights, sandbox_permissions, set_autostart_enabled, AutostartEntry,
+    CgroupWeights, Sandbox, SandboxKind, TcpSocket,
+};
+use crate::{settings, DataType};
+
+const MAX_CONNECTION_ROWS: usize = 50;
 
 mod imp {
     use super::*;
+    use adw::prelude::{ButtonExt, PreferencesGroupExt, SpinRowExt};
     use adw::PreferencesRow;
-    use gtk::prelude::WidgetExt;
 
     #[derive(gtk::CompositeTemplate)]
     #[template(resource = "/io/missioncenter/MissionCenter/ui/apps_page/details_dialog.ui")]
@@ -39,6 +51,10 @@ mod imp {
         icon: TemplateChild<gtk::Image>,
         #[template_child]
         title: TemplateChild<gtk::Label>,
+        #[template_child]
+        tabs: TemplateChild<adw::InlineViewSwitcher>,
+        #[template_child]
+        permissions_page: TemplateChild<adw::ViewStackPage>,
 
         #[template_child]
         id: TemplateChild<gtk::Label>,
@@ -64,7 +80,31 @@ mod imp {
         #[template_child]
         gpu_memory: TemplateChild<LabelCell>,
 
+        #[template_child]
+        connections_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        refresh_connections: TemplateChild<gtk::Button>,
+
+        #[template_child]
+        startup_group: TemplateChild<adw::PreferencesGroup>,
+
+        #[template_child]
+        cgroup_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        apply_weights: TemplateChild<gtk::Button>,
+        #[template_child]
+        cpu_weight: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        io_weight: TemplateChild<adw::SpinRow>,
+
+        #[template_child]
+        sandbox_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        permissions_group: TemplateChild<adw::PreferencesGroup>,
+
         pub model: RefCell<RowModel>,
+        cgroup_weights: RefCell<Option<CgroupWeights>>,
+        connection_rows: RefCell<Vec<adw::ExpanderRow>>,
     }
 
     impl Default for DetailsDialog {
@@ -72,6 +112,8 @@ mod imp {
             Self {
                 icon: TemplateChild::default(),
                 title: TemplateChild::default(),
+                tabs: TemplateChild::default(),
+                permissions_page: TemplateChild::default(),
 
                 id: TemplateChild::default(),
                 kind: TemplateChild::default(),
@@ -86,7 +128,22 @@ mod imp {
                 gpu: TemplateChild::default(),
                 gpu_memory: TemplateChild::default(),
 
+                connections_group: TemplateChild::default(),
+                refresh_connections: TemplateChild::default(),
+
+                startup_group: TemplateChild::default(),
+
+                cgroup_group: TemplateChild::default(),
+                apply_weights: TemplateChild::default(),
+                cpu_weight: TemplateChild::default(),
+                io_weight: TemplateChild::default(),
+
+                sandbox_group: TemplateChild::default(),
+                permissions_group: TemplateChild::default(),
+
                 model: RefCell::new(RowModel::new(ContentType::SectionHeader)),
+                cgroup_weights: RefCell::new(None),
+                connection_rows: RefCell::new(vec![]),
             }
         }
     }
@@ -158,6 +215,281 @@ mod imp {
                 .bind(&*model, "gpu-memory-usage", gpu_memory_label_formatter);
         }
 
+        /// List the autostart entries and services that start the app on their own, matched by
+        /// the app's ID and the name of its executable
+        pub fn bind_startup(&self, service_states: &HashMap<String, bool>) {
+            let model = self.model.borrow();
+            if model.content_type() != ContentType::App {
+                return;
+            }
+
+            let executable = model
+                .children()
+                .item(0)
+                .and_downcast::<RowModel>()
+                .and_then(|process| executable_name(process.command_line().as_str()));
+            let names = related_names(model.id().as_str(), executable.as_deref());
+            if names.is_empty() {
+                return;
+            }
+
+            for entry in autostart_entries() {
+                let id = entry.id.trim_end_matches(".desktop").to_lowercase();
+                let executable = entry.executable().map(|e| e.to_lowercase());
+                if !names.contains(&id) && !executable.is_some_and(|e| names.contains(&e)) {
+                    continue;
+                }
+
+                let row = startup_row(
+                    &entry.name,
+                    &i18n_f("Autostart entry {}, runs at login", &[&entry.id]),
+                    entry.enabled,
+                );
+                connect_autostart_toggle(&row, entry);
+                self.startup_group.add(&row);
+                self.startup_group.set_visible(true);
+            }
+
+            let mut services = service_states
+                .iter()
+                .filter(|(id, _)| {
+                    id.strip_suffix(".service")
+                        .is_some_and(|name| names.contains(&name.to_lowercase()))
+                })
+                .collect::<Vec<_>>();
+            services.sort_unstable();
+            for (id, enabled) in services {
+                let row = startup_row(
+                    id,
+                    &i18n("Service, starts with the system when enabled"),
+                    *enabled,
+                );
+                row.connect_active_notify({
+                    let id = id.clone();
+                    move |row| {
+                        let Ok(sys_info) = app!().sys_info() else {
+                            g_critical!(
+                                "MissionCenter::DetailsDialog",
+                                "Failed to get sys_info from MissionCenterApplication"
+                            );
+                            return;
+                        };
+
+                        if row.is_active() {
+                            sys_info.enable_service(id.clone());
+                        } else {
+                            sys_info.disable_service(id.clone());
+                        }
+                    }
+                });
+                self.startup_group.add(&row);
+                self.startup_group.set_visible(true);
+            }
+        }
+
+        /// What the Flatpak or Snap the app was installed as is, and what it may access; the tab
+        /// for it is only shown for such apps
+        pub fn bind_sandbox(&self, sandbox: Option<&Sandbox>) {
+            let Some(sandbox) = sandbox else {
+                return;
+            };
+            self.tabs.set_visible(true);
+            self.permissions_page.set_visible(true);
+
+            let (kind, id_title) = match sandbox.kind {
+                SandboxKind::Flatpak => ("Flatpak", i18n("Application ID")),
+                SandboxKind::Snap => ("Snap", i18n("Snap Name")),
+            };
+            self.sandbox_group
+                .add(&property_row(&i18n("Installed As"), kind));
+            self.sandbox_group
+                .add(&property_row(&id_title, &sandbox.id));
+
+            let permissions = match sandbox_permissions(sandbox) {
+                Ok(permissions) => permissions,
+                Err(e) => {
+                    g_warning!(
+                        "MissionCenter::DetailsDialog",
+                        "Failed to read the permissions of {}: {}",
+                        sandbox.id,
+                        e
+                    );
+                    return;
+                }
+            };
+
+            if let Some(confinement) = &permissions.confinement {
+                let confinement = match confinement.as_str() {
+                    "strict" => i18n("Strict"),
+                    "classic" => i18n("Classic, not sandboxed"),
+                    "devmode" => i18n("Developer mode, violations are only logged"),
+                    other => other.to_owned(),
+                };
+                self.sandbox_group
+                    .add(&property_row(&i18n("Confinement"), &confinement));
+                return;
+            }
+
+            for (title, entries) in [
+                (i18n("Files"), &permissions.filesystems),
+                (i18n("Devices"), &permissions.devices),
+                (i18n("Sockets"), &permissions.sockets),
+                (i18n("Shared With the System"), &permissions.shared),
+            ] {
+                let value = if entries.is_empty() {
+                    i18n("None")
+                } else {
+                    entries.join(", ")
+                };
+                self.permissions_group.add(&property_row(&title, &value));
+            }
+            self.permissions_group.set_visible(true);
+        }
+
+        /// The TCP sockets of the process and everything below it
+        pub fn bind_connections(&self) {
+            for row in self.connection_rows.take() {
+                self.connections_group.remove(&row);
+            }
+
+            let pids = process_ids(&self.model.borrow());
+            if pids.is_empty() {
+                return;
+            }
+
+            let sockets = match app!().sys_info() {
+                Ok(sys_info) => sys_info.tcp_sockets(pids),
+                Err(e) => Err(e.to_string()),
+            };
+            let sockets = match sockets {
+                Ok(sockets) => sockets,
+                Err(e) => {
+                    g_warning!(
+                        "MissionCenter::DetailsDialog",
+                        "Failed to list TCP sockets: {}",
+                        e
+                    );
+                    self.connections_group.set_visible(false);
+                    return;
+                }
+            };
+
+            let mut sockets = sockets
+                .into_iter()
+                .filter(|socket| socket.state != "LISTEN")
+                .collect::<Vec<_>>();
+            // The ones with data piling up are the interesting ones
+            sockets.sort_by(|s1, s2| {
+                (s2.send_queue + s2.receive_queue).cmp(&(s1.send_queue + s1.receive_queue))
+            });
+            sockets.truncate(MAX_CONNECTION_ROWS);
+
+            let mut rows = vec![];
+            for socket in &sockets {
+                let row = connection_row(socket);
+                self.connections_group.add(&row);
+                rows.push(row);
+            }
+            self.connection_rows.replace(rows);
+
+            self.connections_group.set_visible(!sockets.is_empty());
+        }
+
+        pub fn bind_cgroup_weights(&self) {
+            let pid = self.model.borrow().pid();
+            let weights = if pid != 0 { cgroup_weights(pid) } else { None };
+            let Some(weights) = weights
+                .filter(|w| w.unit.is_some() && (w.cpu_weight.is_some() || w.io_weight.is_some()))
+            else {
+                self.cgroup_group.set_visible(false);
+                return;
+            };
+
+            self.cgroup_group.set_visible(true);
+            if let Some(unit) = &weights.unit {
+                self.cgroup_group
+                    .set_description(Some(&i18n_f("Applies to all processes in {}", &[unit])));
+            }
+
+            self.cpu_weight.set_visible(weights.cpu_weight.is_some());
+            self.cpu_weight
+                .set_value(weights.cpu_weight.unwrap_or_default() as f64);
+            self.io_weight.set_visible(weights.io_weight.is_some());
+            self.io_weight
+                .set_value(weights.io_weight.unwrap_or_default() as f64);
+
+            self.cgroup_weights.replace(Some(weights));
+            self.apply_weights.set_sensitive(false);
+        }
+
+        fn edited_weights(&self) -> (Option<u32>, Option<u32>) {
+            let weights = self.cgroup_weights.borrow();
+            let Some(weights) = weights.as_ref() else {
+                return (None, None);
+            };
+
+            let cpu_weight = Some(self.cpu_weight.value() as u32)
+                .filter(|_| weights.cpu_weight.is_some())
+                .filter(|w| Some(*w) != weights.cpu_weight);
+            let io_weight = Some(self.io_weight.value() as u32)
+                .filter(|_| weights.io_weight.is_some())
+                .filter(|w| Some(*w) != weights.io_weight);
+
+            (cpu_weight, io_weight)
+        }
+
+        fn configure_cgroup_weights(&self) {
+            for row in [&*self.cpu_weight, &*self.io_weight] {
+                row.connect_value_notify({
+                    let this = self.obj().downgrade();
+                    move |_| {
+                        let Some(this) = this.upgrade() else {
+                            return;
+                        };
+                        let this = this.imp();
+
+                        let (cpu_weight, io_weight) = this.edited_weights();
+                        this.apply_weights
+                            .set_sensitive(cpu_weight.is_some() || io_weight.is_some());
+                    }
+                });
+            }
+
+            self.apply_weights.connect_clicked({
+                let this = self.obj().downgrade();
+                move |_| {
+                    let Some(this) = this.upgrade() else {
+                        return;
+                    };
+                    let this = this.imp();
+
+                    let (cpu_weight, io_weight) = this.edited_weights();
+                    let Some(mut weights) = this.cgroup_weights.borrow().clone() else {
+                        return;
+                    };
+
+                    match app!().sys_info() {
+                        Ok(sys_info) => {
+                            sys_info.set_cgroup_weights(weights.clone(), cpu_weight, io_weight);
+                        }
+                        Err(e) => {
+                            g_critical!(
+                                "MissionCenter::DetailsDialog",
+                                "Failed to get sys_info from MissionCenterApplication: {}",
+                                e
+                            );
+                            return;
+                        }
+                    }
+
+                    weights.cpu_weight = cpu_weight.or(weights.cpu_weight);
+                    weights.io_weight = io_weight.or(weights.io_weight);
+                    this.cgroup_weights.replace(Some(weights));
+                    this.apply_weights.set_sensitive(false);
+                }
+            });
+        }
+
         fn unbind(&self) {
             self.cpu.unbind();
             self.memory.unbind();
@@ -188,6 +520,17 @@ mod imp {
     impl ObjectImpl for DetailsDialog {
         fn constructed(&self) {
             self.parent_constructed();
+
+            self.configure_cgroup_weights();
+
+            self.refresh_connections.connect_clicked({
+                let this = self.obj().downgrade();
+                move |_| {
+                    if let Some(this) = this.upgrade() {
+                        this.imp().bind_connections();
+                    }
+                }
+            });
         }
     }
 
@@ -204,6 +547,153 @@ mod imp {
     }
 }
 
+fn property_row(title: &str, value: &str) -> adw::ActionRow {
+    let row = adw::ActionRow::new();
+    row.set_title(title);
+    row.set_subtitle(value);
+    row.set_subtitle_selectable(true);
+    row.add_css_class("property");
+
+    row
+}
+
+fn connection_row(socket: &TcpSocket) -> adw::ExpanderRow {
+    let row = adw::ExpanderRow::new();
+    row.set_title(&socket.peer_address);
+
+    let mut subtitle = vec![socket.state.clone()];
+    if let Some(congestion_control) = &socket.congestion_control {
+        subtitle.push(congestion_control.clone());
+    }
+    if let Some((rtt, _)) = socket.rtt_ms {
+        subtitle.push(i18n_f("RTT {} ms", &[&format!("{:.1}", rtt)]));
+    }
+    row.set_subtitle(&subtitle.join(" · "));
+
+    let bytes = |value: u64| {
+        crate::to_human_readable_nice(value as f32, &DataType::NetworkBytes, &settings!())
+    };
+
+    let mut details = vec![
+        (i18n("Local Address"), socket.local_address.clone()),
+        (i18n("Receive Queue"), bytes(socket.receive_queue)),
+        (i18n("Send Queue"), bytes(socket.send_queue)),
+    ];
+    if let Some(receive_buffer) = socket.receive_buffer {
+        details.push((i18n("Receive Buffer"), bytes(receive_buffer)));
+    }
+    if let Some(send_buffer) = socket.send_buffer {
+        details.push((i18n("Send Buffer"), bytes(send_buffer)));
+    }
+    if let Some((rtt, variance)) = socket.rtt_ms {
+        details.push((
+            i18n("Round Trip Time"),
+            i18n_f(
+                "{} ms (± {} ms)",
+                &[&format!("{:.1}", rtt), &format!("{:.1}", variance)],
+            ),
+        ));
+    }
+    if let Some(retransmits) = socket.retransmits {
+        details.push((i18n("Retransmitted Segments"), retransmits.to_string()));
+    }
+    details.push((
+        i18n("Processes"),
+        socket
+            .pids
+            .iter()
+            .map(|pid| pid.to_string())
+            .collect::<Vec<_>>()
+            .join(", "),
+    ));
+
+    for (title, value) in details {
+        row.add_row(&property_row(&title, &value));
+    }
+
+    row
+}
+
+/// The PID of the row and of all the processes below it
+fn process_ids(model: &RowModel) -> Vec<u32> {
+    let mut pids = vec![];
+    if model.pid() != 0 {
+        pids.push(model.pid());
+    }
+
+    let children = model.children();
+    for index in 0..children.n_items() {
+        if let Some(child) = children.item(index).and_downcast::<RowModel>() {
+            pids.extend(process_ids(&child));
+        }
+    }
+
+    pids
+}
+
+fn startup_row(title: &str, subtitle: &str, active: bool) -> adw::SwitchRow {
+    let row = adw::SwitchRow::new();
+    row.set_title(title);
+    row.set_subtitle(subtitle);
+    row.set_active(active);
+
+    row
+}
+
+fn connect_autostart_toggle(row: &adw::SwitchRow, entry: AutostartEntry) {
+    // Set while a failed change is undone, so it doesn't get written again
+    let reverting = Rc::new(Cell::new(false));
+    row.connect_active_notify(move |row| {
+        if reverting.get() {
+            return;
+        }
+
+        if let Err(e) = set_autostart_enabled(&entry, row.is_active()) {
+            g_warning!(
+                "MissionCenter::DetailsDialog",
+                "Failed to change autostart entry {}: {}",
+                entry.id,
+                e
+            );
+
+            reverting.set(true);
+            row.set_active(!row.is_active());
+            reverting.set(false);
+        }
+    });
+}
+
+fn executable_name(command_line: &str) -> Option<String> {
+    let program = command_line.split_whitespace().next()?;
+    Some(
+        std::path::Path::new(program)
+            .file_name()?
+            .to_str()?
+            .to_owned(),
+    )
+}
+
+/// Lowercase names an autostart entry or service of the app would go by: its ID, the last part
+/// of a reverse DNS ID and its executable; really short ones match too many unrelated things
+fn related_names(app_id: &str, executable: Option<&str>) -> Vec<String> {
+    let app_id = app_id.trim_end_matches(".desktop").to_lowercase();
+
+    let mut names = vec![];
+    if let Some((_, last)) = app_id.rsplit_once('.') {
+        names.push(last.to_owned());
+    }
+    if let Some(executable) = executable {
+        names.push(executable.to_lowercase());
+    }
+    names.push(app_id);
+
+    names.retain(|name| name.len() >= 3);
+    names.sort_unstable();
+    names.dedup();
+
+    names
+}
+
 glib::wrapper! {
     pub struct DetailsDialog(ObjectSubclass<imp::DetailsDialog>)
         @extends adw::Dialog, gtk::Widget,
@@ -211,7 +701,11 @@ glib::wrapper! {
 }
 
 impl DetailsDialog {
-    pub fn new(model: RowModel) -> Self {
+    pub fn new(
+        model: RowModel,
+        service_states: &HashMap<String, bool>,
+        sandbox: Option<&Sandbox>,
+    ) -> Self {
         let this: Self = glib::Object::builder()
             .property("follows-content-size", true)
             .build();
@@ -220,6 +714,10 @@ impl DetailsDialog {
 
         imp.model.replace(model);
         imp.bind();
+        imp.bind_startup(service_states);
+        imp.bind_sandbox(sandbox);
+        imp.bind_cgroup_weights();
+        imp.bind_connections();
 
         this
     }
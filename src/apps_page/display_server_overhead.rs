@@ -0,0 +1,115 @@
+/* apps_page/display_server_overhead.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use gtk::{glib, prelude::*, subclass::prelude::*};
+
+use crate::{i18n::*, magpie_client::DisplayServerUsage};
+
+mod imp {
+    use super::*;
+
+    #[derive(gtk::CompositeTemplate)]
+    #[template(
+        resource = "/io/missioncenter/MissionCenter/ui/apps_page/display_server_overhead.ui"
+    )]
+    pub struct DisplayServerOverhead {
+        #[template_child]
+        pub display_server: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub others: TemplateChild<gtk::Label>,
+    }
+
+    impl Default for DisplayServerOverhead {
+        fn default() -> Self {
+            Self {
+                display_server: TemplateChild::default(),
+                others: TemplateChild::default(),
+            }
+        }
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for DisplayServerOverhead {
+        const NAME: &'static str = "DisplayServerOverhead";
+        type Type = super::DisplayServerOverhead;
+        type ParentType = gtk::Box;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for DisplayServerOverhead {}
+
+    impl WidgetImpl for DisplayServerOverhead {}
+
+    impl BoxImpl for DisplayServerOverhead {}
+}
+
+glib::wrapper! {
+    pub struct DisplayServerOverhead(ObjectSubclass<imp::DisplayServerOverhead>)
+        @extends gtk::Box, gtk::Widget,
+        @implements gtk::Buildable;
+}
+
+impl DisplayServerOverhead {
+    pub fn update(&self, usage: &DisplayServerUsage) {
+        // Nothing to attribute when running headless or the compositor is not visible to us
+        if usage.processes.is_empty() {
+            self.set_visible(false);
+            return;
+        }
+        self.set_visible(true);
+
+        let imp = self.imp();
+
+        let display_server = &usage.display_server;
+        imp.display_server.set_text(&i18n_f(
+            "CPU {}% · GPU {}%",
+            &[
+                &format!("{:.1}", display_server.cpu_usage),
+                &format!("{:.0}", display_server.gpu_usage),
+            ],
+        ));
+
+        let others = &usage.others;
+        imp.others.set_text(&i18n_f(
+            "Apps and other processes: CPU {}% · GPU {}%",
+            &[
+                &format!("{:.1}", others.cpu_usage),
+                &format!("{:.0}", others.gpu_usage),
+            ],
+        ));
+
+        let names = usage
+            .processes
+            .iter()
+            .map(|(pid, name)| format!("{} ({})", name, pid))
+            .collect::<Vec<_>>();
+        self.set_tooltip_text(Some(&i18n_f(
+            "Counted as display server and compositor: {}",
+            &[&names.join(", ")],
+        )));
+    }
+}
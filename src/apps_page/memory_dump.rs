@@ -0,0 +1,231 @@
+/* apps_page/memory_dump.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, TryRecvError};
+use std::time::Duration;
+
+use adw::prelude::*;
+use gtk::gio;
+use gtk::glib::{self, g_warning};
+
+use super::row_model::RowModel;
+use super::AppsPage;
+use crate::{i18n::*, magpie_client, settings, DataType};
+
+/// How often the progress is updated, from how far the dump has grown
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Ask for a folder, warn about what the dump holds and how large it gets, then capture the
+/// memory of the process `row` stands for
+pub fn capture(page: &AppsPage, row: &RowModel) {
+    let pid = row.pid();
+    let name = row.name().to_string();
+    // What the process has resident is a good guess for how much of it ends up in the dump
+    let estimate = row.memory_usage();
+
+    let dialog = gtk::FileDialog::builder()
+        .title(i18n("Save Memory Dump To"))
+        .modal(true)
+        .build();
+    dialog.select_folder(
+        page.root().and_downcast_ref::<gtk::Window>(),
+        None::<&gio::Cancellable>,
+        {
+            let page = page.downgrade();
+            move |result| {
+                let Some(page) = page.upgrade() else {
+                    return;
+                };
+                let Some(directory) = result.ok().and_then(|file| file.path()) else {
+                    return;
+                };
+
+                confirm(&page, pid, name, estimate, directory);
+            }
+        },
+    );
+}
+
+fn confirm(page: &AppsPage, pid: u32, name: String, estimate: u64, directory: PathBuf) {
+    let settings = settings!();
+    let format_size =
+        |size: u64| crate::to_human_readable_nice(size as f32, &DataType::MemoryBytes, &settings);
+
+    let free = gio::File::for_path(&directory)
+        .query_filesystem_info("filesystem::free", gio::Cancellable::NONE)
+        .ok()
+        .map(|info| info.attribute_uint64("filesystem::free"));
+    let fits = free.map_or(true, |free| free > estimate);
+
+    let mut body = i18n_f(
+        "The dump holds everything {} has in memory, which can include passwords and other private data. It will take up about {}, and the process is paused while it is written.",
+        &[&name, &format_size(estimate)],
+    );
+    if let (false, Some(free)) = (fits, free) {
+        body.push_str("\n\n");
+        body.push_str(&i18n_f(
+            "Only {} is free in the chosen folder, the dump may not fit.",
+            &[&format_size(free)],
+        ));
+    }
+
+    let dialog = adw::AlertDialog::new(
+        Some(&i18n_f("Capture Memory Dump of {}?", &[&name])),
+        Some(&body),
+    );
+    dialog.add_responses(&[("cancel", &i18n("_Cancel")), ("capture", &i18n("_Capture"))]);
+    dialog.set_response_appearance(
+        "capture",
+        if fits {
+            adw::ResponseAppearance::Suggested
+        } else {
+            adw::ResponseAppearance::Destructive
+        },
+    );
+    dialog.set_default_response(Some("cancel"));
+    dialog.set_close_response("cancel");
+    dialog.connect_response(Some("capture"), {
+        let page = page.downgrade();
+        move |_, _| {
+            if let Some(page) = page.upgrade() {
+                run(&page, pid, &name, estimate, &directory);
+            }
+        }
+    });
+    dialog.present(Some(page));
+}
+
+fn run(page: &AppsPage, pid: u32, name: &str, estimate: u64, directory: &Path) {
+    let timestamp = glib::DateTime::now_local()
+        .and_then(|now| now.format("%Y%m%d-%H%M%S"))
+        .map(|now| now.to_string())
+        .unwrap_or_default();
+    let file_name = name
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+    let prefix = directory.join(format!("core.{}.{}", file_name, timestamp));
+    let dump_path = magpie_client::memory_dump_path(&prefix, pid);
+
+    let progress = gtk::ProgressBar::new();
+    progress.set_show_text(true);
+    progress.set_text(Some(&i18n("Waiting for authorization…")));
+
+    let dialog = adw::AlertDialog::new(
+        Some(&i18n("Capturing Memory Dump")),
+        Some(&i18n_f(
+            "Writing the memory of {} to {}",
+            &[name, &dump_path.display().to_string()],
+        )),
+    );
+    dialog.set_extra_child(Some(&progress));
+    dialog.add_responses(&[("close", &i18n("_Close"))]);
+    dialog.set_response_enabled("close", false);
+    dialog.set_can_close(false);
+    dialog.present(Some(page));
+
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        let _ = sender.send(magpie_client::capture_memory_dump(pid, &prefix));
+    });
+
+    let name = name.to_owned();
+    glib::timeout_add_local(PROGRESS_INTERVAL, move || {
+        let settings = settings!();
+        let format_size = |size: u64| {
+            crate::to_human_readable_nice(size as f32, &DataType::MemoryBytes, &settings)
+        };
+
+        let result = match receiver.try_recv() {
+            Ok(result) => result,
+            Err(TryRecvError::Disconnected) => Err("The capture stopped unexpectedly".to_owned()),
+            Err(TryRecvError::Empty) => {
+                match std::fs::metadata(&dump_path).map(|metadata| metadata.len()) {
+                    Ok(written) if written > 0 => {
+                        // The estimate is rough, don't claim to be done before `gcore` is
+                        progress.set_fraction((written as f64 / estimate.max(1) as f64).min(0.99));
+                        progress.set_text(Some(&format_size(written)));
+                    }
+                    _ => progress.pulse(),
+                }
+                return glib::ControlFlow::Continue;
+            }
+        };
+
+        match result {
+            Ok(path) => {
+                let size = std::fs::metadata(&path)
+                    .map(|metadata| metadata.len())
+                    .unwrap_or(0);
+                progress.set_fraction(1.);
+                progress.set_text(Some(&format_size(size)));
+
+                dialog.set_heading(Some(&i18n("Memory Dump Captured")));
+                dialog.set_body(&i18n_f(
+                    "The memory of {} was written to {}",
+                    &[&name, &path.display().to_string()],
+                ));
+                dialog.add_response("show", &i18n("_Show in Folder"));
+                dialog.set_response_appearance("show", adw::ResponseAppearance::Suggested);
+                dialog.connect_response(Some("show"), move |dialog, _| {
+                    let window = dialog.root().and_downcast::<gtk::Window>();
+                    gtk::FileLauncher::new(Some(&gio::File::for_path(&path)))
+                        .open_containing_folder(
+                            window.as_ref(),
+                            gio::Cancellable::NONE,
+                            |result| {
+                                if let Err(e) = result {
+                                    g_warning!(
+                                        "MissionCenter::AppsPage",
+                                        "Failed to open memory dump location: {}",
+                                        e
+                                    );
+                                }
+                            },
+                        );
+                });
+            }
+            Err(e) => {
+                g_warning!(
+                    "MissionCenter::AppsPage",
+                    "Failed to capture memory dump of {}: {}",
+                    pid,
+                    e
+                );
+
+                progress.set_visible(false);
+                dialog.set_heading(Some(&i18n("Failed to Capture Memory Dump")));
+                dialog.set_body(&e);
+            }
+        }
+
+        dialog.set_response_enabled("close", true);
+        dialog.set_can_close(true);
+
+        glib::ControlFlow::Break
+    });
+}
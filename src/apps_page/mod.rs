@@ -29,16 +29,25 @@ use glib::translate::from_glib_full;
 use glib::{gobject_ffi, Object};
 use gtk::{gio, glib, subclass::prelude::*};
 
-use crate::magpie_client::App;
+use crate::magpie_client::{App, Sandbox, SandboxKind};
 
 use crate::i18n::{i18n, ni18n_f};
+use app_history::AppHistory;
+use background_jobs_panel::BackgroundJobsPanel;
 use columns::*;
+use display_server_overhead::DisplayServerOverhead;
 use row_model::{ContentType, RowModel, RowModelBuilder, SectionType};
 
 mod actions;
+mod app_history;
+mod background_jobs_panel;
+mod batch_stop;
 mod columns;
 mod details_dialog;
+mod display_server_overhead;
+mod memory_dump;
 mod models;
+mod process_properties;
 mod row_model;
 mod settings;
 
@@ -54,6 +63,12 @@ mod imp {
         #[template_child]
         pub h2: TemplateChild<gtk::Label>,
         #[template_child]
+        pub display_server_overhead: TemplateChild<DisplayServerOverhead>,
+        #[template_child]
+        pub background_jobs_panel: TemplateChild<BackgroundJobsPanel>,
+        #[template_child]
+        pub owner_filter: TemplateChild<gtk::DropDown>,
+        #[template_child]
         pub collapse_label: TemplateChild<gtk::Label>,
         #[template_child]
         pub stop_label: TemplateChild<gtk::Label>,
@@ -68,6 +83,8 @@ mod imp {
         #[template_child]
         pub pid_column: TemplateChild<gtk::ColumnViewColumn>,
         #[template_child]
+        pub user_column: TemplateChild<gtk::ColumnViewColumn>,
+        #[template_child]
         pub cpu_column: TemplateChild<gtk::ColumnViewColumn>,
         #[template_child]
         pub memory_column: TemplateChild<gtk::ColumnViewColumn>,
@@ -92,11 +109,18 @@ mod imp {
 
         pub root_process: Cell<u32>,
         pub running_apps: RefCell<HashMap<String, App>>,
+        /// Whether each service is enabled, kept for the details dialog since the services page
+        /// takes the list over once it's done here
+        pub service_states: RefCell<HashMap<String, bool>>,
+        /// Kept for the details dialog, by app ID
+        pub app_sandboxes: RefCell<HashMap<String, Sandbox>>,
 
         pub row_sorter: OnceCell<gtk::TreeListRowSorter>,
 
         pub app_icons: RefCell<HashMap<u32, String>>,
         pub selected_item: RefCell<RowModel>,
+        /// Every app and process that is selected, `selected_item` is the first of them
+        pub selected_items: RefCell<Vec<RowModel>>,
 
         pub action_stop: gio::SimpleAction,
         pub action_force_stop: gio::SimpleAction,
@@ -107,6 +131,9 @@ mod imp {
         pub action_user_one: gio::SimpleAction,
         pub action_user_two: gio::SimpleAction,
         pub action_details: gio::SimpleAction,
+        pub action_memory_dump: gio::SimpleAction,
+        pub action_open_location: gio::SimpleAction,
+        pub action_properties: gio::SimpleAction,
 
         pub use_merged_stats: Cell<bool>,
     }
@@ -116,6 +143,9 @@ mod imp {
             Self {
                 h1: TemplateChild::default(),
                 h2: TemplateChild::default(),
+                display_server_overhead: TemplateChild::default(),
+                background_jobs_panel: TemplateChild::default(),
+                owner_filter: TemplateChild::default(),
                 collapse_label: TemplateChild::default(),
                 stop_label: TemplateChild::default(),
                 force_stop_label: TemplateChild::default(),
@@ -123,6 +153,7 @@ mod imp {
                 column_view: TemplateChild::default(),
                 name_column: TemplateChild::default(),
                 pid_column: TemplateChild::default(),
+                user_column: TemplateChild::default(),
                 cpu_column: TemplateChild::default(),
                 memory_column: TemplateChild::default(),
                 shared_memory_column: TemplateChild::default(),
@@ -147,11 +178,14 @@ mod imp {
 
                 root_process: Cell::new(1),
                 running_apps: RefCell::new(HashMap::new()),
+                service_states: RefCell::new(HashMap::new()),
+                app_sandboxes: RefCell::new(HashMap::new()),
 
                 row_sorter: OnceCell::new(),
 
                 app_icons: RefCell::new(HashMap::new()),
                 selected_item: RefCell::new(RowModelBuilder::new().build()),
+                selected_items: RefCell::new(vec![]),
 
                 action_stop: gio::SimpleAction::new("stop", None),
                 action_force_stop: gio::SimpleAction::new("force-stop", None),
@@ -162,6 +196,9 @@ mod imp {
                 action_user_one: gio::SimpleAction::new("user-one", None),
                 action_user_two: gio::SimpleAction::new("user-two", None),
                 action_details: gio::SimpleAction::new("details", None),
+                action_memory_dump: gio::SimpleAction::new("memory-dump", None),
+                action_open_location: gio::SimpleAction::new("open-location", None),
+                action_properties: gio::SimpleAction::new("properties", None),
 
                 use_merged_stats: Cell::new(false),
             }
@@ -196,6 +233,9 @@ mod imp {
 
         fn class_init(klass: &mut Self::Class) {
             RowModel::ensure_type();
+            DisplayServerOverhead::ensure_type();
+            BackgroundJobsPanel::ensure_type();
+            AppHistory::ensure_type();
 
             klass.bind_template();
         }
@@ -223,6 +263,7 @@ mod imp {
 
             actions::configure(self);
 
+            configure_column_chooser(&self.column_view);
             update_column_order(&self.column_view);
 
             self.name_column
@@ -234,6 +275,11 @@ mod imp {
             self.pid_column
                 .set_sorter(Some(&pid_sorter(&self.column_view)));
 
+            self.user_column
+                .set_factory(Some(&user_list_item_factory()));
+            self.user_column
+                .set_sorter(Some(&user_sorter(&self.column_view)));
+
             self.cpu_column.set_factory(Some(&cpu_list_item_factory()));
             self.cpu_column
                 .set_sorter(Some(&cpu_sorter(&self.column_view)));
@@ -328,6 +374,9 @@ impl AppsPage {
             &[buffer.as_str()],
         ));
 
+        imp.display_server_overhead.update(&readings.display_server);
+        imp.background_jobs_panel.update(&readings.background_jobs);
+
         update_column_titles(
             &imp.cpu_column,
             &imp.memory_column,
@@ -346,6 +395,8 @@ impl AppsPage {
                     &readings.running_processes,
                     child,
                     &imp.processes_section.children(),
+                    &readings.process_cpu_split,
+                    &readings.process_owners,
                     &imp.app_icons.borrow(),
                     "application-x-executable-symbolic",
                     imp.use_merged_stats.get(),
@@ -358,6 +409,8 @@ impl AppsPage {
         models::update_apps(
             &readings.running_apps,
             &readings.running_processes,
+            &readings.process_cpu_split,
+            &readings.process_owners,
             &process_model_map,
             &mut imp.app_icons.borrow_mut(),
             &imp.apps_section.children(),
@@ -367,9 +420,16 @@ impl AppsPage {
             &mut *imp.running_apps.borrow_mut(),
             std::mem::take(&mut readings.running_apps),
         );
+        update_service_states(&mut imp.service_states.borrow_mut(), readings);
+        update_sandboxes(
+            &imp.apps_section.children(),
+            &mut imp.app_sandboxes.borrow_mut(),
+            readings,
+        );
+        update_game_mode(&process_model_map, readings);
 
         // Select the first item in the list
-        selection_model.set_selected(0);
+        selection_model.select_item(0, true);
 
         true
     }
@@ -397,6 +457,9 @@ impl AppsPage {
             &[buffer.as_str()],
         ));
 
+        imp.display_server_overhead.update(&readings.display_server);
+        imp.background_jobs_panel.update(&readings.background_jobs);
+
         update_column_titles(
             &imp.cpu_column,
             &imp.memory_column,
@@ -415,6 +478,8 @@ impl AppsPage {
                     &readings.running_processes,
                     child,
                     &imp.processes_section.children(),
+                    &readings.process_cpu_split,
+                    &readings.process_owners,
                     &imp.app_icons.borrow(),
                     "application-x-executable-symbolic",
                     imp.use_merged_stats.get(),
@@ -426,6 +491,8 @@ impl AppsPage {
         models::update_apps(
             &readings.running_apps,
             &readings.running_processes,
+            &readings.process_cpu_split,
+            &readings.process_owners,
             &process_model_map,
             &mut imp.app_icons.borrow_mut(),
             &imp.apps_section.children(),
@@ -435,6 +502,13 @@ impl AppsPage {
             &mut *imp.running_apps.borrow_mut(),
             std::mem::take(&mut readings.running_apps),
         );
+        update_service_states(&mut imp.service_states.borrow_mut(), readings);
+        update_sandboxes(
+            &imp.apps_section.children(),
+            &mut imp.app_sandboxes.borrow_mut(),
+            readings,
+        );
+        update_game_mode(&process_model_map, readings);
 
         if let Some(row_sorter) = imp.row_sorter.get() {
             row_sorter.changed(gtk::SorterChange::Different)
@@ -457,6 +531,63 @@ impl AppsPage {
         self.imp().expand();
     }
 
+    pub fn select_process(&self, pid: u32) -> bool {
+        self.select_row(|row_model| {
+            row_model.content_type() == ContentType::Process && row_model.pid() == pid
+        })
+    }
+
+    /// Select the app or process called `name`, or failing that the first one whose name contains
+    /// it, ignoring case
+    pub fn select_process_by_name(&self, name: &str) -> bool {
+        let name = name.to_lowercase();
+        let is_candidate =
+            |row_model: &RowModel| row_model.content_type() != ContentType::SectionHeader;
+
+        self.select_row(|row_model| {
+            is_candidate(row_model) && row_model.name().to_lowercase() == name
+        }) || self.select_row(|row_model| {
+            is_candidate(row_model) && row_model.name().to_lowercase().contains(&name)
+        })
+    }
+
+    fn select_row(&self, predicate: impl Fn(&RowModel) -> bool) -> bool {
+        let imp = self.imp();
+
+        let Some(selection_model) = imp
+            .column_view
+            .model()
+            .and_then(|model| model.downcast::<gtk::MultiSelection>().ok())
+        else {
+            return false;
+        };
+
+        for i in 0..selection_model.n_items() {
+            let Some(row_model) = selection_model
+                .item(i)
+                .and_then(|item| item.downcast::<gtk::TreeListRow>().ok())
+                .and_then(|row| row.item())
+                .and_then(|obj| obj.downcast::<RowModel>().ok())
+            else {
+                continue;
+            };
+
+            if !predicate(&row_model) {
+                continue;
+            }
+
+            imp.column_view.scroll_to(
+                i,
+                None,
+                gtk::ListScrollFlags::FOCUS | gtk::ListScrollFlags::SELECT,
+                None,
+            );
+            return true;
+        }
+
+        false
+    }
+
     pub fn running_apps(&self) -> HashMap<String, App> {
         self.imp().running_apps.borrow().clone()
     }
@@ -480,7 +611,10 @@ fn select_item(model: &gtk::SelectionModel, id: &str) -> bool {
             .and_then(|obj| obj.downcast::<RowModel>().ok())
         {
             if item.content_type() != ContentType::SectionHeader && item.id() == id {
-                model.select_item(i, false);
+                // Right clicking one of several selected rows acts on all of them
+                if !model.is_selected(i) {
+                    model.select_item(i, true);
+                }
                 return true;
             }
         }
@@ -488,3 +622,49 @@ fn select_item(model: &gtk::SelectionModel, id: &str) -> bool {
 
     false
 }
+
+fn update_service_states(
+    service_states: &mut HashMap<String, bool>,
+    readings: &crate::magpie_client::Readings,
+) {
+    service_states.clear();
+    service_states.extend(
+        readings
+            .services
+            .values()
+            .map(|service| (service.id.clone(), service.enabled)),
+    );
+}
+
+fn update_sandboxes(
+    apps: &gio::ListStore,
+    app_sandboxes: &mut HashMap<String, Sandbox>,
+    readings: &mut crate::magpie_client::Readings,
+) {
+    *app_sandboxes = std::mem::take(&mut readings.app_sandboxes);
+
+    for i in 0..apps.n_items() {
+        let Some(app) = apps.item(i).and_then(|obj| obj.downcast::<RowModel>().ok()) else {
+            continue;
+        };
+
+        let sandbox = match app_sandboxes.get(app.id().as_str()).map(|s| s.kind) {
+            Some(SandboxKind::Flatpak) => "Flatpak",
+            Some(SandboxKind::Snap) => "Snap",
+            None => "",
+        };
+        app.set_sandbox(sandbox);
+    }
+}
+
+fn update_game_mode(
+    process_model_map: &HashMap<u32, RowModel>,
+    readings: &crate::magpie_client::Readings,
+) {
+    for (pid, row_model) in process_model_map {
+        let game_mode = readings.game_mode.iter().any(|game| game.pid == *pid);
+        if row_model.game_mode() != game_mode {
+            row_model.set_game_mode(game_mode);
+        }
+    }
+}
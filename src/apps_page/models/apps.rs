@@ -28,11 +28,15 @@ use magpie_types::apps::icon::Icon;
 use magpie_types::apps::App;
 use magpie_types::processes::{Process, ProcessUsageStats};
 
+use super::processes::{owner, set_cpu_split};
 use crate::apps_page::row_model::{ContentType, RowModel, RowModelBuilder, SectionType};
+use crate::magpie_client::{split_cpu_usage, CpuSplit, ProcessOwner, SplitUsage};
 
 pub fn update(
     app_map: &HashMap<String, App>,
     process_map: &HashMap<u32, Process>,
+    cpu_splits: &HashMap<u32, CpuSplit>,
+    process_owners: &HashMap<u32, ProcessOwner>,
     process_model_map: &HashMap<u32, RowModel>,
     app_icons: &mut HashMap<u32, String>,
     list: &gio::ListStore,
@@ -67,6 +71,9 @@ pub fn update(
             continue;
         }
 
+        // An app is shown as running as whoever its first process does
+        let (uid, user) = owner(process_owners, primary_processes[0].pid);
+
         let row_model = if let Some(index) = list.find_with_equal_func(|obj| {
             let Some(row_model) = obj.downcast_ref::<RowModel>() else {
                 return false;
@@ -83,6 +90,8 @@ pub fn update(
                 .content_type(ContentType::App)
                 .section_type(SectionType::Apps)
                 .id(&app.id)
+                .uid(uid)
+                .user(user)
                 .build();
             list.append(&row_model);
             row_model
@@ -120,8 +129,14 @@ pub fn update(
         }
 
         let mut usage_stats = ProcessUsageStats::default();
+        let mut cpu_split = None;
         for process in &primary_processes {
             usage_stats.merge(&process.merged_usage_stats(&process_map));
+            if let Some(split) = split_cpu_usage(process, process_map, cpu_splits, true) {
+                cpu_split
+                    .get_or_insert_with(SplitUsage::default)
+                    .merge(&split);
+            }
             app_icons.insert(process.pid, icon.to_string());
 
             if app_children
@@ -144,6 +159,9 @@ pub fn update(
 
         row_model.set_name(app.name.as_str());
         row_model.set_icon(icon);
+        row_model.set_uid(uid);
+        row_model.set_user(user);
+        set_cpu_split(&row_model, cpu_split);
         row_model.set_cpu_usage(usage_stats.cpu_usage);
         row_model.set_memory_usage(usage_stats.memory_usage);
         row_model.set_shared_memory_usage(usage_stats.shared_memory_usage);
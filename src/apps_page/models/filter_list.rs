@@ -18,6 +18,9 @@
  * SPDX-License-Identifier: GPL-3.0-or-later
  */
 
+use std::cell::RefCell;
+use std::rc::Rc;
+
 use gtk::gio;
 use gtk::glib::g_critical;
 use gtk::prelude::*;
@@ -26,6 +29,13 @@ use textdistance::{Algorithm, Levenshtein};
 
 use crate::app;
 use crate::apps_page::row_model::{ContentType, RowModel};
+use crate::magpie_client::{current_uid, is_system_uid};
+
+use super::search_query::SearchQuery;
+
+/// The entries of the owner drop down, in order
+const OWNER_CURRENT_USER: u32 = 1;
+const OWNER_SYSTEM: u32 = 2;
 
 pub fn model(tree_list_model: impl IsA<gio::ListModel>) -> gtk::FilterListModel {
     let Some(window) = app!().window() else {
@@ -36,6 +46,9 @@ pub fn model(tree_list_model: impl IsA<gio::ListModel>) -> gtk::FilterListModel
         return gtk::FilterListModel::new(Some(tree_list_model), None::<gtk::CustomFilter>);
     };
 
+    // Parsed again only when the search text changes, not for every row
+    let parsed_query = Rc::new(RefCell::new((String::new(), None::<SearchQuery>)));
+
     let filter = gtk::CustomFilter::new({
         let window = window.downgrade();
         move |obj| {
@@ -44,14 +57,6 @@ pub fn model(tree_list_model: impl IsA<gio::ListModel>) -> gtk::FilterListModel
             };
             let window = window.imp();
 
-            if !window.search_button.is_active() {
-                return true;
-            }
-
-            if window.header_search_entry.text().is_empty() {
-                return true;
-            }
-
             let Some(row_model) = obj
                 .downcast_ref::<gtk::TreeListRow>()
                 .and_then(|row| row.item())
@@ -64,9 +69,32 @@ pub fn model(tree_list_model: impl IsA<gio::ListModel>) -> gtk::FilterListModel
                 return true;
             }
 
+            if !owner_matches(window.apps_page.imp().owner_filter.selected(), &row_model) {
+                return false;
+            }
+
+            if !window.search_button.is_active() {
+                return true;
+            }
+
+            let query_text = window.header_search_entry.text();
+            if query_text.is_empty() {
+                return true;
+            }
+
+            {
+                let mut parsed_query = parsed_query.borrow_mut();
+                if parsed_query.0 != query_text.as_str() {
+                    *parsed_query = (query_text.to_string(), SearchQuery::parse(&query_text));
+                }
+                if let Some(query) = &parsed_query.1 {
+                    return query.matches(&row_model);
+                }
+            }
+
             let entry_name = row_model.name().to_lowercase();
             let pid = row_model.pid().to_string();
-            let search_query = window.header_search_entry.text().to_lowercase();
+            let search_query = query_text.to_lowercase();
 
             if entry_name.contains(&search_query) || pid.contains(&search_query) {
                 return true;
@@ -102,5 +130,33 @@ pub fn model(tree_list_model: impl IsA<gio::ListModel>) -> gtk::FilterListModel
         }
     });
 
+    window
+        .imp()
+        .apps_page
+        .imp()
+        .owner_filter
+        .connect_selected_notify({
+            let filter = filter.downgrade();
+            move |_| {
+                if let Some(filter) = filter.upgrade() {
+                    filter.changed(gtk::FilterChange::Different);
+                }
+            }
+        });
+
     gtk::FilterListModel::new(Some(tree_list_model), Some(filter))
 }
+
+fn owner_matches(selected: u32, row_model: &RowModel) -> bool {
+    let uid = row_model.uid();
+    // Owners are not known for remote machines, rather than hiding everything show it all
+    if uid == u32::MAX {
+        return true;
+    }
+
+    match selected {
+        OWNER_CURRENT_USER => current_uid() == Some(uid),
+        OWNER_SYSTEM => is_system_uid(uid),
+        _ => true,
+    }
+}
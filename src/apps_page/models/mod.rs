@@ -30,6 +30,7 @@ mod apps;
 mod base;
 mod filter_list;
 mod processes;
+mod search_query;
 mod selection;
 mod sort_list;
 mod tree_list;
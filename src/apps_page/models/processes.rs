@@ -26,11 +26,14 @@ use gtk::prelude::*;
 use magpie_types::processes::Process;
 
 use crate::apps_page::row_model::{ContentType, RowModel, RowModelBuilder, SectionType};
+use crate::magpie_client::{split_cpu_usage, CpuSplit, ProcessOwner, SplitUsage};
 
 pub fn update(
     process_map: &HashMap<u32, Process>,
     pid: &u32,
     list: &gio::ListStore,
+    cpu_splits: &HashMap<u32, CpuSplit>,
+    process_owners: &HashMap<u32, ProcessOwner>,
     app_icons: &HashMap<u32, String>,
     icon: &str,
     use_merged_stats: bool,
@@ -74,6 +77,9 @@ pub fn update(
         }
     };
 
+    // Set up front, new rows are filtered by their owner as soon as they are added
+    let (uid, user) = owner(process_owners, process.pid);
+
     let row_model = if let Some(index) = list.find_with_equal_func(|obj| {
         let Some(row_model) = obj.downcast_ref::<RowModel>() else {
             return false;
@@ -90,6 +96,8 @@ pub fn update(
             .content_type(ContentType::Process)
             .section_type(SectionType::Processes)
             .id(&process.pid.to_string())
+            .uid(uid)
+            .user(user)
             .build();
         list.append(&row_model);
         row_model
@@ -135,6 +143,12 @@ pub fn update(
     row_model.set_icon(icon);
     row_model.set_command_line(command_line);
     row_model.set_pid(process.pid);
+    row_model.set_uid(uid);
+    row_model.set_user(user);
+    set_cpu_split(
+        &row_model,
+        split_cpu_usage(process, process_map, cpu_splits, use_merged_stats),
+    );
     row_model.set_cpu_usage(usage_stats.cpu_usage);
     row_model.set_memory_usage(usage_stats.memory_usage);
     row_model.set_shared_memory_usage(usage_stats.shared_memory_usage);
@@ -148,6 +162,8 @@ pub fn update(
             process_map,
             child,
             &row_model.children(),
+            cpu_splits,
+            process_owners,
             app_icons,
             icon,
             use_merged_stats,
@@ -157,3 +173,16 @@ pub fn update(
 
     models.insert(process.pid, row_model);
 }
+
+/// UID and user name of a process, `u32::MAX` and an empty name when not known
+pub fn owner(process_owners: &HashMap<u32, ProcessOwner>, pid: u32) -> (u32, &str) {
+    process_owners
+        .get(&pid)
+        .map_or((u32::MAX, ""), |owner| (owner.uid, &*owner.name))
+}
+
+/// Set before the CPU usage, which is what the CPU column redraws on
+pub fn set_cpu_split(row_model: &RowModel, split: Option<SplitUsage>) {
+    row_model.set_cpu_system_usage(split.map_or(-1., |split| split.system_usage));
+    row_model.set_io_wait(split.and_then(|split| split.io_wait_percent).unwrap_or(-1.));
+}
@@ -0,0 +1,179 @@
+/* apps_page/models/search_query.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+//! Searches made of terms like `pid:1234`, `user:alice` or `cmd:/nginx/`, all of which have to
+//! match. A value between slashes is a regular expression, anything else is matched as text
+//! regardless of case.
+
+use regex::{Regex, RegexBuilder};
+
+use crate::apps_page::row_model::RowModel;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Field {
+    /// The name or PID, or for regular expressions the name or command line
+    Any,
+    Name,
+    Pid,
+    User,
+    Command,
+}
+
+impl Field {
+    fn from_prefix(prefix: &str) -> Option<Self> {
+        match prefix.to_lowercase().as_str() {
+            "name" => Some(Field::Name),
+            "pid" => Some(Field::Pid),
+            "user" => Some(Field::User),
+            "cmd" => Some(Field::Command),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+enum Pattern {
+    /// Lowercase, matched anywhere in the value; a PID has to be matched whole
+    Text(String),
+    Regex(Regex),
+}
+
+impl Pattern {
+    fn parse(value: &str) -> Self {
+        if let Some(expression) = value
+            .strip_prefix('/')
+            .and_then(|value| value.strip_suffix('/'))
+        {
+            // A half typed expression is searched for as is until it is valid
+            if let Ok(regex) = RegexBuilder::new(expression).case_insensitive(true).build() {
+                return Pattern::Regex(regex);
+            }
+        }
+
+        Pattern::Text(value.to_lowercase())
+    }
+
+    fn matches(&self, value: &str) -> bool {
+        match self {
+            Pattern::Text(text) => value.to_lowercase().contains(text),
+            Pattern::Regex(regex) => regex.is_match(value),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Term {
+    field: Field,
+    pattern: Pattern,
+}
+
+impl Term {
+    fn matches(&self, row_model: &RowModel) -> bool {
+        let pid = || row_model.pid().to_string();
+
+        match (self.field, &self.pattern) {
+            (Field::Any, Pattern::Text(_)) => {
+                self.pattern.matches(&row_model.name()) || self.pattern.matches(&pid())
+            }
+            (Field::Any, Pattern::Regex(_)) => {
+                self.pattern.matches(&row_model.name())
+                    || self.pattern.matches(&row_model.command_line())
+            }
+            (Field::Name, _) => self.pattern.matches(&row_model.name()),
+            (Field::Pid, Pattern::Text(text)) => pid() == *text,
+            (Field::Pid, Pattern::Regex(_)) => self.pattern.matches(&pid()),
+            (Field::User, _) => self.pattern.matches(&row_model.user()),
+            (Field::Command, _) => self.pattern.matches(&row_model.command_line()),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct SearchQuery {
+    terms: Vec<Term>,
+}
+
+impl SearchQuery {
+    /// `None` for plain text, which is left to the fuzzy search
+    pub fn parse(query: &str) -> Option<Self> {
+        let terms = tokens(query)
+            .into_iter()
+            .map(|token| {
+                let (field, value) = token
+                    .split_once(':')
+                    .and_then(|(prefix, value)| Some((Field::from_prefix(prefix)?, value)))
+                    .unwrap_or((Field::Any, token));
+
+                Term {
+                    field,
+                    pattern: Pattern::parse(value),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let is_plain = terms
+            .iter()
+            .all(|term| term.field == Field::Any && matches!(term.pattern, Pattern::Text(_)));
+        if is_plain {
+            return None;
+        }
+
+        Some(Self { terms })
+    }
+
+    pub fn matches(&self, row_model: &RowModel) -> bool {
+        self.terms.iter().all(|term| term.matches(row_model))
+    }
+}
+
+/// Split on whitespace, except inside a regular expression
+fn tokens(query: &str) -> Vec<&str> {
+    let mut tokens = vec![];
+
+    let mut start = None;
+    let mut in_regex = false;
+    let mut escaped = false;
+    for (i, c) in query.char_indices() {
+        let Some(token_start) = start else {
+            if !c.is_whitespace() {
+                start = Some(i);
+                in_regex = c == '/';
+            }
+            continue;
+        };
+
+        if in_regex {
+            if c == '/' && !escaped {
+                in_regex = false;
+            }
+            escaped = c == '\\' && !escaped;
+        } else if c.is_whitespace() {
+            tokens.push(&query[token_start..i]);
+            start = None;
+        } else if c == '/' && query[token_start..i].ends_with(':') {
+            in_regex = true;
+        }
+    }
+    if let Some(token_start) = start {
+        tokens.push(&query[token_start..]);
+    }
+
+    tokens
+}
@@ -22,57 +22,61 @@ use gtk::gio;
 use gtk::prelude::*;
 use gtk::subclass::prelude::*;
 
+use crate::app;
 use crate::apps_page::row_model::{ContentType, RowModel};
 use crate::apps_page::AppsPage;
 
 pub fn model(
     apps_page: &AppsPage,
     sort_list_model: impl IsA<gio::ListModel>,
-) -> gtk::SingleSelection {
-    let selection_model = gtk::SingleSelection::new(Some(sort_list_model));
-    selection_model.set_autoselect(true);
+) -> gtk::MultiSelection {
+    let selection_model = gtk::MultiSelection::new(Some(sort_list_model));
 
     let this = apps_page.downgrade();
-    selection_model.connect_selected_item_notify(move |model| {
+    selection_model.connect_selection_changed(move |model, _, _| {
         let Some(this) = this.upgrade() else {
             return;
         };
         let imp = this.imp();
 
-        let Some(row_model) = model
-            .selected_item()
-            .and_then(|item| item.downcast::<gtk::TreeListRow>().ok())
-            .and_then(|row| row.item())
-            .and_then(|obj| obj.downcast::<RowModel>().ok())
-        else {
-            return;
-        };
-
-        if row_model.content_type() == ContentType::SectionHeader {
-            imp.action_stop.set_enabled(false);
-            imp.action_force_stop.set_enabled(false);
-            imp.action_suspend.set_enabled(false);
-            imp.action_continue.set_enabled(false);
-            imp.action_hangup.set_enabled(false);
-            imp.action_interrupt.set_enabled(false);
-            imp.action_user_one.set_enabled(false);
-            imp.action_user_two.set_enabled(false);
-            imp.action_details.set_enabled(false);
+        let selection = model.selection();
+        let selected_items = (0..selection.size())
+            .filter_map(|i| {
+                model
+                    .item(selection.nth(i as u32))
+                    .and_then(|item| item.downcast::<gtk::TreeListRow>().ok())
+                    .and_then(|row| row.item())
+                    .and_then(|obj| obj.downcast::<RowModel>().ok())
+            })
+            .filter(|row_model| row_model.content_type() != ContentType::SectionHeader)
+            .collect::<Vec<_>>();
 
-            return;
-        }
+        // Stopping works on everything that is selected, the rest on a single app or process
+        let any = !selected_items.is_empty();
+        let single = selected_items.len() == 1;
 
-        imp.action_stop.set_enabled(true);
-        imp.action_force_stop.set_enabled(true);
-        imp.action_suspend.set_enabled(true);
-        imp.action_continue.set_enabled(true);
-        imp.action_hangup.set_enabled(true);
-        imp.action_interrupt.set_enabled(true);
-        imp.action_user_one.set_enabled(true);
-        imp.action_user_two.set_enabled(true);
-        imp.action_details.set_enabled(true);
+        imp.action_stop.set_enabled(any);
+        imp.action_force_stop.set_enabled(any);
+        imp.action_suspend.set_enabled(single);
+        imp.action_continue.set_enabled(single);
+        imp.action_hangup.set_enabled(single);
+        imp.action_interrupt.set_enabled(single);
+        imp.action_user_one.set_enabled(single);
+        imp.action_user_two.set_enabled(single);
+        imp.action_details.set_enabled(single);
+        // `gcore` needs to run where the process does, and dumps a single process; where it comes
+        // from is looked up on this machine too
+        let local_process = single
+            && selected_items[0].content_type() == ContentType::Process
+            && app!().remote_host().is_none();
+        imp.action_memory_dump.set_enabled(local_process);
+        imp.action_open_location.set_enabled(local_process);
+        imp.action_properties.set_enabled(local_process);
 
-        imp.selected_item.replace(row_model);
+        if let Some(row_model) = selected_items.first() {
+            imp.selected_item.replace(row_model.clone());
+        }
+        imp.selected_items.replace(selected_items);
     });
 
     selection_model
@@ -0,0 +1,185 @@
+/* apps_page/process_properties.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use adw::prelude::*;
+use gtk::gio;
+use gtk::glib::g_warning;
+use gtk::subclass::prelude::*;
+
+use super::row_model::RowModel;
+use super::AppsPage;
+use crate::app;
+use crate::i18n::*;
+use crate::magpie_client::ProcessDetails;
+
+const MAX_VALUE_WIDTH_CHARS: i32 = 50;
+
+fn details(row: &RowModel) -> Result<ProcessDetails, String> {
+    match app!().sys_info() {
+        Ok(sys_info) => sys_info.process_details(row.pid()),
+        Err(e) => {
+            g_warning!("MissionCenter::AppsPage", "Failed to get `sys_info`: {}", e);
+            Err(e.to_string())
+        }
+    }
+}
+
+/// Show the folder the executable of the process `row` stands for is in, with the file
+/// manager picked through the portal
+pub fn open_location(page: &AppsPage, row: &RowModel) {
+    let location = details(row).and_then(|details| {
+        details.exe_location.ok_or_else(|| {
+            if details.exe.is_none() {
+                i18n("The executable of this process can't be looked up.")
+            } else if details.exe_deleted {
+                i18n("The executable was removed or replaced since the process started.")
+            } else {
+                i18n("The executable isn't accessible from Mission Center.")
+            }
+        })
+    });
+
+    match location {
+        Ok(location) => reveal(page, &location),
+        Err(e) => {
+            let dialog = adw::AlertDialog::new(
+                Some(&i18n_f(
+                    "Can't Open the File Location of {}",
+                    &[&row.name()],
+                )),
+                Some(&e),
+            );
+            dialog.add_responses(&[("close", &i18n("_Close"))]);
+            dialog.present(Some(page));
+        }
+    }
+}
+
+/// A popover with where the process `row` stands for comes from, pointing at wherever the
+/// context menu was last opened
+pub fn show(page: &AppsPage, row: &RowModel) {
+    let content = gtk::Box::new(gtk::Orientation::Vertical, 10);
+    content.set_margin_start(5);
+    content.set_margin_end(5);
+    content.set_margin_top(5);
+    content.set_margin_bottom(5);
+
+    let title = gtk::Label::new(Some(&row.name()));
+    title.add_css_class("heading");
+    title.set_halign(gtk::Align::Start);
+    content.append(&title);
+
+    let popover = gtk::Popover::new();
+    popover.set_child(Some(&content));
+
+    match details(row) {
+        Ok(details) => {
+            let grid = gtk::Grid::new();
+            grid.set_row_spacing(5);
+            grid.set_column_spacing(10);
+
+            let mut properties = vec![];
+            if let Some(exe) = &details.exe {
+                let exe = if details.exe_deleted {
+                    i18n_f("{} (deleted)", &[exe])
+                } else {
+                    exe.clone()
+                };
+                properties.push((i18n("Executable"), exe));
+            }
+            properties.push((
+                i18n("Package"),
+                details.package.clone().unwrap_or_else(|| i18n("Unknown")),
+            ));
+            if let Some(flatpak_id) = &details.flatpak_id {
+                properties.push((i18n("Flatpak"), flatpak_id.clone()));
+            }
+            if let Some(snap_name) = &details.snap_name {
+                properties.push((i18n("Snap"), snap_name.clone()));
+            }
+
+            for (i, (name, value)) in properties.iter().enumerate() {
+                let name = gtk::Label::new(Some(name));
+                name.add_css_class("dim-label");
+                name.set_halign(gtk::Align::End);
+                name.set_valign(gtk::Align::Start);
+
+                let value = gtk::Label::new(Some(value));
+                value.set_halign(gtk::Align::Start);
+                value.set_selectable(true);
+                value.set_wrap(true);
+                value.set_wrap_mode(gtk::pango::WrapMode::WordChar);
+                value.set_max_width_chars(MAX_VALUE_WIDTH_CHARS);
+                value.set_xalign(0.);
+
+                grid.attach(&name, 0, i as i32, 1, 1);
+                grid.attach(&value, 1, i as i32, 1, 1);
+            }
+            content.append(&grid);
+
+            if let Some(location) = details.exe_location {
+                let button = gtk::Button::with_label(&i18n("Open File Location"));
+                button.set_halign(gtk::Align::End);
+                button.connect_clicked({
+                    let page = page.downgrade();
+                    let popover = popover.downgrade();
+                    move |_| {
+                        if let Some(popover) = popover.upgrade() {
+                            popover.popdown();
+                        }
+                        if let Some(page) = page.upgrade() {
+                            reveal(&page, &location);
+                        }
+                    }
+                });
+                content.append(&button);
+            }
+        }
+        Err(e) => {
+            let error = gtk::Label::new(Some(&e));
+            error.add_css_class("dim-label");
+            error.set_wrap(true);
+            error.set_max_width_chars(MAX_VALUE_WIDTH_CHARS);
+            content.append(&error);
+        }
+    }
+
+    popover.set_parent(page);
+    popover.set_pointing_to(page.imp().context_menu.pointing_to().as_ref());
+    popover.connect_closed(|popover| popover.unparent());
+    popover.popup();
+}
+
+fn reveal(page: &AppsPage, location: &str) {
+    let window = page.root().and_downcast::<gtk::Window>();
+    gtk::FileLauncher::new(Some(&gio::File::for_path(location))).open_containing_folder(
+        window.as_ref(),
+        gio::Cancellable::NONE,
+        |result| {
+            if let Err(e) = result {
+                g_warning!(
+                    "MissionCenter::AppsPage",
+                    "Failed to open executable location: {}",
+                    e
+                );
+            }
+        },
+    );
+}
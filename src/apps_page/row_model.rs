@@ -50,6 +50,12 @@ mod imp {
 
         #[property(get, set)]
         pub cpu_usage: Cell<f32>,
+        /// The part of `cpu_usage` spent in the kernel; negative when not known
+        #[property(get, set)]
+        pub cpu_system_usage: Cell<f32>,
+        /// Percentage of time spent waiting for block I/O; negative when not known
+        #[property(get, set)]
+        pub io_wait: Cell<f32>,
         #[property(get, set)]
         pub memory_usage: Cell<u64>,
         #[property(get, set)]
@@ -66,6 +72,19 @@ mod imp {
         #[property(get = Self::command_line, set = Self::set_command_line)]
         pub command_line: Cell<glib::GString>,
 
+        /// `u32::MAX`, which is never a valid UID, when the owner is not known
+        #[property(get, set)]
+        pub uid: Cell<u32>,
+        #[property(get = Self::user, set = Self::set_user)]
+        pub user: Cell<glib::GString>,
+
+        /// The process asked Feral GameMode to optimize the system for it
+        #[property(get, set)]
+        pub game_mode: Cell<bool>,
+        /// `Flatpak` or `Snap` for apps installed as one, empty otherwise
+        #[property(get = Self::sandbox, set = Self::set_sandbox)]
+        pub sandbox: Cell<glib::GString>,
+
         pub children: RefCell<gio::ListStore>,
     }
 
@@ -83,6 +102,8 @@ mod imp {
                 section_type: Cell::new(SectionType::Apps),
 
                 cpu_usage: Cell::new(0.),
+                cpu_system_usage: Cell::new(-1.),
+                io_wait: Cell::new(-1.),
                 memory_usage: Cell::new(0),
                 shared_memory_usage: Cell::new(0),
                 disk_usage: Cell::new(0.),
@@ -92,6 +113,12 @@ mod imp {
 
                 command_line: Cell::new(Default::default()),
 
+                uid: Cell::new(u32::MAX),
+                user: Cell::new(glib::GString::default()),
+
+                game_mode: Cell::new(false),
+                sandbox: Cell::new(glib::GString::default()),
+
                 children: RefCell::new(gio::ListStore::new::<super::RowModel>()),
             }
         }
@@ -169,6 +196,42 @@ mod imp {
 
             self.command_line.set(glib::GString::from(command_line));
         }
+
+        pub fn user(&self) -> glib::GString {
+            let user = self.user.take();
+            let result = user.clone();
+            self.user.set(user);
+
+            result
+        }
+
+        pub fn set_user(&self, user: &str) {
+            let current_user = self.user.take();
+            if current_user == user {
+                self.user.set(current_user);
+                return;
+            }
+
+            self.user.set(glib::GString::from(user));
+        }
+
+        pub fn sandbox(&self) -> glib::GString {
+            let sandbox = self.sandbox.take();
+            let result = sandbox.clone();
+            self.sandbox.set(sandbox);
+
+            result
+        }
+
+        pub fn set_sandbox(&self, sandbox: &str) {
+            let current_sandbox = self.sandbox.take();
+            if current_sandbox == sandbox {
+                self.sandbox.set(current_sandbox);
+                return;
+            }
+
+            self.sandbox.set(glib::GString::from(sandbox));
+        }
     }
 
     #[glib::object_subclass]
@@ -240,6 +303,9 @@ pub struct RowModelBuilder {
     network_usage: f32,
     gpu_usage: f32,
     gpu_mem_usage: u64,
+
+    uid: u32,
+    user: glib::GString,
 }
 
 #[allow(unused)]
@@ -263,6 +329,9 @@ impl RowModelBuilder {
             network_usage: 0.,
             gpu_usage: 0.,
             gpu_mem_usage: 0,
+
+            uid: u32::MAX,
+            user: glib::GString::default(),
         }
     }
 
@@ -331,6 +400,16 @@ impl RowModelBuilder {
         self
     }
 
+    pub fn uid(mut self, uid: u32) -> Self {
+        self.uid = uid;
+        self
+    }
+
+    pub fn user(mut self, user: &str) -> Self {
+        self.user = user.into();
+        self
+    }
+
     pub fn build(self) -> RowModel {
         let this = RowModel::new(self.content_type);
 
@@ -351,6 +430,9 @@ impl RowModelBuilder {
             this.network_usage.set(self.network_usage);
             this.gpu_usage.set(self.gpu_usage);
             this.gpu_memory_usage.set(self.gpu_mem_usage);
+
+            this.uid.set(self.uid);
+            this.user.set(self.user);
         }
 
         this
@@ -0,0 +1,259 @@
+/* dbus_interface.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+//! The `io.missioncenter.MissionCenter` interface, exported by the running instance next to the
+//! actions and the `org.gtk.Application` interface `GApplication` already exports, for scripts
+//! and shell extensions.
+//!
+//! `GetSummary` hands out the latest readings, keyed by the same ids the exports and the metrics
+//! stream use; the refresh interval is in milliseconds, and rounded to what the preferences
+//! allow.
+//!
+//! `DisabledCollectors` lists the ids of the collectors that are turned off, out of `processes`,
+//! `disks`, `network`, `gpus`, `fans` and `services`; setting it to an id that isn't one of
+//! those is refused as a whole.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use gtk::gio;
+use gtk::glib::{self, prelude::*};
+
+use crate::application::{disabled_collectors, set_disabled_collectors, DeepLink, INTERVAL_STEP};
+use crate::history::METRICS;
+use crate::magpie_client::{Collector, Readings};
+use crate::{app, settings};
+
+const INTERFACE_NAME: &str = "io.missioncenter.MissionCenter";
+
+const INTROSPECTION_XML: &str = r#"
+<node>
+  <interface name="io.missioncenter.MissionCenter">
+    <method name="ShowPage">
+      <arg name="name" type="s" direction="in"/>
+    </method>
+    <method name="ShowProcess">
+      <arg name="pid" type="u" direction="in"/>
+    </method>
+    <method name="FindProcess">
+      <arg name="name" type="s" direction="in"/>
+    </method>
+    <method name="GetSummary">
+      <arg name="summary" type="a{sd}" direction="out"/>
+    </method>
+    <method name="GetRefreshInterval">
+      <arg name="interval_ms" type="u" direction="out"/>
+    </method>
+    <method name="SetRefreshInterval">
+      <arg name="interval_ms" type="u" direction="in"/>
+    </method>
+    <property name="DisabledCollectors" type="as" access="readwrite"/>
+  </interface>
+</node>
+"#;
+
+/// The bounds of `app-update-interval-u64`, in steps of `INTERVAL_STEP`
+const MIN_INTERVAL_STEPS: u64 = 10;
+const MAX_INTERVAL_STEPS: u64 = 200;
+
+pub struct DBusInterface {
+    connection: gio::DBusConnection,
+    registration: Option<gio::RegistrationId>,
+    summary: Rc<RefCell<HashMap<String, f64>>>,
+}
+
+impl Drop for DBusInterface {
+    fn drop(&mut self) {
+        if let Some(registration) = self.registration.take() {
+            let _ = self.connection.unregister_object(registration);
+        }
+    }
+}
+
+impl DBusInterface {
+    /// Export the interface at the object path of the application
+    pub fn new(connection: &gio::DBusConnection, object_path: &str) -> Result<Self, glib::Error> {
+        let node = gio::DBusNodeInfo::for_xml(INTROSPECTION_XML)?;
+        let Some(interface) = node.lookup_interface(INTERFACE_NAME) else {
+            return Err(glib::Error::new(
+                gio::IOErrorEnum::NotFound,
+                "Missing io.missioncenter.MissionCenter interface",
+            ));
+        };
+
+        let summary = Rc::new(RefCell::new(HashMap::new()));
+
+        let registration = connection
+            .register_object(object_path, &interface)
+            .method_call({
+                let summary = summary.clone();
+                move |_, _, _, _, method, parameters, invocation| {
+                    let reply = method_call(&summary, method, &parameters);
+                    match reply {
+                        Ok(reply) => invocation.return_value(reply.as_ref()),
+                        Err(e) => invocation.return_gerror(e),
+                    }
+                }
+            })
+            .property(|_, _, _, _, property| get_property(property))
+            .set_property(|_, _, _, _, property, value| set_property(property, &value))
+            .build()?;
+
+        Ok(Self {
+            connection: connection.clone(),
+            registration: Some(registration),
+            summary,
+        })
+    }
+
+    pub fn update(&self, readings: &Readings) {
+        let mut summary = self.summary.borrow_mut();
+        summary.clear();
+        for metric in METRICS.iter() {
+            let value = metric.current_value(readings);
+            // Not everything is available on every machine
+            if value.is_finite() {
+                summary.insert(metric.field().id, value as f64);
+            }
+        }
+    }
+}
+
+fn method_call(
+    summary: &Rc<RefCell<HashMap<String, f64>>>,
+    method: &str,
+    parameters: &glib::Variant,
+) -> Result<Option<glib::Variant>, glib::Error> {
+    let invalid_args = || glib::Error::new(gio::DBusError::InvalidArgs, "Invalid arguments");
+
+    match method {
+        "ShowPage" => {
+            let (page,) = parameters.get::<(String,)>().ok_or_else(invalid_args)?;
+            if page.trim().is_empty() {
+                return Err(invalid_args());
+            }
+            open(DeepLink {
+                page: Some(page.trim().to_owned()),
+                ..Default::default()
+            });
+            Ok(None)
+        }
+        "ShowProcess" => {
+            let (pid,) = parameters.get::<(u32,)>().ok_or_else(invalid_args)?;
+            open(DeepLink {
+                pid: Some(pid),
+                ..Default::default()
+            });
+            Ok(None)
+        }
+        "FindProcess" => {
+            let (name,) = parameters.get::<(String,)>().ok_or_else(invalid_args)?;
+            if name.trim().is_empty() {
+                return Err(invalid_args());
+            }
+            open(DeepLink {
+                process: Some(name.trim().to_owned()),
+                ..Default::default()
+            });
+            Ok(None)
+        }
+        "GetSummary" => Ok(Some((summary.borrow().clone(),).to_variant())),
+        "GetRefreshInterval" => {
+            let steps = settings!().uint64("app-update-interval-u64");
+            let interval_ms = (steps as f64 * INTERVAL_STEP * 1000.).round() as u32;
+            Ok(Some((interval_ms,).to_variant()))
+        }
+        "SetRefreshInterval" => {
+            let (interval_ms,) = parameters.get::<(u32,)>().ok_or_else(invalid_args)?;
+            let steps = (interval_ms as f64 / 1000. / INTERVAL_STEP).round() as u64;
+            if !(MIN_INTERVAL_STEPS..=MAX_INTERVAL_STEPS).contains(&steps) {
+                return Err(glib::Error::new(
+                    gio::DBusError::InvalidArgs,
+                    &format!(
+                        "The refresh interval has to be between {} and {} ms",
+                        (MIN_INTERVAL_STEPS as f64 * INTERVAL_STEP * 1000.) as u32,
+                        (MAX_INTERVAL_STEPS as f64 * INTERVAL_STEP * 1000.) as u32
+                    ),
+                ));
+            }
+            settings!()
+                .set_uint64("app-update-interval-u64", steps)
+                .map_err(|e| glib::Error::new(gio::DBusError::Failed, &e.to_string()))?;
+            Ok(None)
+        }
+        _ => Err(glib::Error::new(
+            gio::DBusError::UnknownMethod,
+            &format!("Unknown method {}", method),
+        )),
+    }
+}
+
+fn get_property(property: &str) -> glib::Variant {
+    match property {
+        "DisabledCollectors" => {
+            let disabled = disabled_collectors(&settings!());
+            Collector::ALL
+                .iter()
+                .filter(|collector| disabled.contains(collector))
+                .map(|collector| collector.id().to_owned())
+                .collect::<Vec<_>>()
+                .to_variant()
+        }
+        // GIO only asks for the properties in the introspection data
+        _ => ().to_variant(),
+    }
+}
+
+fn set_property(property: &str, value: &glib::Variant) -> bool {
+    match property {
+        "DisabledCollectors" => {
+            let Some(ids) = value.get::<Vec<String>>() else {
+                return false;
+            };
+            let Some(disabled) = ids
+                .iter()
+                .map(|id| Collector::from_id(id))
+                .collect::<Option<_>>()
+            else {
+                return false;
+            };
+
+            match set_disabled_collectors(&settings!(), &disabled) {
+                Ok(()) => true,
+                Err(e) => {
+                    glib::g_warning!(
+                        "MissionCenter::DBus",
+                        "Failed to set disabled-collectors setting: {}",
+                        e
+                    );
+                    false
+                }
+            }
+        }
+        _ => false,
+    }
+}
+
+fn open(deep_link: DeepLink) {
+    let app = app!();
+    app.activate();
+    app.open_deep_link(deep_link);
+}
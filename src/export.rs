@@ -0,0 +1,416 @@
+/* export.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+//! Common format for everything Mission Center writes out for other programs to consume.
+//!
+//! Exports are tables with a fixed set of fields. Field ids are stable, lowercase, dot separated
+//! names (e.g. `cpu.usage`) that are never translated; numbers are always written in the "C"
+//! locale, with `.` as the decimal separator and no grouping. Every field carries the unit its
+//! values are expressed in, and every export carries the schema version it was written with.
+//!
+//! CSV exports start with two comment lines holding the metadata, followed by a header row:
+//!
+//! ```text
+//! # missioncenter-export kind=graph schema-version=1
+//! # units: timestamp=unix_ms,cpu.usage=percent
+//! timestamp,cpu.usage
+//! 1735689600000,12.5
+//! ```
+//!
+//! JSON exports are a single object:
+//!
+//! ```text
+//! {"format":"missioncenter-export","schema_version":1,"kind":"graph",
+//!  "fields":[{"id":"timestamp","unit":"unix_ms"},{"id":"cpu.usage","unit":"percent"}],
+//!  "rows":[[1735689600000,12.5]]}
+//! ```
+//!
+//! Renaming or removing a field, or changing its unit, requires bumping `SCHEMA_VERSION`.
+//! Adding fields does not.
+
+use std::fmt::Write;
+
+pub const FORMAT_NAME: &str = "missioncenter-export";
+pub const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Unit {
+    None,
+    Percent,
+    Bytes,
+    BytesPerSecond,
+    Hertz,
+    Watts,
+    Volts,
+    Celsius,
+    Rpm,
+    Seconds,
+    UnixTimestampMs,
+}
+
+impl Unit {
+    /// Stable identifier of the unit as written in exports
+    pub fn id(&self) -> &'static str {
+        match self {
+            Unit::None => "none",
+            Unit::Percent => "percent",
+            Unit::Bytes => "bytes",
+            Unit::BytesPerSecond => "bytes_per_second",
+            Unit::Hertz => "hertz",
+            Unit::Watts => "watts",
+            Unit::Volts => "volts",
+            Unit::Celsius => "celsius",
+            Unit::Rpm => "rpm",
+            Unit::Seconds => "seconds",
+            Unit::UnixTimestampMs => "unix_ms",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Field {
+    pub id: String,
+    pub unit: Unit,
+}
+
+impl Field {
+    pub fn new(id: impl Into<String>, unit: Unit) -> Self {
+        Self {
+            id: id.into(),
+            unit,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Number(f64),
+    Text(String),
+    Missing,
+}
+
+impl From<f32> for Value {
+    fn from(value: f32) -> Self {
+        Value::Number(value as f64)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(value: f64) -> Self {
+        Value::Number(value)
+    }
+}
+
+impl From<u64> for Value {
+    fn from(value: u64) -> Self {
+        Value::Number(value as f64)
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Value::Text(value)
+    }
+}
+
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(value: Option<T>) -> Self {
+        value.map(|v| v.into()).unwrap_or(Value::Missing)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Table {
+    /// What is being exported, e.g. `graph` or `processes`; also stable and never translated
+    pub kind: &'static str,
+    pub fields: Vec<Field>,
+    pub rows: Vec<Vec<Value>>,
+}
+
+impl Table {
+    pub fn new(kind: &'static str, fields: Vec<Field>) -> Self {
+        Self {
+            kind,
+            fields,
+            rows: vec![],
+        }
+    }
+
+    /// Append a row; missing trailing values are filled in, extra values are dropped
+    pub fn push_row(&mut self, mut row: Vec<Value>) {
+        row.resize(self.fields.len(), Value::Missing);
+        self.rows.push(row);
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut result = self.csv_header();
+        for row in &self.rows {
+            result.push_str(&csv_row(row));
+        }
+
+        result
+    }
+
+    /// The metadata comments and the header row, for writing rows out one at a time with
+    /// `csv_row`
+    pub fn csv_header(&self) -> String {
+        let mut result = String::new();
+
+        let _ = writeln!(
+            result,
+            "# {} kind={} schema-version={}",
+            FORMAT_NAME, self.kind, SCHEMA_VERSION
+        );
+        let _ = writeln!(
+            result,
+            "# units: {}",
+            self.fields
+                .iter()
+                .map(|f| format!("{}={}", f.id, f.unit.id()))
+                .collect::<Vec<_>>()
+                .join(",")
+        );
+        let _ = writeln!(
+            result,
+            "{}",
+            self.fields
+                .iter()
+                .map(|f| csv_escape(&f.id))
+                .collect::<Vec<_>>()
+                .join(",")
+        );
+
+        result
+    }
+
+    pub fn to_json(&self) -> String {
+        let mut result = String::new();
+
+        let _ = write!(
+            result,
+            "{{\"format\":{},\"schema_version\":{},\"kind\":{},\"fields\":[",
+            json_string(FORMAT_NAME),
+            SCHEMA_VERSION,
+            json_string(self.kind)
+        );
+        for (i, field) in self.fields.iter().enumerate() {
+            if i > 0 {
+                result.push(',');
+            }
+            let _ = write!(
+                result,
+                "{{\"id\":{},\"unit\":{}}}",
+                json_string(&field.id),
+                json_string(field.unit.id())
+            );
+        }
+        result.push_str("],\"rows\":[");
+        for (i, row) in self.rows.iter().enumerate() {
+            if i > 0 {
+                result.push(',');
+            }
+            result.push('[');
+            for (j, value) in row.iter().enumerate() {
+                if j > 0 {
+                    result.push(',');
+                }
+                result.push_str(&json_value(value));
+            }
+            result.push(']');
+        }
+        result.push_str("]}\n");
+
+        result
+    }
+}
+
+/// A single CSV line, newline included
+pub fn csv_row(row: &[Value]) -> String {
+    let mut result = row
+        .iter()
+        .map(|value| match value {
+            Value::Number(n) => format_number(*n).unwrap_or_default(),
+            Value::Text(text) => csv_escape(text),
+            Value::Missing => String::new(),
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+    result.push('\n');
+
+    result
+}
+
+// Shortest representation that round-trips, never in exponent notation and independent of
+// the current locale; `None` for values JSON can't represent
+fn format_number(value: f64) -> Option<String> {
+    if !value.is_finite() {
+        return None;
+    }
+
+    if value.fract() == 0. && value.abs() < 1e15 {
+        Some(format!("{}", value as i64))
+    } else {
+        Some(format!("{}", value))
+    }
+}
+
+fn csv_escape(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) || text.starts_with('#') {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_owned()
+    }
+}
+
+pub fn json_value(value: &Value) -> String {
+    match value {
+        Value::Number(n) => format_number(*n).unwrap_or_else(|| "null".to_owned()),
+        Value::Text(text) => json_string(text),
+        Value::Missing => "null".to_owned(),
+    }
+}
+
+pub fn json_string(text: &str) -> String {
+    let mut result = String::with_capacity(text.len() + 2);
+
+    result.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(result, "\\u{:04x}", c as u32);
+            }
+            c => result.push(c),
+        }
+    }
+    result.push('"');
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table() -> Table {
+        let mut table = Table::new(
+            "processes",
+            vec![
+                Field::new("timestamp", Unit::UnixTimestampMs),
+                Field::new("process.name", Unit::None),
+                Field::new("cpu.usage", Unit::Percent),
+                Field::new("memory.usage", Unit::Bytes),
+            ],
+        );
+        table.push_row(vec![
+            1735689600000_u64.into(),
+            "firefox".to_owned().into(),
+            12.5_f64.into(),
+            1048576_u64.into(),
+        ]);
+        table.push_row(vec![
+            1735689601000_u64.into(),
+            "say \"hi\", then #quit".to_owned().into(),
+            Value::Missing,
+        ]);
+
+        table
+    }
+
+    #[test]
+    fn to_csv() {
+        assert_eq!(
+            table().to_csv(),
+            "# missioncenter-export kind=processes schema-version=1\n\
+             # units: timestamp=unix_ms,process.name=none,cpu.usage=percent,memory.usage=bytes\n\
+             timestamp,process.name,cpu.usage,memory.usage\n\
+             1735689600000,firefox,12.5,1048576\n\
+             1735689601000,\"say \"\"hi\"\", then #quit\",,\n"
+        );
+    }
+
+    #[test]
+    fn to_json() {
+        assert_eq!(
+            table().to_json(),
+            "{\"format\":\"missioncenter-export\",\"schema_version\":1,\"kind\":\"processes\",\
+             \"fields\":[{\"id\":\"timestamp\",\"unit\":\"unix_ms\"},\
+             {\"id\":\"process.name\",\"unit\":\"none\"},\
+             {\"id\":\"cpu.usage\",\"unit\":\"percent\"},\
+             {\"id\":\"memory.usage\",\"unit\":\"bytes\"}],\
+             \"rows\":[[1735689600000,\"firefox\",12.5,1048576],\
+             [1735689601000,\"say \\\"hi\\\", then #quit\",null,null]]}\n"
+        );
+    }
+
+    #[test]
+    fn to_json_empty() {
+        assert_eq!(
+            Table::new("graph", vec![]).to_json(),
+            "{\"format\":\"missioncenter-export\",\"schema_version\":1,\"kind\":\"graph\",\
+             \"fields\":[],\"rows\":[]}\n"
+        );
+    }
+
+    #[test]
+    fn csv_escapes_leading_hash() {
+        assert_eq!(
+            csv_row(&["#comment".to_owned().into(), "a\nb".to_owned().into()]),
+            "\"#comment\",\"a\nb\"\n"
+        );
+    }
+
+    #[test]
+    fn format_number_is_plain() {
+        assert_eq!(format_number(0.).as_deref(), Some("0"));
+        assert_eq!(format_number(-0.).as_deref(), Some("0"));
+        assert_eq!(format_number(42.).as_deref(), Some("42"));
+        assert_eq!(format_number(-3.).as_deref(), Some("-3"));
+        assert_eq!(format_number(12.5).as_deref(), Some("12.5"));
+        assert_eq!(format_number(0.1).as_deref(), Some("0.1"));
+        assert_eq!(format_number(1e-7).as_deref(), Some("0.0000001"));
+        assert_eq!(format_number(1e15).as_deref(), Some("1000000000000000"));
+        assert_eq!(
+            format_number(1.5e20).as_deref(),
+            Some("150000000000000000000")
+        );
+        assert_eq!(format_number(f64::NAN), None);
+        assert_eq!(format_number(f64::INFINITY), None);
+        assert_eq!(json_value(&Value::Number(f64::NEG_INFINITY)), "null");
+        assert_eq!(csv_row(&[Value::Number(f64::NAN)]), "\n");
+    }
+
+    #[test]
+    fn json_string_escapes() {
+        assert_eq!(json_string(""), "\"\"");
+        assert_eq!(json_string("plain"), "\"plain\"");
+        assert_eq!(json_string("a\"b\\c"), "\"a\\\"b\\\\c\"");
+        assert_eq!(json_string("1\n2\r3\t4"), "\"1\\n2\\r3\\t4\"");
+        assert_eq!(
+            json_string("\u{0}\u{1b}\u{1f}"),
+            "\"\\u0000\\u001b\\u001f\""
+        );
+        assert_eq!(json_string("\u{7f}é°C✓"), "\"\u{7f}é°C✓\"");
+    }
+}
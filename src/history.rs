@@ -0,0 +1,406 @@
+/* history.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+//! Opt-in record of a handful of readings, kept on disk so they can be looked at long after they
+//! scrolled off the performance graphs.
+//!
+//! Readings are averaged over a minute and appended to one file per (UTC) day, written in the
+//! export format so other programs can read them as well. Days older than the retention period
+//! are deleted.
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+
+use gtk::glib::{self, g_warning};
+
+use crate::export::{csv_row, Field, Table, Unit, Value};
+use crate::magpie_client::Readings;
+use crate::settings;
+
+pub const SAMPLE_PERIOD_MS: i64 = 60 * 1000;
+const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Metric {
+    CpuUsage,
+    MemoryUsed,
+    MemoryTotal,
+    /// Of the busiest drive
+    DiskBusy,
+    DiskRead,
+    DiskWrite,
+    NetworkReceive,
+    NetworkSend,
+    /// Of the busiest GPU
+    GpuUsage,
+}
+
+pub const METRICS: [Metric; 9] = [
+    Metric::CpuUsage,
+    Metric::MemoryUsed,
+    Metric::MemoryTotal,
+    Metric::DiskBusy,
+    Metric::DiskRead,
+    Metric::DiskWrite,
+    Metric::NetworkReceive,
+    Metric::NetworkSend,
+    Metric::GpuUsage,
+];
+
+impl Metric {
+    pub fn field(&self) -> Field {
+        match self {
+            Metric::CpuUsage => Field::new("cpu.usage", Unit::Percent),
+            Metric::MemoryUsed => Field::new("memory.used", Unit::Bytes),
+            Metric::MemoryTotal => Field::new("memory.total", Unit::Bytes),
+            Metric::DiskBusy => Field::new("disk.busy", Unit::Percent),
+            Metric::DiskRead => Field::new("disk.read", Unit::BytesPerSecond),
+            Metric::DiskWrite => Field::new("disk.write", Unit::BytesPerSecond),
+            Metric::NetworkReceive => Field::new("network.receive", Unit::BytesPerSecond),
+            Metric::NetworkSend => Field::new("network.send", Unit::BytesPerSecond),
+            Metric::GpuUsage => Field::new("gpu.usage", Unit::Percent),
+        }
+    }
+
+    pub fn current_value(&self, readings: &Readings) -> f32 {
+        match self {
+            Metric::CpuUsage => readings.cpu.total_usage_percent,
+            Metric::MemoryUsed => readings
+                .mem_info
+                .mem_total
+                .saturating_sub(readings.mem_info.mem_available)
+                as f32,
+            Metric::MemoryTotal => readings.mem_info.mem_total as f32,
+            Metric::DiskBusy => readings
+                .disks_info
+                .iter()
+                .map(|disk| disk.busy_percent)
+                .fold(0., f32::max),
+            Metric::DiskRead => readings
+                .disks_info
+                .iter()
+                .map(|disk| disk.rx_speed_bytes_ps as f32)
+                .sum(),
+            Metric::DiskWrite => readings
+                .disks_info
+                .iter()
+                .map(|disk| disk.tx_speed_bytes_ps as f32)
+                .sum(),
+            Metric::NetworkReceive => readings
+                .network_connections
+                .iter()
+                .map(|connection| connection.rx_rate_bytes_ps)
+                .sum(),
+            Metric::NetworkSend => readings
+                .network_connections
+                .iter()
+                .map(|connection| connection.tx_rate_bytes_ps)
+                .sum(),
+            Metric::GpuUsage => readings
+                .gpus
+                .values()
+                .filter_map(|gpu| gpu.utilization_percent)
+                .fold(0., f32::max),
+        }
+    }
+}
+
+/// The averages of one sample period
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sample {
+    /// Start of the period
+    pub timestamp_ms: i64,
+    values: [Option<f32>; METRICS.len()],
+}
+
+impl Sample {
+    pub fn value(&self, metric: Metric) -> Option<f32> {
+        self.values[metric as usize]
+    }
+}
+
+/// Averages the readings it is handed and writes them out once per sample period
+pub struct HistoryRecorder {
+    period_start_ms: i64,
+    sums: [f64; METRICS.len()],
+    count: u32,
+}
+
+impl Drop for HistoryRecorder {
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
+
+impl HistoryRecorder {
+    pub fn new() -> Self {
+        prune();
+
+        Self {
+            period_start_ms: period_start(glib::real_time() / 1000),
+            sums: [0.; METRICS.len()],
+            count: 0,
+        }
+    }
+
+    pub fn record(&mut self, readings: &Readings) {
+        let period_start_ms = period_start(glib::real_time() / 1000);
+        if period_start_ms != self.period_start_ms {
+            self.flush();
+
+            if period_start_ms / DAY_MS != self.period_start_ms / DAY_MS {
+                prune();
+            }
+            self.period_start_ms = period_start_ms;
+        }
+
+        for (sum, metric) in self.sums.iter_mut().zip(METRICS.iter()) {
+            let value = metric.current_value(readings);
+            if value.is_finite() {
+                *sum += value as f64;
+            }
+        }
+        self.count += 1;
+    }
+
+    fn flush(&mut self) {
+        if self.count == 0 {
+            return;
+        }
+
+        let mut row = vec![Value::from(self.period_start_ms.max(0) as u64)];
+        row.extend(
+            self.sums
+                .iter()
+                .map(|sum| Value::from(*sum / self.count as f64)),
+        );
+
+        if let Err(e) = append_row(self.period_start_ms, &row) {
+            g_warning!(
+                "MissionCenter::History",
+                "Failed to write history sample: {}",
+                e
+            );
+        }
+
+        self.sums = [0.; METRICS.len()];
+        self.count = 0;
+    }
+}
+
+/// Every sample taken since `since_ms`, oldest first
+pub fn load(since_ms: i64) -> Vec<Sample> {
+    let Ok(read_dir) = std::fs::read_dir(history_dir()) else {
+        return vec![];
+    };
+
+    let first_day = since_ms.div_euclid(DAY_MS);
+    let mut samples = vec![];
+    for path in read_dir.filter_map(|entry| entry.ok()).map(|e| e.path()) {
+        if path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(day_of_file)
+            .map_or(true, |day| day < first_day)
+        {
+            continue;
+        }
+
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        samples.extend(
+            parse(&content)
+                .into_iter()
+                .filter(|sample| sample.timestamp_ms >= since_ms),
+        );
+    }
+    samples.sort_unstable_by_key(|sample| sample.timestamp_ms);
+
+    samples
+}
+
+/// Columns are looked up by field ID, so files written by other versions can still be read
+fn parse(content: &str) -> Vec<Sample> {
+    let mut lines = content.lines().filter(|line| !line.starts_with('#'));
+    let Some(header) = lines.next() else {
+        return vec![];
+    };
+
+    let columns = header
+        .split(',')
+        .map(|id| METRICS.iter().position(|metric| metric.field().id == id))
+        .collect::<Vec<_>>();
+    let Some(timestamp_column) = header.split(',').position(|id| id == "timestamp") else {
+        return vec![];
+    };
+
+    lines
+        .filter_map(|line| {
+            let cells = line.split(',').collect::<Vec<_>>();
+            let timestamp_ms = cells.get(timestamp_column)?.parse::<i64>().ok()?;
+
+            let mut values = [None; METRICS.len()];
+            for (cell, column) in cells.iter().zip(columns.iter()) {
+                if let Some(index) = column {
+                    values[*index] = cell.parse::<f32>().ok();
+                }
+            }
+
+            Some(Sample {
+                timestamp_ms,
+                values,
+            })
+        })
+        .collect()
+}
+
+fn append_row(timestamp_ms: i64, row: &[Value]) -> std::io::Result<()> {
+    let dir = history_dir();
+    std::fs::create_dir_all(&dir)?;
+
+    let path = dir.join(file_name(timestamp_ms.div_euclid(DAY_MS)));
+    let is_new = !path.exists();
+
+    let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+    if is_new {
+        file.write_all(header().as_bytes())?;
+    }
+    file.write_all(csv_row(row).as_bytes())
+}
+
+/// What every file starts with
+fn header() -> String {
+    let mut fields = vec![Field::new("timestamp", Unit::UnixTimestampMs)];
+    fields.extend(METRICS.iter().map(|metric| metric.field()));
+
+    Table::new("history", fields).csv_header()
+}
+
+/// Delete the days that are past the retention period
+fn prune() {
+    let retention_days = settings!().int("history-retention-days").max(1) as i64;
+    let today = (glib::real_time() / 1000).div_euclid(DAY_MS);
+
+    let Ok(read_dir) = std::fs::read_dir(history_dir()) else {
+        return;
+    };
+    for path in read_dir.filter_map(|entry| entry.ok()).map(|e| e.path()) {
+        let Some(day) = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(day_of_file)
+        else {
+            continue;
+        };
+
+        if today - day >= retention_days {
+            if let Err(e) = std::fs::remove_file(&path) {
+                g_warning!(
+                    "MissionCenter::History",
+                    "Failed to delete {}: {}",
+                    path.display(),
+                    e
+                );
+            }
+        }
+    }
+}
+
+fn history_dir() -> PathBuf {
+    glib::user_data_dir().join("missioncenter").join("history")
+}
+
+fn period_start(timestamp_ms: i64) -> i64 {
+    timestamp_ms - timestamp_ms.rem_euclid(SAMPLE_PERIOD_MS)
+}
+
+// Files are named after their day, e.g. `2025-01-31.csv`
+fn file_name(day: i64) -> String {
+    glib::DateTime::from_unix_utc(day * DAY_MS / 1000)
+        .and_then(|date| date.format("%F"))
+        .map(|date| format!("{}.csv", date))
+        .unwrap_or_else(|_| format!("{}.csv", day))
+}
+
+fn day_of_file(file_name: &str) -> Option<i64> {
+    let date = file_name.strip_suffix(".csv")?;
+    let date = glib::DateTime::from_iso8601(&format!("{}T00:00:00Z", date), None).ok()?;
+
+    Some(date.to_unix() * 1000 / DAY_MS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Other tools read these files, and older ones are read back by newer versions
+    const FILE: &str = "# missioncenter-export kind=history schema-version=1\n\
+                        # units: timestamp=unix_ms,cpu.usage=percent,memory.used=bytes,\
+                        memory.total=bytes,disk.busy=percent,disk.read=bytes_per_second,\
+                        disk.write=bytes_per_second,network.receive=bytes_per_second,\
+                        network.send=bytes_per_second,gpu.usage=percent\n\
+                        timestamp,cpu.usage,memory.used,memory.total,disk.busy,disk.read,\
+                        disk.write,network.receive,network.send,gpu.usage\n\
+                        1735689600000,12.5,1048576,4194304,3,0,512,100.5,20,\n";
+
+    #[test]
+    fn header_is_stable() {
+        let header = header();
+        assert!(FILE.starts_with(&header), "{}", header);
+        assert_eq!(header.lines().count(), 3);
+    }
+
+    #[test]
+    fn parse_reads_written_file() {
+        let samples = parse(FILE);
+        assert_eq!(samples.len(), 1);
+
+        let sample = &samples[0];
+        assert_eq!(sample.timestamp_ms, 1735689600000);
+        assert_eq!(sample.value(Metric::CpuUsage), Some(12.5));
+        assert_eq!(sample.value(Metric::MemoryUsed), Some(1048576.));
+        assert_eq!(sample.value(Metric::MemoryTotal), Some(4194304.));
+        assert_eq!(sample.value(Metric::DiskBusy), Some(3.));
+        assert_eq!(sample.value(Metric::DiskRead), Some(0.));
+        assert_eq!(sample.value(Metric::DiskWrite), Some(512.));
+        assert_eq!(sample.value(Metric::NetworkReceive), Some(100.5));
+        assert_eq!(sample.value(Metric::NetworkSend), Some(20.));
+        assert_eq!(sample.value(Metric::GpuUsage), None);
+    }
+
+    #[test]
+    fn parse_looks_up_columns_by_id() {
+        let samples = parse(
+            "# missioncenter-export kind=history schema-version=1\n\
+             # units: memory.used=bytes,timestamp=unix_ms,cpu.temperature=celsius,cpu.usage=percent\n\
+             memory.used,timestamp,cpu.temperature,cpu.usage\n\
+             2048,1735689660000,55,7\n",
+        );
+        assert_eq!(samples.len(), 1);
+
+        let sample = &samples[0];
+        assert_eq!(sample.timestamp_ms, 1735689660000);
+        assert_eq!(sample.value(Metric::CpuUsage), Some(7.));
+        assert_eq!(sample.value(Metric::MemoryUsed), Some(2048.));
+        assert_eq!(sample.value(Metric::MemoryTotal), None);
+    }
+}
@@ -0,0 +1,288 @@
+/* insights.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+//! Checks the readings for patterns that usually point at a problem, and explains them.
+//!
+//! Every rule looks at the latest readings only; a finding is reported once its condition held
+//! for a while, so short spikes don't come and go, and goes away as soon as it no longer holds.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::application::DeepLink;
+use crate::i18n::*;
+use crate::magpie_client::Readings;
+use crate::{settings, DataType};
+
+/// Percentage of time in which some tasks were stalled waiting for memory
+const SWAP_THRASHING_PRESSURE: f32 = 20.;
+const SWAP_THRASHING_DURATION: Duration = Duration::from_secs(10);
+
+/// Percentage of a core's time spent in interrupt handlers
+const IRQ_BOUND_CORE_PERCENT: f32 = 50.;
+const IRQ_BOUND_CORE_DURATION: Duration = Duration::from_secs(10);
+
+const BUSY_DISK_PERCENT: f32 = 95.;
+const BUSY_DISK_MAX_BYTES_PER_SECOND: u64 = 1024 * 1024;
+const BUSY_DISK_DURATION: Duration = Duration::from_secs(30);
+
+const IDLE_GPU_PERCENT: f32 = 10.;
+/// How close to its highest clock speed an idle GPU has to be
+const IDLE_GPU_CLOCK_RATIO: f32 = 0.95;
+const IDLE_GPU_DURATION: Duration = Duration::from_secs(60);
+
+/// Zombies of the same parent
+const ZOMBIE_COUNT: usize = 10;
+const ZOMBIE_DURATION: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Insight {
+    /// Tells the same finding apart across updates, e.g. `busy-disk:nvme0n1`
+    pub id: String,
+    pub title: String,
+    pub explanation: String,
+    /// Where to look into it
+    pub link: DeepLink,
+    pub link_label: String,
+}
+
+pub struct Insights {
+    /// When the condition of each finding started to hold, by id
+    since: HashMap<String, Instant>,
+    current: Vec<Insight>,
+}
+
+impl Insights {
+    pub fn new() -> Self {
+        Self {
+            since: HashMap::new(),
+            current: vec![],
+        }
+    }
+
+    pub fn current(&self) -> &[Insight] {
+        &self.current
+    }
+
+    /// Run every rule on `readings`; returns whether the findings changed
+    pub fn update(&mut self, readings: &Readings) -> bool {
+        let now = Instant::now();
+
+        let mut candidates = vec![];
+        swap_thrashing(readings, &mut candidates);
+        irq_bound_cores(readings, &mut candidates);
+        busy_disks(readings, &mut candidates);
+        idle_gpus_at_max_clocks(readings, &mut candidates);
+        zombies(readings, &mut candidates);
+
+        self.since
+            .retain(|id, _| candidates.iter().any(|(insight, _)| insight.id == *id));
+
+        let mut current = vec![];
+        for (insight, duration) in candidates {
+            let since = *self.since.entry(insight.id.clone()).or_insert(now);
+            if now.duration_since(since) >= duration {
+                current.push(insight);
+            }
+        }
+
+        if current == self.current {
+            return false;
+        }
+        self.current = current;
+
+        true
+    }
+}
+
+fn swap_thrashing(readings: &Readings, candidates: &mut Vec<(Insight, Duration)>) {
+    let mem_info = &readings.mem_info;
+    let swap_used = mem_info.swap_total.saturating_sub(mem_info.swap_free);
+    let pressure = readings
+        .mem_pressure
+        .map(|pressure| pressure.some.avg10)
+        .unwrap_or(0.);
+    if swap_used == 0 || pressure < SWAP_THRASHING_PRESSURE {
+        return;
+    }
+
+    candidates.push((
+        Insight {
+            id: "swap-thrashing".to_owned(),
+            title: i18n("The system is swapping heavily"),
+            explanation: i18n_f(
+                "Programs spend {}% of their time waiting for memory while {} of swap is in use. Memory is moved to and from swap faster than it gets used, which slows everything down; closing memory hungry apps or adding memory helps.",
+                &[
+                    &format!("{:.0}", pressure),
+                    &crate::to_human_readable_nice(
+                        swap_used as f32,
+                        &DataType::MemoryBytes,
+                        &settings!(),
+                    ),
+                ],
+            ),
+            link: page_link("memory"),
+            link_label: i18n("Show Memory"),
+        },
+        SWAP_THRASHING_DURATION,
+    ));
+}
+
+fn irq_bound_cores(readings: &Readings, candidates: &mut Vec<(Insight, Duration)>) {
+    for (core, percent) in readings.cpu_irq_percent.iter().enumerate() {
+        if *percent < IRQ_BOUND_CORE_PERCENT {
+            continue;
+        }
+
+        candidates.push((
+            Insight {
+                id: format!("irq-bound-core:{}", core),
+                title: i18n_f("CPU {} is busy handling interrupts", &[&core.to_string()]),
+                explanation: i18n_f(
+                    "It spends {}% of its time in interrupt handlers. A device, often a network card or a storage controller, sends all of its interrupts to this one CPU; irqbalance, or setting the interrupt affinity, spreads them over all of them.",
+                    &[&format!("{:.0}", percent)],
+                ),
+                link: page_link("cpu"),
+                link_label: i18n("Show CPU"),
+            },
+            IRQ_BOUND_CORE_DURATION,
+        ));
+    }
+}
+
+fn busy_disks(readings: &Readings, candidates: &mut Vec<(Insight, Duration)>) {
+    for disk in &readings.disks_info {
+        let throughput = disk.rx_speed_bytes_ps as u64 + disk.tx_speed_bytes_ps as u64;
+        if disk.busy_percent < BUSY_DISK_PERCENT || throughput >= BUSY_DISK_MAX_BYTES_PER_SECOND {
+            continue;
+        }
+
+        candidates.push((
+            Insight {
+                id: format!("busy-disk:{}", disk.id),
+                title: i18n_f(
+                    "Drive {} is busy but barely transfers any data",
+                    &[&disk.id],
+                ),
+                explanation: i18n_f(
+                    "It has been busy all the time while reading and writing only {}. Many small, scattered reads and writes do this, and so does a drive that is failing and keeps retrying; its health is on the drive's page.",
+                    &[&crate::to_human_readable_nice(
+                        throughput as f32,
+                        &DataType::DriveBytesPerSecond,
+                        &settings!(),
+                    )],
+                ),
+                link: page_link(&format!("disk-{}", disk.id)),
+                link_label: i18n("Show Drive"),
+            },
+            BUSY_DISK_DURATION,
+        ));
+    }
+}
+
+fn idle_gpus_at_max_clocks(readings: &Readings, candidates: &mut Vec<(Insight, Duration)>) {
+    for gpu in readings.gpus.values() {
+        let (Some(utilization), Some(clock_speed), Some(max_clock_speed)) = (
+            gpu.utilization_percent,
+            gpu.clock_speed_mhz,
+            gpu.max_clock_speed_mhz,
+        ) else {
+            continue;
+        };
+        let max_clock_speed = max_clock_speed as f32;
+        if utilization >= IDLE_GPU_PERCENT
+            || max_clock_speed <= 0.
+            || (clock_speed as f32) < max_clock_speed * IDLE_GPU_CLOCK_RATIO
+        {
+            continue;
+        }
+
+        candidates.push((
+            Insight {
+                id: format!("idle-gpu-at-max-clocks:{}", gpu.id),
+                title: i18n_f(
+                    "{} stays at its highest clock speed",
+                    &[gpu.device_name.as_deref().unwrap_or(&gpu.id)],
+                ),
+                explanation: i18n_f(
+                    "It runs at {} while only {}% busy, which costs power and heat for nothing. A performance power profile, a driver setting that turns off power management, or a second monitor with a different refresh rate can keep it from clocking down.",
+                    &[
+                        &crate::to_human_readable_nice(
+                            clock_speed as f32 * 1_000_000.,
+                            &DataType::Hertz,
+                            &settings!(),
+                        ),
+                        &format!("{:.0}", utilization),
+                    ],
+                ),
+                link: page_link(&format!("gpu-{}", gpu.id)),
+                link_label: i18n("Show GPU"),
+            },
+            IDLE_GPU_DURATION,
+        ));
+    }
+}
+
+fn zombies(readings: &Readings, candidates: &mut Vec<(Insight, Duration)>) {
+    let mut by_parent = HashMap::<u32, usize>::new();
+    for zombie in &readings.zombies {
+        *by_parent.entry(zombie.parent).or_default() += 1;
+    }
+
+    for (parent, count) in by_parent {
+        if count < ZOMBIE_COUNT {
+            continue;
+        }
+
+        let name = readings
+            .running_processes
+            .get(&parent)
+            .map(|process| process.name.clone())
+            .unwrap_or_else(|| parent.to_string());
+        candidates.push((
+            Insight {
+                id: format!("zombies:{}", parent),
+                title: ni18n_f(
+                    "{} leaves {} finished child process behind",
+                    "{} leaves {} finished child processes behind",
+                    count as u32,
+                    &[&name, &count.to_string()],
+                ),
+                explanation: i18n_f(
+                    "These zombie processes use no memory or CPU time, but each keeps its process ID taken until {} collects its exit status. A number that keeps growing is a bug in it; the zombies go away once it exits.",
+                    &[&name],
+                ),
+                link: DeepLink {
+                    pid: Some(parent),
+                    ..Default::default()
+                },
+                link_label: i18n("Show Process"),
+            },
+            ZOMBIE_DURATION,
+        ));
+    }
+}
+
+fn page_link(page: &str) -> DeepLink {
+    DeepLink {
+        page: Some(page.to_owned()),
+        ..Default::default()
+    }
+}
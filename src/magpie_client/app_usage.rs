@@ -0,0 +1,205 @@
+/* magpie_client/app_usage.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::{HashMap, HashSet};
+use std::time::Instant;
+
+use gtk::glib;
+use magpie_types::apps::icon::Icon;
+
+use super::proc_stat::{self, ProcStat, CLOCK_TICKS_PER_SECOND};
+use super::{App, Pid, Process};
+
+/// What an app used in total, including the times it wasn't running
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct AppUsage {
+    pub id: String,
+    pub name: String,
+    /// Icon name or path, as of the last time the app was running
+    pub icon: Option<String>,
+    /// Time spent on the CPU, adding up all cores
+    pub cpu_seconds: f64,
+    pub disk_bytes: f64,
+    pub network_bytes: f64,
+    /// As of the last process list
+    pub running: bool,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct AppUsageHistory {
+    /// When the totals started adding up, in seconds since the Unix epoch
+    pub since: i64,
+    pub apps: Vec<AppUsage>,
+}
+
+/// Totals of a single process, as last read from `/proc`
+#[derive(Debug, Clone, Copy)]
+struct ProcessCounters {
+    /// Tells a process apart from an earlier one that had the same PID
+    start_time: u64,
+    cpu_seconds: f64,
+    /// `/proc/PID/io` can only be read for our own processes
+    disk_bytes: Option<f64>,
+}
+
+/// Adds up the usage of every app, which keeps going while the window is hidden. On this machine
+/// the counters the kernel keeps for each process are used, so nothing is lost between two process
+/// lists and what a process used before Mission Center started is included as well. Network usage,
+/// and everything on remote hosts, is added up from the rates in each process list instead
+pub struct AppUsageTracker {
+    since: i64,
+    apps: HashMap<String, AppUsage>,
+    counters: HashMap<Pid, ProcessCounters>,
+    previous_time: Instant,
+    scale_cpu_usage_to_core_count: bool,
+}
+
+impl AppUsageTracker {
+    pub fn new() -> Self {
+        Self {
+            since: glib::real_time() / 1_000_000,
+            apps: HashMap::new(),
+            counters: HashMap::new(),
+            previous_time: Instant::now(),
+            scale_cpu_usage_to_core_count: false,
+        }
+    }
+
+    /// Follows the gatherer, which hands out CPU usage either per core or of the whole CPU
+    pub fn set_scale_cpu_usage_to_core_count(&mut self, v: bool) {
+        self.scale_cpu_usage_to_core_count = v;
+    }
+
+    pub fn update(
+        &mut self,
+        apps: &HashMap<String, App>,
+        processes: &HashMap<Pid, Process>,
+        core_count: usize,
+        proc_stats: &HashMap<Pid, ProcStat>,
+        local: bool,
+    ) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.previous_time).as_secs_f64();
+        self.previous_time = now;
+
+        let cores = if self.scale_cpu_usage_to_core_count {
+            1.
+        } else {
+            core_count.max(1) as f64
+        };
+
+        for usage in self.apps.values_mut() {
+            usage.running = false;
+        }
+
+        let per_app = apps
+            .values()
+            .map(|app| (app, app_processes(app, processes)))
+            .collect::<Vec<_>>();
+        let io = if local {
+            proc_stat::read_io_all(
+                per_app
+                    .iter()
+                    .flat_map(|(_, processes)| processes.iter().map(|process| process.pid)),
+            )
+        } else {
+            HashMap::new()
+        };
+
+        let mut counters = HashMap::new();
+
+        for (app, app_processes) in per_app {
+            let usage = self.apps.entry(app.id.clone()).or_insert_with(|| AppUsage {
+                id: app.id.clone(),
+                ..Default::default()
+            });
+            usage.name = app.name.clone();
+            if let Some(icon) = app.icon.as_ref().and_then(|icon| match &icon.icon {
+                Some(Icon::Path(path)) => Some(path.clone()),
+                Some(Icon::Id(id)) => Some(id.clone()),
+                _ => None,
+            }) {
+                usage.icon = Some(icon);
+            }
+            usage.running = true;
+
+            for process in app_processes {
+                let stats = &process.usage_stats;
+                usage.network_bytes += stats.network_usage as f64 * elapsed;
+
+                // `proc_stats` is empty for remote hosts
+                let current = proc_stats.get(&process.pid).map(|stat| ProcessCounters {
+                    start_time: stat.start_ticks,
+                    cpu_seconds: (stat.user_ticks + stat.system_ticks) as f64
+                        / CLOCK_TICKS_PER_SECOND as f64,
+                    disk_bytes: io.get(&process.pid).map(|bytes| *bytes as f64),
+                });
+                let Some(current) = current else {
+                    usage.cpu_seconds += stats.cpu_usage as f64 / 100. * cores * elapsed;
+                    usage.disk_bytes += stats.disk_usage as f64 * elapsed;
+                    continue;
+                };
+
+                // A process seen for the first time counts with everything it used so far
+                let previous = self
+                    .counters
+                    .get(&process.pid)
+                    .filter(|previous| previous.start_time == current.start_time);
+                usage.cpu_seconds +=
+                    current.cpu_seconds - previous.map_or(0., |previous| previous.cpu_seconds);
+                match (current.disk_bytes, previous.and_then(|p| p.disk_bytes)) {
+                    (Some(disk_bytes), Some(previous)) => usage.disk_bytes += disk_bytes - previous,
+                    (Some(disk_bytes), None) => usage.disk_bytes += disk_bytes,
+                    (None, _) => usage.disk_bytes += stats.disk_usage as f64 * elapsed,
+                }
+
+                counters.insert(process.pid, current);
+            }
+        }
+
+        // Processes that exited are dropped, so a reused PID doesn't keep their totals
+        self.counters = counters;
+    }
+
+    pub fn history(&self) -> AppUsageHistory {
+        AppUsageHistory {
+            since: self.since,
+            apps: self.apps.values().cloned().collect(),
+        }
+    }
+}
+
+/// The processes of `app` and everything they started, each counted once
+fn app_processes<'a>(app: &App, processes: &'a HashMap<Pid, Process>) -> Vec<&'a Process> {
+    let mut seen = HashSet::new();
+    let mut pending = app.pids.clone();
+    let mut result = vec![];
+    while let Some(pid) = pending.pop() {
+        if !seen.insert(pid) {
+            continue;
+        }
+        if let Some(process) = processes.get(&pid) {
+            pending.extend(process.children.iter().copied());
+            result.push(process);
+        }
+    }
+
+    result
+}
@@ -0,0 +1,207 @@
+/* magpie_client/autostart.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+
+use gtk::glib;
+
+use super::cgroup::read_host_file;
+use crate::is_flatpak;
+
+const DESKTOP_ENTRY_GROUP: &str = "Desktop Entry";
+
+/// A program started when the user logs in, as described by an XDG autostart desktop file
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct AutostartEntry {
+    /// File name of the desktop file, e.g. `signal-desktop.desktop`
+    pub id: String,
+    pub name: String,
+    pub exec: String,
+    pub enabled: bool,
+    /// Where the entry was read from; a file in the user's autostart directory overrides the
+    /// system wide one with the same name
+    pub path: PathBuf,
+}
+
+impl AutostartEntry {
+    /// File name of the program that gets started, without any arguments
+    pub fn executable(&self) -> Option<&str> {
+        let program = self
+            .exec
+            .split_whitespace()
+            .find(|arg| !arg.contains('=') && *arg != "env")?;
+        Path::new(program).file_name()?.to_str()
+    }
+}
+
+/// Every autostart entry that applies to the current user, enabled or not
+pub fn entries() -> Vec<AutostartEntry> {
+    let mut entries: Vec<AutostartEntry> = vec![];
+
+    // The user's directory comes first so its files shadow the system wide ones
+    let user_dir = user_autostart_dir();
+    for dir in std::iter::once(user_dir).chain(system_autostart_dirs()) {
+        for file_name in list_host_dir(&dir) {
+            if !file_name.ends_with(".desktop") || entries.iter().any(|e| e.id == file_name) {
+                continue;
+            }
+
+            let path = dir.join(&file_name);
+            let Some(key_file) = load_key_file(&path) else {
+                continue;
+            };
+
+            let hidden = key_file
+                .boolean(DESKTOP_ENTRY_GROUP, "Hidden")
+                .unwrap_or(false);
+            let gnome_enabled = key_file
+                .boolean(DESKTOP_ENTRY_GROUP, "X-GNOME-Autostart-enabled")
+                .unwrap_or(true);
+
+            entries.push(AutostartEntry {
+                name: key_file
+                    .locale_string(DESKTOP_ENTRY_GROUP, "Name", None)
+                    .map(|name| name.to_string())
+                    .unwrap_or_else(|_| file_name.trim_end_matches(".desktop").to_owned()),
+                exec: key_file
+                    .string(DESKTOP_ENTRY_GROUP, "Exec")
+                    .map(|exec| exec.to_string())
+                    .unwrap_or_default(),
+                enabled: !hidden && gnome_enabled,
+                id: file_name,
+                path,
+            });
+        }
+    }
+
+    entries
+}
+
+/// Turn an entry on or off for the current user; system wide entries are never touched, a copy
+/// in the user's autostart directory takes precedence over them instead
+pub fn set_enabled(entry: &AutostartEntry, enabled: bool) -> Result<(), String> {
+    let key_file = load_key_file(&entry.path)
+        .ok_or_else(|| format!("Failed to read {}", entry.path.display()))?;
+
+    key_file.set_boolean(DESKTOP_ENTRY_GROUP, "Hidden", !enabled);
+    // Set by GNOME's own tools; would keep the entry disabled regardless of `Hidden`
+    let _ = key_file.remove_key(DESKTOP_ENTRY_GROUP, "X-GNOME-Autostart-enabled");
+
+    let user_dir = user_autostart_dir();
+    write_host_file(&user_dir, &user_dir.join(&entry.id), &key_file.to_data())
+}
+
+fn user_autostart_dir() -> PathBuf {
+    // The config directory inside the sandbox is the app's own, not the user's
+    if is_flatpak() {
+        glib::home_dir().join(".config").join("autostart")
+    } else {
+        glib::user_config_dir().join("autostart")
+    }
+}
+
+fn system_autostart_dirs() -> Vec<PathBuf> {
+    if is_flatpak() {
+        return vec![PathBuf::from("/etc/xdg/autostart")];
+    }
+
+    glib::system_config_dirs()
+        .into_iter()
+        .map(|dir| dir.join("autostart"))
+        .collect()
+}
+
+fn load_key_file(path: &Path) -> Option<glib::KeyFile> {
+    let content = read_host_file(path.to_str()?)?;
+
+    let key_file = glib::KeyFile::new();
+    key_file
+        .load_from_data(
+            &content,
+            glib::KeyFileFlags::KEEP_COMMENTS | glib::KeyFileFlags::KEEP_TRANSLATIONS,
+        )
+        .ok()?;
+
+    Some(key_file)
+}
+
+fn list_host_dir(dir: &Path) -> Vec<String> {
+    if !is_flatpak() {
+        let Ok(read_dir) = std::fs::read_dir(dir) else {
+            return vec![];
+        };
+        return read_dir
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .collect();
+    }
+
+    let Ok(output) = std::process::Command::new("/usr/bin/flatpak-spawn")
+        .arg("--host")
+        .arg("ls")
+        .arg("-1")
+        .arg("--")
+        .arg(dir)
+        .output()
+    else {
+        return vec![];
+    };
+    if !output.status.success() {
+        return vec![];
+    }
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| line.to_owned())
+        .collect()
+}
+
+fn write_host_file(dir: &Path, path: &Path, content: &str) -> Result<(), String> {
+    if !is_flatpak() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        return std::fs::write(path, content).map_err(|e| e.to_string());
+    }
+
+    let mut child = std::process::Command::new("/usr/bin/flatpak-spawn")
+        .arg("--host")
+        .arg("sh")
+        .arg("-c")
+        .arg(r#"mkdir -p "$1" && cat > "$2""#)
+        .arg("sh")
+        .arg(dir)
+        .arg(path)
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|e| e.to_string())?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(content.as_bytes())
+            .map_err(|e| e.to_string())?;
+    }
+
+    match child.wait() {
+        Ok(status) if status.success() => Ok(()),
+        Ok(status) => Err(format!("Writing {} exited with {}", path.display(), status)),
+        Err(e) => Err(e.to_string()),
+    }
+}
@@ -0,0 +1,201 @@
+/* magpie_client/background_jobs.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+use super::proc_stat::{ProcStat, CLOCK_TICKS_PER_SECOND};
+use super::{Pid, Process};
+
+/// Known tools are reported once they ran this long, so short invocations don't flicker by
+const KNOWN_JOB_MIN_ELAPSED_SECONDS: u64 = 5;
+/// Anything else has to keep the CPU or the disks busy for this long
+const BUSY_MIN_DURATION: Duration = Duration::from_secs(60);
+const BUSY_CPU_PERCENT: f32 = 50.;
+const BUSY_DISK_BYTES_PER_SECOND: f32 = 10. * 1024. * 1024.;
+
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub enum JobKind {
+    Copy,
+    Build,
+    Backup,
+    Archive,
+    Transcode,
+    PackageManager,
+    #[default]
+    Other,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct BackgroundJob {
+    pub pid: Pid,
+    pub name: String,
+    pub command_line: String,
+    pub kind: JobKind,
+    pub elapsed_seconds: u64,
+    /// For known tools, usage of the job and all of its children, so a build is one job and not
+    /// one per compiler
+    pub cpu_usage: f32,
+    pub disk_usage: f32,
+}
+
+fn known_job_kind(executable: &str) -> Option<JobKind> {
+    let kind = match executable {
+        "cp" | "mv" | "dd" | "rsync" | "rclone" | "scp" | "sftp" | "pv" => JobKind::Copy,
+        "make" | "gmake" | "ninja" | "samu" | "cargo" | "cmake" | "meson" | "bazel" | "scons"
+        | "gradle" | "mvn" | "go" | "dotnet" | "rpmbuild" | "dpkg-buildpackage" | "makepkg" => {
+            JobKind::Build
+        }
+        "borg" | "restic" | "duplicity" | "deja-dup" | "rdiff-backup" | "timeshift" | "snapper"
+        | "kopia" => JobKind::Backup,
+        "tar" | "zip" | "unzip" | "7z" | "7za" | "xz" | "zstd" | "gzip" | "pigz" | "bzip2"
+        | "lz4" => JobKind::Archive,
+        "ffmpeg" | "HandBrakeCLI" | "x264" | "x265" | "SvtAv1EncApp" => JobKind::Transcode,
+        "apt" | "apt-get" | "dpkg" | "dnf" | "dnf5" | "yum" | "rpm" | "zypper" | "pacman"
+        | "flatpak" | "snap" | "rpm-ostree" | "emerge" => JobKind::PackageManager,
+        _ => return None,
+    };
+
+    Some(kind)
+}
+
+fn executable_name(process: &Process) -> &str {
+    if let Some(name) = process.exe.rsplit('/').next().filter(|n| !n.is_empty()) {
+        return name;
+    }
+
+    process
+        .cmd
+        .first()
+        .and_then(|cmd| cmd.split_ascii_whitespace().next())
+        .and_then(|cmd| cmd.rsplit('/').next())
+        .unwrap_or(&process.name)
+}
+
+/// Seconds a process has been running, from the start time in `/proc/PID/stat`
+fn elapsed_seconds(stat: &ProcStat, uptime_seconds: u64) -> u64 {
+    uptime_seconds.saturating_sub(stat.start_ticks / CLOCK_TICKS_PER_SECOND)
+}
+
+/// Remembers since when processes have been busy across refreshes
+pub struct BackgroundJobs {
+    busy_since: HashMap<Pid, Instant>,
+}
+
+impl BackgroundJobs {
+    pub fn new() -> Self {
+        Self {
+            busy_since: HashMap::new(),
+        }
+    }
+
+    pub fn update(
+        &mut self,
+        processes: &HashMap<Pid, Process>,
+        proc_stats: &HashMap<Pid, ProcStat>,
+        uptime_seconds: u64,
+    ) -> Vec<BackgroundJob> {
+        let now = Instant::now();
+
+        let mut candidates = HashMap::new();
+        for process in processes.values() {
+            let (kind, usage_stats) = match known_job_kind(executable_name(process)) {
+                Some(kind) => {
+                    self.busy_since.remove(&process.pid);
+                    (kind, process.merged_usage_stats(processes))
+                }
+                None => {
+                    // Only the process itself counts here, merged usage would make every
+                    // parent of a busy process, up to `init`, look busy as well
+                    let usage_stats = process.usage_stats.clone();
+                    let busy = usage_stats.cpu_usage >= BUSY_CPU_PERCENT
+                        || usage_stats.disk_usage >= BUSY_DISK_BYTES_PER_SECOND;
+                    if !busy {
+                        self.busy_since.remove(&process.pid);
+                        continue;
+                    }
+
+                    let busy_since = *self.busy_since.entry(process.pid).or_insert(now);
+                    if now.duration_since(busy_since) < BUSY_MIN_DURATION {
+                        continue;
+                    }
+
+                    (JobKind::Other, usage_stats)
+                }
+            };
+
+            // Not known for remote hosts
+            let Some(elapsed_seconds) = proc_stats
+                .get(&process.pid)
+                .map(|stat| elapsed_seconds(stat, uptime_seconds))
+            else {
+                continue;
+            };
+            if kind != JobKind::Other && elapsed_seconds < KNOWN_JOB_MIN_ELAPSED_SECONDS {
+                continue;
+            }
+
+            candidates.insert(
+                process.pid,
+                BackgroundJob {
+                    pid: process.pid,
+                    name: executable_name(process).to_owned(),
+                    command_line: process.cmd.join(" "),
+                    kind,
+                    elapsed_seconds,
+                    cpu_usage: usage_stats.cpu_usage,
+                    disk_usage: usage_stats.disk_usage,
+                },
+            );
+        }
+
+        self.busy_since.retain(|pid, _| processes.contains_key(pid));
+
+        // Usage of known tools is merged into the outermost one, so drop jobs that run under
+        // them, like the compilers `make` spawns
+        let mut nested = HashSet::new();
+        for (pid, job) in &candidates {
+            if job.kind == JobKind::Other {
+                continue;
+            }
+
+            let mut stack = processes
+                .get(pid)
+                .map(|p| p.children.clone())
+                .unwrap_or_default();
+            while let Some(child) = stack.pop() {
+                if !nested.insert(child) {
+                    continue;
+                }
+                if let Some(process) = processes.get(&child) {
+                    stack.extend_from_slice(&process.children);
+                }
+            }
+        }
+
+        let mut result = candidates
+            .into_values()
+            .filter(|job| !nested.contains(&job.pid))
+            .collect::<Vec<_>>();
+        result.sort_by(|j1, j2| j2.elapsed_seconds.cmp(&j1.elapsed_seconds));
+
+        result
+    }
+}
@@ -0,0 +1,165 @@
+/* magpie_client/bluetooth.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use gtk::{
+    gio,
+    glib::{self, g_debug},
+};
+
+const BLUEZ_CALL_TIMEOUT_MS: i32 = 250;
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct BluetoothDevice {
+    pub name: String,
+    pub address: String,
+    /// Freedesktop icon name as reported by BlueZ, e.g. `audio-headset` or `input-mouse`
+    pub icon: Option<String>,
+    pub battery_percent: Option<u8>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct BluetoothAdapter {
+    pub name: String,
+    pub address: String,
+    pub powered: bool,
+    /// Devices currently connected through this adapter
+    pub devices: Vec<BluetoothDevice>,
+}
+
+/// List the Bluetooth adapters known to BlueZ together with their connected devices; empty if
+/// BlueZ is not running
+pub fn read() -> Vec<BluetoothAdapter> {
+    let connection = match gio::bus_get_sync(gio::BusType::System, gio::Cancellable::NONE) {
+        Ok(connection) => connection,
+        Err(e) => {
+            g_debug!(
+                "MissionCenter::SysInfo",
+                "Failed to connect to the system bus: {}",
+                e
+            );
+            return vec![];
+        }
+    };
+
+    let objects = match connection.call_sync(
+        Some("org.bluez"),
+        "/",
+        "org.freedesktop.DBus.ObjectManager",
+        "GetManagedObjects",
+        None,
+        Some(glib::VariantTy::new("(a{oa{sa{sv}}})").unwrap()),
+        gio::DBusCallFlags::NO_AUTO_START,
+        BLUEZ_CALL_TIMEOUT_MS,
+        gio::Cancellable::NONE,
+    ) {
+        Ok(objects) => objects.child_value(0),
+        Err(e) => {
+            g_debug!(
+                "MissionCenter::SysInfo",
+                "Failed to list Bluetooth devices: {}",
+                e
+            );
+            return vec![];
+        }
+    };
+
+    let mut adapters = vec![];
+    let mut devices = vec![];
+    for entry in objects.iter() {
+        let path = entry.child_value(0);
+        let Some(path) = path.str() else {
+            continue;
+        };
+        let interfaces = entry.child_value(1);
+
+        if let Some(props) = interfaces.lookup_value("org.bluez.Adapter1", None) {
+            adapters.push((
+                path.to_owned(),
+                BluetoothAdapter {
+                    name: string_prop(&props, "Alias").unwrap_or_default(),
+                    address: string_prop(&props, "Address").unwrap_or_default(),
+                    powered: bool_prop(&props, "Powered"),
+                    devices: vec![],
+                },
+            ));
+            continue;
+        }
+
+        let Some(props) = interfaces.lookup_value("org.bluez.Device1", None) else {
+            continue;
+        };
+        if !bool_prop(&props, "Connected") {
+            continue;
+        }
+
+        let Some(adapter_path) = props
+            .lookup_value("Adapter", Some(glib::VariantTy::OBJECT_PATH))
+            .and_then(|v| v.str().map(|s| s.to_owned()))
+        else {
+            continue;
+        };
+
+        let battery_percent = interfaces
+            .lookup_value("org.bluez.Battery1", None)
+            .and_then(|battery| battery.lookup_value("Percentage", Some(glib::VariantTy::BYTE)))
+            .and_then(|v| v.get::<u8>());
+
+        let address = string_prop(&props, "Address").unwrap_or_default();
+        devices.push((
+            adapter_path,
+            BluetoothDevice {
+                name: string_prop(&props, "Alias").unwrap_or_else(|| address.clone()),
+                address,
+                icon: string_prop(&props, "Icon"),
+                battery_percent,
+            },
+        ));
+    }
+
+    for (adapter_path, device) in devices {
+        if let Some((_, adapter)) = adapters.iter_mut().find(|(path, _)| path == &adapter_path) {
+            adapter.devices.push(device);
+        }
+    }
+
+    let mut result = adapters
+        .into_iter()
+        .map(|(_, mut adapter)| {
+            adapter.devices.sort_by(|d1, d2| d1.name.cmp(&d2.name));
+            adapter
+        })
+        .collect::<Vec<_>>();
+    result.sort_by(|a1, a2| a1.address.cmp(&a2.address));
+
+    result
+}
+
+fn string_prop(props: &glib::Variant, name: &str) -> Option<String> {
+    props
+        .lookup_value(name, Some(glib::VariantTy::STRING))
+        .and_then(|v| v.get::<String>())
+}
+
+fn bool_prop(props: &glib::Variant, name: &str) -> bool {
+    props
+        .lookup_value(name, Some(glib::VariantTy::BOOLEAN))
+        .and_then(|v| v.get::<bool>())
+        .unwrap_or(false)
+}
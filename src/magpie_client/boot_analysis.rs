@@ -0,0 +1,182 @@
+/* magpie_client/boot_analysis.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use crate::is_flatpak;
+
+/// Time spent in each stage of the boot, in seconds; stages the system doesn't report, like
+/// firmware and loader on BIOS machines, are `None`
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct BootStages {
+    pub firmware: Option<f32>,
+    pub loader: Option<f32>,
+    pub kernel: Option<f32>,
+    pub initrd: Option<f32>,
+    pub userspace: Option<f32>,
+    pub total: f32,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct UnitBootTime {
+    pub unit: String,
+    /// Seconds the unit took to start
+    pub duration: f32,
+    /// Seconds after the start of userspace the unit became active; only known for units on
+    /// the critical chain
+    pub activated_at: Option<f32>,
+    pub on_critical_chain: bool,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct BootAnalysis {
+    pub stages: BootStages,
+    /// Units sorted by how long they took to start, slowest first
+    pub units: Vec<UnitBootTime>,
+}
+
+pub fn read() -> Result<BootAnalysis, String> {
+    let time = systemd_analyze(&["time"])?;
+    let blame = systemd_analyze(&["blame"])?;
+    // Not fatal, the chain is only used to highlight units
+    let critical_chain = systemd_analyze(&["critical-chain"]).unwrap_or_default();
+
+    let mut units = blame
+        .lines()
+        .filter_map(|line| {
+            let (duration, unit) = line.trim().rsplit_once(char::is_whitespace)?;
+            Some(UnitBootTime {
+                unit: unit.to_owned(),
+                duration: parse_timespan(duration)?,
+                activated_at: None,
+                on_critical_chain: false,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for (unit, activated_at) in parse_critical_chain(&critical_chain) {
+        if let Some(entry) = units.iter_mut().find(|u| u.unit == unit) {
+            entry.activated_at = Some(activated_at);
+            entry.on_critical_chain = true;
+        }
+    }
+
+    units.sort_by(|u1, u2| u2.duration.total_cmp(&u1.duration));
+
+    Ok(BootAnalysis {
+        stages: parse_stages(&time),
+        units,
+    })
+}
+
+fn systemd_analyze(args: &[&str]) -> Result<String, String> {
+    let mut cmd = if is_flatpak() {
+        let mut cmd = std::process::Command::new("/usr/bin/flatpak-spawn");
+        cmd.arg("--host").arg("systemd-analyze");
+        cmd
+    } else {
+        std::process::Command::new("systemd-analyze")
+    };
+    cmd.arg("--no-pager").args(args);
+
+    let output = cmd.output().map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        // Usually "Bootup is not yet finished" while units are still starting
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_owned());
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Parse `Startup finished in 7.1s (firmware) + 3s (loader) + ... = 25.6s`
+fn parse_stages(time: &str) -> BootStages {
+    let mut stages = BootStages::default();
+
+    let Some(line) = time.lines().find(|l| l.starts_with("Startup finished in ")) else {
+        return stages;
+    };
+    let line = &line["Startup finished in ".len()..];
+
+    let (parts, total) = match line.rsplit_once(" = ") {
+        Some((parts, total)) => (parts, parse_timespan(total.trim()).unwrap_or(0.)),
+        None => (line, 0.),
+    };
+    stages.total = total;
+
+    for part in parts.split(" + ") {
+        let Some((duration, stage)) = part.trim().rsplit_once(" (") else {
+            continue;
+        };
+        let duration = parse_timespan(duration);
+        match stage.trim_end_matches(')') {
+            "firmware" => stages.firmware = duration,
+            "loader" => stages.loader = duration,
+            "kernel" => stages.kernel = duration,
+            "initrd" => stages.initrd = duration,
+            "userspace" => stages.userspace = duration,
+            _ => {}
+        }
+    }
+
+    stages
+}
+
+/// Return the units on the critical chain with the time they became active; lines look like
+/// `  └─NetworkManager.service @1.520s +412ms`
+fn parse_critical_chain(critical_chain: &str) -> Vec<(String, f32)> {
+    critical_chain
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim_start_matches(|c: char| {
+                c.is_whitespace() || c == '│' || c == '├' || c == '└' || c == '─'
+            });
+            let mut fields = line.split_ascii_whitespace();
+            let unit = fields.next()?;
+            let activated_at = fields.next()?.strip_prefix('@')?;
+
+            Some((unit.to_owned(), parse_timespan(activated_at)?))
+        })
+        .collect()
+}
+
+/// Parse a systemd time span like `1min 2.345s` or `345ms` into seconds
+fn parse_timespan(timespan: &str) -> Option<f32> {
+    let mut seconds = 0.;
+    let mut parsed_any = false;
+
+    for component in timespan.split_ascii_whitespace() {
+        let unit_start = component
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(component.len());
+        let value = component[..unit_start].parse::<f32>().ok()?;
+        let multiplier = match &component[unit_start..] {
+            "us" | "µs" => 0.000_001,
+            "ms" => 0.001,
+            "s" | "" => 1.,
+            "min" => 60.,
+            "h" => 3600.,
+            "d" => 86400.,
+            _ => return None,
+        };
+
+        seconds += value * multiplier;
+        parsed_any = true;
+    }
+
+    parsed_any.then_some(seconds)
+}
@@ -0,0 +1,175 @@
+/* magpie_client/capabilities.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use gtk::{
+    gio,
+    glib::{self, g_debug},
+    prelude::*,
+};
+
+const DBUS_CALL_TIMEOUT_MS: i32 = 250;
+
+const HWMON_DIR: &str = "/sys/class/hwmon";
+/// Only there while the proprietary NVIDIA kernel module is loaded
+const NVIDIA_DRIVER_VERSION: &str = "/proc/driver/nvidia/version";
+const NVML_LIBRARY: &str = "libnvidia-ml.so.1";
+
+/// What some of the readings are gathered from, and which may not be there on every system
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum DataSource {
+    /// Fan speeds and the sensors they are controlled by
+    Hwmon,
+    /// SMART data, and ejecting drives
+    UDisks2,
+    /// Usage of NVIDIA GPUs
+    Nvml,
+    /// Services, their logs and the boot analysis
+    Systemd,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Availability {
+    Available,
+    /// Nothing on this system would be read from it
+    NotNeeded,
+    Missing,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Capability {
+    pub source: DataSource,
+    pub availability: Availability,
+}
+
+/// Check each data source on this machine
+pub fn read() -> Vec<Capability> {
+    let connection = gio::bus_get_sync(gio::BusType::System, gio::Cancellable::NONE)
+        .inspect_err(|e| {
+            g_debug!(
+                "MissionCenter::SysInfo",
+                "Failed to connect to the system bus: {}",
+                e
+            );
+        })
+        .ok();
+
+    let service = |name: &str| match &connection {
+        Some(connection) if has_service(connection, name) => Availability::Available,
+        _ => Availability::Missing,
+    };
+
+    vec![
+        Capability {
+            source: DataSource::Hwmon,
+            availability: hwmon(),
+        },
+        Capability {
+            source: DataSource::UDisks2,
+            availability: service("org.freedesktop.UDisks2"),
+        },
+        Capability {
+            source: DataSource::Nvml,
+            availability: nvml(),
+        },
+        Capability {
+            source: DataSource::Systemd,
+            availability: service("org.freedesktop.systemd1"),
+        },
+    ]
+}
+
+/// Some sensor chip has to have a driver loaded for any fan to show up
+fn hwmon() -> Availability {
+    let Ok(entries) = std::fs::read_dir(HWMON_DIR) else {
+        return Availability::Missing;
+    };
+
+    let has_fans = entries.filter_map(Result::ok).any(|entry| {
+        std::fs::read_dir(entry.path()).is_ok_and(|mut files| {
+            files.any(|file| {
+                file.is_ok_and(|file| {
+                    let name = file.file_name();
+                    let name = name.to_string_lossy();
+                    name.starts_with("fan") && name.ends_with("_input")
+                })
+            })
+        })
+    });
+
+    if has_fans {
+        Availability::Available
+    } else {
+        Availability::Missing
+    }
+}
+
+/// NVML comes with the driver, but distributions often package it on its own; the gatherer
+/// looks it up like any other library
+fn nvml() -> Availability {
+    if !std::path::Path::new(NVIDIA_DRIVER_VERSION).exists() {
+        return Availability::NotNeeded;
+    }
+
+    let mut cmd = if crate::is_flatpak() {
+        cmd_flatpak_host!("ldconfig -p || /sbin/ldconfig -p")
+    } else {
+        let mut cmd = std::process::Command::new("sh");
+        cmd.arg("-c").arg("ldconfig -p || /sbin/ldconfig -p");
+        cmd
+    };
+
+    match cmd.output() {
+        Ok(output) if String::from_utf8_lossy(&output.stdout).contains(NVML_LIBRARY) => {
+            Availability::Available
+        }
+        _ => Availability::Missing,
+    }
+}
+
+/// Running, or started on demand by the bus
+fn has_service(connection: &gio::DBusConnection, name: &str) -> bool {
+    let call = |method: &str, parameters: Option<glib::Variant>, reply: &str| {
+        connection
+            .call_sync(
+                Some("org.freedesktop.DBus"),
+                "/org/freedesktop/DBus",
+                "org.freedesktop.DBus",
+                method,
+                parameters.as_ref(),
+                Some(glib::VariantTy::new(reply).unwrap()),
+                gio::DBusCallFlags::NONE,
+                DBUS_CALL_TIMEOUT_MS,
+                gio::Cancellable::NONE,
+            )
+            .ok()
+            .map(|reply| reply.child_value(0))
+    };
+
+    if call("NameHasOwner", Some((name,).to_variant()), "(b)")
+        .and_then(|has_owner| has_owner.get::<bool>())
+        .unwrap_or(false)
+    {
+        return true;
+    }
+
+    call("ListActivatableNames", None, "(as)")
+        .and_then(|names| names.get::<Vec<String>>())
+        .is_some_and(|names| names.iter().any(|n| n == name))
+}
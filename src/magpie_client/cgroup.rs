@@ -0,0 +1,154 @@
+/* magpie_client/cgroup.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use super::{privileged, Pid};
+use crate::is_flatpak;
+
+pub const MIN_WEIGHT: u32 = 1;
+pub const MAX_WEIGHT: u32 = 10000;
+pub const DEFAULT_WEIGHT: u32 = 100;
+
+/// The cgroup v2 weights of the systemd unit a process runs in
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct CgroupWeights {
+    /// Path of the cgroup, relative to the root of the cgroup hierarchy
+    pub path: String,
+    /// The systemd unit owning the cgroup, e.g. `app-gnome-firefox-1234.scope`
+    pub unit: Option<String>,
+    /// Whether the unit is managed by the user's systemd instance rather than the system one
+    pub user_unit: bool,
+    pub cpu_weight: Option<u32>,
+    pub io_weight: Option<u32>,
+}
+
+/// Look up the cgroup a process belongs to and its current CPU and I/O weights; `None` on
+/// cgroup v1 systems or if the process is gone
+pub fn weights(pid: Pid) -> Option<CgroupWeights> {
+    let cgroup = read_host_file(&format!("/proc/{}/cgroup", pid))?;
+    // cgroup v2 has a single hierarchy, listed as `0::/path`
+    let path = cgroup
+        .lines()
+        .find_map(|line| line.strip_prefix("0::"))?
+        .trim()
+        .to_owned();
+
+    let unit = path
+        .rsplit('/')
+        .find(|component| {
+            component.ends_with(".scope")
+                || component.ends_with(".service")
+                || component.ends_with(".slice")
+        })
+        .map(|unit| unit.to_owned());
+    let user_unit = path
+        .split('/')
+        .any(|component| component.starts_with("user@") && component.ends_with(".service"))
+        && !unit
+            .as_ref()
+            .map(|unit| unit.starts_with("user@"))
+            .unwrap_or(false);
+
+    let cgroup_dir = format!("/sys/fs/cgroup{}", path);
+    let cpu_weight = read_host_file(&format!("{}/cpu.weight", cgroup_dir))
+        .and_then(|content| content.trim().parse::<u32>().ok());
+    // `io.weight` holds the default weight on the first line, followed by per-device overrides
+    let io_weight = read_host_file(&format!("{}/io.weight", cgroup_dir)).and_then(|content| {
+        content
+            .lines()
+            .find_map(|line| line.strip_prefix("default "))
+            .and_then(|weight| weight.trim().parse::<u32>().ok())
+    });
+
+    Some(CgroupWeights {
+        path,
+        unit,
+        user_unit,
+        cpu_weight,
+        io_weight,
+    })
+}
+
+/// Change the weights through systemd so they persist for the lifetime of the unit and don't
+/// fight with the service manager; system units are changed by the privileged helper
+pub fn set_weights(
+    weights: &CgroupWeights,
+    cpu_weight: Option<u32>,
+    io_weight: Option<u32>,
+) -> Result<(), String> {
+    let Some(unit) = &weights.unit else {
+        return Err(format!("No systemd unit owns cgroup {}", weights.path));
+    };
+
+    let mut properties = vec![];
+    if let Some(cpu_weight) = cpu_weight {
+        properties.push(format!(
+            "CPUWeight={}",
+            cpu_weight.clamp(MIN_WEIGHT, MAX_WEIGHT)
+        ));
+    }
+    if let Some(io_weight) = io_weight {
+        properties.push(format!(
+            "IOWeight={}",
+            io_weight.clamp(MIN_WEIGHT, MAX_WEIGHT)
+        ));
+    }
+    if properties.is_empty() {
+        return Ok(());
+    }
+
+    if !weights.user_unit {
+        return privileged::set_unit_properties(unit, true, &properties);
+    }
+
+    let mut cmd = if is_flatpak() {
+        let mut cmd = std::process::Command::new("/usr/bin/flatpak-spawn");
+        cmd.arg("--host").arg("systemctl");
+        cmd
+    } else {
+        std::process::Command::new("systemctl")
+    };
+    cmd.args(["--user", "set-property", "--runtime"])
+        .arg(unit)
+        .args(&properties);
+
+    match cmd.status() {
+        Ok(status) if status.success() => Ok(()),
+        Ok(status) => Err(format!("systemctl exited with {}", status)),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+pub(super) fn read_host_file(path: &str) -> Option<String> {
+    if !is_flatpak() {
+        return std::fs::read_to_string(path).ok();
+    }
+
+    let output = std::process::Command::new("/usr/bin/flatpak-spawn")
+        .arg("--host")
+        .arg("cat")
+        .arg(path)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    String::from_utf8(output.stdout).ok()
+}
@@ -21,8 +21,9 @@
 use arrayvec::ArrayString;
 use gtk::glib::{g_critical, g_debug};
 use std::num::NonZeroU32;
-use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
-use std::time::Duration;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 use std::{cell::RefCell, collections::HashMap, sync::Arc};
 
 use magpie_types::apps::apps_response;
@@ -57,7 +58,7 @@ use magpie_types::services::services_response;
 use magpie_types::services::services_response::ServiceList;
 pub use magpie_types::services::Service;
 
-use crate::magpie_client::flatpak_app_path;
+use crate::magpie_client::{flatpak_app_path, Gatherer, HostTransport, RemoteHost};
 use crate::{flatpak_data_dir, is_flatpak, show_error_dialog_and_exit};
 
 mod nng {
@@ -77,6 +78,15 @@ type ServicesResponse = services_response::Response;
 
 const ENV_MC_DEBUG_MAGPIE_PROCESS_SOCK: &str = "MC_DEBUG_MAGPIE_PROCESS_SOCK";
 
+/// The wait between tries doubles from the first to the longest one, both when dialing the
+/// gatherer again and when restarting its process
+const RECONNECT_FIRST_DELAY: Duration = Duration::from_millis(100);
+const RECONNECT_MAX_DELAY: Duration = Duration::from_secs(5);
+/// How long the gatherer can stay unreachable before giving up
+const RECONNECT_TIMEOUT: Duration = Duration::from_secs(120);
+/// A gatherer process that ran for this long is restarted right away again if it exits
+const RESTART_RESET_AFTER: Duration = Duration::from_secs(30);
+
 macro_rules! parse_response {
     ($response: ident, $body_kind: path, $response_kind_ok: path, $response_kind_err: path, $do: expr) => {{
         let expected_type = stringify!($response_kind_ok);
@@ -153,7 +163,63 @@ fn random_string<const CAP: usize>() -> ArrayString<CAP> {
     result
 }
 
-fn magpie_command(socket_addr: &str) -> std::process::Command {
+/// Have the gatherer, and every thread it starts, only get the CPU and the disks when nothing else
+/// wants them
+fn lower_priority(command: &mut std::process::Command) {
+    use std::os::unix::process::CommandExt;
+
+    const PRIO_PROCESS: i32 = 0;
+    const SCHED_IDLE: i32 = 5;
+    const IOPRIO_WHO_PROCESS: i32 = 1;
+    const IOPRIO_CLASS_IDLE: i32 = 3;
+    const IOPRIO_CLASS_SHIFT: i32 = 13;
+
+    // There is no wrapper for `ioprio_set` in the C library
+    #[cfg(target_arch = "x86_64")]
+    const SYS_IOPRIO_SET: i64 = 251;
+    #[cfg(any(
+        target_arch = "aarch64",
+        target_arch = "riscv64",
+        target_arch = "loongarch64"
+    ))]
+    const SYS_IOPRIO_SET: i64 = 30;
+
+    #[repr(C)]
+    struct SchedParam {
+        sched_priority: i32,
+    }
+
+    extern "C" {
+        fn setpriority(which: i32, who: u32, prio: i32) -> i32;
+        fn sched_setscheduler(pid: i32, policy: i32, param: *const SchedParam) -> i32;
+        fn syscall(number: i64, ...) -> i64;
+    }
+
+    // Only calls that are safe between `fork` and `exec`; if any fails, the gatherer just
+    // competes like any other process
+    unsafe {
+        command.pre_exec(|| {
+            setpriority(PRIO_PROCESS, 0, 19);
+            sched_setscheduler(0, SCHED_IDLE, &SchedParam { sched_priority: 0 });
+            #[cfg(any(
+                target_arch = "x86_64",
+                target_arch = "aarch64",
+                target_arch = "riscv64",
+                target_arch = "loongarch64"
+            ))]
+            syscall(
+                SYS_IOPRIO_SET,
+                IOPRIO_WHO_PROCESS,
+                0,
+                IOPRIO_CLASS_IDLE << IOPRIO_CLASS_SHIFT,
+            );
+
+            Ok(())
+        });
+    }
+}
+
+fn magpie_command(socket_addr: &str, idle_priority: bool) -> std::process::Command {
     fn executable() -> String {
         use gtk::glib::g_debug;
 
@@ -215,11 +281,19 @@ fn magpie_command(socket_addr: &str) -> std::process::Command {
             .arg(format!(
                 "--env=RUST_LOG={}",
                 std::env::var("RUST_LOG").unwrap_or_default()
-            ))
-            .arg(executable());
+            ));
+        // Started on the host, out of reach of `lower_priority`; with no I/O priority of its
+        // own, the kernel derives one from the niceness
+        if idle_priority {
+            cmd.arg("nice").arg("-n").arg("19");
+        }
+        cmd.arg(executable());
         cmd
     } else {
         let mut cmd = std::process::Command::new(executable());
+        if idle_priority {
+            lower_priority(&mut cmd);
+        }
 
         if let Some(mut appdir) = std::env::var_os("APPDIR") {
             appdir.push("/runtime/default");
@@ -238,6 +312,166 @@ fn magpie_command(socket_addr: &str) -> std::process::Command {
     command
 }
 
+/// Starts the gatherer on `host` and forwards its socket to `socket_addr`, so the rest of the
+/// client talks to it as if it was running locally
+fn ssh_command(host: &RemoteHost, socket_addr: &str) -> std::process::Command {
+    let local_socket = socket_addr.trim_start_matches("ipc://");
+    let remote_socket = format!("/tmp/magpie_{}.ipc", random_string::<8>());
+
+    let mut command = if is_flatpak() {
+        let mut cmd = std::process::Command::new("/usr/bin/flatpak-spawn");
+        cmd.arg("--host").arg("ssh");
+        cmd
+    } else {
+        std::process::Command::new("ssh")
+    };
+
+    // Never prompt for a password, there is no terminal to type it in; a pseudo-terminal is still
+    // requested so the remote gatherer gets a hangup when the connection goes away
+    command
+        .arg("-tt")
+        .args(["-o", "BatchMode=yes"])
+        .args(["-o", "ExitOnForwardFailure=yes"])
+        .args(["-o", "StreamLocalBindUnlink=yes"])
+        .args(["-o", "ServerAliveInterval=10"]);
+    if let Some(port) = host.port {
+        command.arg("-p").arg(port.to_string());
+    }
+    if let Some(identity_file) = &host.identity_file {
+        command.arg("-i").arg(identity_file);
+    }
+    if let Some(user) = &host.user {
+        command.arg("-l").arg(user);
+    }
+
+    command
+        .env_remove("LD_PRELOAD")
+        .stdout(std::process::Stdio::inherit())
+        .stderr(std::process::Stdio::inherit())
+        .arg("-L")
+        .arg(format!("{}:{}", local_socket, remote_socket))
+        .arg("--")
+        .arg(&host.hostname)
+        .arg(format!(
+            "missioncenter-magpie --addr ipc://{0}; rm -f {0}",
+            remote_socket
+        ));
+
+    g_debug!(
+        "MissionCenter::Gatherer",
+        "Starting Magpie on {} ({})",
+        host.name,
+        host.destination()
+    );
+
+    command
+}
+
+/// Forwards connections to `socket_addr` to the gatherer listening on `host`, with `socat`
+/// taking care of TLS, which the socket library only supports with a configuration we can't set
+fn tls_proxy_command(host: &RemoteHost, socket_addr: &str) -> std::process::Command {
+    let local_socket = socket_addr.trim_start_matches("ipc://");
+
+    let mut command = if is_flatpak() {
+        let mut cmd = std::process::Command::new("/usr/bin/flatpak-spawn");
+        cmd.arg("--host").arg("socat");
+        cmd
+    } else {
+        std::process::Command::new("socat")
+    };
+
+    let mut remote = format!(
+        "OPENSSL:{}:{},verify=1",
+        host.hostname,
+        host.port.unwrap_or_default()
+    );
+    if let Some(ca_file) = &host.identity_file {
+        remote.push_str(",cafile=");
+        remote.push_str(ca_file);
+    }
+
+    command
+        .env_remove("LD_PRELOAD")
+        .stdout(std::process::Stdio::inherit())
+        .stderr(std::process::Stdio::inherit())
+        .arg(format!("UNIX-LISTEN:{},fork,unlink-early", local_socket))
+        .arg(remote);
+
+    g_debug!(
+        "MissionCenter::Gatherer",
+        "Connecting to Magpie on {} ({}) over TLS",
+        host.name,
+        host.destination()
+    );
+
+    command
+}
+
+/// How the gatherer is reached, and what has to run for it to be
+#[derive(Debug, Clone)]
+enum Transport {
+    /// Started on this machine
+    Local,
+    /// Started on a remote host over SSH, see `ssh_command`
+    Ssh(RemoteHost),
+    /// Already listening on a remote host, dialed directly
+    Tcp(RemoteHost),
+    /// Already listening on a remote host, see `tls_proxy_command`
+    Tls(RemoteHost),
+}
+
+impl Transport {
+    fn new(remote: Option<RemoteHost>) -> Self {
+        match remote {
+            None => Transport::Local,
+            Some(host) => match host.transport {
+                HostTransport::Ssh => Transport::Ssh(host),
+                HostTransport::Tcp => Transport::Tcp(host),
+                HostTransport::Tls => Transport::Tls(host),
+            },
+        }
+    }
+
+    /// Whether a process has to run for the gatherer to be reachable, see `command`
+    fn needs_process(&self) -> bool {
+        !matches!(self, Transport::Tcp(_))
+    }
+
+    /// Where the client dials the gatherer, NUL terminated
+    fn socket_addr(&self) -> Arc<str> {
+        match self {
+            Transport::Local if is_flatpak() => Arc::from(format!(
+                "ipc://{}/magpie.ipc\0",
+                flatpak_data_dir().display()
+            )),
+            Transport::Tcp(host) => Arc::from(format!(
+                "tcp://{}:{}\0",
+                host.hostname,
+                host.port.unwrap_or_default()
+            )),
+            // Forwarded by a process on the host, which can reach the app's data directory;
+            // several hosts can be connected to at once
+            _ if is_flatpak() => Arc::from(format!(
+                "ipc://{}/magpie_{}.ipc\0",
+                flatpak_data_dir().display(),
+                random_string::<8>()
+            )),
+            _ => Arc::from(format!("ipc:///tmp/magpie_{}.ipc\0", random_string::<8>())),
+        }
+    }
+
+    /// The process making the gatherer reachable at `socket_addr`, `None` when it already is;
+    /// only one started on this machine can be kept out of the way with `idle_priority`
+    fn command(&self, socket_addr: &str, idle_priority: bool) -> Option<std::process::Command> {
+        match self {
+            Transport::Local => Some(magpie_command(socket_addr, idle_priority)),
+            Transport::Ssh(host) => Some(ssh_command(host, socket_addr)),
+            Transport::Tcp(_) => None,
+            Transport::Tls(host) => Some(tls_proxy_command(host, socket_addr)),
+        }
+    }
+}
+
 fn connect_socket(socket: &mut nng_c::Socket, socket_addr: &str) -> bool {
     let _ = socket.close();
     socket.id = 0;
@@ -283,33 +517,16 @@ fn connect_socket(socket: &mut nng_c::Socket, socket_addr: &str) -> bool {
     }
 }
 
+enum RequestError {
+    Failed,
+    /// The socket has to be dialed again before the next request
+    ConnectionLost,
+}
+
 fn make_request(
     request: ipc::Request,
     socket: &mut nng_c::Socket,
-    socket_addr: &str,
-) -> Option<ipc::Response> {
-    fn try_reconnect(socket: &mut nng_c::Socket, socket_addr: &str) {
-        socket.close();
-
-        for i in 0..=5 {
-            if !connect_socket(socket, socket_addr) {
-                g_critical!(
-                    "MissionCenter::Gatherer",
-                    "Failed to reconnect to Magpie. Retrying in 100ms (try {}/5)",
-                    i + 1
-                );
-                std::thread::sleep(Duration::from_millis(100));
-                continue;
-            }
-
-            return;
-        }
-
-        show_error_dialog_and_exit(
-            "Lost connection to Magpie and failed to reconnect after 5 tries. Giving up.",
-        );
-    }
-
+) -> Result<ipc::Response, RequestError> {
     let mut req_buf = Vec::new();
 
     if let Err(e) = request.encode(&mut req_buf) {
@@ -319,7 +536,7 @@ fn make_request(
             req_buf,
             e
         );
-        return None;
+        return Err(RequestError::Failed);
     }
 
     if let Err(error_code) = socket
@@ -335,7 +552,7 @@ fn make_request(
                     "MissionCenter::Gatherer",
                     "Failed to send request: The socket is not open"
                 );
-                try_reconnect(socket, socket_addr);
+                return Err(RequestError::ConnectionLost);
             }
             nng::NNG_EINVAL => {
                 g_critical!(
@@ -380,7 +597,7 @@ fn make_request(
                 );
             }
         }
-        return None;
+        return Err(RequestError::Failed);
     }
 
     let message = match socket.recv_msg().map_err(|e| e.raw_code()) {
@@ -395,7 +612,7 @@ fn make_request(
                         "MissionCenter::Gatherer",
                         "Failed to read message: The socket is not open"
                     );
-                    try_reconnect(socket, socket_addr);
+                    return Err(RequestError::ConnectionLost);
                 }
                 nng::NNG_EINVAL => {
                     g_critical!(
@@ -441,7 +658,7 @@ fn make_request(
                     );
                 }
             };
-            return None;
+            return Err(RequestError::Failed);
         }
     };
 
@@ -450,7 +667,7 @@ fn make_request(
             "MissionCenter::Gatherer",
             "Failed to read response: Empty message"
         );
-        return None;
+        return Err(RequestError::Failed);
     }
 
     let response = match ipc::Response::decode(message.body()) {
@@ -461,22 +678,49 @@ fn make_request(
                 "Error while decoding response: {:?}",
                 e
             );
-            return None;
+            return Err(RequestError::Failed);
         }
     };
 
-    Some(response)
+    Ok(response)
+}
+
+/// Whether the connection to the gatherer is being restored, shared with the thread keeping its
+/// process running
+#[derive(Default)]
+struct ConnectionState {
+    reconnecting: AtomicBool,
+    on_change: Mutex<Option<Box<dyn Fn(bool) + Send>>>,
+}
+
+impl ConnectionState {
+    fn set_reconnecting(&self, reconnecting: bool) {
+        if self.reconnecting.swap(reconnecting, Ordering::AcqRel) == reconnecting {
+            return;
+        }
+
+        if let Ok(on_change) = self.on_change.lock() {
+            if let Some(on_change) = on_change.as_ref() {
+                on_change(reconnecting);
+            }
+        }
+    }
 }
 
 pub struct Client {
     socket: RefCell<nng_c::Socket>,
 
     socket_addr: Arc<str>,
+    transport: Transport,
     child_thread: RefCell<std::thread::JoinHandle<()>>,
     stop_requested: Arc<AtomicBool>,
+    restart_requested: Arc<AtomicBool>,
+    connection: Arc<ConnectionState>,
+    request_count: AtomicU64,
 
     core_count: AtomicU32,
     scale_cpu_usage_to_core_count: AtomicBool,
+    idle_priority: AtomicBool,
 }
 
 impl Drop for Client {
@@ -486,20 +730,14 @@ impl Drop for Client {
 }
 
 impl Client {
-    pub fn new() -> Self {
+    pub fn new(remote: Option<RemoteHost>) -> Self {
+        let transport = Transport::new(remote);
         let socket_addr =
             if let Ok(mut existing_sock) = std::env::var(ENV_MC_DEBUG_MAGPIE_PROCESS_SOCK) {
                 existing_sock.push('\0');
                 Arc::from(existing_sock)
             } else {
-                if is_flatpak() {
-                    Arc::from(format!(
-                        "ipc://{}/magpie.ipc\0",
-                        flatpak_data_dir().display()
-                    ))
-                } else {
-                    Arc::from(format!("ipc:///tmp/magpie_{}.ipc\0", random_string::<8>()))
-                }
+                transport.socket_addr()
             };
 
         let socket = nng_c::Socket::req0().expect("Could not create initial socket");
@@ -508,22 +746,60 @@ impl Client {
             socket: RefCell::new(socket),
 
             socket_addr,
+            transport,
             child_thread: RefCell::new(std::thread::spawn(|| {})),
             stop_requested: Arc::new(AtomicBool::new(false)),
+            restart_requested: Arc::new(AtomicBool::new(false)),
+            connection: Arc::new(ConnectionState::default()),
+            request_count: AtomicU64::new(0),
 
             core_count: AtomicU32::new(1),
             scale_cpu_usage_to_core_count: AtomicBool::new(false),
+            idle_priority: AtomicBool::new(false),
         }
     }
 
     pub fn start(&self) {
+        if self.try_start() {
+            return;
+        }
+
+        match &self.transport {
+            Transport::Ssh(host) => show_error_dialog_and_exit(&format!(
+                "Failed to connect to the Gatherer on {}; check that Mission Center is installed there and that SSH can log in without a password",
+                host.destination()
+            )),
+            Transport::Tcp(host) | Transport::Tls(host) => show_error_dialog_and_exit(&format!(
+                "Failed to connect to the Gatherer on {}; check that it is running there and listening on that port",
+                host.destination()
+            )),
+            Transport::Local => {
+                show_error_dialog_and_exit("Failed to connect to Gatherer socket")
+            }
+        }
+    }
+
+    /// Like `start`, but leaves it to the caller to deal with the Gatherer not being reachable
+    pub fn try_start(&self) -> bool {
         fn start_magpie_process_thread(
             socket_addr: Arc<str>,
+            transport: Transport,
             stop_requested: Arc<AtomicBool>,
+            restart_requested: Arc<AtomicBool>,
+            connection: Arc<ConnectionState>,
+            idle_priority: bool,
         ) -> std::thread::JoinHandle<()> {
             std::thread::spawn(move || {
-                fn spawn_child(socket_addr: &str) -> std::process::Child {
-                    match magpie_command(socket_addr.trim_end_matches('\0')).spawn() {
+                fn spawn_child(
+                    socket_addr: &str,
+                    transport: &Transport,
+                    idle_priority: bool,
+                ) -> std::process::Child {
+                    let socket_addr = socket_addr.trim_end_matches('\0');
+                    let Some(mut command) = transport.command(socket_addr, idle_priority) else {
+                        unreachable!("Only started for transports that need a process");
+                    };
+                    match command.spawn() {
                         Ok(child) => child,
                         Err(e) => {
                             g_critical!(
@@ -539,7 +815,9 @@ impl Client {
                     }
                 }
 
-                let mut child = spawn_child(&socket_addr);
+                let mut child = spawn_child(&socket_addr, &transport, idle_priority);
+                let mut started = Instant::now();
+                let mut restart_delay = RECONNECT_FIRST_DELAY;
 
                 while !stop_requested.load(Ordering::Relaxed) {
                     match child.try_wait() {
@@ -547,15 +825,43 @@ impl Client {
                             let _ = std::fs::remove_file(&socket_addr[6..]);
 
                             if !stop_requested.load(Ordering::Relaxed) {
+                                connection.set_reconnecting(true);
+
+                                // One that keeps crashing right after starting is restarted less
+                                // and less often
+                                if started.elapsed() >= RESTART_RESET_AFTER {
+                                    restart_delay = RECONNECT_FIRST_DELAY;
+                                }
                                 g_critical!(
                                     "MissionCenter::Gatherer",
-                                    "Magpie process exited unexpectedly: {}. Restarting...",
-                                    exit_status
+                                    "Magpie process exited unexpectedly: {}. Restarting in {}ms...",
+                                    exit_status,
+                                    restart_delay.as_millis()
                                 );
-                                std::mem::swap(&mut child, &mut spawn_child(&socket_addr));
+
+                                let restart_at = Instant::now() + restart_delay;
+                                while Instant::now() < restart_at {
+                                    if stop_requested.load(Ordering::Relaxed) {
+                                        return;
+                                    }
+                                    std::thread::sleep(Duration::from_millis(100));
+                                }
+                                restart_delay = (restart_delay * 2).min(RECONNECT_MAX_DELAY);
+
+                                std::mem::swap(
+                                    &mut child,
+                                    &mut spawn_child(&socket_addr, &transport, idle_priority),
+                                );
+                                started = Instant::now();
                             }
                         }
                         Ok(None) => {
+                            // Restarted the same way as when it crashes
+                            if restart_requested.swap(false, Ordering::Relaxed) {
+                                let _ = child.kill();
+                                continue;
+                            }
+
                             std::thread::sleep(Duration::from_millis(100));
                             continue;
                         }
@@ -577,9 +883,17 @@ impl Client {
             })
         }
 
-        if !std::env::var(ENV_MC_DEBUG_MAGPIE_PROCESS_SOCK).is_ok() {
-            *self.child_thread.borrow_mut() =
-                start_magpie_process_thread(self.socket_addr.clone(), self.stop_requested.clone());
+        if !std::env::var(ENV_MC_DEBUG_MAGPIE_PROCESS_SOCK).is_ok()
+            && self.transport.needs_process()
+        {
+            *self.child_thread.borrow_mut() = start_magpie_process_thread(
+                self.socket_addr.clone(),
+                self.transport.clone(),
+                self.stop_requested.clone(),
+                self.restart_requested.clone(),
+                self.connection.clone(),
+                self.idle_priority.load(Ordering::Relaxed),
+            );
         }
 
         const START_WAIT_TIME_MS: u64 = 300;
@@ -590,13 +904,13 @@ impl Client {
             std::thread::sleep(Duration::from_millis(START_WAIT_TIME_MS / 2));
 
             if connect_socket(&mut *self.socket.borrow_mut(), &self.socket_addr) {
-                return;
+                return true;
             }
 
             std::thread::sleep(Duration::from_millis(START_WAIT_TIME_MS / 2));
         }
 
-        show_error_dialog_and_exit("Failed to connect to Gatherer socket");
+        false
     }
 
     pub fn stop(&self) {
@@ -606,19 +920,84 @@ impl Client {
             std::thread::spawn(|| {}),
         );
         let _ = child_thread.join();
+        self.connection.set_reconnecting(false);
+    }
+
+    /// Whether the gatherer started on this machine gets only what the rest of the system leaves
+    /// of the CPU and the disks; applies from the next time it is started
+    pub fn set_idle_priority(&self, idle_priority: bool) {
+        self.idle_priority.store(idle_priority, Ordering::Relaxed);
+    }
+
+    /// Call `f` from whichever thread notices the gatherer going away, with `true`, and again
+    /// with `false` once it answers again
+    pub fn connect_reconnecting<F: Fn(bool) + Send + 'static>(&self, f: F) {
+        if let Ok(mut on_change) = self.connection.on_change.lock() {
+            *on_change = Some(Box::new(f));
+        }
+    }
+
+    fn request(&self, request: ipc::Request) -> Option<ipc::Response> {
+        let mut socket = self.socket.borrow_mut();
+
+        self.request_count.fetch_add(1, Ordering::Relaxed);
+        match make_request(request, &mut socket) {
+            Ok(response) => {
+                self.connection.set_reconnecting(false);
+                Some(response)
+            }
+            Err(RequestError::ConnectionLost) => {
+                self.reconnect(&mut socket);
+                None
+            }
+            Err(RequestError::Failed) => None,
+        }
+    }
+
+    /// Dial the gatherer until it answers, waiting longer after every failed try; its process is
+    /// restarted meanwhile if it crashed
+    fn reconnect(&self, socket: &mut nng_c::Socket) {
+        self.connection.set_reconnecting(true);
+
+        let start = Instant::now();
+        let mut delay = RECONNECT_FIRST_DELAY;
+        for attempt in 1.. {
+            if self.stop_requested.load(Ordering::Relaxed)
+                || connect_socket(socket, &self.socket_addr)
+            {
+                return;
+            }
+
+            if start.elapsed() >= RECONNECT_TIMEOUT {
+                break;
+            }
+
+            g_critical!(
+                "MissionCenter::Gatherer",
+                "Failed to reconnect to Magpie. Retrying in {}ms (try {})",
+                delay.as_millis(),
+                attempt
+            );
+            std::thread::sleep(delay);
+            delay = (delay * 2).min(RECONNECT_MAX_DELAY);
+        }
+
+        show_error_dialog_and_exit(&format!(
+            "Lost connection to Magpie and failed to reconnect for {} seconds. Giving up.",
+            RECONNECT_TIMEOUT.as_secs()
+        ));
     }
 }
 
-impl Client {
-    pub fn set_scale_cpu_usage_to_core_count(&self, v: bool) {
+impl Gatherer for Client {
+    fn set_scale_cpu_usage_to_core_count(&self, v: bool) {
         self.scale_cpu_usage_to_core_count
             .store(v, Ordering::Relaxed);
     }
 
-    pub fn cpu(&self) -> Cpu {
-        let mut socket = self.socket.borrow_mut();
-
-        let response = make_request(ipc::req_get_cpu(), &mut socket, self.socket_addr.as_ref())
+    fn cpu(&self) -> Cpu {
+        let response = self
+            .request(ipc::req_get_cpu())
             .and_then(|response| response.body);
 
         let cpu = parse_response!(
@@ -634,15 +1013,10 @@ impl Client {
         cpu
     }
 
-    pub fn memory(&self) -> Memory {
-        let mut socket = self.socket.borrow_mut();
-
-        let response = make_request(
-            ipc::req_get_memory(memory_request::Kind::Memory),
-            &mut socket,
-            self.socket_addr.as_ref(),
-        )
-        .and_then(|response| response.body);
+    fn memory(&self) -> Memory {
+        let response = self
+            .request(ipc::req_get_memory(memory_request::Kind::Memory))
+            .and_then(|response| response.body);
 
         parse_response!(
             response,
@@ -664,15 +1038,10 @@ impl Client {
         )
     }
 
-    pub fn memory_devices(&self) -> Vec<MemoryDevice> {
-        let mut socket = self.socket.borrow_mut();
-
-        let response = make_request(
-            ipc::req_get_memory(memory_request::Kind::MemoryDevices),
-            &mut socket,
-            self.socket_addr.as_ref(),
-        )
-        .and_then(|response| response.body);
+    fn memory_devices(&self) -> Vec<MemoryDevice> {
+        let response = self
+            .request(ipc::req_get_memory(memory_request::Kind::MemoryDevices))
+            .and_then(|response| response.body);
 
         parse_response!(
             response,
@@ -695,10 +1064,9 @@ impl Client {
         )
     }
 
-    pub fn disks_info(&self) -> Vec<Disk> {
-        let mut socket = self.socket.borrow_mut();
-
-        let response = make_request(ipc::req_get_disks(), &mut socket, self.socket_addr.as_ref())
+    fn disks_info(&self) -> Vec<Disk> {
+        let response = self
+            .request(ipc::req_get_disks())
             .and_then(|response| response.body);
 
         parse_response!(
@@ -710,15 +1078,10 @@ impl Client {
         )
     }
 
-    pub fn eject_disk(&self, disk_id: String) -> Result<(), ErrorEjectFailed> {
-        let mut socket = self.socket.borrow_mut();
-
-        let response = make_request(
-            ipc::req_eject_disk(disk_id),
-            &mut socket,
-            self.socket_addr.as_ref(),
-        )
-        .and_then(|response| response.body);
+    fn eject_disk(&self, disk_id: String) -> Result<(), ErrorEjectFailed> {
+        let response = self
+            .request(ipc::req_eject_disk(disk_id))
+            .and_then(|response| response.body);
 
         let result = parse_response_with_err!(
             response,
@@ -744,15 +1107,10 @@ impl Client {
         }
     }
 
-    pub fn smart_data(&self, disk_id: String) -> Option<SmartData> {
-        let mut socket = self.socket.borrow_mut();
-
-        let response = make_request(
-            ipc::req_get_smart_data(disk_id),
-            &mut socket,
-            self.socket_addr.as_ref(),
-        )
-        .and_then(|response| response.body);
+    fn smart_data(&self, disk_id: String) -> Option<SmartData> {
+        let response = self
+            .request(ipc::req_get_smart_data(disk_id))
+            .and_then(|response| response.body);
 
         parse_response!(
             response,
@@ -763,10 +1121,9 @@ impl Client {
         )
     }
 
-    pub fn fans_info(&self) -> Vec<Fan> {
-        let mut socket = self.socket.borrow_mut();
-
-        let response = make_request(ipc::req_get_fans(), &mut socket, self.socket_addr.as_ref())
+    fn fans_info(&self) -> Vec<Fan> {
+        let response = self
+            .request(ipc::req_get_fans())
             .and_then(|response| response.body);
 
         parse_response!(
@@ -778,15 +1135,10 @@ impl Client {
         )
     }
 
-    pub fn network_connections(&self) -> Vec<Connection> {
-        let mut socket = self.socket.borrow_mut();
-
-        let response = make_request(
-            ipc::req_get_connections(),
-            &mut socket,
-            self.socket_addr.as_ref(),
-        )
-        .and_then(|response| response.body);
+    fn network_connections(&self) -> Vec<Connection> {
+        let response = self
+            .request(ipc::req_get_connections())
+            .and_then(|response| response.body);
 
         parse_response!(
             response,
@@ -797,10 +1149,9 @@ impl Client {
         )
     }
 
-    pub fn gpus(&self) -> HashMap<String, Gpu> {
-        let mut socket = self.socket.borrow_mut();
-
-        let response = make_request(ipc::req_get_gpus(), &mut socket, self.socket_addr.as_ref())
+    fn gpus(&self) -> HashMap<String, Gpu> {
+        let response = self
+            .request(ipc::req_get_gpus())
             .and_then(|response| response.body);
 
         parse_response!(
@@ -812,15 +1163,10 @@ impl Client {
         )
     }
 
-    pub fn processes(&self) -> (HashMap<u32, Process>, Option<NetworkStatsError>) {
-        let mut socket = self.socket.borrow_mut();
-
-        let response = make_request(
-            ipc::req_get_processes(),
-            &mut socket,
-            self.socket_addr.as_ref(),
-        )
-        .and_then(|response| response.body);
+    fn processes(&self) -> (HashMap<u32, Process>, Option<NetworkStatsError>) {
+        let response = self
+            .request(ipc::req_get_processes())
+            .and_then(|response| response.body);
 
         let (mut processes, network_stats_error) = parse_response!(
             response,
@@ -849,10 +1195,9 @@ impl Client {
         (processes, network_stats_error)
     }
 
-    pub fn apps(&self) -> HashMap<String, App> {
-        let mut socket = self.socket.borrow_mut();
-
-        let response = make_request(ipc::req_get_apps(), &mut socket, self.socket_addr.as_ref())
+    fn apps(&self) -> HashMap<String, App> {
+        let response = self
+            .request(ipc::req_get_apps())
             .and_then(|response| response.body);
 
         parse_response!(
@@ -870,15 +1215,10 @@ impl Client {
         )
     }
 
-    pub fn services(&self) -> HashMap<String, Service> {
-        let mut socket = self.socket.borrow_mut();
-
-        let response = make_request(
-            ipc::req_get_services(),
-            &mut socket,
-            self.socket_addr.as_ref(),
-        )
-        .and_then(|response| response.body);
+    fn services(&self) -> HashMap<String, Service> {
+        let response = self
+            .request(ipc::req_get_services())
+            .and_then(|response| response.body);
 
         parse_response!(
             response,
@@ -895,15 +1235,10 @@ impl Client {
         )
     }
 
-    pub fn service_logs(&self, service_id: String, pid: Option<NonZeroU32>) -> String {
-        let mut socket = self.socket.borrow_mut();
-
-        let response = make_request(
-            ipc::req_get_logs(service_id, pid),
-            &mut socket,
-            self.socket_addr.as_ref(),
-        )
-        .and_then(|response| response.body);
+    fn service_logs(&self, service_id: String, pid: Option<NonZeroU32>) -> String {
+        let response = self
+            .request(ipc::req_get_logs(service_id, pid))
+            .and_then(|response| response.body);
 
         parse_response!(
             response,
@@ -914,15 +1249,10 @@ impl Client {
         )
     }
 
-    pub fn terminate_processes(&self, pids: Vec<u32>) {
-        let mut socket = self.socket.borrow_mut();
-
-        let response = make_request(
-            ipc::req_terminate_processes(pids),
-            &mut socket,
-            self.socket_addr.as_ref(),
-        )
-        .and_then(|response| response.body);
+    fn terminate_processes(&self, pids: Vec<u32>) {
+        let response = self
+            .request(ipc::req_terminate_processes(pids))
+            .and_then(|response| response.body);
 
         parse_response!(
             response,
@@ -933,15 +1263,10 @@ impl Client {
         )
     }
 
-    pub fn kill_processes(&self, pids: Vec<u32>) {
-        let mut socket = self.socket.borrow_mut();
-
-        let response = make_request(
-            ipc::req_kill_processes(pids),
-            &mut socket,
-            self.socket_addr.as_ref(),
-        )
-        .and_then(|response| response.body);
+    fn kill_processes(&self, pids: Vec<u32>) {
+        let response = self
+            .request(ipc::req_kill_processes(pids))
+            .and_then(|response| response.body);
 
         parse_response!(
             response,
@@ -952,15 +1277,10 @@ impl Client {
         )
     }
 
-    pub fn interrupt_processes(&self, pids: Vec<u32>) {
-        let mut socket = self.socket.borrow_mut();
-
-        let response = make_request(
-            ipc::req_interrupt_processes(pids),
-            &mut socket,
-            self.socket_addr.as_ref(),
-        )
-        .and_then(|response| response.body);
+    fn interrupt_processes(&self, pids: Vec<u32>) {
+        let response = self
+            .request(ipc::req_interrupt_processes(pids))
+            .and_then(|response| response.body);
 
         parse_response!(
             response,
@@ -971,15 +1291,10 @@ impl Client {
         )
     }
 
-    pub fn signal_user_one_processes(&self, pids: Vec<u32>) {
-        let mut socket = self.socket.borrow_mut();
-
-        let response = make_request(
-            ipc::req_signal_user_one_processes(pids),
-            &mut socket,
-            self.socket_addr.as_ref(),
-        )
-        .and_then(|response| response.body);
+    fn signal_user_one_processes(&self, pids: Vec<u32>) {
+        let response = self
+            .request(ipc::req_signal_user_one_processes(pids))
+            .and_then(|response| response.body);
 
         parse_response!(
             response,
@@ -990,15 +1305,10 @@ impl Client {
         )
     }
 
-    pub fn signal_user_two_processes(&self, pids: Vec<u32>) {
-        let mut socket = self.socket.borrow_mut();
-
-        let response = make_request(
-            ipc::req_signal_user_two_processes(pids),
-            &mut socket,
-            self.socket_addr.as_ref(),
-        )
-        .and_then(|response| response.body);
+    fn signal_user_two_processes(&self, pids: Vec<u32>) {
+        let response = self
+            .request(ipc::req_signal_user_two_processes(pids))
+            .and_then(|response| response.body);
 
         parse_response!(
             response,
@@ -1009,15 +1319,10 @@ impl Client {
         )
     }
 
-    pub fn hangup_processes(&self, pids: Vec<u32>) {
-        let mut socket = self.socket.borrow_mut();
-
-        let response = make_request(
-            ipc::req_hangup_processes(pids),
-            &mut socket,
-            self.socket_addr.as_ref(),
-        )
-        .and_then(|response| response.body);
+    fn hangup_processes(&self, pids: Vec<u32>) {
+        let response = self
+            .request(ipc::req_hangup_processes(pids))
+            .and_then(|response| response.body);
 
         parse_response!(
             response,
@@ -1028,15 +1333,10 @@ impl Client {
         )
     }
 
-    pub fn continue_processes(&self, pids: Vec<u32>) {
-        let mut socket = self.socket.borrow_mut();
-
-        let response = make_request(
-            ipc::req_continue_processes(pids),
-            &mut socket,
-            self.socket_addr.as_ref(),
-        )
-        .and_then(|response| response.body);
+    fn continue_processes(&self, pids: Vec<u32>) {
+        let response = self
+            .request(ipc::req_continue_processes(pids))
+            .and_then(|response| response.body);
 
         parse_response!(
             response,
@@ -1047,15 +1347,10 @@ impl Client {
         )
     }
 
-    pub fn suspend_processes(&self, pids: Vec<u32>) {
-        let mut socket = self.socket.borrow_mut();
-
-        let response = make_request(
-            ipc::req_suspend_processes(pids),
-            &mut socket,
-            self.socket_addr.as_ref(),
-        )
-        .and_then(|response| response.body);
+    fn suspend_processes(&self, pids: Vec<u32>) {
+        let response = self
+            .request(ipc::req_suspend_processes(pids))
+            .and_then(|response| response.body);
 
         parse_response!(
             response,
@@ -1066,15 +1361,10 @@ impl Client {
         )
     }
 
-    pub fn start_service(&self, service_id: String) {
-        let mut socket = self.socket.borrow_mut();
-
-        let response = make_request(
-            ipc::req_start_service(service_id),
-            &mut socket,
-            self.socket_addr.as_ref(),
-        )
-        .and_then(|response| response.body);
+    fn start_service(&self, service_id: String) {
+        let response = self
+            .request(ipc::req_start_service(service_id))
+            .and_then(|response| response.body);
 
         parse_response!(
             response,
@@ -1085,15 +1375,10 @@ impl Client {
         )
     }
 
-    pub fn stop_service(&self, service_id: String) {
-        let mut socket = self.socket.borrow_mut();
-
-        let response = make_request(
-            ipc::req_stop_service(service_id),
-            &mut socket,
-            self.socket_addr.as_ref(),
-        )
-        .and_then(|response| response.body);
+    fn stop_service(&self, service_id: String) {
+        let response = self
+            .request(ipc::req_stop_service(service_id))
+            .and_then(|response| response.body);
 
         parse_response!(
             response,
@@ -1104,15 +1389,10 @@ impl Client {
         )
     }
 
-    pub fn restart_service(&self, service_id: String) {
-        let mut socket = self.socket.borrow_mut();
-
-        let response = make_request(
-            ipc::req_restart_service(service_id),
-            &mut socket,
-            self.socket_addr.as_ref(),
-        )
-        .and_then(|response| response.body);
+    fn restart_service(&self, service_id: String) {
+        let response = self
+            .request(ipc::req_restart_service(service_id))
+            .and_then(|response| response.body);
 
         parse_response!(
             response,
@@ -1123,15 +1403,10 @@ impl Client {
         )
     }
 
-    pub fn enable_service(&self, service_id: String) {
-        let mut socket = self.socket.borrow_mut();
-
-        let response = make_request(
-            ipc::req_enable_service(service_id),
-            &mut socket,
-            self.socket_addr.as_ref(),
-        )
-        .and_then(|response| response.body);
+    fn enable_service(&self, service_id: String) {
+        let response = self
+            .request(ipc::req_enable_service(service_id))
+            .and_then(|response| response.body);
 
         parse_response!(
             response,
@@ -1142,15 +1417,10 @@ impl Client {
         )
     }
 
-    pub fn disable_service(&self, service_id: String) {
-        let mut socket = self.socket.borrow_mut();
-
-        let response = make_request(
-            ipc::req_disable_service(service_id),
-            &mut socket,
-            self.socket_addr.as_ref(),
-        )
-        .and_then(|response| response.body);
+    fn disable_service(&self, service_id: String) {
+        let response = self
+            .request(ipc::req_disable_service(service_id))
+            .and_then(|response| response.body);
 
         parse_response!(
             response,
@@ -1160,4 +1430,19 @@ impl Client {
             |_| {}
         )
     }
+
+    fn request_count(&self) -> u64 {
+        self.request_count.load(Ordering::Relaxed)
+    }
+
+    fn restart(&self) -> bool {
+        if std::env::var(ENV_MC_DEBUG_MAGPIE_PROCESS_SOCK).is_ok()
+            || !self.transport.needs_process()
+        {
+            return false;
+        }
+
+        self.restart_requested.store(true, Ordering::Relaxed);
+        true
+    }
 }
@@ -0,0 +1,134 @@
+/* magpie_client/collectors.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+/// The parts of the readings that can be asked from the gatherer less often than the update
+/// interval; CPU and memory usage drive most graphs and always follow it
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Collector {
+    /// Along with the apps they belong to
+    Processes,
+    Disks,
+    Network,
+    Gpus,
+    Fans,
+    Services,
+}
+
+impl Collector {
+    pub const ALL: [Collector; 6] = [
+        Collector::Processes,
+        Collector::Disks,
+        Collector::Network,
+        Collector::Gpus,
+        Collector::Fans,
+        Collector::Services,
+    ];
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            Collector::Processes => "processes",
+            Collector::Disks => "disks",
+            Collector::Network => "network",
+            Collector::Gpus => "gpus",
+            Collector::Fans => "fans",
+            Collector::Services => "services",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .find(|collector| collector.id() == id)
+            .copied()
+    }
+}
+
+/// How often collectors are read, for those that aren't read on every update
+pub type CollectorIntervals = HashMap<Collector, Duration>;
+
+/// Tells which collectors are due on each update, and keeps what the others read last time
+pub struct CollectorSchedule {
+    intervals: CollectorIntervals,
+    /// Never read, their readings stay empty
+    disabled: HashSet<Collector>,
+    last_read: HashMap<Collector, Instant>,
+}
+
+impl CollectorSchedule {
+    pub fn new() -> Self {
+        Self {
+            intervals: HashMap::new(),
+            disabled: HashSet::new(),
+            last_read: HashMap::new(),
+        }
+    }
+
+    pub fn configure(&mut self, intervals: CollectorIntervals) {
+        self.intervals = intervals;
+    }
+
+    pub fn set_disabled(&mut self, disabled: HashSet<Collector>) {
+        // Read right away once enabled again
+        for collector in &disabled {
+            self.last_read.remove(collector);
+        }
+        self.disabled = disabled;
+    }
+
+    /// Whether `collector` should be read on this update, which comes `update_interval` after
+    /// the last one; remembers it was read if so
+    pub fn is_due(&mut self, collector: Collector, update_interval: Duration) -> bool {
+        if self.disabled.contains(&collector) {
+            return false;
+        }
+
+        let now = Instant::now();
+
+        let due = match (
+            self.intervals.get(&collector),
+            self.last_read.get(&collector),
+        ) {
+            // Updates don't come exactly on time, rounding to the closest one keeps an interval
+            // that is a multiple of the update interval from slipping by a whole update
+            (Some(interval), Some(last_read)) => {
+                now.duration_since(*last_read) + update_interval / 2 >= *interval
+            }
+            _ => true,
+        };
+        if due {
+            self.last_read.insert(collector, now);
+        }
+
+        due
+    }
+
+    /// The readings of `collector` to hand over to the window; those read on their own schedule
+    /// are copied, so they can be handed over again until they are read next
+    pub fn hand_over<T: Clone + Default>(&self, collector: Collector, value: &mut T) -> T {
+        if self.intervals.contains_key(&collector) && !self.disabled.contains(&collector) {
+            value.clone()
+        } else {
+            std::mem::take(value)
+        }
+    }
+}
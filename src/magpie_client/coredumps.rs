@@ -0,0 +1,124 @@
+/* magpie_client/coredumps.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use super::{journal, Pid};
+use crate::is_flatpak;
+
+/// Journal message ID systemd-coredump logs every crash with
+const COREDUMP_MESSAGE_ID: &str = "fc2e22bc6ee647b6b90729ab34a250b1";
+const MAX_ENTRIES: u32 = 200;
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Coredump {
+    pub exe: String,
+    pub name: String,
+    pub pid: Pid,
+    pub uid: u32,
+    /// Signal name, e.g. `SIGSEGV`
+    pub signal: String,
+    /// Microseconds since the Unix epoch
+    pub timestamp: u64,
+    /// Where the dump was stored; `None` if it was only logged or has since been cleaned up
+    pub filename: Option<String>,
+    /// Size of the stored dump; unknown when running as a Flatpak
+    pub size: Option<u64>,
+    /// The log message, including the stack trace of the crashing thread when available
+    pub summary: String,
+}
+
+/// List the crashes recorded by systemd-coredump, newest first
+pub fn read() -> Result<Vec<Coredump>, String> {
+    let entries = journal::query(&[
+        format!("MESSAGE_ID={}", COREDUMP_MESSAGE_ID),
+        "--reverse".to_owned(),
+        format!("--lines={}", MAX_ENTRIES),
+        // Leave out `COREDUMP`, which holds the whole dump when it is stored in the journal
+        "--output-fields=MESSAGE,COREDUMP_EXE,COREDUMP_COMM,COREDUMP_PID,COREDUMP_UID,\
+            COREDUMP_SIGNAL,COREDUMP_SIGNAL_NAME,COREDUMP_TIMESTAMP,COREDUMP_FILENAME"
+            .to_owned(),
+    ])?;
+
+    Ok(entries
+        .into_iter()
+        .map(|entry| {
+            let field = |name: &str| entry.get(name).cloned().unwrap_or_default();
+            let number = |name: &str| {
+                entry
+                    .get(name)
+                    .and_then(|value| value.parse::<u64>().ok())
+                    .unwrap_or(0)
+            };
+
+            let signal = entry
+                .get("COREDUMP_SIGNAL_NAME")
+                .cloned()
+                .unwrap_or_else(|| signal_name(number("COREDUMP_SIGNAL")));
+
+            let timestamp = match number("COREDUMP_TIMESTAMP") {
+                0 => number("__REALTIME_TIMESTAMP"),
+                ts => ts,
+            };
+
+            let filename = entry.get("COREDUMP_FILENAME").cloned();
+            let size = if is_flatpak() {
+                None
+            } else {
+                filename
+                    .as_ref()
+                    .and_then(|f| std::fs::metadata(f).ok())
+                    .map(|m| m.len())
+            };
+            // The journal keeps the path of dumps that were removed by `systemd-tmpfiles`
+            let filename = if is_flatpak() || size.is_some() {
+                filename
+            } else {
+                None
+            };
+
+            Coredump {
+                exe: field("COREDUMP_EXE"),
+                name: field("COREDUMP_COMM"),
+                pid: number("COREDUMP_PID") as Pid,
+                uid: number("COREDUMP_UID") as u32,
+                signal,
+                timestamp,
+                filename,
+                size,
+                summary: field("MESSAGE"),
+            }
+        })
+        .collect())
+}
+
+fn signal_name(signal: u64) -> String {
+    match signal {
+        3 => "SIGQUIT".to_owned(),
+        4 => "SIGILL".to_owned(),
+        5 => "SIGTRAP".to_owned(),
+        6 => "SIGABRT".to_owned(),
+        7 => "SIGBUS".to_owned(),
+        8 => "SIGFPE".to_owned(),
+        11 => "SIGSEGV".to_owned(),
+        24 => "SIGXCPU".to_owned(),
+        25 => "SIGXFSZ".to_owned(),
+        31 => "SIGSYS".to_owned(),
+        signal => format!("SIG{}", signal),
+    }
+}
@@ -0,0 +1,78 @@
+/* magpie_client/cpu_smoothing.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::HashMap;
+use std::time::Instant;
+
+use super::{Pid, Process};
+
+/// How long it takes for a change in CPU usage to show up about two thirds of the way; in
+/// seconds, so the smoothing is the same whatever the update speed
+const TIME_CONSTANT: f32 = 3.;
+
+/// Turns the CPU usage of every process into an exponential moving average of it, so the numbers
+/// on the Apps page don't jump around on every refresh
+pub struct CpuSmoothing {
+    enabled: bool,
+    previous: HashMap<Pid, f32>,
+    previous_time: Instant,
+}
+
+impl CpuSmoothing {
+    pub fn new() -> Self {
+        Self {
+            enabled: false,
+            previous: HashMap::new(),
+            previous_time: Instant::now(),
+        }
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.previous.clear();
+        }
+    }
+
+    /// Smooth the CPU usage of `processes` in place; apps add up the usage of their processes, so
+    /// they follow
+    pub fn update(&mut self, processes: &mut HashMap<Pid, Process>) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.previous_time).as_secs_f32();
+        self.previous_time = now;
+
+        if !self.enabled {
+            return;
+        }
+
+        let weight = 1. - (-elapsed / TIME_CONSTANT).exp();
+
+        let mut current = HashMap::with_capacity(processes.len());
+        for (pid, process) in processes.iter_mut() {
+            let usage = &mut process.usage_stats.cpu_usage;
+            // New processes start from what they use right away, not from zero
+            if let Some(previous) = self.previous.get(pid) {
+                *usage = previous + (*usage - previous) * weight;
+            }
+            current.insert(*pid, *usage);
+        }
+        self.previous = current;
+    }
+}
@@ -0,0 +1,163 @@
+/* magpie_client/cpu_split.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::HashMap;
+use std::time::Instant;
+
+use super::proc_stat::{ProcStat, CLOCK_TICKS_PER_SECOND};
+use super::{Pid, Process};
+
+/// Time spent waiting for block I/O is only counted with delay accounting turned on
+const TASK_DELAYACCT: &str = "/proc/sys/kernel/task_delayacct";
+
+/// How the CPU time of a process splits between its own code and the kernel working for it
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct CpuSplit {
+    /// Share of the CPU usage spent in system calls, from 0 to 1; the rest ran in user space
+    pub system_fraction: f32,
+    /// Percentage of the time spent waiting for block I/O, if delay accounting is on
+    pub io_wait_percent: Option<f32>,
+}
+
+/// The split of a process' CPU usage, in the same units as `ProcessUsageStats::cpu_usage`
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct SplitUsage {
+    pub system_usage: f32,
+    pub io_wait_percent: Option<f32>,
+}
+
+impl SplitUsage {
+    pub fn merge(&mut self, other: &SplitUsage) {
+        self.system_usage += other.system_usage;
+        self.io_wait_percent = match (self.io_wait_percent, other.io_wait_percent) {
+            // Several processes can wait at the same time, like they can use several cores
+            (Some(lhs), Some(rhs)) => Some(lhs + rhs),
+            (lhs, rhs) => lhs.or(rhs),
+        };
+    }
+}
+
+/// A process that exited without its parent collecting its exit status
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct Zombie {
+    pub pid: Pid,
+    pub parent: Pid,
+}
+
+/// Remembers the CPU times of every process, to tell what they were spent on since the last
+/// refresh
+pub struct CpuSplitTracker {
+    previous: HashMap<Pid, ProcStat>,
+    previous_time: Instant,
+    /// Found while reading the CPU times, which come from the same file as the process state
+    zombies: Vec<Zombie>,
+}
+
+impl CpuSplitTracker {
+    pub fn new() -> Self {
+        Self {
+            previous: HashMap::new(),
+            previous_time: Instant::now(),
+            zombies: vec![],
+        }
+    }
+
+    /// The zombies found by the last update
+    pub fn zombies(&self) -> Vec<Zombie> {
+        self.zombies.clone()
+    }
+
+    /// `stats` are those of the processes in the last process list, see `proc_stat::read_all`
+    pub fn update(&mut self, stats: &HashMap<Pid, ProcStat>) -> HashMap<Pid, CpuSplit> {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.previous_time).as_secs_f32();
+        self.previous_time = now;
+
+        let delay_accounting =
+            std::fs::read_to_string(TASK_DELAYACCT).is_ok_and(|enabled| enabled.trim() == "1");
+
+        self.zombies.clear();
+
+        let mut result = HashMap::with_capacity(stats.len());
+        for (pid, stat) in stats {
+            if stat.state == 'Z' {
+                self.zombies.push(Zombie {
+                    pid: *pid,
+                    parent: stat.parent,
+                });
+            }
+
+            // A PID that was reused belongs to a different process
+            let Some(previous) = self
+                .previous
+                .get(pid)
+                .filter(|previous| previous.start_ticks == stat.start_ticks)
+            else {
+                continue;
+            };
+
+            let user = stat.user_ticks.saturating_sub(previous.user_ticks);
+            let system = stat.system_ticks.saturating_sub(previous.system_ticks);
+            let io_wait = stat.io_wait_ticks.saturating_sub(previous.io_wait_ticks);
+
+            result.insert(
+                *pid,
+                CpuSplit {
+                    system_fraction: if user + system > 0 {
+                        system as f32 / (user + system) as f32
+                    } else {
+                        0.
+                    },
+                    io_wait_percent: (delay_accounting && elapsed > 0.).then(|| {
+                        (io_wait as f32 / CLOCK_TICKS_PER_SECOND as f32 / elapsed * 100.).min(100.)
+                    }),
+                },
+            );
+        }
+        self.previous = stats.clone();
+
+        result
+    }
+}
+
+/// Split the CPU usage of `process`, along with that of its children when `merged`, the way the
+/// stats shown for it are
+pub fn split_usage(
+    process: &Process,
+    processes: &HashMap<Pid, Process>,
+    splits: &HashMap<Pid, CpuSplit>,
+    merged: bool,
+) -> Option<SplitUsage> {
+    let split = splits.get(&process.pid)?;
+    let mut result = SplitUsage {
+        system_usage: process.usage_stats.cpu_usage * split.system_fraction,
+        io_wait_percent: split.io_wait_percent,
+    };
+
+    if merged {
+        for child in process.children.iter().filter_map(|pid| processes.get(pid)) {
+            if let Some(child) = split_usage(child, processes, splits, true) {
+                result.merge(&child);
+            }
+        }
+    }
+
+    Some(result)
+}
@@ -0,0 +1,317 @@
+/* magpie_client/disk_encryption.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use gtk::glib::g_debug;
+
+use crate::is_flatpak;
+
+/// The cipher LUKS uses unless told otherwise
+const DEFAULT_CIPHER: &str = "aes-xts-plain64";
+const DEFAULT_CIPHER_CRYPTO_API_NAME: &str = "xts(aes)";
+const DEFAULT_KEY_SIZE_BITS: u32 = 512;
+
+static BENCHMARK: OnceLock<Option<CipherBenchmark>> = OnceLock::new();
+
+/// A dm-crypt mapping, e.g. an unlocked LUKS volume
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct EncryptedVolume {
+    /// Device mapper name, e.g. `luks-0f3c…`
+    pub name: String,
+    /// Kernel name of the mapping, e.g. `dm-0`
+    pub device: String,
+    /// LUKS version, `None` for plain dm-crypt and other formats
+    pub luks_version: Option<u8>,
+    /// The physical disk the volume is stored on, e.g. `nvme0n1`
+    pub disk_id: String,
+    /// Average time to complete a request on the volume and on the disk beneath it since the
+    /// last refresh; the difference is roughly what encryption costs
+    pub latency_ms: Option<f32>,
+    pub disk_latency_ms: Option<f32>,
+}
+
+/// The kernel implementation used for the default LUKS cipher
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct CryptoDriver {
+    /// Crypto API driver name, e.g. `xts-aes-aesni`
+    pub driver: String,
+    /// Whether the driver uses AES instructions of the CPU rather than the generic C code
+    pub accelerated: bool,
+}
+
+/// Output of `cryptsetup benchmark` for the default cipher, in MiB/s
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct CipherBenchmark {
+    pub encryption: f32,
+    pub decryption: f32,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct DiskEncryption {
+    pub volumes: Vec<EncryptedVolume>,
+    pub driver: Option<CryptoDriver>,
+    /// Filled in once the benchmark, which runs in the background, completes
+    pub benchmark: Option<CipherBenchmark>,
+}
+
+/// Keeps the I/O counters of the previous refresh to turn them into latencies
+pub struct DiskEncryptionMonitor {
+    previous_stats: HashMap<String, (u64, u64)>,
+    benchmark_started: bool,
+}
+
+impl DiskEncryptionMonitor {
+    pub fn new() -> Self {
+        Self {
+            previous_stats: HashMap::new(),
+            benchmark_started: false,
+        }
+    }
+
+    pub fn update(&mut self) -> DiskEncryption {
+        let mut volumes = encrypted_volumes();
+        if volumes.is_empty() {
+            return DiskEncryption::default();
+        }
+
+        // Takes a few seconds, so only spend them on systems that use encryption
+        if !self.benchmark_started {
+            self.benchmark_started = true;
+            std::thread::spawn(|| {
+                let _ = BENCHMARK.set(run_benchmark());
+            });
+        }
+
+        let mut stats = HashMap::new();
+        for volume in &mut volumes {
+            volume.latency_ms = self.latency(&volume.device, &mut stats);
+            volume.disk_latency_ms = self.latency(&volume.disk_id, &mut stats);
+        }
+        self.previous_stats = stats;
+
+        DiskEncryption {
+            volumes,
+            driver: crypto_driver(),
+            benchmark: BENCHMARK.get().copied().flatten(),
+        }
+    }
+
+    fn latency(&self, device: &str, stats: &mut HashMap<String, (u64, u64)>) -> Option<f32> {
+        let (requests, ticks) = *stats
+            .entry(device.to_owned())
+            .or_insert_with(|| block_device_stats(device).unwrap_or_default());
+        let (previous_requests, previous_ticks) = self.previous_stats.get(device)?;
+
+        let requests = requests.checked_sub(*previous_requests)?;
+        let ticks = ticks.checked_sub(*previous_ticks)?;
+        if requests == 0 {
+            return None;
+        }
+
+        Some(ticks as f32 / requests as f32)
+    }
+}
+
+/// Completed requests and milliseconds spent on them, from `/sys/block/DEV/stat`
+fn block_device_stats(device: &str) -> Option<(u64, u64)> {
+    let stat = std::fs::read_to_string(format!("/sys/block/{}/stat", device)).ok()?;
+    let fields = stat
+        .split_ascii_whitespace()
+        .map(|f| f.parse::<u64>().unwrap_or(0))
+        .collect::<Vec<_>>();
+    if fields.len() < 8 {
+        return None;
+    }
+
+    // Reads completed, merged, sectors, milliseconds; then the same for writes
+    Some((fields[0] + fields[4], fields[3] + fields[7]))
+}
+
+fn encrypted_volumes() -> Vec<EncryptedVolume> {
+    let Ok(entries) = std::fs::read_dir("/sys/block") else {
+        return vec![];
+    };
+
+    let mut result = vec![];
+    for entry in entries.filter_map(|e| e.ok()) {
+        let device = entry.file_name().to_string_lossy().into_owned();
+        if !device.starts_with("dm-") {
+            continue;
+        }
+
+        let dm_path = entry.path().join("dm");
+        let Ok(uuid) = std::fs::read_to_string(dm_path.join("uuid")) else {
+            continue;
+        };
+        // dm-crypt mappings set up by cryptsetup have UUIDs like `CRYPT-LUKS2-<uuid>-<name>`
+        let Some(uuid) = uuid.trim().strip_prefix("CRYPT-") else {
+            continue;
+        };
+        let luks_version = uuid
+            .strip_prefix("LUKS")
+            .and_then(|v| v.chars().next())
+            .and_then(|v| v.to_digit(10))
+            .map(|v| v as u8);
+
+        let name = std::fs::read_to_string(dm_path.join("name"))
+            .map(|n| n.trim().to_owned())
+            .unwrap_or_else(|_| device.clone());
+
+        for disk_id in backing_disks(&device) {
+            result.push(EncryptedVolume {
+                name: name.clone(),
+                device: device.clone(),
+                luks_version,
+                disk_id,
+                latency_ms: None,
+                disk_latency_ms: None,
+            });
+        }
+    }
+
+    result.sort_by(|v1, v2| v1.name.cmp(&v2.name));
+    result
+}
+
+/// Walk down the device mapper stack (e.g. LUKS on LVM) to the physical disks
+fn backing_disks(device: &str) -> Vec<String> {
+    let Ok(slaves) = std::fs::read_dir(format!("/sys/block/{}/slaves", device)) else {
+        return vec![];
+    };
+
+    let mut result = vec![];
+    for slave in slaves.filter_map(|s| s.ok()) {
+        let slave = slave.file_name().to_string_lossy().into_owned();
+
+        if slave.starts_with("dm-") {
+            result.extend(backing_disks(&slave));
+        } else if Path::new("/sys/block").join(&slave).exists() {
+            result.push(slave);
+        } else if let Some(disk) = std::fs::canonicalize(format!("/sys/class/block/{}", slave))
+            .ok()
+            .and_then(|path| {
+                // Partitions live in a directory named after their disk
+                path.parent()
+                    .and_then(|p| p.file_name())
+                    .map(|d| d.to_string_lossy().into_owned())
+            })
+        {
+            result.push(disk);
+        }
+    }
+
+    result.sort();
+    result.dedup();
+    result
+}
+
+/// Find the highest priority implementation of the default cipher in `/proc/crypto`; it is
+/// what dm-crypt ends up using
+fn crypto_driver() -> Option<CryptoDriver> {
+    let crypto = std::fs::read_to_string("/proc/crypto").ok()?;
+
+    let mut best: Option<(i64, String)> = None;
+    for block in crypto.split("\n\n") {
+        let mut name = None;
+        let mut driver = None;
+        let mut priority = 0;
+        for line in block.lines() {
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            match key.trim() {
+                "name" => name = Some(value.trim()),
+                "driver" => driver = Some(value.trim()),
+                "priority" => priority = value.trim().parse().unwrap_or(0),
+                _ => {}
+            }
+        }
+
+        if name != Some(DEFAULT_CIPHER_CRYPTO_API_NAME) {
+            continue;
+        }
+        let Some(driver) = driver else {
+            continue;
+        };
+        if best.as_ref().map(|(p, _)| priority > *p).unwrap_or(true) {
+            best = Some((priority, driver.to_owned()));
+        }
+    }
+
+    best.map(|(_, driver)| CryptoDriver {
+        accelerated: !driver.contains("generic"),
+        driver,
+    })
+}
+
+fn run_benchmark() -> Option<CipherBenchmark> {
+    let args = [
+        "cryptsetup".to_owned(),
+        "benchmark".to_owned(),
+        format!("--cipher={}", DEFAULT_CIPHER),
+        format!("--key-size={}", DEFAULT_KEY_SIZE_BITS),
+    ];
+
+    let mut cmd = if is_flatpak() {
+        let mut cmd = std::process::Command::new("/usr/bin/flatpak-spawn");
+        cmd.arg("--host");
+        cmd.args(&args);
+        cmd
+    } else {
+        let mut cmd = std::process::Command::new(&args[0]);
+        cmd.args(&args[1..]);
+        cmd
+    };
+
+    let output = match cmd.output() {
+        Ok(output) if output.status.success() => output,
+        Ok(output) => {
+            g_debug!(
+                "MissionCenter::SysInfo",
+                "cryptsetup benchmark failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+            return None;
+        }
+        Err(e) => {
+            g_debug!(
+                "MissionCenter::SysInfo",
+                "Failed to run cryptsetup benchmark: {}",
+                e
+            );
+            return None;
+        }
+    };
+
+    // The last line reads `aes-xts   512b   2345.6 MiB/s   2401.2 MiB/s`
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let line = stdout.lines().rev().find(|l| l.contains("MiB/s"))?;
+    let mut speeds = line
+        .split_ascii_whitespace()
+        .filter_map(|field| field.parse::<f32>().ok());
+
+    Some(CipherBenchmark {
+        encryption: speeds.next()?,
+        decryption: speeds.next()?,
+    })
+}
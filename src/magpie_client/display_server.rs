@@ -0,0 +1,114 @@
+/* magpie_client/display_server.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::HashMap;
+
+use super::{Pid, Process};
+
+/// Executables of display servers, compositors and the shells that embed one
+const DISPLAY_SERVER_EXECUTABLES: &[&str] = &[
+    "Xorg",
+    "X",
+    "Xwayland",
+    "gnome-shell",
+    "mutter",
+    "mutter-x11-frames",
+    "kwin_wayland",
+    "kwin_x11",
+    "sway",
+    "Hyprland",
+    "weston",
+    "labwc",
+    "wayfire",
+    "river",
+    "niri",
+    "cosmic-comp",
+    "gamescope",
+    "phoc",
+    "cinnamon",
+    "muffin",
+    "marco",
+    "xfwm4",
+    "budgie-wm",
+    "gala",
+    "enlightenment",
+    "picom",
+    "compton",
+    "xcompmgr",
+];
+
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct UsageShare {
+    pub cpu_usage: f32,
+    pub gpu_usage: f32,
+    pub gpu_memory_usage: u64,
+}
+
+/// Usage of the display server and compositor, split from everything else that runs
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct DisplayServerUsage {
+    pub display_server: UsageShare,
+    pub others: UsageShare,
+    /// The processes counted as display server, by PID and executable name
+    pub processes: Vec<(Pid, String)>,
+}
+
+/// The executable name a process is classified by; `name` is truncated by the kernel to 15
+/// characters and can be changed by the process, so the executable path is preferred
+fn executable_name(process: &Process) -> &str {
+    if let Some(name) = process.exe.rsplit('/').next().filter(|n| !n.is_empty()) {
+        return name;
+    }
+
+    process
+        .cmd
+        .first()
+        .and_then(|cmd| cmd.split_ascii_whitespace().next())
+        .and_then(|cmd| cmd.rsplit('/').next())
+        .unwrap_or(&process.name)
+}
+
+fn is_display_server(process: &Process) -> bool {
+    DISPLAY_SERVER_EXECUTABLES.contains(&executable_name(process))
+}
+
+pub fn summarize(processes: &HashMap<Pid, Process>) -> DisplayServerUsage {
+    let mut result = DisplayServerUsage::default();
+
+    for process in processes.values() {
+        let usage_stats = &process.usage_stats;
+        let share = if is_display_server(process) {
+            result
+                .processes
+                .push((process.pid, executable_name(process).to_owned()));
+            &mut result.display_server
+        } else {
+            &mut result.others
+        };
+
+        share.cpu_usage += usage_stats.cpu_usage;
+        share.gpu_usage += usage_stats.gpu_usage;
+        share.gpu_memory_usage += usage_stats.gpu_memory_usage;
+    }
+
+    result.processes.sort();
+
+    result
+}
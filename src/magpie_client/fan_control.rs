@@ -0,0 +1,365 @@
+/* magpie_client/fan_control.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use gtk::glib::{g_critical, g_message, g_warning};
+
+use super::{privileged, Fan};
+use crate::is_flatpak;
+use crate::performance_page::MK_TO_0_C;
+
+/// The lowest duty cycle a user can set a fan to; below this a lot of fans stall
+pub const MIN_MANUAL_PWM_PERCENT: f32 = 20.;
+/// Fans under manual control are handed back to the firmware above this temperature
+pub const MAX_MANUAL_TEMP_C: f32 = 85.;
+
+const PWM_ENABLE_MANUAL: u8 = 1;
+const PWM_ENABLE_AUTO: u8 = 2;
+
+// Only touch the fan when the curve asks for a noticeably different speed, not at every reading
+const CURVE_HYSTERESIS_PERCENT: f32 = 5.;
+
+/// A temperature (°C) to fan duty cycle (%) mapping, linearly interpolated between points
+#[derive(Debug, Clone, PartialEq)]
+pub struct FanCurve {
+    points: Vec<(f32, f32)>,
+}
+
+impl Default for FanCurve {
+    fn default() -> Self {
+        Self::new(vec![(30., 20.), (50., 35.), (65., 60.), (80., 100.)])
+    }
+}
+
+impl std::fmt::Display for FanCurve {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let points = self
+            .points
+            .iter()
+            .map(|(temp, pwm)| format!("{:.0}:{:.0}", temp, pwm))
+            .collect::<Vec<_>>()
+            .join(",");
+        write!(f, "{}", points)
+    }
+}
+
+impl FanCurve {
+    pub fn new(mut points: Vec<(f32, f32)>) -> Self {
+        for (_, pwm) in points.iter_mut() {
+            *pwm = pwm.clamp(MIN_MANUAL_PWM_PERCENT, 100.);
+        }
+        points.sort_unstable_by(|(t1, _), (t2, _)| t1.total_cmp(t2));
+
+        Self { points }
+    }
+
+    /// Parse a curve in the `temp:pwm,temp:pwm,...` format used in the settings
+    pub fn parse(curve: &str) -> Option<Self> {
+        let points = curve
+            .split(',')
+            .map(|point| {
+                let (temp, pwm) = point.trim().split_once(':')?;
+                Some((temp.parse::<f32>().ok()?, pwm.parse::<f32>().ok()?))
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        if points.is_empty() {
+            return None;
+        }
+
+        Some(Self::new(points))
+    }
+
+    pub fn points(&self) -> &[(f32, f32)] {
+        &self.points
+    }
+
+    pub fn pwm_percent(&self, temp_c: f32) -> f32 {
+        let Some(&(first_temp, first_pwm)) = self.points.first() else {
+            return 100.;
+        };
+        if temp_c <= first_temp {
+            return first_pwm;
+        }
+
+        for window in self.points.windows(2) {
+            let (t1, p1) = window[0];
+            let (t2, p2) = window[1];
+            if temp_c <= t2 {
+                if t2 <= t1 {
+                    return p2;
+                }
+                return p1 + (p2 - p1) * (temp_c - t1) / (t2 - t1);
+            }
+        }
+
+        self.points.last().map(|(_, pwm)| *pwm).unwrap_or(100.)
+    }
+}
+
+/// The PWM channel that controls `fan<fan_index>` of `hwmon<hwmon_index>`, if there is one.
+///
+/// hwmon has no attribute that ties a PWM channel to a fan; `pwmN` drives `fanN` for the drivers
+/// that have both, any other pairing is a guess, so fans without their own `pwmN` and
+/// `pwmN_enable` aren't offered for control at all
+pub fn pwm_channel(hwmon_index: u32, fan_index: u32) -> Option<u32> {
+    let exists = |attribute: String| Path::new(&hwmon_path(hwmon_index, &attribute)).exists();
+
+    (exists(format!("fan{}_input", fan_index))
+        && exists(pwm_attribute(fan_index, ""))
+        && exists(pwm_attribute(fan_index, "_enable")))
+    .then_some(fan_index)
+}
+
+/// Keeps track of the fans that were switched to manual control so that they can be handed back
+/// to automatic control when the user asks for it, when a safety limit is hit, or when the app
+/// exits.
+pub struct FanControl {
+    // (hwmon index, fan index) -> PWM channel and value of `pwmN_enable` before we took over
+    controlled: HashMap<(u32, u32), (u32, u8)>,
+    curves: HashMap<(u32, u32), FanCurve>,
+    // The duty cycle last requested by a curve, for each fan with a curve
+    curve_pwm: HashMap<(u32, u32), f32>,
+}
+
+impl Drop for FanControl {
+    fn drop(&mut self) {
+        self.restore_all();
+    }
+}
+
+impl FanControl {
+    pub fn new() -> Self {
+        Self {
+            controlled: HashMap::new(),
+            curves: HashMap::new(),
+            curve_pwm: HashMap::new(),
+        }
+    }
+
+    /// Set the fan to a fixed duty cycle, or hand it back to automatic control when `pwm_percent`
+    /// is `None`; returns whether the fan is now at that speed
+    pub fn set_speed(
+        &mut self,
+        hwmon_index: u32,
+        fan_index: u32,
+        pwm_percent: Option<f32>,
+    ) -> bool {
+        let key = (hwmon_index, fan_index);
+
+        let Some(pwm_percent) = pwm_percent else {
+            if let Some((channel, original_mode)) = self.controlled.remove(&key) {
+                Self::restore(hwmon_index, channel, original_mode);
+            }
+            return true;
+        };
+
+        let pwm_percent = pwm_percent.clamp(MIN_MANUAL_PWM_PERCENT, 100.);
+        let pwm = (pwm_percent * 255. / 100.).round() as u8;
+
+        let (channel, original_mode) = match self.controlled.get(&key) {
+            Some(controlled) => *controlled,
+            None => {
+                let Some(channel) = pwm_channel(hwmon_index, fan_index) else {
+                    g_critical!(
+                        "MissionCenter::FanControl",
+                        "No PWM channel controls fan {} of hwmon{}",
+                        fan_index,
+                        hwmon_index
+                    );
+                    return false;
+                };
+                let mode = std::fs::read_to_string(hwmon_path(
+                    hwmon_index,
+                    &pwm_attribute(channel, "_enable"),
+                ))
+                .ok()
+                .and_then(|mode| mode.trim().parse::<u8>().ok())
+                .unwrap_or(PWM_ENABLE_AUTO);
+                (channel, mode)
+            }
+        };
+
+        let writes = [
+            (pwm_attribute(channel, "_enable"), PWM_ENABLE_MANUAL as u32),
+            (pwm_attribute(channel, ""), pwm as u32),
+        ];
+        if let Err(e) = write_sysfs(hwmon_index, &writes) {
+            g_critical!(
+                "MissionCenter::FanControl",
+                "Failed to set speed for fan {} of hwmon{}: {}",
+                fan_index,
+                hwmon_index,
+                e
+            );
+            return false;
+        }
+
+        self.controlled.insert(key, (channel, original_mode));
+        true
+    }
+
+    /// Drive the fan using `curve` from now on, or stop using a curve and hand the fan back to
+    /// automatic control when `curve` is `None`
+    pub fn set_curve(&mut self, hwmon_index: u32, fan_index: u32, curve: Option<FanCurve>) {
+        let key = (hwmon_index, fan_index);
+        self.curve_pwm.remove(&key);
+
+        match curve {
+            Some(curve) => {
+                self.curves.insert(key, curve);
+            }
+            None => {
+                if self.curves.remove(&key).is_some() {
+                    self.set_speed(hwmon_index, fan_index, None);
+                }
+            }
+        }
+    }
+
+    /// Set the speed of every fan that has a curve based on its current temperature
+    pub fn apply_curves(&mut self, fans: &[Fan]) {
+        for fan in fans {
+            let key = (fan.hwmon_index as u32, fan.fan_index as u32);
+            let Some(curve) = self.curves.get(&key) else {
+                continue;
+            };
+
+            let Some(temp_mk) = fan.temp_amount else {
+                continue;
+            };
+            let temp_c = (temp_mk as i32 + MK_TO_0_C) as f32 / 1000.;
+            // `enforce_limits` hands the fan over to the firmware above this
+            if temp_c >= MAX_MANUAL_TEMP_C {
+                self.curve_pwm.remove(&key);
+                continue;
+            }
+
+            let pwm_percent = curve.pwm_percent(temp_c);
+            if let Some(last_pwm) = self.curve_pwm.get(&key) {
+                if (last_pwm - pwm_percent).abs() < CURVE_HYSTERESIS_PERCENT {
+                    continue;
+                }
+            }
+
+            // Drop a curve that can't be followed, likely because authorization was refused,
+            // instead of asking again at every reading
+            if !self.set_speed(key.0, key.1, Some(pwm_percent)) {
+                self.curves.remove(&key);
+                self.curve_pwm.remove(&key);
+                continue;
+            }
+            self.curve_pwm.insert(key, pwm_percent);
+        }
+    }
+
+    /// Hand fans that got too hot back to the firmware
+    pub fn enforce_limits(&mut self, fans: &[Fan]) {
+        for fan in fans {
+            let key = (fan.hwmon_index as u32, fan.fan_index as u32);
+            if !self.controlled.contains_key(&key) {
+                continue;
+            }
+
+            let Some(temp_mk) = fan.temp_amount else {
+                continue;
+            };
+            let temp_c = (temp_mk as i32 + MK_TO_0_C) as f32 / 1000.;
+            if temp_c < MAX_MANUAL_TEMP_C {
+                continue;
+            }
+
+            g_warning!(
+                "MissionCenter::FanControl",
+                "Temperature for fan {} of hwmon{} reached {:.1} °C, reverting to automatic control",
+                key.1,
+                key.0,
+                temp_c
+            );
+            self.set_speed(key.0, key.1, None);
+        }
+    }
+
+    pub fn restore_all(&mut self) {
+        for ((hwmon_index, _), (channel, original_mode)) in self.controlled.drain() {
+            Self::restore(hwmon_index, channel, original_mode);
+        }
+    }
+
+    fn restore(hwmon_index: u32, channel: u32, original_mode: u8) {
+        // Never leave a fan in manual mode; if it was like that before we touched it, hand it to
+        // the firmware instead
+        let mode = if original_mode == PWM_ENABLE_MANUAL {
+            PWM_ENABLE_AUTO
+        } else {
+            original_mode
+        };
+
+        match write_sysfs(
+            hwmon_index,
+            &[(pwm_attribute(channel, "_enable"), mode as u32)],
+        ) {
+            Ok(_) => {
+                g_message!(
+                    "MissionCenter::FanControl",
+                    "PWM channel {} of hwmon{} returned to automatic control",
+                    channel,
+                    hwmon_index
+                );
+            }
+            Err(e) => {
+                g_critical!(
+                    "MissionCenter::FanControl",
+                    "Failed to return PWM channel {} of hwmon{} to automatic control: {}",
+                    channel,
+                    hwmon_index,
+                    e
+                );
+            }
+        }
+    }
+}
+
+fn hwmon_path(hwmon_index: u32, attribute: &str) -> String {
+    format!("/sys/class/hwmon/hwmon{}/{}", hwmon_index, attribute)
+}
+
+fn pwm_attribute(channel: u32, suffix: &str) -> String {
+    format!("pwm{}{}", channel, suffix)
+}
+
+// Try writing directly first, some setups grant users access to the PWM controls through udev
+// rules, and fall back to the helper's `set-fan-speed` otherwise; authorization is only asked
+// for once, that helper stays around
+fn write_sysfs(hwmon_index: u32, writes: &[(String, u32)]) -> Result<(), String> {
+    if !is_flatpak()
+        && writes.iter().all(|(attribute, value)| {
+            std::fs::write(hwmon_path(hwmon_index, attribute), value.to_string()).is_ok()
+        })
+    {
+        return Ok(());
+    }
+
+    writes.iter().try_for_each(|(attribute, value)| {
+        privileged::write_fan_control(hwmon_index, attribute, *value)
+    })
+}
@@ -0,0 +1,233 @@
+/* magpie_client/foreground_boost.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::io::BufRead;
+use std::sync::{Arc, Mutex};
+
+use gtk::glib::{g_debug, g_warning};
+
+use super::{cgroup, CgroupWeights, Pid};
+use crate::is_flatpak;
+
+/// The focused app gets this many times its usual CPU weight; enough to win against
+/// background work without starving it
+const BOOST_FACTOR: u32 = 2;
+
+/// The app currently getting a larger share of CPU time
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct BoostedApp {
+    pub pid: Pid,
+    /// The systemd scope the app runs in, e.g. `app-gnome-firefox-1234.scope`
+    pub unit: String,
+    pub cpu_weight: u32,
+    pub original_cpu_weight: u32,
+}
+
+/// Whether the focused window can be detected in this session. Only X11 advertises it to other
+/// clients; on Wayland, XWayland only knows about its own windows, so the focused one would be
+/// missed most of the time.
+pub fn is_supported() -> bool {
+    std::env::var("XDG_SESSION_TYPE").is_ok_and(|session_type| session_type == "x11")
+}
+
+/// Raises the CPU weight of the app owning the focused window and puts it back once another
+/// window gets focus, the feature is turned off or Mission Center exits
+pub struct ForegroundBoost {
+    focus: Option<FocusWatcher>,
+    boosted: Option<(BoostedApp, CgroupWeights)>,
+}
+
+impl Drop for ForegroundBoost {
+    fn drop(&mut self) {
+        self.restore();
+    }
+}
+
+impl ForegroundBoost {
+    pub fn new() -> Self {
+        Self {
+            focus: None,
+            boosted: None,
+        }
+    }
+
+    /// Does nothing where the focused window can't be detected, see `is_supported`
+    pub fn set_enabled(&mut self, enabled: bool) {
+        if !enabled || !is_supported() {
+            self.focus = None;
+            self.restore();
+            return;
+        }
+
+        if self.focus.is_none() {
+            self.focus = FocusWatcher::start();
+        }
+    }
+
+    pub fn update(&mut self) -> Option<BoostedApp> {
+        let focus = self.focus.as_ref()?;
+
+        let Some(pid) = focus.pid() else {
+            self.restore();
+            return None;
+        };
+
+        if let Some((boosted, _)) = &self.boosted {
+            if boosted.pid == pid {
+                return Some(boosted.clone());
+            }
+        }
+
+        self.restore();
+
+        let weights = cgroup::weights(pid)?;
+        let unit = weights.unit.clone()?;
+        // Only touch scopes that belong to a single app; boosting the whole session would defeat
+        // the purpose, and system units would ask for a password on every focus change
+        if !weights.user_unit || !unit.starts_with("app-") {
+            return None;
+        }
+
+        let original_cpu_weight = weights.cpu_weight.unwrap_or(cgroup::DEFAULT_WEIGHT);
+        let cpu_weight = (original_cpu_weight * BOOST_FACTOR).min(cgroup::MAX_WEIGHT);
+        if let Err(e) = cgroup::set_weights(&weights, Some(cpu_weight), None) {
+            g_warning!(
+                "MissionCenter::SysInfo",
+                "Failed to boost the CPU weight of {}: {}",
+                unit,
+                e
+            );
+            return None;
+        }
+
+        let boosted = BoostedApp {
+            pid,
+            unit,
+            cpu_weight,
+            original_cpu_weight,
+        };
+        self.boosted = Some((boosted.clone(), weights));
+
+        Some(boosted)
+    }
+
+    fn restore(&mut self) {
+        let Some((boosted, weights)) = self.boosted.take() else {
+            return;
+        };
+
+        if let Err(e) = cgroup::set_weights(&weights, Some(boosted.original_cpu_weight), None) {
+            // The app most likely exited, taking its scope with it
+            g_debug!(
+                "MissionCenter::SysInfo",
+                "Failed to restore the CPU weight of {}: {}",
+                boosted.unit,
+                e
+            );
+        }
+    }
+}
+
+/// Follows the focused window through EWMH with a single `xprop` that reports every change,
+/// so that the refresh loop only has to look at the last one
+struct FocusWatcher {
+    xprop: std::process::Child,
+    pid: Arc<Mutex<Option<Pid>>>,
+}
+
+impl Drop for FocusWatcher {
+    fn drop(&mut self) {
+        let _ = self.xprop.kill();
+        let _ = self.xprop.wait();
+    }
+}
+
+impl FocusWatcher {
+    fn start() -> Option<Self> {
+        let mut xprop = xprop(&["-root", "-spy", "_NET_ACTIVE_WINDOW"])
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::null())
+            .spawn()
+            .inspect_err(|e| {
+                g_warning!(
+                    "MissionCenter::SysInfo",
+                    "Failed to follow the focused window: {}",
+                    e
+                );
+            })
+            .ok()?;
+        let output = xprop.stdout.take()?;
+
+        let pid = Arc::new(Mutex::new(None));
+        std::thread::spawn({
+            let pid = pid.clone();
+            move || {
+                // Ends once `xprop` is gone
+                for line in std::io::BufReader::new(output).lines() {
+                    let Ok(line) = line else {
+                        break;
+                    };
+                    let focused = window_pid(&line);
+                    if let Ok(mut pid) = pid.lock() {
+                        *pid = focused;
+                    }
+                }
+            }
+        });
+
+        Some(Self { xprop, pid })
+    }
+
+    fn pid(&self) -> Option<Pid> {
+        self.pid.lock().ok().and_then(|pid| *pid)
+    }
+}
+
+/// The PID of the app owning the window in a `_NET_ACTIVE_WINDOW` line from `xprop`, e.g.
+/// `_NET_ACTIVE_WINDOW(WINDOW): window id # 0x2c00006`; looked up once per focus change
+fn window_pid(active_window: &str) -> Option<Pid> {
+    let window_id = active_window.rsplit(' ').next()?.trim();
+    if !window_id.starts_with("0x") || window_id == "0x0" {
+        return None;
+    }
+
+    // `_NET_WM_PID(CARDINAL) = 4242`
+    let output = xprop(&["-id", window_id, "_NET_WM_PID"]).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let pid = String::from_utf8(output.stdout).ok()?;
+    pid.rsplit('=').next()?.trim().parse::<Pid>().ok()
+}
+
+fn xprop(args: &[&str]) -> std::process::Command {
+    let mut cmd = if is_flatpak() {
+        let mut cmd = std::process::Command::new("/usr/bin/flatpak-spawn");
+        // Takes `xprop` along when it is stopped
+        cmd.arg("--host").arg("--watch-bus").arg("xprop");
+        cmd
+    } else {
+        std::process::Command::new("xprop")
+    };
+    cmd.args(args);
+
+    cmd
+}
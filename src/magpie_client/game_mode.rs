@@ -0,0 +1,100 @@
+/* magpie_client/game_mode.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use gtk::{
+    gio,
+    glib::{self, g_debug},
+    prelude::*,
+};
+
+use super::Pid;
+
+const GAME_MODE_BUS_NAME: &str = "com.feralinteractive.GameMode";
+const GAME_MODE_PATH: &str = "/com/feralinteractive/GameMode";
+const GAME_MODE_CALL_TIMEOUT_MS: i32 = 250;
+
+/// A process that asked Feral GameMode to optimize the system for it
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct GameModeClient {
+    pub pid: Pid,
+    /// Path of the game's executable, as registered with the daemon
+    pub executable: String,
+}
+
+/// The games GameMode is currently active for, empty when it's idle or not running at all
+pub fn read() -> Vec<GameModeClient> {
+    let connection = match gio::bus_get_sync(gio::BusType::Session, gio::Cancellable::NONE) {
+        Ok(connection) => connection,
+        Err(e) => {
+            g_debug!(
+                "MissionCenter::SysInfo",
+                "Failed to connect to the session bus: {}",
+                e
+            );
+            return vec![];
+        }
+    };
+
+    // Not auto started, asking would otherwise launch the daemon just to find it idle
+    let games = match connection.call_sync(
+        Some(GAME_MODE_BUS_NAME),
+        GAME_MODE_PATH,
+        GAME_MODE_BUS_NAME,
+        "ListGames",
+        None,
+        Some(glib::VariantTy::new("(a(io))").unwrap()),
+        gio::DBusCallFlags::NO_AUTO_START,
+        GAME_MODE_CALL_TIMEOUT_MS,
+        gio::Cancellable::NONE,
+    ) {
+        Ok(games) => games.child_value(0),
+        Err(_) => return vec![],
+    };
+
+    games
+        .iter()
+        .filter_map(|game| {
+            let (pid, path) = game.get::<(i32, glib::variant::ObjectPath)>()?;
+            Some(GameModeClient {
+                pid: pid as Pid,
+                executable: executable(&connection, path.as_str()).unwrap_or_default(),
+            })
+        })
+        .collect()
+}
+
+fn executable(connection: &gio::DBusConnection, path: &str) -> Option<String> {
+    connection
+        .call_sync(
+            Some(GAME_MODE_BUS_NAME),
+            path,
+            "org.freedesktop.DBus.Properties",
+            "Get",
+            Some(&("com.feralinteractive.GameMode.Game", "Executable").to_variant()),
+            Some(glib::VariantTy::new("(v)").unwrap()),
+            gio::DBusCallFlags::NO_AUTO_START,
+            GAME_MODE_CALL_TIMEOUT_MS,
+            gio::Cancellable::NONE,
+        )
+        .ok()?
+        .child_value(0)
+        .as_variant()?
+        .get::<String>()
+}
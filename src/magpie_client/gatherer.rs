@@ -0,0 +1,75 @@
+/* magpie_client/gatherer.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::HashMap;
+use std::num::NonZeroU32;
+
+use magpie_types::processes::processes_response::process_map::NetworkStatsError;
+
+use super::{
+    App, Connection, Cpu, Disk, ErrorEjectFailed, Fan, Gpu, Memory, MemoryDevice, Process, Service,
+    SmartData,
+};
+
+/// Where the readings come from, and what acts on the processes and services they list; `Client`
+/// talks to magpie, the other implementations stand in for it
+pub trait Gatherer {
+    fn set_scale_cpu_usage_to_core_count(&self, v: bool);
+
+    fn cpu(&self) -> Cpu;
+    fn memory(&self) -> Memory;
+    fn memory_devices(&self) -> Vec<MemoryDevice>;
+    fn disks_info(&self) -> Vec<Disk>;
+    fn eject_disk(&self, disk_id: String) -> Result<(), ErrorEjectFailed>;
+    fn smart_data(&self, disk_id: String) -> Option<SmartData>;
+    fn fans_info(&self) -> Vec<Fan>;
+    fn network_connections(&self) -> Vec<Connection>;
+    fn gpus(&self) -> HashMap<String, Gpu>;
+    fn processes(&self) -> (HashMap<u32, Process>, Option<NetworkStatsError>);
+    fn apps(&self) -> HashMap<String, App>;
+    fn services(&self) -> HashMap<String, Service>;
+    fn service_logs(&self, service_id: String, pid: Option<NonZeroU32>) -> String;
+
+    fn terminate_processes(&self, pids: Vec<u32>);
+    fn kill_processes(&self, pids: Vec<u32>);
+    fn interrupt_processes(&self, pids: Vec<u32>);
+    fn signal_user_one_processes(&self, pids: Vec<u32>);
+    fn signal_user_two_processes(&self, pids: Vec<u32>);
+    fn hangup_processes(&self, pids: Vec<u32>);
+    fn continue_processes(&self, pids: Vec<u32>);
+    fn suspend_processes(&self, pids: Vec<u32>);
+
+    fn start_service(&self, service_id: String);
+    fn stop_service(&self, service_id: String);
+    fn restart_service(&self, service_id: String);
+    fn enable_service(&self, service_id: String);
+    fn disable_service(&self, service_id: String);
+
+    /// How many requests were made to it so far, for telling what it costs
+    fn request_count(&self) -> u64 {
+        0
+    }
+
+    /// Restart the gatherer process, for when a part of it stopped working; `false` when it is
+    /// not one Mission Center started
+    fn restart(&self) -> bool {
+        false
+    }
+}
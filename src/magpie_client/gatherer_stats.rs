@@ -0,0 +1,139 @@
+/* magpie_client/gatherer_stats.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use super::proc_stat::{self, CLOCK_TICKS_PER_SECOND};
+use super::{Pid, Process};
+
+/// Every build of magpie has an executable named like this, with a suffix for the C library it
+/// is linked against in the Flatpak
+const MAGPIE_EXECUTABLE: &str = "missioncenter-magpie";
+
+/// The readings that are timed, each being one or a few requests to the gatherer
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Reading {
+    Processes,
+    Apps,
+    Cpu,
+    Memory,
+    Disks,
+    Network,
+    Gpus,
+    Fans,
+    Services,
+}
+
+impl Reading {
+    pub const ALL: [Reading; 9] = [
+        Reading::Processes,
+        Reading::Apps,
+        Reading::Cpu,
+        Reading::Memory,
+        Reading::Disks,
+        Reading::Network,
+        Reading::Gpus,
+        Reading::Fans,
+        Reading::Services,
+    ];
+}
+
+/// What the gatherer itself costs
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct GathererStats {
+    pub pid: Option<Pid>,
+    /// In percent of one core, as of the last process list
+    pub cpu_usage: Option<f32>,
+    /// User and system time since it started; only known for this machine
+    pub cpu_time: Option<Duration>,
+    pub memory_bytes: Option<u64>,
+    /// How long each reading took the last time it was made
+    pub read_durations: HashMap<Reading, Duration>,
+    pub request_count: u64,
+    /// Since the stats were asked for the previous time
+    pub requests_per_second: f32,
+}
+
+/// Keeps track of the gatherer's own usage as the readings come in
+pub struct GathererStatsTracker {
+    stats: GathererStats,
+    previous_request_count: u64,
+    previous_time: Instant,
+}
+
+impl GathererStatsTracker {
+    pub fn new() -> Self {
+        Self {
+            stats: GathererStats::default(),
+            previous_request_count: 0,
+            previous_time: Instant::now(),
+        }
+    }
+
+    pub fn record(&mut self, reading: Reading, duration: Duration) {
+        self.stats.read_durations.insert(reading, duration);
+    }
+
+    /// Find the gatherer in the process list it sent, which works wherever it runs
+    pub fn update_process(&mut self, processes: &HashMap<Pid, Process>) {
+        let magpie = processes.values().find(|process| {
+            process
+                .exe
+                .rsplit('/')
+                .next()
+                .is_some_and(|name| name.starts_with(MAGPIE_EXECUTABLE))
+        });
+
+        self.stats.pid = magpie.map(|process| process.pid);
+        self.stats.cpu_usage = magpie.map(|process| process.usage_stats.cpu_usage);
+        self.stats.memory_bytes = magpie.map(|process| process.usage_stats.memory_usage);
+    }
+
+    pub fn stats(&mut self, request_count: u64, local: bool) -> GathererStats {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.previous_time).as_secs_f32();
+        self.previous_time = now;
+
+        self.stats.requests_per_second = if elapsed > 0. {
+            request_count.saturating_sub(self.previous_request_count) as f32 / elapsed
+        } else {
+            0.
+        };
+        self.previous_request_count = request_count;
+        self.stats.request_count = request_count;
+
+        self.stats.cpu_time = if local {
+            self.stats.pid.and_then(read_cpu_time)
+        } else {
+            None
+        };
+
+        self.stats.clone()
+    }
+}
+
+/// `utime` plus `stime` from `/proc/PID/stat`
+fn read_cpu_time(pid: Pid) -> Option<Duration> {
+    let stat = proc_stat::read(pid)?;
+    Some(Duration::from_millis(
+        (stat.user_ticks + stat.system_ticks) * 1000 / CLOCK_TICKS_PER_SECOND,
+    ))
+}
@@ -0,0 +1,117 @@
+/* magpie_client/gpu_supervisor.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+use gtk::glib::{g_critical, g_message, g_warning};
+
+use super::gatherer::Gatherer;
+use super::Gpu;
+
+/// How long GPUs have to be missing before the gatherer is restarted; a reading can be skipped
+/// now and then without anything being wrong
+const FIRST_RESTART_AFTER: Duration = Duration::from_secs(10);
+/// The wait between restarts doubles up to this
+const MAX_RESTART_DELAY: Duration = Duration::from_secs(300);
+/// A GPU can also have been unplugged, stop trying at some point
+const MAX_RESTARTS: u32 = 5;
+
+/// A GPU that stops being reported usually means that the part of the gatherer responsible for
+/// GPUs crashed. That part only runs as long as the gatherer does, so the gatherer is restarted,
+/// less and less often while the GPUs stay missing
+pub struct GpuSupervisor {
+    known: HashSet<String>,
+    missing: HashSet<String>,
+    next_restart: Option<Instant>,
+    restart_delay: Duration,
+    restarts: u32,
+}
+
+impl GpuSupervisor {
+    pub fn new(gpus: &HashMap<String, Gpu>) -> Self {
+        Self {
+            known: gpus.keys().cloned().collect(),
+            missing: HashSet::new(),
+            next_restart: None,
+            restart_delay: FIRST_RESTART_AFTER,
+            restarts: 0,
+        }
+    }
+
+    pub fn update(&mut self, gpus: &HashMap<String, Gpu>, magpie: &dyn Gatherer) {
+        for id in self.known.iter() {
+            if gpus.contains_key(id) {
+                if self.missing.remove(id) {
+                    g_message!(
+                        "MissionCenter::SysInfo",
+                        "Statistics for GPU {} are being reported again",
+                        id
+                    );
+                }
+            } else if self.missing.insert(id.clone()) {
+                g_critical!(
+                    "MissionCenter::SysInfo",
+                    "Gatherer stopped reporting statistics for GPU {}",
+                    id
+                );
+            }
+        }
+        self.known.extend(gpus.keys().cloned());
+
+        if self.missing.is_empty() {
+            self.next_restart = None;
+            self.restart_delay = FIRST_RESTART_AFTER;
+            self.restarts = 0;
+            return;
+        }
+
+        let now = Instant::now();
+        let next_restart = *self.next_restart.get_or_insert(now + self.restart_delay);
+        if now < next_restart || self.restarts >= MAX_RESTARTS {
+            return;
+        }
+
+        self.restarts += 1;
+        if !magpie.restart() {
+            // Not a gatherer started by us, nothing to be done about it from here
+            self.restarts = MAX_RESTARTS;
+            return;
+        }
+
+        self.restart_delay = (self.restart_delay * 2).min(MAX_RESTART_DELAY);
+        self.next_restart = Some(now + self.restart_delay);
+
+        if self.restarts < MAX_RESTARTS {
+            g_warning!(
+                "MissionCenter::SysInfo",
+                "Restarting the gatherer to get the statistics of {} GPU(s) back, next try in {}s",
+                self.missing.len(),
+                self.restart_delay.as_secs()
+            );
+        } else {
+            g_warning!(
+                "MissionCenter::SysInfo",
+                "Restarted the gatherer {} times without getting the GPU statistics back, giving up",
+                MAX_RESTARTS
+            );
+        }
+    }
+}
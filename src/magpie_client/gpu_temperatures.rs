@@ -0,0 +1,155 @@
+/* magpie_client/gpu_temperatures.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use super::{nvidia_smi, Gpu};
+
+// Used when the driver doesn't report a critical temperature; roughly where current GPUs
+// start throttling
+const DEFAULT_EDGE_CRITICAL_C: f32 = 95.;
+const DEFAULT_HOTSPOT_CRITICAL_C: f32 = 110.;
+const DEFAULT_MEMORY_CRITICAL_C: f32 = 100.;
+
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct TemperatureSensor {
+    pub celsius: f32,
+    /// Temperature at which the hardware starts protecting itself
+    pub critical_celsius: f32,
+}
+
+impl TemperatureSensor {
+    pub fn headroom(&self) -> f32 {
+        self.critical_celsius - self.celsius
+    }
+}
+
+/// The sensors a GPU exposes besides the edge temperature the gatherer reports
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct GpuTemperatures {
+    pub edge: Option<TemperatureSensor>,
+    /// Hottest point on the die, called junction temperature by AMD
+    pub hotspot: Option<TemperatureSensor>,
+    pub memory: Option<TemperatureSensor>,
+}
+
+impl GpuTemperatures {
+    /// The sensor closest to its limit; the edge temperature can look fine while the hotspot
+    /// or the memory are already throttling
+    pub fn limiting_sensor(&self) -> Option<TemperatureSensor> {
+        [self.edge, self.hotspot, self.memory]
+            .into_iter()
+            .flatten()
+            .min_by(|s1, s2| s1.headroom().total_cmp(&s2.headroom()))
+    }
+}
+
+/// Read the extra temperature sensors of each GPU, keyed by GPU id (its PCI address)
+pub fn read(gpus: &HashMap<String, Gpu>) -> HashMap<String, GpuTemperatures> {
+    let mut result = HashMap::new();
+    let mut nvidia_gpus = vec![];
+
+    for (id, gpu) in gpus {
+        let device_path = Path::new("/sys/bus/pci/devices").join(id);
+
+        let mut temperatures = read_hwmon(&device_path);
+        if temperatures.edge.is_none() {
+            temperatures.edge = gpu.temperature_c.map(|celsius| TemperatureSensor {
+                celsius,
+                critical_celsius: DEFAULT_EDGE_CRITICAL_C,
+            });
+        }
+
+        // The proprietary NVIDIA driver doesn't register hwmon sensors
+        if temperatures.hotspot.is_none()
+            && temperatures.memory.is_none()
+            && nvidia_smi::is_nvidia(id)
+        {
+            nvidia_gpus.push(id.as_str());
+        }
+
+        result.insert(id.clone(), temperatures);
+    }
+
+    if !nvidia_gpus.is_empty() {
+        let memory_temperatures = nvidia_smi::memory_temperatures();
+        for id in nvidia_gpus {
+            let Some(celsius) = memory_temperatures.get(&nvidia_smi::normalize_pci_id(id)) else {
+                continue;
+            };
+            if let Some(temperatures) = result.get_mut(id) {
+                temperatures.memory = Some(TemperatureSensor {
+                    celsius: *celsius,
+                    critical_celsius: DEFAULT_MEMORY_CRITICAL_C,
+                });
+            }
+        }
+    }
+
+    result
+}
+
+fn read_hwmon(device_path: &Path) -> GpuTemperatures {
+    let mut result = GpuTemperatures::default();
+
+    let Ok(hwmons) = std::fs::read_dir(device_path.join("hwmon")) else {
+        return result;
+    };
+
+    for hwmon in hwmons.filter_map(|h| h.ok()) {
+        let hwmon = hwmon.path();
+        // amdgpu registers up to three sensors, i915/xe use similar labels
+        for index in 1..=8 {
+            let Ok(label) = std::fs::read_to_string(hwmon.join(format!("temp{}_label", index)))
+            else {
+                continue;
+            };
+            let Some(celsius) = read_millidegrees(&hwmon, &format!("temp{}_input", index)) else {
+                continue;
+            };
+            let critical = read_millidegrees(&hwmon, &format!("temp{}_crit", index))
+                // Some drivers report a placeholder when the limit is unknown
+                .filter(|c| *c > 0. && *c < 200.);
+
+            let (slot, default_critical) = match label.trim() {
+                "edge" | "gpu" => (&mut result.edge, DEFAULT_EDGE_CRITICAL_C),
+                "junction" | "hotspot" => (&mut result.hotspot, DEFAULT_HOTSPOT_CRITICAL_C),
+                "mem" | "vram" => (&mut result.memory, DEFAULT_MEMORY_CRITICAL_C),
+                _ => continue,
+            };
+            *slot = Some(TemperatureSensor {
+                celsius,
+                critical_celsius: critical.unwrap_or(default_critical),
+            });
+        }
+    }
+
+    result
+}
+
+fn read_millidegrees(hwmon: &Path, file: &str) -> Option<f32> {
+    std::fs::read_to_string(hwmon.join(file))
+        .ok()?
+        .trim()
+        .parse::<i64>()
+        .ok()
+        .map(|v| v as f32 / 1000.)
+}
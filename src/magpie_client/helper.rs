@@ -0,0 +1,314 @@
+/* magpie_client/helper.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+//! `missioncenter-helper`, started as root through `pkexec` for the few things Mission Center
+//! can't do as the user. The first argument picks the operation, each one has a polkit action of
+//! its own in `io.missioncenter.MissionCenter.policy`:
+//!
+//!  * `signal NAME PID...`: send a signal to processes of other users
+//!  * `set-fan-speed`: takes `hwmonN pwmM[_enable] VALUE` lines on its input, answering each one
+//!    with `OK` or with what went wrong, until the input is closed
+//!  * `set-unit-properties [--runtime] UNIT NAME=VALUE...`: `systemctl set-property`, for
+//!    resource controls only
+//!  * `write-unit-drop-in UNIT FILE`: the drop-in is read from the input, systemd is reloaded
+//!  * `remove-unit-drop-in UNIT FILE`
+//!  * `dump-memory PID PREFIX`: `gcore`, the dump is handed to the user who asked for it
+//!
+//! Nothing is taken on trust, every argument is checked here again. Errors go to the standard
+//! error, the last line is what is shown to the user.
+
+use std::io::{BufRead, Write};
+use std::os::unix::fs::MetadataExt;
+
+extern "C" {
+    fn kill(pid: i32, sig: i32) -> i32;
+}
+
+const HWMON_DIRECTORY: &str = "/sys/class/hwmon";
+const SYSTEM_UNIT_DIRECTORY: &str = "/etc/systemd/system";
+
+/// The properties Mission Center changes, all of them resource controls
+const UNIT_PROPERTIES: &[&str] = &["CPUWeight", "IOWeight", "MemoryMax", "CPUQuota", "TasksMax"];
+
+fn main() {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let Some((operation, args)) = args.split_first() else {
+        fail("No operation given");
+    };
+
+    let result = match operation.as_str() {
+        "signal" => signal(args),
+        "set-fan-speed" => set_fan_speed(),
+        "set-unit-properties" => set_unit_properties(args),
+        "write-unit-drop-in" => write_unit_drop_in(args),
+        "remove-unit-drop-in" => remove_unit_drop_in(args),
+        "dump-memory" => dump_memory(args),
+        _ => Err(format!("Unknown operation: {}", operation)),
+    };
+
+    if let Err(e) = result {
+        fail(&e);
+    }
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("{}", message);
+    std::process::exit(1);
+}
+
+fn signal(args: &[String]) -> Result<(), String> {
+    let Some((name, pids)) = args.split_first() else {
+        return Err("No signal given".to_owned());
+    };
+
+    // The numbers of the architectures Mission Center runs on
+    let signal = match name.as_str() {
+        "HUP" => 1,
+        "INT" => 2,
+        "KILL" => 9,
+        "USR1" => 10,
+        "USR2" => 12,
+        "TERM" => 15,
+        "CONT" => 18,
+        "STOP" => 19,
+        _ => return Err(format!("Not a signal: {}", name)),
+    };
+
+    if pids.is_empty() {
+        return Err("No processes to signal".to_owned());
+    }
+    // Process groups and init are out of bounds
+    let pids = pids
+        .iter()
+        .map(|pid| match pid.parse::<i32>() {
+            Ok(pid) if pid > 1 => Ok(pid),
+            _ => Err(format!("Not a process id: {}", pid)),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let failed = pids
+        .iter()
+        .filter(|pid| unsafe { kill(**pid, signal) } != 0)
+        .map(|pid| pid.to_string())
+        .collect::<Vec<_>>();
+    if !failed.is_empty() {
+        return Err(format!("Failed to send {} to {}", name, failed.join(" ")));
+    }
+
+    Ok(())
+}
+
+fn set_fan_speed() -> Result<(), String> {
+    let mut stdout = std::io::stdout();
+    for line in std::io::stdin().lock().lines() {
+        let line = line.map_err(|e| e.to_string())?;
+        let answer = match write_fan_control(&line) {
+            Ok(()) => "OK".to_owned(),
+            Err(e) => e,
+        };
+        writeln!(stdout, "{}", answer).map_err(|e| e.to_string())?;
+        stdout.flush().map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+/// `hwmonN pwmM VALUE` sets the duty cycle, `hwmonN pwmM_enable VALUE` the control mode
+fn write_fan_control(line: &str) -> Result<(), String> {
+    let [hwmon, attribute, value] = line.split_ascii_whitespace().collect::<Vec<_>>()[..] else {
+        return Err(format!("Not a fan control: {}", line));
+    };
+
+    let is_indexed =
+        |name: &str, prefix: &str| name.strip_prefix(prefix).is_some_and(is_decimal_number);
+    if !is_indexed(hwmon, "hwmon") {
+        return Err(format!("Not a hwmon device: {}", hwmon));
+    }
+    let max_value = match attribute.strip_suffix("_enable") {
+        Some(channel) if is_indexed(channel, "pwm") => 5,
+        None if is_indexed(attribute, "pwm") => 255,
+        _ => return Err(format!("Not a fan control: {}", attribute)),
+    };
+    match value.parse::<u32>() {
+        Ok(parsed) if is_decimal_number(value) && parsed <= max_value => {}
+        _ => return Err(format!("Refusing to write {} to {}", value, attribute)),
+    }
+
+    let path = format!("{}/{}/{}", HWMON_DIRECTORY, hwmon, attribute);
+    std::fs::write(&path, value).map_err(|e| format!("Failed to write {}: {}", path, e))
+}
+
+fn set_unit_properties(args: &[String]) -> Result<(), String> {
+    let (runtime, args) = match args.split_first() {
+        Some((flag, args)) if flag == "--runtime" => (true, args),
+        _ => (false, args),
+    };
+    let Some((unit, properties)) = args.split_first() else {
+        return Err("No unit given".to_owned());
+    };
+    check_unit_name(unit)?;
+
+    if properties.is_empty() {
+        return Err("No properties to set".to_owned());
+    }
+    for property in properties {
+        let Some((name, value)) = property.split_once('=') else {
+            return Err(format!("Not a property: {}", property));
+        };
+        if !UNIT_PROPERTIES.contains(&name) {
+            return Err(format!("Refusing to set {}", name));
+        }
+        // A number, a percentage or `infinity`, nothing that needs quoting; empty resets
+        let valid_value = value.is_empty()
+            || value == "infinity"
+            || is_decimal_number(value.strip_suffix('%').unwrap_or(value));
+        if !valid_value {
+            return Err(format!("Refusing to set {} to {}", name, value));
+        }
+    }
+
+    let mut cmd = std::process::Command::new("systemctl");
+    cmd.arg("set-property");
+    if runtime {
+        cmd.arg("--runtime");
+    }
+    cmd.arg("--").arg(unit).args(properties);
+    run(cmd)
+}
+
+fn write_unit_drop_in(args: &[String]) -> Result<(), String> {
+    let path = drop_in_path(args)?;
+
+    let mut contents = String::new();
+    std::io::Read::read_to_string(&mut std::io::stdin(), &mut contents)
+        .map_err(|e| e.to_string())?;
+
+    if let Some(directory) = path.parent() {
+        std::fs::create_dir_all(directory)
+            .map_err(|e| format!("Failed to create {}: {}", directory.display(), e))?;
+    }
+    std::fs::write(&path, contents)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    daemon_reload()
+}
+
+fn remove_unit_drop_in(args: &[String]) -> Result<(), String> {
+    let path = drop_in_path(args)?;
+    match std::fs::remove_file(&path) {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(format!("Failed to remove {}: {}", path.display(), e)),
+    }
+
+    daemon_reload()
+}
+
+/// Where the drop-in in `args` goes, once the unit and the file name are checked
+fn drop_in_path(args: &[String]) -> Result<std::path::PathBuf, String> {
+    let [unit, file_name] = args else {
+        return Err("Expected a unit and a drop-in name".to_owned());
+    };
+    check_unit_name(unit)?;
+    if !is_unit_name(file_name) || !file_name.ends_with(".conf") {
+        return Err(format!("Not a drop-in name: {}", file_name));
+    }
+
+    Ok(format!("{}/{}.d/{}", SYSTEM_UNIT_DIRECTORY, unit, file_name).into())
+}
+
+fn daemon_reload() -> Result<(), String> {
+    let mut cmd = std::process::Command::new("systemctl");
+    cmd.arg("daemon-reload");
+    run(cmd)
+}
+
+fn dump_memory(args: &[String]) -> Result<(), String> {
+    let [pid, prefix] = args else {
+        return Err("Expected a process id and where to write the dump".to_owned());
+    };
+    if !pid.parse::<i32>().is_ok_and(|pid| pid > 1) {
+        return Err(format!("Not a process id: {}", pid));
+    }
+
+    // The dump goes to the user who asked for it, and only where they could write it themselves
+    let uid = std::env::var("PKEXEC_UID")
+        .ok()
+        .and_then(|uid| uid.parse::<u32>().ok())
+        .ok_or_else(|| "Not started through pkexec".to_owned())?;
+    let prefix = std::path::Path::new(prefix);
+    let directory = prefix
+        .parent()
+        .filter(|directory| prefix.is_absolute() && !directory.as_os_str().is_empty())
+        .ok_or_else(|| format!("Not a folder to write to: {}", prefix.display()))?;
+    let owner = std::fs::metadata(directory)
+        .map_err(|e| format!("Failed to look at {}: {}", directory.display(), e))?
+        .uid();
+    if owner != uid {
+        return Err(format!("{} does not belong to you", directory.display()));
+    }
+
+    let mut cmd = std::process::Command::new("gcore");
+    cmd.arg("-o").arg(prefix).arg(pid);
+    run(cmd)?;
+
+    // `gcore` runs as root, the dump would otherwise only be readable by root
+    let mut dump = prefix.as_os_str().to_owned();
+    dump.push(format!(".{}", pid));
+    std::os::unix::fs::chown(&dump, Some(uid), None)
+        .map_err(|e| format!("Failed to hand over {}: {}", dump.to_string_lossy(), e))
+}
+
+fn run(mut cmd: std::process::Command) -> Result<(), String> {
+    let output = cmd.output().map_err(|e| e.to_string())?;
+    if output.status.success() {
+        return Ok(());
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    match stderr.lines().rev().find(|line| !line.trim().is_empty()) {
+        Some(line) => Err(line.trim().to_owned()),
+        None => Err(format!(
+            "{:?} exited with {}",
+            cmd.get_program(),
+            output.status
+        )),
+    }
+}
+
+fn check_unit_name(unit: &str) -> Result<(), String> {
+    if is_unit_name(unit) {
+        Ok(())
+    } else {
+        Err(format!("Not a unit name: {}", unit))
+    }
+}
+
+fn is_unit_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || ":-_.@\\".contains(c))
+}
+
+fn is_decimal_number(s: &str) -> bool {
+    !s.is_empty() && s.chars().all(|c| c.is_ascii_digit())
+}
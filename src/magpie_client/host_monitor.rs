@@ -0,0 +1,160 @@
+/* magpie_client/host_monitor.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use gtk::glib::g_debug;
+
+use super::{Client, Connection, Cpu, Disk, Gatherer, Memory, Readings, RemoteHost};
+
+/// How long to wait before trying to reach a host that couldn't be connected to again
+const RECONNECT_DELAY: Duration = Duration::from_secs(30);
+
+/// The handful of numbers shown for a host in the hosts overview
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct HostSummary {
+    pub reachable: bool,
+    pub cpu_usage_percent: f32,
+    pub memory_used_percent: f32,
+    /// Of the busiest disk
+    pub disk_busy_percent: f32,
+    /// Sent and received, over all interfaces
+    pub network_bytes_ps: f32,
+}
+
+impl HostSummary {
+    pub fn from_readings(readings: &Readings) -> Self {
+        Self::new(
+            &readings.cpu,
+            &readings.mem_info,
+            &readings.disks_info,
+            &readings.network_connections,
+        )
+    }
+
+    fn new(cpu: &Cpu, memory: &Memory, disks: &[Disk], connections: &[Connection]) -> Self {
+        let memory_used_percent = if memory.mem_total > 0 {
+            let used = memory.mem_total.saturating_sub(memory.mem_available);
+            used as f32 * 100. / memory.mem_total as f32
+        } else {
+            0.
+        };
+
+        Self {
+            reachable: true,
+            cpu_usage_percent: cpu.total_usage_percent,
+            memory_used_percent,
+            disk_busy_percent: disks
+                .iter()
+                .map(|disk| disk.busy_percent)
+                .fold(0., f32::max),
+            network_bytes_ps: connections
+                .iter()
+                .map(|connection| connection.tx_rate_bytes_ps + connection.rx_rate_bytes_ps)
+                .sum(),
+        }
+    }
+}
+
+/// Keeps a separate, lightweight, connection to a machine other than the one Mission Center is
+/// showing, and samples it in the background
+pub struct HostMonitor {
+    latest: Arc<Mutex<Option<HostSummary>>>,
+    running: Arc<AtomicBool>,
+}
+
+impl Drop for HostMonitor {
+    fn drop(&mut self) {
+        // Not joined, connecting to an unreachable host can take a while; the thread notices on
+        // its own and takes the connection down with it
+        self.running.store(false, Ordering::Release);
+    }
+}
+
+impl HostMonitor {
+    /// Monitors `host`, or this machine when `None`
+    pub fn new(host: Option<RemoteHost>, interval: Duration) -> Self {
+        let latest = Arc::new(Mutex::new(None));
+        let running = Arc::new(AtomicBool::new(true));
+
+        std::thread::spawn({
+            let latest = latest.clone();
+            let running = running.clone();
+            move || Self::monitor(host, interval, latest, running)
+        });
+
+        Self { latest, running }
+    }
+
+    /// The most recent sample, `None` until the host was first reached or given up on
+    pub fn latest(&self) -> Option<HostSummary> {
+        *self.latest.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn monitor(
+        host: Option<RemoteHost>,
+        interval: Duration,
+        latest: Arc<Mutex<Option<HostSummary>>>,
+        running: Arc<AtomicBool>,
+    ) {
+        let set_latest = |summary: HostSummary| {
+            *latest.lock().unwrap_or_else(|e| e.into_inner()) = Some(summary);
+        };
+
+        while running.load(Ordering::Acquire) {
+            let client = Client::new(host.clone());
+            if !client.try_start() {
+                g_debug!(
+                    "MissionCenter::HostMonitor",
+                    "Failed to reach {}, retrying in {:?}",
+                    host.as_ref()
+                        .map_or("this machine", |host| host.name.as_str()),
+                    RECONNECT_DELAY
+                );
+                set_latest(HostSummary::default());
+                sleep_while_running(&running, RECONNECT_DELAY);
+                continue;
+            }
+
+            while running.load(Ordering::Acquire) {
+                set_latest(HostSummary::new(
+                    &client.cpu(),
+                    &client.memory(),
+                    &client.disks_info(),
+                    &client.network_connections(),
+                ));
+                sleep_while_running(&running, interval);
+            }
+        }
+    }
+}
+
+fn sleep_while_running(running: &AtomicBool, duration: Duration) {
+    const STEP: Duration = Duration::from_millis(100);
+
+    let mut remaining = duration;
+    while !remaining.is_zero() && running.load(Ordering::Acquire) {
+        let step = remaining.min(STEP);
+        std::thread::sleep(step);
+        remaining -= step;
+    }
+}
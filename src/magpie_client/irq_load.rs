@@ -0,0 +1,92 @@
+/* magpie_client/irq_load.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+/// Time spent by a core, in clock ticks, as counted in `/proc/stat`
+#[derive(Debug, Default, Copy, Clone)]
+struct CoreTicks {
+    interrupts: u64,
+    total: u64,
+}
+
+/// Remembers how long each core spent handling hardware and software interrupts, to tell how
+/// busy they kept it since the last refresh
+pub struct IrqLoad {
+    previous: Vec<CoreTicks>,
+}
+
+impl IrqLoad {
+    pub fn new() -> Self {
+        Self { previous: vec![] }
+    }
+
+    /// The percentage of time each core spent in interrupt handlers, in core order; empty until
+    /// there are two readings to compare
+    pub fn update(&mut self) -> Vec<f32> {
+        let current = read();
+
+        let result = if current.len() == self.previous.len() {
+            current
+                .iter()
+                .zip(self.previous.iter())
+                .map(|(current, previous)| {
+                    let total = current.total.saturating_sub(previous.total);
+                    if total == 0 {
+                        return 0.;
+                    }
+
+                    current.interrupts.saturating_sub(previous.interrupts) as f32 / total as f32
+                        * 100.
+                })
+                .collect()
+        } else {
+            vec![]
+        };
+        self.previous = current;
+
+        result
+    }
+}
+
+fn read() -> Vec<CoreTicks> {
+    let Ok(content) = std::fs::read_to_string("/proc/stat") else {
+        return vec![];
+    };
+
+    content
+        .lines()
+        // The first line adds up all cores, those after it are `cpu0`, `cpu1` and so on
+        .filter(|line| line.starts_with("cpu") && !line.starts_with("cpu "))
+        .map(|line| {
+            // user nice system idle iowait irq softirq steal guest guest_nice; guest time is
+            // already counted in user time
+            let ticks = line
+                .split_ascii_whitespace()
+                .skip(1)
+                .take(8)
+                .map(|field| field.parse::<u64>().unwrap_or(0))
+                .collect::<Vec<_>>();
+
+            CoreTicks {
+                interrupts: ticks.get(5).copied().unwrap_or(0) + ticks.get(6).copied().unwrap_or(0),
+                total: ticks.iter().sum(),
+            }
+        })
+        .collect()
+}
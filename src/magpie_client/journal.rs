@@ -0,0 +1,99 @@
+/* magpie_client/journal.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::HashMap;
+
+use crate::is_flatpak;
+
+pub type JournalEntry = HashMap<String, String>;
+
+/// Run `journalctl` on the host with the given filters and return the entries, oldest first
+pub fn query(args: &[String]) -> Result<Vec<JournalEntry>, String> {
+    let mut cmd = if is_flatpak() {
+        let mut cmd = std::process::Command::new("/usr/bin/flatpak-spawn");
+        cmd.arg("--host").arg("journalctl");
+        cmd
+    } else {
+        std::process::Command::new("journalctl")
+    };
+    cmd.arg("--no-pager")
+        .arg("--quiet")
+        .arg("--output=export")
+        .args(args);
+
+    let output = cmd.output().map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_owned());
+    }
+
+    Ok(parse_export(&output.stdout))
+}
+
+/// Parse the journal export format: entries are separated by empty lines and every field is
+/// either `KEY=value` or, for values that are not plain text, the key followed by a
+/// little-endian 64-bit length and the raw data
+fn parse_export(mut data: &[u8]) -> Vec<JournalEntry> {
+    let mut result = vec![];
+    let mut entry = JournalEntry::new();
+
+    while !data.is_empty() {
+        let line_end = data.iter().position(|b| *b == b'\n').unwrap_or(data.len());
+        let line = &data[..line_end];
+
+        if line.is_empty() {
+            if !entry.is_empty() {
+                result.push(std::mem::take(&mut entry));
+            }
+            data = &data[(line_end + 1).min(data.len())..];
+            continue;
+        }
+
+        let (key, value, consumed) = match line.iter().position(|b| *b == b'=') {
+            Some(separator) => (&line[..separator], &line[separator + 1..], line_end + 1),
+            None => {
+                let binary = &data[(line_end + 1).min(data.len())..];
+                if binary.len() < 8 {
+                    break;
+                }
+                let mut size = [0_u8; 8];
+                size.copy_from_slice(&binary[..8]);
+                let size = u64::from_le_bytes(size) as usize;
+                if binary.len() < 8 + size {
+                    break;
+                }
+
+                (line, &binary[8..8 + size], line_end + 1 + 8 + size + 1)
+            }
+        };
+
+        entry.insert(
+            String::from_utf8_lossy(key).into_owned(),
+            String::from_utf8_lossy(value).into_owned(),
+        );
+
+        data = &data[consumed.min(data.len())..];
+    }
+
+    if !entry.is_empty() {
+        result.push(entry);
+    }
+
+    result
+}
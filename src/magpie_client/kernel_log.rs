@@ -0,0 +1,69 @@
+/* magpie_client/kernel_log.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use super::journal;
+
+/// Syslog priorities, as used by the kernel and journald
+pub const PRIORITY_EMERGENCY: u8 = 0;
+pub const PRIORITY_ERROR: u8 = 3;
+pub const PRIORITY_WARNING: u8 = 4;
+pub const PRIORITY_DEBUG: u8 = 7;
+
+const MAX_ENTRIES: u32 = 2000;
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct KernelLogEntry {
+    /// Microseconds since the Unix epoch
+    pub timestamp: u64,
+    pub priority: u8,
+    pub message: String,
+}
+
+/// Read the kernel messages of the current boot from the journal, keeping only those at least
+/// as severe as `max_priority`
+pub fn read(max_priority: u8) -> Result<Vec<KernelLogEntry>, String> {
+    let entries = journal::query(&[
+        "--dmesg".to_owned(),
+        "--output-fields=PRIORITY,MESSAGE".to_owned(),
+        format!(
+            "--priority={}..{}",
+            PRIORITY_EMERGENCY,
+            max_priority.min(PRIORITY_DEBUG)
+        ),
+        format!("--lines={}", MAX_ENTRIES),
+    ])?;
+
+    Ok(entries
+        .into_iter()
+        .filter_map(|entry| {
+            Some(KernelLogEntry {
+                timestamp: entry
+                    .get("__REALTIME_TIMESTAMP")
+                    .and_then(|ts| ts.parse().ok())
+                    .unwrap_or(0),
+                priority: entry
+                    .get("PRIORITY")
+                    .and_then(|p| p.parse().ok())
+                    .unwrap_or(PRIORITY_DEBUG),
+                message: entry.get("MESSAGE")?.trim_end().to_owned(),
+            })
+        })
+        .collect())
+}
@@ -0,0 +1,103 @@
+/* magpie_client/memory_dump.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::path::{Path, PathBuf};
+
+use gtk::gio;
+use gtk::glib::{self, prelude::*};
+
+use super::{privileged, Pid};
+use crate::is_flatpak;
+
+/// Where the document portal mounts the files and folders picked from a Flatpak
+const DOCUMENT_PORTAL_DIR: &str = "/run/user";
+
+/// Where `gcore` will write the dump of `pid` when given `prefix`
+pub fn memory_dump_path(prefix: &Path, pid: Pid) -> PathBuf {
+    let mut path = prefix.as_os_str().to_owned();
+    path.push(format!(".{}", pid));
+    PathBuf::from(path)
+}
+
+/// Write the memory of a running process to `<prefix>.<pid>` with gdb's `gcore`, asking for
+/// authorization through polkit; the process is stopped until the dump is complete.
+///
+/// Blocks until `gcore` exits, the dump is handed over to the user once it is written.
+pub fn capture_memory_dump(pid: Pid, prefix: &Path) -> Result<PathBuf, String> {
+    let host_prefix = if is_flatpak() {
+        host_path(prefix)?
+    } else {
+        prefix.to_owned()
+    };
+
+    privileged::dump_memory(pid, &host_prefix)?;
+
+    Ok(memory_dump_path(prefix, pid))
+}
+
+/// Folders picked from a Flatpak are handed over by the document portal, under a path only the
+/// sandbox knows; `gcore` runs on the host, where the folder has its real path
+fn host_path(path: &Path) -> Result<PathBuf, String> {
+    // /run/user/<uid>/doc/<document id>/<picked folder>/...
+    let Ok(relative) = path.strip_prefix(DOCUMENT_PORTAL_DIR) else {
+        return Ok(path.to_owned());
+    };
+    let components = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect::<Vec<_>>();
+    if components.len() < 4 || components[1] != "doc" {
+        return Ok(path.to_owned());
+    }
+    let document_id = components[2].clone();
+
+    let connection = gio::bus_get_sync(gio::BusType::Session, gio::Cancellable::NONE)
+        .map_err(|e| e.to_string())?;
+    let reply = connection
+        .call_sync(
+            Some("org.freedesktop.portal.Documents"),
+            "/org/freedesktop/portal/documents",
+            "org.freedesktop.portal.Documents",
+            "GetHostPaths",
+            Some(&(vec![document_id.clone()],).to_variant()),
+            Some(glib::VariantTy::new("(a{say})").unwrap()),
+            gio::DBusCallFlags::NONE,
+            -1,
+            gio::Cancellable::NONE,
+        )
+        .map_err(|e| e.to_string())?;
+
+    let Some(host_paths) = reply
+        .child_value(0)
+        .get::<std::collections::HashMap<String, Vec<u8>>>()
+    else {
+        return Err("Unexpected reply from the document portal".to_owned());
+    };
+    let Some(host_path) = host_paths.get(&document_id) else {
+        return Err("The document portal does not know the folder".to_owned());
+    };
+
+    // The path comes NUL terminated
+    let host_path = host_path.strip_suffix(&[0]).unwrap_or(host_path);
+    let mut result = PathBuf::from(String::from_utf8_lossy(host_path).to_string());
+    result.extend(&components[4..]);
+
+    Ok(result)
+}
@@ -0,0 +1,371 @@
+/* magpie_client/metrics_stream.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+//! Hands the readings over to other monitoring tools, as they are gathered.
+//!
+//! Every reading is turned into a sample of the same metrics the history keeps, and the samples
+//! are sent out in batches, either as JSON lines or as an OTLP/JSON metrics request. Batches are
+//! appended to a file, or POSTed to an `http://` or `https://` URL, from a thread of their own so
+//! a slow endpoint never holds up the readings.
+
+use std::fmt::Write as _;
+use std::fs::OpenOptions;
+use std::io::Write as _;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::{Duration, Instant};
+
+use gtk::glib::{self, g_warning};
+
+use super::Readings;
+use crate::config::VERSION;
+use crate::export::{json_string, json_value, Unit, Value, FORMAT_NAME, SCHEMA_VERSION};
+use crate::history::METRICS;
+
+const SEND_TIMEOUT_SECONDS: u32 = 10;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum StreamFormat {
+    /// One export formatted object per sample and line
+    JsonLines,
+    /// An OpenTelemetry `ExportMetricsServiceRequest`, in its JSON encoding, per batch
+    Otlp,
+}
+
+impl StreamFormat {
+    /// The format stored in the settings under `id`
+    pub fn from_id(id: &str) -> Option<Self> {
+        match id {
+            "json-lines" => Some(StreamFormat::JsonLines),
+            "otlp" => Some(StreamFormat::Otlp),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreamConfig {
+    pub format: StreamFormat,
+    /// A file to append to, or a URL to POST to
+    pub target: String,
+    /// How often a batch is sent out
+    pub interval: Duration,
+}
+
+impl StreamConfig {
+    fn is_url(&self) -> bool {
+        self.target.starts_with("http://") || self.target.starts_with("https://")
+    }
+}
+
+struct Sample {
+    timestamp_ms: i64,
+    values: Vec<f32>,
+}
+
+/// A batch ready to go out, and where to
+struct Batch {
+    target: String,
+    is_url: bool,
+    body: String,
+}
+
+pub struct MetricsStream {
+    config: Option<StreamConfig>,
+    /// Reported along with the samples, so several machines can stream to the same place
+    host: String,
+    samples: Vec<Sample>,
+    batch_start: Instant,
+    sender: Option<Sender<Batch>>,
+}
+
+impl MetricsStream {
+    pub fn new(host: String) -> Self {
+        Self {
+            config: None,
+            host,
+            samples: vec![],
+            batch_start: Instant::now(),
+            sender: None,
+        }
+    }
+
+    /// Start, stop or change the stream; `None` turns it off
+    pub fn configure(&mut self, config: Option<StreamConfig>) {
+        if config == self.config {
+            return;
+        }
+
+        self.samples.clear();
+        self.batch_start = Instant::now();
+        self.config = config;
+
+        // Dropping the sender stops the thread once it is done with what it was handed
+        if self.config.is_none() {
+            self.sender = None;
+        } else if self.sender.is_none() {
+            let (sender, receiver) = mpsc::channel();
+            std::thread::spawn(move || send_batches(receiver));
+            self.sender = Some(sender);
+        }
+    }
+
+    pub fn push(&mut self, readings: &Readings) {
+        let Some(config) = &self.config else {
+            return;
+        };
+
+        self.samples.push(Sample {
+            timestamp_ms: glib::real_time() / 1000,
+            values: METRICS
+                .iter()
+                .map(|metric| metric.current_value(readings))
+                .collect(),
+        });
+
+        if self.batch_start.elapsed() < config.interval {
+            return;
+        }
+
+        let body = match config.format {
+            StreamFormat::JsonLines => self.json_lines(),
+            StreamFormat::Otlp => self.otlp(),
+        };
+        let batch = Batch {
+            target: config.target.clone(),
+            is_url: config.is_url(),
+            body,
+        };
+        self.samples.clear();
+        self.batch_start = Instant::now();
+
+        if let Some(sender) = &self.sender {
+            let _ = sender.send(batch);
+        }
+    }
+
+    fn json_lines(&self) -> String {
+        let mut result = String::new();
+
+        for sample in &self.samples {
+            let _ = write!(
+                result,
+                "{{\"format\":{},\"schema_version\":{},\"kind\":\"metrics\",\"host\":{},\"timestamp\":{},\"values\":{{",
+                json_string(FORMAT_NAME),
+                SCHEMA_VERSION,
+                json_string(&self.host),
+                sample.timestamp_ms
+            );
+            for (i, (metric, value)) in METRICS.iter().zip(sample.values.iter()).enumerate() {
+                if i > 0 {
+                    result.push(',');
+                }
+                let _ = write!(
+                    result,
+                    "{}:{}",
+                    json_string(&metric.field().id),
+                    json_value(&Value::from(*value))
+                );
+            }
+            result.push_str("}}\n");
+        }
+
+        result
+    }
+
+    // https://opentelemetry.io/docs/specs/otlp/#json-protobuf-encoding; every metric is a gauge,
+    // with a data point for each sample
+    fn otlp(&self) -> String {
+        let mut result = String::new();
+
+        let _ = write!(
+            result,
+            "{{\"resourceMetrics\":[{{\"resource\":{{\"attributes\":[{},{}]}},\"scopeMetrics\":[{{\"scope\":{{\"name\":\"missioncenter\",\"version\":{}}},\"metrics\":[",
+            otlp_attribute("service.name", "missioncenter"),
+            otlp_attribute("host.name", &self.host),
+            json_string(VERSION)
+        );
+        for (i, metric) in METRICS.iter().enumerate() {
+            if i > 0 {
+                result.push(',');
+            }
+
+            let field = metric.field();
+            let _ = write!(
+                result,
+                "{{\"name\":{},\"unit\":{},\"gauge\":{{\"dataPoints\":[",
+                json_string(&format!("missioncenter.{}", field.id)),
+                json_string(otlp_unit(field.unit))
+            );
+
+            let mut first = true;
+            for sample in &self.samples {
+                let value = sample.values[i];
+                // JSON has no representation for these
+                if !value.is_finite() {
+                    continue;
+                }
+                if !first {
+                    result.push(',');
+                }
+                first = false;
+
+                // 64 bit integers are strings in the JSON encoding
+                let _ = write!(
+                    result,
+                    "{{\"timeUnixNano\":\"{}\",\"asDouble\":{}}}",
+                    sample.timestamp_ms.max(0) as u64 * 1_000_000,
+                    json_value(&Value::from(value))
+                );
+            }
+            result.push_str("]}}");
+        }
+        result.push_str("]}]}]}\n");
+
+        result
+    }
+}
+
+fn otlp_attribute(key: &str, value: &str) -> String {
+    format!(
+        "{{\"key\":{},\"value\":{{\"stringValue\":{}}}}}",
+        json_string(key),
+        json_string(value)
+    )
+}
+
+// Units are written in UCUM, as OpenTelemetry expects
+fn otlp_unit(unit: Unit) -> &'static str {
+    match unit {
+        Unit::None => "1",
+        Unit::Percent => "%",
+        Unit::Bytes => "By",
+        Unit::BytesPerSecond => "By/s",
+        Unit::Hertz => "Hz",
+        Unit::Watts => "W",
+        Unit::Volts => "V",
+        Unit::Celsius => "Cel",
+        Unit::Rpm => "{rotation}/min",
+        Unit::Seconds => "s",
+        Unit::UnixTimestampMs => "ms",
+    }
+}
+
+fn send_batches(receiver: Receiver<Batch>) {
+    // The same failure usually repeats for every batch, only say something when it changes
+    let mut last_error = None;
+
+    while let Ok(batch) = receiver.recv() {
+        let result = if batch.is_url {
+            post(&batch.target, &batch.body)
+        } else {
+            append(&batch.target, &batch.body)
+        };
+
+        match result {
+            Ok(()) => last_error = None,
+            Err(e) => {
+                if last_error.as_ref() != Some(&e) {
+                    g_warning!(
+                        "MissionCenter::MetricsStream",
+                        "Failed to send metrics to {}: {}",
+                        batch.target,
+                        e
+                    );
+                }
+                last_error = Some(e);
+            }
+        }
+    }
+}
+
+fn append(path: &str, body: &str) -> Result<(), String> {
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(body.as_bytes()))
+        .map_err(|e| e.to_string())
+}
+
+fn post(url: &str, body: &str) -> Result<(), String> {
+    let mut child = std::process::Command::new("curl")
+        .args([
+            "--silent",
+            "--show-error",
+            "--fail",
+            "--max-time",
+            &SEND_TIMEOUT_SECONDS.to_string(),
+            "--header",
+            "Content-Type: application/json",
+            "--data-binary",
+            "@-",
+            url,
+        ])
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .map_err(|e| e.to_string())?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(body.as_bytes())
+            .map_err(|e| e.to_string())?;
+    }
+
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_owned());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_lines() {
+        let mut stream = MetricsStream::new("build-box".to_owned());
+        stream.samples.push(Sample {
+            timestamp_ms: 1735689600000,
+            values: vec![12.5, 1048576., 4194304., 3., 0., 512., 100.5, 20., f32::NAN],
+        });
+        stream.samples.push(Sample {
+            timestamp_ms: 1735689601000,
+            values: vec![0.25; METRICS.len()],
+        });
+
+        assert_eq!(
+            stream.json_lines(),
+            "{\"format\":\"missioncenter-export\",\"schema_version\":1,\"kind\":\"metrics\",\
+             \"host\":\"build-box\",\"timestamp\":1735689600000,\"values\":{\
+             \"cpu.usage\":12.5,\"memory.used\":1048576,\"memory.total\":4194304,\
+             \"disk.busy\":3,\"disk.read\":0,\"disk.write\":512,\
+             \"network.receive\":100.5,\"network.send\":20,\"gpu.usage\":null}}\n\
+             {\"format\":\"missioncenter-export\",\"schema_version\":1,\"kind\":\"metrics\",\
+             \"host\":\"build-box\",\"timestamp\":1735689601000,\"values\":{\
+             \"cpu.usage\":0.25,\"memory.used\":0.25,\"memory.total\":0.25,\
+             \"disk.busy\":0.25,\"disk.read\":0.25,\"disk.write\":0.25,\
+             \"network.receive\":0.25,\"network.send\":0.25,\"gpu.usage\":0.25}}\n"
+        );
+    }
+}
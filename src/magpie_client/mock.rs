@@ -0,0 +1,271 @@
+/* magpie_client/mock.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+//! Made up readings, built with the `mock-gatherer` feature, to work on the UI where magpie
+//! doesn't run.
+//!
+//! Every value follows a slow wave of its own, so the graphs move and the lists reorder;
+//! processes that are ended go away, everything else that acts on the system does nothing.
+
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::num::NonZeroU32;
+use std::time::Instant;
+
+use magpie_types::disks::DiskKind;
+use magpie_types::network::ConnectionKind;
+use magpie_types::processes::processes_response::process_map::NetworkStatsError;
+use magpie_types::processes::ProcessUsageStats;
+
+use super::{
+    App, Connection, Cpu, Disk, ErrorEjectFailed, Fan, Gatherer, Gpu, Memory, MemoryDevice,
+    Process, Service, SmartData,
+};
+
+const CORE_COUNT: usize = 8;
+
+const GIB: f32 = 1024. * 1024. * 1024.;
+const MEMORY_TOTAL: f32 = 16. * GIB;
+const SWAP_TOTAL: f32 = 4. * GIB;
+
+/// PID, name, parent and the usual CPU usage, in percent of one core
+const PROCESSES: &[(u32, &str, u32, f32)] = &[
+    (1, "systemd", 0, 0.1),
+    (1200, "gnome-shell", 1, 8.),
+    (1300, "missioncenter", 1, 3.),
+    (2000, "firefox", 1, 25.),
+    (2010, "Web Content", 2000, 40.),
+    (2011, "Web Content", 2000, 12.),
+    (2500, "cargo", 1, 90.),
+    (2510, "rustc", 2500, 380.),
+];
+
+/// App id, name and the PID of its main process
+const APPS: &[(&str, &str, u32)] = &[
+    ("org.gnome.Shell", "GNOME Shell", 1200),
+    ("io.missioncenter.MissionCenter", "Mission Center", 1300),
+    ("org.mozilla.firefox", "Firefox", 2000),
+];
+
+pub struct MockGatherer {
+    start: Instant,
+    scale_cpu_usage_to_core_count: Cell<bool>,
+    ended: RefCell<HashSet<u32>>,
+}
+
+impl MockGatherer {
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            scale_cpu_usage_to_core_count: Cell::new(false),
+            ended: RefCell::new(HashSet::new()),
+        }
+    }
+
+    /// From 0 to 1, going around once every `period` seconds
+    fn wave(&self, period: f32, phase: f32) -> f32 {
+        let t = self.start.elapsed().as_secs_f32();
+        ((t / period + phase) * std::f32::consts::TAU).sin() * 0.5 + 0.5
+    }
+
+    fn end(&self, pids: Vec<u32>) {
+        self.ended.borrow_mut().extend(pids);
+    }
+}
+
+impl Gatherer for MockGatherer {
+    fn set_scale_cpu_usage_to_core_count(&self, v: bool) {
+        self.scale_cpu_usage_to_core_count.set(v);
+    }
+
+    fn cpu(&self) -> Cpu {
+        let core_usage_percent = (0..CORE_COUNT)
+            .map(|core| 5. + 90. * self.wave(20. + core as f32 * 3., core as f32 / 8.))
+            .collect::<Vec<f32>>();
+
+        Cpu {
+            name: Some("Mock CPU".to_owned()),
+            total_usage_percent: core_usage_percent.iter().sum::<f32>() / CORE_COUNT as f32,
+            core_usage_percent,
+            uptime_seconds: (3600 + self.start.elapsed().as_secs()) as _,
+            ..Default::default()
+        }
+    }
+
+    fn memory(&self) -> Memory {
+        let available = MEMORY_TOTAL * (0.3 + 0.4 * self.wave(60., 0.));
+
+        Memory {
+            mem_total: MEMORY_TOTAL as _,
+            mem_available: available as _,
+            mem_free: (available * 0.5) as _,
+            swap_total: SWAP_TOTAL as _,
+            swap_free: (SWAP_TOTAL * (0.8 + 0.2 * self.wave(90., 0.5))) as _,
+            ..Default::default()
+        }
+    }
+
+    fn memory_devices(&self) -> Vec<MemoryDevice> {
+        vec![]
+    }
+
+    fn disks_info(&self) -> Vec<Disk> {
+        let reading = self.wave(30., 0.25).powi(3);
+        let writing = self.wave(45., 0.).powi(4);
+
+        vec![Disk {
+            id: "nvme0n1".to_owned(),
+            model: Some("Mock Drive".to_owned()),
+            kind: Some(DiskKind::NvMe as i32),
+            capacity_bytes: 1_000_000_000_000,
+            busy_percent: 100. * reading.max(writing),
+            rx_speed_bytes_ps: (500_000_000. * reading) as _,
+            tx_speed_bytes_ps: (200_000_000. * writing) as _,
+            ..Default::default()
+        }]
+    }
+
+    fn eject_disk(&self, _disk_id: String) -> Result<(), ErrorEjectFailed> {
+        Ok(())
+    }
+
+    fn smart_data(&self, _disk_id: String) -> Option<SmartData> {
+        None
+    }
+
+    fn fans_info(&self) -> Vec<Fan> {
+        vec![]
+    }
+
+    fn network_connections(&self) -> Vec<Connection> {
+        vec![Connection {
+            id: "eth0".to_owned(),
+            kind: ConnectionKind::Other as i32,
+            rx_rate_bytes_ps: (12_500_000. * self.wave(15., 0.).powi(2)) as _,
+            tx_rate_bytes_ps: (2_500_000. * self.wave(25., 0.3).powi(2)) as _,
+            ..Default::default()
+        }]
+    }
+
+    fn gpus(&self) -> HashMap<String, Gpu> {
+        HashMap::new()
+    }
+
+    fn processes(&self) -> (HashMap<u32, Process>, Option<NetworkStatsError>) {
+        let ended = self.ended.borrow();
+        let factor = if self.scale_cpu_usage_to_core_count.get() {
+            1.
+        } else {
+            CORE_COUNT as f32
+        };
+
+        let mut processes = HashMap::new();
+        for (i, (pid, name, parent, cpu_usage)) in PROCESSES.iter().enumerate() {
+            if ended.contains(pid) || ended.contains(parent) {
+                continue;
+            }
+
+            let process = Process {
+                pid: *pid,
+                name: name.to_string(),
+                cmd: vec![name.to_string()],
+                children: PROCESSES
+                    .iter()
+                    .filter(|(child, _, child_parent, _)| {
+                        child_parent == pid && !ended.contains(child)
+                    })
+                    .map(|(child, ..)| *child)
+                    .collect(),
+                usage_stats: ProcessUsageStats {
+                    cpu_usage: cpu_usage * (0.5 + self.wave(10. + i as f32, i as f32 / 5.))
+                        / factor,
+                    memory_usage: (GIB * 0.05 * (i + 1) as f32 * (0.8 + 0.4 * self.wave(40., 0.)))
+                        as _,
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            processes.insert(*pid, process);
+        }
+
+        (processes, None)
+    }
+
+    fn apps(&self) -> HashMap<String, App> {
+        let ended = self.ended.borrow();
+
+        APPS.iter()
+            .filter(|(_, _, pid)| !ended.contains(pid))
+            .map(|(id, name, pid)| {
+                (
+                    id.to_string(),
+                    App {
+                        id: id.to_string(),
+                        name: name.to_string(),
+                        pids: vec![*pid],
+                        ..Default::default()
+                    },
+                )
+            })
+            .collect()
+    }
+
+    fn services(&self) -> HashMap<String, Service> {
+        HashMap::new()
+    }
+
+    fn service_logs(&self, _service_id: String, _pid: Option<NonZeroU32>) -> String {
+        String::new()
+    }
+
+    fn terminate_processes(&self, pids: Vec<u32>) {
+        self.end(pids);
+    }
+
+    fn kill_processes(&self, pids: Vec<u32>) {
+        self.end(pids);
+    }
+
+    fn interrupt_processes(&self, pids: Vec<u32>) {
+        self.end(pids);
+    }
+
+    fn signal_user_one_processes(&self, _pids: Vec<u32>) {}
+
+    fn signal_user_two_processes(&self, _pids: Vec<u32>) {}
+
+    fn hangup_processes(&self, pids: Vec<u32>) {
+        self.end(pids);
+    }
+
+    fn continue_processes(&self, _pids: Vec<u32>) {}
+
+    fn suspend_processes(&self, _pids: Vec<u32>) {}
+
+    fn start_service(&self, _service_id: String) {}
+
+    fn stop_service(&self, _service_id: String) {}
+
+    fn restart_service(&self, _service_id: String) {}
+
+    fn enable_service(&self, _service_id: String) {}
+
+    fn disable_service(&self, _service_id: String) {}
+}
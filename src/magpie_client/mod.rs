@@ -18,7 +18,7 @@
  * SPDX-License-Identifier: GPL-3.0-or-later
  */
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::num::NonZeroU32;
 use std::sync::atomic;
 use std::sync::atomic::{AtomicBool, AtomicU64};
@@ -27,7 +27,7 @@ use std::sync::mpsc::{Receiver, Sender};
 use std::sync::{Arc, OnceLock};
 use std::time::Duration;
 
-use gtk::glib::{g_critical, g_debug, g_warning, idle_add_once};
+use gtk::glib::{self, g_critical, g_debug, g_warning, idle_add_once};
 
 use crate::app;
 use crate::application::{BASE_INTERVAL, INTERVAL_STEP};
@@ -36,6 +36,7 @@ pub use client::{
     App, Client, Connection, Cpu, Disk, DiskKind, ErrorEjectFailed, Fan, Gpu, Memory, MemoryDevice,
     Process, Service, SmartData,
 };
+use gatherer::Gatherer;
 use magpie_types::processes::processes_response::process_map::NetworkStatsError;
 
 macro_rules! cmd_flatpak_host {
@@ -52,7 +53,93 @@ macro_rules! cmd_flatpak_host {
     }};
 }
 
+mod app_usage;
+mod autostart;
+mod background_jobs;
+mod bluetooth;
+mod boot_analysis;
+mod capabilities;
+mod cgroup;
 mod client;
+mod collectors;
+mod coredumps;
+mod cpu_smoothing;
+mod cpu_split;
+mod disk_encryption;
+mod display_server;
+mod fan_control;
+mod foreground_boost;
+mod game_mode;
+mod gatherer;
+mod gatherer_stats;
+mod gpu_supervisor;
+mod gpu_temperatures;
+mod host_monitor;
+mod irq_load;
+mod journal;
+mod kernel_log;
+mod memory_dump;
+mod metrics_stream;
+#[cfg(feature = "mock-gatherer")]
+mod mock;
+mod nvidia_smi;
+mod privileged;
+mod proc_stat;
+mod process_details;
+mod process_owners;
+mod psi;
+mod recording;
+mod remote_host;
+mod sandbox;
+mod sandboxed_services;
+mod service_logs;
+mod service_override;
+mod service_processes;
+mod sockets;
+mod stop_processes;
+mod unit_files;
+mod ups;
+mod users;
+
+pub use app_usage::{AppUsage, AppUsageHistory};
+pub use autostart::{
+    entries as autostart_entries, set_enabled as set_autostart_enabled, AutostartEntry,
+};
+pub use background_jobs::{BackgroundJob, JobKind};
+pub use bluetooth::{BluetoothAdapter, BluetoothDevice};
+pub use boot_analysis::{BootAnalysis, BootStages, UnitBootTime};
+pub use capabilities::{Availability, Capability, DataSource};
+pub use cgroup::{weights as cgroup_weights, CgroupWeights};
+pub use collectors::{Collector, CollectorIntervals};
+pub use coredumps::Coredump;
+pub use cpu_split::{split_usage as split_cpu_usage, CpuSplit, SplitUsage, Zombie};
+pub use disk_encryption::{CipherBenchmark, CryptoDriver, DiskEncryption, EncryptedVolume};
+pub use display_server::{DisplayServerUsage, UsageShare};
+pub use fan_control::{pwm_channel, FanCurve, MIN_MANUAL_PWM_PERCENT};
+pub use foreground_boost::{is_supported as foreground_boost_supported, BoostedApp};
+pub use game_mode::GameModeClient;
+pub use gatherer_stats::{GathererStats, Reading};
+pub use gpu_temperatures::{GpuTemperatures, TemperatureSensor};
+pub use host_monitor::{HostMonitor, HostSummary};
+pub use kernel_log::{KernelLogEntry, PRIORITY_DEBUG, PRIORITY_ERROR, PRIORITY_WARNING};
+pub use memory_dump::{capture_memory_dump, memory_dump_path};
+pub use metrics_stream::{StreamConfig, StreamFormat};
+pub use process_details::ProcessDetails;
+pub use process_owners::{current_uid, is_system_uid, ProcessOwner};
+pub use psi::{Pressure, PressureAverages};
+pub use recording::Recording;
+pub use remote_host::{HostTransport, RemoteHost};
+pub use sandbox::{permissions as sandbox_permissions, Sandbox, SandboxKind, SandboxPermissions};
+pub use service_logs::ServiceLogEntry;
+pub use service_override::{ServiceOverride, RESTART_POLICIES};
+pub use service_processes::ServiceProcess;
+pub use sockets::TcpSocket;
+pub use stop_processes::StopOutcome;
+pub use unit_files::{set_start_type as set_service_start_type, StartType};
+pub use ups::Ups;
+pub use users::{
+    lock_session, set_slice_limits, terminate_session, Session, SliceLimits, User, UserSlice,
+};
 
 pub type Pid = u32;
 
@@ -84,6 +171,7 @@ fn flatpak_app_path() -> &'static str {
 enum Message {
     ContinueReading,
     UpdateCoreCountAffectsPercentages(bool),
+    SetSmoothCpuUsage(bool),
     TerminateProcesses(Vec<Pid>),
     KillProcesses(Vec<Pid>),
     InterruptProcesses(Vec<Pid>),
@@ -92,7 +180,27 @@ enum Message {
     HangupProcesses(Vec<Pid>),
     ContinueProcesses(Vec<Pid>),
     SuspendProcesses(Vec<Pid>),
-    GetServiceLogs(String, Option<NonZeroU32>),
+    StopProcesses(
+        Vec<Pid>,
+        bool,
+        Box<dyn FnOnce(Vec<(Pid, StopOutcome)>) + Send>,
+    ),
+    GetServiceLogs(String, Option<NonZeroU32>, u8, u32),
+    GetServiceProcesses(String),
+    GetServiceOverride(String),
+    SetServiceOverride(
+        String,
+        ServiceOverride,
+        Box<dyn FnOnce(Result<(), String>) + Send>,
+    ),
+    GetKernelLog(u8),
+    GetCoredumps,
+    GetBootAnalysis,
+    GetCapabilities,
+    GetGathererStats,
+    GetAppUsage,
+    GetTcpSockets(Vec<Pid>),
+    GetProcessDetails(Pid),
     StartService(String),
     StopService(String),
     RestartService(String),
@@ -100,30 +208,76 @@ enum Message {
     DisableService(String),
     EjectDisk(String),
     SmartData(String),
+    SetFanSpeed(u32, u32, Option<f32>),
+    SetFanCurve(u32, u32, Option<FanCurve>),
+    SetCgroupWeights(CgroupWeights, Option<u32>, Option<u32>),
+    SetForegroundBoost(bool),
+    SetMetricsStream(Option<StreamConfig>),
+    SetCollectorIntervals(CollectorIntervals),
+    SetDisabledCollectors(HashSet<Collector>),
 }
 
 enum Response {
-    String(String),
+    ServiceLogs(Result<Vec<ServiceLogEntry>, String>),
+    ServiceProcesses(Result<Vec<ServiceProcess>, String>),
+    ServiceOverride(Result<ServiceOverride, String>),
     EjectResult(Result<(), ErrorEjectFailed>),
     SmartData(Option<SmartData>),
+    KernelLog(Result<Vec<KernelLogEntry>, String>),
+    Coredumps(Result<Vec<Coredump>, String>),
+    BootAnalysis(Result<BootAnalysis, String>),
+    Capabilities(Result<Vec<Capability>, String>),
+    GathererStats(GathererStats),
+    AppUsage(AppUsageHistory),
+    TcpSockets(Result<Vec<TcpSocket>, String>),
+    ProcessDetails(Result<ProcessDetails, String>),
 }
 
 #[derive(Debug)]
 pub struct Readings {
     pub cpu: Cpu,
     pub mem_info: Memory,
+    pub mem_pressure: Option<Pressure>,
+    pub cpu_pressure: Option<Pressure>,
+    pub io_pressure: Option<Pressure>,
     pub mem_devices: Vec<MemoryDevice>,
     pub disks_info: Vec<Disk>,
     pub network_connections: Vec<Connection>,
     pub gpus: HashMap<String, Gpu>,
     pub fans: Vec<Fan>,
+    pub ups: Vec<Ups>,
+    pub bluetooth: Vec<BluetoothAdapter>,
+    pub users: Vec<User>,
+    pub display_server: DisplayServerUsage,
+    pub background_jobs: Vec<BackgroundJob>,
+    pub disk_encryption: DiskEncryption,
+    pub gpu_temperatures: HashMap<String, GpuTemperatures>,
+    pub foreground_boost: Option<BoostedApp>,
+    /// Games Feral GameMode is active for
+    pub game_mode: Vec<GameModeClient>,
 
     pub running_apps: HashMap<String, App>,
+    /// The apps that were installed as a Flatpak or a Snap, by app ID; only known for this
+    /// machine
+    pub app_sandboxes: HashMap<String, Sandbox>,
     pub running_processes: HashMap<u32, Process>,
+    /// User and system time, and time waiting for I/O, of the running processes; only known for
+    /// this machine
+    pub process_cpu_split: HashMap<u32, CpuSplit>,
+    /// Who each running process runs as; only known for this machine
+    pub process_owners: HashMap<u32, ProcessOwner>,
+    pub zombies: Vec<Zombie>,
+    /// Percentage of time each core spent handling interrupts; only known for this machine
+    pub cpu_irq_percent: Vec<f32>,
 
     pub network_stats_error: Option<NetworkStatsError>,
 
     pub services: HashMap<String, Service>,
+    /// The state of the unit file of each service; only known for this machine
+    pub service_start_types: HashMap<String, StartType>,
+    /// The services that come from a Snap or a Flatpak, the others are plain systemd units;
+    /// only known for this machine
+    pub service_sources: HashMap<String, SandboxKind>,
 }
 
 impl Readings {
@@ -131,23 +285,54 @@ impl Readings {
         Self {
             cpu: Default::default(),
             mem_info: Memory::default(),
+            mem_pressure: None,
+            cpu_pressure: None,
+            io_pressure: None,
             mem_devices: vec![],
             disks_info: vec![],
             network_connections: vec![],
             gpus: HashMap::new(),
             fans: vec![],
+            ups: vec![],
+            bluetooth: vec![],
+            users: vec![],
+            display_server: DisplayServerUsage::default(),
+            background_jobs: vec![],
+            disk_encryption: DiskEncryption::default(),
+            gpu_temperatures: HashMap::new(),
+            foreground_boost: None,
+            game_mode: vec![],
 
             running_apps: HashMap::new(),
+            app_sandboxes: HashMap::new(),
             running_processes: HashMap::new(),
+            process_cpu_split: HashMap::new(),
+            process_owners: HashMap::new(),
+            zombies: vec![],
+            cpu_irq_percent: vec![],
             network_stats_error: None,
 
             services: HashMap::new(),
+            service_start_types: HashMap::new(),
+            service_sources: HashMap::new(),
         }
     }
 }
 
+/// Everything not asked from the gatherer is read from this machine, which makes no sense when
+/// looking at a remote one
+fn local_only<T: Default>(local: bool, read: impl FnOnce() -> T) -> T {
+    if local {
+        read()
+    } else {
+        T::default()
+    }
+}
+
 pub struct MagpieClient {
     speed: Arc<AtomicU64>,
+    // In milliseconds, 0 while the window is shown
+    background_interval: Arc<AtomicU64>,
 
     refresh_thread: Option<std::thread::JoinHandle<()>>,
     refresh_thread_running: Arc<AtomicBool>,
@@ -176,6 +361,7 @@ impl Default for MagpieClient {
 
         Self {
             speed: Arc::new(0.into()),
+            background_interval: Arc::new(0.into()),
 
             refresh_thread: None,
             refresh_thread_running: Arc::new(true.into()),
@@ -187,21 +373,31 @@ impl Default for MagpieClient {
 }
 
 impl MagpieClient {
-    pub fn new() -> Self {
+    /// Reads from the gatherer on `remote` when set, otherwise from one started on this machine;
+    /// `recording` saves the readings to a file, or plays them back instead; `idle_priority` keeps
+    /// a gatherer started on this machine out of the way of everything else running on it
+    pub fn new(
+        remote: Option<RemoteHost>,
+        recording: Option<Recording>,
+        idle_priority: bool,
+    ) -> Self {
         let speed = Arc::new(AtomicU64::new(
             (BASE_INTERVAL / INTERVAL_STEP).round() as u64
         ));
+        let background_interval = Arc::new(AtomicU64::new(0));
         let refresh_thread_running = Arc::new(AtomicBool::new(true));
 
         let s = speed.clone();
+        let b = background_interval.clone();
         let run = refresh_thread_running.clone();
 
         let (tx, rx) = mpsc::channel::<Message>();
         let (resp_tx, resp_rx) = mpsc::channel::<Response>();
         Self {
             speed,
+            background_interval,
             refresh_thread: Some(std::thread::spawn(move || {
-                Self::gather_and_proxy(rx, resp_tx, run, s);
+                Self::gather_and_proxy(rx, resp_tx, run, s, b, remote, recording, idle_priority);
             })),
             refresh_thread_running,
             sender: tx,
@@ -213,6 +409,15 @@ impl MagpieClient {
         self.speed.store(speed, atomic::Ordering::Release);
     }
 
+    /// While the window is hidden, only what the history, the metrics stream and the
+    /// notifications need is gathered, every `interval`; `None` goes back to gathering everything
+    /// at the update speed
+    pub fn set_background_interval(&self, interval: Option<Duration>) {
+        let interval_ms = interval.map_or(0, |interval| interval.as_millis().max(1) as u64);
+        self.background_interval
+            .store(interval_ms, atomic::Ordering::Release);
+    }
+
     pub fn set_core_count_affects_percentages(&self, show: bool) {
         match self
             .sender
@@ -228,6 +433,122 @@ impl MagpieClient {
         }
     }
 
+    /// Average the CPU usage of processes over the last few seconds instead of showing each
+    /// reading as is
+    pub fn set_smooth_cpu_usage(&self, smooth: bool) {
+        match self.sender.send(Message::SetSmoothCpuUsage(smooth)) {
+            Err(e) => {
+                g_critical!(
+                    "MissionCenter::SysInfo",
+                    "Error sending SetSmoothCpuUsage to gatherer: {e}"
+                );
+            }
+            _ => {}
+        }
+    }
+
+    /// Send batches of readings to another monitoring tool, or stop doing so with `None`
+    pub fn set_metrics_stream(&self, config: Option<StreamConfig>) {
+        match self.sender.send(Message::SetMetricsStream(config)) {
+            Err(e) => {
+                g_critical!(
+                    "MissionCenter::SysInfo",
+                    "Error sending SetMetricsStream to gatherer: {e}"
+                );
+            }
+            _ => {}
+        }
+    }
+
+    /// Read the collectors in `intervals` on their own schedule, every other one on every update
+    pub fn set_collector_intervals(&self, intervals: CollectorIntervals) {
+        match self.sender.send(Message::SetCollectorIntervals(intervals)) {
+            Err(e) => {
+                g_critical!(
+                    "MissionCenter::SysInfo",
+                    "Error sending SetCollectorIntervals to gatherer: {e}"
+                );
+            }
+            _ => {}
+        }
+    }
+
+    /// Stop reading the collectors in `disabled`, which leaves their readings empty, to save the
+    /// work of gathering them
+    pub fn set_disabled_collectors(&self, disabled: HashSet<Collector>) {
+        match self.sender.send(Message::SetDisabledCollectors(disabled)) {
+            Err(e) => {
+                g_critical!(
+                    "MissionCenter::SysInfo",
+                    "Error sending SetDisabledCollectors to gatherer: {e}"
+                );
+            }
+            _ => {}
+        }
+    }
+
+    pub fn set_foreground_boost(&self, enabled: bool) {
+        match self.sender.send(Message::SetForegroundBoost(enabled)) {
+            Err(e) => {
+                g_critical!(
+                    "MissionCenter::SysInfo",
+                    "Error sending SetForegroundBoost to gatherer: {e}"
+                );
+            }
+            _ => {}
+        }
+    }
+
+    pub fn set_fan_speed(&self, hwmon_index: u32, fan_index: u32, pwm_percent: Option<f32>) {
+        match self
+            .sender
+            .send(Message::SetFanSpeed(hwmon_index, fan_index, pwm_percent))
+        {
+            Err(e) => {
+                g_critical!(
+                    "MissionCenter::SysInfo",
+                    "Error sending SetFanSpeed to gatherer: {e}"
+                );
+            }
+            _ => {}
+        }
+    }
+
+    pub fn set_fan_curve(&self, hwmon_index: u32, fan_index: u32, curve: Option<FanCurve>) {
+        match self
+            .sender
+            .send(Message::SetFanCurve(hwmon_index, fan_index, curve))
+        {
+            Err(e) => {
+                g_critical!(
+                    "MissionCenter::SysInfo",
+                    "Error sending SetFanCurve to gatherer: {e}"
+                );
+            }
+            _ => {}
+        }
+    }
+
+    pub fn set_cgroup_weights(
+        &self,
+        weights: CgroupWeights,
+        cpu_weight: Option<u32>,
+        io_weight: Option<u32>,
+    ) {
+        match self
+            .sender
+            .send(Message::SetCgroupWeights(weights, cpu_weight, io_weight))
+        {
+            Err(e) => {
+                g_critical!(
+                    "MissionCenter::SysInfo",
+                    "Error sending SetCgroupWeights to gatherer: {e}"
+                );
+            }
+            _ => {}
+        }
+    }
+
     pub fn continue_reading(&self) {
         match self.sender.send(Message::ContinueReading) {
             Err(e) => {
@@ -275,139 +596,538 @@ impl MagpieClient {
         }
     }
 
+    /// Terminate, or with `force` kill, `pids` and give them a moment to exit; `done` is handed
+    /// what became of each of them on the main thread, without blocking it in the meantime
+    pub fn stop_processes(
+        &self,
+        pids: Vec<Pid>,
+        force: bool,
+        done: impl FnOnce(Vec<(Pid, StopOutcome)>) + Send + 'static,
+    ) {
+        match self
+            .sender
+            .send(Message::StopProcesses(pids, force, Box::new(done)))
+        {
+            Err(e) => {
+                g_critical!(
+                    "MissionCenter::SysInfo",
+                    "Error sending StopProcesses to gatherer: {e}",
+                );
+            }
+            _ => {}
+        }
+    }
+
     #[inline(always)]
     pub fn interrupt_process(&self, pid: u32) {
         self.interrupt_processes(vec![pid]);
     }
 
-    pub fn interrupt_processes(&self, pids: Vec<u32>) {
-        match self.sender.send(Message::InterruptProcesses(pids)) {
+    pub fn interrupt_processes(&self, pids: Vec<u32>) {
+        match self.sender.send(Message::InterruptProcesses(pids)) {
+            Err(e) => {
+                g_critical!(
+                    "MissionCenter::SysInfo",
+                    "Error sending InterruptProcesses to gatherer: {e}",
+                );
+            }
+            _ => {}
+        }
+    }
+
+    #[inline(always)]
+    pub fn user_signal_one_process(&self, pid: u32) {
+        self.user_signal_one_processes(vec![pid]);
+    }
+
+    pub fn user_signal_one_processes(&self, pids: Vec<u32>) {
+        match self.sender.send(Message::User1Processes(pids)) {
+            Err(e) => {
+                g_critical!(
+                    "MissionCenter::SysInfo",
+                    "Error sending User1Processes to gatherer: {e}",
+                );
+            }
+            _ => {}
+        }
+    }
+
+    #[inline(always)]
+    pub fn user_signal_two_process(&self, pid: u32) {
+        self.user_signal_two_processes(vec![pid]);
+    }
+
+    pub fn user_signal_two_processes(&self, pids: Vec<u32>) {
+        match self.sender.send(Message::User2Processes(pids)) {
+            Err(e) => {
+                g_critical!(
+                    "MissionCenter::SysInfo",
+                    "Error sending User2Processes to gatherer: {e}",
+                );
+            }
+            _ => {}
+        }
+    }
+
+    #[inline(always)]
+    pub fn hangup_process(&self, pid: u32) {
+        self.hangup_processes(vec![pid]);
+    }
+
+    pub fn hangup_processes(&self, pids: Vec<u32>) {
+        match self.sender.send(Message::HangupProcesses(pids)) {
+            Err(e) => {
+                g_critical!(
+                    "MissionCenter::SysInfo",
+                    "Error sending HangupProcesses to gatherer: {e}",
+                );
+            }
+            _ => {}
+        }
+    }
+
+    #[inline(always)]
+    pub fn continue_process(&self, pid: u32) {
+        self.continue_processes(vec![pid]);
+    }
+
+    pub fn continue_processes(&self, pids: Vec<u32>) {
+        match self.sender.send(Message::ContinueProcesses(pids)) {
+            Err(e) => {
+                g_critical!(
+                    "MissionCenter::SysInfo",
+                    "Error sending ContinueProcesses to gatherer: {e}",
+                );
+            }
+            _ => {}
+        }
+    }
+
+    #[inline(always)]
+    pub fn suspend_process(&self, pid: u32) {
+        self.suspend_processes(vec![pid]);
+    }
+
+    pub fn suspend_processes(&self, pids: Vec<u32>) {
+        match self.sender.send(Message::SuspendProcesses(pids)) {
+            Err(e) => {
+                g_critical!(
+                    "MissionCenter::SysInfo",
+                    "Error sending SuspendProcesses to gatherer: {e}",
+                );
+            }
+            _ => {}
+        }
+    }
+
+    /// The last `lines` log messages of a service at least as severe as `max_priority`, oldest
+    /// first; a remote host only has the messages of the main process, without their severity
+    pub fn service_logs(
+        &self,
+        service_id: String,
+        pid: Option<NonZeroU32>,
+        max_priority: u8,
+        lines: u32,
+    ) -> Result<Vec<ServiceLogEntry>, String> {
+        let sid = service_id.clone();
+        match self.sender.send(Message::GetServiceLogs(
+            service_id,
+            pid,
+            max_priority,
+            lines,
+        )) {
+            Err(e) => {
+                g_critical!(
+                    "MissionCenter::SysInfo",
+                    "Error sending GetServiceLogs({sid}) to gatherer: {e}",
+                );
+
+                return Err(e.to_string());
+            }
+            _ => {}
+        }
+
+        match self.receiver.recv() {
+            Ok(Response::ServiceLogs(entries)) => entries,
+            Err(e) => {
+                g_critical!(
+                    "MissionCenter::SysInfo",
+                    "Error receiving GetServiceLogs response: {}",
+                    e
+                );
+                Err(e.to_string())
+            }
+            _ => {
+                g_critical!(
+                    "MissionCenter::SysInfo",
+                    "Error receiving GetServiceLogs response. Wrong type"
+                );
+
+                Err("Wrong response type".to_owned())
+            }
+        }
+    }
+
+    pub fn service_processes(&self, service_id: String) -> Result<Vec<ServiceProcess>, String> {
+        let sid = service_id.clone();
+        match self.sender.send(Message::GetServiceProcesses(service_id)) {
+            Err(e) => {
+                g_critical!(
+                    "MissionCenter::SysInfo",
+                    "Error sending GetServiceProcesses({sid}) to gatherer: {e}",
+                );
+
+                return Err(e.to_string());
+            }
+            _ => {}
+        }
+
+        match self.receiver.recv() {
+            Ok(Response::ServiceProcesses(processes)) => processes,
+            Err(e) => {
+                g_critical!(
+                    "MissionCenter::SysInfo",
+                    "Error receiving GetServiceProcesses response: {}",
+                    e
+                );
+                Err(e.to_string())
+            }
+            _ => {
+                g_critical!(
+                    "MissionCenter::SysInfo",
+                    "Error receiving GetServiceProcesses response. Wrong type"
+                );
+
+                Err("Wrong response type".to_owned())
+            }
+        }
+    }
+
+    pub fn service_override(&self, service_id: String) -> Result<ServiceOverride, String> {
+        let sid = service_id.clone();
+        match self.sender.send(Message::GetServiceOverride(service_id)) {
+            Err(e) => {
+                g_critical!(
+                    "MissionCenter::SysInfo",
+                    "Error sending GetServiceOverride({sid}) to gatherer: {e}",
+                );
+
+                return Err(e.to_string());
+            }
+            _ => {}
+        }
+
+        match self.receiver.recv() {
+            Ok(Response::ServiceOverride(service_override)) => service_override,
+            Err(e) => {
+                g_critical!(
+                    "MissionCenter::SysInfo",
+                    "Error receiving GetServiceOverride response: {}",
+                    e
+                );
+                Err(e.to_string())
+            }
+            _ => {
+                g_critical!(
+                    "MissionCenter::SysInfo",
+                    "Error receiving GetServiceOverride response. Wrong type"
+                );
+
+                Err("Wrong response type".to_owned())
+            }
+        }
+    }
+
+    /// Write the override of `service_id`, which asks for authorization; `done` is handed the
+    /// outcome on the main thread, without blocking it in the meantime
+    pub fn set_service_override(
+        &self,
+        service_id: String,
+        service_override: ServiceOverride,
+        done: impl FnOnce(Result<(), String>) + Send + 'static,
+    ) {
+        let sid = service_id.clone();
+        match self.sender.send(Message::SetServiceOverride(
+            service_id,
+            service_override,
+            Box::new(done),
+        )) {
+            Err(e) => {
+                g_critical!(
+                    "MissionCenter::SysInfo",
+                    "Error sending SetServiceOverride({sid}) to gatherer: {e}",
+                );
+            }
+            _ => {}
+        }
+    }
+
+    pub fn kernel_log(&self, max_priority: u8) -> Result<Vec<KernelLogEntry>, String> {
+        match self.sender.send(Message::GetKernelLog(max_priority)) {
+            Err(e) => {
+                g_critical!(
+                    "MissionCenter::SysInfo",
+                    "Error sending GetKernelLog({max_priority}) to gatherer: {e}",
+                );
+
+                return Err(e.to_string());
+            }
+            _ => {}
+        }
+
+        match self.receiver.recv() {
+            Ok(Response::KernelLog(entries)) => entries,
+            Err(e) => {
+                g_critical!(
+                    "MissionCenter::SysInfo",
+                    "Error receiving GetKernelLog response: {}",
+                    e
+                );
+                Err(e.to_string())
+            }
+            _ => {
+                g_critical!(
+                    "MissionCenter::SysInfo",
+                    "Error receiving GetKernelLog response. Wrong type"
+                );
+
+                Err("Wrong response type".to_owned())
+            }
+        }
+    }
+
+    pub fn coredumps(&self) -> Result<Vec<Coredump>, String> {
+        match self.sender.send(Message::GetCoredumps) {
+            Err(e) => {
+                g_critical!(
+                    "MissionCenter::SysInfo",
+                    "Error sending GetCoredumps to gatherer: {e}",
+                );
+
+                return Err(e.to_string());
+            }
+            _ => {}
+        }
+
+        match self.receiver.recv() {
+            Ok(Response::Coredumps(coredumps)) => coredumps,
+            Err(e) => {
+                g_critical!(
+                    "MissionCenter::SysInfo",
+                    "Error receiving GetCoredumps response: {}",
+                    e
+                );
+                Err(e.to_string())
+            }
+            _ => {
+                g_critical!(
+                    "MissionCenter::SysInfo",
+                    "Error receiving GetCoredumps response. Wrong type"
+                );
+
+                Err("Wrong response type".to_owned())
+            }
+        }
+    }
+
+    pub fn boot_analysis(&self) -> Result<BootAnalysis, String> {
+        match self.sender.send(Message::GetBootAnalysis) {
+            Err(e) => {
+                g_critical!(
+                    "MissionCenter::SysInfo",
+                    "Error sending GetBootAnalysis to gatherer: {e}",
+                );
+
+                return Err(e.to_string());
+            }
+            _ => {}
+        }
+
+        match self.receiver.recv() {
+            Ok(Response::BootAnalysis(boot_analysis)) => boot_analysis,
+            Err(e) => {
+                g_critical!(
+                    "MissionCenter::SysInfo",
+                    "Error receiving GetBootAnalysis response: {}",
+                    e
+                );
+                Err(e.to_string())
+            }
+            _ => {
+                g_critical!(
+                    "MissionCenter::SysInfo",
+                    "Error receiving GetBootAnalysis response. Wrong type"
+                );
+
+                Err("Wrong response type".to_owned())
+            }
+        }
+    }
+
+    pub fn capabilities(&self) -> Result<Vec<Capability>, String> {
+        match self.sender.send(Message::GetCapabilities) {
+            Err(e) => {
+                g_critical!(
+                    "MissionCenter::SysInfo",
+                    "Error sending GetCapabilities to gatherer: {e}",
+                );
+
+                return Err(e.to_string());
+            }
+            _ => {}
+        }
+
+        match self.receiver.recv() {
+            Ok(Response::Capabilities(capabilities)) => capabilities,
+            Err(e) => {
+                g_critical!(
+                    "MissionCenter::SysInfo",
+                    "Error receiving GetCapabilities response: {}",
+                    e
+                );
+                Err(e.to_string())
+            }
+            _ => {
+                g_critical!(
+                    "MissionCenter::SysInfo",
+                    "Error receiving GetCapabilities response. Wrong type"
+                );
+
+                Err("Wrong response type".to_owned())
+            }
+        }
+    }
+
+    pub fn gatherer_stats(&self) -> Option<GathererStats> {
+        match self.sender.send(Message::GetGathererStats) {
             Err(e) => {
                 g_critical!(
                     "MissionCenter::SysInfo",
-                    "Error sending InterruptProcesses to gatherer: {e}",
+                    "Error sending GetGathererStats to gatherer: {e}",
                 );
+
+                return None;
             }
             _ => {}
         }
-    }
-
-    #[inline(always)]
-    pub fn user_signal_one_process(&self, pid: u32) {
-        self.user_signal_one_processes(vec![pid]);
-    }
 
-    pub fn user_signal_one_processes(&self, pids: Vec<u32>) {
-        match self.sender.send(Message::User1Processes(pids)) {
+        match self.receiver.recv() {
+            Ok(Response::GathererStats(stats)) => Some(stats),
             Err(e) => {
                 g_critical!(
                     "MissionCenter::SysInfo",
-                    "Error sending User1Processes to gatherer: {e}",
+                    "Error receiving GetGathererStats response: {}",
+                    e
                 );
+                None
+            }
+            _ => {
+                g_critical!(
+                    "MissionCenter::SysInfo",
+                    "Error receiving GetGathererStats response. Wrong type"
+                );
+                None
             }
-            _ => {}
         }
     }
 
-    #[inline(always)]
-    pub fn user_signal_two_process(&self, pid: u32) {
-        self.user_signal_two_processes(vec![pid]);
-    }
-
-    pub fn user_signal_two_processes(&self, pids: Vec<u32>) {
-        match self.sender.send(Message::User2Processes(pids)) {
+    pub fn app_usage(&self) -> Option<AppUsageHistory> {
+        match self.sender.send(Message::GetAppUsage) {
             Err(e) => {
                 g_critical!(
                     "MissionCenter::SysInfo",
-                    "Error sending User2Processes to gatherer: {e}",
+                    "Error sending GetAppUsage to gatherer: {e}",
                 );
+
+                return None;
             }
             _ => {}
         }
-    }
-
-    #[inline(always)]
-    pub fn hangup_process(&self, pid: u32) {
-        self.hangup_processes(vec![pid]);
-    }
 
-    pub fn hangup_processes(&self, pids: Vec<u32>) {
-        match self.sender.send(Message::HangupProcesses(pids)) {
+        match self.receiver.recv() {
+            Ok(Response::AppUsage(history)) => Some(history),
             Err(e) => {
                 g_critical!(
                     "MissionCenter::SysInfo",
-                    "Error sending HangupProcesses to gatherer: {e}",
+                    "Error receiving GetAppUsage response: {}",
+                    e
                 );
+                None
+            }
+            _ => {
+                g_critical!(
+                    "MissionCenter::SysInfo",
+                    "Error receiving GetAppUsage response. Wrong type"
+                );
+                None
             }
-            _ => {}
         }
     }
 
-    #[inline(always)]
-    pub fn continue_process(&self, pid: u32) {
-        self.continue_processes(vec![pid]);
-    }
-
-    pub fn continue_processes(&self, pids: Vec<u32>) {
-        match self.sender.send(Message::ContinueProcesses(pids)) {
+    pub fn tcp_sockets(&self, pids: Vec<Pid>) -> Result<Vec<TcpSocket>, String> {
+        match self.sender.send(Message::GetTcpSockets(pids)) {
             Err(e) => {
                 g_critical!(
                     "MissionCenter::SysInfo",
-                    "Error sending ContinueProcesses to gatherer: {e}",
+                    "Error sending GetTcpSockets to gatherer: {e}",
                 );
+
+                return Err(e.to_string());
             }
             _ => {}
         }
-    }
 
-    #[inline(always)]
-    pub fn suspend_process(&self, pid: u32) {
-        self.suspend_processes(vec![pid]);
-    }
-
-    pub fn suspend_processes(&self, pids: Vec<u32>) {
-        match self.sender.send(Message::SuspendProcesses(pids)) {
+        match self.receiver.recv() {
+            Ok(Response::TcpSockets(sockets)) => sockets,
             Err(e) => {
                 g_critical!(
                     "MissionCenter::SysInfo",
-                    "Error sending SuspendProcesses to gatherer: {e}",
+                    "Error receiving GetTcpSockets response: {}",
+                    e
                 );
+                Err(e.to_string())
+            }
+            _ => {
+                g_critical!(
+                    "MissionCenter::SysInfo",
+                    "Error receiving GetTcpSockets response. Wrong type"
+                );
+
+                Err("Wrong response type".to_owned())
             }
-            _ => {}
         }
     }
 
-    pub fn service_logs(&self, service_id: String, pid: Option<NonZeroU32>) -> String {
-        let sid = service_id.clone();
-        match self.sender.send(Message::GetServiceLogs(service_id, pid)) {
+    pub fn process_details(&self, pid: Pid) -> Result<ProcessDetails, String> {
+        match self.sender.send(Message::GetProcessDetails(pid)) {
             Err(e) => {
                 g_critical!(
                     "MissionCenter::SysInfo",
-                    "Error sending GetServiceLogs({sid}) to gatherer: {e}",
+                    "Error sending GetProcessDetails to gatherer: {e}",
                 );
 
-                return String::new();
+                return Err(e.to_string());
             }
             _ => {}
         }
 
         match self.receiver.recv() {
-            Ok(Response::String(logs)) => logs,
+            Ok(Response::ProcessDetails(details)) => details,
             Err(e) => {
                 g_critical!(
                     "MissionCenter::SysInfo",
-                    "Error receiving GetServiceLogs response: {}",
+                    "Error receiving GetProcessDetails response: {}",
                     e
                 );
-                String::new()
+                Err(e.to_string())
             }
             _ => {
                 g_critical!(
                     "MissionCenter::SysInfo",
-                    "Error receiving GetServiceLogs response. Wrong type"
+                    "Error receiving GetProcessDetails response. Wrong type"
                 );
 
-                String::new()
+                Err("Wrong response type".to_owned())
             }
         }
     }
@@ -548,11 +1268,21 @@ impl MagpieClient {
 
 impl MagpieClient {
     fn handle_incoming_message(
-        magpie: &Client,
+        magpie: &dyn Gatherer,
+        fan_control: &mut fan_control::FanControl,
+        foreground_boost: &mut foreground_boost::ForegroundBoost,
+        cpu_smoothing: &mut cpu_smoothing::CpuSmoothing,
+        gatherer_stats: &mut gatherer_stats::GathererStatsTracker,
+        app_usage: &mut app_usage::AppUsageTracker,
+        metrics_stream: &mut metrics_stream::MetricsStream,
+        schedule: &mut collectors::CollectorSchedule,
+        local: bool,
         rx: &mut Receiver<Message>,
         tx: &mut Sender<Response>,
         timeout: Duration,
     ) -> bool {
+        const REMOTE_UNSUPPORTED: &str = "Not available when monitoring a remote host";
+
         match rx.recv_timeout(timeout) {
             Ok(message) => match message {
                 Message::ContinueReading => {
@@ -563,29 +1293,44 @@ impl MagpieClient {
                 }
                 Message::UpdateCoreCountAffectsPercentages(show) => {
                     magpie.set_scale_cpu_usage_to_core_count(show);
+                    app_usage.set_scale_cpu_usage_to_core_count(show);
                 }
-                Message::TerminateProcesses(pid) => {
-                    magpie.terminate_processes(pid);
+                Message::SetSmoothCpuUsage(smooth) => {
+                    cpu_smoothing.set_enabled(smooth);
+                }
+                Message::TerminateProcesses(pids) => {
+                    let pids = Self::signal_others(local, pids, privileged::Signal::Terminate);
+                    magpie.terminate_processes(pids);
                 }
                 Message::KillProcesses(pids) => {
+                    let pids = Self::signal_others(local, pids, privileged::Signal::Kill);
                     magpie.kill_processes(pids);
                 }
+                Message::StopProcesses(pids, force, done) => {
+                    stop_processes::stop(magpie, local, pids, force, done);
+                }
                 Message::InterruptProcesses(pids) => {
+                    let pids = Self::signal_others(local, pids, privileged::Signal::Interrupt);
                     magpie.interrupt_processes(pids);
                 }
                 Message::HangupProcesses(pids) => {
+                    let pids = Self::signal_others(local, pids, privileged::Signal::Hangup);
                     magpie.hangup_processes(pids);
                 }
                 Message::ContinueProcesses(pids) => {
+                    let pids = Self::signal_others(local, pids, privileged::Signal::Continue);
                     magpie.continue_processes(pids);
                 }
                 Message::SuspendProcesses(pids) => {
+                    let pids = Self::signal_others(local, pids, privileged::Signal::Stop);
                     magpie.suspend_processes(pids);
                 }
                 Message::User1Processes(pids) => {
+                    let pids = Self::signal_others(local, pids, privileged::Signal::User1);
                     magpie.signal_user_one_processes(pids);
                 }
                 Message::User2Processes(pids) => {
+                    let pids = Self::signal_others(local, pids, privileged::Signal::User2);
                     magpie.signal_user_two_processes(pids);
                 }
                 Message::StartService(name) => {
@@ -603,9 +1348,9 @@ impl MagpieClient {
                 Message::DisableService(name) => {
                     magpie.disable_service(name);
                 }
-                Message::GetServiceLogs(name, pid) => {
-                    let resp = magpie.service_logs(name, pid);
-                    if let Err(e) = tx.send(Response::String(resp)) {
+                Message::GetServiceLogs(name, pid, max_priority, lines) => {
+                    let resp = service_logs::read(magpie, local, name, pid, max_priority, lines);
+                    if let Err(e) = tx.send(Response::ServiceLogs(resp)) {
                         g_critical!(
                             "MissionCenter::SysInfo",
                             "Error sending GetServiceLogs response: {}",
@@ -613,6 +1358,144 @@ impl MagpieClient {
                         );
                     }
                 }
+                Message::GetServiceProcesses(name) => {
+                    let resp = if local {
+                        service_processes::read(&name)
+                    } else {
+                        Err(REMOTE_UNSUPPORTED.to_owned())
+                    };
+                    if let Err(e) = tx.send(Response::ServiceProcesses(resp)) {
+                        g_critical!(
+                            "MissionCenter::SysInfo",
+                            "Error sending GetServiceProcesses response: {}",
+                            e
+                        );
+                    }
+                }
+                Message::GetServiceOverride(name) => {
+                    let resp = if local {
+                        service_override::read(&name)
+                    } else {
+                        Err(REMOTE_UNSUPPORTED.to_owned())
+                    };
+                    if let Err(e) = tx.send(Response::ServiceOverride(resp)) {
+                        g_critical!(
+                            "MissionCenter::SysInfo",
+                            "Error sending GetServiceOverride response: {}",
+                            e
+                        );
+                    }
+                }
+                Message::SetServiceOverride(name, service_override, done) => {
+                    if local {
+                        service_override::write_in_background(name, service_override, done);
+                    } else {
+                        idle_add_once(move || done(Err(REMOTE_UNSUPPORTED.to_owned())));
+                    }
+                }
+                Message::GetKernelLog(max_priority) => {
+                    let resp = if local {
+                        kernel_log::read(max_priority)
+                    } else {
+                        Err(REMOTE_UNSUPPORTED.to_owned())
+                    };
+                    if let Err(e) = tx.send(Response::KernelLog(resp)) {
+                        g_critical!(
+                            "MissionCenter::SysInfo",
+                            "Error sending GetKernelLog response: {}",
+                            e
+                        );
+                    }
+                }
+                Message::GetCoredumps => {
+                    let resp = if local {
+                        coredumps::read()
+                    } else {
+                        Err(REMOTE_UNSUPPORTED.to_owned())
+                    };
+                    if let Err(e) = tx.send(Response::Coredumps(resp)) {
+                        g_critical!(
+                            "MissionCenter::SysInfo",
+                            "Error sending GetCoredumps response: {}",
+                            e
+                        );
+                    }
+                }
+                Message::GetBootAnalysis => {
+                    let resp = if local {
+                        boot_analysis::read()
+                    } else {
+                        Err(REMOTE_UNSUPPORTED.to_owned())
+                    };
+                    if let Err(e) = tx.send(Response::BootAnalysis(resp)) {
+                        g_critical!(
+                            "MissionCenter::SysInfo",
+                            "Error sending GetBootAnalysis response: {}",
+                            e
+                        );
+                    }
+                }
+                Message::GetCapabilities => {
+                    let resp = if local {
+                        Ok(capabilities::read())
+                    } else {
+                        Err(REMOTE_UNSUPPORTED.to_owned())
+                    };
+                    if let Err(e) = tx.send(Response::Capabilities(resp)) {
+                        g_critical!(
+                            "MissionCenter::SysInfo",
+                            "Error sending GetCapabilities response: {}",
+                            e
+                        );
+                    }
+                }
+                Message::GetGathererStats => {
+                    let resp = gatherer_stats.stats(magpie.request_count(), local);
+                    if let Err(e) = tx.send(Response::GathererStats(resp)) {
+                        g_critical!(
+                            "MissionCenter::SysInfo",
+                            "Error sending GetGathererStats response: {}",
+                            e
+                        );
+                    }
+                }
+                Message::GetAppUsage => {
+                    if let Err(e) = tx.send(Response::AppUsage(app_usage.history())) {
+                        g_critical!(
+                            "MissionCenter::SysInfo",
+                            "Error sending GetAppUsage response: {}",
+                            e
+                        );
+                    }
+                }
+                Message::GetTcpSockets(pids) => {
+                    let resp = if local {
+                        sockets::read(&pids)
+                    } else {
+                        Err(REMOTE_UNSUPPORTED.to_owned())
+                    };
+                    if let Err(e) = tx.send(Response::TcpSockets(resp)) {
+                        g_critical!(
+                            "MissionCenter::SysInfo",
+                            "Error sending GetTcpSockets response: {}",
+                            e
+                        );
+                    }
+                }
+                Message::GetProcessDetails(pid) => {
+                    let resp = if local {
+                        process_details::read(pid)
+                    } else {
+                        Err(REMOTE_UNSUPPORTED.to_owned())
+                    };
+                    if let Err(e) = tx.send(Response::ProcessDetails(resp)) {
+                        g_critical!(
+                            "MissionCenter::SysInfo",
+                            "Error sending GetProcessDetails response: {}",
+                            e
+                        );
+                    }
+                }
                 Message::EjectDisk(disk_id) => {
                     if let Err(e) = tx.send(Response::EjectResult(magpie.eject_disk(disk_id))) {
                         g_critical!(
@@ -630,6 +1513,46 @@ impl MagpieClient {
                         );
                     }
                 }
+                // The indices and cgroups refer to the remote machine, acting on them here would
+                // change the wrong fan or process
+                Message::SetFanSpeed(..)
+                | Message::SetFanCurve(..)
+                | Message::SetCgroupWeights(..)
+                    if !local =>
+                {
+                    g_warning!(
+                        "MissionCenter::SysInfo",
+                        "Fan speeds and CPU weights can only be changed on this machine"
+                    );
+                }
+                Message::SetFanSpeed(hwmon_index, fan_index, pwm_percent) => {
+                    fan_control.set_speed(hwmon_index, fan_index, pwm_percent);
+                }
+                Message::SetFanCurve(hwmon_index, fan_index, curve) => {
+                    fan_control.set_curve(hwmon_index, fan_index, curve);
+                }
+                Message::SetCgroupWeights(weights, cpu_weight, io_weight) => {
+                    if let Err(e) = cgroup::set_weights(&weights, cpu_weight, io_weight) {
+                        g_warning!(
+                            "MissionCenter::SysInfo",
+                            "Failed to change the weights of cgroup {}: {}",
+                            weights.path,
+                            e
+                        );
+                    }
+                }
+                Message::SetForegroundBoost(enabled) => {
+                    foreground_boost.set_enabled(enabled);
+                }
+                Message::SetMetricsStream(config) => {
+                    metrics_stream.configure(config);
+                }
+                Message::SetCollectorIntervals(intervals) => {
+                    schedule.configure(intervals);
+                }
+                Message::SetDisabledCollectors(disabled) => {
+                    schedule.set_disabled(disabled);
+                }
             },
             Err(_) => {}
         }
@@ -637,29 +1560,161 @@ impl MagpieClient {
         true
     }
 
+    /// Processes of other users only take signals from root; those get theirs after asking for
+    /// authorization, on a thread of its own so readings keep coming in the meantime. The ones
+    /// left to send through the gatherer are returned
+    fn signal_others(local: bool, pids: Vec<Pid>, signal: privileged::Signal) -> Vec<Pid> {
+        if !local {
+            return pids;
+        }
+
+        let (own, others) = privileged::split_by_owner(pids);
+        if !others.is_empty() {
+            std::thread::spawn(move || {
+                if let Err(e) = privileged::signal_processes(&others, signal) {
+                    g_critical!(
+                        "MissionCenter::SysInfo",
+                        "Failed to send {:?} to processes {:?}: {}",
+                        signal,
+                        others,
+                        e
+                    );
+                }
+            });
+        }
+
+        own
+    }
+
+    fn connect(
+        remote: Option<RemoteHost>,
+        recording: Option<Recording>,
+        idle_priority: bool,
+    ) -> Box<dyn Gatherer> {
+        let path = match recording {
+            Some(Recording::Replay { path, speed }) => {
+                return Box::new(recording::ReplayGatherer::new(&path, speed));
+            }
+            Some(Recording::Record(path)) => Some(path),
+            None => None,
+        };
+
+        let gatherer = Self::connect_gatherer(remote, idle_priority);
+        match path {
+            Some(path) => Box::new(recording::RecordingGatherer::new(gatherer, &path)),
+            None => gatherer,
+        }
+    }
+
+    fn connect_gatherer(remote: Option<RemoteHost>, idle_priority: bool) -> Box<dyn Gatherer> {
+        // Made up readings for this machine, for working on the UI where magpie doesn't run
+        #[cfg(feature = "mock-gatherer")]
+        if remote.is_none() {
+            return Box::new(mock::MockGatherer::new());
+        }
+
+        let client = Client::new(remote);
+        client.set_idle_priority(idle_priority);
+        client.connect_reconnecting(|reconnecting| {
+            idle_add_once(move || {
+                if let Some(window) = app!().window() {
+                    window.set_gatherer_reconnecting(reconnecting);
+                }
+            });
+        });
+        client.start();
+
+        Box::new(client)
+    }
+
     fn gather_and_proxy(
         mut rx: Receiver<Message>,
         mut tx: Sender<Response>,
         running: Arc<AtomicBool>,
         speed: Arc<AtomicU64>,
+        background_interval: Arc<AtomicU64>,
+        remote: Option<RemoteHost>,
+        recording: Option<Recording>,
+        idle_priority: bool,
     ) {
-        let magpie = Client::new();
-        magpie.start();
+        // A replay is of another time, if not of another machine; the mock's readings are made up,
+        // acting on them would change the wrong fans, processes and services of this one
+        let local = remote.is_none()
+            && !matches!(recording, Some(Recording::Replay { .. }))
+            && !cfg!(feature = "mock-gatherer");
+        let host_name = match &remote {
+            Some(remote) => remote.name.clone(),
+            None => glib::host_name().to_string(),
+        };
+
+        let magpie = Self::connect(remote, recording, idle_priority);
+
+        // Hands any fans we control back to the firmware when the thread exits
+        let mut fan_control = fan_control::FanControl::new();
+        let mut job_tracker = background_jobs::BackgroundJobs::new();
+        let mut encryption_monitor = disk_encryption::DiskEncryptionMonitor::new();
+        // Also dropped on exit, which gives the focused app its original CPU weight back
+        let mut foreground_boost = foreground_boost::ForegroundBoost::new();
+        let mut metrics_stream = metrics_stream::MetricsStream::new(host_name);
+        let mut cpu_split_tracker = cpu_split::CpuSplitTracker::new();
+        let mut process_owners = process_owners::ProcessOwners::new();
+        let mut user_tracker = users::UserTracker::new();
+        let mut app_sandboxes = sandbox::AppSandboxes::new();
+        let mut sandboxed_services = sandboxed_services::SandboxedServices::new();
+        let mut cpu_smoothing = cpu_smoothing::CpuSmoothing::new();
+        let mut gatherer_stats = gatherer_stats::GathererStatsTracker::new();
+        let mut app_usage = app_usage::AppUsageTracker::new();
+        let mut irq_load = irq_load::IrqLoad::new();
+        let mut schedule = collectors::CollectorSchedule::new();
 
         let (running_processes, network_stats_error) = magpie.processes();
+        // Read once per process list, for everything that needs more than the gatherer knows
+        let mut proc_stats = local_only(local, || {
+            proc_stat::read_all(running_processes.keys().copied())
+        });
+        let owners = local_only(local, || process_owners.update(&running_processes));
+        let users = local_only(local, || user_tracker.update(&running_processes, &owners));
+        let display_server = display_server::summarize(&running_processes);
+        let running_apps = magpie.apps();
         let mut readings = Readings {
+            app_sandboxes: local_only(local, || app_sandboxes.update(&running_apps)),
+            process_cpu_split: local_only(local, || cpu_split_tracker.update(&proc_stats)),
+            process_owners: owners,
+            zombies: cpu_split_tracker.zombies(),
+            cpu_irq_percent: local_only(local, || irq_load.update()),
             running_processes,
             network_stats_error,
-            running_apps: magpie.apps(),
+            running_apps,
             disks_info: magpie.disks_info(),
             gpus: magpie.gpus(),
             cpu: magpie.cpu(),
             mem_info: magpie.memory(),
+            mem_pressure: local_only(local, || psi::read("memory")),
+            cpu_pressure: local_only(local, || psi::read("cpu")),
+            io_pressure: local_only(local, || psi::read("io")),
             mem_devices: magpie.memory_devices(),
             fans: magpie.fans_info(),
+            ups: local_only(local, ups::read),
+            bluetooth: local_only(local, bluetooth::read),
+            users,
+            display_server,
+            background_jobs: vec![],
+            disk_encryption: local_only(local, || encryption_monitor.update()),
+            gpu_temperatures: HashMap::new(),
+            foreground_boost: None,
+            game_mode: local_only(local, game_mode::read),
             network_connections: magpie.network_connections(),
             services: magpie.services(),
+            service_start_types: local_only(local, unit_files::read),
+            service_sources: HashMap::new(),
         };
+        readings.service_sources =
+            local_only(local, || sandboxed_services.update(&mut readings.services));
+        readings.background_jobs = job_tracker.update(
+            &readings.running_processes,
+            &proc_stats,
+            readings.cpu.uptime_seconds,
+        );
 
         readings
             .disks_info
@@ -668,19 +1723,45 @@ impl MagpieClient {
             .network_connections
             .sort_unstable_by(|n1, n2| n1.id.cmp(&n2.id));
 
+        if local {
+            nvidia_smi::fill_missing_stats(&mut readings.gpus);
+            readings.gpu_temperatures = gpu_temperatures::read(&readings.gpus);
+        }
+
+        let mut gpu_supervisor = gpu_supervisor::GpuSupervisor::new(&readings.gpus);
+
         idle_add_once({
             let initial_readings = Readings {
                 cpu: readings.cpu.clone(),
                 mem_info: readings.mem_info.clone(),
+                mem_pressure: readings.mem_pressure,
+                cpu_pressure: readings.cpu_pressure,
+                io_pressure: readings.io_pressure,
                 mem_devices: std::mem::take(&mut readings.mem_devices),
                 disks_info: std::mem::take(&mut readings.disks_info),
                 fans: std::mem::take(&mut readings.fans),
+                ups: std::mem::take(&mut readings.ups),
+                bluetooth: std::mem::take(&mut readings.bluetooth),
+                users: std::mem::take(&mut readings.users),
+                display_server: std::mem::take(&mut readings.display_server),
+                background_jobs: std::mem::take(&mut readings.background_jobs),
+                disk_encryption: std::mem::take(&mut readings.disk_encryption),
+                gpu_temperatures: std::mem::take(&mut readings.gpu_temperatures),
+                foreground_boost: readings.foreground_boost.clone(),
+                game_mode: std::mem::take(&mut readings.game_mode),
                 network_connections: std::mem::take(&mut readings.network_connections),
                 gpus: std::mem::take(&mut readings.gpus),
                 running_apps: std::mem::take(&mut readings.running_apps),
+                app_sandboxes: std::mem::take(&mut readings.app_sandboxes),
                 running_processes: std::mem::take(&mut readings.running_processes),
+                process_cpu_split: std::mem::take(&mut readings.process_cpu_split),
+                process_owners: std::mem::take(&mut readings.process_owners),
+                zombies: std::mem::take(&mut readings.zombies),
+                cpu_irq_percent: std::mem::take(&mut readings.cpu_irq_percent),
                 network_stats_error: std::mem::take(&mut readings.network_stats_error),
                 services: std::mem::take(&mut readings.services),
+                service_start_types: std::mem::take(&mut readings.service_start_types),
+                service_sources: std::mem::take(&mut readings.service_sources),
             };
 
             move || {
@@ -690,17 +1771,37 @@ impl MagpieClient {
         });
 
         loop {
-            match rx.recv() {
+            // Mission Center can quit, or switch hosts, before the first readings are shown
+            if !running.load(atomic::Ordering::Acquire) {
+                return;
+            }
+
+            match rx.recv_timeout(Duration::from_millis(100)) {
                 Ok(message) => match message {
                     Message::ContinueReading => {
                         break;
                     }
                     Message::UpdateCoreCountAffectsPercentages(show) => {
                         magpie.set_scale_cpu_usage_to_core_count(show);
+                        app_usage.set_scale_cpu_usage_to_core_count(show);
+                    }
+                    Message::SetSmoothCpuUsage(smooth) => {
+                        cpu_smoothing.set_enabled(smooth);
+                    }
+                    // Sent along with the other settings when the window opens
+                    Message::SetMetricsStream(config) => {
+                        metrics_stream.configure(config);
+                    }
+                    Message::SetCollectorIntervals(intervals) => {
+                        schedule.configure(intervals);
+                    }
+                    Message::SetDisabledCollectors(disabled) => {
+                        schedule.set_disabled(disabled);
                     }
                     _ => {}
                 },
-                Err(_) => {
+                Err(mpsc::RecvTimeoutError::Timeout) => {}
+                Err(mpsc::RecvTimeoutError::Disconnected) => {
                     g_warning!(
                         "MissionCenter::SysInfo",
                         "No more messages in the buffer and channel closed",
@@ -713,40 +1814,124 @@ impl MagpieClient {
         'read_loop: while running.load(atomic::Ordering::Acquire) {
             let loop_start = std::time::Instant::now();
 
-            let timer = std::time::Instant::now();
-            (readings.running_processes, readings.network_stats_error) = magpie.processes();
-            g_debug!(
-                "MissionCenter::Perf",
-                "Process load load took: {:?}",
-                timer.elapsed()
-            );
+            // Whatever is only ever shown in the window is left out while it is hidden, the
+            // pages are not updated with the empty lists in the meantime
+            let background_interval_ms = background_interval.load(atomic::Ordering::Acquire);
+            let background = background_interval_ms > 0;
+            let update_interval = if background {
+                Duration::from_millis(background_interval_ms)
+            } else {
+                Duration::from_millis(
+                    ((speed.load(atomic::Ordering::Relaxed) as f64 * INTERVAL_STEP) * 1000.) as u64,
+                )
+            };
 
-            let timer = std::time::Instant::now();
-            readings.running_apps = magpie.apps();
-            g_debug!(
-                "MissionCenter::Perf",
-                "Running apps load took: {:?}",
-                timer.elapsed(),
-            );
+            let processes_due = schedule.is_due(Collector::Processes, update_interval);
+            if processes_due {
+                let timer = std::time::Instant::now();
+                (readings.running_processes, readings.network_stats_error) = magpie.processes();
+                proc_stats = local_only(local, || {
+                    proc_stat::read_all(readings.running_processes.keys().copied())
+                });
+                cpu_smoothing.update(&mut readings.running_processes);
+                g_debug!(
+                    "MissionCenter::Perf",
+                    "Process load load took: {:?}",
+                    timer.elapsed()
+                );
+                gatherer_stats.record(Reading::Processes, timer.elapsed());
+                gatherer_stats.update_process(&readings.running_processes);
+
+                let timer = std::time::Instant::now();
+                readings.running_apps = magpie.apps();
+                readings.app_sandboxes =
+                    local_only(local, || app_sandboxes.update(&readings.running_apps));
+                app_usage.update(
+                    &readings.running_apps,
+                    &readings.running_processes,
+                    readings.cpu.core_usage_percent.len(),
+                    &proc_stats,
+                    local,
+                );
+                g_debug!(
+                    "MissionCenter::Perf",
+                    "Running apps load took: {:?}",
+                    timer.elapsed(),
+                );
+                gatherer_stats.record(Reading::Apps, timer.elapsed());
+            }
+
+            if !background {
+                readings.process_owners =
+                    local_only(local, || process_owners.update(&readings.running_processes));
+
+                let timer = std::time::Instant::now();
+                readings.users = local_only(local, || {
+                    user_tracker.update(&readings.running_processes, &readings.process_owners)
+                });
+                g_debug!(
+                    "MissionCenter::Perf",
+                    "Users load took: {:?}",
+                    timer.elapsed()
+                );
+
+                readings.display_server = display_server::summarize(&readings.running_processes);
+                // The split is about the time between two process lists
+                if processes_due {
+                    readings.process_cpu_split =
+                        local_only(local, || cpu_split_tracker.update(&proc_stats));
+                    readings.zombies = cpu_split_tracker.zombies();
+                }
+                readings.background_jobs = job_tracker.update(
+                    &readings.running_processes,
+                    &proc_stats,
+                    readings.cpu.uptime_seconds,
+                );
+            }
 
             let timer = std::time::Instant::now();
-            readings.disks_info = magpie.disks_info();
+            if schedule.is_due(Collector::Disks, update_interval) {
+                readings.disks_info = magpie.disks_info();
+                gatherer_stats.record(Reading::Disks, timer.elapsed());
+            }
+            readings.io_pressure = local_only(local, || psi::read("io"));
             g_debug!(
                 "MissionCenter::Perf",
                 "Disks info load took: {:?}",
                 timer.elapsed()
             );
 
-            let timer = std::time::Instant::now();
-            readings.gpus = magpie.gpus();
-            g_debug!(
-                "MissionCenter::Perf",
-                "GPU info load took: {:?}",
-                timer.elapsed()
-            );
+            if !background {
+                let timer = std::time::Instant::now();
+                readings.disk_encryption = local_only(local, || encryption_monitor.update());
+                g_debug!(
+                    "MissionCenter::Perf",
+                    "Disk encryption load took: {:?}",
+                    timer.elapsed()
+                );
+            }
+
+            if schedule.is_due(Collector::Gpus, update_interval) {
+                let timer = std::time::Instant::now();
+                readings.gpus = magpie.gpus();
+                gatherer_stats.record(Reading::Gpus, timer.elapsed());
+                if local {
+                    nvidia_smi::fill_missing_stats(&mut readings.gpus);
+                    readings.gpu_temperatures = gpu_temperatures::read(&readings.gpus);
+                }
+                g_debug!(
+                    "MissionCenter::Perf",
+                    "GPU info load took: {:?}",
+                    timer.elapsed()
+                );
+                gpu_supervisor.update(&readings.gpus, magpie.as_ref());
+            }
 
             let timer = std::time::Instant::now();
             readings.cpu = magpie.cpu();
+            gatherer_stats.record(Reading::Cpu, timer.elapsed());
+            readings.cpu_pressure = local_only(local, || psi::read("cpu"));
+            readings.cpu_irq_percent = local_only(local, || irq_load.update());
             g_debug!(
                 "MissionCenter::Perf",
                 "CPU info load took: {:?}",
@@ -755,36 +1940,83 @@ impl MagpieClient {
 
             let timer = std::time::Instant::now();
             readings.mem_info = magpie.memory();
+            gatherer_stats.record(Reading::Memory, timer.elapsed());
+            readings.mem_pressure = local_only(local, || psi::read("memory"));
             g_debug!(
                 "MissionCenter::Perf",
                 "Memory info load took: {:?}",
                 timer.elapsed()
             );
 
-            let timer = std::time::Instant::now();
-            readings.network_connections = magpie.network_connections();
-            g_debug!(
-                "MissionCenter::Perf",
-                "Network devices info load took: {:?}",
-                timer.elapsed()
-            );
+            if schedule.is_due(Collector::Network, update_interval) {
+                let timer = std::time::Instant::now();
+                readings.network_connections = magpie.network_connections();
+                g_debug!(
+                    "MissionCenter::Perf",
+                    "Network devices info load took: {:?}",
+                    timer.elapsed()
+                );
+                gatherer_stats.record(Reading::Network, timer.elapsed());
+            }
+
+            if schedule.is_due(Collector::Fans, update_interval) {
+                let timer = std::time::Instant::now();
+                readings.fans = magpie.fans_info();
+                gatherer_stats.record(Reading::Fans, timer.elapsed());
+                if local {
+                    fan_control.apply_curves(&readings.fans);
+                    fan_control.enforce_limits(&readings.fans);
+                }
+                g_debug!(
+                    "MissionCenter::Perf",
+                    "Fans info load took: {:?}",
+                    timer.elapsed()
+                );
+            }
 
             let timer = std::time::Instant::now();
-            readings.fans = magpie.fans_info();
+            readings.ups = local_only(local, ups::read);
             g_debug!(
                 "MissionCenter::Perf",
-                "Fans info load took: {:?}",
+                "UPS info load took: {:?}",
                 timer.elapsed()
             );
 
+            if !background {
+                let timer = std::time::Instant::now();
+                readings.bluetooth = local_only(local, bluetooth::read);
+                g_debug!(
+                    "MissionCenter::Perf",
+                    "Bluetooth info load took: {:?}",
+                    timer.elapsed()
+                );
+            }
+
             let timer = std::time::Instant::now();
-            readings.services = magpie.services();
+            readings.foreground_boost = local_only(local, || foreground_boost.update());
+            if !background {
+                readings.game_mode = local_only(local, game_mode::read);
+            }
             g_debug!(
                 "MissionCenter::Perf",
-                "Services load took: {:?}",
+                "Foreground boost took: {:?}",
                 timer.elapsed()
             );
 
+            if !background && schedule.is_due(Collector::Services, update_interval) {
+                let timer = std::time::Instant::now();
+                readings.services = magpie.services();
+                readings.service_start_types = local_only(local, unit_files::read);
+                readings.service_sources =
+                    local_only(local, || sandboxed_services.update(&mut readings.services));
+                g_debug!(
+                    "MissionCenter::Perf",
+                    "Services load took: {:?}",
+                    timer.elapsed()
+                );
+                gatherer_stats.record(Reading::Services, timer.elapsed());
+            }
+
             readings
                 .disks_info
                 .sort_unstable_by(|d1, d2| d1.id.cmp(&d2.id));
@@ -796,19 +2028,48 @@ impl MagpieClient {
                 break 'read_loop;
             }
 
+            metrics_stream.push(&readings);
+
             idle_add_once({
                 let mut new_readings = Readings {
                     cpu: readings.cpu.clone(),
                     mem_info: readings.mem_info.clone(),
+                    mem_pressure: readings.mem_pressure,
+                    cpu_pressure: readings.cpu_pressure,
+                    io_pressure: readings.io_pressure,
                     mem_devices: readings.mem_devices.clone(),
-                    disks_info: std::mem::take(&mut readings.disks_info),
-                    fans: std::mem::take(&mut readings.fans),
-                    network_connections: std::mem::take(&mut readings.network_connections),
-                    gpus: std::mem::take(&mut readings.gpus),
-                    running_apps: std::mem::take(&mut readings.running_apps),
-                    running_processes: std::mem::take(&mut readings.running_processes),
-                    network_stats_error: std::mem::take(&mut readings.network_stats_error),
-                    services: std::mem::take(&mut readings.services),
+                    disks_info: schedule.hand_over(Collector::Disks, &mut readings.disks_info),
+                    fans: schedule.hand_over(Collector::Fans, &mut readings.fans),
+                    ups: std::mem::take(&mut readings.ups),
+                    bluetooth: std::mem::take(&mut readings.bluetooth),
+                    users: std::mem::take(&mut readings.users),
+                    display_server: std::mem::take(&mut readings.display_server),
+                    background_jobs: std::mem::take(&mut readings.background_jobs),
+                    disk_encryption: std::mem::take(&mut readings.disk_encryption),
+                    gpu_temperatures: schedule
+                        .hand_over(Collector::Gpus, &mut readings.gpu_temperatures),
+                    foreground_boost: readings.foreground_boost.clone(),
+                    game_mode: std::mem::take(&mut readings.game_mode),
+                    network_connections: schedule
+                        .hand_over(Collector::Network, &mut readings.network_connections),
+                    gpus: schedule.hand_over(Collector::Gpus, &mut readings.gpus),
+                    running_apps: schedule
+                        .hand_over(Collector::Processes, &mut readings.running_apps),
+                    app_sandboxes: schedule
+                        .hand_over(Collector::Processes, &mut readings.app_sandboxes),
+                    running_processes: schedule
+                        .hand_over(Collector::Processes, &mut readings.running_processes),
+                    process_cpu_split: std::mem::take(&mut readings.process_cpu_split),
+                    process_owners: std::mem::take(&mut readings.process_owners),
+                    zombies: std::mem::take(&mut readings.zombies),
+                    cpu_irq_percent: std::mem::take(&mut readings.cpu_irq_percent),
+                    network_stats_error: schedule
+                        .hand_over(Collector::Processes, &mut readings.network_stats_error),
+                    services: schedule.hand_over(Collector::Services, &mut readings.services),
+                    service_start_types: schedule
+                        .hand_over(Collector::Services, &mut readings.service_start_types),
+                    service_sources: schedule
+                        .hand_over(Collector::Services, &mut readings.service_sources),
                 };
 
                 move || {
@@ -834,10 +2095,7 @@ impl MagpieClient {
                 }
             });
 
-            let mut wait_time = Duration::from_millis(
-                ((speed.load(atomic::Ordering::Relaxed) as f64 * INTERVAL_STEP) * 1000.) as u64,
-            )
-            .saturating_sub(loop_start.elapsed());
+            let mut wait_time = update_interval.saturating_sub(loop_start.elapsed());
 
             const ITERATIONS_COUNT: u32 = 10;
 
@@ -845,7 +2103,20 @@ impl MagpieClient {
             for _ in 0..ITERATIONS_COUNT {
                 let wait_timer = std::time::Instant::now();
 
-                if !Self::handle_incoming_message(&magpie, &mut rx, &mut tx, wait_time_fraction) {
+                if !Self::handle_incoming_message(
+                    magpie.as_ref(),
+                    &mut fan_control,
+                    &mut foreground_boost,
+                    &mut cpu_smoothing,
+                    &mut gatherer_stats,
+                    &mut app_usage,
+                    &mut metrics_stream,
+                    &mut schedule,
+                    local,
+                    &mut rx,
+                    &mut tx,
+                    wait_time_fraction,
+                ) {
                     break 'read_loop;
                 }
 
@@ -853,13 +2124,31 @@ impl MagpieClient {
                     break 'read_loop;
                 }
 
+                // The window was shown again, it gets complete readings right away
+                if background && background_interval.load(atomic::Ordering::Acquire) == 0 {
+                    continue 'read_loop;
+                }
+
                 wait_time = wait_time.saturating_sub(wait_timer.elapsed());
                 if wait_time.is_zero() {
                     break;
                 }
             }
 
-            if !Self::handle_incoming_message(&magpie, &mut rx, &mut tx, wait_time) {
+            if !Self::handle_incoming_message(
+                magpie.as_ref(),
+                &mut fan_control,
+                &mut foreground_boost,
+                &mut cpu_smoothing,
+                &mut gatherer_stats,
+                &mut app_usage,
+                &mut metrics_stream,
+                &mut schedule,
+                local,
+                &mut rx,
+                &mut tx,
+                wait_time,
+            ) {
                 break 'read_loop;
             }
 
@@ -869,5 +2158,7 @@ impl MagpieClient {
                 "Full read-publish cycle took {elapsed_since_start:?}",
             );
         }
+
+        fan_control.restore_all();
     }
 }
@@ -0,0 +1,269 @@
+/* magpie_client/nvidia_smi.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use gtk::glib::{g_debug, g_warning};
+
+use super::Gpu;
+use crate::is_flatpak;
+
+const QUERY_FIELDS: &str =
+    "pci.bus_id,name,utilization.gpu,memory.used,memory.total,temperature.gpu,power.draw";
+
+const PCI_VENDOR_ID_NVIDIA: &str = "0x10de";
+
+// Starting nvidia-smi takes a while and wakes up every GPU, reuse its answer for a few refreshes
+const MIN_QUERY_INTERVAL: Duration = Duration::from_secs(5);
+
+static NVIDIA_SMI_UNAVAILABLE: AtomicBool = AtomicBool::new(false);
+static LAST_QUERY: Mutex<Option<(Instant, Vec<NvidiaSmiGpu>)>> = Mutex::new(None);
+// Only HBM cards report a memory temperature, don't keep asking the others
+static MEMORY_TEMPERATURE_UNSUPPORTED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Clone)]
+struct NvidiaSmiGpu {
+    pci_id: String,
+    name: Option<String>,
+    utilization_percent: Option<f32>,
+    used_memory: Option<u64>,
+    total_memory: Option<u64>,
+    temperature_c: Option<f32>,
+    power_draw_watts: Option<f32>,
+}
+
+// When NVML can't be loaded (containers, driver/library version mismatch) the gatherer still
+// reports NVIDIA GPUs, but without any of the dynamic statistics; `nvidia-smi` is usually still
+// around and working in those cases, so use it to fill in the blanks.
+pub fn fill_missing_stats(gpus: &mut HashMap<String, Gpu>) {
+    if NVIDIA_SMI_UNAVAILABLE.load(Ordering::Relaxed) {
+        return;
+    }
+
+    // Integrated GPUs never report used memory, only look at the NVIDIA ones
+    if !gpus.iter().any(|(id, gpu)| {
+        (gpu.utilization_percent.is_none() || gpu.used_memory.is_none()) && is_nvidia(id)
+    }) {
+        return;
+    }
+
+    let Some(smi_gpus) = cached_query() else {
+        return;
+    };
+
+    for smi_gpu in smi_gpus {
+        let Some(gpu) = gpus
+            .values_mut()
+            .find(|gpu| normalize_pci_id(&gpu.id) == smi_gpu.pci_id)
+        else {
+            continue;
+        };
+
+        if gpu.device_name.is_none() {
+            gpu.device_name = smi_gpu.name;
+        }
+        if gpu.utilization_percent.is_none() {
+            gpu.utilization_percent = smi_gpu.utilization_percent;
+        }
+        if gpu.used_memory.is_none() {
+            gpu.used_memory = smi_gpu.used_memory;
+        }
+        if gpu.total_memory.is_none() {
+            gpu.total_memory = smi_gpu.total_memory;
+        }
+        if gpu.temperature_c.is_none() {
+            gpu.temperature_c = smi_gpu.temperature_c;
+        }
+        if gpu.power_draw_watts.is_none() {
+            gpu.power_draw_watts = smi_gpu.power_draw_watts;
+        }
+    }
+}
+
+/// Whether the GPU with the PCI address `gpu_id` is made by NVIDIA
+pub fn is_nvidia(gpu_id: &str) -> bool {
+    std::fs::read_to_string(
+        Path::new("/sys/bus/pci/devices")
+            .join(gpu_id)
+            .join("vendor"),
+    )
+    .map(|vendor| vendor.trim() == PCI_VENDOR_ID_NVIDIA)
+    .unwrap_or(false)
+}
+
+fn cached_query() -> Option<Vec<NvidiaSmiGpu>> {
+    let mut last_query = LAST_QUERY.lock().ok()?;
+    if let Some((timestamp, smi_gpus)) = last_query.as_ref() {
+        if timestamp.elapsed() < MIN_QUERY_INTERVAL {
+            return Some(smi_gpus.clone());
+        }
+    }
+
+    let smi_gpus = query()?;
+    *last_query = Some((Instant::now(), smi_gpus.clone()));
+
+    Some(smi_gpus)
+}
+
+fn query() -> Option<Vec<NvidiaSmiGpu>> {
+    let command_line = format!(
+        "nvidia-smi --query-gpu={} --format=csv,noheader,nounits",
+        QUERY_FIELDS
+    );
+
+    let mut cmd = if is_flatpak() {
+        cmd_flatpak_host!(&command_line)
+    } else {
+        let mut cmd = std::process::Command::new("sh");
+        cmd.arg("-c").arg(&command_line);
+        cmd
+    };
+
+    let output = match cmd.output() {
+        Ok(output) if output.status.success() => output,
+        Ok(output) => {
+            g_warning!(
+                "MissionCenter::SysInfo",
+                "nvidia-smi fallback is not available ({}), GPU statistics will be incomplete",
+                output.status
+            );
+            NVIDIA_SMI_UNAVAILABLE.store(true, Ordering::Relaxed);
+            return None;
+        }
+        Err(e) => {
+            g_warning!(
+                "MissionCenter::SysInfo",
+                "Failed to run nvidia-smi, GPU statistics will be incomplete: {}",
+                e
+            );
+            NVIDIA_SMI_UNAVAILABLE.store(true, Ordering::Relaxed);
+            return None;
+        }
+    };
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let result = stdout.lines().filter_map(parse_line).collect::<Vec<_>>();
+
+    g_debug!(
+        "MissionCenter::SysInfo",
+        "nvidia-smi reported {} GPU(s)",
+        result.len()
+    );
+
+    Some(result)
+}
+
+/// Memory temperatures keyed by normalized PCI id; not available through the gatherer
+pub fn memory_temperatures() -> HashMap<String, f32> {
+    let mut result = HashMap::new();
+
+    if NVIDIA_SMI_UNAVAILABLE.load(Ordering::Relaxed)
+        || MEMORY_TEMPERATURE_UNSUPPORTED.load(Ordering::Relaxed)
+    {
+        return result;
+    }
+
+    let command_line =
+        "nvidia-smi --query-gpu=pci.bus_id,temperature.memory --format=csv,noheader,nounits";
+    let mut cmd = if is_flatpak() {
+        cmd_flatpak_host!(command_line)
+    } else {
+        let mut cmd = std::process::Command::new("sh");
+        cmd.arg("-c").arg(command_line);
+        cmd
+    };
+
+    let output = match cmd.output() {
+        Ok(output) if output.status.success() => output,
+        _ => {
+            MEMORY_TEMPERATURE_UNSUPPORTED.store(true, Ordering::Relaxed);
+            return result;
+        }
+    };
+
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        let Some((pci_id, temperature)) = line.split_once(',') else {
+            continue;
+        };
+        if let Ok(temperature) = temperature.trim().parse::<f32>() {
+            result.insert(normalize_pci_id(pci_id), temperature);
+        }
+    }
+
+    if result.is_empty() {
+        g_debug!(
+            "MissionCenter::SysInfo",
+            "nvidia-smi doesn't report memory temperatures for any GPU"
+        );
+        MEMORY_TEMPERATURE_UNSUPPORTED.store(true, Ordering::Relaxed);
+    }
+
+    result
+}
+
+fn parse_line(line: &str) -> Option<NvidiaSmiGpu> {
+    fn value(field: Option<&str>) -> Option<&str> {
+        // Unsupported fields are reported as "[N/A]" or "[Not Supported]"
+        field
+            .map(|f| f.trim())
+            .filter(|f| !f.is_empty() && !f.starts_with('['))
+    }
+
+    let mut fields = line.split(',');
+
+    let pci_id = normalize_pci_id(value(fields.next())?);
+    let name = value(fields.next()).map(|name| name.to_owned());
+    let utilization_percent = value(fields.next()).and_then(|v| v.parse::<f32>().ok());
+    // Memory is reported in MiB
+    let used_memory = value(fields.next())
+        .and_then(|v| v.parse::<u64>().ok())
+        .map(|v| v * 1024 * 1024);
+    let total_memory = value(fields.next())
+        .and_then(|v| v.parse::<u64>().ok())
+        .map(|v| v * 1024 * 1024);
+    let temperature_c = value(fields.next()).and_then(|v| v.parse::<f32>().ok());
+    let power_draw_watts = value(fields.next()).and_then(|v| v.parse::<f32>().ok());
+
+    Some(NvidiaSmiGpu {
+        pci_id,
+        name,
+        utilization_percent,
+        used_memory,
+        total_memory,
+        temperature_c,
+        power_draw_watts,
+    })
+}
+
+// nvidia-smi uses a 32-bit PCI domain (`00000000:01:00.0`) while sysfs uses a 16-bit one
+// (`0000:01:00.0`); only compare the bus, device and function
+pub fn normalize_pci_id(pci_id: &str) -> String {
+    let pci_id = pci_id.trim().to_ascii_lowercase();
+    match pci_id.split_once(':') {
+        Some((_, bus_device_function)) if bus_device_function.contains(':') => {
+            bus_device_function.to_owned()
+        }
+        _ => pci_id,
+    }
+}
@@ -0,0 +1,315 @@
+/* magpie_client/privileged.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+//! What needs root is done by `missioncenter-helper`, started through `pkexec`, which asks for
+//! authorization through polkit. Each operation of the helper has a polkit action of its own, see
+//! `io.missioncenter.MissionCenter.policy`; the helper checks its arguments again itself.
+//!
+//! Fan speeds go to a helper that is started the first time it's needed and runs until Mission
+//! Center exits, so polkit only asks once; everything else starts a helper of its own. Inside
+//! the Flatpak the helper is started on the host, where the policy can't be installed, and polkit
+//! falls back to its generic action for running programs as root.
+
+use std::collections::HashSet;
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::fs::MetadataExt;
+use std::path::Path;
+use std::sync::Mutex;
+
+use super::{flatpak_app_path, Pid};
+use crate::config::LIBEXECDIR;
+use crate::is_flatpak;
+
+/// What `pkexec` exits with when the authorization dialog is dismissed, or not shown at all
+const PKEXEC_DISMISSED: i32 = 126;
+const PKEXEC_NOT_AUTHORIZED: i32 = 127;
+
+const HELPER_NAME: &str = "missioncenter-helper";
+
+/// Where the administrator's unit files and drop-ins go
+const SYSTEM_UNIT_DIRECTORY: &str = "/etc/systemd/system";
+
+/// The signals Mission Center sends, by the names the helper knows them by
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Signal {
+    Terminate,
+    Kill,
+    Interrupt,
+    Hangup,
+    Continue,
+    Stop,
+    User1,
+    User2,
+}
+
+impl Signal {
+    fn name(&self) -> &'static str {
+        match self {
+            Signal::Terminate => "TERM",
+            Signal::Kill => "KILL",
+            Signal::Interrupt => "INT",
+            Signal::Hangup => "HUP",
+            Signal::Continue => "CONT",
+            Signal::Stop => "STOP",
+            Signal::User1 => "USR1",
+            Signal::User2 => "USR2",
+        }
+    }
+}
+
+/// Started the first time a fan is controlled
+static FAN_HELPER: Mutex<Option<FanHelper>> = Mutex::new(None);
+
+/// Split `pids` into the processes of the user Mission Center runs as, which need no
+/// authorization, and those of everyone else; processes that exited in the meantime count as
+/// the user's
+pub fn split_by_owner(pids: Vec<Pid>) -> (Vec<Pid>, Vec<Pid>) {
+    let Ok(uid) = std::fs::metadata("/proc/self").map(|metadata| metadata.uid()) else {
+        return (pids, vec![]);
+    };
+
+    // The host's processes aren't in the sandbox's `/proc`, ask the host who they belong to
+    if is_flatpak() {
+        let others = host_processes_of_others(&pids, uid);
+        return pids.into_iter().partition(|pid| !others.contains(pid));
+    }
+
+    pids.into_iter().partition(|pid| {
+        std::fs::metadata(format!("/proc/{}", pid)).map_or(true, |metadata| metadata.uid() == uid)
+    })
+}
+
+fn host_processes_of_others(pids: &[Pid], uid: u32) -> HashSet<Pid> {
+    let mut cmd = std::process::Command::new("/usr/bin/flatpak-spawn");
+    cmd.args(["--host", "stat", "-c", "%u %n"]);
+    cmd.args(pids.iter().map(|pid| format!("/proc/{}", pid)));
+
+    // `stat` fails for the processes that are gone, and still prints the others
+    let Ok(output) = cmd.output() else {
+        return HashSet::new();
+    };
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let (owner, path) = line.split_once(' ')?;
+            let pid = path.strip_prefix("/proc/")?.parse::<Pid>().ok()?;
+            (owner.parse::<u32>().ok()? != uid).then_some(pid)
+        })
+        .collect()
+}
+
+pub fn signal_processes(pids: &[Pid], signal: Signal) -> Result<(), String> {
+    if pids.is_empty() {
+        return Ok(());
+    }
+
+    let mut cmd = helper("signal");
+    cmd.arg(signal.name());
+    cmd.args(pids.iter().map(|pid| pid.to_string()));
+    run(cmd, None)
+}
+
+/// Write `value` to the fan control `attribute`, `pwmN` or `pwmN_enable`, of `hwmon<hwmon_index>`
+pub fn write_fan_control(hwmon_index: u32, attribute: &str, value: u32) -> Result<(), String> {
+    let mut helper = FAN_HELPER.lock().map_err(|e| e.to_string())?;
+
+    let request = format!("hwmon{} {} {}", hwmon_index, attribute, value);
+    let result = match &mut *helper {
+        Some(helper) => helper.request(&request),
+        helper @ None => {
+            FanHelper::start().and_then(|started| helper.insert(started).request(&request))
+        }
+    };
+
+    // Start over the next time, after authorization was refused for instance
+    if helper.as_ref().is_some_and(|helper| helper.has_exited()) {
+        *helper = None;
+    }
+
+    result
+}
+
+/// The helper running `set-fan-speed`, which takes one fan control to write per line
+struct FanHelper {
+    child: std::process::Child,
+    stdin: Option<std::process::ChildStdin>,
+    stdout: BufReader<std::process::ChildStdout>,
+}
+
+impl Drop for FanHelper {
+    fn drop(&mut self) {
+        // It runs as root, so it can't be killed; closing its input ends it instead
+        drop(self.stdin.take());
+        let _ = self.child.wait();
+    }
+}
+
+impl FanHelper {
+    /// Authorization is asked for when the first write is handled
+    fn start() -> Result<Self, String> {
+        let mut cmd = helper("set-fan-speed");
+        cmd.stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::null());
+
+        let mut child = cmd.spawn().map_err(|e| e.to_string())?;
+        let stdin = child.stdin.take();
+        let Some(stdout) = child.stdout.take() else {
+            return Err("Failed to read from pkexec".to_owned());
+        };
+
+        Ok(Self {
+            child,
+            stdin,
+            stdout: BufReader::new(stdout),
+        })
+    }
+
+    fn request(&mut self, request: &str) -> Result<(), String> {
+        let Some(stdin) = self.stdin.as_mut() else {
+            return Err(self.exit_error());
+        };
+        if writeln!(stdin, "{}", request).is_err() {
+            return Err(self.exit_error());
+        }
+
+        let mut answer = String::new();
+        match self.stdout.read_line(&mut answer) {
+            Ok(0) | Err(_) => Err(self.exit_error()),
+            Ok(_) if answer.trim() == "OK" => Ok(()),
+            Ok(_) => Err(answer.trim().to_owned()),
+        }
+    }
+
+    fn has_exited(&self) -> bool {
+        self.stdin.is_none()
+    }
+
+    fn exit_error(&mut self) -> String {
+        drop(self.stdin.take());
+        match self.child.wait() {
+            Ok(status) => match status.code() {
+                Some(PKEXEC_DISMISSED) | Some(PKEXEC_NOT_AUTHORIZED) => "Not authorized".to_owned(),
+                _ => format!("pkexec exited with {}", status),
+            },
+            Err(e) => e.to_string(),
+        }
+    }
+}
+
+/// `systemctl set-property` for the system unit `unit`; with `runtime` the properties are
+/// forgotten at the next boot
+pub fn set_unit_properties(unit: &str, runtime: bool, properties: &[String]) -> Result<(), String> {
+    if properties.is_empty() {
+        return Ok(());
+    }
+
+    let mut cmd = helper("set-unit-properties");
+    if runtime {
+        cmd.arg("--runtime");
+    }
+    cmd.arg(unit).args(properties);
+    run(cmd, None)
+}
+
+/// Write `contents` to the drop-in `file_name` of the system unit `unit`, or remove it for
+/// `None`, and reload systemd
+pub fn write_unit_drop_in(
+    unit: &str,
+    file_name: &str,
+    contents: Option<&str>,
+) -> Result<(), String> {
+    let operation = if contents.is_some() {
+        "write-unit-drop-in"
+    } else {
+        "remove-unit-drop-in"
+    };
+
+    let mut cmd = helper(operation);
+    cmd.arg(unit).arg(file_name);
+    run(cmd, contents)
+}
+
+/// The drop-in directory of the system unit `unit`
+pub fn unit_drop_in_directory(unit: &str) -> String {
+    format!("{}/{}.d", SYSTEM_UNIT_DIRECTORY, unit)
+}
+
+/// Write the memory of `pid` to `<prefix>.<pid>` with `gcore`; `prefix` is a path on the host
+pub fn dump_memory(pid: Pid, prefix: &Path) -> Result<(), String> {
+    let mut cmd = helper("dump-memory");
+    cmd.arg(pid.to_string()).arg(prefix);
+    run(cmd, None)
+}
+
+/// `missioncenter-helper operation`, as root
+fn helper(operation: &str) -> std::process::Command {
+    let path = if is_flatpak() {
+        format!("{}/libexec/{}", flatpak_app_path(), HELPER_NAME)
+    } else {
+        format!("{}/{}", LIBEXECDIR, HELPER_NAME)
+    };
+
+    let mut cmd = pkexec();
+    cmd.arg(path).arg(operation);
+    cmd
+}
+
+/// `pkexec` on the host, the sandbox has no polkit agent to talk to
+fn pkexec() -> std::process::Command {
+    if is_flatpak() {
+        let mut cmd = std::process::Command::new("/usr/bin/flatpak-spawn");
+        cmd.arg("--host").arg("pkexec");
+        cmd
+    } else {
+        std::process::Command::new("pkexec")
+    }
+}
+
+/// Run `cmd`, handing it `input` if there is any
+fn run(mut cmd: std::process::Command, input: Option<&str>) -> Result<(), String> {
+    cmd.stdin(if input.is_some() {
+        std::process::Stdio::piped()
+    } else {
+        std::process::Stdio::null()
+    })
+    .stdout(std::process::Stdio::null())
+    .stderr(std::process::Stdio::piped());
+
+    let mut child = cmd.spawn().map_err(|e| e.to_string())?;
+    if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+        // An error shows up in what the helper exits with
+        let _ = stdin.write_all(input.as_bytes());
+    }
+
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+    match output.status.code() {
+        Some(0) => Ok(()),
+        Some(PKEXEC_DISMISSED) | Some(PKEXEC_NOT_AUTHORIZED) => Err("Not authorized".to_owned()),
+        _ => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            match stderr.lines().rev().find(|line| !line.trim().is_empty()) {
+                Some(line) => Err(line.trim().to_owned()),
+                None => Err(format!("pkexec exited with {}", output.status)),
+            }
+        }
+    }
+}
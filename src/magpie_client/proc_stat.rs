@@ -0,0 +1,202 @@
+/* magpie_client/proc_stat.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::HashMap;
+
+use super::cgroup::read_host_file;
+use super::Pid;
+use crate::is_flatpak;
+
+/// The kernel reports CPU times in clock ticks, which are 100 per second on every architecture
+/// Linux runs on
+pub const CLOCK_TICKS_PER_SECOND: u64 = 100;
+
+/// What Mission Center uses from `/proc/PID/stat`
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct ProcStat {
+    pub pid: Pid,
+    pub state: char,
+    pub parent: Pid,
+    /// Time spent in user space, in clock ticks
+    pub user_ticks: u64,
+    /// Time spent in the kernel on behalf of the process, in clock ticks
+    pub system_ticks: u64,
+    /// When the process started, in clock ticks since boot
+    pub start_ticks: u64,
+    /// Time spent waiting for block I/O, in clock ticks; only counted with delay accounting on
+    pub io_wait_ticks: u64,
+}
+
+impl ProcStat {
+    /// Zombies are done running, they only wait for their parent to collect them
+    pub fn is_running(&self) -> bool {
+        !matches!(self.state, 'Z' | 'X')
+    }
+}
+
+/// Parse one `/proc/PID/stat`
+pub fn parse(stat: &str) -> Option<ProcStat> {
+    // The command name is in parentheses and may contain anything, including parentheses and
+    // spaces, so count fields after the last closing one
+    let (pid, fields) = stat.rsplit_once(')')?;
+    let pid = pid.split_once(" (")?.0.trim().parse::<Pid>().ok()?;
+    let fields = fields.split_ascii_whitespace().collect::<Vec<_>>();
+    let field = |index: usize| fields.get(index).and_then(|f| f.parse::<u64>().ok());
+
+    Some(ProcStat {
+        pid,
+        state: fields.first()?.chars().next()?,
+        parent: field(1)? as Pid,
+        user_ticks: field(11)?,
+        system_ticks: field(12)?,
+        start_ticks: field(19)?,
+        io_wait_ticks: field(39).unwrap_or(0),
+    })
+}
+
+/// `/proc/PID/stat` of `pid`; `None` if the process is gone
+pub fn read(pid: Pid) -> Option<ProcStat> {
+    parse(&read_host_file(&format!("/proc/{}/stat", pid))?)
+}
+
+/// `/proc/PID/stat` of each of `pids` that is still around.
+///
+/// Processes come from the gatherer, which runs on the host; the Flatpak has a PID namespace of
+/// its own, so there they're read on the host, all of them at once
+pub fn read_all(pids: impl IntoIterator<Item = Pid>) -> HashMap<Pid, ProcStat> {
+    if !is_flatpak() {
+        return pids
+            .into_iter()
+            .filter_map(|pid| std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok())
+            .filter_map(|stat| parse(&stat))
+            .map(|stat| (stat.pid, stat))
+            .collect();
+    }
+
+    // Every file is a single line, which starts with the PID
+    read_on_host("cat", &[], pids, "stat")
+        .lines()
+        .filter_map(parse)
+        .map(|stat| (stat.pid, stat))
+        .collect()
+}
+
+/// Bytes read from and written to storage by each of `pids`, from `/proc/PID/io`; that can only
+/// be read for the processes of the user Mission Center runs as
+pub fn read_io_all(pids: impl IntoIterator<Item = Pid>) -> HashMap<Pid, u64> {
+    let mut result = HashMap::new();
+    let mut add = |pid: Pid, line: &str| {
+        let bytes = line
+            .strip_prefix("read_bytes:")
+            .or_else(|| line.strip_prefix("write_bytes:"))
+            .and_then(|bytes| bytes.trim().parse::<u64>().ok());
+        if let Some(bytes) = bytes {
+            *result.entry(pid).or_insert(0) += bytes;
+        }
+    };
+
+    if !is_flatpak() {
+        for pid in pids {
+            if let Ok(io) = std::fs::read_to_string(format!("/proc/{}/io", pid)) {
+                io.lines().for_each(|line| add(pid, line));
+            }
+        }
+        return result;
+    }
+
+    // `/proc/PID/io:read_bytes: 1234`
+    let output = read_on_host(
+        "grep",
+        &["-s", "-H", "-E", "^(read|write)_bytes:"],
+        pids,
+        "io",
+    );
+    for line in output.lines() {
+        let Some((path, line)) = line.split_once(':') else {
+            continue;
+        };
+        let pid = path
+            .strip_prefix("/proc/")
+            .and_then(|path| path.strip_suffix("/io"))
+            .and_then(|pid| pid.parse::<Pid>().ok());
+        if let Some(pid) = pid {
+            add(pid, line);
+        }
+    }
+
+    result
+}
+
+/// Run `program` on the host with `/proc/PID/<file>` of every one of `pids`; processes that are
+/// gone make it complain, and still print the others
+fn read_on_host(
+    program: &str,
+    args: &[&str],
+    pids: impl IntoIterator<Item = Pid>,
+    file: &str,
+) -> String {
+    let paths = pids
+        .into_iter()
+        .map(|pid| format!("/proc/{}/{}", pid, file))
+        .collect::<Vec<_>>();
+    if paths.is_empty() {
+        return String::new();
+    }
+
+    let output = std::process::Command::new("/usr/bin/flatpak-spawn")
+        .arg("--host")
+        .arg(program)
+        .args(args)
+        .args(paths)
+        .stderr(std::process::Stdio::null())
+        .output();
+    match output {
+        Ok(output) => String::from_utf8_lossy(&output.stdout).into_owned(),
+        Err(_) => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_counts_fields_after_the_name() {
+        let stat = "1234 (Web Content (1)) S 1200 1234 1200 0 -1 4194560 2619 0 0 0 150 42 0 0 \
+                    20 0 30 0 9876 1000000 500 18446744073709551615 1 1 0 0 0 0 0 4096 1260 \
+                    0 0 0 17 3 0 0 7 0 0";
+        let stat = parse(stat).unwrap();
+
+        assert_eq!(stat.pid, 1234);
+        assert_eq!(stat.state, 'S');
+        assert_eq!(stat.parent, 1200);
+        assert_eq!(stat.user_ticks, 150);
+        assert_eq!(stat.system_ticks, 42);
+        assert_eq!(stat.start_ticks, 9876);
+        assert_eq!(stat.io_wait_ticks, 7);
+        assert!(stat.is_running());
+    }
+
+    #[test]
+    fn parse_rejects_truncated_stat() {
+        assert_eq!(parse("1234 (cat) R 1"), None);
+        assert_eq!(parse(""), None);
+    }
+}
@@ -0,0 +1,197 @@
+/* magpie_client/process_details.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::path::PathBuf;
+
+use gtk::glib;
+
+use super::cgroup::read_host_file;
+use super::Pid;
+use crate::is_flatpak;
+
+/// What the kernel appends to the executable's path once the file is gone
+const DELETED_SUFFIX: &str = " (deleted)";
+
+/// Where a process comes from
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ProcessDetails {
+    pub pid: Pid,
+    /// Path of the executable, on the host or, for sandboxed apps, inside the sandbox; `None` for
+    /// kernel threads, and for other users' processes when not running as root
+    pub exe: Option<String>,
+    /// The executable was removed or replaced since the process started, usually by an update
+    pub exe_deleted: bool,
+    /// Where Mission Center itself can find the executable; `None` if it can't, like for the
+    /// host's executables from inside the Flatpak
+    pub exe_location: Option<String>,
+    /// The distribution package the executable was installed with
+    pub package: Option<String>,
+    /// The application ID of the Flatpak the process runs in
+    pub flatpak_id: Option<String>,
+    /// The name of the Snap the process runs in
+    pub snap_name: Option<String>,
+}
+
+pub fn read(pid: Pid) -> Result<ProcessDetails, String> {
+    let cgroup = read_host_file(&format!("/proc/{}/cgroup", pid))
+        .ok_or_else(|| format!("Process {} is not running", pid))?;
+
+    let (exe, exe_deleted) = match read_link(&format!("/proc/{}/exe", pid)) {
+        Some(exe) => match exe.strip_suffix(DELETED_SUFFIX) {
+            Some(exe) => (Some(exe.to_owned()), true),
+            None => (Some(exe), false),
+        },
+        None => (None, false),
+    };
+
+    let flatpak_id = flatpak_id(&cgroup);
+    let exe_location = match &exe {
+        Some(exe) if !exe_deleted => location(exe, flatpak_id.as_deref()),
+        _ => None,
+    };
+    let snap_name = exe.as_deref().and_then(snap_name);
+    // Sandboxed executables don't come from the host's packages
+    let package = match (&exe, &flatpak_id, &snap_name) {
+        (Some(exe), None, None) if !exe_deleted => package(exe),
+        _ => None,
+    };
+
+    Ok(ProcessDetails {
+        pid,
+        exe,
+        exe_deleted,
+        exe_location,
+        package,
+        flatpak_id,
+        snap_name,
+    })
+}
+
+/// Flatpak starts apps in a scope like `app-flatpak-org.gnome.Calculator-12345.scope`
+fn flatpak_id(cgroup: &str) -> Option<String> {
+    cgroup
+        .lines()
+        .find_map(|line| line.strip_prefix("0::"))?
+        .split('/')
+        .find_map(|component| {
+            let id = component
+                .strip_prefix("app-flatpak-")?
+                .strip_suffix(".scope")?;
+            // Drop the instance number
+            let (id, _) = id.rsplit_once('-')?;
+            Some(id.to_owned())
+        })
+}
+
+/// Flatpak apps see their own files under `/app`, which is where they were installed to
+/// outside the sandbox; the Flatpak of Mission Center has read access to both installations
+fn location(exe: &str, flatpak_id: Option<&str>) -> Option<String> {
+    let candidates = match flatpak_id {
+        Some(id) => {
+            // Anything else comes from the runtime, which has no stable path to point at
+            let file = exe.strip_prefix("/app/")?;
+            [
+                glib::home_dir().join(".local/share/flatpak"),
+                PathBuf::from("/var/lib/flatpak"),
+            ]
+            .into_iter()
+            .map(|installation| {
+                installation
+                    .join("app")
+                    .join(id)
+                    .join("current/active/files")
+                    .join(file)
+            })
+            .collect()
+        }
+        None => vec![PathBuf::from(exe)],
+    };
+
+    candidates
+        .into_iter()
+        .find(|candidate| candidate.exists())
+        .map(|candidate| candidate.to_string_lossy().into_owned())
+}
+
+/// Snaps are mounted under `/snap/<name>/<revision>`
+fn snap_name(exe: &str) -> Option<String> {
+    let name = exe.strip_prefix("/snap/")?.split('/').next()?;
+    (!name.is_empty()).then(|| name.to_owned())
+}
+
+/// Ask each package manager in turn which package installed `path`
+fn package(path: &str) -> Option<String> {
+    const QUERIES: &[&[&str]] = &[
+        &["dpkg-query", "--search"],
+        &["rpm", "--query", "--file", "--queryformat", "%{NAME}\\n"],
+        &["pacman", "--query", "--quiet", "--owns"],
+    ];
+
+    // Debian's merged /usr has files registered under their old path
+    let mut paths = vec![path];
+    if let Some(unmerged) = path.strip_prefix("/usr") {
+        paths.push(unmerged);
+    }
+
+    QUERIES.iter().find_map(|query| {
+        paths.iter().find_map(|path| {
+            let output = host_command(query).arg(path).output().ok()?;
+            if !output.status.success() {
+                return None;
+            }
+
+            let output = String::from_utf8_lossy(&output.stdout);
+            let line = output.lines().next()?.trim();
+            // `dpkg-query` prints `package[:arch]: path`, the others just the package
+            let package = line.split(':').next()?.trim();
+            (!package.is_empty()).then(|| package.to_owned())
+        })
+    })
+}
+
+fn read_link(path: &str) -> Option<String> {
+    if !is_flatpak() {
+        return std::fs::read_link(path)
+            .ok()
+            .map(|target| target.to_string_lossy().into_owned());
+    }
+
+    let output = host_command(&["readlink", path]).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let target = String::from_utf8_lossy(&output.stdout)
+        .trim_end()
+        .to_owned();
+    (!target.is_empty()).then_some(target)
+}
+
+pub(super) fn host_command(args: &[&str]) -> std::process::Command {
+    if is_flatpak() {
+        let mut cmd = std::process::Command::new("/usr/bin/flatpak-spawn");
+        cmd.arg("--host").args(args);
+        cmd
+    } else {
+        let mut cmd = std::process::Command::new(args[0]);
+        cmd.args(&args[1..]);
+        cmd
+    }
+}
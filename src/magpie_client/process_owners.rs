@@ -0,0 +1,120 @@
+/* magpie_client/process_owners.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::HashMap;
+use std::os::unix::fs::MetadataExt;
+use std::sync::{Arc, OnceLock};
+use std::time::SystemTime;
+
+use super::{Pid, Process};
+
+const PASSWD: &str = "/etc/passwd";
+/// Where the host's user database is, if the Flatpak was given access to it
+const HOST_PASSWD: &str = "/run/host/etc/passwd";
+
+/// The lowest UID `useradd` hands out to people, as set in the default `/etc/login.defs`
+const FIRST_REGULAR_UID: u32 = 1000;
+const NOBODY_UID: u32 = 65534;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProcessOwner {
+    pub uid: u32,
+    /// The UID itself when it has no entry in the user database
+    pub name: Arc<str>,
+}
+
+/// Daemons and the like, as opposed to the people using the machine
+pub fn is_system_uid(uid: u32) -> bool {
+    uid < FIRST_REGULAR_UID || uid == NOBODY_UID
+}
+
+/// The user Mission Center runs as
+pub fn current_uid() -> Option<u32> {
+    static CURRENT_UID: OnceLock<Option<u32>> = OnceLock::new();
+
+    *CURRENT_UID.get_or_init(|| {
+        std::fs::metadata("/proc/self")
+            .ok()
+            .map(|metadata| metadata.uid())
+    })
+}
+
+/// Looks up who each process runs as; user names are read again whenever the user database
+/// changes
+pub struct ProcessOwners {
+    names: HashMap<u32, Arc<str>>,
+    passwd_modified: Option<SystemTime>,
+}
+
+impl ProcessOwners {
+    pub fn new() -> Self {
+        Self {
+            names: HashMap::new(),
+            passwd_modified: None,
+        }
+    }
+
+    pub fn update(&mut self, processes: &HashMap<Pid, Process>) -> HashMap<Pid, ProcessOwner> {
+        self.refresh_names();
+
+        processes
+            .keys()
+            .filter_map(|pid| {
+                let uid = std::fs::metadata(format!("/proc/{}", pid)).ok()?.uid();
+                let name = self
+                    .names
+                    .entry(uid)
+                    .or_insert_with(|| Arc::from(uid.to_string()))
+                    .clone();
+
+                Some((*pid, ProcessOwner { uid, name }))
+            })
+            .collect()
+    }
+
+    fn refresh_names(&mut self) {
+        let path = if crate::is_flatpak() && std::path::Path::new(HOST_PASSWD).exists() {
+            HOST_PASSWD
+        } else {
+            PASSWD
+        };
+
+        let modified = std::fs::metadata(path).and_then(|m| m.modified()).ok();
+        if modified.is_some() && modified == self.passwd_modified {
+            return;
+        }
+        self.passwd_modified = modified;
+
+        let Ok(passwd) = std::fs::read_to_string(path) else {
+            return;
+        };
+
+        // name:password:UID:GID:GECOS:home:shell
+        self.names = passwd
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.split(':');
+                let name = fields.next()?;
+                let uid = fields.nth(1)?.parse::<u32>().ok()?;
+                Some((uid, Arc::from(name)))
+            })
+            .collect();
+    }
+}
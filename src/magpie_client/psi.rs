@@ -0,0 +1,78 @@
+/* magpie_client/psi.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct PressureAverages {
+    pub avg10: f32,
+    pub avg60: f32,
+    pub avg300: f32,
+}
+
+/// Pressure stall information for a resource, as reported in `/proc/pressure`; the averages are
+/// the percentage of time in which some (or all, for `full`) tasks were stalled on the resource
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct Pressure {
+    pub some: PressureAverages,
+    pub full: Option<PressureAverages>,
+}
+
+/// Read the pressure for `resource` (`cpu`, `memory` or `io`); `None` if the kernel was built
+/// without PSI support or it was disabled with `psi=0`
+pub fn read(resource: &str) -> Option<Pressure> {
+    let content = std::fs::read_to_string(format!("/proc/pressure/{}", resource)).ok()?;
+
+    let mut result = Pressure::default();
+    let mut found_some = false;
+    for line in content.lines() {
+        let mut fields = line.split_ascii_whitespace();
+        let kind = fields.next();
+
+        let mut averages = PressureAverages::default();
+        for field in fields {
+            let Some((key, value)) = field.split_once('=') else {
+                continue;
+            };
+            let Ok(value) = value.parse::<f32>() else {
+                continue;
+            };
+            match key {
+                "avg10" => averages.avg10 = value,
+                "avg60" => averages.avg60 = value,
+                "avg300" => averages.avg300 = value,
+                _ => {}
+            }
+        }
+
+        match kind {
+            Some("some") => {
+                result.some = averages;
+                found_some = true;
+            }
+            Some("full") => result.full = Some(averages),
+            _ => {}
+        }
+    }
+
+    if found_some {
+        Some(result)
+    } else {
+        None
+    }
+}
@@ -0,0 +1,498 @@
+/* magpie_client/recording.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+//! Saves what the gatherer answers to a file, and plays it back later in its place.
+//!
+//! A recording is a header followed by one frame per answer: what was asked for, in one byte,
+//! the milliseconds since the recording started, as a little endian `u64`, and the number of
+//! messages, as a little endian `u32`, followed by the messages themselves, length delimited.
+//!
+//! Only what comes from the gatherer is recorded; what the app reads from `/proc` itself is left
+//! out of a replay, like it is for remote hosts.
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::io::Write;
+use std::num::NonZeroU32;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use gtk::glib::g_warning;
+use magpie_types::processes::processes_response::process_map::NetworkStatsError;
+use magpie_types::prost::Message;
+
+use super::{
+    App, Connection, Cpu, Disk, ErrorEjectFailed, Fan, Gatherer, Gpu, Memory, MemoryDevice,
+    Process, Service, SmartData,
+};
+
+const HEADER: &[u8] = b"MissionCenterRecording1\n";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Recording {
+    /// Save the answers of the gatherer to a file
+    Record(PathBuf),
+    /// Play a recording back instead of starting the gatherer, `speed` times faster
+    Replay { path: PathBuf, speed: f32 },
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+#[repr(u8)]
+enum Kind {
+    Cpu = 0,
+    Memory = 1,
+    MemoryDevices = 2,
+    Disks = 3,
+    Fans = 4,
+    Connections = 5,
+    Gpus = 6,
+    Processes = 7,
+    Apps = 8,
+    Services = 9,
+}
+
+impl Kind {
+    const ALL: [Kind; 10] = [
+        Kind::Cpu,
+        Kind::Memory,
+        Kind::MemoryDevices,
+        Kind::Disks,
+        Kind::Fans,
+        Kind::Connections,
+        Kind::Gpus,
+        Kind::Processes,
+        Kind::Apps,
+        Kind::Services,
+    ];
+}
+
+/// Passes everything through to `inner`, writing down its answers on the way
+pub struct RecordingGatherer {
+    inner: Box<dyn Gatherer>,
+    file: RefCell<Option<std::io::BufWriter<std::fs::File>>>,
+    start: Instant,
+}
+
+impl RecordingGatherer {
+    pub fn new(inner: Box<dyn Gatherer>, path: &Path) -> Self {
+        let file = std::fs::File::create(path)
+            .and_then(|file| {
+                let mut file = std::io::BufWriter::new(file);
+                file.write_all(HEADER)?;
+                Ok(file)
+            })
+            .inspect_err(|e| {
+                g_warning!(
+                    "MissionCenter::Recording",
+                    "Failed to start recording to {}: {}",
+                    path.display(),
+                    e
+                );
+            })
+            .ok();
+
+        Self {
+            inner,
+            file: RefCell::new(file),
+            start: Instant::now(),
+        }
+    }
+
+    fn write<'a, M: Message + 'a>(
+        &self,
+        kind: Kind,
+        messages: impl ExactSizeIterator<Item = &'a M>,
+    ) {
+        let mut file = self.file.borrow_mut();
+        let Some(writer) = file.as_mut() else {
+            return;
+        };
+
+        let mut frame = vec![kind as u8];
+        frame.extend_from_slice(&(self.start.elapsed().as_millis() as u64).to_le_bytes());
+        frame.extend_from_slice(&(messages.len() as u32).to_le_bytes());
+        for message in messages {
+            // Only fails when out of space, and a `Vec` grows
+            let _ = message.encode_length_delimited(&mut frame);
+        }
+
+        if let Err(e) = writer.write_all(&frame).and_then(|_| writer.flush()) {
+            g_warning!(
+                "MissionCenter::Recording",
+                "Failed to write to the recording, it stops here: {}",
+                e
+            );
+            *file = None;
+        }
+    }
+}
+
+impl Gatherer for RecordingGatherer {
+    fn set_scale_cpu_usage_to_core_count(&self, v: bool) {
+        self.inner.set_scale_cpu_usage_to_core_count(v);
+    }
+
+    fn cpu(&self) -> Cpu {
+        let cpu = self.inner.cpu();
+        self.write(Kind::Cpu, std::iter::once(&cpu));
+        cpu
+    }
+
+    fn memory(&self) -> Memory {
+        let memory = self.inner.memory();
+        self.write(Kind::Memory, std::iter::once(&memory));
+        memory
+    }
+
+    fn memory_devices(&self) -> Vec<MemoryDevice> {
+        let devices = self.inner.memory_devices();
+        self.write(Kind::MemoryDevices, devices.iter());
+        devices
+    }
+
+    fn disks_info(&self) -> Vec<Disk> {
+        let disks = self.inner.disks_info();
+        self.write(Kind::Disks, disks.iter());
+        disks
+    }
+
+    fn eject_disk(&self, disk_id: String) -> Result<(), ErrorEjectFailed> {
+        self.inner.eject_disk(disk_id)
+    }
+
+    fn smart_data(&self, disk_id: String) -> Option<SmartData> {
+        self.inner.smart_data(disk_id)
+    }
+
+    fn fans_info(&self) -> Vec<Fan> {
+        let fans = self.inner.fans_info();
+        self.write(Kind::Fans, fans.iter());
+        fans
+    }
+
+    fn network_connections(&self) -> Vec<Connection> {
+        let connections = self.inner.network_connections();
+        self.write(Kind::Connections, connections.iter());
+        connections
+    }
+
+    fn gpus(&self) -> HashMap<String, Gpu> {
+        let gpus = self.inner.gpus();
+        self.write(Kind::Gpus, gpus.values());
+        gpus
+    }
+
+    fn processes(&self) -> (HashMap<u32, Process>, Option<NetworkStatsError>) {
+        let (processes, network_stats_error) = self.inner.processes();
+        self.write(Kind::Processes, processes.values());
+        (processes, network_stats_error)
+    }
+
+    fn apps(&self) -> HashMap<String, App> {
+        let apps = self.inner.apps();
+        self.write(Kind::Apps, apps.values());
+        apps
+    }
+
+    fn services(&self) -> HashMap<String, Service> {
+        let services = self.inner.services();
+        self.write(Kind::Services, services.values());
+        services
+    }
+
+    fn service_logs(&self, service_id: String, pid: Option<NonZeroU32>) -> String {
+        self.inner.service_logs(service_id, pid)
+    }
+
+    fn terminate_processes(&self, pids: Vec<u32>) {
+        self.inner.terminate_processes(pids);
+    }
+
+    fn kill_processes(&self, pids: Vec<u32>) {
+        self.inner.kill_processes(pids);
+    }
+
+    fn interrupt_processes(&self, pids: Vec<u32>) {
+        self.inner.interrupt_processes(pids);
+    }
+
+    fn signal_user_one_processes(&self, pids: Vec<u32>) {
+        self.inner.signal_user_one_processes(pids);
+    }
+
+    fn signal_user_two_processes(&self, pids: Vec<u32>) {
+        self.inner.signal_user_two_processes(pids);
+    }
+
+    fn hangup_processes(&self, pids: Vec<u32>) {
+        self.inner.hangup_processes(pids);
+    }
+
+    fn continue_processes(&self, pids: Vec<u32>) {
+        self.inner.continue_processes(pids);
+    }
+
+    fn suspend_processes(&self, pids: Vec<u32>) {
+        self.inner.suspend_processes(pids);
+    }
+
+    fn start_service(&self, service_id: String) {
+        self.inner.start_service(service_id);
+    }
+
+    fn stop_service(&self, service_id: String) {
+        self.inner.stop_service(service_id);
+    }
+
+    fn restart_service(&self, service_id: String) {
+        self.inner.restart_service(service_id);
+    }
+
+    fn enable_service(&self, service_id: String) {
+        self.inner.enable_service(service_id);
+    }
+
+    fn disable_service(&self, service_id: String) {
+        self.inner.disable_service(service_id);
+    }
+
+    fn request_count(&self) -> u64 {
+        self.inner.request_count()
+    }
+
+    fn restart(&self) -> bool {
+        self.inner.restart()
+    }
+}
+
+struct Frame {
+    time_ms: u64,
+    count: u32,
+    messages: Vec<u8>,
+}
+
+/// Answers with what was recorded at the same point in time, or with the last answers once the
+/// recording is over; acting on processes and services does nothing
+pub struct ReplayGatherer {
+    frames: HashMap<Kind, Vec<Frame>>,
+    start: Instant,
+    speed: f32,
+    over: Cell<bool>,
+}
+
+impl ReplayGatherer {
+    pub fn new(path: &Path, speed: f32) -> Self {
+        let frames = std::fs::read(path)
+            .map_err(|e| e.to_string())
+            .and_then(|content| read_frames(&content))
+            .unwrap_or_else(|e| {
+                g_warning!(
+                    "MissionCenter::Recording",
+                    "Failed to read the recording {}: {}",
+                    path.display(),
+                    e
+                );
+                HashMap::new()
+            });
+
+        Self {
+            frames,
+            start: Instant::now(),
+            speed,
+            over: Cell::new(false),
+        }
+    }
+
+    fn read<M: Message + Default>(&self, kind: Kind) -> Vec<M> {
+        let Some(frames) = self.frames.get(&kind).filter(|frames| !frames.is_empty()) else {
+            return vec![];
+        };
+
+        let now_ms = (self.start.elapsed().as_secs_f32() * self.speed * 1000.) as u64;
+        let index = frames
+            .partition_point(|frame| frame.time_ms <= now_ms)
+            .saturating_sub(1);
+        if index + 1 == frames.len() && !self.over.replace(true) {
+            g_warning!(
+                "MissionCenter::Recording",
+                "The recording is over, the last readings stay"
+            );
+        }
+
+        let frame = &frames[index];
+        let mut messages = frame.messages.as_slice();
+        let mut result = Vec::with_capacity(frame.count as usize);
+        for _ in 0..frame.count {
+            match M::decode_length_delimited(&mut messages) {
+                Ok(message) => result.push(message),
+                Err(e) => {
+                    g_warning!(
+                        "MissionCenter::Recording",
+                        "Failed to decode a recorded message: {}",
+                        e
+                    );
+                    break;
+                }
+            }
+        }
+
+        result
+    }
+}
+
+fn read_frames(content: &[u8]) -> Result<HashMap<Kind, Vec<Frame>>, String> {
+    let Some(mut content) = content.strip_prefix(HEADER) else {
+        return Err("Not a Mission Center recording".to_owned());
+    };
+
+    let mut frames = HashMap::<Kind, Vec<Frame>>::new();
+    while !content.is_empty() {
+        if content.len() < 13 {
+            return Err("The last frame is cut short".to_owned());
+        }
+
+        let kind = Kind::ALL
+            .into_iter()
+            .find(|kind| *kind as u8 == content[0])
+            .ok_or_else(|| format!("Unknown kind of frame: {}", content[0]))?;
+        let time_ms = u64::from_le_bytes(content[1..9].try_into().unwrap_or_default());
+        let count = u32::from_le_bytes(content[9..13].try_into().unwrap_or_default());
+        content = &content[13..];
+
+        // Walk over the messages to find where the next frame starts
+        let mut rest = content;
+        for _ in 0..count {
+            let length = magpie_types::prost::decode_length_delimiter(&mut rest)
+                .map_err(|e| e.to_string())?;
+            if rest.len() < length {
+                return Err("The last frame is cut short".to_owned());
+            }
+            rest = &rest[length..];
+        }
+        let messages = content[..content.len() - rest.len()].to_vec();
+        content = rest;
+
+        frames.entry(kind).or_default().push(Frame {
+            time_ms,
+            count,
+            messages,
+        });
+    }
+
+    Ok(frames)
+}
+
+impl Gatherer for ReplayGatherer {
+    // Recorded after scaling
+    fn set_scale_cpu_usage_to_core_count(&self, _v: bool) {}
+
+    fn cpu(&self) -> Cpu {
+        self.read(Kind::Cpu).pop().unwrap_or_default()
+    }
+
+    fn memory(&self) -> Memory {
+        self.read(Kind::Memory).pop().unwrap_or_default()
+    }
+
+    fn memory_devices(&self) -> Vec<MemoryDevice> {
+        self.read(Kind::MemoryDevices)
+    }
+
+    fn disks_info(&self) -> Vec<Disk> {
+        self.read(Kind::Disks)
+    }
+
+    fn eject_disk(&self, _disk_id: String) -> Result<(), ErrorEjectFailed> {
+        Ok(())
+    }
+
+    fn smart_data(&self, _disk_id: String) -> Option<SmartData> {
+        None
+    }
+
+    fn fans_info(&self) -> Vec<Fan> {
+        self.read(Kind::Fans)
+    }
+
+    fn network_connections(&self) -> Vec<Connection> {
+        self.read(Kind::Connections)
+    }
+
+    fn gpus(&self) -> HashMap<String, Gpu> {
+        self.read::<Gpu>(Kind::Gpus)
+            .into_iter()
+            .map(|gpu| (gpu.id.clone(), gpu))
+            .collect()
+    }
+
+    fn processes(&self) -> (HashMap<u32, Process>, Option<NetworkStatsError>) {
+        let processes = self
+            .read::<Process>(Kind::Processes)
+            .into_iter()
+            .map(|process| (process.pid, process))
+            .collect();
+
+        (processes, None)
+    }
+
+    fn apps(&self) -> HashMap<String, App> {
+        self.read::<App>(Kind::Apps)
+            .into_iter()
+            .map(|app| (app.id.clone(), app))
+            .collect()
+    }
+
+    fn services(&self) -> HashMap<String, Service> {
+        self.read::<Service>(Kind::Services)
+            .into_iter()
+            .map(|service| (service.id.clone(), service))
+            .collect()
+    }
+
+    fn service_logs(&self, _service_id: String, _pid: Option<NonZeroU32>) -> String {
+        String::new()
+    }
+
+    fn terminate_processes(&self, _pids: Vec<u32>) {}
+
+    fn kill_processes(&self, _pids: Vec<u32>) {}
+
+    fn interrupt_processes(&self, _pids: Vec<u32>) {}
+
+    fn signal_user_one_processes(&self, _pids: Vec<u32>) {}
+
+    fn signal_user_two_processes(&self, _pids: Vec<u32>) {}
+
+    fn hangup_processes(&self, _pids: Vec<u32>) {}
+
+    fn continue_processes(&self, _pids: Vec<u32>) {}
+
+    fn suspend_processes(&self, _pids: Vec<u32>) {}
+
+    fn start_service(&self, _service_id: String) {}
+
+    fn stop_service(&self, _service_id: String) {}
+
+    fn restart_service(&self, _service_id: String) {}
+
+    fn enable_service(&self, _service_id: String) {}
+
+    fn disable_service(&self, _service_id: String) {}
+}
@@ -0,0 +1,161 @@
+/* magpie_client/remote_host.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::fmt::{Display, Formatter};
+
+/// How the gatherer on a remote host is reached
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum HostTransport {
+    /// Started over SSH, with its socket forwarded to this machine
+    #[default]
+    Ssh,
+    /// Already running on the host, listening on a TCP port
+    Tcp,
+    /// Already running on the host, behind a TLS terminating proxy
+    Tls,
+}
+
+impl HostTransport {
+    /// The URL scheme hosts using the transport are entered with, if any
+    fn scheme(&self) -> Option<&'static str> {
+        match self {
+            HostTransport::Ssh => None,
+            HostTransport::Tcp => Some("tcp://"),
+            HostTransport::Tls => Some("tls+tcp://"),
+        }
+    }
+}
+
+/// A machine whose gatherer is read from, instead of the one of this machine
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RemoteHost {
+    pub name: String,
+    pub transport: HostTransport,
+    pub user: Option<String>,
+    pub hostname: String,
+    /// Required for the TCP and TLS transports, the gatherer has no well known port
+    pub port: Option<u16>,
+    /// Over SSH the private key to log in with, when missing the SSH agent and config decide;
+    /// over TLS the certificate authority to check the host against, when missing the system ones
+    /// are used
+    pub identity_file: Option<String>,
+}
+
+impl RemoteHost {
+    /// Builds a host from what the user typed in; `destination` is `[user@]hostname[:port]` for
+    /// SSH, or `tcp://hostname:port` and `tls+tcp://hostname:port` for a gatherer that is already
+    /// listening
+    pub fn new(name: &str, destination: &str, identity_file: &str) -> Option<Self> {
+        let name = name.trim();
+        let destination = destination.trim();
+        let identity_file = identity_file.trim();
+
+        // These are the separators used when storing the host list
+        if name.is_empty() || name.contains([';', '=', ',']) || identity_file.contains([';', ',']) {
+            return None;
+        }
+
+        let (transport, destination) = [HostTransport::Tcp, HostTransport::Tls]
+            .into_iter()
+            .find_map(|transport| {
+                let scheme = transport.scheme()?;
+                Some((transport, destination.strip_prefix(scheme)?))
+            })
+            .unwrap_or((HostTransport::Ssh, destination));
+
+        let (user, host_port) = match destination.rsplit_once('@') {
+            Some((user, host_port)) if !user.is_empty() => (Some(user.to_owned()), host_port),
+            Some(_) => return None,
+            None => (None, destination),
+        };
+
+        let (hostname, port) = match host_port.rsplit_once(':') {
+            Some((hostname, port)) => (hostname, Some(port.parse::<u16>().ok()?)),
+            None => (host_port, None),
+        };
+        if hostname.is_empty() || hostname.contains([';', ',', '/', ' ']) {
+            return None;
+        }
+        // Nothing to log in to when connecting straight to the gatherer
+        if transport != HostTransport::Ssh && (user.is_some() || port.is_none()) {
+            return None;
+        }
+
+        Some(Self {
+            name: name.to_owned(),
+            transport,
+            user,
+            hostname: hostname.to_owned(),
+            port,
+            identity_file: if identity_file.is_empty() {
+                None
+            } else {
+                Some(identity_file.to_owned())
+            },
+        })
+    }
+
+    /// Parses a `name=[user@]hostname[:port][,identity-file]` entry
+    pub fn parse(entry: &str) -> Option<Self> {
+        let (name, rest) = entry.split_once('=')?;
+        let (destination, identity_file) = rest.split_once(',').unwrap_or((rest, ""));
+
+        Self::new(name, destination, identity_file)
+    }
+
+    /// Parses a semicolon separated list of entries, skipping the ones that are malformed
+    pub fn parse_list(list: &str) -> Vec<Self> {
+        list.split(';')
+            .filter(|entry| !entry.is_empty())
+            .filter_map(Self::parse)
+            .collect()
+    }
+
+    /// `[user@]hostname[:port]`, prefixed with the scheme of the transport unless it is SSH, as
+    /// shown to the user
+    pub fn destination(&self) -> String {
+        let mut destination = String::new();
+        if let Some(scheme) = self.transport.scheme() {
+            destination.push_str(scheme);
+        }
+        if let Some(user) = &self.user {
+            destination.push_str(user);
+            destination.push('@');
+        }
+        destination.push_str(&self.hostname);
+        if let Some(port) = self.port {
+            destination.push(':');
+            destination.push_str(&port.to_string());
+        }
+
+        destination
+    }
+}
+
+impl Display for RemoteHost {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}={}", self.name, self.destination())?;
+        if let Some(identity_file) = &self.identity_file {
+            write!(f, ",{}", identity_file)?;
+        }
+
+        Ok(())
+    }
+}
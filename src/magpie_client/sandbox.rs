@@ -0,0 +1,181 @@
+/* magpie_client/sandbox.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use gtk::glib;
+
+use super::App;
+
+const SYSTEM_FLATPAK_INSTALLATION: &str = "/var/lib/flatpak";
+const SNAP_MOUNT_DIR: &str = "/snap";
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SandboxKind {
+    Flatpak,
+    Snap,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sandbox {
+    pub kind: SandboxKind,
+    /// The application ID of a Flatpak, or the name of a Snap
+    pub id: String,
+}
+
+/// What an app is allowed to do outside of its sandbox
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct SandboxPermissions {
+    /// Only for Snaps: `strict`, `devmode`, or `classic` for Snaps that aren't confined at all
+    pub confinement: Option<String>,
+    /// e.g. `home`, `xdg-download` or `/media:ro`
+    pub filesystems: Vec<String>,
+    /// e.g. `dri` or `all`
+    pub devices: Vec<String>,
+    /// e.g. `wayland`, `pulseaudio` or `ssh-auth`
+    pub sockets: Vec<String>,
+    /// `network` and `ipc`
+    pub shared: Vec<String>,
+}
+
+/// Finds out which apps were installed as a Flatpak or a Snap, going by their IDs, which are
+/// the names of their desktop files; the Flatpak of Mission Center can read both installations
+pub struct AppSandboxes {
+    known: HashMap<String, Option<Sandbox>>,
+}
+
+impl AppSandboxes {
+    pub fn new() -> Self {
+        Self {
+            known: HashMap::new(),
+        }
+    }
+
+    pub fn update(&mut self, apps: &HashMap<String, App>) -> HashMap<String, Sandbox> {
+        self.known.retain(|id, _| apps.contains_key(id));
+
+        apps.keys()
+            .filter_map(|id| {
+                self.known
+                    .entry(id.clone())
+                    .or_insert_with(|| detect(id))
+                    .clone()
+                    .map(|sandbox| (id.clone(), sandbox))
+            })
+            .collect()
+    }
+}
+
+fn detect(app_id: &str) -> Option<Sandbox> {
+    if flatpak_directory(app_id).is_some() {
+        return Some(Sandbox {
+            kind: SandboxKind::Flatpak,
+            id: app_id.to_owned(),
+        });
+    }
+
+    // Snaps export their desktop files as `<snap>_<app>.desktop`
+    let (snap, _) = app_id.split_once('_')?;
+    if snap.is_empty() || !PathBuf::from(SNAP_MOUNT_DIR).join(snap).is_dir() {
+        return None;
+    }
+
+    Some(Sandbox {
+        kind: SandboxKind::Snap,
+        id: snap.to_owned(),
+    })
+}
+
+/// The deployed app, from the per-user installation first since that is what `flatpak run`
+/// prefers too
+fn flatpak_directory(app_id: &str) -> Option<PathBuf> {
+    if app_id.is_empty() || app_id.contains('/') {
+        return None;
+    }
+
+    [
+        glib::home_dir().join(".local/share/flatpak"),
+        PathBuf::from(SYSTEM_FLATPAK_INSTALLATION),
+    ]
+    .into_iter()
+    .map(|installation| installation.join("app").join(app_id).join("current/active"))
+    .find(|directory| directory.is_dir())
+}
+
+/// Read the permissions the app was built with; changes made with `flatpak override` or
+/// Flatseal aren't included
+pub fn permissions(sandbox: &Sandbox) -> Result<SandboxPermissions, String> {
+    match sandbox.kind {
+        SandboxKind::Flatpak => flatpak_permissions(&sandbox.id),
+        SandboxKind::Snap => snap_permissions(&sandbox.id),
+    }
+}
+
+fn flatpak_permissions(app_id: &str) -> Result<SandboxPermissions, String> {
+    let metadata = flatpak_directory(app_id)
+        .map(|directory| directory.join("metadata"))
+        .ok_or_else(|| format!("{} is not installed", app_id))?;
+    let metadata = ini::Ini::load_from_file(&metadata).map_err(|e| e.to_string())?;
+
+    let list = |key: &str| {
+        metadata
+            .get_from(Some("Context"), key)
+            .map(|value| {
+                value
+                    .split(';')
+                    // Entries starting with `!` take away what the runtime would grant
+                    .filter(|entry| !entry.is_empty() && !entry.starts_with('!'))
+                    .map(|entry| entry.to_owned())
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    Ok(SandboxPermissions {
+        confinement: None,
+        filesystems: list("filesystems"),
+        devices: list("devices"),
+        sockets: list("sockets"),
+        shared: list("shared"),
+    })
+}
+
+/// The interfaces a Snap is connected to are up to snapd, only its confinement is a property of
+/// the Snap itself
+fn snap_permissions(name: &str) -> Result<SandboxPermissions, String> {
+    let path = PathBuf::from(SNAP_MOUNT_DIR)
+        .join(name)
+        .join("current/meta/snap.yaml");
+    let snap_yaml = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+
+    // A top level key, so it isn't indented
+    let confinement = snap_yaml
+        .lines()
+        .find_map(|line| line.strip_prefix("confinement:"))
+        .map(|value| value.trim().trim_matches(['"', '\'']).to_owned())
+        // Left out for the default
+        .unwrap_or_else(|| "strict".to_owned());
+
+    Ok(SandboxPermissions {
+        confinement: Some(confinement),
+        ..Default::default()
+    })
+}
@@ -0,0 +1,206 @@
+/* magpie_client/sandboxed_services.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use gtk::gio;
+use gtk::glib::{self, g_debug, prelude::*};
+
+use super::process_details::host_command;
+use super::{Pid, SandboxKind, Service};
+
+/// Neither changes often, and running `snap` and `flatpak` with every reading would cost more
+/// than reading the services themselves
+const REFRESH_INTERVAL: Duration = Duration::from_secs(10);
+const PORTAL_CALL_TIMEOUT_MS: i32 = 1000;
+
+struct SnapService {
+    /// `<snap>.<app>`, as `snap services` lists it
+    name: String,
+    enabled: bool,
+    active: bool,
+}
+
+/// Finds the daemons that run in a Snap or a Flatpak. Snap services are systemd units that
+/// only need to be marked, and are added in case systemd didn't list them; Flatpak apps running
+/// in the background have no unit and are added as services of their own, by application ID
+pub struct SandboxedServices {
+    refreshed: Option<Instant>,
+    snap_services: Vec<SnapService>,
+    background_apps: Vec<Service>,
+}
+
+impl SandboxedServices {
+    pub fn new() -> Self {
+        Self {
+            refreshed: None,
+            snap_services: vec![],
+            background_apps: vec![],
+        }
+    }
+
+    /// Add what systemd doesn't list to `services` and tell which of them come from a sandbox
+    pub fn update(
+        &mut self,
+        services: &mut HashMap<String, Service>,
+    ) -> HashMap<String, SandboxKind> {
+        if self
+            .refreshed
+            .map_or(true, |refreshed| refreshed.elapsed() >= REFRESH_INTERVAL)
+        {
+            self.snap_services = snap_services();
+            self.background_apps = background_apps();
+            self.refreshed = Some(Instant::now());
+        }
+
+        let mut sources = HashMap::new();
+
+        for snap_service in &self.snap_services {
+            let id = format!("snap.{}.service", snap_service.name);
+            services.entry(id.clone()).or_insert_with(|| Service {
+                id: id.clone(),
+                description: Some(snap_service.name.clone()),
+                enabled: snap_service.enabled,
+                running: snap_service.active,
+                ..Default::default()
+            });
+            sources.insert(id, SandboxKind::Snap);
+        }
+
+        for app in &self.background_apps {
+            if services.contains_key(&app.id) {
+                continue;
+            }
+            services.insert(app.id.clone(), app.clone());
+            sources.insert(app.id.clone(), SandboxKind::Flatpak);
+        }
+
+        sources
+    }
+}
+
+fn snap_services() -> Vec<SnapService> {
+    let output = match host_command(&["snap", "services"]).output() {
+        Ok(output) if output.status.success() => output,
+        // No snapd, which is the case on most systems
+        _ => return vec![],
+    };
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        // The header: Service, Startup, Current, Notes
+        .skip(1)
+        .filter_map(|line| {
+            let mut columns = line.split_whitespace();
+            let name = columns.next()?.to_owned();
+            let enabled = columns.next()? == "enabled";
+            let active = columns.next()? == "active";
+            Some(SnapService {
+                name,
+                enabled,
+                active,
+            })
+        })
+        .collect()
+}
+
+/// The Flatpak apps the background portal reports as running without a window
+fn background_apps() -> Vec<Service> {
+    let connection = match gio::bus_get_sync(gio::BusType::Session, gio::Cancellable::NONE) {
+        Ok(connection) => connection,
+        Err(e) => {
+            g_debug!(
+                "MissionCenter::SysInfo",
+                "Failed to connect to the session bus: {}",
+                e
+            );
+            return vec![];
+        }
+    };
+
+    let apps = match connection.call_sync(
+        Some("org.freedesktop.background.Monitor"),
+        "/org/freedesktop/background/monitor",
+        "org.freedesktop.DBus.Properties",
+        "Get",
+        Some(&("org.freedesktop.background.Monitor", "BackgroundApps").to_variant()),
+        Some(glib::VariantTy::new("(v)").unwrap()),
+        gio::DBusCallFlags::NO_AUTO_START,
+        PORTAL_CALL_TIMEOUT_MS,
+        gio::Cancellable::NONE,
+    ) {
+        Ok(reply) => match reply.child_value(0).as_variant() {
+            Some(apps) => apps,
+            None => return vec![],
+        },
+        Err(e) => {
+            // Only portals from 1.16 on monitor background apps
+            g_debug!(
+                "MissionCenter::SysInfo",
+                "Failed to list background apps: {}",
+                e
+            );
+            return vec![];
+        }
+    };
+
+    let pids = instance_pids();
+
+    apps.iter()
+        .filter_map(|app| {
+            let app = glib::VariantDict::new(Some(&app));
+            let id = app.lookup::<String>("app_id").ok().flatten()?;
+            let instance = app.lookup::<String>("instance").ok().flatten();
+            let message = app
+                .lookup::<String>("message")
+                .ok()
+                .flatten()
+                .filter(|message| !message.is_empty());
+
+            Some(Service {
+                description: Some(message.unwrap_or_else(|| id.clone())),
+                pid: instance.and_then(|instance| pids.get(&instance).copied()),
+                id,
+                running: true,
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
+/// The process of the app in each running Flatpak instance
+fn instance_pids() -> HashMap<String, Pid> {
+    let output = match host_command(&["flatpak", "ps", "--columns=instance,child-pid"]).output() {
+        Ok(output) if output.status.success() => output,
+        _ => return HashMap::new(),
+    };
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let mut columns = line.split_whitespace();
+            let instance = columns.next()?.to_owned();
+            // Also skips the header, if there is one
+            let pid = columns.next()?.parse().ok()?;
+            Some((instance, pid))
+        })
+        .collect()
+}
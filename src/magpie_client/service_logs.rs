@@ -0,0 +1,87 @@
+/* magpie_client/service_logs.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::num::NonZeroU32;
+
+use super::gatherer::Gatherer;
+use super::journal;
+use super::kernel_log::{PRIORITY_DEBUG, PRIORITY_EMERGENCY};
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ServiceLogEntry {
+    /// Microseconds since the Unix epoch; `None` for logs that only came as text
+    pub timestamp: Option<u64>,
+    /// `None` for logs that only came as text, which can't be filtered by severity
+    pub priority: Option<u8>,
+    pub message: String,
+}
+
+/// The last `lines` messages of `service_id` at least as severe as `max_priority`, oldest first
+///
+/// The journal of this machine is asked directly, for the messages of all processes of the
+/// service; the gatherer of a remote host only hands out the log of the service's main process
+/// as text, which is cut down to `lines` but can't be filtered
+pub fn read(
+    magpie: &dyn Gatherer,
+    local: bool,
+    service_id: String,
+    pid: Option<NonZeroU32>,
+    max_priority: u8,
+    lines: u32,
+) -> Result<Vec<ServiceLogEntry>, String> {
+    if !local {
+        let logs = magpie.service_logs(service_id, pid);
+        let all_lines = logs.lines().collect::<Vec<_>>();
+        let first = all_lines.len().saturating_sub(lines as usize);
+
+        return Ok(all_lines[first..]
+            .iter()
+            .map(|line| ServiceLogEntry {
+                timestamp: None,
+                priority: None,
+                message: line.to_string(),
+            })
+            .collect());
+    }
+
+    let entries = journal::query(&[
+        format!("--unit={}", service_id),
+        "--output-fields=PRIORITY,MESSAGE".to_owned(),
+        format!(
+            "--priority={}..{}",
+            PRIORITY_EMERGENCY,
+            max_priority.min(PRIORITY_DEBUG)
+        ),
+        format!("--lines={}", lines),
+    ])?;
+
+    Ok(entries
+        .into_iter()
+        .filter_map(|entry| {
+            Some(ServiceLogEntry {
+                timestamp: entry
+                    .get("__REALTIME_TIMESTAMP")
+                    .and_then(|ts| ts.parse().ok()),
+                priority: entry.get("PRIORITY").and_then(|p| p.parse().ok()),
+                message: entry.get("MESSAGE")?.trim_end().to_owned(),
+            })
+        })
+        .collect())
+}
@@ -0,0 +1,273 @@
+/* magpie_client/service_override.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use gtk::glib;
+
+use super::cgroup::read_host_file;
+use super::privileged;
+
+/// The drop-in Mission Center writes to; `systemctl edit` uses `override.conf`, which is left
+/// alone so hand-made changes aren't lost
+const DROP_IN_FILE_NAME: &str = "50-mission-center.conf";
+
+/// What `Restart=` can be set to
+pub const RESTART_POLICIES: &[&str] = &[
+    "no",
+    "on-success",
+    "on-failure",
+    "on-abnormal",
+    "on-watchdog",
+    "on-abort",
+    "always",
+];
+
+/// The common tweaks made to a service without editing its unit file
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ServiceOverride {
+    /// Variables added to the environment of the service, by name
+    pub environment: Vec<(String, String)>,
+    /// One of `RESTART_POLICIES`; `None` keeps what the unit file says
+    pub restart: Option<String>,
+    /// Percent of the time of one CPU, more than 100 for several
+    pub cpu_quota: Option<u32>,
+    /// Bytes
+    pub memory_max: Option<u64>,
+}
+
+impl ServiceOverride {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// What Mission Center set for `service_id` before; the defaults if it never did. Only systemd
+/// services have drop-ins, not the Flatpak apps listed with them
+pub fn read(service_id: &str) -> Result<ServiceOverride, String> {
+    if !service_id.ends_with(".service") {
+        return Err(format!("{} is not a systemd service", service_id));
+    }
+
+    let path = format!(
+        "{}/{}",
+        privileged::unit_drop_in_directory(service_id),
+        DROP_IN_FILE_NAME
+    );
+    Ok(read_host_file(&path)
+        .map(|contents| parse(&contents))
+        .unwrap_or_default())
+}
+
+/// Write the drop-in and reload systemd, which asks for authorization through polkit; an empty
+/// override removes it. It applies the next time the service starts
+pub fn write(service_id: &str, service_override: &ServiceOverride) -> Result<(), String> {
+    if service_override.is_empty() {
+        return privileged::write_unit_drop_in(service_id, DROP_IN_FILE_NAME, None);
+    }
+
+    let contents = render(service_override)?;
+    privileged::write_unit_drop_in(service_id, DROP_IN_FILE_NAME, Some(&contents))
+}
+
+/// Like `write`, on a thread of its own since waiting for authorization can take a while; `done`
+/// is handed the outcome on the main thread
+pub fn write_in_background(
+    service_id: String,
+    service_override: ServiceOverride,
+    done: impl FnOnce(Result<(), String>) + Send + 'static,
+) {
+    std::thread::spawn(move || {
+        let result = write(&service_id, &service_override);
+        glib::idle_add_once(move || done(result));
+    });
+}
+
+fn render(service_override: &ServiceOverride) -> Result<String, String> {
+    let mut contents = "# Written by Mission Center\n[Service]\n".to_owned();
+
+    for (name, value) in &service_override.environment {
+        let valid_name = name
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid_name {
+            return Err(format!("Not a valid environment variable name: {}", name));
+        }
+        if value.contains(['\n', '\r']) {
+            return Err(format!("The value of {} can't span several lines", name));
+        }
+
+        // Quoted, so spaces are kept; `%` starts a specifier
+        let value = value
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('%', "%%");
+        contents.push_str(&format!("Environment=\"{}={}\"\n", name, value));
+    }
+
+    if let Some(restart) = &service_override.restart {
+        if !RESTART_POLICIES.contains(&restart.as_str()) {
+            return Err(format!("Not a restart policy: {}", restart));
+        }
+        contents.push_str(&format!("Restart={}\n", restart));
+    }
+
+    if let Some(cpu_quota) = service_override.cpu_quota {
+        contents.push_str(&format!("CPUQuota={}%\n", cpu_quota.max(1)));
+    }
+
+    if let Some(memory_max) = service_override.memory_max {
+        contents.push_str(&format!("MemoryMax={}\n", memory_max));
+    }
+
+    Ok(contents)
+}
+
+/// Only reads back what `render` writes
+fn parse(contents: &str) -> ServiceOverride {
+    let mut service_override = ServiceOverride::default();
+
+    for line in contents.lines() {
+        let Some((key, value)) = line.trim().split_once('=') else {
+            continue;
+        };
+
+        match key {
+            "Environment" => {
+                let value = value
+                    .strip_prefix('"')
+                    .and_then(|value| value.strip_suffix('"'))
+                    .unwrap_or(value);
+                let value = unescape(value).replace("%%", "%");
+                if let Some((name, value)) = value.split_once('=') {
+                    service_override
+                        .environment
+                        .push((name.to_owned(), value.to_owned()));
+                }
+            }
+            "Restart" => service_override.restart = Some(value.to_owned()),
+            "CPUQuota" => {
+                service_override.cpu_quota = value.trim_end_matches('%').parse().ok();
+            }
+            "MemoryMax" => service_override.memory_max = value.parse().ok(),
+            _ => {}
+        }
+    }
+
+    service_override
+}
+
+fn unescape(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => result.extend(chars.next()),
+            c => result.push(c),
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_environment(environment: &[(&str, &str)]) -> ServiceOverride {
+        ServiceOverride {
+            environment: environment
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let service_override = ServiceOverride {
+            environment: vec![("RUST_LOG".to_owned(), "debug".to_owned())],
+            restart: Some("on-failure".to_owned()),
+            cpu_quota: Some(150),
+            memory_max: Some(512 * 1024 * 1024),
+        };
+
+        let contents = render(&service_override).unwrap();
+        assert_eq!(
+            contents,
+            "# Written by Mission Center\n\
+             [Service]\n\
+             Environment=\"RUST_LOG=debug\"\n\
+             Restart=on-failure\n\
+             CPUQuota=150%\n\
+             MemoryMax=536870912\n"
+        );
+        assert_eq!(parse(&contents), service_override);
+    }
+
+    #[test]
+    fn round_trip_special_characters() {
+        let service_override = with_environment(&[
+            ("QUOTES", r#"say "hello""#),
+            ("PERCENT", "100% and %h and %%"),
+            ("SPACES", "  leading and trailing  "),
+            ("BACKSLASHES", r"C:\path\ and \n and \"),
+            ("MIXED", r#"\%%"\""#),
+            ("EQUALS", "a=b=c"),
+            ("EMPTY", ""),
+        ]);
+
+        let contents = render(&service_override).unwrap();
+        assert_eq!(parse(&contents), service_override);
+    }
+
+    #[test]
+    fn render_escapes() {
+        let contents = render(&with_environment(&[("A", r#"1" 2% 3\"#)])).unwrap();
+        assert!(contents.contains(r#"Environment="A=1\" 2%% 3\\""#));
+    }
+
+    #[test]
+    fn render_rejects_invalid_input() {
+        assert!(render(&with_environment(&[("1ABC", "x")])).is_err());
+        assert!(render(&with_environment(&[("A B", "x")])).is_err());
+        assert!(render(&with_environment(&[("", "x")])).is_err());
+        assert!(render(&with_environment(&[("A", "two\nlines")])).is_err());
+
+        let service_override = ServiceOverride {
+            restart: Some("sometimes".to_owned()),
+            ..Default::default()
+        };
+        assert!(render(&service_override).is_err());
+    }
+
+    #[test]
+    fn parse_ignores_unknown_lines() {
+        let service_override = parse("[Service]\nUser=nobody\n# Restart=always\nMemoryMax=1024\n");
+        assert_eq!(
+            service_override,
+            ServiceOverride {
+                memory_max: Some(1024),
+                ..Default::default()
+            }
+        );
+    }
+}
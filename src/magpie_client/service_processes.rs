@@ -0,0 +1,92 @@
+/* magpie_client/service_processes.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::HashSet;
+
+use gtk::gio;
+use gtk::glib::{self, prelude::*};
+
+use super::{proc_stat, Pid};
+
+const SYSTEMD_CALL_TIMEOUT_MS: i32 = 1000;
+
+/// A process in the control group of a service
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ServiceProcess {
+    pub pid: Pid,
+    /// The process that started it, if that is part of the service too
+    pub parent: Option<Pid>,
+    pub command_line: String,
+}
+
+/// Ask systemd for the processes in the control group of `service_id`, including those of
+/// its sub-cgroups; empty for services that aren't running
+pub fn read(service_id: &str) -> Result<Vec<ServiceProcess>, String> {
+    let connection = gio::bus_get_sync(gio::BusType::System, gio::Cancellable::NONE)
+        .map_err(|e| e.to_string())?;
+
+    let processes = match connection.call_sync(
+        Some("org.freedesktop.systemd1"),
+        "/org/freedesktop/systemd1",
+        "org.freedesktop.systemd1.Manager",
+        "GetUnitProcesses",
+        Some(&(service_id,).to_variant()),
+        Some(glib::VariantTy::new("(a(sus))").unwrap()),
+        gio::DBusCallFlags::NO_AUTO_START,
+        SYSTEMD_CALL_TIMEOUT_MS,
+        gio::Cancellable::NONE,
+    ) {
+        Ok(processes) => processes.child_value(0),
+        // Only units that are loaded have a control group
+        Err(e)
+            if gio::DBusError::remote_error(&e).as_deref()
+                == Some("org.freedesktop.systemd1.NoSuchUnit") =>
+        {
+            return Ok(vec![])
+        }
+        Err(e) => return Err(e.to_string()),
+    };
+
+    let mut processes = processes
+        .iter()
+        .filter_map(|process| {
+            let (_cgroup, pid, command_line) = process.get::<(String, u32, String)>()?;
+            Some(ServiceProcess {
+                pid,
+                parent: parent(pid),
+                command_line,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let pids = processes
+        .iter()
+        .map(|process| process.pid)
+        .collect::<HashSet<_>>();
+    for process in &mut processes {
+        process.parent = process.parent.filter(|parent| pids.contains(parent));
+    }
+
+    Ok(processes)
+}
+
+fn parent(pid: Pid) -> Option<Pid> {
+    proc_stat::read(pid).map(|stat| stat.parent)
+}
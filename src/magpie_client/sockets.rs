@@ -0,0 +1,173 @@
+/* magpie_client/sockets.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use super::Pid;
+use crate::is_flatpak;
+
+/// Flags `ss` prints in the same spot as the congestion control algorithm
+const TCP_INFO_FLAGS: &[&str] = &["ts", "sack", "ecn", "ecnseen", "fastopen"];
+
+/// A TCP socket and what the kernel knows about the state of its transfers, as reported by
+/// `ss`, which gets it over netlink (sock_diag)
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct TcpSocket {
+    /// Processes that have the socket open
+    pub pids: Vec<Pid>,
+    /// e.g. `ESTAB`, `LISTEN` or `CLOSE-WAIT`
+    pub state: String,
+    pub local_address: String,
+    pub peer_address: String,
+    /// Bytes received that the process hasn't read yet
+    pub receive_queue: u64,
+    /// Bytes sent that the peer hasn't acknowledged yet
+    pub send_queue: u64,
+    /// e.g. `cubic` or `bbr`
+    pub congestion_control: Option<String>,
+    /// Smoothed round trip time and its variance, in milliseconds
+    pub rtt_ms: Option<(f32, f32)>,
+    pub receive_buffer: Option<u64>,
+    pub send_buffer: Option<u64>,
+    /// Segments retransmitted over the lifetime of the socket
+    pub retransmits: Option<u64>,
+}
+
+/// The TCP sockets owned by any of `pids`; sockets of other users' processes can only be
+/// attributed when running as root
+pub fn read(pids: &[Pid]) -> Result<Vec<TcpSocket>, String> {
+    let mut cmd = if is_flatpak() {
+        let mut cmd = std::process::Command::new("/usr/bin/flatpak-spawn");
+        cmd.arg("--host").arg("ss");
+        cmd
+    } else {
+        std::process::Command::new("ss")
+    };
+    // All TCP sockets, with internal info, memory usage and owning processes, no header
+    cmd.args(["-t", "-a", "-n", "-i", "-m", "-p", "-H"]);
+
+    let output = cmd.output().map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_owned());
+    }
+
+    Ok(parse(&String::from_utf8_lossy(&output.stdout))
+        .into_iter()
+        .filter(|socket| socket.pids.iter().any(|pid| pids.contains(pid)))
+        .collect())
+}
+
+/// Each socket is a line of `State Recv-Q Send-Q Local Peer Process`, followed by indented
+/// lines with the details
+fn parse(output: &str) -> Vec<TcpSocket> {
+    let mut sockets: Vec<TcpSocket> = vec![];
+
+    for line in output.lines() {
+        if line.starts_with(char::is_whitespace) {
+            if let Some(socket) = sockets.last_mut() {
+                parse_details(socket, line);
+            }
+            continue;
+        }
+
+        let mut fields = line.split_whitespace();
+        let (Some(state), Some(receive_queue), Some(send_queue), Some(local), Some(peer)) = (
+            fields.next(),
+            fields.next(),
+            fields.next(),
+            fields.next(),
+            fields.next(),
+        ) else {
+            continue;
+        };
+
+        let mut socket = TcpSocket {
+            pids: parse_pids(line),
+            state: state.to_owned(),
+            local_address: local.to_owned(),
+            peer_address: peer.to_owned(),
+            receive_queue: receive_queue.parse().unwrap_or(0),
+            send_queue: send_queue.parse().unwrap_or(0),
+            ..Default::default()
+        };
+
+        // Older versions print the details on the same line
+        let rest = fields.collect::<Vec<_>>().join(" ");
+        parse_details(&mut socket, &rest);
+
+        sockets.push(socket);
+    }
+
+    sockets
+}
+
+/// `users:(("firefox",pid=1234,fd=55),("firefox",pid=1240,fd=12))`
+fn parse_pids(line: &str) -> Vec<Pid> {
+    line.split("pid=")
+        .skip(1)
+        .filter_map(|part| {
+            part.split(|c: char| !c.is_ascii_digit())
+                .next()?
+                .parse()
+                .ok()
+        })
+        .collect()
+}
+
+fn parse_details(socket: &mut TcpSocket, line: &str) {
+    let tokens = line.split_whitespace().collect::<Vec<_>>();
+
+    for (index, token) in tokens.iter().enumerate() {
+        if let Some(skmem) = token
+            .strip_prefix("skmem:(")
+            .and_then(|s| s.strip_suffix(')'))
+        {
+            for value in skmem.split(',') {
+                if let Some(size) = value.strip_prefix("rb") {
+                    socket.receive_buffer = size.parse().ok();
+                } else if let Some(size) = value.strip_prefix("tb") {
+                    socket.send_buffer = size.parse().ok();
+                }
+            }
+        } else if let Some(rtt) = token.strip_prefix("rtt:") {
+            socket.rtt_ms = rtt
+                .split_once('/')
+                .and_then(|(rtt, var)| Some((rtt.parse().ok()?, var.parse().ok()?)));
+        } else if let Some(retransmits) = token.strip_prefix("retrans:") {
+            // Currently unacknowledged / total
+            socket.retransmits = retransmits
+                .rsplit('/')
+                .next()
+                .and_then(|total| total.parse().ok());
+        } else if token.starts_with("wscale:") || token.starts_with("rto:") {
+            // The algorithm comes right before the first of these, if the socket has one
+            if socket.congestion_control.is_some() || index == 0 {
+                continue;
+            }
+
+            let candidate = tokens[index - 1];
+            if candidate
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_')
+                && !TCP_INFO_FLAGS.contains(&candidate)
+            {
+                socket.congestion_control = Some(candidate.to_owned());
+            }
+        }
+    }
+}
@@ -0,0 +1,135 @@
+/* magpie_client/stop_processes.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use gtk::glib;
+
+use super::gatherer::Gatherer;
+use super::{privileged, proc_stat, Pid};
+
+/// How long processes get to exit before they are reported as still running
+const EXIT_TIMEOUT: Duration = Duration::from_secs(1);
+const EXIT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StopOutcome {
+    Stopped,
+    /// The signal went out, but there is no way to tell whether the process exited, like for a
+    /// remote host
+    Signalled,
+    /// The process hadn't exited by the time it was checked on
+    StillRunning,
+    Failed(String),
+}
+
+/// Terminate, or with `force` kill, `pids` and hand what became of each of them to `done`, on
+/// the main thread; other users' processes are signalled with `pkexec`, like for single
+/// processes
+///
+/// Only asking the gatherer happens on the calling thread, waiting for authorization and for
+/// the processes to exit is done on a thread of its own
+pub fn stop(
+    magpie: &dyn Gatherer,
+    local: bool,
+    pids: Vec<Pid>,
+    force: bool,
+    done: impl FnOnce(Vec<(Pid, StopOutcome)>) + Send + 'static,
+) {
+    let (own, others) = if local {
+        privileged::split_by_owner(pids.clone())
+    } else {
+        (pids.clone(), vec![])
+    };
+
+    if force {
+        magpie.kill_processes(own);
+    } else {
+        magpie.terminate_processes(own);
+    }
+
+    std::thread::spawn(move || {
+        let outcomes = wait_for_exit(local, pids, others, force);
+        glib::idle_add_once(move || done(outcomes));
+    });
+}
+
+fn wait_for_exit(
+    local: bool,
+    pids: Vec<Pid>,
+    others: Vec<Pid>,
+    force: bool,
+) -> Vec<(Pid, StopOutcome)> {
+    let signal = if force {
+        privileged::Signal::Kill
+    } else {
+        privileged::Signal::Terminate
+    };
+
+    let mut outcomes = HashMap::new();
+    if let Err(e) = privileged::signal_processes(&others, signal) {
+        outcomes.extend(
+            others
+                .iter()
+                .map(|pid| (*pid, StopOutcome::Failed(e.clone()))),
+        );
+    }
+
+    let mut pending = pids
+        .iter()
+        .copied()
+        .filter(|pid| !outcomes.contains_key(pid))
+        .collect::<Vec<_>>();
+
+    // Without the processes at hand all we know is that the gatherer was asked to stop them
+    if !local {
+        outcomes.extend(pending.drain(..).map(|pid| (pid, StopOutcome::Signalled)));
+    }
+
+    let deadline = Instant::now() + EXIT_TIMEOUT;
+    while !pending.is_empty() {
+        let stats = proc_stat::read_all(pending.iter().copied());
+        pending.retain(|pid| {
+            let running = stats.get(pid).is_some_and(|stat| stat.is_running());
+            if !running {
+                outcomes.insert(*pid, StopOutcome::Stopped);
+            }
+            running
+        });
+
+        if Instant::now() >= deadline {
+            break;
+        }
+        std::thread::sleep(EXIT_POLL_INTERVAL);
+    }
+    outcomes.extend(
+        pending
+            .into_iter()
+            .map(|pid| (pid, StopOutcome::StillRunning)),
+    );
+
+    pids.into_iter()
+        .map(|pid| {
+            let outcome = outcomes.remove(&pid).unwrap_or(StopOutcome::Stopped);
+            (pid, outcome)
+        })
+        .collect()
+}
@@ -0,0 +1,161 @@
+/* magpie_client/unit_files.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::HashMap;
+
+use gtk::gio;
+use gtk::glib::{self, g_debug, prelude::*};
+
+const SYSTEMD_CALL_TIMEOUT_MS: i32 = 1000;
+
+/// Whether and how a service is started, which is the state of its unit file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StartType {
+    /// Started at boot, or by whatever it was installed for
+    Enabled,
+    /// Only started when asked for
+    Disabled,
+    /// Has nothing to enable, it is only ever started by other units
+    Static,
+    /// Can't be started at all, not even by hand
+    Masked,
+    /// Any of the states no choice is offered for, like `alias`, `indirect` or `generated`
+    Other(String),
+}
+
+impl StartType {
+    pub fn from_state(state: &str) -> Self {
+        match state {
+            "enabled" | "enabled-runtime" => StartType::Enabled,
+            "disabled" => StartType::Disabled,
+            "static" => StartType::Static,
+            "masked" | "masked-runtime" => StartType::Masked,
+            other => StartType::Other(other.to_owned()),
+        }
+    }
+
+    pub fn state(&self) -> &str {
+        match self {
+            StartType::Enabled => "enabled",
+            StartType::Disabled => "disabled",
+            StartType::Static => "static",
+            StartType::Masked => "masked",
+            StartType::Other(state) => state,
+        }
+    }
+}
+
+/// The start type of every service unit file, by unit name; empty if systemd can't be asked
+pub fn read() -> HashMap<String, StartType> {
+    let connection = match gio::bus_get_sync(gio::BusType::System, gio::Cancellable::NONE) {
+        Ok(connection) => connection,
+        Err(e) => {
+            g_debug!(
+                "MissionCenter::SysInfo",
+                "Failed to connect to the system bus: {}",
+                e
+            );
+            return HashMap::new();
+        }
+    };
+
+    let unit_files = match connection.call_sync(
+        Some("org.freedesktop.systemd1"),
+        "/org/freedesktop/systemd1",
+        "org.freedesktop.systemd1.Manager",
+        "ListUnitFilesByPatterns",
+        Some(&(Vec::<String>::new(), vec!["*.service".to_owned()]).to_variant()),
+        Some(glib::VariantTy::new("(a(ss))").unwrap()),
+        gio::DBusCallFlags::NO_AUTO_START,
+        SYSTEMD_CALL_TIMEOUT_MS,
+        gio::Cancellable::NONE,
+    ) {
+        Ok(unit_files) => unit_files.child_value(0),
+        Err(e) => {
+            g_debug!("MissionCenter::SysInfo", "Failed to list unit files: {}", e);
+            return HashMap::new();
+        }
+    };
+
+    unit_files
+        .iter()
+        .filter_map(|unit_file| {
+            let (path, state) = unit_file.get::<(String, String)>()?;
+            let name = path.rsplit('/').next()?.to_owned();
+            Some((name, StartType::from_state(&state)))
+        })
+        .collect()
+}
+
+/// Change the start type of `unit` the way `systemctl` does, unmasking it first when it is
+/// masked; asks for authorization through polkit, so it is done on its own thread and `done`
+/// is handed the outcome on the main thread
+pub fn set_start_type(
+    unit: &str,
+    current: StartType,
+    start_type: StartType,
+    done: impl FnOnce(Result<(), String>) + Send + 'static,
+) {
+    let unit = unit.to_owned();
+    std::thread::spawn(move || {
+        let result = change_unit_file(&unit, &current, &start_type);
+        glib::idle_add_once(move || done(result));
+    });
+}
+
+fn change_unit_file(unit: &str, current: &StartType, start_type: &StartType) -> Result<(), String> {
+    let connection = gio::bus_get_sync(gio::BusType::System, gio::Cancellable::NONE)
+        .map_err(|e| e.to_string())?;
+
+    let call = |method: &str, parameters: glib::Variant| {
+        connection
+            .call_sync(
+                Some("org.freedesktop.systemd1"),
+                "/org/freedesktop/systemd1",
+                "org.freedesktop.systemd1.Manager",
+                method,
+                Some(&parameters),
+                None,
+                gio::DBusCallFlags::ALLOW_INTERACTIVE_AUTHORIZATION,
+                -1,
+                gio::Cancellable::NONE,
+            )
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    };
+
+    let units = vec![unit.to_owned()];
+    if *current == StartType::Masked && *start_type != StartType::Masked {
+        call("UnmaskUnitFiles", (units.clone(), false).to_variant())?;
+    }
+    match start_type {
+        StartType::Enabled => call(
+            "EnableUnitFiles",
+            (units.clone(), false, false).to_variant(),
+        )?,
+        StartType::Disabled => call("DisableUnitFiles", (units.clone(), false).to_variant())?,
+        StartType::Masked => call("MaskUnitFiles", (units.clone(), false, false).to_variant())?,
+        // Unmasking is all there is to it
+        StartType::Static | StartType::Other(_) => {}
+    }
+
+    // What `systemctl daemon-reload` does, so the change is picked up right away
+    call("Reload", ().to_variant())
+}
@@ -0,0 +1,204 @@
+/* magpie_client/ups.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::time::Duration;
+
+use gtk::glib::g_debug;
+
+// Default address of the Network UPS Tools daemon (upsd)
+const NUT_ADDRESS: ([u8; 4], u16) = ([127, 0, 0, 1], 3493);
+const NUT_TIMEOUT: Duration = Duration::from_millis(250);
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Ups {
+    pub name: String,
+    pub description: Option<String>,
+    /// Space separated NUT status flags, e.g. `OL CHRG` or `OB DISCHRG LB`
+    pub status: String,
+    pub charge_percent: Option<f32>,
+    pub load_percent: Option<f32>,
+    pub runtime_seconds: Option<u64>,
+    pub input_voltage: Option<f32>,
+}
+
+impl Ups {
+    pub fn on_battery(&self) -> bool {
+        self.status
+            .split_ascii_whitespace()
+            .any(|flag| flag == "OB")
+    }
+
+    pub fn low_battery(&self) -> bool {
+        self.status
+            .split_ascii_whitespace()
+            .any(|flag| flag == "LB")
+    }
+}
+
+/// Query every UPS known to the local NUT daemon; empty if NUT is not installed or not running
+pub fn read() -> Vec<Ups> {
+    let address = SocketAddr::from(NUT_ADDRESS);
+    let stream = match TcpStream::connect_timeout(&address, NUT_TIMEOUT) {
+        Ok(stream) => stream,
+        Err(_) => return vec![],
+    };
+    let _ = stream.set_read_timeout(Some(NUT_TIMEOUT));
+    let _ = stream.set_write_timeout(Some(NUT_TIMEOUT));
+
+    let mut session = match NutSession::new(stream) {
+        Some(session) => session,
+        None => return vec![],
+    };
+
+    let names = match session.list("UPS", None) {
+        Ok(names) => names,
+        Err(e) => {
+            g_debug!(
+                "MissionCenter::SysInfo",
+                "Failed to list UPS devices: {}",
+                e
+            );
+            return vec![];
+        }
+    };
+
+    let mut result = Vec::with_capacity(names.len());
+    for (name, description) in names {
+        let vars = match session.list("VAR", Some(&name)) {
+            Ok(vars) => vars.into_iter().collect::<HashMap<_, _>>(),
+            Err(e) => {
+                g_debug!(
+                    "MissionCenter::SysInfo",
+                    "Failed to read variables of UPS {}: {}",
+                    name,
+                    e
+                );
+                continue;
+            }
+        };
+
+        let parse_f32 = |var: &str| vars.get(var).and_then(|v| v.parse::<f32>().ok());
+
+        let model = [vars.get("device.mfr"), vars.get("device.model")]
+            .into_iter()
+            .flatten()
+            .map(|s| s.as_str())
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        result.push(Ups {
+            description: if !model.is_empty() {
+                Some(model)
+            } else if !description.is_empty() && description != "Unavailable" {
+                Some(description)
+            } else {
+                None
+            },
+            status: vars.get("ups.status").cloned().unwrap_or_default(),
+            charge_percent: parse_f32("battery.charge"),
+            load_percent: parse_f32("ups.load"),
+            runtime_seconds: parse_f32("battery.runtime").map(|v| v as u64),
+            input_voltage: parse_f32("input.voltage"),
+            name,
+        });
+    }
+
+    result
+}
+
+struct NutSession {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+}
+
+impl NutSession {
+    fn new(stream: TcpStream) -> Option<Self> {
+        let writer = stream.try_clone().ok()?;
+        Some(Self {
+            reader: BufReader::new(stream),
+            writer,
+        })
+    }
+
+    // Runs `LIST <kind> [<ups>]` and returns the `(name, value)` pairs of the answer, e.g. for
+    // `LIST VAR myups` lines like `VAR myups battery.charge "100"` become `("battery.charge", "100")`
+    fn list(&mut self, kind: &str, ups: Option<&str>) -> Result<Vec<(String, String)>, String> {
+        let query = match ups {
+            Some(ups) => format!("{} {}", kind, ups),
+            None => kind.to_owned(),
+        };
+
+        self.writer
+            .write_all(format!("LIST {}\n", query).as_bytes())
+            .map_err(|e| e.to_string())?;
+
+        let begin = format!("BEGIN LIST {}", query);
+        let end = format!("END LIST {}", query);
+        let prefix = format!("{} ", query);
+
+        let mut result = vec![];
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if self
+                .reader
+                .read_line(&mut line)
+                .map_err(|e| e.to_string())?
+                == 0
+            {
+                return Err("connection closed".to_owned());
+            }
+
+            let line = line.trim_end();
+            if line.starts_with("ERR ") {
+                return Err(line.to_owned());
+            }
+            if line == begin {
+                continue;
+            }
+            if line == end {
+                break;
+            }
+
+            let Some((name, value)) = line
+                .strip_prefix(&prefix)
+                .and_then(|rest| rest.split_once(' '))
+            else {
+                continue;
+            };
+
+            result.push((name.to_owned(), unquote(value)));
+        }
+
+        Ok(result)
+    }
+}
+
+fn unquote(value: &str) -> String {
+    value
+        .trim()
+        .trim_start_matches('"')
+        .trim_end_matches('"')
+        .replace("\\\"", "\"")
+        .replace("\\\\", "\\")
+}
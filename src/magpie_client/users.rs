@@ -0,0 +1,527 @@
+/* magpie_client/users.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use gtk::{
+    gio,
+    glib::{self, g_critical, g_debug},
+    prelude::*,
+};
+
+use super::{privileged, Pid, Process, ProcessOwner};
+
+const LOGIND_CALL_TIMEOUT_MS: i32 = 250;
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Session {
+    pub id: String,
+    pub seat: Option<String>,
+    pub tty: Option<String>,
+    /// `x11`, `wayland`, `tty`, `mir` or `unspecified`
+    pub kind: String,
+    /// `online`, `active` or `closing`
+    pub state: String,
+    pub remote_host: Option<String>,
+    /// Seconds since the Unix epoch
+    pub start_time: u64,
+    pub leader: Pid,
+}
+
+/// Resource usage and limits of a user's `user-UID.slice` cgroup
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct UserSlice {
+    pub memory_current: Option<u64>,
+    pub memory_max: Option<u64>,
+    /// CPU quota as a percentage of a single core, from `cpu.max`
+    pub cpu_quota_percent: Option<f32>,
+    pub tasks_max: Option<u64>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct User {
+    pub uid: u32,
+    pub name: String,
+    pub sessions: Vec<Session>,
+    pub process_count: usize,
+    pub cpu_usage: f32,
+    pub memory_usage: u64,
+    pub slice: UserSlice,
+}
+
+/// Set once the limits of a slice were changed, so they are read again
+static SLICE_LIMITS_CHANGED: AtomicBool = AtomicBool::new(false);
+
+/// Lists the users with at least one logind session. What logind says about each session is kept
+/// until its signals tell that sessions came, went or changed, and the limits of each user's
+/// slice until they are set from Mission Center; only the memory use is read on every update.
+pub struct UserTracker {
+    logind: Option<LogindWatch>,
+    /// The sessions and the UID and name of their users, by object path
+    sessions: HashMap<String, (u32, String, Session)>,
+    slices: HashMap<u32, UserSlice>,
+}
+
+impl UserTracker {
+    pub fn new() -> Self {
+        Self {
+            logind: LogindWatch::start(),
+            sessions: HashMap::new(),
+            slices: HashMap::new(),
+        }
+    }
+
+    /// The users with their usage summed up using who `owners` says each process belongs to;
+    /// empty if logind is not available
+    pub fn update(
+        &mut self,
+        processes: &HashMap<Pid, Process>,
+        owners: &HashMap<Pid, ProcessOwner>,
+    ) -> Vec<User> {
+        let Some(logind) = &self.logind else {
+            return vec![];
+        };
+
+        let changes = logind.take_changes();
+        if changes.sessions {
+            match logind.list_sessions(&mut self.sessions, &changes.properties) {
+                Some(sessions) => self.sessions = sessions,
+                None => {
+                    self.sessions.clear();
+                    logind.list_again();
+                }
+            }
+        } else {
+            for path in &changes.properties {
+                if let Some((_, _, session)) = self.sessions.get_mut(path) {
+                    *session = logind.read_session(&session.id, session.seat.clone(), path);
+                }
+            }
+        }
+
+        let limits_changed = SLICE_LIMITS_CHANGED.swap(false, Ordering::Relaxed);
+        let uids = self
+            .sessions
+            .values()
+            .map(|(uid, _, _)| *uid)
+            .collect::<HashSet<_>>();
+        self.slices.retain(|uid, _| uids.contains(uid));
+        for uid in uids {
+            match self.slices.get_mut(&uid) {
+                Some(slice) if !limits_changed => {
+                    slice.memory_current = read_slice_value(uid, "memory.current");
+                }
+                _ => {
+                    self.slices.insert(uid, read_user_slice(uid));
+                }
+            }
+        }
+
+        let mut users: HashMap<u32, User> = HashMap::new();
+        for (uid, name, session) in self.sessions.values() {
+            users
+                .entry(*uid)
+                .or_insert_with(|| User {
+                    uid: *uid,
+                    name: name.clone(),
+                    slice: self.slices.get(uid).copied().unwrap_or_default(),
+                    ..Default::default()
+                })
+                .sessions
+                .push(session.clone());
+        }
+
+        for process in processes.values() {
+            let Some(owner) = owners.get(&process.pid) else {
+                continue;
+            };
+            if let Some(user) = users.get_mut(&owner.uid) {
+                user.process_count += 1;
+                user.cpu_usage += process.usage_stats.cpu_usage;
+                user.memory_usage += process.usage_stats.memory_usage;
+            }
+        }
+
+        let mut result = users
+            .into_values()
+            .map(|mut user| {
+                user.sessions.sort_by(|s1, s2| s1.id.cmp(&s2.id));
+                user
+            })
+            .collect::<Vec<_>>();
+        result.sort_by(|u1, u2| u1.name.cmp(&u2.name));
+
+        result
+    }
+}
+
+/// What logind signalled since the last update
+#[derive(Default)]
+struct Changes {
+    /// Sessions came or went, the list has to be read again
+    sessions: bool,
+    /// Object paths of the sessions whose properties changed
+    properties: HashSet<String>,
+}
+
+/// Listens to logind's signals. They are delivered to a main context of its own, which is
+/// dispatched on every update, since the thread updates run on has no main loop.
+struct LogindWatch {
+    connection: gio::DBusConnection,
+    context: glib::MainContext,
+    subscriptions: Vec<gio::SignalSubscriptionId>,
+    changes: Arc<Mutex<Changes>>,
+}
+
+impl Drop for LogindWatch {
+    fn drop(&mut self) {
+        for subscription in self.subscriptions.drain(..) {
+            self.connection.signal_unsubscribe(subscription);
+        }
+    }
+}
+
+impl LogindWatch {
+    fn start() -> Option<Self> {
+        let connection = gio::bus_get_sync(gio::BusType::System, gio::Cancellable::NONE)
+            .inspect_err(|e| {
+                g_debug!(
+                    "MissionCenter::SysInfo",
+                    "Failed to connect to the system bus: {}",
+                    e
+                );
+            })
+            .ok()?;
+
+        // Nothing was read yet
+        let changes = Arc::new(Mutex::new(Changes {
+            sessions: true,
+            ..Default::default()
+        }));
+
+        let context = glib::MainContext::new();
+        let subscriptions = context
+            .with_thread_default(|| {
+                let sessions = connection.signal_subscribe(
+                    Some("org.freedesktop.login1"),
+                    Some("org.freedesktop.login1.Manager"),
+                    None,
+                    Some("/org/freedesktop/login1"),
+                    None,
+                    gio::DBusSignalFlags::NONE,
+                    {
+                        let changes = changes.clone();
+                        move |_, _, _, _, member, _| {
+                            if member == "SessionNew" || member == "SessionRemoved" {
+                                if let Ok(mut changes) = changes.lock() {
+                                    changes.sessions = true;
+                                }
+                            }
+                        }
+                    },
+                );
+
+                let properties = connection.signal_subscribe(
+                    Some("org.freedesktop.login1"),
+                    Some("org.freedesktop.DBus.Properties"),
+                    Some("PropertiesChanged"),
+                    None,
+                    Some("org.freedesktop.login1.Session"),
+                    gio::DBusSignalFlags::NONE,
+                    {
+                        let changes = changes.clone();
+                        move |_, _, path, _, _, _| {
+                            if let Ok(mut changes) = changes.lock() {
+                                changes.properties.insert(path.to_owned());
+                            }
+                        }
+                    },
+                );
+
+                vec![sessions, properties]
+            })
+            .ok()?;
+
+        Some(Self {
+            connection,
+            context,
+            subscriptions,
+            changes,
+        })
+    }
+
+    fn take_changes(&self) -> Changes {
+        while self.context.iteration(false) {}
+
+        self.changes
+            .lock()
+            .map(|mut changes| std::mem::take(&mut *changes))
+            .unwrap_or_default()
+    }
+
+    /// The sessions logind knows about, `None` if it could not be asked; those in `known` are
+    /// kept as they are, unless their path is in `changed`
+    fn list_sessions(
+        &self,
+        known: &mut HashMap<String, (u32, String, Session)>,
+        changed: &HashSet<String>,
+    ) -> Option<HashMap<String, (u32, String, Session)>> {
+        let sessions = match self.connection.call_sync(
+            Some("org.freedesktop.login1"),
+            "/org/freedesktop/login1",
+            "org.freedesktop.login1.Manager",
+            "ListSessions",
+            None,
+            Some(glib::VariantTy::new("(a(susso))").unwrap()),
+            gio::DBusCallFlags::NO_AUTO_START,
+            LOGIND_CALL_TIMEOUT_MS,
+            gio::Cancellable::NONE,
+        ) {
+            Ok(sessions) => sessions.child_value(0),
+            Err(e) => {
+                g_debug!(
+                    "MissionCenter::SysInfo",
+                    "Failed to list login sessions: {}",
+                    e
+                );
+                return None;
+            }
+        };
+
+        let sessions = sessions
+            .iter()
+            .filter_map(|session| {
+                let (id, uid, name, seat, path) =
+                    session.get::<(String, u32, String, String, glib::variant::ObjectPath)>()?;
+                let path = path.as_str().to_owned();
+
+                let session = match known.remove(&path) {
+                    Some((_, _, session)) if !changed.contains(&path) => session,
+                    _ => self.read_session(&id, Some(seat).filter(|s| !s.is_empty()), &path),
+                };
+
+                Some((path, (uid, name, session)))
+            })
+            .collect();
+
+        Some(sessions)
+    }
+
+    /// Have the sessions listed on the next update, when listing them failed
+    fn list_again(&self) {
+        if let Ok(mut changes) = self.changes.lock() {
+            changes.sessions = true;
+        }
+    }
+
+    fn read_session(&self, id: &str, seat: Option<String>, path: &str) -> Session {
+        let props = self
+            .connection
+            .call_sync(
+                Some("org.freedesktop.login1"),
+                path,
+                "org.freedesktop.DBus.Properties",
+                "GetAll",
+                Some(&("org.freedesktop.login1.Session",).to_variant()),
+                Some(glib::VariantTy::new("(a{sv})").unwrap()),
+                gio::DBusCallFlags::NO_AUTO_START,
+                LOGIND_CALL_TIMEOUT_MS,
+                gio::Cancellable::NONE,
+            )
+            .ok()
+            .map(|props| props.child_value(0));
+        let string_prop = |name: &str| {
+            props
+                .as_ref()
+                .and_then(|p| p.lookup_value(name, Some(glib::VariantTy::STRING)))
+                .and_then(|v| v.get::<String>())
+                .filter(|s| !s.is_empty())
+        };
+
+        Session {
+            id: id.to_owned(),
+            seat,
+            tty: string_prop("TTY"),
+            kind: string_prop("Type").unwrap_or_default(),
+            state: string_prop("State").unwrap_or_default(),
+            remote_host: string_prop("RemoteHost"),
+            start_time: props
+                .as_ref()
+                .and_then(|p| p.lookup_value("Timestamp", Some(glib::VariantTy::UINT64)))
+                .and_then(|v| v.get::<u64>())
+                .map(|usec| usec / 1_000_000)
+                .unwrap_or(0),
+            leader: props
+                .as_ref()
+                .and_then(|p| p.lookup_value("Leader", Some(glib::VariantTy::UINT32)))
+                .and_then(|v| v.get::<u32>())
+                .unwrap_or(0),
+        }
+    }
+}
+
+fn read_slice_file(uid: u32, file: &str) -> Option<String> {
+    std::fs::read_to_string(format!(
+        "/sys/fs/cgroup/user.slice/user-{}.slice/{}",
+        uid, file
+    ))
+    .ok()
+    .map(|content| content.trim().to_owned())
+}
+
+// `max` means no limit
+fn read_slice_value(uid: u32, file: &str) -> Option<u64> {
+    read_slice_file(uid, file).and_then(|v| v.parse::<u64>().ok())
+}
+
+fn read_user_slice(uid: u32) -> UserSlice {
+    let cpu_quota_percent = read_slice_file(uid, "cpu.max").and_then(|cpu_max| {
+        let mut fields = cpu_max.split_ascii_whitespace();
+        let quota = fields.next()?.parse::<f32>().ok()?;
+        let period = fields.next()?.parse::<f32>().ok()?;
+        if period > 0. {
+            Some(quota / period * 100.)
+        } else {
+            None
+        }
+    });
+
+    UserSlice {
+        memory_current: read_slice_value(uid, "memory.current"),
+        memory_max: read_slice_value(uid, "memory.max"),
+        cpu_quota_percent,
+        tasks_max: read_slice_value(uid, "pids.max"),
+    }
+}
+
+/// What the limits of a user's slice are set to; `None` for no limit
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct SliceLimits {
+    /// Bytes
+    pub memory_max: Option<u64>,
+    /// Percent of the time of one CPU, more than 100 for several
+    pub cpu_quota_percent: Option<u32>,
+    pub tasks_max: Option<u64>,
+}
+
+impl From<&UserSlice> for SliceLimits {
+    fn from(slice: &UserSlice) -> Self {
+        Self {
+            memory_max: slice.memory_max,
+            cpu_quota_percent: slice.cpu_quota_percent.map(|quota| quota.round() as u32),
+            tasks_max: slice.tasks_max,
+        }
+    }
+}
+
+/// Set the limits of the `user-UID.slice` of `uid` the way `systemctl set-property` does, so they
+/// persist; that takes the helper, which asks for authorization through polkit, so it is done on
+/// its own thread and `done` is handed the outcome on the main thread
+pub fn set_slice_limits(
+    uid: u32,
+    limits: SliceLimits,
+    done: impl FnOnce(Result<(), String>) + Send + 'static,
+) {
+    std::thread::spawn(move || {
+        let result = set_unit_properties(&format!("user-{}.slice", uid), &limits);
+        if result.is_ok() {
+            SLICE_LIMITS_CHANGED.store(true, Ordering::Relaxed);
+        }
+        glib::idle_add_once(move || done(result));
+    });
+}
+
+fn set_unit_properties(unit: &str, limits: &SliceLimits) -> Result<(), String> {
+    const NO_LIMIT: &str = "infinity";
+
+    let properties = [
+        format!(
+            "MemoryMax={}",
+            limits
+                .memory_max
+                .map_or(NO_LIMIT.to_owned(), |max| max.to_string())
+        ),
+        // An empty quota takes it away
+        format!(
+            "CPUQuota={}",
+            limits
+                .cpu_quota_percent
+                .map_or(String::new(), |percent| format!("{}%", percent.max(1)))
+        ),
+        format!(
+            "TasksMax={}",
+            limits
+                .tasks_max
+                .map_or(NO_LIMIT.to_owned(), |max| max.to_string())
+        ),
+    ];
+
+    privileged::set_unit_properties(unit, false, &properties)
+}
+
+/// Lock the screen of a session; asks for authorization through polkit if it belongs to
+/// another user
+pub fn lock_session(id: &str) {
+    call_logind_manager("LockSession", id);
+}
+
+/// End a session and all of its processes; asks for authorization through polkit if it belongs
+/// to another user
+pub fn terminate_session(id: &str) {
+    call_logind_manager("TerminateSession", id);
+}
+
+fn call_logind_manager(method: &'static str, session_id: &str) {
+    let connection = match gio::bus_get_sync(gio::BusType::System, gio::Cancellable::NONE) {
+        Ok(connection) => connection,
+        Err(e) => {
+            g_critical!(
+                "MissionCenter::SysInfo",
+                "Failed to connect to the system bus: {}",
+                e
+            );
+            return;
+        }
+    };
+
+    connection.call(
+        Some("org.freedesktop.login1"),
+        "/org/freedesktop/login1",
+        "org.freedesktop.login1.Manager",
+        method,
+        Some(&(session_id,).to_variant()),
+        None,
+        gio::DBusCallFlags::ALLOW_INTERACTIVE_AUTHORIZATION,
+        -1,
+        gio::Cancellable::NONE,
+        move |result| {
+            if let Err(e) = result {
+                g_critical!(
+                    "MissionCenter::SysInfo",
+                    "Failed to call {} on logind: {}",
+                    method,
+                    e
+                );
+            }
+        },
+    );
+}
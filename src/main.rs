@@ -35,11 +35,19 @@ use crate::i18n::ni18n_f;
 
 mod application;
 mod apps_page;
+mod dbus_interface;
+mod export;
+mod history;
 mod i18n;
+mod insights;
 mod magpie_client;
 mod performance_page;
 mod preferences;
+mod profiles;
+mod report;
 mod services_page;
+mod status_icon;
+mod users_page;
 mod widgets;
 mod window;
 
@@ -87,6 +95,14 @@ pub fn is_flatpak() -> bool {
     *IS_FLATPAK.get_or_init(|| Path::new("/.flatpak-info").exists())
 }
 
+/// Whether widgets should animate; turned off by the system-wide reduced motion preference and by
+/// the app's own setting, which overrides GTK's
+pub fn animations_enabled() -> bool {
+    gtk::Settings::default()
+        .map(|settings| settings.is_gtk_enable_animations())
+        .unwrap_or(true)
+}
+
 // tysm gdu
 pub fn to_long_human_readable_time(seconds: u64) -> String {
     const USEC_PER_YEAR: u64 = 60 * 60 * 6 * 1461; // ((60 * 60 * 24) as f32 * 365.25);
@@ -214,6 +230,33 @@ fn data_type_setting_name(data_type: &DataType) -> &'static str {
     }
 }
 
+/// How sizes, speeds and frequencies are written out
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct NumberFormat {
+    /// How many decimals small values get; every order of magnitude above that gets one less
+    pub decimal_places: usize,
+    /// No space between the value and its unit, and no `i` for binary units
+    pub compact: bool,
+}
+
+impl Default for NumberFormat {
+    fn default() -> Self {
+        Self {
+            decimal_places: 2,
+            compact: false,
+        }
+    }
+}
+
+impl NumberFormat {
+    pub fn from_settings(settings: &Settings) -> Self {
+        Self {
+            decimal_places: settings.int("number-decimal-places").clamp(0, 3) as usize,
+            compact: settings.boolean("number-compact"),
+        }
+    }
+}
+
 pub fn to_human_readable_adv_str(
     value_bytes: f32,
     use_bytes: bool,
@@ -221,6 +264,7 @@ pub fn to_human_readable_adv_str(
     per_second: bool,
     unit_label: &str,
     min_exponent: usize,
+    format: NumberFormat,
 ) -> String {
     const UNITS: [&'static str; 9] = ["", "K", "M", "G", "T", "P", "E", "Z", "Y"];
 
@@ -251,22 +295,27 @@ pub fn to_human_readable_adv_str(
     // This sacrifices some precision for the sake of readability
     let dec_to_display = if exponent > min_exponent {
         if value < 10.0 {
-            2
+            format.decimal_places
         } else if value < 100.0 {
-            1
+            format.decimal_places.saturating_sub(1)
         } else {
-            0
+            format.decimal_places.saturating_sub(2)
         }
     } else {
         0
     };
 
     format!(
-        "{0:.1$} {2}{3}{4}{5}",
+        "{0:.1$}{2}{3}{4}{5}{6}",
         value,
         dec_to_display,
+        if format.compact { "" } else { " " },
         UNITS[exponent],
-        if use_binary { "i" } else { "" },
+        if use_binary && !format.compact {
+            "i"
+        } else {
+            ""
+        },
         unit_label,
         label
     )
@@ -277,6 +326,8 @@ pub fn to_human_readable_nice(
     data_type: &DataType,
     settings: &Settings,
 ) -> String {
+    let format = NumberFormat::from_settings(settings);
+
     let label = match data_type {
         DataType::Hertz => "Hz",
         DataType::Watts => "W",
@@ -294,13 +345,19 @@ pub fn to_human_readable_nice(
                 )),
                 *data_type == DataType::NetworkBytesPerSecond
                     || *data_type == DataType::DriveBytesPerSecond,
-                if bytes { "B" } else { "b" },
+                // Bytes are what sizes are assumed to be in, only bits need telling apart
+                match (bytes, format.compact) {
+                    (true, true) => "",
+                    (true, false) => "B",
+                    (false, _) => "b",
+                },
                 1,
+                format,
             );
         }
     };
 
-    to_human_readable_adv_str(value_bytes, true, false, false, label, 0)
+    to_human_readable_adv_str(value_bytes, true, false, false, label, 0, format)
 }
 
 pub fn show_error_dialog_and_exit(message: &str) -> ! {
@@ -350,10 +407,21 @@ fn main() {
 
     let app = MissionCenterApplication::new(
         "io.missioncenter.MissionCenter",
-        &gio::ApplicationFlags::empty(),
+        &gio::ApplicationFlags::HANDLES_COMMAND_LINE,
     );
     gtk::Application::set_default(app.upcast_ref::<gtk::Application>());
 
     let exit_code = app.run();
+
+    if let Some(args) = app.take_relaunch_args() {
+        use std::os::unix::process::CommandExt;
+
+        let error = match env::current_exe() {
+            Ok(exe) => std::process::Command::new(exe).args(args).exec(),
+            Err(e) => e,
+        };
+        eprintln!("Failed to restart Mission Center: {}", error);
+    }
+
     std::process::exit(exit_code.value());
 }
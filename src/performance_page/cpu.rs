@@ -24,8 +24,21 @@ use adw::subclass::prelude::*;
 use glib::{ParamSpec, Properties, Value};
 use gtk::{gio, glib, prelude::*};
 
-use super::{widgets::GraphWidget, PageExt};
-use crate::{application::INTERVAL_STEP, i18n::*, settings, to_short_human_readable_time};
+use super::{
+    export_graph_data, save_page_image, update_game_mode_badge, update_pressure_label,
+    widgets::{GraphWidget, HistoryDialog, HistoryKind, TopProcesses},
+    PageExt,
+};
+use crate::{
+    application::INTERVAL_STEP, export::Unit, i18n::*, settings, to_short_human_readable_time,
+};
+
+/// What the data sets of the graphs are exported as, in the order `export_graph_data` is
+/// handed them
+pub(super) const GRAPH_FIELDS: [(&str, Unit); 2] = [
+    ("cpu.usage", Unit::Percent),
+    ("cpu.kernel_usage", Unit::Percent),
+];
 
 mod imp {
     use super::*;
@@ -34,6 +47,16 @@ mod imp {
     const GRAPH_SELECTION_OVERALL: i32 = 1;
     const GRAPH_SELECTION_ALL: i32 = 2;
 
+    // Utilization is drawn as data set 0, the time spent in the kernel as data set 1
+    fn cpu_usage_text(index: usize, value: f32) -> String {
+        let value = format!("{:.1}", value);
+        if index == 0 {
+            i18n_f("Utilization: {}%", &[&value])
+        } else {
+            i18n_f("Kernel: {}%", &[&value])
+        }
+    }
+
     #[derive(Properties)]
     #[properties(wrapper_type = super::PerformancePageCpu)]
     #[derive(gtk::CompositeTemplate)]
@@ -42,6 +65,8 @@ mod imp {
         #[template_child]
         pub cpu_name: TemplateChild<gtk::Label>,
         #[template_child]
+        pub game_mode_badge: TemplateChild<gtk::Label>,
+        #[template_child]
         pub usage_graphs: TemplateChild<gtk::Grid>,
         #[template_child]
         pub graph_max_duration: TemplateChild<gtk::Label>,
@@ -53,6 +78,7 @@ mod imp {
         summary_mode: Cell<bool>,
 
         pub graph_widgets: Cell<Vec<GraphWidget>>,
+        game_mode_active: Cell<Option<bool>>,
 
         #[property(get = Self::infobar_content, type = Option < gtk::Widget >)]
         pub infobar_content: OnceCell<gtk::Box>,
@@ -65,6 +91,8 @@ mod imp {
         pub threads: OnceCell<gtk::Label>,
         pub handles: OnceCell<gtk::Label>,
         pub uptime: OnceCell<gtk::Label>,
+        pub pressure: OnceCell<gtk::Label>,
+        pub top_consumers: OnceCell<TopProcesses>,
         pub base_speed: OnceCell<gtk::Label>,
         pub sockets: OnceCell<gtk::Label>,
         pub virt_proc: OnceCell<gtk::Label>,
@@ -85,6 +113,7 @@ mod imp {
         fn default() -> Self {
             Self {
                 cpu_name: Default::default(),
+                game_mode_badge: Default::default(),
                 usage_graphs: Default::default(),
                 graph_max_duration: Default::default(),
                 context_menu: Default::default(),
@@ -92,6 +121,7 @@ mod imp {
                 summary_mode: Cell::new(false),
 
                 graph_widgets: Cell::new(Vec::new()),
+                game_mode_active: Cell::new(None),
 
                 infobar_content: Default::default(),
                 power_row: Default::default(),
@@ -103,6 +133,8 @@ mod imp {
                 threads: Default::default(),
                 handles: Default::default(),
                 uptime: Default::default(),
+                pressure: Default::default(),
+                top_consumers: Default::default(),
                 base_speed: Default::default(),
                 sockets: Default::default(),
                 virt_proc: Default::default(),
@@ -283,6 +315,49 @@ mod imp {
                 }
             });
             actions.add_action(&action);
+
+            let action = gio::SimpleAction::new("export-data", None);
+            action.connect_activate({
+                let this = this.downgrade();
+                move |_, _| {
+                    let Some(this) = this.upgrade() else {
+                        return;
+                    };
+
+                    let imp = this.imp();
+                    let graph_widgets = imp.graph_widgets.take();
+                    export_graph_data(
+                        this.upcast_ref(),
+                        &[(graph_widgets[0].clone(), 0), (graph_widgets[0].clone(), 1)],
+                        &GRAPH_FIELDS,
+                    );
+                    imp.graph_widgets.set(graph_widgets);
+                }
+            });
+            actions.add_action(&action);
+
+            let action = gio::SimpleAction::new("save-image", None);
+            action.connect_activate({
+                let this = this.downgrade();
+                move |_, _| {
+                    if let Some(this) = this.upgrade() {
+                        save_page_image(this.upcast_ref());
+                    }
+                }
+            });
+            actions.add_action(&action);
+
+            let action = gio::SimpleAction::new("history", None);
+            action.connect_activate({
+                let this = this.downgrade();
+                move |_, _| {
+                    if let Some(this) = this.upgrade() {
+                        HistoryDialog::new(HistoryKind::Cpu, this.base_color())
+                            .present(Some(this.upcast_ref::<gtk::Widget>()));
+                    }
+                }
+            });
+            actions.add_action(&action);
         }
 
         fn configure_context_menu(this: &super::PerformancePageCpu) {
@@ -324,9 +399,10 @@ mod imp {
 
             if let Some(base_speed) = this.base_speed.get() {
                 if let Some(base_frequency) = static_cpu_info.base_freq_khz {
-                    base_speed.set_text(&format!(
-                        "{:.2} GHz",
-                        base_frequency as f32 / (1000. * 1000.)
+                    base_speed.set_text(&crate::to_human_readable_nice(
+                        base_frequency as f32 * 1000.,
+                        &DataType::Hertz,
+                        &settings!(),
                     ));
                 } else {
                     base_speed.set_text(&i18n("Unknown"));
@@ -429,6 +505,13 @@ mod imp {
 
             this.graph_widgets.set(graph_widgets);
 
+            update_game_mode_badge(
+                this.obj().upcast_ref(),
+                &this.game_mode_badge,
+                &this.game_mode_active,
+                &readings.game_mode,
+            );
+
             if let Some(utilization) = this.utilization.get() {
                 utilization.set_text(&format!(
                     "{}%",
@@ -436,14 +519,15 @@ mod imp {
                 ));
             }
 
+            let settings = settings!();
+
             if let Some(speed) = this.speed.get() {
-                speed.set_text(&format!(
-                    "{:.2} GHz",
-                    dynamic_cpu_info.current_frequency_mhz as f32 / 1000.
+                speed.set_text(&crate::to_human_readable_nice(
+                    dynamic_cpu_info.current_frequency_mhz as f32 * 1_000_000.,
+                    &DataType::Hertz,
+                    &settings,
                 ));
             }
-
-            let settings = settings!();
             if let Some(power_draw) = this.power_draw.get() {
                 if let Some(power_draw_num) = dynamic_cpu_info.power_draw_w {
                     power_draw.set_text(&crate::to_human_readable_nice(
@@ -482,6 +566,18 @@ mod imp {
                 ));
             }
 
+            if let Some(pressure) = this.pressure.get() {
+                update_pressure_label(pressure, readings.cpu_pressure.as_ref());
+            }
+
+            if let Some(top_consumers) = this.top_consumers.get() {
+                top_consumers.update(
+                    &readings.running_processes,
+                    |process| process.usage_stats.cpu_usage,
+                    |usage| format!("{:.1}%", usage),
+                );
+            }
+
             if let (Some(cpufreq_driver), Some(cpufreq_driver_label)) =
                 (this.cpufreq_driver.get(), this.cpufreq_driver_label.get())
             {
@@ -622,6 +718,11 @@ mod imp {
                 .get()
                 .and_then(|v| Some(v.label()))
                 .unwrap_or("".into());
+            let pressure = self
+                .pressure
+                .get()
+                .and_then(|v| Some(v.label()))
+                .unwrap_or("".into());
 
             format!(
                 r#"CPU
@@ -645,7 +746,8 @@ mod imp {
     Processes:   {}
     Threads:     {}
     Handles:     {}
-    Up time:     {}"#,
+    Up time:     {}
+    Pressure:    {}"#,
                 self.cpu_name.label(),
                 base_speed,
                 sockets,
@@ -663,7 +765,8 @@ mod imp {
                 processes,
                 threads,
                 handles,
-                uptime
+                uptime,
+                pressure
             )
         }
 
@@ -696,6 +799,28 @@ mod imp {
             graph_widgets[0].set_data_visible(1, show_kernel_times);
             graph_widgets[0].set_base_color(&base_color);
             graph_widgets[0].set_visible(graph_selection == GRAPH_SELECTION_OVERALL);
+            graph_widgets[0].set_value_formatter(cpu_usage_text);
+            graph_widgets[0].set_axis_formatter(|value| format!("{:.0}%", value));
+            graph_widgets[0].connect_downsample_notify({
+                let this = self.obj().downgrade();
+                move |graph_widget| {
+                    let Some(this) = this.upgrade() else {
+                        return;
+                    };
+
+                    let settings = settings!();
+                    let data_points = settings.int("performance-page-data-points") as u32;
+                    let delay = settings.uint64("app-update-interval-u64");
+                    let graph_max_duration =
+                        (((delay as f64) * INTERVAL_STEP) * (data_points as f64)).round() as u32;
+
+                    this.imp()
+                        .graph_max_duration
+                        .set_text(&to_short_human_readable_time(
+                            graph_max_duration * graph_widget.downsample(),
+                        ));
+                }
+            });
 
             let this = self.obj().upcast_ref::<super::PerformancePageCpu>().clone();
             graph_widgets[0].connect_local("resize", true, move |_| {
@@ -753,6 +878,9 @@ mod imp {
                     });
                 }
                 graph_widgets[graph_widget_index].set_data_points(data_points);
+                graph_widgets[graph_widget_index].set_value_formatter(cpu_usage_text);
+                graph_widgets[graph_widget_index]
+                    .set_axis_formatter(|value| format!("{:.0}%", value));
                 graph_widgets[graph_widget_index].set_smooth_graphs(smooth);
                 graph_widgets[graph_widget_index].set_do_animation(sliding);
                 graph_widgets[graph_widget_index].set_expected_animation_ticks(delay);
@@ -829,6 +957,7 @@ mod imp {
             Self::configure_actions(&this);
             Self::configure_context_menu(&this);
 
+            TopProcesses::ensure_type();
             let sidebar_content_builder = gtk::Builder::from_resource(
                 "/io/missioncenter/MissionCenter/ui/performance_page/cpu_details.ui",
             );
@@ -878,6 +1007,16 @@ mod imp {
                     .object::<gtk::Label>("uptime")
                     .expect("Could not find `uptime` object in details pane"),
             );
+            let _ = self.pressure.set(
+                sidebar_content_builder
+                    .object::<gtk::Label>("pressure")
+                    .expect("Could not find `pressure` object in details pane"),
+            );
+            let _ = self.top_consumers.set(
+                sidebar_content_builder
+                    .object::<TopProcesses>("top_consumers")
+                    .expect("Could not find `top_consumers` object in details pane"),
+            );
             let _ = self.base_speed.set(
                 sidebar_content_builder
                     .object::<gtk::Label>("base_speed")
@@ -997,10 +1136,14 @@ impl PerformancePageCpu {
             let graph_max_duration =
                 (((delay as f64) * INTERVAL_STEP) * (data_points as f64)).round() as u32;
 
+            let widgets = this.graph_widgets.take();
+
+            let downsample = widgets.first().map(|w| w.downsample()).unwrap_or(1);
             this.graph_max_duration
-                .set_text(&to_short_human_readable_time(graph_max_duration));
+                .set_text(&to_short_human_readable_time(
+                    graph_max_duration * downsample,
+                ));
 
-            let widgets = this.graph_widgets.take();
             for graph_widget in &widgets {
                 graph_widget.set_data_points(data_points);
                 graph_widget.set_smooth_graphs(smooth);
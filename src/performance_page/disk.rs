@@ -19,6 +19,7 @@
  */
 
 use std::cell::{Cell, OnceCell, RefCell};
+use std::collections::HashMap;
 
 use adw::{prelude::AdwDialogExt, subclass::prelude::*};
 use glib::{g_warning, ParamSpec, Properties, Value};
@@ -27,11 +28,24 @@ use gtk::{gio, glib, prelude::*};
 use magpie_types::disks::{Disk, DiskKind};
 
 use crate::application::INTERVAL_STEP;
+use crate::export::Unit;
 use crate::i18n::*;
+use crate::magpie_client::{CryptoDriver, DiskEncryption, Pressure, Process};
 use crate::{app, to_short_human_readable_time};
 
-use super::widgets::{EjectFailureDialog, GraphWidget, SmartDataDialog, SmartFailureDialog};
-use super::PageExt;
+use super::widgets::{
+    EjectFailureDialog, GraphWidget, HistoryDialog, HistoryKind, SmartDataDialog,
+    SmartFailureDialog,
+};
+use super::{export_graph_data, save_page_image, update_pressure_label, PageExt};
+
+/// What the data sets of the graphs are exported as, in the order `export_graph_data` is
+/// handed them
+pub(super) const GRAPH_FIELDS: [(&str, Unit); 3] = [
+    ("disk.busy", Unit::Percent),
+    ("disk.read", Unit::BytesPerSecond),
+    ("disk.write", Unit::BytesPerSecond),
+];
 
 mod imp {
     use super::*;
@@ -136,6 +150,51 @@ mod imp {
                 }
             });
             actions.add_action(&action);
+
+            let action = gio::SimpleAction::new("export-data", None);
+            action.connect_activate({
+                let this = this.downgrade();
+                move |_, _| {
+                    let Some(this) = this.upgrade() else {
+                        return;
+                    };
+
+                    let imp = this.imp();
+                    export_graph_data(
+                        this.upcast_ref(),
+                        &[
+                            (imp.usage_graph.get(), 0),
+                            (imp.disk_transfer_rate_graph.get(), 0),
+                            (imp.disk_transfer_rate_graph.get(), 1),
+                        ],
+                        &GRAPH_FIELDS,
+                    );
+                }
+            });
+            actions.add_action(&action);
+
+            let action = gio::SimpleAction::new("save-image", None);
+            action.connect_activate({
+                let this = this.downgrade();
+                move |_, _| {
+                    if let Some(this) = this.upgrade() {
+                        save_page_image(this.upcast_ref());
+                    }
+                }
+            });
+            actions.add_action(&action);
+
+            let action = gio::SimpleAction::new("history", None);
+            action.connect_activate({
+                let this = this.downgrade();
+                move |_, _| {
+                    if let Some(this) = this.upgrade() {
+                        HistoryDialog::new(HistoryKind::Disk, this.base_color())
+                            .present(Some(this.upcast_ref::<gtk::Widget>()));
+                    }
+                }
+            });
+            actions.add_action(&action);
         }
 
         fn configure_context_menu(this: &super::PerformancePageDisk) {
@@ -215,6 +274,28 @@ mod imp {
             this.disk_transfer_rate_graph.set_dashed(1, true);
             this.disk_transfer_rate_graph.set_filled(1, false);
 
+            this.usage_graph.set_value_formatter(|_, value| {
+                i18n_f("Active time: {}%", &[&format!("{:.1}", value)])
+            });
+            this.usage_graph
+                .set_axis_formatter(|value| format!("{:.0}%", value));
+            this.disk_transfer_rate_graph.set_axis_formatter(|value| {
+                crate::to_human_readable_nice(value, &DataType::DriveBytesPerSecond, &settings!())
+            });
+            this.disk_transfer_rate_graph
+                .set_value_formatter(|index, value| {
+                    let value = crate::to_human_readable_nice(
+                        value,
+                        &DataType::DriveBytesPerSecond,
+                        &settings!(),
+                    );
+                    if index == 0 {
+                        i18n_f("Read: {}", &[&value])
+                    } else {
+                        i18n_f("Write: {}", &[&value])
+                    }
+                });
+
             this.infobar_content
                 .legend_read()
                 .set_resource(Some("/io/missioncenter/MissionCenter/line-solid-disk.svg"));
@@ -434,6 +515,88 @@ mod imp {
             true
         }
 
+        pub fn update_encryption(
+            this: &super::PerformancePageDisk,
+            disk: &Disk,
+            encryption: &DiskEncryption,
+        ) {
+            let this = this.imp();
+
+            let volumes = encryption
+                .volumes
+                .iter()
+                .filter(|v| v.disk_id == disk.id)
+                .collect::<Vec<_>>();
+            if volumes.is_empty() {
+                this.infobar_content.set_encryption_visible(false);
+                return;
+            }
+
+            let format = match volumes[0].luks_version {
+                Some(version) => format!("LUKS{}", version),
+                None => "dm-crypt".to_owned(),
+            };
+            let mut parts = vec![format, cipher_implementation(encryption.driver.as_ref())];
+
+            // Encrypted volumes sit on top of the disk, so whatever a request spends on top of
+            // the time the disk took is mostly encryption
+            let overhead = volumes
+                .iter()
+                .filter_map(|v| Some(v.latency_ms? - v.disk_latency_ms?))
+                .fold(None, |max: Option<f32>, o| {
+                    Some(max.map_or(o, |m| m.max(o)))
+                });
+            if let Some(overhead) = overhead {
+                parts.push(i18n_f(
+                    "+{} ms latency",
+                    &[&format!("{:.2}", overhead.max(0.))],
+                ));
+            }
+
+            let encryption_label = this.infobar_content.encryption();
+            encryption_label.set_text(&parts.join(" · "));
+
+            let mut tooltip = volumes
+                .iter()
+                .map(|v| format!("{} ({})", v.name, v.device))
+                .collect::<Vec<_>>();
+            if let Some(driver) = &encryption.driver {
+                tooltip.push(i18n_f("Kernel driver: {}", &[&driver.driver]));
+            }
+            if let Some(benchmark) = &encryption.benchmark {
+                tooltip.push(i18n_f(
+                    "Cipher throughput: {} MiB/s encryption, {} MiB/s decryption",
+                    &[
+                        &format!("{:.0}", benchmark.encryption),
+                        &format!("{:.0}", benchmark.decryption),
+                    ],
+                ));
+            }
+            encryption_label.set_tooltip_text(Some(&tooltip.join("\n")));
+
+            this.infobar_content.set_encryption_visible(true);
+        }
+
+        pub fn update_pressure(this: &super::PerformancePageDisk, io_pressure: Option<&Pressure>) {
+            // Pressure is only tracked system-wide, it is the same on every disk page
+            update_pressure_label(this.imp().infobar_content.io_pressure(), io_pressure);
+        }
+
+        pub fn update_top_consumers(
+            this: &super::PerformancePageDisk,
+            processes: &HashMap<u32, Process>,
+        ) {
+            // Processes report their I/O summed over all drives, so each page lists the same ones
+            let settings = settings!();
+            this.imp().infobar_content.top_consumers().update(
+                processes,
+                |process| process.usage_stats.disk_usage,
+                |usage| {
+                    crate::to_human_readable_nice(usage, &DataType::DriveBytesPerSecond, &settings)
+                },
+            );
+        }
+
         pub fn update_animations(this: &super::PerformancePageDisk) -> bool {
             let this = this.imp();
 
@@ -459,7 +622,9 @@ mod imp {
     Write speed:           {}
     Total written          {}
     Active time:           {}
-    Average response time: {}"#,
+    Average response time: {}
+    Encryption:            {}
+    I/O pressure:          {}"#,
                 self.disk_id.label(),
                 self.model.label(),
                 self.infobar_content.capacity().label(),
@@ -472,6 +637,12 @@ mod imp {
                 self.infobar_content.total_write().label(),
                 self.infobar_content.active_time().label(),
                 self.infobar_content.avg_response_time().label(),
+                if self.infobar_content.encryption_visible() {
+                    self.infobar_content.encryption().label()
+                } else {
+                    i18n("None").into()
+                },
+                self.infobar_content.io_pressure().label(),
             )
         }
     }
@@ -554,7 +725,9 @@ impl PerformancePageDisk {
             let this = this.imp();
 
             this.graph_max_duration
-                .set_text(&to_short_human_readable_time(graph_max_duration));
+                .set_text(&to_short_human_readable_time(
+                    graph_max_duration * this.usage_graph.downsample(),
+                ));
             this.usage_graph.set_data_points(data_points);
             this.usage_graph.set_smooth_graphs(smooth);
             this.usage_graph.set_do_animation(sliding);
@@ -567,6 +740,16 @@ impl PerformancePageDisk {
         }
         update_refresh_rate_sensitive_labels(&this, settings);
 
+        this.imp().usage_graph.connect_downsample_notify({
+            let this = this.downgrade();
+            let settings = settings.clone();
+            move |_| {
+                if let Some(this) = this.upgrade() {
+                    update_refresh_rate_sensitive_labels(&this, &settings);
+                }
+            }
+        });
+
         settings.connect_changed(Some("performance-page-data-points"), {
             let this = this.downgrade();
             move |settings, _| {
@@ -614,7 +797,37 @@ impl PerformancePageDisk {
         imp::PerformancePageDisk::update_readings(self, index, disk)
     }
 
+    pub fn update_encryption(&self, disk: &Disk, encryption: &DiskEncryption) {
+        imp::PerformancePageDisk::update_encryption(self, disk, encryption)
+    }
+
+    pub fn update_pressure(&self, io_pressure: Option<&Pressure>) {
+        imp::PerformancePageDisk::update_pressure(self, io_pressure)
+    }
+
+    pub fn update_top_consumers(&self, processes: &HashMap<u32, Process>) {
+        imp::PerformancePageDisk::update_top_consumers(self, processes)
+    }
+
     pub fn update_animations(&self) -> bool {
         imp::PerformancePageDisk::update_animations(self)
     }
 }
+
+fn cipher_implementation(driver: Option<&CryptoDriver>) -> String {
+    let Some(driver) = driver else {
+        return i18n("AES");
+    };
+
+    if !driver.accelerated {
+        i18n("Software AES")
+    } else if driver.driver.contains("vaes") {
+        "VAES".to_owned()
+    } else if driver.driver.contains("aesni") {
+        "AES-NI".to_owned()
+    } else if driver.driver.ends_with("-ce") || driver.driver.contains("-ce-") {
+        i18n("ARMv8 Crypto Extensions")
+    } else {
+        i18n("Hardware AES")
+    }
+}
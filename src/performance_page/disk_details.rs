@@ -21,6 +21,8 @@
 use glib::{ParamSpec, Properties, Value};
 use gtk::{gdk::prelude::*, glib, subclass::prelude::*};
 
+use super::widgets::TopProcesses;
+
 mod imp {
     use super::*;
     use std::cell::Cell;
@@ -62,6 +64,12 @@ mod imp {
         pub wwn: TemplateChild<gtk::Label>,
         #[template_child]
         pub serial_number: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub encryption: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub io_pressure: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub top_consumers: TemplateChild<TopProcesses>,
 
         #[property(get, set)]
         rotation_visible: Cell<bool>,
@@ -69,6 +77,8 @@ mod imp {
         wwn_visible: Cell<bool>,
         #[property(get, set)]
         serial_number_visible: Cell<bool>,
+        #[property(get, set)]
+        encryption_visible: Cell<bool>,
     }
 
     impl Default for DiskDetails {
@@ -89,9 +99,13 @@ mod imp {
                 disk_type: Default::default(),
                 wwn: Default::default(),
                 serial_number: Default::default(),
+                encryption: Default::default(),
+                io_pressure: Default::default(),
+                top_consumers: Default::default(),
                 rotation_visible: Cell::new(false),
                 wwn_visible: Cell::new(false),
                 serial_number_visible: Cell::new(false),
+                encryption_visible: Cell::new(false),
             }
         }
     }
@@ -105,6 +119,7 @@ mod imp {
         type ParentType = gtk::Box;
 
         fn class_init(klass: &mut Self::Class) {
+            TopProcesses::ensure_type();
             klass.bind_template();
         }
 
@@ -210,4 +225,16 @@ impl DiskDetails {
     pub fn wwn(&self) -> &gtk::Label {
         &self.imp().wwn
     }
+
+    pub fn encryption(&self) -> &gtk::Label {
+        &self.imp().encryption
+    }
+
+    pub fn io_pressure(&self) -> &gtk::Label {
+        &self.imp().io_pressure
+    }
+
+    pub fn top_consumers(&self) -> &TopProcesses {
+        &self.imp().top_consumers
+    }
 }
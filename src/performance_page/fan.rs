@@ -19,19 +19,31 @@
  */
 
 use std::cell::{Cell, OnceCell, RefCell};
+use std::time::Duration;
 
 use adw;
 use adw::subclass::prelude::*;
-use glib::{ParamSpec, Properties, Value};
+use glib::{g_warning, ParamSpec, Properties, Value};
 use gtk::{gio, glib, prelude::*};
 
 use magpie_types::fan::Fan;
 
-use super::widgets::GraphWidget;
+use super::widgets::{FanCorrelationWidget, FanCurveWidget, GaugeWidget, GraphWidget};
 use crate::application::INTERVAL_STEP;
+use crate::export::Unit;
 use crate::i18n::*;
-use crate::performance_page::{PageExt, MK_TO_0_C};
+use crate::magpie_client::{pwm_channel, FanCurve, MIN_MANUAL_PWM_PERCENT};
+use crate::performance_page::{export_graph_data, save_page_image, PageExt, MK_TO_0_C};
 use crate::to_short_human_readable_time;
+use crate::{app, settings};
+
+/// What the data sets of the graphs are exported as, in the order `export_graph_data` is
+/// handed them
+pub(super) const GRAPH_FIELDS: [(&str, Unit); 3] = [
+    ("fan.speed", Unit::Rpm),
+    ("fan.pwm", Unit::Percent),
+    ("fan.temperature", Unit::Celsius),
+];
 
 mod imp {
     use super::*;
@@ -50,6 +62,8 @@ mod imp {
         #[template_child]
         pub temp_graph: TemplateChild<GraphWidget>,
 
+        #[template_child]
+        pub speed_graph_caption: TemplateChild<gtk::Box>,
         #[template_child]
         pub speed_max_y: TemplateChild<gtk::Label>,
         #[template_child]
@@ -64,6 +78,20 @@ mod imp {
         #[template_child]
         pub temp_graph_box: TemplateChild<gtk::Box>,
 
+        #[template_child]
+        pub presentation: TemplateChild<gtk::Stack>,
+        #[template_child]
+        pub speed_gauge: TemplateChild<GaugeWidget>,
+        #[template_child]
+        pub pwm_gauge: TemplateChild<GaugeWidget>,
+        #[template_child]
+        pub temp_gauge: TemplateChild<GaugeWidget>,
+
+        #[template_child]
+        pub correlation: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub correlation_graph: TemplateChild<FanCorrelationWidget>,
+
         #[template_child]
         pub context_menu: TemplateChild<gtk::Popover>,
 
@@ -84,6 +112,16 @@ mod imp {
         pub legend_pwm: OnceCell<gtk::Picture>,
         pub pwm: OnceCell<gtk::Label>,
         pub temp: OnceCell<gtk::Label>,
+        pub box_control: OnceCell<gtk::Box>,
+        pub manual_control: OnceCell<gtk::Switch>,
+        pub pwm_scale: OnceCell<gtk::Scale>,
+        pub box_curve: OnceCell<gtk::Box>,
+        pub curve_control: OnceCell<gtk::Switch>,
+        pub fan_curve: OnceCell<FanCurveWidget>,
+
+        hwmon_index: Cell<u32>,
+        fan_index: Cell<u32>,
+        pwm_update_source: RefCell<Option<glib::SourceId>>,
     }
 
     impl Default for PerformancePageFan {
@@ -93,12 +131,19 @@ mod imp {
                 title_temp_name: Default::default(),
                 speed_graph: Default::default(),
                 temp_graph: Default::default(),
+                speed_graph_caption: Default::default(),
                 speed_max_y: Default::default(),
                 speed_graph_max_duration: Default::default(),
                 speed_box: Default::default(),
                 temp_max_y: Default::default(),
                 temp_graph_max_duration: Default::default(),
                 temp_graph_box: Default::default(),
+                presentation: Default::default(),
+                speed_gauge: Default::default(),
+                pwm_gauge: Default::default(),
+                temp_gauge: Default::default(),
+                correlation: Default::default(),
+                correlation_graph: Default::default(),
                 context_menu: Default::default(),
 
                 name: RefCell::new(String::new()),
@@ -114,6 +159,16 @@ mod imp {
                 legend_pwm: Default::default(),
                 pwm: Default::default(),
                 temp: Default::default(),
+                box_control: Default::default(),
+                manual_control: Default::default(),
+                pwm_scale: Default::default(),
+                box_curve: Default::default(),
+                curve_control: Default::default(),
+                fan_curve: Default::default(),
+
+                hwmon_index: Cell::new(0),
+                fan_index: Cell::new(0),
+                pwm_update_source: RefCell::new(None),
             }
         }
     }
@@ -152,6 +207,202 @@ mod imp {
                 }
             });
             actions.add_action(&action);
+
+            let action = gio::SimpleAction::new("export-data", None);
+            action.connect_activate({
+                let this = this.downgrade();
+                move |_, _| {
+                    let Some(this) = this.upgrade() else {
+                        return;
+                    };
+
+                    let imp = this.imp();
+                    export_graph_data(
+                        this.upcast_ref(),
+                        &[
+                            (imp.speed_graph.get(), 0),
+                            (imp.speed_graph.get(), 1),
+                            (imp.temp_graph.get(), 0),
+                        ],
+                        &GRAPH_FIELDS,
+                    );
+                }
+            });
+            actions.add_action(&action);
+
+            let action = gio::SimpleAction::new("save-image", None);
+            action.connect_activate({
+                let this = this.downgrade();
+                move |_, _| {
+                    if let Some(this) = this.upgrade() {
+                        save_page_image(this.upcast_ref());
+                    }
+                }
+            });
+            actions.add_action(&action);
+        }
+
+        fn configure_fan_control(this: &super::PerformancePageFan) {
+            let imp = this.imp();
+
+            let (Some(manual_control), Some(pwm_scale)) =
+                (imp.manual_control.get(), imp.pwm_scale.get())
+            else {
+                return;
+            };
+
+            pwm_scale
+                .adjustment()
+                .set_lower(MIN_MANUAL_PWM_PERCENT as f64);
+
+            manual_control.connect_active_notify({
+                let this = this.downgrade();
+                move |manual_control| {
+                    let Some(this) = this.upgrade() else {
+                        return;
+                    };
+                    let imp = this.imp();
+
+                    if let Some(source) = imp.pwm_update_source.take() {
+                        source.remove();
+                    }
+
+                    // A fan is either driven by the slider or by its curve, never both
+                    if manual_control.is_active() {
+                        if let Some(curve_control) = imp.curve_control.get() {
+                            curve_control.set_active(false);
+                        }
+                    }
+
+                    let pwm_percent = if manual_control.is_active() {
+                        imp.pwm_scale.get().map(|scale| scale.value() as f32)
+                    } else {
+                        None
+                    };
+                    imp.set_fan_speed(pwm_percent);
+                }
+            });
+
+            // Don't hammer sysfs (or the polkit agent) while the slider is being dragged
+            pwm_scale.connect_value_changed({
+                let this = this.downgrade();
+                move |_| {
+                    let Some(this) = this.upgrade() else {
+                        return;
+                    };
+                    let imp = this.imp();
+
+                    if let Some(source) = imp.pwm_update_source.take() {
+                        source.remove();
+                    }
+
+                    let source = glib::timeout_add_local_once(Duration::from_millis(300), {
+                        let this = this.downgrade();
+                        move || {
+                            let Some(this) = this.upgrade() else {
+                                return;
+                            };
+                            let imp = this.imp();
+
+                            imp.pwm_update_source.take();
+
+                            let manual = imp
+                                .manual_control
+                                .get()
+                                .map(|mc| mc.is_active())
+                                .unwrap_or(false);
+                            if !manual {
+                                return;
+                            }
+
+                            let pwm_percent = imp.pwm_scale.get().map(|scale| scale.value() as f32);
+                            imp.set_fan_speed(pwm_percent);
+                        }
+                    });
+                    imp.pwm_update_source.replace(Some(source));
+                }
+            });
+        }
+
+        fn configure_fan_curve(this: &super::PerformancePageFan) {
+            let imp = this.imp();
+
+            let (Some(curve_control), Some(fan_curve)) =
+                (imp.curve_control.get(), imp.fan_curve.get())
+            else {
+                return;
+            };
+
+            curve_control.connect_active_notify({
+                let this = this.downgrade();
+                move |curve_control| {
+                    let Some(this) = this.upgrade() else {
+                        return;
+                    };
+                    let imp = this.imp();
+
+                    if curve_control.is_active() {
+                        if let Some(manual_control) = imp.manual_control.get() {
+                            manual_control.set_active(false);
+                        }
+                    }
+
+                    imp.apply_fan_curve();
+                }
+            });
+
+            fan_curve.connect_local("changed", false, {
+                let this = this.downgrade();
+                move |_| {
+                    if let Some(this) = this.upgrade() {
+                        this.imp().apply_fan_curve();
+                    }
+                    None
+                }
+            });
+        }
+
+        fn apply_fan_curve(&self) {
+            let curve = match (self.curve_control.get(), self.fan_curve.get()) {
+                (Some(curve_control), Some(fan_curve)) if curve_control.is_active() => {
+                    Some(fan_curve.curve())
+                }
+                _ => None,
+            };
+
+            save_fan_curve(self.hwmon_index.get(), self.fan_index.get(), curve.as_ref());
+
+            match app!().sys_info() {
+                Ok(sys_info) => {
+                    sys_info.set_fan_curve(self.hwmon_index.get(), self.fan_index.get(), curve);
+                }
+                Err(e) => {
+                    g_warning!(
+                        "MissionCenter::PerformancePage",
+                        "Failed to get sys_info from MissionCenterApplication: {}",
+                        e
+                    );
+                }
+            }
+        }
+
+        fn set_fan_speed(&self, pwm_percent: Option<f32>) {
+            match app!().sys_info() {
+                Ok(sys_info) => {
+                    sys_info.set_fan_speed(
+                        self.hwmon_index.get(),
+                        self.fan_index.get(),
+                        pwm_percent,
+                    );
+                }
+                Err(e) => {
+                    g_warning!(
+                        "MissionCenter::PerformancePage",
+                        "Failed to get sys_info from MissionCenterApplication: {}",
+                        e
+                    );
+                }
+            }
         }
 
         fn configure_context_menu(this: &super::PerformancePageFan) {
@@ -183,6 +434,9 @@ mod imp {
 
             let this = this.imp();
 
+            this.hwmon_index.set(fan.hwmon_index as u32);
+            this.fan_index.set(fan.fan_index as u32);
+
             this.speed_graph.connect_local("resize", true, move |_| {
                 let this = t.imp();
 
@@ -235,8 +489,22 @@ mod imp {
 
             this.speed_graph.set_filled(1, false);
             this.speed_graph.set_dashed(1, true);
+            this.speed_graph.set_value_formatter(|index, value| {
+                if index == 0 {
+                    i18n_f("Speed: {} RPM", &[&format!("{:.0}", value)])
+                } else {
+                    i18n_f("PWM: {}%", &[&format!("{:.0}", value)])
+                }
+            });
+            this.temp_graph
+                .set_axis_formatter(|value| format!("{:.0} °C", value));
+            this.temp_graph.set_value_formatter(|_, value| {
+                i18n_f("Temperature: {} °C", &[&format!("{:.0}", value)])
+            });
 
             if fan.pwm_percent.is_none() {
+                this.pwm_gauge.set_visible(false);
+
                 if let Some(box_pwm) = this.bow_pwm.get() {
                     box_pwm.set_visible(false);
                 }
@@ -249,6 +517,11 @@ mod imp {
             if fan.temp_amount.is_none() {
                 this.temp_graph_box.set_visible(false);
 
+                // Nothing to base a curve on
+                if let Some(box_curve) = this.box_curve.get() {
+                    box_curve.set_visible(false);
+                }
+
                 if let Some(sidebar_temp_box) = this.box_temp.get() {
                     sidebar_temp_box.set_visible(false);
                 }
@@ -256,7 +529,29 @@ mod imp {
 
             if let Some(max_rpm) = fan.max_rpm {
                 this.speed_max_y.set_text(&format!("{}", max_rpm));
+                this.speed_gauge.set_max_value(max_rpm as f32);
+                this.correlation_graph.set_max_rpm(max_rpm as f32);
             }
+
+            // Only fans of this computer with a PWM channel of their own can be controlled
+            let controllable = fan.pwm_percent.is_some()
+                && app!().remote_host().is_none()
+                && pwm_channel(fan.hwmon_index as u32, fan.fan_index as u32).is_some();
+            if let Some(box_control) = this.box_control.get() {
+                box_control.set_visible(controllable);
+            }
+
+            if controllable && fan.temp_amount.is_some() {
+                if let Some(curve) = load_fan_curve(fan.hwmon_index as u32, fan.fan_index as u32) {
+                    if let (Some(fan_curve), Some(curve_control)) =
+                        (this.fan_curve.get(), this.curve_control.get())
+                    {
+                        fan_curve.set_curve(&curve);
+                        curve_control.set_active(true);
+                    }
+                }
+            }
+
             true
         }
 
@@ -280,6 +575,17 @@ mod imp {
                 speed_send.set_text(&i18n_f("{} RPM", &[&format!("{}", fan.rpm)]));
             }
 
+            // Keep the slider in sync with what the fan is actually doing until the user takes over
+            if let (Some(manual_control), Some(pwm_scale), Some(pwm_percent)) = (
+                this.manual_control.get(),
+                this.pwm_scale.get(),
+                fan.pwm_percent,
+            ) {
+                if !manual_control.is_active() {
+                    pwm_scale.set_value((pwm_percent * 100.) as f64);
+                }
+            }
+
             if let Some(pwm) = this.pwm.get() {
                 pwm.set_text(&i18n_f(
                     "{}%",
@@ -296,29 +602,53 @@ mod imp {
                     temp.set_text(&i18n_f("{} °C", &[&format!("{:.1}", fan_temp_c)]));
                 }
 
+                if let Some(fan_curve) = this.fan_curve.get() {
+                    fan_curve.set_current_temperature(Some(fan_temp_c));
+                }
+
                 this.temp_graph.add_data_point(0, fan_temp_c);
+                this.temp_gauge.set_max_value(fan_temp_c.max(100.));
+                this.temp_gauge.set_reading(
+                    fan_temp_c,
+                    &i18n_f("{} °C", &[&format!("{:.0}", fan_temp_c)]),
+                );
                 this.temp_max_y.set_text(&format!(
                     "{} °C",
                     this.temp_graph
                         .max_all_time(0)
                         .unwrap_or(fan_temp_c.round())
                 ));
+
+                this.correlation_graph
+                    .add_sample(fan_temp_c, fan.rpm as f32);
+                this.correlation
+                    .set_text(&match this.correlation_graph.correlation() {
+                        Some(correlation) => {
+                            i18n_f("Correlation {}", &[&format!("{:.2}", correlation)])
+                        }
+                        // Not enough variation yet, e.g. an idle system at a steady temperature
+                        None => i18n("Correlation unknown"),
+                    });
             }
 
             this.speed_graph.add_data_point(0, fan.rpm as f32);
+            this.speed_gauge.set_reading(
+                fan.rpm as f32,
+                &i18n_f("{} RPM", &[&format!("{}", fan.rpm)]),
+            );
             if let Some(pwm_percent) = fan.pwm_percent {
                 this.speed_graph.add_data_point(1, pwm_percent * 100.);
+                this.pwm_gauge.set_reading(
+                    pwm_percent * 100.,
+                    &i18n_f("{}%", &[&format!("{:.0}", pwm_percent * 100.)]),
+                );
             }
 
             if fan.max_rpm.is_none() {
-                this.speed_max_y.set_text(&i18n_f(
-                    "{} RPM",
-                    &[&this
-                        .speed_graph
-                        .max_all_time(0)
-                        .unwrap_or(fan.rpm as f32)
-                        .to_string()],
-                ));
+                let max_rpm = this.speed_graph.max_all_time(0).unwrap_or(fan.rpm as f32);
+                this.speed_max_y
+                    .set_text(&i18n_f("{} RPM", &[&max_rpm.to_string()]));
+                this.speed_gauge.set_max_value(max_rpm);
             }
 
             true
@@ -373,6 +703,8 @@ mod imp {
         type ParentType = gtk::Box;
 
         fn class_init(klass: &mut Self::Class) {
+            FanCorrelationWidget::ensure_type();
+            GaugeWidget::ensure_type();
             klass.bind_template();
         }
 
@@ -403,6 +735,7 @@ mod imp {
             Self::configure_actions(&this);
             Self::configure_context_menu(&this);
 
+            FanCurveWidget::ensure_type();
             let sidebar_content_builder = gtk::Builder::from_resource(
                 "/io/missioncenter/MissionCenter/ui/performance_page/fan_details.ui",
             );
@@ -451,6 +784,41 @@ mod imp {
                     .object::<gtk::Label>("temp")
                     .expect("Could not find `temp` object in details pane"),
             );
+
+            let _ = self.box_control.set(
+                sidebar_content_builder
+                    .object::<gtk::Box>("box_control")
+                    .expect("Could not find `box_control` object in details pane"),
+            );
+            let _ = self.manual_control.set(
+                sidebar_content_builder
+                    .object::<gtk::Switch>("manual_control")
+                    .expect("Could not find `manual_control` object in details pane"),
+            );
+            let _ = self.pwm_scale.set(
+                sidebar_content_builder
+                    .object::<gtk::Scale>("pwm_scale")
+                    .expect("Could not find `pwm_scale` object in details pane"),
+            );
+
+            let _ = self.box_curve.set(
+                sidebar_content_builder
+                    .object::<gtk::Box>("box_curve")
+                    .expect("Could not find `box_curve` object in details pane"),
+            );
+            let _ = self.curve_control.set(
+                sidebar_content_builder
+                    .object::<gtk::Switch>("curve_control")
+                    .expect("Could not find `curve_control` object in details pane"),
+            );
+            let _ = self.fan_curve.set(
+                sidebar_content_builder
+                    .object::<FanCurveWidget>("fan_curve")
+                    .expect("Could not find `fan_curve` object in details pane"),
+            );
+
+            Self::configure_fan_control(&this);
+            Self::configure_fan_curve(&this);
         }
     }
 
@@ -459,6 +827,53 @@ mod imp {
     impl BoxImpl for PerformancePageFan {}
 }
 
+// Fan curves are stored as `hwmon<N>/fan<M>=temp:pwm,temp:pwm,...` entries separated by `;`
+fn fan_curve_id(hwmon_index: u32, fan_index: u32) -> String {
+    format!("hwmon{}/fan{}", hwmon_index, fan_index)
+}
+
+fn load_fan_curve(hwmon_index: u32, fan_index: u32) -> Option<FanCurve> {
+    let id = fan_curve_id(hwmon_index, fan_index);
+
+    settings!()
+        .string("performance-page-fan-curves")
+        .split(';')
+        .filter_map(|entry| entry.split_once('='))
+        .find(|(fan, _)| *fan == id)
+        .and_then(|(_, curve)| FanCurve::parse(curve))
+}
+
+fn save_fan_curve(hwmon_index: u32, fan_index: u32, curve: Option<&FanCurve>) {
+    let id = fan_curve_id(hwmon_index, fan_index);
+
+    let settings = settings!();
+    let current = settings.string("performance-page-fan-curves");
+
+    let mut entries = current
+        .split(';')
+        .filter(|entry| !entry.is_empty())
+        .filter(|entry| entry.split_once('=').map(|(fan, _)| fan) != Some(id.as_str()))
+        .map(|entry| entry.to_owned())
+        .collect::<Vec<_>>();
+    if let Some(curve) = curve {
+        entries.push(format!("{}={}", id, curve));
+    }
+
+    let new = entries.join(";");
+    if new == current.as_str() {
+        return;
+    }
+
+    if let Err(e) = settings.set_string("performance-page-fan-curves", &new) {
+        g_warning!(
+            "MissionCenter::PerformancePage",
+            "Failed to save fan curve for {}: {}",
+            id,
+            e
+        );
+    }
+}
+
 glib::wrapper! {
     pub struct PerformancePageFan(ObjectSubclass<imp::PerformancePageFan>)
         @extends gtk::Box, gtk::Widget,
@@ -498,7 +913,8 @@ impl PerformancePageFan {
 
             let this = this.imp();
 
-            let time_string = &to_short_human_readable_time(graph_max_duration);
+            let time_string =
+                &to_short_human_readable_time(graph_max_duration * this.speed_graph.downsample());
 
             this.speed_graph_max_duration.set_text(time_string);
             this.speed_graph.set_data_points(data_points);
@@ -511,9 +927,21 @@ impl PerformancePageFan {
             this.temp_graph.set_smooth_graphs(smooth);
             this.temp_graph.set_do_animation(sliding);
             this.temp_graph.set_expected_animation_ticks(delay as u32);
+
+            this.correlation_graph.set_max_samples(data_points);
         }
         update_refresh_rate_sensitive_labels(&this, settings);
 
+        this.imp().speed_graph.connect_downsample_notify({
+            let this = this.downgrade();
+            let settings = settings.clone();
+            move |_| {
+                if let Some(this) = this.upgrade() {
+                    update_refresh_rate_sensitive_labels(&this, &settings);
+                }
+            }
+        });
+
         settings.connect_changed(Some("performance-page-data-points"), {
             let this = this.downgrade();
             move |settings, _| {
@@ -550,6 +978,25 @@ impl PerformancePageFan {
             }
         });
 
+        fn update_presentation(this: &PerformancePageFan, settings: &gio::Settings) {
+            let this = this.imp();
+
+            let gauges = settings.boolean("performance-page-gauges");
+            this.presentation
+                .set_visible_child_name(if gauges { "gauges" } else { "graphs" });
+            this.speed_graph_caption.set_visible(!gauges);
+        }
+        update_presentation(&this, settings);
+
+        settings.connect_changed(Some("performance-page-gauges"), {
+            let this = this.downgrade();
+            move |settings, _| {
+                if let Some(this) = this.upgrade() {
+                    update_presentation(&this, settings);
+                }
+            }
+        });
+
         this
     }
 
@@ -29,11 +29,34 @@ use gtk::{gio, glib, prelude::*};
 use magpie_types::gpus::Gpu;
 use magpie_types::gpus::OpenGlVariant;
 
-use super::{widgets::GraphWidget, GpuDetails, PageExt};
+use super::{
+    export_graph_data, save_page_image, update_game_mode_badge,
+    widgets::{GaugeWidget, GraphWidget, HistoryDialog, HistoryKind},
+    GpuDetails, PageExt,
+};
 use crate::{
-    application::INTERVAL_STEP, i18n::*, settings, to_short_human_readable_time, DataType,
+    application::INTERVAL_STEP,
+    export::Unit,
+    i18n::*,
+    magpie_client::{GameModeClient, GpuTemperatures, TemperatureSensor},
+    settings, to_short_human_readable_time, DataType,
 };
 
+/// Degrees below a sensor's critical temperature at which it is highlighted
+const TEMPERATURE_WARNING_HEADROOM_C: f32 = 10.;
+
+/// What the data sets of the graphs are exported as, in the order `export_graph_data` is
+/// handed them
+pub(super) const GRAPH_FIELDS: [(&str, Unit); 7] = [
+    ("gpu.usage", Unit::Percent),
+    ("gpu.encoder_usage", Unit::Percent),
+    ("gpu.decoder_usage", Unit::Percent),
+    ("gpu.memory_used", Unit::Bytes),
+    ("gpu.temperature", Unit::Celsius),
+    ("gpu.hotspot_temperature", Unit::Celsius),
+    ("gpu.memory_temperature", Unit::Celsius),
+];
+
 mod imp {
     use super::*;
 
@@ -47,6 +70,8 @@ mod imp {
         #[template_child]
         pub device_name: TemplateChild<gtk::Label>,
         #[template_child]
+        pub game_mode_badge: TemplateChild<gtk::Label>,
+        #[template_child]
         pub graph_utilization: TemplateChild<GraphWidget>,
         #[template_child]
         pub container_bottom: TemplateChild<gtk::Box>,
@@ -63,6 +88,20 @@ mod imp {
         #[template_child]
         pub usage_graph_memory: TemplateChild<GraphWidget>,
         #[template_child]
+        pub temperature_graph: TemplateChild<gtk::Box>,
+        #[template_child]
+        pub temperature_max_y: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub usage_graph_temperature: TemplateChild<GraphWidget>,
+        #[template_child]
+        pub temperature_presentation: TemplateChild<gtk::Stack>,
+        #[template_child]
+        pub edge_gauge: TemplateChild<GaugeWidget>,
+        #[template_child]
+        pub hotspot_gauge: TemplateChild<GaugeWidget>,
+        #[template_child]
+        pub memory_temperature_gauge: TemplateChild<GaugeWidget>,
+        #[template_child]
         pub context_menu: TemplateChild<gtk::Popover>,
         #[template_child]
         pub graph_max_duration: TemplateChild<gtk::Label>,
@@ -81,6 +120,7 @@ mod imp {
         pub infobar_content: GpuDetails,
 
         show_enc_dec_action: gio::SimpleAction,
+        pub game_mode_active: Cell<Option<bool>>,
     }
 
     impl Default for PerformancePageGpu {
@@ -88,6 +128,7 @@ mod imp {
             Self {
                 gpu_id: Default::default(),
                 device_name: Default::default(),
+                game_mode_badge: Default::default(),
                 graph_utilization: Default::default(),
                 container_bottom: Default::default(),
                 encode_decode_graph: Default::default(),
@@ -96,6 +137,13 @@ mod imp {
                 total_memory: Default::default(),
                 memory_graph_label: Default::default(),
                 usage_graph_memory: Default::default(),
+                temperature_graph: Default::default(),
+                temperature_max_y: Default::default(),
+                usage_graph_temperature: Default::default(),
+                temperature_presentation: Default::default(),
+                edge_gauge: Default::default(),
+                hotspot_gauge: Default::default(),
+                memory_temperature_gauge: Default::default(),
                 context_menu: Default::default(),
                 graph_max_duration: Default::default(),
 
@@ -112,6 +160,8 @@ mod imp {
                     None,
                     &glib::Variant::from(true),
                 ),
+
+                game_mode_active: Cell::new(None),
             }
         }
     }
@@ -151,6 +201,55 @@ mod imp {
             });
             actions.add_action(&action);
 
+            let action = gio::SimpleAction::new("export-data", None);
+            action.connect_activate({
+                let this = this.downgrade();
+                move |_, _| {
+                    let Some(this) = this.upgrade() else {
+                        return;
+                    };
+
+                    let imp = this.imp();
+                    export_graph_data(
+                        this.upcast_ref(),
+                        &[
+                            (imp.graph_utilization.get(), 0),
+                            (imp.usage_graph_encode_decode.get(), 0),
+                            (imp.usage_graph_encode_decode.get(), 1),
+                            (imp.usage_graph_memory.get(), 0),
+                            (imp.usage_graph_temperature.get(), 0),
+                            (imp.usage_graph_temperature.get(), 1),
+                            (imp.usage_graph_temperature.get(), 2),
+                        ],
+                        &GRAPH_FIELDS,
+                    );
+                }
+            });
+            actions.add_action(&action);
+
+            let action = gio::SimpleAction::new("save-image", None);
+            action.connect_activate({
+                let this = this.downgrade();
+                move |_, _| {
+                    if let Some(this) = this.upgrade() {
+                        save_page_image(this.upcast_ref());
+                    }
+                }
+            });
+            actions.add_action(&action);
+
+            let action = gio::SimpleAction::new("history", None);
+            action.connect_activate({
+                let this = this.downgrade();
+                move |_, _| {
+                    if let Some(this) = this.upgrade() {
+                        HistoryDialog::new(HistoryKind::Gpu, this.base_color())
+                            .present(Some(this.upcast_ref::<gtk::Widget>()));
+                    }
+                }
+            });
+            actions.add_action(&action);
+
             let action = &this.imp().show_enc_dec_action;
             action.set_enabled(true);
             action.connect_activate(move |action, _| {
@@ -233,6 +332,9 @@ mod imp {
                     this.usage_graph_memory
                         .set_vertical_line_count((width * (a / b) / 30.).round().max(5.) as u32);
 
+                    this.usage_graph_temperature
+                        .set_vertical_line_count((width * (a / b) / 30.).round().max(5.) as u32);
+
                     None
                 }
             });
@@ -271,6 +373,7 @@ mod imp {
                         // space.
                         this.container_bottom.set_visible(
                             this.memory_graph.property::<bool>("visible")
+                                || this.temperature_graph.is_visible()
                                 || this.encode_decode_available.get(),
                         );
                     }
@@ -341,12 +444,57 @@ mod imp {
 
             this.infobar_content.pci_addr().set_text(gpu.id.as_ref());
 
+            this.usage_graph_temperature.set_filled(1, false);
+            this.usage_graph_temperature.set_filled(2, false);
+            this.usage_graph_temperature.set_dashed(2, true);
+
+            this.graph_utilization
+                .set_axis_formatter(|value| format!("{:.0}%", value));
+            this.usage_graph_encode_decode
+                .set_axis_formatter(|value| format!("{:.0}%", value));
+            this.usage_graph_memory.set_axis_formatter(|value| {
+                crate::to_human_readable_nice(value, &DataType::MemoryBytes, &settings!())
+            });
+            this.usage_graph_temperature
+                .set_axis_formatter(|value| format!("{:.0} °C", value));
+            this.graph_utilization.set_value_formatter(|_, value| {
+                i18n_f("Utilization: {}%", &[&format!("{:.1}", value)])
+            });
+            this.usage_graph_encode_decode
+                .set_value_formatter(|index, value| {
+                    let value = format!("{:.1}", value);
+                    if index == 0 {
+                        i18n_f("Encode: {}%", &[&value])
+                    } else {
+                        i18n_f("Decode: {}%", &[&value])
+                    }
+                });
+            this.usage_graph_memory.set_value_formatter(|index, value| {
+                let value =
+                    crate::to_human_readable_nice(value, &DataType::MemoryBytes, &settings!());
+                if index == 0 {
+                    i18n_f("Dedicated: {}", &[&value])
+                } else {
+                    i18n_f("Shared: {}", &[&value])
+                }
+            });
+            this.usage_graph_temperature
+                .set_value_formatter(|index, value| {
+                    let value = format!("{:.0}", value);
+                    match index {
+                        0 => i18n_f("Edge: {} °C", &[&value]),
+                        1 => i18n_f("Hotspot: {} °C", &[&value]),
+                        _ => i18n_f("Memory: {} °C", &[&value]),
+                    }
+                });
+
             true
         }
 
         pub fn update_readings(
             this: &super::PerformancePageGpu,
             gpu: &Gpu,
+            temperatures: Option<&GpuTemperatures>,
             index: Option<usize>,
         ) -> bool {
             let settings = &settings!();
@@ -365,14 +513,16 @@ mod imp {
             this.update_memory_info(gpu, settings);
             this.update_memory_speed(gpu, settings);
             this.update_video_encode_decode(gpu);
-            this.update_temperature(gpu);
+            this.update_temperature(gpu, temperatures);
             this.update_pcie(gpu);
 
             // The usage graph is `homogeneous: true`, so we need to hide the container if all
             // contained graphs are hidden so that the usage graph expands to fill the available
             // space.
             this.container_bottom.set_visible(
-                this.memory_graph.property::<bool>("visible") || this.encode_decode_available.get(),
+                this.memory_graph.property::<bool>("visible")
+                    || this.temperature_graph.is_visible()
+                    || this.encode_decode_available.get(),
             );
 
             true
@@ -384,6 +534,7 @@ mod imp {
             this.graph_utilization.update_animation();
             this.usage_graph_memory.update_animation();
             this.usage_graph_encode_decode.update_animation();
+            this.usage_graph_temperature.update_animation();
 
             true
         }
@@ -407,7 +558,7 @@ mod imp {
     Memory speed:  {} / {}
     Power draw:    {}{}
     Encode/Decode: {} / {}
-    Temperature:   {}"#,
+    Temperature:   {} {}"#,
                 self.gpu_id.label(),
                 self.device_name.label(),
                 self.infobar_content.opengl_version().label(),
@@ -429,6 +580,7 @@ mod imp {
                 self.infobar_content.encode_percent().label(),
                 self.infobar_content.decode_percent().label(),
                 self.infobar_content.temperature().label(),
+                self.infobar_content.temperature_sensors().label(),
             )
         }
 
@@ -758,15 +910,108 @@ mod imp {
             );
         }
 
-        fn update_temperature(&self, gpu: &Gpu) {
-            if let Some(temp) = gpu.temperature_c {
-                self.infobar_content.box_temp().set_visible(true);
+        fn update_temperature(&self, gpu: &Gpu, temperatures: Option<&GpuTemperatures>) {
+            let temperatures = temperatures.copied().unwrap_or_default();
 
-                self.infobar_content
-                    .temperature()
-                    .set_text(&format!("{} °C", temp.round() as i32));
-            } else {
+            let edge = temperatures
+                .edge
+                .map(|sensor| sensor.celsius)
+                .or(gpu.temperature_c);
+            let Some(edge) = edge else {
                 self.infobar_content.box_temp().set_visible(false);
+                self.temperature_graph.set_visible(false);
+                return;
+            };
+
+            self.infobar_content.box_temp().set_visible(true);
+            self.infobar_content
+                .temperature()
+                .set_text(&format!("{} °C", edge.round() as i32));
+
+            let mut sensors = vec![];
+            if let Some(hotspot) = temperatures.hotspot {
+                sensors.push(i18n_f(
+                    "Hotspot {} °C",
+                    &[&format!("{}", hotspot.celsius.round() as i32)],
+                ));
+            }
+            if let Some(memory) = temperatures.memory {
+                sensors.push(i18n_f(
+                    "Memory {} °C",
+                    &[&format!("{}", memory.celsius.round() as i32)],
+                ));
+            }
+            let sensors_label = self.infobar_content.temperature_sensors();
+            sensors_label.set_visible(!sensors.is_empty());
+            sensors_label.set_text(&sensors.join(" · "));
+
+            // Warn based on whichever sensor is closest to its limit, the hotspot and the memory
+            // throttle well before the edge temperature looks worrying
+            let temperature_label = self.infobar_content.temperature();
+            temperature_label.remove_css_class("warning");
+            temperature_label.remove_css_class("error");
+            temperature_label.set_tooltip_text(None);
+            if let Some(sensor) = temperatures.limiting_sensor() {
+                if sensor.headroom() <= 0. {
+                    temperature_label.add_css_class("error");
+                } else if sensor.headroom() < TEMPERATURE_WARNING_HEADROOM_C {
+                    temperature_label.add_css_class("warning");
+                }
+                if sensor.headroom() < TEMPERATURE_WARNING_HEADROOM_C {
+                    temperature_label.set_tooltip_text(Some(&i18n_f(
+                        "Close to the critical temperature of {} °C",
+                        &[&format!("{}", sensor.critical_celsius.round() as i32)],
+                    )));
+                }
+            }
+
+            self.temperature_graph.set_visible(true);
+            self.usage_graph_temperature.add_data_point(0, edge);
+            self.usage_graph_temperature
+                .set_data_visible(1, temperatures.hotspot.is_some());
+            if let Some(hotspot) = temperatures.hotspot {
+                self.usage_graph_temperature
+                    .add_data_point(1, hotspot.celsius);
+            }
+            self.usage_graph_temperature
+                .set_data_visible(2, temperatures.memory.is_some());
+            if let Some(memory) = temperatures.memory {
+                self.usage_graph_temperature
+                    .add_data_point(2, memory.celsius);
+            }
+
+            let max_all_time = (0..3)
+                .filter_map(|i| self.usage_graph_temperature.max_all_time(i))
+                .fold(edge, f32::max);
+            self.temperature_max_y
+                .set_text(&format!("{} °C", max_all_time.round() as i32));
+
+            let edge_sensor = temperatures.edge.unwrap_or(TemperatureSensor {
+                celsius: edge,
+                critical_celsius: f32::NAN,
+            });
+            for (gauge, sensor) in [
+                (&*self.edge_gauge, Some(edge_sensor)),
+                (&*self.hotspot_gauge, temperatures.hotspot),
+                (&*self.memory_temperature_gauge, temperatures.memory),
+            ] {
+                gauge.set_visible(sensor.is_some());
+                let Some(sensor) = sensor else {
+                    continue;
+                };
+
+                // Room past the critical temperature, so the critical zone shows
+                let critical = sensor.critical_celsius;
+                gauge.set_max_value(if critical.is_nan() {
+                    sensor.celsius.max(100.)
+                } else {
+                    critical + TEMPERATURE_WARNING_HEADROOM_C
+                });
+                gauge.set_zones(critical - TEMPERATURE_WARNING_HEADROOM_C, critical);
+                gauge.set_reading(
+                    sensor.celsius,
+                    &format!("{} °C", sensor.celsius.round() as i32),
+                );
             }
         }
 
@@ -799,6 +1044,7 @@ mod imp {
         type ParentType = gtk::Box;
 
         fn class_init(klass: &mut Self::Class) {
+            GaugeWidget::ensure_type();
             klass.bind_template();
         }
 
@@ -877,7 +1123,9 @@ impl PerformancePageGpu {
                 (((delay as f64) * INTERVAL_STEP) * (data_points as f64)).round() as u32;
 
             this.graph_max_duration
-                .set_text(&to_short_human_readable_time(graph_max_duration));
+                .set_text(&to_short_human_readable_time(
+                    graph_max_duration * this.graph_utilization.downsample(),
+                ));
 
             this.graph_utilization.set_data_points(data_points);
             this.graph_utilization.set_smooth_graphs(smooth);
@@ -894,12 +1142,27 @@ impl PerformancePageGpu {
             this.usage_graph_memory.set_do_animation(sliding);
             this.usage_graph_memory
                 .set_expected_animation_ticks(delay as u32);
+            this.usage_graph_temperature.set_data_points(data_points);
+            this.usage_graph_temperature.set_smooth_graphs(smooth);
+            this.usage_graph_temperature.set_do_animation(sliding);
+            this.usage_graph_temperature
+                .set_expected_animation_ticks(delay as u32);
         }
 
         let this: Self = glib::Object::builder().property("name", name).build();
         let settings = settings!();
         update_refresh_rate_sensitive_labels(&this, &settings);
 
+        this.imp().graph_utilization.connect_downsample_notify({
+            let this = this.downgrade();
+            let settings = settings.clone();
+            move |_| {
+                if let Some(this) = this.upgrade() {
+                    update_refresh_rate_sensitive_labels(&this, &settings);
+                }
+            }
+        });
+
         settings.connect_changed(Some("performance-page-data-points"), {
             let this = this.downgrade();
             move |settings, _| {
@@ -936,6 +1199,23 @@ impl PerformancePageGpu {
             }
         });
 
+        fn update_presentation(this: &PerformancePageGpu, settings: &gio::Settings) {
+            let gauges = settings.boolean("performance-page-gauges");
+            this.imp()
+                .temperature_presentation
+                .set_visible_child_name(if gauges { "gauges" } else { "graph" });
+        }
+        update_presentation(&this, &settings);
+
+        settings.connect_changed(Some("performance-page-gauges"), {
+            let this = this.downgrade();
+            move |settings, _| {
+                if let Some(this) = this.upgrade() {
+                    update_presentation(&this, settings);
+                }
+            }
+        });
+
         this
     }
 
@@ -943,8 +1223,23 @@ impl PerformancePageGpu {
         imp::PerformancePageGpu::set_static_information(self, index, gpu)
     }
 
-    pub fn update_readings(&self, gpu: &Gpu, index: Option<usize>) -> bool {
-        imp::PerformancePageGpu::update_readings(self, gpu, index)
+    pub fn update_readings(
+        &self,
+        gpu: &Gpu,
+        temperatures: Option<&GpuTemperatures>,
+        index: Option<usize>,
+    ) -> bool {
+        imp::PerformancePageGpu::update_readings(self, gpu, temperatures, index)
+    }
+
+    pub fn update_game_mode(&self, game_mode: &[GameModeClient]) {
+        let imp = self.imp();
+        update_game_mode_badge(
+            self.upcast_ref(),
+            &imp.game_mode_badge,
+            &imp.game_mode_active,
+            game_mode,
+        );
     }
 
     pub fn update_animations(&self) -> bool {
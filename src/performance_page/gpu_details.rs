@@ -73,6 +73,8 @@ mod imp {
         #[template_child]
         pub temperature: TemplateChild<gtk::Label>,
         #[template_child]
+        pub temperature_sensors: TemplateChild<gtk::Label>,
+        #[template_child]
         pub opengl_version: TemplateChild<gtk::Label>,
         #[template_child]
         pub vulkan_version: TemplateChild<gtk::Label>,
@@ -163,6 +165,7 @@ mod imp {
                 encode_percent: TemplateChild::default(),
                 decode_percent: TemplateChild::default(),
                 temperature: TemplateChild::default(),
+                temperature_sensors: TemplateChild::default(),
                 opengl_version: TemplateChild::default(),
                 vulkan_version: TemplateChild::default(),
                 pcie_speed_label: TemplateChild::default(),
@@ -389,6 +392,10 @@ impl GpuDetails {
         &self.imp().temperature
     }
 
+    pub fn temperature_sensors(&self) -> &gtk::Label {
+        &self.imp().temperature_sensors
+    }
+
     pub fn opengl_version(&self) -> &gtk::Label {
         &self.imp().opengl_version
     }
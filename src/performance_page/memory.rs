@@ -25,10 +25,22 @@ use glib::{ParamSpec, Properties, Value};
 use gtk::{gio, glib, prelude::*};
 
 use super::{
-    widgets::{GraphWidget, MemoryCompositionWidget},
+    export_graph_data, save_page_image, update_pressure_label,
+    widgets::{GraphWidget, HistoryDialog, HistoryKind, MemoryCompositionWidget, TopProcesses},
     PageExt,
 };
-use crate::{application::INTERVAL_STEP, i18n::*, settings, to_short_human_readable_time};
+use crate::{
+    application::INTERVAL_STEP, export::Unit, i18n::*, settings, to_short_human_readable_time,
+};
+
+/// What the data sets of the graphs are exported as, in the order `export_graph_data` is
+/// handed them
+pub(super) const GRAPH_FIELDS: [(&str, Unit); 4] = [
+    ("memory.committed", Unit::Bytes),
+    ("memory.dirty", Unit::Bytes),
+    ("memory.used", Unit::Bytes),
+    ("memory.swap_used", Unit::Bytes),
+];
 
 mod imp {
     use super::*;
@@ -94,6 +106,8 @@ mod imp {
         pub slots_used: OnceCell<gtk::Label>,
         pub form_factor: OnceCell<gtk::Label>,
         pub ram_type: OnceCell<gtk::Label>,
+        pub pressure: OnceCell<gtk::Label>,
+        pub top_consumers: OnceCell<TopProcesses>,
 
         pub legend_used: OnceCell<gtk::Picture>,
         pub legend_commited: OnceCell<gtk::Picture>,
@@ -144,6 +158,7 @@ mod imp {
                 slots_used: Default::default(),
                 form_factor: Default::default(),
                 ram_type: Default::default(),
+                pressure: Default::default(),
 
                 legend_used: Default::default(),
                 legend_commited: Default::default(),
@@ -197,6 +212,52 @@ mod imp {
             });
             actions.add_action(&action);
 
+            let action = gio::SimpleAction::new("export-data", None);
+            action.connect_activate({
+                let this = this.downgrade();
+                move |_, _| {
+                    let Some(this) = this.upgrade() else {
+                        return;
+                    };
+
+                    let imp = this.imp();
+                    export_graph_data(
+                        this.upcast_ref(),
+                        &[
+                            (imp.usage_graph.get(), 0),
+                            (imp.usage_graph.get(), 1),
+                            (imp.usage_graph.get(), 2),
+                            (imp.swap_usage_graph.get(), 0),
+                        ],
+                        &GRAPH_FIELDS,
+                    );
+                }
+            });
+            actions.add_action(&action);
+
+            let action = gio::SimpleAction::new("save-image", None);
+            action.connect_activate({
+                let this = this.downgrade();
+                move |_, _| {
+                    if let Some(this) = this.upgrade() {
+                        save_page_image(this.upcast_ref());
+                    }
+                }
+            });
+            actions.add_action(&action);
+
+            let action = gio::SimpleAction::new("history", None);
+            action.connect_activate({
+                let this = this.downgrade();
+                move |_, _| {
+                    if let Some(this) = this.upgrade() {
+                        HistoryDialog::new(HistoryKind::Memory, this.base_color())
+                            .present(Some(this.upcast_ref::<gtk::Widget>()));
+                    }
+                }
+            });
+            actions.add_action(&action);
+
             let action = gio::SimpleAction::new_stateful(
                 "memory_composition",
                 None,
@@ -476,6 +537,18 @@ mod imp {
                 ch.set_text(&cached);
             }
 
+            if let Some(pressure) = this.pressure.get() {
+                update_pressure_label(pressure, readings.mem_pressure.as_ref());
+            }
+
+            if let Some(top_consumers) = this.top_consumers.get() {
+                top_consumers.update(
+                    &readings.running_processes,
+                    |process| process.usage_stats.memory_usage as f32,
+                    |usage| crate::to_human_readable_nice(usage, &DataType::MemoryBytes, settings),
+                );
+            }
+
             if mem_info.swap_total == 0 {
                 this.action_swap_usage.set_enabled(false);
                 this.set_swap_space_graph_visible(false);
@@ -576,7 +649,8 @@ mod imp {
     Speed:       {}
     Slots used:  {}
     Form factor: {}
-    Type:        {}"#,
+    Type:        {}
+    Pressure:    {}"#,
                 self.total_ram.label(),
                 self.in_use
                     .get()
@@ -642,6 +716,10 @@ mod imp {
                         }
                     })
                     .unwrap_or(unknown.into()),
+                self.pressure
+                    .get()
+                    .map(|l| l.label())
+                    .unwrap_or(unknown.into()),
             )
         }
     }
@@ -683,6 +761,30 @@ mod imp {
             self.usage_graph.set_filled(0, false);
             self.usage_graph.set_dashed(0, true);
             self.usage_graph.set_filled(1, false);
+            for graph in [&*self.usage_graph, &*self.swap_usage_graph] {
+                graph.set_axis_formatter(|value| {
+                    crate::to_human_readable_nice(value, &DataType::MemoryBytes, &settings!())
+                });
+            }
+            self.usage_graph.set_value_formatter(|index, value| {
+                let value =
+                    crate::to_human_readable_nice(value, &DataType::MemoryBytes, &settings!());
+                match index {
+                    0 => i18n_f("Committed: {}", &[&value]),
+                    1 => i18n_f("Dirty: {}", &[&value]),
+                    _ => i18n_f("In use: {}", &[&value]),
+                }
+            });
+            self.swap_usage_graph.set_value_formatter(|_, value| {
+                i18n_f(
+                    "Swap in use: {}",
+                    &[&crate::to_human_readable_nice(
+                        value,
+                        &DataType::MemoryBytes,
+                        &settings!(),
+                    )],
+                )
+            });
 
             Self::configure_actions(&this);
             Self::configure_context_menu(&this);
@@ -739,6 +841,7 @@ mod imp {
                     .expect("Could not find `label_free` object in tooltip widget"),
             );
 
+            TopProcesses::ensure_type();
             let sidebar_content_builder = gtk::Builder::from_resource(
                 "/io/missioncenter/MissionCenter/ui/performance_page/memory_details.ui",
             );
@@ -815,6 +918,17 @@ mod imp {
                 .expect("Could not find `ram_type` object in details pane");
             ram_type.set_label(default_label);
             let _ = self.ram_type.set(ram_type);
+
+            let _ = self.pressure.set(
+                sidebar_content_builder
+                    .object::<gtk::Label>("pressure")
+                    .expect("Could not find `pressure` object in details pane"),
+            );
+            let _ = self.top_consumers.set(
+                sidebar_content_builder
+                    .object::<TopProcesses>("top_consumers")
+                    .expect("Could not find `top_consumers` object in details pane"),
+            );
         }
     }
 
@@ -867,7 +981,9 @@ impl PerformancePageMemory {
                 (((delay as f64) * INTERVAL_STEP) * (data_points as f64)).round() as u32;
 
             this.graph_max_duration
-                .set_text(&to_short_human_readable_time(graph_max_duration));
+                .set_text(&to_short_human_readable_time(
+                    graph_max_duration * this.usage_graph.downsample(),
+                ));
 
             this.usage_graph.set_data_points(data_points);
             this.swap_usage_graph.set_data_points(data_points);
@@ -881,6 +997,16 @@ impl PerformancePageMemory {
         }
         update_refresh_rate_sensitive_labels(&this, settings);
 
+        this.imp().usage_graph.connect_downsample_notify({
+            let this = this.downgrade();
+            let settings = settings.clone();
+            move |_| {
+                if let Some(this) = this.upgrade() {
+                    update_refresh_rate_sensitive_labels(&this, &settings);
+                }
+            }
+        });
+
         settings.connect_changed(Some("performance-page-data-points"), {
             let this = this.downgrade();
             move |settings, _| {
@@ -36,9 +36,16 @@ use magpie_types::fan::Fan;
 use magpie_types::gpus::Gpu;
 use magpie_types::network::{Connection, ConnectionKind};
 
-use crate::{i18n::*, magpie_client::DiskKind, settings};
+use crate::{
+    application::INTERVAL_STEP,
+    export::{self, Field, Table},
+    i18n::*,
+    magpie_client::{DiskKind, GameModeClient, Pressure, Ups},
+    settings,
+};
 
-use widgets::{GraphWidget, SidebarDropHint};
+pub use widgets::GraphWidget;
+use widgets::SidebarDropHint;
 
 mod cpu;
 mod disk;
@@ -49,6 +56,7 @@ mod gpu_details;
 mod memory;
 mod network;
 mod summary_graph;
+mod ups;
 mod widgets;
 
 type SummaryGraph = summary_graph::SummaryGraph;
@@ -59,32 +67,586 @@ type NetworkPage = network::PerformancePageNetwork;
 type GpuPage = gpu::PerformancePageGpu;
 type GpuDetails = gpu_details::GpuDetails;
 type FanPage = fan::PerformancePageFan;
+type UpsPage = ups::PerformancePageUps;
 
 trait PageExt {
     fn infobar_collapsed(&self);
     fn infobar_uncollapsed(&self);
 }
 
-const MK_TO_0_C: i32 = -273150;
+pub const MK_TO_0_C: i32 = -273150;
+
+// GNOME color palette: Blue 4
+pub const CPU_BASE_COLOR: [u8; 3] = [0x1c, 0x71, 0xd8];
+// GNOME color palette: Blue 2
+pub const MEMORY_BASE_COLOR: [u8; 3] = [0x62, 0xa0, 0xea];
+// GNOME color palette: Green 5
+pub const DISK_BASE_COLOR: [u8; 3] = [0x26, 0xa2, 0x69];
+// GNOME color palette: Purple 1
+pub const NETWORK_BASE_COLOR: [u8; 3] = [0xdc, 0x8a, 0xdd];
+// GNOME color palette: Red 1
+pub const GPU_BASE_COLOR: [u8; 3] = [0xf6, 0x61, 0x51];
+// GNOME color palette: Purple 4
+pub const FAN_BASE_COLOR: [u8; 3] = [0x81, 0x3d, 0x9c];
+// GNOME color palette: Orange 3
+pub const UPS_BASE_COLOR: [u8; 3] = [0xff, 0x78, 0x00];
+
+/// The categories graphs are colored by, along with their default colors
+pub const GRAPH_COLOR_CATEGORIES: [(&str, [u8; 3]); 7] = [
+    ("cpu", CPU_BASE_COLOR),
+    ("memory", MEMORY_BASE_COLOR),
+    ("disk", DISK_BASE_COLOR),
+    ("network", NETWORK_BASE_COLOR),
+    ("gpu", GPU_BASE_COLOR),
+    ("fan", FAN_BASE_COLOR),
+    ("ups", UPS_BASE_COLOR),
+];
+
+fn rgba(color: [u8; 3]) -> gdk::RGBA {
+    gdk::RGBA::new(
+        color[0] as f32 / 255.,
+        color[1] as f32 / 255.,
+        color[2] as f32 / 255.,
+        1.,
+    )
+}
+
+// Colors are stored as `name=#rrggbb` entries separated by `;`, where the name is either a
+// category or the name of a device's page
+fn load_graph_color(name: &str) -> Option<gdk::RGBA> {
+    settings!()
+        .string("performance-page-colors")
+        .split(';')
+        .filter_map(|entry| entry.split_once('='))
+        .find(|(entry_name, _)| *entry_name == name)
+        .and_then(|(_, color)| gdk::RGBA::parse(color).ok())
+}
+
+/// Set the color of a category or of a single device's page; `None` goes back to the default
+pub fn save_graph_color(name: &str, color: Option<&gdk::RGBA>) {
+    let settings = settings!();
+    let current = settings.string("performance-page-colors");
+
+    let mut entries = current
+        .split(';')
+        .filter(|entry| !entry.is_empty())
+        .filter(|entry| entry.split_once('=').map(|(entry_name, _)| entry_name) != Some(name))
+        .map(|entry| entry.to_owned())
+        .collect::<Vec<_>>();
+    if let Some(color) = color {
+        entries.push(format!(
+            "{}=#{:02x}{:02x}{:02x}",
+            name,
+            (color.red() * 255.).round() as u8,
+            (color.green() * 255.).round() as u8,
+            (color.blue() * 255.).round() as u8
+        ));
+    }
+
+    let new = entries.join(";");
+    if new == current.as_str() {
+        return;
+    }
+
+    if let Err(e) = settings.set_string("performance-page-colors", &new) {
+        g_warning!(
+            "MissionCenter::PerformancePage",
+            "Failed to save the graph color of {}: {}",
+            name,
+            e
+        );
+    }
+}
+
+/// Forget the colors picked for categories and devices alike
+pub fn reset_graph_colors() {
+    if let Err(e) = settings!().set_string("performance-page-colors", "") {
+        g_warning!(
+            "MissionCenter::PerformancePage",
+            "Failed to reset the graph colors: {}",
+            e
+        );
+    }
+}
+
+pub fn category_color(category: &str) -> gdk::RGBA {
+    load_graph_color(category).unwrap_or_else(|| {
+        rgba(
+            GRAPH_COLOR_CATEGORIES
+                .iter()
+                .find(|(name, _)| *name == category)
+                .map(|(_, color)| *color)
+                .unwrap_or(CPU_BASE_COLOR),
+        )
+    })
+}
+
+/// The labels picked for devices in the sidebar, by page name
+fn load_graph_labels() -> HashMap<String, String> {
+    settings!()
+        .value("performance-sidebar-labels")
+        .get::<HashMap<String, String>>()
+        .unwrap_or_default()
+}
+
+fn load_graph_label(page_name: &str) -> Option<String> {
+    load_graph_labels().remove(page_name)
+}
+
+/// Name the device shown on a page; `None` goes back to the name it was found with
+fn save_graph_label(page_name: &str, label: Option<&str>) {
+    let mut labels = load_graph_labels();
+    match label {
+        Some(label) => {
+            labels.insert(page_name.to_owned(), label.to_owned());
+        }
+        None => {
+            labels.remove(page_name);
+        }
+    }
+
+    if let Err(e) = settings!().set_value("performance-sidebar-labels", &labels.to_variant()) {
+        g_warning!(
+            "MissionCenter::PerformancePage",
+            "Failed to save the label of {}: {}",
+            page_name,
+            e
+        );
+    }
+}
+
+/// The large title at the top of a page, which names its device
+fn page_title_label(widget: &gtk::Widget) -> Option<gtk::Label> {
+    let mut child = widget.first_child();
+    while let Some(widget) = child {
+        match widget.downcast_ref::<gtk::Label>() {
+            Some(label) if label.has_css_class("title-1") => return Some(label.clone()),
+            Some(_) => {}
+            None => {
+                if let Some(label) = page_title_label(&widget) {
+                    return Some(label);
+                }
+            }
+        }
+        child = widget.next_sibling();
+    }
+
+    None
+}
+
+/// Page names start with their category, like `disk-nvme0n1`
+fn page_category(page_name: &str) -> &str {
+    match page_name
+        .split_once('-')
+        .map_or(page_name, |(prefix, _)| prefix)
+    {
+        "net" => "network",
+        category => category,
+    }
+}
+
+/// The color picked for the device shown on the page, falling back to that of its category
+fn page_color(page_name: &str) -> gdk::RGBA {
+    load_graph_color(page_name).unwrap_or_else(|| category_color(page_category(page_name)))
+}
+
+/// Show how much of the time tasks were stalled waiting for a resource; high utilization alone
+/// doesn't mean anything is waiting, contention does
+fn update_pressure_label(label: &gtk::Label, pressure: Option<&Pressure>) {
+    let Some(pressure) = pressure else {
+        label.set_text(&i18n("Unavailable"));
+        label.set_tooltip_text(Some(&i18n(
+            "The kernel does not report pressure stall information",
+        )));
+        return;
+    };
+
+    label.set_text(&i18n_f(
+        "{}% (10 s) · {}% (1 min)",
+        &[
+            &format!("{:.1}", pressure.some.avg10),
+            &format!("{:.1}", pressure.some.avg60),
+        ],
+    ));
+
+    let mut tooltip = i18n("Share of time in which at least one task was stalled");
+    if let Some(full) = pressure.full {
+        tooltip.push('\n');
+        tooltip.push_str(&i18n_f(
+            "All tasks stalled: {}% (10 s) · {}% (1 min)",
+            &[&format!("{:.1}", full.avg10), &format!("{:.1}", full.avg60)],
+        ));
+    }
+    label.set_tooltip_text(Some(&tooltip));
+}
+
+/// Show `badge` while Feral GameMode is active, and mark on the graphs of `page` when it turns
+/// on or off; `was_active` is `None` until the first readings came in
+fn update_game_mode_badge(
+    page: &gtk::Widget,
+    badge: &gtk::Label,
+    was_active: &Cell<Option<bool>>,
+    game_mode: &[GameModeClient],
+) {
+    let active = !game_mode.is_empty();
+    if was_active
+        .replace(Some(active))
+        .is_some_and(|was| was != active)
+    {
+        let text = if active {
+            i18n("GameMode on")
+        } else {
+            i18n("GameMode off")
+        };
+        for_each_graph_widget(page, &|graph_widget| graph_widget.add_annotation(&text));
+    }
+
+    badge.set_visible(active);
+    if active {
+        let games = game_mode
+            .iter()
+            .map(|game| {
+                let name = game.executable.rsplit('/').next().unwrap_or_default();
+                if name.is_empty() {
+                    i18n_f("PID {}", &[&game.pid.to_string()])
+                } else {
+                    format!("{} ({})", name, game.pid)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        badge.set_tooltip_text(Some(&i18n_f("GameMode is active for:\n{}", &[&games])));
+    }
+}
+
+/// Time ranges, in seconds, that the graphs of a page can be switched to
+const TIME_WINDOWS: [u32; 4] = [60, 5 * 60, 30 * 60, 60 * 60];
+
+/// How many samples each point has to average for a graph to span `window` seconds
+fn time_window_downsample(settings: &gio::Settings, window: u32) -> u32 {
+    let data_points = settings.int("performance-page-data-points") as f64;
+    let delay = settings.uint64("app-update-interval-u64") as f64;
+
+    let graph_duration = delay * INTERVAL_STEP * data_points;
+    if graph_duration <= 0. {
+        return 1;
+    }
+
+    ((window as f64 / graph_duration).round() as u32).max(1)
+}
+
+// Time ranges are stored as `page-name=seconds` entries separated by `;`
+fn load_time_window(page_name: &str) -> u32 {
+    settings!()
+        .string("performance-page-time-windows")
+        .split(';')
+        .filter_map(|entry| entry.split_once('='))
+        .find(|(page, _)| *page == page_name)
+        .and_then(|(_, window)| window.parse::<u32>().ok())
+        .filter(|window| TIME_WINDOWS.contains(window))
+        .unwrap_or(TIME_WINDOWS[0])
+}
+
+fn save_time_window(page_name: &str, window: u32) {
+    let settings = settings!();
+    let current = settings.string("performance-page-time-windows");
+
+    let mut entries = current
+        .split(';')
+        .filter(|entry| !entry.is_empty())
+        .filter(|entry| entry.split_once('=').map(|(page, _)| page) != Some(page_name))
+        .map(|entry| entry.to_owned())
+        .collect::<Vec<_>>();
+    if window != TIME_WINDOWS[0] {
+        entries.push(format!("{}={}", page_name, window));
+    }
+
+    let new = entries.join(";");
+    if new == current.as_str() {
+        return;
+    }
+
+    if let Err(e) = settings.set_string("performance-page-time-windows", &new) {
+        g_warning!(
+            "MissionCenter::PerformancePage",
+            "Failed to save time range for {}: {}",
+            page_name,
+            e
+        );
+    }
+}
+
+fn for_each_graph_widget(widget: &gtk::Widget, f: &impl Fn(&GraphWidget)) {
+    let mut child = widget.first_child();
+    while let Some(widget) = child {
+        match widget.downcast_ref::<GraphWidget>() {
+            Some(graph_widget) => f(graph_widget),
+            None => for_each_graph_widget(&widget, f),
+        }
+        child = widget.next_sibling();
+    }
+}
+
+/// The first graph on a page, which shows its main reading
+fn main_graph_widget(widget: &gtk::Widget) -> Option<GraphWidget> {
+    let mut child = widget.first_child();
+    while let Some(widget) = child {
+        match widget.downcast_ref::<GraphWidget>() {
+            Some(graph_widget) => return Some(graph_widget.clone()),
+            None => {
+                if let Some(graph_widget) = main_graph_widget(&widget) {
+                    return Some(graph_widget);
+                }
+            }
+        }
+        child = widget.next_sibling();
+    }
+
+    None
+}
+
+/// The details shown next to a page's graphs; `None` for anything in the page stack that isn't a
+/// device page, like the placeholder of a page opened in its own window
+fn infobar_content(page: &gtk::Widget) -> Option<gtk::Widget> {
+    page.find_property("infobar-content")?;
+    page.property::<Option<gtk::Widget>>("infobar-content")
+}
+
+/// Switch every graph on `page` to `window`, keeping the history for the other time ranges
+fn apply_time_window(page: &gtk::Widget, window: u32) {
+    let settings = settings!();
+    let factors = TIME_WINDOWS
+        .iter()
+        .map(|window| time_window_downsample(&settings, *window))
+        .collect::<Vec<_>>();
+    let downsample = time_window_downsample(&settings, window);
+
+    for_each_graph_widget(page, &|graph_widget| {
+        graph_widget.set_resolutions(&factors);
+        graph_widget.set_downsample(downsample);
+    });
+}
+
+/// Let the user save the history shown by a page's graphs as CSV; every column is a data set of
+/// one of the graphs, exported as the field at the same position in `fields`
+fn export_graph_data(
+    page: &gtk::Widget,
+    columns: &[(GraphWidget, usize)],
+    fields: &[(&str, export::Unit)],
+) {
+    let Some((first_graph, _)) = columns.first() else {
+        return;
+    };
+
+    let settings = settings!();
+    let interval_ms = settings.uint64("app-update-interval-u64") as f64
+        * INTERVAL_STEP
+        * 1000.
+        * first_graph.downsample() as f64;
+    let now_ms = glib::real_time() / 1000;
+
+    let data = columns
+        .iter()
+        .map(|(graph, index)| graph.displayed_data(*index).unwrap_or_default())
+        .collect::<Vec<_>>();
+    let csv = graph_table(fields, &data, now_ms, interval_ms).to_csv();
+
+    let dialog = gtk::FileDialog::builder()
+        .title(i18n("Export Data"))
+        .initial_name(format!("{}.csv", page_file_name(page)))
+        .modal(true)
+        .build();
+    dialog.save(
+        page.root().and_downcast_ref::<gtk::Window>(),
+        None::<&gio::Cancellable>,
+        move |result| {
+            let Some(path) = result.ok().and_then(|file| file.path()) else {
+                return;
+            };
+
+            if let Err(e) = std::fs::write(&path, csv.as_bytes()) {
+                g_warning!(
+                    "MissionCenter::PerformancePage",
+                    "Failed to export graph data to {}: {}",
+                    path.display(),
+                    e
+                );
+            }
+        },
+    );
+}
+
+/// The export of graph `data`, one column per field; the newest points of all columns were read
+/// at `now_ms`, the others `interval_ms` apart
+fn graph_table(
+    fields: &[(&str, export::Unit)],
+    data: &[Vec<f32>],
+    now_ms: i64,
+    interval_ms: f64,
+) -> Table {
+    let row_count = data.iter().map(|values| values.len()).max().unwrap_or(0);
+
+    let mut table_fields = vec![Field::new("timestamp", export::Unit::UnixTimestampMs)];
+    table_fields.extend(fields.iter().map(|(id, unit)| Field::new(*id, *unit)));
+    let mut table = Table::new("graph", table_fields);
+
+    // The newest point of every graph is the one on the right edge, line them up from there
+    for row in 0..row_count {
+        let age = (row_count - 1 - row) as f64;
+        let mut values = vec![export::Value::from(
+            (now_ms - (age * interval_ms).round() as i64).max(0) as u64,
+        )];
+        values.extend(data.iter().map(
+            |column| match (row + column.len()).checked_sub(row_count) {
+                Some(index) => export::Value::from(column[index]),
+                None => export::Value::Missing,
+            },
+        ));
+        table.push_row(values);
+    }
+
+    table
+}
+
+/// Let the user save a snapshot of `page`: as PNG, the way it is shown, or as SVG with just the
+/// graphs, each where it is on the page
+fn save_page_image(page: &gtk::Widget) {
+    let width = page.width();
+    let height = page.height();
+    if width <= 0 || height <= 0 {
+        return;
+    }
+
+    // Both are taken right away, the graphs would have moved on once a file is picked
+    let png = render_png(page, width, height);
+    let svg = render_svg(page, width, height);
+
+    let png_filter = gtk::FileFilter::new();
+    png_filter.set_name(Some(&i18n("PNG Image")));
+    png_filter.add_suffix("png");
+    let svg_filter = gtk::FileFilter::new();
+    svg_filter.set_name(Some(&i18n("SVG Image")));
+    svg_filter.add_suffix("svg");
+
+    let filters = gio::ListStore::new::<gtk::FileFilter>();
+    filters.append(&png_filter);
+    filters.append(&svg_filter);
+
+    let dialog = gtk::FileDialog::builder()
+        .title(i18n("Save Graph as Image"))
+        .initial_name(format!("{}.png", page_file_name(page)))
+        .filters(&filters)
+        .default_filter(&png_filter)
+        .modal(true)
+        .build();
+    dialog.save(
+        page.root().and_downcast_ref::<gtk::Window>(),
+        None::<&gio::Cancellable>,
+        move |result| {
+            let Some(path) = result.ok().and_then(|file| file.path()) else {
+                return;
+            };
+
+            let is_svg = path
+                .extension()
+                .is_some_and(|extension| extension.eq_ignore_ascii_case("svg"));
+            let result = if is_svg {
+                std::fs::write(&path, svg.as_bytes()).map_err(|e| e.to_string())
+            } else {
+                match &png {
+                    Some(png) => std::fs::write(&path, png).map_err(|e| e.to_string()),
+                    None => Err("Failed to render the page".to_owned()),
+                }
+            };
+
+            if let Err(e) = result {
+                g_warning!(
+                    "MissionCenter::PerformancePage",
+                    "Failed to save graph image to {}: {}",
+                    path.display(),
+                    e
+                );
+            }
+        },
+    );
+}
+
+fn render_png(page: &gtk::Widget, width: i32, height: i32) -> Option<glib::Bytes> {
+    let renderer = page.native()?.renderer()?;
+
+    let bounds = gtk::graphene::Rect::new(0., 0., width as f32, height as f32);
+    let snapshot = gtk::Snapshot::new();
+    // Pages don't draw a background of their own, the window does
+    let background = if adw::StyleManager::default().is_dark() {
+        gdk::RGBA::new(
+            0x22 as f32 / 255.,
+            0x22 as f32 / 255.,
+            0x26 as f32 / 255.,
+            1.,
+        )
+    } else {
+        gdk::RGBA::new(
+            0xfa as f32 / 255.,
+            0xfa as f32 / 255.,
+            0xfb as f32 / 255.,
+            1.,
+        )
+    };
+    snapshot.append_color(&background, &bounds);
+    gtk::WidgetPaintable::new(Some(page)).snapshot(&snapshot, width as f64, height as f64);
+
+    let node = snapshot.to_node()?;
+    Some(
+        renderer
+            .render_texture(&node, Some(&bounds))
+            .save_to_png_bytes(),
+    )
+}
+
+fn render_svg(page: &gtk::Widget, width: i32, height: i32) -> String {
+    let svg = RefCell::new(format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" viewBox=\"0 0 {width} {height}\">\n"
+    ));
+
+    for_each_graph_widget(page, &|graph_widget| {
+        if !graph_widget.is_drawable() {
+            return;
+        }
+        let Some(bounds) = graph_widget.compute_bounds(page) else {
+            return;
+        };
+
+        let mut svg = svg.borrow_mut();
+        let _ = writeln!(
+            svg,
+            "<g transform=\"translate({:.1} {:.1})\">",
+            bounds.x(),
+            bounds.y()
+        );
+        svg.push_str(&graph_widget.to_svg_elements(bounds.width(), bounds.height()));
+        svg.push_str("</g>\n");
+    });
+
+    let mut svg = svg.into_inner();
+    svg.push_str("</svg>\n");
+
+    svg
+}
+
+/// Name to suggest when saving something from `page`, e.g. `cpu`
+fn page_file_name(page: &gtk::Widget) -> String {
+    page.parent()
+        .and_downcast::<gtk::Stack>()
+        .and_then(|stack| stack.page(page).name())
+        .map(|name| name.to_string())
+        .unwrap_or_else(|| "graph".to_owned())
+}
 
 mod imp {
     use super::*;
     use crate::DataType;
     use std::marker::PhantomData;
 
-    // GNOME color palette: Blue 4
-    const CPU_BASE_COLOR: [u8; 3] = [0x1c, 0x71, 0xd8];
-    // GNOME color palette: Blue 2
-    const MEMORY_BASE_COLOR: [u8; 3] = [0x62, 0xa0, 0xea];
-    // GNOME color palette: Green 5
-    const DISK_BASE_COLOR: [u8; 3] = [0x26, 0xa2, 0x69];
-    // GNOME color palette: Purple 1
-    const NETWORK_BASE_COLOR: [u8; 3] = [0xdc, 0x8a, 0xdd];
-    // GNOME color palette: Purple 4
-    const FAN_BASE_COLOR: [u8; 3] = [0x81, 0x3d, 0x9c];
-    // GNOME color palette: Red 1
-    const GPU_BASE_COLOR: [u8; 3] = [0xf6, 0x61, 0x51];
-
     enum Pages {
         Cpu((SummaryGraph, CpuPage)),
         Memory((SummaryGraph, MemoryPage)),
@@ -92,6 +654,7 @@ mod imp {
         Network(HashMap<String, (SummaryGraph, NetworkPage)>),
         Gpu(HashMap<String, (SummaryGraph, GpuPage)>),
         Fan(HashMap<String, (SummaryGraph, FanPage)>),
+        Ups(HashMap<String, (SummaryGraph, UpsPage)>),
     }
 
     #[derive(Properties)]
@@ -126,6 +689,16 @@ mod imp {
 
         context_menu_view_actions: Cell<HashMap<String, gio::SimpleAction>>,
         current_view_action: Cell<gio::SimpleAction>,
+
+        // Pages opened in their own window, by page name; a placeholder takes their place in
+        // the page stack until the window is closed
+        detached_pages: RefCell<HashMap<String, adw::Window>>,
+        // Page titles replaced by a label the user picked, by page name
+        default_page_titles: RefCell<HashMap<String, String>>,
+
+        // Minimum, average and maximum of the main graph of the visible page, added to its details
+        statistics_readout: gtk::Box,
+        statistics_values: [gtk::Label; 3],
     }
 
     impl Default for PerformancePage {
@@ -149,11 +722,91 @@ mod imp {
 
                 context_menu_view_actions: Cell::new(HashMap::new()),
                 current_view_action: Cell::new(gio::SimpleAction::new("", None)),
+
+                detached_pages: RefCell::new(HashMap::new()),
+                default_page_titles: RefCell::new(HashMap::new()),
+
+                statistics_readout: gtk::Box::new(gtk::Orientation::Horizontal, 15),
+                statistics_values: std::array::from_fn(|_| gtk::Label::new(None)),
             }
         }
     }
 
     impl PerformancePage {
+        fn set_up_statistics_readout(&self) {
+            let captions = [i18n("Minimum"), i18n("Average"), i18n("Maximum")];
+            for (caption, value) in captions.iter().zip(&self.statistics_values) {
+                let caption = gtk::Label::builder()
+                    .label(caption)
+                    .halign(gtk::Align::Start)
+                    .css_classes(["caption"])
+                    .build();
+                value.set_halign(gtk::Align::Start);
+                value.add_css_class("title-4");
+
+                let column = gtk::Box::new(gtk::Orientation::Vertical, 3);
+                column.append(&caption);
+                column.append(value);
+                self.statistics_readout.append(&column);
+            }
+        }
+
+        /// Move the statistics readout into the details of `page`, below everything else
+        fn move_statistics_readout(&self, page: &gtk::Widget) {
+            if let Some(parent) = self.statistics_readout.parent().and_downcast::<gtk::Box>() {
+                parent.remove(&self.statistics_readout);
+            }
+
+            if let Some(infobar) = infobar_content(page).and_downcast::<gtk::Box>() {
+                infobar.append(&self.statistics_readout);
+            }
+        }
+
+        /// Show the axis labels and statistics on the graphs of every page, as set in the
+        /// preferences; pages come and go with their devices, so this runs on every update
+        fn apply_graph_overlays(&self) {
+            let settings = settings!();
+            let axis_labels = settings.boolean("performance-page-graph-axis-labels");
+            let statistics = settings.boolean("performance-page-graph-statistics");
+            let apply = |graph_widget: &GraphWidget| {
+                graph_widget.set_axis_labels_visible(axis_labels);
+                graph_widget.set_show_statistics(statistics);
+            };
+
+            let mut child = self.page_stack.first_child();
+            while let Some(page) = child {
+                for_each_graph_widget(&page, &apply);
+                child = page.next_sibling();
+            }
+            for window in self.detached_pages.borrow().values() {
+                for_each_graph_widget(window.upcast_ref(), &apply);
+            }
+        }
+
+        /// Update the statistics readout of the visible page, if turned on
+        fn update_statistics(&self) {
+            let show = settings!().boolean("performance-page-graph-statistics");
+
+            let statistics = self.page_stack.visible_child().and_then(|page| {
+                let graph_widget = main_graph_widget(&page)?;
+                Some((graph_widget.statistics()?, graph_widget))
+            });
+            let Some((statistics, graph_widget)) = statistics.filter(|_| show) else {
+                self.statistics_readout.set_visible(false);
+                return;
+            };
+
+            let index = graph_widget.statistics_data_set() as usize;
+            for (label, value) in self.statistics_values.iter().zip([
+                statistics.min,
+                statistics.average,
+                statistics.max,
+            ]) {
+                label.set_text(&graph_widget.format_value(index, value));
+            }
+            self.statistics_readout.set_visible(true);
+        }
+
         pub fn sidebar(&self) -> gtk::ListBox {
             self.sidebar.borrow().clone()
         }
@@ -519,6 +1172,94 @@ mod imp {
             });
             actions.add_action(&action);
 
+            let action = gio::SimpleAction::new_stateful(
+                "time-window",
+                Some(glib::VariantTy::STRING),
+                &glib::Variant::from(TIME_WINDOWS[0].to_string()),
+            );
+            action.connect_activate({
+                let this = this.downgrade();
+                move |action, parameter| {
+                    let this = match this.upgrade() {
+                        Some(this) => this,
+                        None => return,
+                    };
+                    let this = this.imp();
+
+                    let Some(window) = parameter
+                        .and_then(|p| p.get::<String>())
+                        .and_then(|w| w.parse::<u32>().ok())
+                    else {
+                        return;
+                    };
+
+                    let (Some(page), Some(page_name)) = (
+                        this.page_stack.visible_child(),
+                        this.page_stack.visible_child_name(),
+                    ) else {
+                        return;
+                    };
+
+                    action.set_state(&glib::Variant::from(window.to_string()));
+                    save_time_window(&page_name, window);
+                    apply_time_window(&page, window);
+                }
+            });
+            actions.add_action(&action);
+
+            let action = gio::SimpleAction::new("open-in-window", Some(glib::VariantTy::STRING));
+            action.connect_activate({
+                let this = this.downgrade();
+                move |_, parameter| {
+                    let Some(this) = this.upgrade() else {
+                        return;
+                    };
+
+                    if let Some(page_name) = parameter.and_then(|p| p.get::<String>()) {
+                        this.imp().detach_page(&page_name);
+                    }
+                }
+            });
+            actions.add_action(&action);
+
+            let action = gio::SimpleAction::new("change-color", Some(glib::VariantTy::STRING));
+            action.connect_activate({
+                let this = this.downgrade();
+                move |_, parameter| {
+                    let Some(this) = this.upgrade() else {
+                        return;
+                    };
+                    let Some(page_name) = parameter.and_then(|p| p.get::<String>()) else {
+                        return;
+                    };
+
+                    let dialog = gtk::ColorDialog::builder()
+                        .title(i18n("Graph Color"))
+                        .with_alpha(false)
+                        .modal(true)
+                        .build();
+                    dialog.choose_rgba(
+                        this.root().and_downcast_ref::<gtk::Window>(),
+                        Some(&page_color(&page_name)),
+                        None::<&gio::Cancellable>,
+                        move |result| {
+                            if let Ok(color) = result {
+                                save_graph_color(&page_name, Some(&color));
+                            }
+                        },
+                    );
+                }
+            });
+            actions.add_action(&action);
+
+            let action = gio::SimpleAction::new("reset-color", Some(glib::VariantTy::STRING));
+            action.connect_activate(|_, parameter| {
+                if let Some(page_name) = parameter.and_then(|p| p.get::<String>()) {
+                    save_graph_color(&page_name, None);
+                }
+            });
+            actions.add_action(&action);
+
             let action = gio::SimpleAction::new_stateful("cpu", None, &glib::Variant::from(true));
             action.connect_activate({
                 let this = this.downgrade();
@@ -781,13 +1522,149 @@ mod imp {
             });
             actions.add_action(&action);
             view_actions.insert("fan".to_string(), action);
+            let action = gio::SimpleAction::new_stateful("ups", None, &glib::Variant::from(false));
+            action.connect_activate({
+                let this = this.downgrade();
+                move |action, _| {
+                    let this = match this.upgrade() {
+                        Some(this) => this,
+                        None => return,
+                    };
+                    let this = this.imp();
+
+                    let pages = this.pages.take();
+                    for page in &pages {
+                        let ups_pages = match page {
+                            Pages::Ups(ups_pages) => ups_pages,
+                            _ => continue,
+                        };
+
+                        let ups_page = ups_pages.values().next();
+                        if ups_page.is_none() {
+                            continue;
+                        }
+                        let ups_page = ups_page.unwrap();
+
+                        let row = ups_page.0.parent();
+                        if row.is_none() {
+                            continue;
+                        }
+                        let row = row.unwrap();
+
+                        this.sidebar()
+                            .select_row(row.downcast_ref::<gtk::ListBoxRow>());
+
+                        let prev_action = this.current_view_action.replace(action.clone());
+                        prev_action.set_state(&glib::Variant::from(false));
+                        action.set_state(&glib::Variant::from(true));
+
+                        break;
+                    }
+                    this.pages.set(pages);
+                }
+            });
+            actions.add_action(&action);
+            view_actions.insert("ups".to_string(), action);
 
             self.context_menu_view_actions.set(view_actions);
 
             actions
         }
 
-        fn configure_page<P: PageExt + IsA<gtk::Widget>>(&self, page: &P) {
+        fn apply_graph_colors(&self) {
+            let pages = self.pages.take();
+
+            for page in &pages {
+                match page {
+                    Pages::Cpu((summary, page)) => {
+                        let color = page_color("cpu");
+                        summary.set_base_color(color);
+                        page.set_base_color(color);
+                    }
+                    Pages::Memory((summary, page)) => {
+                        let color = page_color("memory");
+                        summary.set_base_color(color);
+                        page.set_base_color(color);
+                    }
+                    Pages::Disk(pages) => {
+                        for (page_name, (summary, page)) in pages {
+                            let color = page_color(page_name);
+                            summary.set_base_color(color);
+                            page.set_base_color(color);
+                        }
+                    }
+                    Pages::Network(pages) => {
+                        for (page_name, (summary, page)) in pages {
+                            let color = page_color(page_name);
+                            summary.set_base_color(color);
+                            page.set_base_color(color);
+                        }
+                    }
+                    Pages::Gpu(pages) => {
+                        for (page_name, (summary, page)) in pages {
+                            let color = page_color(page_name);
+                            summary.set_base_color(color);
+                            page.set_base_color(color);
+                        }
+                    }
+                    Pages::Fan(pages) => {
+                        for (page_name, (summary, page)) in pages {
+                            let color = page_color(page_name);
+                            summary.set_base_color(color);
+                            page.set_base_color(color);
+                        }
+                    }
+                    Pages::Ups(pages) => {
+                        for (page_name, (summary, page)) in pages {
+                            let color = page_color(page_name);
+                            summary.set_base_color(color);
+                            page.set_base_color(color);
+                        }
+                    }
+                }
+            }
+
+            self.pages.set(pages);
+        }
+
+        /// Show the label picked for the device of `graph`, if any, in the sidebar and on its page
+        fn apply_graph_label(&self, graph: &SummaryGraph) {
+            graph.load_custom_heading();
+
+            let page_name = graph.widget_name().to_string();
+            let page = match self.detached_pages.borrow().get(&page_name) {
+                Some(window) => {
+                    window.set_title(Some(&graph.heading()));
+                    window.clone().upcast::<gtk::Widget>()
+                }
+                None => match self.page_stack.child_by_name(&page_name) {
+                    Some(page) => page,
+                    None => return,
+                },
+            };
+            let Some(title) = page_title_label(&page) else {
+                return;
+            };
+
+            let mut default_titles = self.default_page_titles.borrow_mut();
+            match load_graph_label(&page_name) {
+                Some(label) => {
+                    default_titles
+                        .entry(page_name)
+                        .or_insert_with(|| title.text().to_string());
+                    title.set_text(&label);
+                }
+                None => {
+                    if let Some(default_title) = default_titles.remove(&page_name) {
+                        title.set_text(&default_title);
+                    }
+                }
+            }
+        }
+
+        fn configure_page<P: PageExt + IsA<gtk::Widget>>(&self, page: &P, page_name: &str) {
+            apply_time_window(page.upcast_ref(), load_time_window(page_name));
+
             self.page_content.connect_collapsed_notify({
                 let page = page.downgrade();
                 move |pc| {
@@ -811,6 +1688,8 @@ mod imp {
         fn add_to_sidebar(&self, graph: &SummaryGraph, hint: Option<i32>) {
             let sidebar = self.sidebar();
 
+            self.apply_graph_label(graph);
+
             let drag_source = gtk::DragSource::builder()
                 .actions(gdk::DragAction::empty())
                 .build();
@@ -820,6 +1699,64 @@ mod imp {
                 drag_source.set_actions(gdk::DragAction::MOVE);
             }
 
+            let right_click_controller = gtk::GestureClick::new();
+            right_click_controller.set_button(3); // Secondary click (AKA right click)
+            right_click_controller.connect_released({
+                let graph = graph.downgrade();
+                move |_click, _n_press, x, y| {
+                    let Some(graph) = graph.upgrade() else {
+                        return;
+                    };
+
+                    let page_name = graph.widget_name();
+
+                    let menu = gio::Menu::new();
+                    menu.append(
+                        Some(&i18n("Open in New Window")),
+                        Some(&gio::Action::print_detailed_name(
+                            "graph.open-in-window",
+                            Some(&page_name.to_variant()),
+                        )),
+                    );
+
+                    let color_section = gio::Menu::new();
+                    color_section.append(
+                        Some(&i18n("Change Color…")),
+                        Some(&gio::Action::print_detailed_name(
+                            "graph.change-color",
+                            Some(&page_name.to_variant()),
+                        )),
+                    );
+                    if load_graph_color(&page_name).is_some() {
+                        color_section.append(
+                            Some(&i18n("Reset Color")),
+                            Some(&gio::Action::print_detailed_name(
+                                "graph.reset-color",
+                                Some(&page_name.to_variant()),
+                            )),
+                        );
+                    }
+                    menu.append_section(None, &color_section);
+
+                    let popover = gtk::PopoverMenu::from_model(Some(&menu));
+                    popover.set_parent(&graph);
+                    popover.set_has_arrow(false);
+                    popover.set_pointing_to(Some(&gdk::Rectangle::new(
+                        x.round() as i32,
+                        y.round() as i32,
+                        1,
+                        1,
+                    )));
+                    popover.connect_closed(|popover| {
+                        // The menu item is activated after the popover closes
+                        let popover = popover.clone();
+                        glib::idle_add_local_once(move || popover.unparent());
+                    });
+                    popover.popup();
+                }
+            });
+            graph.add_controller(right_click_controller);
+
             let mut summary_graphs = self.summary_graphs.take();
 
             summary_graphs.insert(graph.clone(), drag_source.clone());
@@ -944,28 +1881,160 @@ mod imp {
                                 None => continue,
                             };
 
-                            sidebar_order.push_str(graph.widget_name().as_str());
-                            sidebar_order.push(';');
-                        }
+                            sidebar_order.push_str(graph.widget_name().as_str());
+                            sidebar_order.push(';');
+                        }
+
+                        let sidebar_order = if !sidebar_order.is_empty() {
+                            &sidebar_order[..sidebar_order.len() - 1]
+                        } else {
+                            ""
+                        };
+
+                        settings
+                            .set_string("performance-sidebar-order", sidebar_order)
+                            .unwrap_or_else(|_| {
+                                g_warning!(
+                                    "MissionCenter::PerformancePage",
+                                    "Failed to set performance-sidebar-order setting"
+                                );
+                            });
+                    }
+                });
+
+                row.add_controller(drag_source);
+            }
+        }
+
+        /// Move a device page, along with its details, into a window of its own, so several of them
+        /// can be watched side by side; it keeps being updated like it was in the page stack
+        fn detach_page(&self, page_name: &str) {
+            if let Some(window) = self.detached_pages.borrow().get(page_name) {
+                window.present();
+                return;
+            }
+
+            let Some(page) = self.page_stack.child_by_name(page_name) else {
+                return;
+            };
+
+            let summary_graphs = self.summary_graphs.take();
+            let title = summary_graphs
+                .keys()
+                .find(|graph| graph.widget_name().as_str() == page_name)
+                .map(|graph| graph.heading())
+                .unwrap_or_else(|| page_name.to_owned());
+            self.summary_graphs.set(summary_graphs);
+
+            let was_visible = self.page_stack.visible_child_name().as_deref() == Some(page_name);
+
+            let infobar = infobar_content(&page);
+            if let Some(infobar) = &infobar {
+                if self.info_bar.child().as_ref() == Some(infobar) {
+                    self.info_bar.set_child(None::<&gtk::Widget>);
+                }
+            }
+            self.page_stack.remove(&page);
+
+            let show_window = gtk::Button::with_label(&i18n("Show Window"));
+            show_window.set_halign(gtk::Align::Center);
+            show_window.add_css_class("pill");
+            show_window.connect_clicked({
+                let this = self.obj().downgrade();
+                let page_name = page_name.to_owned();
+                move |_| {
+                    if let Some(this) = this.upgrade() {
+                        this.imp().detach_page(&page_name);
+                    }
+                }
+            });
+            let placeholder = adw::StatusPage::builder()
+                .icon_name("window-new-symbolic")
+                .title(i18n("Open in a Separate Window"))
+                .description(i18n("Close the window to bring the page back here"))
+                .child(&show_window)
+                .build();
+            self.page_stack.add_named(&placeholder, Some(page_name));
+            if was_visible {
+                self.page_stack.set_visible_child_name(page_name);
+            }
+
+            let content = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+            page.set_hexpand(true);
+            content.append(&page);
+            if let Some(infobar) = &infobar {
+                let scrolled_window = gtk::ScrolledWindow::builder()
+                    .hscrollbar_policy(gtk::PolicyType::Never)
+                    .width_request(320)
+                    .child(infobar)
+                    .build();
+                content.append(&scrolled_window);
+            }
+
+            let toolbar_view = adw::ToolbarView::new();
+            toolbar_view.add_top_bar(&adw::HeaderBar::new());
+            toolbar_view.set_content(Some(&content));
+
+            let window = adw::Window::builder()
+                .title(title)
+                .default_width(900)
+                .default_height(540)
+                .content(&toolbar_view)
+                .build();
+            window.connect_close_request({
+                let this = self.obj().downgrade();
+                let page_name = page_name.to_owned();
+                move |_| {
+                    if let Some(this) = this.upgrade() {
+                        this.imp().attach_page(&page_name);
+                    }
+                    glib::Propagation::Proceed
+                }
+            });
+
+            self.detached_pages
+                .borrow_mut()
+                .insert(page_name.to_owned(), window.clone());
+            window.present();
+        }
 
-                        let sidebar_order = if !sidebar_order.is_empty() {
-                            &sidebar_order[..sidebar_order.len() - 1]
-                        } else {
-                            ""
-                        };
+        /// Put a page opened in its own window back into the page stack
+        fn attach_page(&self, page_name: &str) {
+            let Some(window) = self.detached_pages.borrow_mut().remove(page_name) else {
+                return;
+            };
 
-                        settings
-                            .set_string("performance-sidebar-order", sidebar_order)
-                            .unwrap_or_else(|_| {
-                                g_warning!(
-                                    "MissionCenter::PerformancePage",
-                                    "Failed to set performance-sidebar-order setting"
-                                );
-                            });
-                    }
-                });
+            // The device could have gone away in the meantime, taking the placeholder with it
+            let Some(placeholder) = self
+                .page_stack
+                .child_by_name(page_name)
+                .filter(|child| child.is::<adw::StatusPage>())
+            else {
+                return;
+            };
 
-                row.add_controller(drag_source);
+            let Some(content) = window
+                .content()
+                .and_downcast::<adw::ToolbarView>()
+                .and_then(|toolbar_view| toolbar_view.content())
+                .and_downcast::<gtk::Box>()
+            else {
+                return;
+            };
+            let Some(page) = content.first_child() else {
+                return;
+            };
+            if let Some(scrolled_window) = page.next_sibling().and_downcast::<gtk::ScrolledWindow>()
+            {
+                scrolled_window.set_child(None::<&gtk::Widget>);
+            }
+            content.remove(&page);
+
+            let was_visible = self.page_stack.visible_child_name().as_deref() == Some(page_name);
+            self.page_stack.remove(&placeholder);
+            self.page_stack.add_named(&page, Some(page_name));
+            if was_visible {
+                self.page_stack.set_visible_child_name(page_name);
             }
         }
 
@@ -984,12 +2053,7 @@ mod imp {
                 _ => {}
             }
 
-            summary.set_base_color(gdk::RGBA::new(
-                CPU_BASE_COLOR[0] as f32 / 255.,
-                CPU_BASE_COLOR[1] as f32 / 255.,
-                CPU_BASE_COLOR[2] as f32 / 255.,
-                1.,
-            ));
+            summary.set_base_color(page_color("cpu"));
 
             let settings = settings!();
 
@@ -1007,15 +2071,10 @@ mod imp {
                 .set_expected_animation_ticks(settings.uint64("app-update-interval-u64") as u32);
 
             let page = CpuPage::new(&settings);
-            page.set_base_color(gdk::RGBA::new(
-                CPU_BASE_COLOR[0] as f32 / 255.,
-                CPU_BASE_COLOR[1] as f32 / 255.,
-                CPU_BASE_COLOR[2] as f32 / 255.,
-                1.,
-            ));
+            page.set_base_color(page_color("cpu"));
             page.set_static_information(readings);
 
-            self.configure_page(&page);
+            self.configure_page(&page, "cpu");
 
             self.page_stack.add_named(&page, Some("cpu"));
             self.add_to_sidebar(&summary, None);
@@ -1045,12 +2104,7 @@ mod imp {
             summary.set_info1("0/0 GiB");
             summary.set_info2("0%");
 
-            summary.set_base_color(gdk::RGBA::new(
-                MEMORY_BASE_COLOR[0] as f32 / 255.,
-                MEMORY_BASE_COLOR[1] as f32 / 255.,
-                MEMORY_BASE_COLOR[2] as f32 / 255.,
-                1.,
-            ));
+            summary.set_base_color(page_color("memory"));
 
             let settings = settings!();
 
@@ -1069,21 +2123,11 @@ mod imp {
                 .set_expected_animation_ticks(settings.uint64("app-update-interval-u64") as u32);
 
             let page = MemoryPage::new(&settings);
-            page.set_base_color(gdk::RGBA::new(
-                MEMORY_BASE_COLOR[0] as f32 / 255.,
-                MEMORY_BASE_COLOR[1] as f32 / 255.,
-                MEMORY_BASE_COLOR[2] as f32 / 255.,
-                1.,
-            ));
-            page.set_memory_color(gdk::RGBA::new(
-                DISK_BASE_COLOR[0] as f32 / 255.,
-                DISK_BASE_COLOR[1] as f32 / 255.,
-                DISK_BASE_COLOR[2] as f32 / 255.,
-                1.,
-            ));
+            page.set_base_color(page_color("memory"));
+            page.set_memory_color(rgba(DISK_BASE_COLOR));
             page.set_static_information(readings);
 
-            self.configure_page(&page);
+            self.configure_page(&page, "memory");
 
             self.page_stack.add_named(&page, Some("memory"));
             self.add_to_sidebar(&summary, None);
@@ -1181,12 +2225,11 @@ mod imp {
                 }
             ));
 
-            summary.set_base_color(gdk::RGBA::new(
-                DISK_BASE_COLOR[0] as f32 / 255.,
-                DISK_BASE_COLOR[1] as f32 / 255.,
-                DISK_BASE_COLOR[2] as f32 / 255.,
-                1.,
-            ));
+            summary.set_base_color(page_color(&page_name));
+            summary.split(&i18n("Read"), &i18n("Write"));
+            summary
+                .graph_widget()
+                .set_scaling(GraphWidget::auto_pow2_scaling());
 
             let settings = settings!();
 
@@ -1205,15 +2248,10 @@ mod imp {
                 .set_expected_animation_ticks(settings.uint64("app-update-interval-u64") as u32);
 
             let page = DiskPage::new(&page_name, &settings);
-            page.set_base_color(gdk::RGBA::new(
-                DISK_BASE_COLOR[0] as f32 / 255.,
-                DISK_BASE_COLOR[1] as f32 / 255.,
-                DISK_BASE_COLOR[2] as f32 / 255.,
-                1.,
-            ));
+            page.set_base_color(page_color(&page_name));
             page.set_static_information(disk_id, disk);
 
-            self.configure_page(&page);
+            self.configure_page(&page, &page_name);
 
             self.page_stack.add_named(&page, Some(&page_name));
             self.add_to_sidebar(&summary, pos_hint);
@@ -1269,19 +2307,11 @@ mod imp {
             let summary = SummaryGraph::new();
             summary.set_widget_name(&page_name);
             summary.set_heading(format!("{} ({})", conn_type, if_name));
-            {
-                let graph_widget = summary.graph_widget();
-                graph_widget.set_data_set_count(2);
-                graph_widget.set_scaling(GraphWidget::auto_pow2_scaling());
-                graph_widget.set_filled(0, false);
-                graph_widget.set_dashed(0, true);
-                graph_widget.set_base_color(gdk::RGBA::new(
-                    NETWORK_BASE_COLOR[0] as f32 / 255.,
-                    NETWORK_BASE_COLOR[1] as f32 / 255.,
-                    NETWORK_BASE_COLOR[2] as f32 / 255.,
-                    1.,
-                ));
-            }
+            summary.split(&i18n("Receive"), &i18n("Send"));
+            summary
+                .graph_widget()
+                .set_scaling(GraphWidget::auto_pow2_scaling());
+            summary.set_base_color(page_color(&page_name));
 
             let settings = settings!();
 
@@ -1304,38 +2334,41 @@ mod imp {
                         .graph_widget()
                         .set_scaling(GraphWidget::no_scaling());
                     summary.graph_widget().set_value_range_max(max_speed as f32);
+                    summary
+                        .secondary_graph_widget()
+                        .set_value_range_max(max_speed as f32);
                 }
                 settings.connect_changed(Some("performance-page-network-dynamic-scaling"), {
-                    let graph = summary.graph_widget().downgrade();
+                    let summary = summary.downgrade();
                     move |settings, _| {
-                        let graph = match graph.upgrade() {
-                            Some(graph) => graph,
+                        let summary = match summary.upgrade() {
+                            Some(summary) => summary,
                             None => return,
                         };
 
                         let dynamic_scaling =
                             settings.boolean("performance-page-network-dynamic-scaling");
 
+                        // The scaling of the second graph follows that of the first one
+                        let graph = summary.graph_widget();
                         if dynamic_scaling {
                             graph.set_scaling(GraphWidget::auto_pow2_scaling());
                         } else {
                             graph.set_scaling(GraphWidget::no_scaling());
                         }
                         graph.set_value_range_max(max_speed as f32);
+                        summary
+                            .secondary_graph_widget()
+                            .set_value_range_max(max_speed as f32);
                     }
                 });
             }
 
             let page = NetworkPage::new(if_name, conn_kind, &settings);
-            page.set_base_color(gdk::RGBA::new(
-                NETWORK_BASE_COLOR[0] as f32 / 255.,
-                NETWORK_BASE_COLOR[1] as f32 / 255.,
-                NETWORK_BASE_COLOR[2] as f32 / 255.,
-                1.,
-            ));
+            page.set_base_color(page_color(&page_name));
 
             page.set_static_information(connection);
-            self.configure_page(&page);
+            self.configure_page(&page, &page_name);
 
             self.page_stack.add_named(&page, Some(&page_name));
             self.add_to_sidebar(&summary, pos_hint);
@@ -1412,22 +2445,12 @@ mod imp {
             }
             summary.set_info2(info2.as_str());
 
-            summary.set_base_color(gdk::RGBA::new(
-                GPU_BASE_COLOR[0] as f32 / 255.,
-                GPU_BASE_COLOR[1] as f32 / 255.,
-                GPU_BASE_COLOR[2] as f32 / 255.,
-                1.,
-            ));
+            summary.set_base_color(page_color(&page_name));
 
-            page.set_base_color(gdk::RGBA::new(
-                GPU_BASE_COLOR[0] as f32 / 255.,
-                GPU_BASE_COLOR[1] as f32 / 255.,
-                GPU_BASE_COLOR[2] as f32 / 255.,
-                1.,
-            ));
+            page.set_base_color(page_color(&page_name));
             page.set_static_information(index, gpu);
 
-            self.configure_page(&page);
+            self.configure_page(&page, &page_name);
 
             self.page_stack.add_named(&page, Some(&page_name));
             self.add_to_sidebar(&summary, pos_hint);
@@ -1506,12 +2529,7 @@ mod imp {
             } else {
                 summary.set_heading(i18n("Fan"));
             }
-            summary.set_base_color(gdk::RGBA::new(
-                FAN_BASE_COLOR[0] as f32 / 255.,
-                FAN_BASE_COLOR[1] as f32 / 255.,
-                FAN_BASE_COLOR[2] as f32 / 255.,
-                1.,
-            ));
+            summary.set_base_color(page_color(&page_name));
 
             let settings = settings!();
 
@@ -1533,15 +2551,10 @@ mod imp {
                 .set_expected_animation_ticks(settings.uint64("app-update-interval-u64") as u32);
 
             let page = FanPage::new(&page_name, &settings);
-            page.set_base_color(gdk::RGBA::new(
-                FAN_BASE_COLOR[0] as f32 / 255.,
-                FAN_BASE_COLOR[1] as f32 / 255.,
-                FAN_BASE_COLOR[2] as f32 / 255.,
-                1.,
-            ));
+            page.set_base_color(page_color(&page_name));
             page.set_static_information(fan_static_info);
 
-            self.configure_page(&page);
+            self.configure_page(&page, &page_name);
 
             self.page_stack.add_named(&page, Some(&page_name));
             self.add_to_sidebar(&summary, pos_hint);
@@ -1568,6 +2581,89 @@ mod imp {
             (page_name, (summary, page))
         }
 
+        fn set_up_ups_pages(
+            &self,
+            pages: &mut Vec<Pages>,
+            readings: &crate::magpie_client::Readings,
+        ) {
+            let mut upses = HashMap::new();
+            for i in 0..readings.ups.len() {
+                let mut ret = self.create_ups_page(readings, i, None);
+                upses.insert(std::mem::take(&mut ret.0), ret.1);
+            }
+
+            pages.push(Pages::Ups(upses));
+        }
+
+        fn ups_page_name(ups: &Ups) -> String {
+            format!("ups-{}", ups.name)
+        }
+
+        pub fn create_ups_page(
+            &self,
+            readings: &crate::magpie_client::Readings,
+            index: usize,
+            pos_hint: Option<i32>,
+        ) -> (String, (SummaryGraph, UpsPage)) {
+            let ups_static_info = &readings.ups[index];
+
+            let page_name = Self::ups_page_name(ups_static_info);
+
+            let summary = SummaryGraph::new();
+            summary.set_widget_name(&page_name);
+
+            summary.set_heading(i18n("UPS"));
+            summary.set_info1(
+                ups_static_info
+                    .description
+                    .as_ref()
+                    .unwrap_or(&ups_static_info.name)
+                    .as_str(),
+            );
+            summary.set_base_color(page_color(&page_name));
+
+            let settings = settings!();
+
+            summary
+                .graph_widget()
+                .set_data_points(settings.int("performance-page-data-points") as u32);
+            summary
+                .graph_widget()
+                .set_smooth_graphs(settings.boolean("performance-smooth-graphs"));
+            summary
+                .graph_widget()
+                .set_do_animation(settings.boolean("performance-sliding-graphs"));
+            summary
+                .graph_widget()
+                .set_expected_animation_ticks(settings.uint64("app-update-interval-u64") as u32);
+
+            let page = UpsPage::new(&page_name, &settings);
+            page.set_base_color(page_color(&page_name));
+            page.set_static_information(ups_static_info);
+
+            self.configure_page(&page, &page_name);
+
+            self.page_stack.add_named(&page, Some(&page_name));
+            self.add_to_sidebar(&summary, pos_hint);
+
+            let mut actions = self.context_menu_view_actions.take();
+            match actions.get("ups") {
+                None => {
+                    g_critical!(
+                        "MissionCenter::PerformancePage",
+                        "Failed to wire up UPS action for {}, logic bug?",
+                        ups_static_info.name
+                    );
+                }
+                Some(action) => {
+                    actions.insert(page_name.clone(), action.clone());
+                }
+            }
+            self.context_menu_view_actions.set(actions);
+
+            (page_name, (summary, page))
+        }
+
         pub fn default_sort_sidebar_entries(&self) {
             fn add_graph_to_sidebar(
                 graph: Option<(SummaryGraph, gtk::DragSource)>,
@@ -1605,6 +2701,7 @@ mod imp {
             let mut net_graphs = Vec::with_capacity(summary_graphs.len());
             let mut gpu_graphs = Vec::with_capacity(summary_graphs.len());
             let mut fan_graphs = Vec::with_capacity(summary_graphs.len());
+            let mut ups_graphs = Vec::with_capacity(summary_graphs.len());
 
             for (graph, drag_source) in &summary_graphs {
                 graph.set_is_enabled(true);
@@ -1621,6 +2718,8 @@ mod imp {
                     gpu_graphs.push((graph.clone(), drag_source.clone()));
                 } else if graph.widget_name().starts_with("fan") {
                     fan_graphs.push((graph.clone(), drag_source.clone()));
+                } else if graph.widget_name().starts_with("ups") {
+                    ups_graphs.push((graph.clone(), drag_source.clone()));
                 }
             }
 
@@ -1631,6 +2730,7 @@ mod imp {
             net_graphs.sort_unstable_by(|(g1, _), (g2, _)| g1.widget_name().cmp(&g2.widget_name()));
             gpu_graphs.sort_unstable_by(|(g1, _), (g2, _)| g1.widget_name().cmp(&g2.widget_name()));
             fan_graphs.sort_unstable_by(|(g1, _), (g2, _)| g1.widget_name().cmp(&g2.widget_name()));
+            ups_graphs.sort_unstable_by(|(g1, _), (g2, _)| g1.widget_name().cmp(&g2.widget_name()));
 
             let sidebar = self.sidebar();
             sidebar.remove_all();
@@ -1642,6 +2742,7 @@ mod imp {
             add_graphs_to_sidebar(net_graphs, &sidebar, &mut index);
             add_graphs_to_sidebar(gpu_graphs, &sidebar, &mut index);
             add_graphs_to_sidebar(fan_graphs, &sidebar, &mut index);
+            add_graphs_to_sidebar(ups_graphs, &sidebar, &mut index);
         }
     }
 
@@ -1659,6 +2760,7 @@ mod imp {
             this.set_up_network_pages(&mut pages, &readings);
             this.set_up_gpu_pages(&mut pages, &readings);
             this.set_up_fan_pages(&mut pages, &readings);
+            this.set_up_ups_pages(&mut pages, &readings);
             this.pages.set(pages);
 
             this.default_sort_sidebar_entries();
@@ -1773,7 +2875,10 @@ mod imp {
                         pages.get(disk_page_name).and_then(|v| Some(v.clone()))
                     {
                         summary_graphs.remove(&graph);
-                        page_stack.remove(&page);
+                        // Either the page or, if it is open in its own window, its placeholder
+                        if let Some(child) = page_stack.child_by_name(disk_page_name) {
+                            page_stack.remove(&child);
+                        }
                         pages.remove(disk_page_name);
 
                         let parent = match graph.parent() {
@@ -1896,6 +3001,30 @@ mod imp {
                         );
                         pages_to_destroy.clear();
 
+                        this.imp().summary_graphs.set(summary_graphs);
+                    }
+                    Pages::Ups(ups_pages) => {
+                        for ups_page_name in ups_pages.keys() {
+                            if !readings
+                                .ups
+                                .iter()
+                                .any(|ups| &Self::ups_page_name(&ups) == ups_page_name)
+                            {
+                                pages_to_destroy.push(ups_page_name.clone());
+                            }
+                        }
+
+                        let mut summary_graphs = this.imp().summary_graphs.take();
+
+                        remove_pages(
+                            &pages_to_destroy,
+                            ups_pages,
+                            &mut summary_graphs,
+                            &this.sidebar(),
+                            &this.imp().page_stack,
+                        );
+                        pages_to_destroy.clear();
+
                         this.imp().summary_graphs.set(summary_graphs);
                     }
                 }
@@ -2011,7 +3140,10 @@ mod imp {
                                 graph_widget.set_smooth_graphs(smooth);
                                 graph_widget.set_do_animation(sliding);
                                 graph_widget.set_expected_animation_ticks(delay);
-                                graph_widget.add_data_point(0, disk.busy_percent);
+                                graph_widget.add_data_point(0, disk.rx_speed_bytes_ps as f32);
+                                summary
+                                    .secondary_graph_widget()
+                                    .add_data_point(0, disk.tx_speed_bytes_ps as f32);
                                 if let Some(temp_mk) = disk.temperature_milli_k {
                                     summary.set_info2(format!(
                                         "{:.0}% ({:.0} °C)",
@@ -2026,6 +3158,9 @@ mod imp {
                                     if hide_index { None } else { Some(index) },
                                     disk,
                                 );
+                                page.update_encryption(disk, &readings.disk_encryption);
+                                page.update_pressure(readings.io_pressure.as_ref());
+                                page.update_top_consumers(&readings.running_processes);
                             } else {
                                 new_devices.push(index);
                             }
@@ -2085,8 +3220,10 @@ mod imp {
                                 graph_widget.set_do_animation(sliding);
                                 graph_widget.set_expected_animation_ticks(delay);
 
-                                graph_widget.add_data_point(0, network_connection.tx_rate_bytes_ps);
-                                graph_widget.add_data_point(1, network_connection.rx_rate_bytes_ps);
+                                graph_widget.add_data_point(0, network_connection.rx_rate_bytes_ps);
+                                summary
+                                    .secondary_graph_widget()
+                                    .add_data_point(0, network_connection.tx_rate_bytes_ps);
 
                                 let send_speed = network_connection.tx_rate_bytes_ps;
                                 let rec_speed = network_connection.rx_rate_bytes_ps;
@@ -2106,6 +3243,13 @@ mod imp {
                                 summary.set_info2(i18n_f("{}: {}", &["R", &rect_speeed]));
 
                                 result &= page.update_readings(network_connection);
+                                page.update_top_consumers(
+                                    readings
+                                        .network_stats_error
+                                        .is_none()
+                                        .then_some(&readings.running_processes),
+                                );
+                                page.update_bluetooth(&readings.bluetooth);
                             } else {
                                 new_devices.push(index);
                             }
@@ -2167,17 +3311,27 @@ mod imp {
                                     summary.set_heading(i18n("GPU"));
                                 }
 
+                                let temperatures = readings.gpu_temperatures.get(id);
+
                                 let mut info2 = ArrayString::<256>::new();
                                 if let Some(v) = gpu.utilization_percent {
                                     graph_widget.add_data_point(0, v);
                                     let _ = write!(&mut info2, "{v}%");
                                 }
-                                if let Some(v) = gpu.temperature_c.map(|v| v.round() as u32) {
+                                // Show the sensor closest to its limit, it is the one that decides
+                                // whether the GPU throttles
+                                if let Some(v) = temperatures
+                                    .and_then(|t| t.limiting_sensor())
+                                    .map(|s| s.celsius)
+                                    .or(gpu.temperature_c)
+                                    .map(|v| v.round() as u32)
+                                {
                                     let _ = write!(&mut info2, " ({v} °C)");
                                 }
                                 summary.set_info2(info2.as_str());
 
-                                result &= page.update_readings(gpu, index);
+                                result &= page.update_readings(gpu, temperatures, index);
+                                page.update_game_mode(&readings.game_mode);
                             } else {
                                 new_devices.push((index, id.as_str()));
                             }
@@ -2280,10 +3434,76 @@ mod imp {
                             pages.insert(page_name, page);
                         }
                     }
+                    Pages::Ups(pages) => {
+                        let mut last_sidebar_pos = -1;
+                        let mut consecutive_dev_count = 0;
+
+                        let mut new_devices = Vec::new();
+                        for (index, ups) in readings.ups.iter().enumerate() {
+                            if let Some((summary, page)) = pages.get(&Self::ups_page_name(&ups)) {
+                                // Search for a group of existing UPSes and try to add new entries at that position
+                                summary
+                                    .parent()
+                                    .and_then(|p| p.downcast_ref::<gtk::ListBoxRow>().cloned())
+                                    .and_then(|row| {
+                                        let sidebar_pos = row.index();
+                                        if sidebar_pos == last_sidebar_pos + 1 {
+                                            consecutive_dev_count += 1;
+                                        } else {
+                                            consecutive_dev_count = 1;
+                                        };
+                                        last_sidebar_pos = sidebar_pos;
+
+                                        Some(())
+                                    });
+
+                                let graph_widget = summary.graph_widget();
+                                graph_widget.set_data_points(data_points);
+                                graph_widget.set_smooth_graphs(smooth);
+                                graph_widget.set_do_animation(sliding);
+                                graph_widget.set_expected_animation_ticks(delay);
+                                if let Some(charge_percent) = ups.charge_percent {
+                                    graph_widget.add_data_point(0, charge_percent);
+                                }
+
+                                summary.set_info1(
+                                    ups.description.as_ref().unwrap_or(&ups.name).as_str(),
+                                );
+                                summary.set_info2(match ups.charge_percent {
+                                    Some(charge_percent) => format!(
+                                        "{:.0}% ({})",
+                                        charge_percent,
+                                        ups::status_text(ups)
+                                    ),
+                                    None => ups::status_text(ups),
+                                });
+
+                                result &= page.update_readings(ups);
+                            } else {
+                                new_devices.push(index);
+                            }
+                        }
+
+                        for index in new_devices {
+                            let (page_name, page) = this.imp().create_ups_page(
+                                readings,
+                                index,
+                                if last_sidebar_pos > -1 && consecutive_dev_count > 1 {
+                                    last_sidebar_pos += 1;
+                                    Some(last_sidebar_pos)
+                                } else {
+                                    None
+                                },
+                            );
+                            pages.insert(page_name, page);
+                        }
+                    }
                 }
             }
 
             this.imp().pages.set(pages);
+            this.imp().apply_graph_overlays();
+            this.imp().update_statistics();
 
             result
         }
@@ -2339,6 +3559,14 @@ mod imp {
                             result &= page.update_animations();
                         }
                     }
+                    Pages::Ups(pages) => {
+                        for (summary, page) in pages.values() {
+                            let graph_widget = summary.graph_widget();
+
+                            result &= graph_widget.update_animation();
+                            result &= page.update_animations();
+                        }
+                    }
                 }
             }
 
@@ -2390,6 +3618,67 @@ mod imp {
             let group = self.configure_actions();
             this.insert_action_group("graph", Some(&group));
 
+            let settings = settings!();
+            self.set_up_statistics_readout();
+            for key in [
+                "performance-page-graph-axis-labels",
+                "performance-page-graph-statistics",
+            ] {
+                settings.connect_changed(Some(key), {
+                    let this = self.obj().downgrade();
+                    move |_, _| {
+                        if let Some(this) = this.upgrade() {
+                            this.imp().apply_graph_overlays();
+                            this.imp().update_statistics();
+                        }
+                    }
+                });
+            }
+            settings.connect_changed(Some("performance-sidebar-labels"), {
+                let this = self.obj().downgrade();
+                move |_, _| {
+                    let Some(this) = this.upgrade() else {
+                        return;
+                    };
+                    let this = this.imp();
+
+                    let summary_graphs = this.summary_graphs.take();
+                    for graph in summary_graphs.keys() {
+                        this.apply_graph_label(graph);
+                    }
+                    this.summary_graphs.set(summary_graphs);
+                }
+            });
+            settings.connect_changed(Some("performance-page-colors"), {
+                let this = self.obj().downgrade();
+                move |_, _| {
+                    if let Some(this) = this.upgrade() {
+                        this.imp().apply_graph_colors();
+                    }
+                }
+            });
+
+            // The factors behind each time range depend on how long the default graph spans
+            for key in ["performance-page-data-points", "app-update-interval-u64"] {
+                settings.connect_changed(Some(key), {
+                    let this = self.obj().downgrade();
+                    move |_, _| {
+                        let Some(this) = this.upgrade() else {
+                            return;
+                        };
+                        let this = this.imp();
+
+                        let mut child = this.page_stack.first_child();
+                        while let Some(page) = child {
+                            if let Some(page_name) = this.page_stack.page(&page).name() {
+                                apply_time_window(&page, load_time_window(&page_name));
+                            }
+                            child = page.next_sibling();
+                        }
+                    }
+                });
+            }
+
             self.breakpoint.set_condition(Some(
                 &adw::BreakpointCondition::parse("max-width: 570sp").unwrap(),
             ));
@@ -2465,11 +3754,12 @@ mod imp {
             });
 
             if let Some(child) = self.page_stack.visible_child() {
-                let infobar_content = child.property::<Option<gtk::Widget>>("infobar-content");
-                self.info_bar.set_child(infobar_content.as_ref());
+                self.info_bar.set_child(infobar_content(&child).as_ref());
+                self.move_statistics_readout(&child);
             }
             self.page_stack.connect_visible_child_notify({
                 let this = self.obj().downgrade();
+                let group = group.clone();
                 move |page_stack| {
                     let this = match this.upgrade() {
                         Some(this) => this,
@@ -2477,9 +3767,22 @@ mod imp {
                     };
 
                     if let Some(child) = page_stack.visible_child() {
-                        let infobar_content =
-                            child.property::<Option<gtk::Widget>>("infobar-content");
-                        this.imp().info_bar.set_child(infobar_content.as_ref());
+                        this.imp()
+                            .info_bar
+                            .set_child(infobar_content(&child).as_ref());
+                        this.imp().move_statistics_readout(&child);
+                        this.imp().update_statistics();
+                    }
+
+                    if let (Some(page_name), Some(action)) = (
+                        page_stack.visible_child_name(),
+                        group
+                            .lookup_action("time-window")
+                            .and_then(|a| a.downcast::<gio::SimpleAction>().ok()),
+                    ) {
+                        action.set_state(&glib::Variant::from(
+                            load_time_window(&page_name).to_string(),
+                        ));
                     }
                 }
             });
@@ -2511,6 +3814,76 @@ impl PerformancePage {
         imp::PerformancePage::update_animations(self)
     }
 
+    /// Select a device page by its full name (e.g. `gpu-0`) or by its kind, in which case the first
+    /// visible device of that kind in the sidebar is selected
+    pub fn select_page(&self, name: &str) -> bool {
+        let name = match name {
+            "network" => "net",
+            "drive" => "disk",
+            name => name,
+        };
+        let prefix = format!("{}-", name);
+
+        let sidebar = self.imp().sidebar();
+
+        let mut selected_row = None;
+        let mut row_index = 0;
+        while let Some(row) = sidebar.row_at_index(row_index) {
+            row_index += 1;
+
+            if !row.is_visible() {
+                continue;
+            }
+
+            let Some(child) = row.child() else {
+                continue;
+            };
+
+            let child_name = child.widget_name();
+            if child_name.as_str() == name {
+                selected_row = Some(row);
+                break;
+            }
+
+            if selected_row.is_none() && child_name.starts_with(&prefix) {
+                selected_row = Some(row);
+            }
+        }
+
+        let Some(row) = selected_row else {
+            return false;
+        };
+        sidebar.select_row(Some(&row));
+
+        true
+    }
+
+    /// The name and heading of every device page shown in the sidebar, in sidebar order
+    pub fn pages(&self) -> Vec<(String, String)> {
+        let sidebar = self.imp().sidebar();
+
+        let mut result = vec![];
+        let mut row_index = 0;
+        while let Some(row) = sidebar.row_at_index(row_index) {
+            row_index += 1;
+
+            if !row.is_visible() {
+                continue;
+            }
+
+            let Some(graph) = row
+                .child()
+                .and_then(|child| child.downcast::<SummaryGraph>().ok())
+            else {
+                continue;
+            };
+
+            result.push((graph.widget_name().to_string(), graph.heading()));
+        }
+
+        result
+    }
+
     pub fn sidebar_enable_all(&self) {
         let this = self.imp();
 
@@ -2568,3 +3941,83 @@ impl PerformancePage {
         this.default_sort_sidebar_entries();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header_row(fields: &[(&str, export::Unit)]) -> String {
+        graph_table(fields, &[], 0, 1000.)
+            .csv_header()
+            .lines()
+            .skip(1)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    // Spreadsheets and scripts pick the columns by these
+    #[test]
+    fn graph_csv_headers() {
+        assert_eq!(
+            header_row(&cpu::GRAPH_FIELDS),
+            "# units: timestamp=unix_ms,cpu.usage=percent,cpu.kernel_usage=percent\n\
+             timestamp,cpu.usage,cpu.kernel_usage"
+        );
+        assert_eq!(
+            header_row(&memory::GRAPH_FIELDS),
+            "# units: timestamp=unix_ms,memory.committed=bytes,memory.dirty=bytes,\
+             memory.used=bytes,memory.swap_used=bytes\n\
+             timestamp,memory.committed,memory.dirty,memory.used,memory.swap_used"
+        );
+        assert_eq!(
+            header_row(&disk::GRAPH_FIELDS),
+            "# units: timestamp=unix_ms,disk.busy=percent,disk.read=bytes_per_second,\
+             disk.write=bytes_per_second\n\
+             timestamp,disk.busy,disk.read,disk.write"
+        );
+        assert_eq!(
+            header_row(&network::GRAPH_FIELDS),
+            "# units: timestamp=unix_ms,network.send=bytes_per_second,\
+             network.receive=bytes_per_second\n\
+             timestamp,network.send,network.receive"
+        );
+        assert_eq!(
+            header_row(&gpu::GRAPH_FIELDS),
+            "# units: timestamp=unix_ms,gpu.usage=percent,gpu.encoder_usage=percent,\
+             gpu.decoder_usage=percent,gpu.memory_used=bytes,gpu.temperature=celsius,\
+             gpu.hotspot_temperature=celsius,gpu.memory_temperature=celsius\n\
+             timestamp,gpu.usage,gpu.encoder_usage,gpu.decoder_usage,gpu.memory_used,\
+             gpu.temperature,gpu.hotspot_temperature,gpu.memory_temperature"
+        );
+        assert_eq!(
+            header_row(&fan::GRAPH_FIELDS),
+            "# units: timestamp=unix_ms,fan.speed=rpm,fan.pwm=percent,fan.temperature=celsius\n\
+             timestamp,fan.speed,fan.pwm,fan.temperature"
+        );
+        assert_eq!(
+            header_row(&ups::GRAPH_FIELDS),
+            "# units: timestamp=unix_ms,ups.charge=percent,ups.load=percent\n\
+             timestamp,ups.charge,ups.load"
+        );
+    }
+
+    #[test]
+    fn graph_csv_lines_up_newest_points() {
+        let table = graph_table(
+            &cpu::GRAPH_FIELDS,
+            &[vec![1., 2., 3.5], vec![0.25]],
+            1735689600000,
+            1000.,
+        );
+
+        assert_eq!(
+            table.to_csv(),
+            "# missioncenter-export kind=graph schema-version=1\n\
+             # units: timestamp=unix_ms,cpu.usage=percent,cpu.kernel_usage=percent\n\
+             timestamp,cpu.usage,cpu.kernel_usage\n\
+             1735689598000,1,\n\
+             1735689599000,2,\n\
+             1735689600000,3.5,0.25\n"
+        );
+    }
+}
@@ -19,6 +19,7 @@
  */
 
 use std::cell::{Cell, OnceCell, RefCell};
+use std::collections::HashMap;
 
 use adw::subclass::prelude::*;
 use glib::{ParamSpec, Properties, Value};
@@ -26,8 +27,25 @@ use gtk::{gio, glib, prelude::*};
 
 use magpie_types::network::{Connection, ConnectionKind};
 
-use super::{widgets::GraphWidget, PageExt};
-use crate::{application::INTERVAL_STEP, i18n::*, settings, to_short_human_readable_time};
+use super::{
+    export_graph_data, save_page_image,
+    widgets::{GraphWidget, HistoryDialog, HistoryKind, TopProcesses},
+    PageExt,
+};
+use crate::{
+    application::INTERVAL_STEP,
+    export::Unit,
+    i18n::*,
+    magpie_client::{BluetoothAdapter, Process},
+    settings, to_short_human_readable_time,
+};
+
+/// What the data sets of the graphs are exported as, in the order `export_graph_data` is
+/// handed them
+pub(super) const GRAPH_FIELDS: [(&str, Unit); 2] = [
+    ("network.send", Unit::BytesPerSecond),
+    ("network.receive", Unit::BytesPerSecond),
+];
 
 mod imp {
     use super::*;
@@ -78,7 +96,11 @@ mod imp {
         pub hw_address: OnceCell<gtk::Label>,
         pub ipv4_address: OnceCell<gtk::Label>,
         pub ipv6_address: OnceCell<gtk::Label>,
+        pub bluetooth_box: OnceCell<gtk::Box>,
+        pub bluetooth_list: OnceCell<gtk::Box>,
+        pub top_consumers: OnceCell<TopProcesses>,
 
+        bluetooth_adapters: RefCell<Vec<BluetoothAdapter>>,
         signal_strength_percent: Cell<Option<u8>>,
         pub use_bytes: Cell<bool>,
         // in bps
@@ -118,7 +140,11 @@ mod imp {
                 hw_address: Default::default(),
                 ipv4_address: Default::default(),
                 ipv6_address: Default::default(),
+                bluetooth_box: Default::default(),
+                bluetooth_list: Default::default(),
+                top_consumers: Default::default(),
 
+                bluetooth_adapters: RefCell::new(vec![]),
                 signal_strength_percent: Cell::new(None),
                 use_bytes: Cell::new(false),
                 max_speed: Cell::new(None),
@@ -177,6 +203,47 @@ mod imp {
                 }
             });
             actions.add_action(&action);
+
+            let action = gio::SimpleAction::new("export-data", None);
+            action.connect_activate({
+                let this = this.downgrade();
+                move |_, _| {
+                    let Some(this) = this.upgrade() else {
+                        return;
+                    };
+
+                    let imp = this.imp();
+                    export_graph_data(
+                        this.upcast_ref(),
+                        &[(imp.usage_graph.get(), 0), (imp.usage_graph.get(), 1)],
+                        &GRAPH_FIELDS,
+                    );
+                }
+            });
+            actions.add_action(&action);
+
+            let action = gio::SimpleAction::new("save-image", None);
+            action.connect_activate({
+                let this = this.downgrade();
+                move |_, _| {
+                    if let Some(this) = this.upgrade() {
+                        save_page_image(this.upcast_ref());
+                    }
+                }
+            });
+            actions.add_action(&action);
+
+            let action = gio::SimpleAction::new("history", None);
+            action.connect_activate({
+                let this = this.downgrade();
+                move |_, _| {
+                    if let Some(this) = this.upgrade() {
+                        HistoryDialog::new(HistoryKind::Network, this.base_color())
+                            .present(Some(this.upcast_ref::<gtk::Widget>()));
+                    }
+                }
+            });
+            actions.add_action(&action);
         }
 
         fn configure_context_menu(this: &super::PerformancePageNetwork) {
@@ -329,6 +396,21 @@ mod imp {
 
             this.usage_graph.set_filled(0, false);
             this.usage_graph.set_dashed(0, true);
+            this.usage_graph.set_axis_formatter(|value| {
+                crate::to_human_readable_nice(value, &DataType::NetworkBytesPerSecond, &settings!())
+            });
+            this.usage_graph.set_value_formatter(|index, value| {
+                let value = crate::to_human_readable_nice(
+                    value,
+                    &DataType::NetworkBytesPerSecond,
+                    &settings!(),
+                );
+                if index == 0 {
+                    i18n_f("Send: {}", &[&value])
+                } else {
+                    i18n_f("Receive: {}", &[&value])
+                }
+            });
 
             this.max_speed.set(connection.max_speed_bytes_ps);
 
@@ -483,6 +565,122 @@ mod imp {
             true
         }
 
+        /// `processes` is `None` when the gatherer can't account network usage to processes
+        pub fn update_top_consumers(
+            this: &super::PerformancePageNetwork,
+            processes: Option<&HashMap<u32, Process>>,
+        ) {
+            let Some(top_consumers) = this.imp().top_consumers.get() else {
+                return;
+            };
+
+            // Usage is only known per process, not per interface, so each page lists the same ones
+            let no_processes = HashMap::new();
+            let settings = settings!();
+            top_consumers.update(
+                processes.unwrap_or(&no_processes),
+                |process| process.usage_stats.network_usage,
+                |usage| {
+                    crate::to_human_readable_nice(
+                        usage,
+                        &DataType::NetworkBytesPerSecond,
+                        &settings,
+                    )
+                },
+            );
+        }
+
+        pub fn update_bluetooth(
+            this: &super::PerformancePageNetwork,
+            adapters: &[BluetoothAdapter],
+        ) {
+            let this = this.imp();
+
+            if this.bluetooth_adapters.borrow().as_slice() == adapters {
+                return;
+            }
+            this.bluetooth_adapters.replace(adapters.to_vec());
+
+            let (Some(bluetooth_box), Some(bluetooth_list)) =
+                (this.bluetooth_box.get(), this.bluetooth_list.get())
+            else {
+                return;
+            };
+
+            bluetooth_box.set_visible(!adapters.is_empty());
+
+            while let Some(child) = bluetooth_list.first_child() {
+                bluetooth_list.remove(&child);
+            }
+
+            fn row(icon_name: &str, text: &str, detail: Option<String>) -> gtk::Box {
+                let row = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+
+                row.append(&gtk::Image::from_icon_name(icon_name));
+
+                let label = gtk::Label::new(Some(text));
+                label.add_css_class("caption");
+                label.set_halign(gtk::Align::Start);
+                label.set_hexpand(true);
+                label.set_ellipsize(gtk::pango::EllipsizeMode::End);
+                label.set_selectable(true);
+                row.append(&label);
+
+                if let Some(detail) = detail {
+                    let label = gtk::Label::new(Some(&detail));
+                    label.add_css_class("caption");
+                    label.add_css_class("dim-label");
+                    row.append(&label);
+                }
+
+                row
+            }
+
+            for adapter in adapters {
+                bluetooth_list.append(&row(
+                    if adapter.powered {
+                        "bluetooth-active-symbolic"
+                    } else {
+                        "bluetooth-disabled-symbolic"
+                    },
+                    &format!("{} ({})", adapter.name, adapter.address),
+                    if adapter.powered {
+                        None
+                    } else {
+                        Some(i18n("Off"))
+                    },
+                ));
+
+                if adapter.powered && adapter.devices.is_empty() {
+                    let label = gtk::Label::new(Some(&i18n("No connected devices")));
+                    label.add_css_class("caption");
+                    label.add_css_class("dim-label");
+                    label.set_halign(gtk::Align::Start);
+                    label.set_margin_start(22);
+                    bluetooth_list.append(&label);
+                }
+
+                for device in &adapter.devices {
+                    let icon_name = device
+                        .icon
+                        .as_ref()
+                        .map(|icon| format!("{}-symbolic", icon))
+                        .unwrap_or_else(|| "bluetooth-symbolic".to_owned());
+
+                    let device_row = row(
+                        &icon_name,
+                        &device.name,
+                        device
+                            .battery_percent
+                            .map(|percent| i18n_f("Battery {}%", &[&percent.to_string()])),
+                    );
+                    device_row.set_margin_start(22);
+                    device_row.set_tooltip_text(Some(&device.address));
+                    bluetooth_list.append(&device_row);
+                }
+            }
+        }
+
         pub fn update_animations(this: &super::PerformancePageNetwork) -> bool {
             let this = this.imp();
 
@@ -602,6 +800,7 @@ mod imp {
             Self::configure_actions(&this);
             Self::configure_context_menu(&this);
 
+            TopProcesses::ensure_type();
             let sidebar_content_builder = gtk::Builder::from_resource(
                 "/io/missioncenter/MissionCenter/ui/performance_page/network_details.ui",
             );
@@ -687,6 +886,21 @@ mod imp {
                     .object::<gtk::Label>("ipv6_address")
                     .expect("Could not find `ipv6_address` object in details pane"),
             );
+            let _ = self.bluetooth_box.set(
+                sidebar_content_builder
+                    .object::<gtk::Box>("bluetooth_box")
+                    .expect("Could not find `bluetooth_box` object in details pane"),
+            );
+            let _ = self.top_consumers.set(
+                sidebar_content_builder
+                    .object::<TopProcesses>("top_consumers")
+                    .expect("Could not find `top_consumers` object in details pane"),
+            );
+            let _ = self.bluetooth_list.set(
+                sidebar_content_builder
+                    .object::<gtk::Box>("bluetooth_list")
+                    .expect("Could not find `bluetooth_list` object in details pane"),
+            );
         }
     }
 
@@ -742,7 +956,9 @@ impl PerformancePageNetwork {
 
             let this = this.imp();
             this.graph_max_duration
-                .set_text(&to_short_human_readable_time(graph_max_duration));
+                .set_text(&to_short_human_readable_time(
+                    graph_max_duration * this.usage_graph.downsample(),
+                ));
 
             this.usage_graph.set_data_points(data_points);
             this.usage_graph.set_smooth_graphs(smooth);
@@ -751,6 +967,16 @@ impl PerformancePageNetwork {
         }
         update_refresh_rate_sensitive_labels(&this, settings);
 
+        this.imp().usage_graph.connect_downsample_notify({
+            let this = this.downgrade();
+            let settings = settings.clone();
+            move |_| {
+                if let Some(this) = this.upgrade() {
+                    update_refresh_rate_sensitive_labels(&this, &settings);
+                }
+            }
+        });
+
         this.imp()
             .use_bytes
             .set(settings.boolean("performance-page-network-use-bytes"));
@@ -880,6 +1106,14 @@ impl PerformancePageNetwork {
         imp::PerformancePageNetwork::update_readings(self, connection)
     }
 
+    pub fn update_top_consumers(&self, processes: Option<&HashMap<u32, Process>>) {
+        imp::PerformancePageNetwork::update_top_consumers(self, processes)
+    }
+
+    pub fn update_bluetooth(&self, adapters: &[BluetoothAdapter]) {
+        imp::PerformancePageNetwork::update_bluetooth(self, adapters)
+    }
+
     pub fn update_animations(&self) -> bool {
         imp::PerformancePageNetwork::update_animations(self)
     }
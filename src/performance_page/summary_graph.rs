@@ -19,14 +19,16 @@
  * SPDX-License-Identifier: GPL-3.0-or-later
  */
 
+use std::cell::RefCell;
 use std::collections::HashSet;
 
-use adw::subclass::prelude::*;
+use adw::{prelude::*, subclass::prelude::*};
 use glib::{ParamSpec, Properties, Value};
-use gtk::{gdk, glib, prelude::*};
+use gtk::{gdk, glib};
 
 use super::widgets::{GraphWidget, SidebarDropHint};
-use crate::settings;
+use super::{load_graph_label, save_graph_label};
+use crate::{i18n::*, settings};
 
 mod imp {
     use std::marker::PhantomData;
@@ -45,6 +47,9 @@ mod imp {
         pub drag_handle_icon: TemplateChild<gtk::Image>,
         #[template_child]
         pub graph_widget: TemplateChild<GraphWidget>,
+        /// Shown under the main graph for the other direction of a device, e.g. writes under reads
+        #[template_child]
+        pub secondary_graph_widget: TemplateChild<GraphWidget>,
         #[template_child]
         label_heading: TemplateChild<gtk::Label>,
         #[template_child]
@@ -52,8 +57,14 @@ mod imp {
         #[template_child]
         label_info2: TemplateChild<gtk::Label>,
         #[template_child]
+        pub rename_button: TemplateChild<gtk::Button>,
+        #[template_child]
         pub enabled_switch: TemplateChild<gtk::Switch>,
 
+        // The heading set by the page, shown unless the user picked a label of their own
+        default_heading: RefCell<String>,
+        custom_heading: RefCell<Option<String>>,
+
         #[property(get = Self::is_enabled, set = Self::set_enabled)]
         is_enabled: PhantomData<bool>,
 
@@ -73,11 +84,16 @@ mod imp {
                 drop_hint: SidebarDropHint::new(),
                 drag_handle_icon: Default::default(),
                 graph_widget: Default::default(),
+                secondary_graph_widget: Default::default(),
                 label_heading: Default::default(),
                 label_info1: Default::default(),
                 label_info2: Default::default(),
+                rename_button: Default::default(),
                 enabled_switch: Default::default(),
 
+                default_heading: RefCell::new(String::new()),
+                custom_heading: RefCell::new(None),
+
                 is_enabled: PhantomData,
 
                 base_color: PhantomData,
@@ -103,6 +119,8 @@ mod imp {
 
         fn set_base_color(&self, base_color: gdk::RGBA) {
             self.graph_widget.set_base_color(base_color);
+            self.secondary_graph_widget
+                .set_base_color(secondary_color(&base_color));
         }
 
         fn heading(&self) -> String {
@@ -110,7 +128,74 @@ mod imp {
         }
 
         fn set_heading(&self, heading: String) {
-            self.label_heading.set_text(&heading);
+            self.default_heading.replace(heading);
+            self.update_heading();
+        }
+
+        pub fn set_custom_heading(&self, custom_heading: Option<String>) {
+            self.custom_heading.replace(custom_heading);
+            self.update_heading();
+        }
+
+        fn update_heading(&self) {
+            let default_heading = self.default_heading.borrow();
+            match self.custom_heading.borrow().as_ref() {
+                Some(custom_heading) => {
+                    self.label_heading.set_text(custom_heading);
+                    self.label_heading
+                        .set_tooltip_text(Some(default_heading.as_str()));
+                }
+                None => {
+                    self.label_heading.set_text(&default_heading);
+                    self.label_heading.set_tooltip_text(None);
+                }
+            }
+        }
+
+        fn show_rename_dialog(&self) {
+            let obj = self.obj();
+            let page_name = obj.widget_name().to_string();
+            let current = self.custom_heading.borrow().clone();
+
+            let entry = adw::EntryRow::builder()
+                .title(i18n("Name"))
+                .text(current.as_deref().unwrap_or(&self.default_heading.borrow()))
+                .activates_default(true)
+                .build();
+            let fields = gtk::ListBox::builder()
+                .selection_mode(gtk::SelectionMode::None)
+                .css_classes(["boxed-list"])
+                .build();
+            fields.append(&entry);
+
+            let dialog = adw::AlertDialog::builder()
+                .heading(i18n("Rename"))
+                .body(i18n_f(
+                    "Shown in the sidebar and on the page instead of “{}”",
+                    &[self.default_heading.borrow().as_str()],
+                ))
+                .extra_child(&fields)
+                .build();
+            dialog.add_response("cancel", &i18n("_Cancel"));
+            if current.is_some() {
+                dialog.add_response("reset", &i18n("_Reset"));
+            }
+            dialog.add_response("rename", &i18n("_Rename"));
+            dialog.set_response_appearance("rename", adw::ResponseAppearance::Suggested);
+            dialog.set_default_response(Some("rename"));
+            dialog.set_close_response("cancel");
+
+            dialog.connect_response(None, move |_, response| match response {
+                "reset" => save_graph_label(&page_name, None),
+                "rename" => {
+                    let label = entry.text();
+                    let label = label.trim();
+                    save_graph_label(&page_name, (!label.is_empty()).then_some(label));
+                }
+                _ => {}
+            });
+
+            dialog.present(Some(&*obj));
         }
 
         fn info1(&self) -> String {
@@ -171,6 +256,33 @@ mod imp {
         fn constructed(&self) {
             self.parent_constructed();
 
+            // The pages only set up and animate the main graph, the other one follows it
+            for property in [
+                "data-points",
+                "smooth-graphs",
+                "do-animation",
+                "expected-animation-ticks",
+                "animation-ticks",
+                "scaling",
+                "only-scale-up",
+            ] {
+                self.graph_widget
+                    .bind_property(property, &*self.secondary_graph_widget, property)
+                    .sync_create()
+                    .build();
+            }
+            self.secondary_graph_widget
+                .connect_animation_ticks_notify(|graph_widget| graph_widget.queue_draw());
+
+            self.rename_button.connect_clicked({
+                let this = self.obj().downgrade();
+                move |_| {
+                    if let Some(this) = this.upgrade() {
+                        this.imp().show_rename_dialog();
+                    }
+                }
+            });
+
             self.enabled_switch.connect_active_notify({
                 let this = self.obj().downgrade();
                 move |switch| {
@@ -226,6 +338,14 @@ mod imp {
     impl BoxImpl for SummaryGraph {}
 }
 
+/// The color of the second graph, the one opposite `color` on the color wheel so it stands apart
+fn secondary_color(color: &gdk::RGBA) -> gdk::RGBA {
+    let (hue, saturation, value) = gtk::rgb_to_hsv(color.red(), color.green(), color.blue());
+    let (red, green, blue) = gtk::hsv_to_rgb((hue + 0.5) % 1., saturation, value);
+
+    gdk::RGBA::new(red, green, blue, color.alpha())
+}
+
 glib::wrapper! {
     pub struct SummaryGraph(ObjectSubclass<imp::SummaryGraph>)
         @extends gtk::Widget, gtk::Box,
@@ -239,16 +359,39 @@ impl SummaryGraph {
 
     pub fn set_edit_mode(&self, edit_mode: bool) {
         self.imp().drag_handle_icon.set_visible(edit_mode);
+        self.imp().rename_button.set_visible(edit_mode);
         self.imp().enabled_switch.set_visible(edit_mode);
         if let Some(parent) = self.parent() {
             parent.set_visible(edit_mode || self.is_enabled());
         }
     }
 
+    /// Show the label the user picked for the graph, if any, instead of its heading
+    pub fn load_custom_heading(&self) {
+        self.imp()
+            .set_custom_heading(load_graph_label(self.widget_name().as_str()));
+    }
+
     pub fn graph_widget(&self) -> GraphWidget {
         self.imp().graph_widget.clone()
     }
 
+    pub fn secondary_graph_widget(&self) -> GraphWidget {
+        self.imp().secondary_graph_widget.clone()
+    }
+
+    /// Show two graphs on top of each other instead of one, labelled `label` and
+    /// `secondary_label`, like reads and writes of a drive
+    pub fn split(&self, label: &str, secondary_label: &str) {
+        let imp = self.imp();
+
+        imp.graph_widget.set_height_request(24);
+        imp.graph_widget.set_data_label(0, Some(label));
+        imp.secondary_graph_widget.set_visible(true);
+        imp.secondary_graph_widget
+            .set_data_label(0, Some(secondary_label));
+    }
+
     pub fn show_drop_hint_top(&self) {
         self.hide_drop_hint();
 
@@ -0,0 +1,582 @@
+/* performance_page/ups.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::cell::{Cell, OnceCell, RefCell};
+
+use adw;
+use adw::subclass::prelude::*;
+use glib::{ParamSpec, Properties, Value};
+use gtk::{gio, glib, prelude::*};
+
+use super::widgets::GraphWidget;
+use crate::app;
+use crate::application::INTERVAL_STEP;
+use crate::export::Unit;
+use crate::i18n::*;
+use crate::magpie_client::Ups;
+use crate::performance_page::{export_graph_data, save_page_image, PageExt};
+use crate::{to_long_human_readable_time, to_short_human_readable_time};
+
+// Notify when running on battery with less than this much charge left, unless the UPS reports
+// low battery by itself first
+const LOW_BATTERY_PERCENT: f32 = 20.;
+
+/// What the data sets of the graphs are exported as, in the order `export_graph_data` is
+/// handed them
+pub(super) const GRAPH_FIELDS: [(&str, Unit); 2] =
+    [("ups.charge", Unit::Percent), ("ups.load", Unit::Percent)];
+
+mod imp {
+    use super::*;
+
+    #[derive(Properties)]
+    #[properties(wrapper_type = super::PerformancePageUps)]
+    #[derive(gtk::CompositeTemplate)]
+    #[template(resource = "/io/missioncenter/MissionCenter/ui/performance_page/ups.ui")]
+    pub struct PerformancePageUps {
+        #[template_child]
+        pub title_ups_name: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub title_model: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub charge_graph: TemplateChild<GraphWidget>,
+        #[template_child]
+        pub charge_graph_max_duration: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub load_graph: TemplateChild<GraphWidget>,
+        #[template_child]
+        pub load_graph_max_duration: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub load_graph_box: TemplateChild<gtk::Box>,
+        #[template_child]
+        pub context_menu: TemplateChild<gtk::Popover>,
+
+        #[property(get = Self::name, set = Self::set_name, type = String)]
+        name: RefCell<String>,
+        #[property(get, set)]
+        base_color: Cell<gtk::gdk::RGBA>,
+        #[property(get, set)]
+        summary_mode: Cell<bool>,
+
+        #[property(get = Self::infobar_content, type = Option < gtk::Widget >)]
+        pub infobar_content: OnceCell<gtk::Grid>,
+
+        pub status: OnceCell<gtk::Label>,
+        pub charge: OnceCell<gtk::Label>,
+        pub load: OnceCell<gtk::Label>,
+        pub load_box: OnceCell<gtk::Box>,
+        pub runtime: OnceCell<gtk::Label>,
+        pub runtime_box: OnceCell<gtk::Box>,
+        pub input_voltage: OnceCell<gtk::Label>,
+        pub input_voltage_box: OnceCell<gtk::Box>,
+
+        low_battery_notified: Cell<bool>,
+    }
+
+    impl Default for PerformancePageUps {
+        fn default() -> Self {
+            Self {
+                title_ups_name: Default::default(),
+                title_model: Default::default(),
+                charge_graph: Default::default(),
+                charge_graph_max_duration: Default::default(),
+                load_graph: Default::default(),
+                load_graph_max_duration: Default::default(),
+                load_graph_box: Default::default(),
+                context_menu: Default::default(),
+
+                name: RefCell::new(String::new()),
+                base_color: Cell::new(gtk::gdk::RGBA::new(0.0, 0.0, 0.0, 1.0)),
+                summary_mode: Cell::new(false),
+
+                infobar_content: Default::default(),
+
+                status: Default::default(),
+                charge: Default::default(),
+                load: Default::default(),
+                load_box: Default::default(),
+                runtime: Default::default(),
+                runtime_box: Default::default(),
+                input_voltage: Default::default(),
+                input_voltage_box: Default::default(),
+
+                low_battery_notified: Cell::new(false),
+            }
+        }
+    }
+
+    impl PerformancePageUps {
+        fn name(&self) -> String {
+            self.name.borrow().clone()
+        }
+
+        fn set_name(&self, name: String) {
+            if name == *self.name.borrow() {
+                return;
+            }
+
+            self.name.replace(name);
+        }
+
+        fn infobar_content(&self) -> Option<gtk::Widget> {
+            self.infobar_content.get().map(|ic| ic.clone().into())
+        }
+    }
+
+    impl PerformancePageUps {
+        fn configure_actions(this: &super::PerformancePageUps) {
+            let actions = gio::SimpleActionGroup::new();
+            this.insert_action_group("graph", Some(&actions));
+
+            let action = gio::SimpleAction::new("copy", None);
+            action.connect_activate({
+                let this = this.downgrade();
+                move |_, _| {
+                    if let Some(this) = this.upgrade() {
+                        let clipboard = this.clipboard();
+                        clipboard.set_text(this.imp().data_summary().as_str());
+                    }
+                }
+            });
+            actions.add_action(&action);
+
+            let action = gio::SimpleAction::new("export-data", None);
+            action.connect_activate({
+                let this = this.downgrade();
+                move |_, _| {
+                    let Some(this) = this.upgrade() else {
+                        return;
+                    };
+
+                    let imp = this.imp();
+                    export_graph_data(
+                        this.upcast_ref(),
+                        &[(imp.charge_graph.get(), 0), (imp.load_graph.get(), 0)],
+                        &GRAPH_FIELDS,
+                    );
+                }
+            });
+            actions.add_action(&action);
+
+            let action = gio::SimpleAction::new("save-image", None);
+            action.connect_activate({
+                let this = this.downgrade();
+                move |_, _| {
+                    if let Some(this) = this.upgrade() {
+                        save_page_image(this.upcast_ref());
+                    }
+                }
+            });
+            actions.add_action(&action);
+        }
+
+        fn configure_context_menu(this: &super::PerformancePageUps) {
+            let right_click_controller = gtk::GestureClick::new();
+            right_click_controller.set_button(3); // Secondary click (AKA right click)
+            right_click_controller.connect_released({
+                let this = this.downgrade();
+                move |_click, _n_press, x, y| {
+                    if let Some(this) = this.upgrade() {
+                        let this = this.imp();
+                        this.context_menu
+                            .set_pointing_to(Some(&gtk::gdk::Rectangle::new(
+                                x.round() as i32,
+                                y.round() as i32,
+                                1,
+                                1,
+                            )));
+                        this.context_menu.popup();
+                    }
+                }
+            });
+            this.add_controller(right_click_controller);
+        }
+    }
+
+    impl PerformancePageUps {
+        pub fn set_static_information(this: &super::PerformancePageUps, ups: &Ups) -> bool {
+            let this = this.imp();
+
+            this.title_ups_name.set_text(&ups.name);
+            if let Some(description) = &ups.description {
+                this.title_model.set_text(description);
+            }
+
+            if ups.load_percent.is_none() {
+                this.load_graph_box.set_visible(false);
+                if let Some(load_box) = this.load_box.get() {
+                    load_box.set_visible(false);
+                }
+            }
+
+            if ups.runtime_seconds.is_none() {
+                if let Some(runtime_box) = this.runtime_box.get() {
+                    runtime_box.set_visible(false);
+                }
+            }
+
+            if ups.input_voltage.is_none() {
+                if let Some(input_voltage_box) = this.input_voltage_box.get() {
+                    input_voltage_box.set_visible(false);
+                }
+            }
+
+            true
+        }
+
+        pub fn update_readings(this: &super::PerformancePageUps, ups: &Ups) -> bool {
+            let this = this.imp();
+
+            if let Some(status) = this.status.get() {
+                status.set_text(&status_text(ups));
+            }
+
+            if let Some(charge_percent) = ups.charge_percent {
+                if let Some(charge) = this.charge.get() {
+                    charge.set_text(&format!("{:.0}%", charge_percent));
+                }
+                this.charge_graph.add_data_point(0, charge_percent);
+            }
+
+            if let Some(load_percent) = ups.load_percent {
+                if let Some(load) = this.load.get() {
+                    load.set_text(&format!("{:.0}%", load_percent));
+                }
+                this.load_graph.add_data_point(0, load_percent);
+            }
+
+            if let (Some(runtime), Some(runtime_seconds)) =
+                (this.runtime.get(), ups.runtime_seconds)
+            {
+                runtime.set_text(&to_long_human_readable_time(runtime_seconds));
+            }
+
+            if let (Some(input_voltage), Some(voltage)) =
+                (this.input_voltage.get(), ups.input_voltage)
+            {
+                input_voltage.set_text(&i18n_f("{} V", &[&format!("{:.0}", voltage)]));
+            }
+
+            this.notify_low_battery(ups);
+
+            true
+        }
+
+        fn notify_low_battery(&self, ups: &Ups) {
+            let low_battery = ups.low_battery()
+                || (ups.on_battery()
+                    && ups
+                        .charge_percent
+                        .map(|charge| charge < LOW_BATTERY_PERCENT)
+                        .unwrap_or(false));
+
+            let notification_id = format!("ups-low-battery-{}", ups.name);
+
+            if !low_battery {
+                if self.low_battery_notified.replace(false) {
+                    app!().withdraw_notification(&notification_id);
+                }
+                return;
+            }
+
+            if self.low_battery_notified.replace(true) {
+                return;
+            }
+
+            let notification =
+                gio::Notification::new(&i18n_f("{} is low on battery", &[&ups.name]));
+            let body = match ups.runtime_seconds {
+                Some(runtime_seconds) => i18n_f(
+                    "The system is running on battery power; about {} left. Save your work.",
+                    &[&to_long_human_readable_time(runtime_seconds)],
+                ),
+                None => i18n("The system is running on battery power. Save your work."),
+            };
+            notification.set_body(Some(&body));
+            notification.set_priority(gio::NotificationPriority::Urgent);
+            notification.set_default_action_and_target_value(
+                "app.show-page",
+                Some(&self.name().to_variant()),
+            );
+
+            app!().send_notification(Some(&notification_id), &notification);
+        }
+
+        pub fn update_animations(this: &super::PerformancePageUps) -> bool {
+            let this = this.imp();
+
+            this.charge_graph.update_animation();
+            this.load_graph.update_animation();
+
+            true
+        }
+
+        fn data_summary(&self) -> String {
+            let unsupported = glib::GString::from(i18n("Unsupported"));
+
+            let text_of = |label: Option<&gtk::Label>| {
+                label
+                    .filter(|label| label.is_visible() && !label.text().is_empty())
+                    .map(|label| label.text())
+                    .unwrap_or(unsupported.clone())
+            };
+
+            format!(
+                r#"UPS
+
+    {}
+    {}
+
+    Status:            {}
+    Battery Charge:    {}
+    Load:              {}
+    Estimated Runtime: {}
+    Input Voltage:     {}"#,
+                self.title_ups_name.text(),
+                self.title_model.text(),
+                text_of(self.status.get()),
+                text_of(self.charge.get()),
+                text_of(self.load.get()),
+                text_of(self.runtime.get()),
+                text_of(self.input_voltage.get()),
+            )
+        }
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for PerformancePageUps {
+        const NAME: &'static str = "PerformancePageUps";
+        type Type = super::PerformancePageUps;
+        type ParentType = gtk::Box;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for PerformancePageUps {
+        fn properties() -> &'static [ParamSpec] {
+            Self::derived_properties()
+        }
+
+        fn set_property(&self, id: usize, value: &Value, pspec: &ParamSpec) {
+            self.derived_set_property(id, value, pspec);
+        }
+
+        fn property(&self, id: usize, pspec: &ParamSpec) -> Value {
+            self.derived_property(id, pspec)
+        }
+
+        fn constructed(&self) {
+            self.parent_constructed();
+
+            let obj = self.obj();
+            let this = obj.upcast_ref::<super::PerformancePageUps>().clone();
+
+            Self::configure_actions(&this);
+            Self::configure_context_menu(&this);
+
+            self.charge_graph
+                .set_value_formatter(|_, value| i18n_f("Charge: {}%", &[&format!("{:.0}", value)]));
+            for graph in [&*self.charge_graph, &*self.load_graph] {
+                graph.set_axis_formatter(|value| format!("{:.0}%", value));
+            }
+            self.load_graph
+                .set_value_formatter(|_, value| i18n_f("Load: {}%", &[&format!("{:.0}", value)]));
+
+            let sidebar_content_builder = gtk::Builder::from_resource(
+                "/io/missioncenter/MissionCenter/ui/performance_page/ups_details.ui",
+            );
+
+            let _ = self.infobar_content.set(
+                sidebar_content_builder
+                    .object::<gtk::Grid>("root")
+                    .expect("Could not find `root` object in details pane"),
+            );
+
+            let _ = self.status.set(
+                sidebar_content_builder
+                    .object::<gtk::Label>("status")
+                    .expect("Could not find `status` object in details pane"),
+            );
+            let _ = self.charge.set(
+                sidebar_content_builder
+                    .object::<gtk::Label>("charge")
+                    .expect("Could not find `charge` object in details pane"),
+            );
+            let _ = self.load.set(
+                sidebar_content_builder
+                    .object::<gtk::Label>("load")
+                    .expect("Could not find `load` object in details pane"),
+            );
+            let _ = self.load_box.set(
+                sidebar_content_builder
+                    .object::<gtk::Box>("load_box")
+                    .expect("Could not find `load_box` object in details pane"),
+            );
+            let _ = self.runtime.set(
+                sidebar_content_builder
+                    .object::<gtk::Label>("runtime")
+                    .expect("Could not find `runtime` object in details pane"),
+            );
+            let _ = self.runtime_box.set(
+                sidebar_content_builder
+                    .object::<gtk::Box>("runtime_box")
+                    .expect("Could not find `runtime_box` object in details pane"),
+            );
+            let _ = self.input_voltage.set(
+                sidebar_content_builder
+                    .object::<gtk::Label>("input_voltage")
+                    .expect("Could not find `input_voltage` object in details pane"),
+            );
+            let _ = self.input_voltage_box.set(
+                sidebar_content_builder
+                    .object::<gtk::Box>("input_voltage_box")
+                    .expect("Could not find `input_voltage_box` object in details pane"),
+            );
+        }
+    }
+
+    impl WidgetImpl for PerformancePageUps {}
+
+    impl BoxImpl for PerformancePageUps {}
+}
+
+glib::wrapper! {
+    pub struct PerformancePageUps(ObjectSubclass<imp::PerformancePageUps>)
+        @extends gtk::Box, gtk::Widget,
+        @implements gio::ActionGroup, gio::ActionMap;
+}
+
+impl PageExt for PerformancePageUps {
+    fn infobar_collapsed(&self) {
+        self.imp()
+            .infobar_content
+            .get()
+            .and_then(|ic| Some(ic.set_margin_top(10)));
+    }
+
+    fn infobar_uncollapsed(&self) {
+        self.imp()
+            .infobar_content
+            .get()
+            .and_then(|ic| Some(ic.set_margin_top(65)));
+    }
+}
+
+pub fn status_text(ups: &Ups) -> String {
+    if ups.low_battery() {
+        i18n("On battery, low")
+    } else if ups.on_battery() {
+        i18n("On battery")
+    } else if ups
+        .status
+        .split_ascii_whitespace()
+        .any(|flag| flag == "CHRG")
+    {
+        i18n("Online, charging")
+    } else if ups.status.split_ascii_whitespace().any(|flag| flag == "OL") {
+        i18n("Online")
+    } else if ups.status.is_empty() {
+        i18n("Unknown")
+    } else {
+        ups.status.clone()
+    }
+}
+
+impl PerformancePageUps {
+    pub fn new(name: &str, settings: &gio::Settings) -> Self {
+        let this: Self = glib::Object::builder().property("name", name).build();
+
+        fn update_refresh_rate_sensitive_labels(
+            this: &PerformancePageUps,
+            settings: &gio::Settings,
+        ) {
+            let data_points = settings.int("performance-page-data-points") as u32;
+            let smooth = settings.boolean("performance-smooth-graphs");
+            let sliding = settings.boolean("performance-sliding-graphs");
+            let delay = settings.uint64("app-update-interval-u64");
+            let graph_max_duration =
+                (((delay as f64) * INTERVAL_STEP) * (data_points as f64)).round() as u32;
+
+            let this = this.imp();
+
+            let time_string =
+                &to_short_human_readable_time(graph_max_duration * this.charge_graph.downsample());
+
+            this.charge_graph_max_duration.set_text(time_string);
+            this.charge_graph.set_data_points(data_points);
+            this.charge_graph.set_smooth_graphs(smooth);
+            this.charge_graph.set_do_animation(sliding);
+            this.charge_graph.set_expected_animation_ticks(delay as u32);
+
+            this.load_graph_max_duration.set_text(time_string);
+            this.load_graph.set_data_points(data_points);
+            this.load_graph.set_smooth_graphs(smooth);
+            this.load_graph.set_do_animation(sliding);
+            this.load_graph.set_expected_animation_ticks(delay as u32);
+        }
+        update_refresh_rate_sensitive_labels(&this, settings);
+
+        this.imp().charge_graph.connect_downsample_notify({
+            let this = this.downgrade();
+            let settings = settings.clone();
+            move |_| {
+                if let Some(this) = this.upgrade() {
+                    update_refresh_rate_sensitive_labels(&this, &settings);
+                }
+            }
+        });
+
+        for key in [
+            "performance-page-data-points",
+            "app-update-interval-u64",
+            "performance-smooth-graphs",
+            "performance-sliding-graphs",
+        ] {
+            settings.connect_changed(Some(key), {
+                let this = this.downgrade();
+                move |settings, _| {
+                    if let Some(this) = this.upgrade() {
+                        update_refresh_rate_sensitive_labels(&this, settings);
+                    }
+                }
+            });
+        }
+
+        this
+    }
+
+    pub fn set_static_information(&self, ups: &Ups) -> bool {
+        imp::PerformancePageUps::set_static_information(self, ups)
+    }
+
+    pub fn update_readings(&self, ups: &Ups) -> bool {
+        imp::PerformancePageUps::update_readings(self, ups)
+    }
+
+    pub fn update_animations(&self) -> bool {
+        imp::PerformancePageUps::update_animations(self)
+    }
+}
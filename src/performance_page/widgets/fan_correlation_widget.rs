@@ -0,0 +1,295 @@
+/* performance_page/widgets/fan_correlation_widget.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+
+use glib::{ParamSpec, Properties, Value};
+use gtk::{
+    gdk,
+    gdk::prelude::*,
+    glib::{self, subclass::prelude::*},
+    graphene,
+    gsk::{self, PathBuilder, Stroke},
+    prelude::*,
+    subclass::prelude::*,
+    Snapshot,
+};
+
+use super::GRAPH_RADIUS;
+
+const POINT_RADIUS: f32 = 3.;
+const MIN_TEMP_SPAN_C: f32 = 10.;
+
+mod imp {
+    use super::*;
+
+    #[derive(Properties)]
+    #[properties(wrapper_type = super::FanCorrelationWidget)]
+    pub struct FanCorrelationWidget {
+        #[property(get, set)]
+        base_color: Cell<gdk::RGBA>,
+        #[property(get, set = Self::set_max_samples)]
+        max_samples: Cell<u32>,
+        /// Upper bound of the RPM axis, the highest speed seen is used when 0
+        #[property(get, set)]
+        max_rpm: Cell<f32>,
+
+        /// (temperature in °C, speed in RPM), oldest first
+        pub(crate) samples: RefCell<VecDeque<(f32, f32)>>,
+    }
+
+    impl Default for FanCorrelationWidget {
+        fn default() -> Self {
+            Self {
+                base_color: Cell::new(gdk::RGBA::new(0., 0., 0., 1.)),
+                max_samples: Cell::new(60),
+                max_rpm: Cell::new(0.),
+
+                samples: RefCell::new(VecDeque::new()),
+            }
+        }
+    }
+
+    impl FanCorrelationWidget {
+        fn set_max_samples(&self, max_samples: u32) {
+            self.max_samples.set(max_samples);
+
+            let mut samples = self.samples.borrow_mut();
+            while samples.len() > max_samples as usize {
+                samples.pop_front();
+            }
+        }
+
+        fn render(&self, snapshot: &Snapshot, width: f32, height: f32) {
+            let base_color = self.base_color.get();
+            let stroke_color =
+                gdk::RGBA::new(base_color.red(), base_color.green(), base_color.blue(), 1.);
+            let grid_color =
+                gdk::RGBA::new(base_color.red(), base_color.green(), base_color.blue(), 0.2);
+
+            let radius = graphene::Size::new(GRAPH_RADIUS, GRAPH_RADIUS);
+            let bounds = gsk::RoundedRect::new(
+                graphene::Rect::new(0., 0., width, height),
+                radius,
+                radius,
+                radius,
+                radius,
+            );
+
+            snapshot.push_rounded_clip(&bounds);
+
+            let samples = self.samples.borrow();
+            let (temp_min, temp_max) = temperature_range(&samples);
+            let rpm_max = if self.max_rpm.get() > 0. {
+                self.max_rpm.get()
+            } else {
+                samples
+                    .iter()
+                    .map(|(_, rpm)| *rpm)
+                    .fold(0., f32::max)
+                    .max(1.)
+                    * 1.1
+            };
+
+            let to_widget_coords = |temp: f32, rpm: f32| {
+                (
+                    (temp - temp_min) / (temp_max - temp_min) * width,
+                    height - (rpm / rpm_max).min(1.) * height,
+                )
+            };
+
+            // One vertical line every 5 °C, one horizontal line every 25% of the RPM range
+            let grid = PathBuilder::new();
+            let mut temp = (temp_min / 5.).floor() * 5. + 5.;
+            while temp < temp_max {
+                let (x, _) = to_widget_coords(temp, 0.);
+                grid.move_to(x, 0.);
+                grid.line_to(x, height);
+                temp += 5.;
+            }
+            for fraction in [0.25, 0.5, 0.75] {
+                let (_, y) = to_widget_coords(temp_min, rpm_max * fraction);
+                grid.move_to(0., y);
+                grid.line_to(width, y);
+            }
+            snapshot.append_stroke(&grid.to_path(), &Stroke::new(1.), &grid_color);
+
+            // Older samples fade out so the current behaviour stands out
+            let count = samples.len();
+            for (i, (temp, rpm)) in samples.iter().enumerate() {
+                let (x, y) = to_widget_coords(*temp, *rpm);
+                let age = (i + 1) as f32 / count as f32;
+                let color = gdk::RGBA::new(
+                    base_color.red(),
+                    base_color.green(),
+                    base_color.blue(),
+                    0.15 + 0.85 * age,
+                );
+
+                let point = PathBuilder::new();
+                point.add_circle(
+                    &graphene::Point::new(x, y),
+                    if i + 1 == count {
+                        POINT_RADIUS * 1.5
+                    } else {
+                        POINT_RADIUS
+                    },
+                );
+                snapshot.append_fill(&point.to_path(), gsk::FillRule::Winding, &color);
+            }
+
+            if let Some((slope, intercept)) = linear_fit(&samples) {
+                let (x1, y1) = to_widget_coords(temp_min, slope * temp_min + intercept);
+                let (x2, y2) = to_widget_coords(temp_max, slope * temp_max + intercept);
+
+                let trend = PathBuilder::new();
+                trend.move_to(x1, y1);
+                trend.line_to(x2, y2);
+
+                let stroke = Stroke::new(1.);
+                stroke.set_dash(&[4., 4.]);
+                snapshot.append_stroke(&trend.to_path(), &stroke, &stroke_color);
+            }
+
+            snapshot.pop();
+
+            snapshot.append_border(&bounds, &[1.; 4], &[stroke_color; 4]);
+        }
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for FanCorrelationWidget {
+        const NAME: &'static str = "FanCorrelationWidget";
+        type Type = super::FanCorrelationWidget;
+        type ParentType = gtk::Widget;
+    }
+
+    impl ObjectImpl for FanCorrelationWidget {
+        fn properties() -> &'static [ParamSpec] {
+            Self::derived_properties()
+        }
+
+        fn set_property(&self, id: usize, value: &Value, pspec: &ParamSpec) {
+            self.derived_set_property(id, value, pspec);
+        }
+
+        fn property(&self, id: usize, pspec: &ParamSpec) -> Value {
+            self.derived_property(id, pspec)
+        }
+    }
+
+    impl WidgetImpl for FanCorrelationWidget {
+        fn snapshot(&self, snapshot: &Snapshot) {
+            let this = self.obj();
+            self.render(snapshot, this.width() as f32, this.height() as f32);
+        }
+    }
+}
+
+fn temperature_range(samples: &VecDeque<(f32, f32)>) -> (f32, f32) {
+    let (min, max) = samples
+        .iter()
+        .fold((f32::MAX, f32::MIN), |(min, max), (temp, _)| {
+            (min.min(*temp), max.max(*temp))
+        });
+    if min > max {
+        return (20., 20. + MIN_TEMP_SPAN_C * 2.);
+    }
+
+    // Pad the range so a fan sitting at a steady temperature doesn't turn into a vertical line
+    let center = (min + max) / 2.;
+    let span = (max - min).max(MIN_TEMP_SPAN_C) + 4.;
+    (center - span / 2., center + span / 2.)
+}
+
+/// Least squares fit of RPM over temperature, as `(slope, intercept)`
+fn linear_fit(samples: &VecDeque<(f32, f32)>) -> Option<(f32, f32)> {
+    let (mean_temp, mean_rpm, variance_temp, covariance, _) = statistics(samples)?;
+    if variance_temp <= f32::EPSILON {
+        return None;
+    }
+
+    let slope = covariance / variance_temp;
+    Some((slope, mean_rpm - slope * mean_temp))
+}
+
+/// Means, variances and covariance of temperature and RPM
+fn statistics(samples: &VecDeque<(f32, f32)>) -> Option<(f32, f32, f32, f32, f32)> {
+    if samples.len() < 3 {
+        return None;
+    }
+
+    let n = samples.len() as f32;
+    let mean_temp = samples.iter().map(|(t, _)| *t).sum::<f32>() / n;
+    let mean_rpm = samples.iter().map(|(_, r)| *r).sum::<f32>() / n;
+
+    let (mut variance_temp, mut variance_rpm, mut covariance) = (0., 0., 0.);
+    for (temp, rpm) in samples {
+        let (dt, dr) = (temp - mean_temp, rpm - mean_rpm);
+        variance_temp += dt * dt;
+        variance_rpm += dr * dr;
+        covariance += dt * dr;
+    }
+
+    Some((
+        mean_temp,
+        mean_rpm,
+        variance_temp / n,
+        covariance / n,
+        variance_rpm / n,
+    ))
+}
+
+glib::wrapper! {
+    pub struct FanCorrelationWidget(ObjectSubclass<imp::FanCorrelationWidget>)
+        @extends gtk::Widget,
+        @implements gtk::Buildable;
+}
+
+impl FanCorrelationWidget {
+    pub fn new() -> Self {
+        glib::Object::new()
+    }
+
+    pub fn add_sample(&self, temp_c: f32, rpm: f32) {
+        {
+            let mut samples = self.imp().samples.borrow_mut();
+            samples.push_back((temp_c, rpm));
+            while samples.len() > self.max_samples() as usize {
+                samples.pop_front();
+            }
+        }
+
+        self.queue_draw();
+    }
+
+    /// Pearson correlation between temperature and fan speed; `None` until there is enough
+    /// variation in both to tell
+    pub fn correlation(&self) -> Option<f32> {
+        let (_, _, variance_temp, covariance, variance_rpm) =
+            statistics(&self.imp().samples.borrow())?;
+        if variance_temp <= f32::EPSILON || variance_rpm <= f32::EPSILON {
+            return None;
+        }
+
+        Some(covariance / (variance_temp.sqrt() * variance_rpm.sqrt()))
+    }
+}
@@ -0,0 +1,340 @@
+/* performance_page/widgets/fan_curve_widget.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::cell::{Cell, RefCell};
+
+use glib::{ParamSpec, Properties, Value};
+use gtk::{
+    gdk,
+    gdk::prelude::*,
+    glib::{
+        self,
+        subclass::{prelude::*, Signal},
+    },
+    graphene,
+    gsk::{self, PathBuilder, Stroke},
+    prelude::*,
+    subclass::prelude::*,
+    Snapshot,
+};
+
+use super::GRAPH_RADIUS;
+use crate::magpie_client::{FanCurve, MIN_MANUAL_PWM_PERCENT};
+
+const TEMP_MIN_C: f32 = 20.;
+const TEMP_MAX_C: f32 = 100.;
+const POINT_RADIUS: f32 = 4.;
+const GRAB_DISTANCE: f32 = 12.;
+
+mod imp {
+    use super::*;
+
+    #[derive(Properties)]
+    #[properties(wrapper_type = super::FanCurveWidget)]
+    pub struct FanCurveWidget {
+        #[property(get, set)]
+        base_color: Cell<gdk::RGBA>,
+
+        pub(crate) points: RefCell<Vec<(f32, f32)>>,
+        pub(crate) current_temperature: Cell<Option<f32>>,
+
+        dragged_point: Cell<Option<usize>>,
+        drag_start: Cell<(f32, f32)>,
+    }
+
+    impl Default for FanCurveWidget {
+        fn default() -> Self {
+            Self {
+                base_color: Cell::new(gdk::RGBA::new(0., 0., 0., 1.)),
+
+                points: RefCell::new(FanCurve::default().points().to_vec()),
+                current_temperature: Cell::new(None),
+
+                dragged_point: Cell::new(None),
+                drag_start: Cell::new((0., 0.)),
+            }
+        }
+    }
+
+    impl FanCurveWidget {
+        fn to_widget_coords(&self, temp: f32, pwm: f32, width: f32, height: f32) -> (f32, f32) {
+            (
+                (temp - TEMP_MIN_C) / (TEMP_MAX_C - TEMP_MIN_C) * width,
+                height - pwm / 100. * height,
+            )
+        }
+
+        fn to_curve_coords(&self, x: f32, y: f32, width: f32, height: f32) -> (f32, f32) {
+            (
+                TEMP_MIN_C + x / width.max(1.) * (TEMP_MAX_C - TEMP_MIN_C),
+                (height - y) / height.max(1.) * 100.,
+            )
+        }
+
+        fn configure_gestures(&self) {
+            let this = self.obj();
+
+            let drag = gtk::GestureDrag::new();
+            drag.connect_drag_begin({
+                let this = this.downgrade();
+                move |_, x, y| {
+                    let Some(this) = this.upgrade() else {
+                        return;
+                    };
+                    let imp = this.imp();
+
+                    let (x, y) = (x as f32, y as f32);
+                    let (width, height) = (this.width() as f32, this.height() as f32);
+
+                    let nearest = imp
+                        .points
+                        .borrow()
+                        .iter()
+                        .map(|(temp, pwm)| imp.to_widget_coords(*temp, *pwm, width, height))
+                        .map(|(px, py)| ((px - x).powi(2) + (py - y).powi(2)).sqrt())
+                        .enumerate()
+                        .filter(|(_, distance)| *distance <= GRAB_DISTANCE)
+                        .min_by(|(_, d1), (_, d2)| d1.total_cmp(d2))
+                        .map(|(i, _)| i);
+
+                    imp.dragged_point.set(nearest);
+                    imp.drag_start.set((x, y));
+                }
+            });
+            drag.connect_drag_update({
+                let this = this.downgrade();
+                move |_, offset_x, offset_y| {
+                    let Some(this) = this.upgrade() else {
+                        return;
+                    };
+                    let imp = this.imp();
+
+                    let Some(index) = imp.dragged_point.get() else {
+                        return;
+                    };
+
+                    let (start_x, start_y) = imp.drag_start.get();
+                    let (width, height) = (this.width() as f32, this.height() as f32);
+                    let (temp, pwm) = imp.to_curve_coords(
+                        start_x + offset_x as f32,
+                        start_y + offset_y as f32,
+                        width,
+                        height,
+                    );
+
+                    {
+                        let mut points = imp.points.borrow_mut();
+
+                        // Points can't be moved past their neighbours, the curve has to stay a
+                        // function of temperature
+                        let min_temp = if index > 0 {
+                            points[index - 1].0 + 1.
+                        } else {
+                            TEMP_MIN_C
+                        };
+                        let max_temp = if index + 1 < points.len() {
+                            points[index + 1].0 - 1.
+                        } else {
+                            TEMP_MAX_C
+                        };
+
+                        points[index] = (
+                            temp.round().clamp(min_temp, max_temp.max(min_temp)),
+                            pwm.round().clamp(MIN_MANUAL_PWM_PERCENT, 100.),
+                        );
+                    }
+
+                    this.queue_draw();
+                }
+            });
+            drag.connect_drag_end({
+                let this = this.downgrade();
+                move |_, _, _| {
+                    let Some(this) = this.upgrade() else {
+                        return;
+                    };
+
+                    if this.imp().dragged_point.take().is_some() {
+                        this.emit_by_name::<()>("changed", &[]);
+                    }
+                }
+            });
+            this.add_controller(drag);
+        }
+
+        fn render(&self, snapshot: &Snapshot, width: f32, height: f32) {
+            let base_color = self.base_color.get();
+            let stroke_color =
+                gdk::RGBA::new(base_color.red(), base_color.green(), base_color.blue(), 1.);
+            let grid_color =
+                gdk::RGBA::new(base_color.red(), base_color.green(), base_color.blue(), 0.2);
+            let fill_color = gdk::RGBA::new(
+                base_color.red(),
+                base_color.green(),
+                base_color.blue(),
+                50. / 256.,
+            );
+
+            let radius = graphene::Size::new(GRAPH_RADIUS, GRAPH_RADIUS);
+            let bounds = gsk::RoundedRect::new(
+                graphene::Rect::new(0., 0., width, height),
+                radius,
+                radius,
+                radius,
+                radius,
+            );
+
+            snapshot.push_rounded_clip(&bounds);
+
+            // One vertical line every 10 °C, one horizontal line every 25%
+            let grid = PathBuilder::new();
+            let mut temp = TEMP_MIN_C + 10.;
+            while temp < TEMP_MAX_C {
+                let (x, _) = self.to_widget_coords(temp, 0., width, height);
+                grid.move_to(x, 0.);
+                grid.line_to(x, height);
+                temp += 10.;
+            }
+            for pwm in [25., 50., 75.] {
+                let (_, y) = self.to_widget_coords(TEMP_MIN_C, pwm, width, height);
+                grid.move_to(0., y);
+                grid.line_to(width, y);
+            }
+            snapshot.append_stroke(&grid.to_path(), &Stroke::new(1.), &grid_color);
+
+            let points = self
+                .points
+                .borrow()
+                .iter()
+                .map(|(temp, pwm)| self.to_widget_coords(*temp, *pwm, width, height))
+                .collect::<Vec<_>>();
+
+            if let (Some(first), Some(last)) = (points.first(), points.last()) {
+                // The curve is flat before the first and after the last point
+                let line = PathBuilder::new();
+                line.move_to(0., first.1);
+                for (x, y) in points.iter() {
+                    line.line_to(*x, *y);
+                }
+                line.line_to(width, last.1);
+
+                let fill = PathBuilder::new();
+                fill.move_to(0., height);
+                fill.line_to(0., first.1);
+                for (x, y) in points.iter() {
+                    fill.line_to(*x, *y);
+                }
+                fill.line_to(width, last.1);
+                fill.line_to(width, height);
+                fill.close();
+
+                snapshot.append_fill(&fill.to_path(), gsk::FillRule::Winding, &fill_color);
+                snapshot.append_stroke(&line.to_path(), &Stroke::new(2.), &stroke_color);
+            }
+
+            let handles = PathBuilder::new();
+            for (x, y) in points.iter() {
+                handles.add_circle(&graphene::Point::new(*x, *y), POINT_RADIUS);
+            }
+            snapshot.append_fill(&handles.to_path(), gsk::FillRule::Winding, &stroke_color);
+
+            if let Some(temp) = self.current_temperature.get() {
+                let (x, _) = self.to_widget_coords(temp, 0., width, height);
+                let marker = PathBuilder::new();
+                marker.move_to(x, 0.);
+                marker.line_to(x, height);
+
+                let stroke = Stroke::new(1.);
+                stroke.set_dash(&[4., 4.]);
+                snapshot.append_stroke(&marker.to_path(), &stroke, &stroke_color);
+            }
+
+            snapshot.pop();
+
+            snapshot.append_border(&bounds, &[1.; 4], &[stroke_color; 4]);
+        }
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for FanCurveWidget {
+        const NAME: &'static str = "FanCurveWidget";
+        type Type = super::FanCurveWidget;
+        type ParentType = gtk::Widget;
+    }
+
+    impl ObjectImpl for FanCurveWidget {
+        fn properties() -> &'static [ParamSpec] {
+            Self::derived_properties()
+        }
+
+        fn signals() -> &'static [Signal] {
+            use std::sync::OnceLock;
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| vec![Signal::builder("changed").build()])
+        }
+
+        fn set_property(&self, id: usize, value: &Value, pspec: &ParamSpec) {
+            self.derived_set_property(id, value, pspec);
+        }
+
+        fn property(&self, id: usize, pspec: &ParamSpec) -> Value {
+            self.derived_property(id, pspec)
+        }
+
+        fn constructed(&self) {
+            self.parent_constructed();
+
+            self.configure_gestures();
+        }
+    }
+
+    impl WidgetImpl for FanCurveWidget {
+        fn snapshot(&self, snapshot: &Snapshot) {
+            let this = self.obj();
+            self.render(snapshot, this.width() as f32, this.height() as f32);
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct FanCurveWidget(ObjectSubclass<imp::FanCurveWidget>)
+        @extends gtk::Widget,
+        @implements gtk::Buildable;
+}
+
+impl FanCurveWidget {
+    pub fn new() -> Self {
+        glib::Object::new()
+    }
+
+    pub fn curve(&self) -> FanCurve {
+        FanCurve::new(self.imp().points.borrow().clone())
+    }
+
+    pub fn set_curve(&self, curve: &FanCurve) {
+        self.imp().points.replace(curve.points().to_vec());
+        self.queue_draw();
+    }
+
+    pub fn set_current_temperature(&self, temp_c: Option<f32>) {
+        self.imp().current_temperature.set(temp_c);
+        self.queue_draw();
+    }
+}
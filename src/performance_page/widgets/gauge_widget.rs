@@ -0,0 +1,281 @@
+/* performance_page/widgets/gauge_widget.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::cell::{Cell, RefCell};
+use std::f32::consts::PI;
+
+use glib::{ParamSpec, Properties, Value};
+use gtk::{
+    gdk,
+    gdk::prelude::*,
+    glib::{self, subclass::prelude::*},
+    graphene,
+    gsk::{self, LineCap, PathBuilder, Stroke},
+    pango,
+    prelude::*,
+    subclass::prelude::*,
+    Snapshot,
+};
+
+/// The dial opens at the bottom, going clockwise from the lower left to the lower right
+const START_ANGLE: f32 = PI * 0.75;
+const SWEEP_ANGLE: f32 = PI * 1.5;
+/// Straight segments an arc across the whole dial is drawn with
+const ARC_SEGMENTS: f32 = 64.;
+
+/// The warning and error colors of the Adwaita palette
+const WARNING_COLOR: gdk::RGBA = gdk::RGBA::new(0.898, 0.647, 0.039, 1.);
+const ERROR_COLOR: gdk::RGBA = gdk::RGBA::new(0.878, 0.106, 0.141, 1.);
+
+mod imp {
+    use super::*;
+
+    #[derive(Properties)]
+    #[properties(wrapper_type = super::GaugeWidget)]
+    pub struct GaugeWidget {
+        #[property(get, set)]
+        base_color: Cell<gdk::RGBA>,
+        #[property(get, set)]
+        value: Cell<f32>,
+        #[property(get, set)]
+        min_value: Cell<f32>,
+        #[property(get, set)]
+        max_value: Cell<f32>,
+        /// Where the warning zone starts, there is none when NaN
+        #[property(get, set)]
+        warning_value: Cell<f32>,
+        /// Where the critical zone starts, there is none when NaN
+        #[property(get, set)]
+        critical_value: Cell<f32>,
+        /// The reading, as shown in the middle of the dial
+        #[property(get, set)]
+        text: RefCell<String>,
+        /// What is measured, shown under the reading
+        #[property(get, set)]
+        caption: RefCell<String>,
+    }
+
+    impl Default for GaugeWidget {
+        fn default() -> Self {
+            Self {
+                base_color: Cell::new(gdk::RGBA::new(0., 0., 0., 1.)),
+                value: Cell::new(0.),
+                min_value: Cell::new(0.),
+                max_value: Cell::new(100.),
+                warning_value: Cell::new(f32::NAN),
+                critical_value: Cell::new(f32::NAN),
+                text: RefCell::new(String::new()),
+                caption: RefCell::new(String::new()),
+            }
+        }
+    }
+
+    impl GaugeWidget {
+        /// How far along the dial `value` is, from 0 to 1
+        fn fraction(&self, value: f32) -> f32 {
+            let (min, max) = (self.min_value.get(), self.max_value.get());
+            if max <= min {
+                return 0.;
+            }
+
+            ((value - min) / (max - min)).clamp(0., 1.)
+        }
+
+        fn arc(&self, center: (f32, f32), radius: f32, from: f32, to: f32) -> gsk::Path {
+            let (from, to) = (
+                START_ANGLE + SWEEP_ANGLE * from,
+                START_ANGLE + SWEEP_ANGLE * to,
+            );
+            let segments = ((to - from) / SWEEP_ANGLE * ARC_SEGMENTS).ceil().max(1.) as u32;
+
+            let path_builder = PathBuilder::new();
+            for i in 0..=segments {
+                let angle = from + (to - from) * i as f32 / segments as f32;
+                let (x, y) = (
+                    center.0 + radius * angle.cos(),
+                    center.1 + radius * angle.sin(),
+                );
+                if i == 0 {
+                    path_builder.move_to(x, y);
+                } else {
+                    path_builder.line_to(x, y);
+                }
+            }
+
+            path_builder.to_path()
+        }
+
+        fn text_layout(&self, text: &str, scale: f64) -> pango::Layout {
+            let layout = self.obj().create_pango_layout(Some(text));
+            let attributes = pango::AttrList::new();
+            attributes.insert(pango::AttrFloat::new_scale(scale));
+            layout.set_attributes(Some(&attributes));
+
+            layout
+        }
+
+        fn render(&self, snapshot: &Snapshot, width: f32, height: f32) {
+            let base_color = self.base_color.get();
+            let with_alpha = |color: gdk::RGBA, alpha| {
+                gdk::RGBA::new(color.red(), color.green(), color.blue(), alpha)
+            };
+
+            let thickness = (width.min(height) / 12.).clamp(4., 12.);
+            let radius = width.min(height) / 2. - thickness;
+            if radius <= 0. {
+                return;
+            }
+            // The dial is open at the bottom, so it sits a bit lower to look centered
+            let center = (width / 2., height / 2. + radius * 0.15);
+
+            let stroke = Stroke::new(thickness);
+            stroke.set_line_cap(LineCap::Butt);
+
+            snapshot.append_stroke(
+                &self.arc(center, radius, 0., 1.),
+                &stroke,
+                &with_alpha(base_color, 0.2),
+            );
+
+            let value = self.value.get();
+            let (warning, critical) = (self.warning_value.get(), self.critical_value.get());
+            let mut zones = vec![];
+            if !warning.is_nan() {
+                let end = if critical.is_nan() {
+                    1.
+                } else {
+                    self.fraction(critical)
+                };
+                zones.push((self.fraction(warning), end, WARNING_COLOR));
+            }
+            if !critical.is_nan() {
+                zones.push((self.fraction(critical), 1., ERROR_COLOR));
+            }
+            for (from, to, color) in zones {
+                if to > from {
+                    snapshot.append_stroke(
+                        &self.arc(center, radius, from, to),
+                        &stroke,
+                        &with_alpha(color, 0.35),
+                    );
+                }
+            }
+
+            let value_color = if !critical.is_nan() && value >= critical {
+                ERROR_COLOR
+            } else if !warning.is_nan() && value >= warning {
+                WARNING_COLOR
+            } else {
+                with_alpha(base_color, 1.)
+            };
+            let fraction = self.fraction(value);
+            if fraction > 0. {
+                let stroke = Stroke::new(thickness);
+                stroke.set_line_cap(LineCap::Round);
+                snapshot.append_stroke(
+                    &self.arc(center, radius, 0., fraction),
+                    &stroke,
+                    &value_color,
+                );
+            }
+
+            let text_color = self.obj().color();
+            let text = self.text_layout(&self.text.borrow(), pango::SCALE_X_LARGE);
+            let (text_width, text_height) = text.pixel_size();
+            snapshot.save();
+            snapshot.translate(&graphene::Point::new(
+                center.0 - text_width as f32 / 2.,
+                center.1 - text_height as f32 / 2.,
+            ));
+            snapshot.append_layout(&text, &text_color);
+            snapshot.restore();
+
+            let caption = self.text_layout(&self.caption.borrow(), pango::SCALE_SMALL);
+            let (caption_width, _) = caption.pixel_size();
+            snapshot.save();
+            snapshot.translate(&graphene::Point::new(
+                center.0 - caption_width as f32 / 2.,
+                center.1 + text_height as f32 / 2.,
+            ));
+            snapshot.append_layout(&caption, &with_alpha(text_color, 0.7));
+            snapshot.restore();
+        }
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for GaugeWidget {
+        const NAME: &'static str = "GaugeWidget";
+        type Type = super::GaugeWidget;
+        type ParentType = gtk::Widget;
+    }
+
+    impl ObjectImpl for GaugeWidget {
+        fn properties() -> &'static [ParamSpec] {
+            Self::derived_properties()
+        }
+
+        fn set_property(&self, id: usize, value: &Value, pspec: &ParamSpec) {
+            self.derived_set_property(id, value, pspec);
+        }
+
+        fn property(&self, id: usize, pspec: &ParamSpec) -> Value {
+            self.derived_property(id, pspec)
+        }
+
+        fn constructed(&self) {
+            self.parent_constructed();
+
+            // Everything the gauge has is drawn
+            self.obj()
+                .connect_notify_local(None, |this, _| this.queue_draw());
+        }
+    }
+
+    impl WidgetImpl for GaugeWidget {
+        fn snapshot(&self, snapshot: &Snapshot) {
+            let this = self.obj();
+            self.render(snapshot, this.width() as f32, this.height() as f32);
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct GaugeWidget(ObjectSubclass<imp::GaugeWidget>)
+        @extends gtk::Widget,
+        @implements gtk::Buildable;
+}
+
+impl GaugeWidget {
+    pub fn new() -> Self {
+        glib::Object::new()
+    }
+
+    /// Show `value` as `text`, in one go
+    pub fn set_reading(&self, value: f32, text: &str) {
+        self.set_value(value);
+        self.set_text(text);
+    }
+
+    /// Mark the range from `warning` up to `critical`, and from `critical` up; either can be NaN
+    pub fn set_zones(&self, warning: f32, critical: f32) {
+        self.set_warning_value(warning);
+        self.set_critical_value(critical);
+    }
+}
@@ -18,8 +18,9 @@
  * SPDX-License-Identifier: GPL-3.0-or-later
  */
 
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
 use std::cmp::Ordering;
+use std::fmt::Write;
 
 use glib::{ParamSpec, Properties, Value};
 use gtk::{
@@ -37,8 +38,10 @@ use gtk::{
 };
 
 pub use imp::DataSetDescriptor;
+use imp::Generation;
 
 use super::GRAPH_RADIUS;
+use crate::{application::INTERVAL_STEP, i18n::*, settings, to_short_human_readable_time};
 
 /// Values are truncated to the minimum and maximum values
 const NO_SCALING: i32 = 0;
@@ -49,17 +52,332 @@ const AUTO_POW2_SCALING: i32 = 2;
 /// The graph min and max values are hardcoded to the range [0, 1], and all values are normalized to this range
 const NORMALIZED_SCALING: i32 = 3;
 
+/// How many points the rolling average drawn with the statistics spans
+const ROLLING_AVERAGE_POINTS: usize = 10;
+
+/// The lowest, highest and average value of a data set over the points on screen
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Statistics {
+    pub min: f32,
+    pub max: f32,
+    pub average: f32,
+}
+
+impl Statistics {
+    fn of(values: &[f32]) -> Option<Self> {
+        if values.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            min: values.iter().copied().fold(f32::INFINITY, f32::min),
+            max: values.iter().copied().fold(f32::NEG_INFINITY, f32::max),
+            average: values.iter().sum::<f32>() / values.len() as f32,
+        })
+    }
+}
+
+/// A visible data set as drawn on a stacked graph, between the total of the data sets below it
+/// and that total plus its own values; both are relative to the minimum of the value range
+struct StackedBand {
+    index: usize,
+    lower: Vec<f32>,
+    upper: Vec<f32>,
+}
+
+/// The bands of the visible data sets, bottom to top; shorter data sets are aligned to the right
+fn stacked_bands(data_sets: &[DataSetDescriptor], range_min: f32) -> Vec<StackedBand> {
+    let len = data_sets
+        .iter()
+        .filter(|values| values.visible)
+        .map(|values| values.data_set.len())
+        .max()
+        .unwrap_or(0);
+
+    let mut lower = vec![0.; len];
+    let mut result = vec![];
+    for (index, values) in data_sets.iter().enumerate() {
+        if !values.visible {
+            continue;
+        }
+
+        let offset = len - values.data_set.len();
+        let mut upper = lower.clone();
+        for (i, value) in values.data_set.iter().enumerate() {
+            upper[offset + i] += (value - range_min).max(0.);
+        }
+
+        result.push(StackedBand {
+            index,
+            lower: std::mem::replace(&mut lower, upper.clone()),
+            upper,
+        });
+    }
+
+    result
+}
+
+/// Continue `path_builder` through `points`, with a straight line to the first one, or a move
+/// if `move_to` is set
+fn trace(path_builder: &PathBuilder, points: &[(f32, f32)], smooth: bool, move_to: bool) {
+    let Some(&(x, y)) = points.first() else {
+        return;
+    };
+    if move_to {
+        path_builder.move_to(x, y);
+    } else {
+        path_builder.line_to(x, y);
+    }
+
+    for pair in points.windows(2) {
+        let ((last_x, last_y), (x, y)) = (pair[0], pair[1]);
+        if smooth {
+            let middle_x = (last_x + x) / 2.;
+            path_builder.cubic_to(middle_x, last_y, middle_x, y, x, y);
+        } else {
+            path_builder.line_to(x, y);
+        }
+    }
+}
+
+/// Pick the points that keep the shape of a line with more of them than there is room to draw,
+/// using largest-triangle-three-buckets: the first and last points are kept, the rest are split
+/// into `threshold - 2` buckets and from each the point forming the largest triangle with the
+/// one kept before it and the average of the next bucket is kept
+///
+/// Returns the indices of the points kept, in order
+fn largest_triangle_three_buckets(points: &[(f32, f32)], threshold: usize) -> Vec<usize> {
+    let len = points.len();
+    if threshold < 3 || len <= threshold {
+        return (0..len).collect();
+    }
+
+    let bucket_count = threshold - 2;
+    let bucket_start = |bucket: usize| (bucket * (len - 2) / bucket_count + 1).min(len - 1);
+
+    let mut result = Vec::with_capacity(threshold);
+    result.push(0);
+
+    let mut previous = 0;
+    for bucket in 0..bucket_count {
+        let (start, end) = (bucket_start(bucket), bucket_start(bucket + 1));
+        // The last bucket is followed by the last point alone
+        let next = &points[end..bucket_start(bucket + 2).max(end + 1).min(len)];
+        let (next_x, next_y) = next
+            .iter()
+            .fold((0., 0.), |(x, y), point| (x + point.0, y + point.1));
+        let (next_x, next_y) = (next_x / next.len() as f32, next_y / next.len() as f32);
+
+        let (previous_x, previous_y) = points[previous];
+        let mut largest_area = -1.;
+        for (i, (x, y)) in points.iter().enumerate().take(end).skip(start) {
+            // Twice the area, which compares the same
+            let area = ((previous_x - next_x) * (y - previous_y)
+                - (previous_x - x) * (next_y - previous_y))
+                .abs();
+            if area > largest_area {
+                largest_area = area;
+                previous = i;
+            }
+        }
+        result.push(previous);
+    }
+
+    result.push(len - 1);
+
+    result
+}
+
+/// Something drawn that is kept until what it was drawn from, the key, changes
+struct Cached<K, T> {
+    key: Option<K>,
+    value: Option<T>,
+}
+
+impl<K, T> Default for Cached<K, T> {
+    fn default() -> Self {
+        Self {
+            key: None,
+            value: None,
+        }
+    }
+}
+
+impl<K: PartialEq, T> Cached<K, T> {
+    fn get_or_draw(&mut self, key: K, draw: impl FnOnce() -> T) -> &T {
+        if self.key.as_ref() != Some(&key) || self.value.is_none() {
+            self.value = Some(draw());
+            self.key = Some(key);
+        }
+
+        self.value.as_ref().unwrap()
+    }
+}
+
+/// Everything the grid lines depend on, besides how far they scrolled
+#[derive(PartialEq)]
+struct GridKey {
+    size: (f32, f32),
+    scale_factor: f64,
+    line_count: (u32, u32),
+    color: gdk::RGBA,
+}
+
+/// Everything the plotted data sets depend on, besides their values and how far they slid
+#[derive(PartialEq)]
+struct PlotKey {
+    size: (f32, f32),
+    scale_factor: f64,
+    value_range: (f32, f32),
+    scaling: i32,
+    only_scale_up: bool,
+    smooth: bool,
+    stacked: bool,
+    animate: bool,
+    /// Whether each data set is visible, filled and dashed, and how many points it has
+    data_sets: Vec<(bool, bool, bool, usize)>,
+    colors: Vec<(gdk::RGBA, gdk::RGBA)>,
+}
+
+/// How many points are added to a plotted line before all of it is drawn again
+const MAX_TAIL_POINTS: usize = 16;
+
+/// The data sets as drawn, for as long as the `PlotKey` they were drawn with holds
+#[derive(Default)]
+struct PlotCache {
+    key: Option<PlotKey>,
+    /// Stacked data sets depend on each other, they are drawn again together
+    stacked: Cached<Vec<Generation>, Option<gsk::RenderNode>>,
+    data_sets: Vec<DataSetPlot>,
+}
+
+/// A data set drawn in two parts, so that a new point only means drawing the newest ones again:
+/// the points that were already there are kept, and slide left as new ones are added
+#[derive(Default)]
+struct DataSetPlot {
+    /// The line up to the point before the newest, as the values were at `generation`
+    prefix: Option<gsk::RenderNode>,
+    generation: Option<Generation>,
+    /// The prefix ends where the tail starts; otherwise it is the whole line and any change
+    /// means drawing it again
+    extendable: bool,
+    /// The line from the end of the prefix to the newest point
+    tail: Cached<Generation, Option<gsk::RenderNode>>,
+}
+
+fn record(draw: impl FnOnce(&Snapshot)) -> Option<gsk::RenderNode> {
+    let snapshot = Snapshot::new();
+    draw(&snapshot);
+    snapshot.to_node()
+}
+
+fn append_translated(snapshot: &Snapshot, node: Option<&gsk::RenderNode>, x: f32) {
+    if let Some(node) = node {
+        snapshot.save();
+        snapshot.translate(&graphene::Point::new(x, 0.));
+        snapshot.append_node(node);
+        snapshot.restore();
+    }
+}
+
+fn svg_color(color: &gdk::RGBA) -> String {
+    format!(
+        "#{:02x}{:02x}{:02x}",
+        (color.red() * 255.).round() as u8,
+        (color.green() * 255.).round() as u8,
+        (color.blue() * 255.).round() as u8,
+    )
+}
+
 mod imp {
     use super::*;
 
+    /// Counts the changes to the values of a data set, so that what was drawn from them is kept
+    /// until they change, without keeping a copy to compare with
+    #[derive(Debug, Default, Copy, Clone, PartialEq)]
+    pub struct Generation {
+        /// Changes that could have moved any point
+        pub(super) edits: u64,
+        /// Points added at the end, the others slid left
+        pub(super) shifts: u64,
+        /// Changes to the newest point only
+        pub(super) updates: u64,
+    }
+
     #[derive(Clone)]
     pub struct DataSetDescriptor {
         pub dashed: bool,
         pub fill: bool,
         pub visible: bool,
+        /// Drawn in the base color of the graph when not set
+        pub color: Option<gdk::RGBA>,
+        /// Shown in the legend and the tooltip
+        pub label: Option<String>,
 
         pub data_set: Vec<f32>,
         pub max_all_time: f32,
+
+        pub(super) resolutions: Vec<Resolution>,
+        pub(super) generation: Generation,
+    }
+
+    /// The data set averaged over `factor` consecutive samples. One is kept for every time range
+    /// the graph can be switched to, so that switching shows the full history right away.
+    #[derive(Clone)]
+    pub(super) struct Resolution {
+        pub(super) factor: u32,
+        pub(super) values: Vec<f32>,
+
+        sum: f32,
+        count: u32,
+    }
+
+    impl Resolution {
+        pub(super) fn new(factor: u32, data_points: usize) -> Self {
+            Self {
+                factor: factor.max(1),
+                values: vec![0.; data_points],
+
+                sum: 0.,
+                count: 0,
+            }
+        }
+
+        /// Returns `true` if the sample started a new data point, otherwise it was folded into
+        /// the last one
+        pub(super) fn push(&mut self, value: f32) -> bool {
+            let new_point = self.count == 0;
+            if new_point {
+                self.values.push(value);
+                self.values.remove(0);
+                self.sum = 0.;
+            }
+
+            self.sum += value;
+            self.count += 1;
+            if let Some(last) = self.values.last_mut() {
+                *last = self.sum / self.count as f32;
+            }
+
+            if self.count >= self.factor {
+                self.count = 0;
+            }
+
+            new_point
+        }
+
+        pub(super) fn reset(&mut self, values: &[f32]) {
+            self.values = values.to_vec();
+            self.sum = 0.;
+            self.count = 0;
+        }
+
+        fn resize(&mut self, count: u32) {
+            // we need to truncate from the correct side
+            self.values.reverse();
+            self.values.resize(count as _, 0.);
+            self.values.reverse();
+        }
     }
 
     #[derive(Properties)]
@@ -91,8 +409,25 @@ mod imp {
         horizontal_line_count: Cell<u32>,
         #[property(get, set = Self::set_vertical_line_count)]
         vertical_line_count: Cell<u32>,
+        /// How many samples are averaged into each point that is drawn
+        #[property(get, set = Self::set_downsample)]
+        downsample: Cell<u32>,
+        /// Each data set is drawn on top of the ones before it, instead of all of them from zero
+        #[property(get, set = Self::set_stacked)]
+        stacked: Cell<bool>,
+        /// Label the horizontal grid lines with their value and the bottom edge with how long ago
+        /// the points above it were read
+        #[property(get, set = Self::set_axis_labels_visible)]
+        axis_labels_visible: Cell<bool>,
+        /// Mark the minimum, maximum and rolling average of the data set the statistics are about
+        #[property(get, set = Self::set_show_statistics)]
+        show_statistics: Cell<bool>,
+        /// Index of the data set the statistics are about, usually the main one
+        #[property(get, set)]
+        statistics_data_set: Cell<u32>,
 
         pub data_sets: Cell<Vec<DataSetDescriptor>>,
+        resolution_factors: Cell<Vec<u32>>,
 
         scroll_offset: Cell<u32>,
         prev_size: Cell<(i32, i32)>,
@@ -103,6 +438,19 @@ mod imp {
         expected_animation_ticks: Cell<u32>,
         #[property(get, set)]
         do_animation: Cell<bool>,
+
+        hover_x: Cell<Option<f32>>,
+        /// Events marked on the graph, as the monotonic time they happened at and their label
+        pub(super) annotations: RefCell<Vec<(i64, String)>>,
+        /// Turns a value of the data set at the given index into the text shown when hovering
+        pub(super) value_formatter: RefCell<Option<Box<dyn Fn(usize, f32) -> String>>>,
+        /// Turns a value into the short text the grid lines are labelled with
+        pub(super) axis_formatter: RefCell<Option<Box<dyn Fn(f32) -> String>>>,
+
+        /// The horizontal and vertical grid lines, the latter before scrolling
+        grid_cache: RefCell<Cached<GridKey, (Option<gsk::RenderNode>, Option<gsk::RenderNode>)>>,
+        /// The data sets where they rest once sliding is over
+        plot_cache: RefCell<PlotCache>,
     }
 
     impl Default for GraphWidget {
@@ -126,21 +474,40 @@ mod imp {
                 base_color: Cell::new(gdk::RGBA::new(0., 0., 0., 1.)),
                 horizontal_line_count: Cell::new(9),
                 vertical_line_count: Cell::new(6),
+                downsample: Cell::new(1),
+                stacked: Cell::new(false),
+                axis_labels_visible: Cell::new(false),
+                show_statistics: Cell::new(false),
+                statistics_data_set: Cell::new(0),
 
                 data_sets: Cell::new(vec![DataSetDescriptor {
                     dashed: false,
                     fill: true,
                     visible: true,
+                    color: None,
+                    label: None,
 
                     data_set,
                     max_all_time: 0.,
+
+                    resolutions: vec![Resolution::new(1, DATA_SET_LEN_DEFAULT)],
+                    generation: Generation::default(),
                 }]),
+                resolution_factors: Cell::new(vec![1]),
 
                 scroll_offset: Cell::new(0),
                 prev_size: Cell::new((0, 0)),
                 animation_ticks: Cell::new(0),
                 expected_animation_ticks: Cell::new(10),
                 do_animation: Cell::new(false),
+
+                hover_x: Cell::new(None),
+                annotations: RefCell::new(vec![]),
+                value_formatter: RefCell::new(None),
+                axis_formatter: RefCell::new(None),
+
+                grid_cache: RefCell::new(Cached::default()),
+                plot_cache: RefCell::new(PlotCache::default()),
             }
         }
     }
@@ -175,6 +542,7 @@ mod imp {
                             *value =
                                 value.clamp(self.value_range_min.get(), self.value_range_max.get());
                         }
+                        values.generation.edits += 1;
                     }
 
                     self.data_sets.set(data_sets);
@@ -188,6 +556,7 @@ mod imp {
                         for value in values.data_set.iter_mut() {
                             *value = value.max(self.value_range_min.get());
                         }
+                        values.generation.edits += 1;
                     }
 
                     self.data_sets.set(data_sets);
@@ -205,6 +574,10 @@ mod imp {
             if self.data_points.take() != count {
                 let mut data_points = self.data_sets.take();
                 for values in data_points.iter_mut() {
+                    for resolution in values.resolutions.iter_mut() {
+                        resolution.resize(count);
+                    }
+
                     if count == (values.data_set.len() as u32) {
                         continue;
                     }
@@ -212,6 +585,7 @@ mod imp {
                     values.data_set.reverse();
                     values.data_set.resize(count as _, 0.);
                     values.data_set.reverse();
+                    values.generation.edits += 1;
                 }
                 self.data_sets.set(data_points);
             }
@@ -219,6 +593,15 @@ mod imp {
         }
 
         fn set_data_sets(&self, count: u32) {
+            let data_point_count = self.data_points.get() as usize;
+
+            let factors = self.resolution_factors.take();
+            let resolutions = factors
+                .iter()
+                .map(|factor| Resolution::new(*factor, data_point_count))
+                .collect();
+            self.resolution_factors.set(factors);
+
             let mut data_points = self.data_sets.take();
             data_points.resize(
                 count as _,
@@ -226,9 +609,14 @@ mod imp {
                     dashed: false,
                     fill: true,
                     visible: true,
+                    color: None,
+                    label: None,
 
-                    data_set: vec![0.; self.data_points.get() as _],
+                    data_set: vec![0.; data_point_count],
                     max_all_time: 0.,
+
+                    resolutions,
+                    generation: Generation::default(),
                 },
             );
             self.data_sets.set(data_points);
@@ -236,6 +624,84 @@ mod imp {
             self.data_set_count.set(count);
         }
 
+        pub(super) fn set_resolution_factors(&self, mut factors: Vec<u32>) {
+            let downsample = self.downsample.get();
+            let data_point_count = self.data_points.get() as usize;
+
+            // The resolution on screen is always kept, switching away from it is up to the caller
+            factors.push(downsample);
+            for factor in factors.iter_mut() {
+                *factor = (*factor).max(1);
+            }
+            factors.sort_unstable();
+            factors.dedup();
+
+            let mut data_sets = self.data_sets.take();
+            for values in data_sets.iter_mut() {
+                values
+                    .resolutions
+                    .retain(|resolution| factors.contains(&resolution.factor));
+                for factor in &factors {
+                    if !values.resolutions.iter().any(|r| r.factor == *factor) {
+                        values
+                            .resolutions
+                            .push(Resolution::new(*factor, data_point_count));
+                    }
+                }
+            }
+            self.data_sets.set(data_sets);
+
+            self.resolution_factors.set(factors);
+        }
+
+        fn set_downsample(&self, downsample: u32) {
+            let downsample = downsample.max(1);
+            if self.downsample.get() == downsample {
+                return;
+            }
+            self.downsample.set(downsample);
+
+            let mut factors = self.resolution_factors.take();
+            if !factors.contains(&downsample) {
+                factors.push(downsample);
+            }
+            self.set_resolution_factors(factors);
+
+            let this = self.obj();
+
+            let mut data_sets = self.data_sets.take();
+            let mut max = self.value_range_min.get();
+            for values in data_sets.iter_mut() {
+                let Some(resolution) = values
+                    .resolutions
+                    .iter()
+                    .find(|resolution| resolution.factor == downsample)
+                else {
+                    continue;
+                };
+                values.data_set.clone_from(&resolution.values);
+                values.generation.edits += 1;
+
+                for value in values.data_set.iter_mut() {
+                    if self.scaling.get() == NO_SCALING {
+                        *value =
+                            value.clamp(self.value_range_min.get(), self.value_range_max.get());
+                    } else {
+                        *value = value.max(self.value_range_min.get());
+                    }
+                    max = max.max(*value);
+                }
+            }
+
+            let scaling = self.scaling.get();
+            if scaling == AUTO_SCALING || scaling == AUTO_POW2_SCALING {
+                this.scale(&mut data_sets, max);
+            }
+            self.data_sets.set(data_sets);
+
+            this.queue_draw();
+        }
+
         fn set_horizontal_line_count(&self, count: u32) {
             if self.horizontal_line_count.get() != count {
                 self.horizontal_line_count.set(count);
@@ -250,6 +716,27 @@ mod imp {
             }
         }
 
+        fn set_stacked(&self, stacked: bool) {
+            if self.stacked.get() != stacked {
+                self.stacked.set(stacked);
+                self.obj().upcast_ref::<super::GraphWidget>().queue_draw();
+            }
+        }
+
+        fn set_axis_labels_visible(&self, visible: bool) {
+            if self.axis_labels_visible.get() != visible {
+                self.axis_labels_visible.set(visible);
+                self.obj().upcast_ref::<super::GraphWidget>().queue_draw();
+            }
+        }
+
+        fn set_show_statistics(&self, show: bool) {
+            if self.show_statistics.get() != show {
+                self.show_statistics.set(show);
+                self.obj().upcast_ref::<super::GraphWidget>().queue_draw();
+            }
+        }
+
         fn set_smooth_graphs(&self, smooth: bool) {
             if self.smooth_graphs.get() != smooth {
                 self.smooth_graphs.set(smooth);
@@ -257,242 +744,986 @@ mod imp {
             }
         }
 
+        // Sliding is turned off when reduced motion is requested, whatever the graph settings say
+        pub(super) fn animate(&self) -> bool {
+            self.do_animation.get() && crate::animations_enabled()
+        }
+
         fn set_expected_animation_ticks(&self, ticks: u32) {
             if ticks > 0 {
                 if self.expected_animation_ticks.get() != ticks {
                     self.expected_animation_ticks.set(ticks);
                 }
-            }
-        }
+            }
+        }
+
+        fn configure_hover(&self) {
+            let this = self.obj();
+
+            let motion = gtk::EventControllerMotion::new();
+            motion.connect_motion({
+                let this = this.downgrade();
+                move |_, x, _| {
+                    if let Some(this) = this.upgrade() {
+                        this.imp().hover_x.set(Some(x as f32));
+                        this.queue_draw();
+                    }
+                }
+            });
+            motion.connect_leave({
+                let this = this.downgrade();
+                move |_| {
+                    if let Some(this) = this.upgrade() {
+                        this.imp().hover_x.set(None);
+                        this.queue_draw();
+                    }
+                }
+            });
+            this.add_controller(motion);
+
+            this.set_has_tooltip(true);
+            this.connect_query_tooltip(|this, x, _, keyboard, tooltip| {
+                if keyboard {
+                    return false;
+                }
+
+                match this.imp().tooltip_text(x as f32) {
+                    Some(text) => {
+                        tooltip.set_text(Some(&text));
+                        true
+                    }
+                    None => false,
+                }
+            });
+        }
+
+        /// Index of the data point closest to `x`, the oldest point being at index 0
+        fn data_point_at(&self, x: f32) -> Option<usize> {
+            let width = self.obj().width() as f32;
+            let count = self.data_points.get() as usize;
+            if width <= 0. || count < 2 {
+                return None;
+            }
+
+            let spacing_x = width / (count - 1) as f32;
+            Some(((x.max(0.) / spacing_x).round() as usize).min(count - 1))
+        }
+
+        /// Seconds each data point stands for
+        fn point_duration(&self) -> f64 {
+            settings!().uint64("app-update-interval-u64") as f64
+                * INTERVAL_STEP
+                * self.downsample.get() as f64
+        }
+
+        /// Annotations still on the graph, with the index of the data point they fall on
+        fn visible_annotations(&self) -> Vec<(usize, String)> {
+            let count = self.data_points.get() as usize;
+            let point_duration_us = self.point_duration() * 1_000_000.;
+            if count < 2 || point_duration_us <= 0. {
+                return vec![];
+            }
+
+            let now = glib::monotonic_time();
+            let mut annotations = self.annotations.borrow_mut();
+            annotations
+                .retain(|(time, _)| ((now - time) as f64 / point_duration_us) < count as f64);
+
+            annotations
+                .iter()
+                .map(|(time, text)| {
+                    let age = ((now - time) as f64 / point_duration_us).round() as usize;
+                    (count - 1 - age.min(count - 1), text.clone())
+                })
+                .collect()
+        }
+
+        fn tooltip_text(&self, x: f32) -> Option<String> {
+            let index = self.data_point_at(x)?;
+            let count = self.data_points.get() as usize;
+
+            let point_duration = self.point_duration();
+            let age = ((count - 1 - index) as f64 * point_duration).round() as u32;
+
+            let mut text = if age == 0 {
+                i18n("Now")
+            } else {
+                i18n_f("{} ago", &[&to_short_human_readable_time(age)])
+            };
+
+            let formatter = self.value_formatter.borrow();
+            let data_sets = self.data_sets.take();
+            let mut order = (0..data_sets.len()).collect::<Vec<_>>();
+            // Listed in the order they are drawn in, top to bottom
+            if self.stacked.get() {
+                order.reverse();
+            }
+            for i in order {
+                let values = &data_sets[i];
+                if !values.visible {
+                    continue;
+                }
+
+                // Data sets shorter than the graph are aligned to the right
+                let Some(value) = values
+                    .data_set
+                    .len()
+                    .checked_sub(count - index)
+                    .and_then(|index| values.data_set.get(index))
+                else {
+                    continue;
+                };
+
+                text.push('\n');
+                if let Some(label) = &values.label {
+                    text.push_str(label);
+                    text.push_str(": ");
+                }
+                match formatter.as_ref() {
+                    Some(formatter) => text.push_str(&formatter(i, *value)),
+                    None => text.push_str(&format!("{:.1}", value)),
+                }
+            }
+            self.data_sets.set(data_sets);
+
+            for (_, annotation) in self
+                .visible_annotations()
+                .into_iter()
+                .filter(|(i, _)| *i == index)
+            {
+                text.push('\n');
+                text.push_str(&annotation);
+            }
+
+            Some(text)
+        }
+
+        pub fn try_increment_scroll(&self) {
+            if !self.scroll.get() {
+                return;
+            }
+
+            self.scroll_offset
+                .set(self.scroll_offset.get().wrapping_add(1));
+        }
+    }
+
+    impl GraphWidget {
+        /// The stroke and fill color of every data set, by index; on stacked graphs, those without
+        /// a color of their own get lighter the higher up they are, so they can be told apart
+        pub(super) fn data_set_colors(
+            &self,
+            data_sets: &[DataSetDescriptor],
+            base_color: &gdk::RGBA,
+        ) -> Vec<(gdk::RGBA, gdk::RGBA)> {
+            let stacked = self.stacked.get();
+            let visible_count = data_sets.iter().filter(|values| values.visible).count();
+
+            let mut position = 0;
+            data_sets
+                .iter()
+                .map(|values| {
+                    let color = values.color.unwrap_or(*base_color);
+                    let fill_alpha = if stacked && values.color.is_none() && visible_count > 1 {
+                        0.6 - 0.45 * position as f32 / (visible_count - 1) as f32
+                    } else {
+                        100. / 256.
+                    };
+                    if values.visible {
+                        position += 1;
+                    }
+
+                    (
+                        gdk::RGBA::new(color.red(), color.green(), color.blue(), 1.),
+                        gdk::RGBA::new(color.red(), color.green(), color.blue(), fill_alpha),
+                    )
+                })
+                .collect()
+        }
+
+        #[inline]
+        fn draw_outline(&self, snapshot: &Snapshot, bounds: &gsk::RoundedRect, color: &gdk::RGBA) {
+            let stroke_color = gdk::RGBA::new(color.red(), color.green(), color.blue(), 1.);
+            snapshot.append_border(&bounds, &[1.; 4], &[stroke_color.clone(); 4]);
+        }
+
+        #[inline]
+        fn draw_grid(
+            &self,
+            snapshot: &Snapshot,
+            width: f32,
+            height: f32,
+            scale_factor: f64,
+            data_point_count: usize,
+            color: &gdk::RGBA,
+        ) {
+            let key = GridKey {
+                size: (width, height),
+                scale_factor,
+                line_count: (
+                    self.obj().horizontal_line_count(),
+                    self.obj().vertical_line_count(),
+                ),
+                color: *color,
+            };
+
+            let scale_factor = scale_factor as f32;
+            let color = gdk::RGBA::new(color.red(), color.green(), color.blue(), 51. / 256.);
+
+            let stroke = Stroke::new(1.);
+
+            let horizontal_line_count = self.obj().horizontal_line_count() + 1;
+            let vertical_line_count = self.obj().vertical_line_count() + 1;
+
+            let animdist = if self.animate() {
+                width / (data_point_count - 2) as f32
+            } else {
+                width / (data_point_count - 1) as f32
+            };
+
+            let col_width = width / vertical_line_count as f32;
+            let col_height = height - scale_factor;
+
+            let anim_offset = if self.obj().scroll() {
+                ((animdist)
+                    * (-(self.scroll_offset.get() as f32) + 1f32
+                        - self.animation_ticks.get().saturating_sub(1) as f32
+                            / self.expected_animation_ticks.get() as f32))
+                    .rem_euclid(col_width)
+            } else {
+                0.
+            };
+
+            let mut grid_cache = self.grid_cache.borrow_mut();
+            let (horizontal, vertical) = grid_cache.get_or_draw(key, || {
+                let horizontal = record(|snapshot| {
+                    let row_width = width - scale_factor;
+                    let row_height = height / horizontal_line_count as f32;
+
+                    for i in 1..horizontal_line_count {
+                        let path_builder = PathBuilder::new();
+                        path_builder.move_to(scale_factor / 2., row_height * i as f32);
+                        path_builder.line_to(row_width, row_height * i as f32);
+                        snapshot.append_stroke(&path_builder.to_path(), &stroke, &color);
+                    }
+                });
+
+                let vertical = record(|snapshot| {
+                    for i in 0..vertical_line_count {
+                        let path_builder = PathBuilder::new();
+                        path_builder.move_to(col_width * i as f32, scale_factor / 2.);
+                        path_builder.line_to(col_width * i as f32, col_height);
+                        snapshot.append_stroke(&path_builder.to_path(), &stroke, &color);
+                    }
+                });
+
+                (horizontal, vertical)
+            });
+
+            if let Some(horizontal) = horizontal {
+                snapshot.append_node(horizontal);
+            }
+            if let Some(vertical) = vertical {
+                snapshot.save();
+                snapshot.translate(&graphene::Point::new(anim_offset, 0.));
+                snapshot.append_node(vertical);
+                snapshot.restore();
+            }
+        }
+
+        /// Where `value`, relative to the minimum of the value range, goes for the point at
+        /// `index` of a data set of `len` points, once sliding is over
+        fn point_at(
+            &self,
+            width: f32,
+            height: f32,
+            len: usize,
+            index: usize,
+            value: f32,
+        ) -> (f32, f32) {
+            let (spacing_x, start_x) = self.resting_x_layout(width, len);
+            let range = self.value_range_max.get() - self.value_range_min.get();
+
+            (
+                index as f32 * spacing_x + start_x,
+                height - ((value.clamp(0., range) / range) * height),
+            )
+        }
+
+        /// The line through the values of `data_points`, and the index of the first value on it;
+        /// leading values too small to see are left out, the line then rises from the bottom
+        fn plot_line(
+            &self,
+            width: f32,
+            height: f32,
+            scale_factor: f64,
+            data_points: &mut DataSetDescriptor,
+        ) -> (Vec<(f32, f32)>, usize) {
+            let scale_factor = scale_factor as f32;
+            let len = data_points.data_set.len();
+
+            let (skip, values) = if self.scaling.get() != NORMALIZED_SCALING {
+                let min = self.value_range_min.get();
+                let skip = data_points
+                    .data_set
+                    .iter()
+                    .take_while(|value| **value - min <= scale_factor)
+                    .count();
+                let values = data_points.data_set[skip..]
+                    .iter()
+                    .map(|value| *value - min)
+                    .collect::<Vec<_>>();
+
+                (skip, values)
+            } else {
+                let mut min = self.value_range_min.get();
+                let mut max = self.value_range_max.get();
+                for value in data_points.data_set.iter() {
+                    if *value < min {
+                        min = *value;
+                    }
+                    if *value > max {
+                        max = *value;
+                    }
+                }
+
+                if data_points.max_all_time < max {
+                    data_points.max_all_time = max;
+                }
+
+                if self.only_scale_up.get() {
+                    max = data_points.max_all_time;
+                }
+
+                let downscale_factor = max - min;
+                let values = data_points
+                    .data_set
+                    .iter()
+                    .map(|value| {
+                        if downscale_factor == 0. {
+                            0.
+                        } else {
+                            (*value - min) / downscale_factor
+                        }
+                    })
+                    .collect::<Vec<_>>();
+
+                (0, values)
+            };
+
+            let mut points = values
+                .iter()
+                .enumerate()
+                .map(|(i, value)| self.point_at(width, height, len, skip + i, *value))
+                .collect::<Vec<_>>();
+            if points.is_empty() {
+                return (points, skip);
+            }
+
+            // Long histories have many more points than there are pixels across
+            if points.len() > width as usize {
+                points = largest_triangle_three_buckets(&points, width as usize)
+                    .into_iter()
+                    .map(|i| points[i])
+                    .collect();
+            }
+
+            if skip > 0 {
+                let (spacing_x, _) = self.resting_x_layout(width, len);
+                points.insert(0, ((skip as f32 - 2.) * spacing_x, height));
+            }
+
+            (points, skip)
+        }
+
+        /// Stroke `line`, and fill below it if `data_points` is filled
+        fn draw_line(
+            &self,
+            snapshot: &Snapshot,
+            height: f32,
+            line: &[(f32, f32)],
+            data_points: &DataSetDescriptor,
+            color: &gdk::RGBA,
+        ) {
+            let (Some(first), Some(last)) = (line.first(), line.last()) else {
+                return;
+            };
+            if line.len() < 2 {
+                return;
+            }
+
+            let stroke_color = gdk::RGBA::new(color.red(), color.green(), color.blue(), 1.);
+            let fill_color = gdk::RGBA::new(color.red(), color.green(), color.blue(), 100. / 256.);
+            let smooth = self.smooth_graphs.get() && crate::animations_enabled();
+
+            if data_points.fill {
+                let path_builder = PathBuilder::new();
+                trace(&path_builder, line, smooth, true);
+                path_builder.line_to(last.0, height);
+                path_builder.line_to(first.0, height);
+                path_builder.close();
+                snapshot.append_fill(&path_builder.to_path(), FillRule::Winding, &fill_color);
+            }
+
+            let stroke = Stroke::new(1.);
+            if data_points.dashed {
+                stroke.set_dash(&[5., 5.]);
+            }
+
+            let path_builder = PathBuilder::new();
+            trace(&path_builder, line, smooth, true);
+            snapshot.append_stroke(&path_builder.to_path(), &stroke, &stroke_color);
+        }
+
+        fn small_text_layout(&self, text: &str) -> gtk::pango::Layout {
+            let layout = self.obj().create_pango_layout(Some(text));
+            let attributes = gtk::pango::AttrList::new();
+            attributes.insert(gtk::pango::AttrFloat::new_scale(gtk::pango::SCALE_SMALL));
+            layout.set_attributes(Some(&attributes));
+
+            layout
+        }
+
+        /// Values along the right edge, at the grid lines, and times along the bottom edge, in
+        /// the text color of the theme so they stay readable with light and dark styles
+        fn draw_axis_labels(&self, snapshot: &Snapshot, width: f32, height: f32) {
+            if !self.axis_labels_visible.get() {
+                return;
+            }
+
+            let color = self.obj().color();
+            let color = gdk::RGBA::new(color.red(), color.green(), color.blue(), 0.7);
+
+            // Normalized graphs have no scale to speak of
+            let rows = self.horizontal_line_count.get() + 1;
+            if self.scaling.get() != NORMALIZED_SCALING {
+                let min = self.value_range_min.get();
+                let range = self.value_range_max.get() - min;
+                let formatter = self.axis_formatter.borrow();
+
+                let row_height = height / rows as f32;
+                let text_height = self.small_text_layout("0").pixel_size().1 as f32;
+                // Every other line, or fewer, on graphs too short for all of them
+                let step = ((text_height * 1.5) / row_height).ceil().max(1.) as u32;
+
+                for i in (1..rows).filter(|i| i % step == 0) {
+                    let value = min + range * (1. - i as f32 / rows as f32);
+                    let text = match formatter.as_ref() {
+                        Some(formatter) => formatter(value),
+                        None => format!("{:.0}", value),
+                    };
+
+                    let layout = self.small_text_layout(&text);
+                    let (text_width, text_height) = layout.pixel_size();
+
+                    snapshot.save();
+                    snapshot.translate(&graphene::Point::new(
+                        width - text_width as f32 - 4.,
+                        row_height * i as f32 - text_height as f32,
+                    ));
+                    snapshot.append_layout(&layout, &color);
+                    snapshot.restore();
+                }
+            }
+
+            let count = self.data_points.get() as usize;
+            if count < 2 {
+                return;
+            }
+            let duration = (count - 1) as f64 * self.point_duration();
+
+            let columns = self.vertical_line_count.get() + 1;
+            let column_width = width / columns as f32;
+            let widest = self
+                .small_text_layout(&i18n_f(
+                    "{} ago",
+                    &[&to_short_human_readable_time(duration.round() as u32)],
+                ))
+                .pixel_size()
+                .0 as f32;
+            let step = ((widest + 8.) / column_width).ceil().max(1.) as u32;
+
+            for i in (0..columns).filter(|i| i % step == 0) {
+                let age = duration * (columns - i) as f64 / columns as f64;
+                let layout = self.small_text_layout(&i18n_f(
+                    "{} ago",
+                    &[&to_short_human_readable_time(age.round() as u32)],
+                ));
+                let text_height = layout.pixel_size().1 as f32;
+
+                snapshot.save();
+                snapshot.translate(&graphene::Point::new(
+                    column_width * i as f32 + 4.,
+                    height - text_height - 2.,
+                ));
+                snapshot.append_layout(&layout, &color);
+                snapshot.restore();
+            }
+        }
+
+        /// The distance between points, and where the first one is, for a data set of `len` points;
+        /// while sliding, the first point is off screen and the rest follow the animation
+        fn x_layout(&self, width: f32, len: usize) -> (f32, f32) {
+            let (spacing_x, start_x) = self.resting_x_layout(width, len);
+            (spacing_x, start_x + self.slide_offset(spacing_x))
+        }
+
+        /// Same as `x_layout`, once sliding is over
+        fn resting_x_layout(&self, width: f32, len: usize) -> (f32, f32) {
+            if !self.animate() {
+                return (width / (len - 1) as f32, 0.);
+            }
+
+            let spacing_x = width / (len - 2) as f32;
+            (spacing_x, -spacing_x)
+        }
+
+        /// How far right of where they rest the points are while sliding
+        fn slide_offset(&self, spacing_x: f32) -> f32 {
+            if !self.animate() {
+                return 0.;
+            }
+
+            spacing_x
+                * (1f32
+                    - self.animation_ticks.get().saturating_sub(1) as f32
+                        / self.expected_animation_ticks.get() as f32)
+        }
+
+        /// Plot the data sets, only drawing them again when they change; while sliding, the
+        /// drawing is moved along instead
+        fn draw_data_sets(
+            &self,
+            snapshot: &Snapshot,
+            width: f32,
+            height: f32,
+            scale_factor: f64,
+            data_sets: &mut [DataSetDescriptor],
+            colors: &[(gdk::RGBA, gdk::RGBA)],
+        ) {
+            let key = PlotKey {
+                size: (width, height),
+                scale_factor,
+                value_range: (self.value_range_min.get(), self.value_range_max.get()),
+                scaling: self.scaling.get(),
+                only_scale_up: self.only_scale_up.get(),
+                smooth: self.smooth_graphs.get() && crate::animations_enabled(),
+                stacked: self.stacked.get(),
+                animate: self.animate(),
+                data_sets: data_sets
+                    .iter()
+                    .map(|values| {
+                        (
+                            values.visible,
+                            values.fill,
+                            values.dashed,
+                            values.data_set.len(),
+                        )
+                    })
+                    .collect(),
+                colors: colors.to_vec(),
+            };
+
+            let mut plot_cache = self.plot_cache.borrow_mut();
+            if plot_cache.key.as_ref() != Some(&key) {
+                *plot_cache = PlotCache {
+                    key: Some(key),
+                    ..Default::default()
+                };
+            }
+
+            let (spacing_x, _) = self.resting_x_layout(width, self.data_points.get() as usize);
+            let slide_offset = self.slide_offset(spacing_x);
+
+            if self.stacked.get() {
+                let generations = data_sets.iter().map(|values| values.generation).collect();
+                let node = plot_cache.stacked.get_or_draw(generations, || {
+                    record(|snapshot| {
+                        self.plot_stacked(snapshot, width, height, data_sets, colors);
+                    })
+                });
+                append_translated(snapshot, node.as_ref(), slide_offset);
+                return;
+            }
+
+            plot_cache
+                .data_sets
+                .resize_with(data_sets.len(), Default::default);
+            for ((values, (color, _)), plot) in data_sets
+                .iter_mut()
+                .zip(colors)
+                .zip(plot_cache.data_sets.iter_mut())
+            {
+                if !values.visible {
+                    continue;
+                }
+
+                let added = self.plot_data_set(plot, width, height, scale_factor, values, color);
+
+                // The prefix slid left by as many points as were added since it was drawn
+                let (spacing_x, _) = self.resting_x_layout(width, values.data_set.len());
+                append_translated(
+                    snapshot,
+                    plot.prefix.as_ref(),
+                    slide_offset - added as f32 * spacing_x,
+                );
+                if let Some(tail) = plot.tail.value.as_ref().filter(|_| plot.extendable) {
+                    append_translated(snapshot, tail.as_ref(), slide_offset);
+                }
+            }
+        }
+
+        /// Bring `plot` up to date with `values`: while points are only added, those that were
+        /// there already are kept and only the newest ones are drawn. Returns how many points were
+        /// added since the prefix was drawn.
+        fn plot_data_set(
+            &self,
+            plot: &mut DataSetPlot,
+            width: f32,
+            height: f32,
+            scale_factor: f64,
+            values: &mut DataSetDescriptor,
+            color: &gdk::RGBA,
+        ) -> usize {
+            let generation = values.generation;
+            let len = values.data_set.len();
+
+            // Normalizing, or picking points to fit the width, can move every point with a new one
+            let appendable = self.scaling.get() != NORMALIZED_SCALING && len <= width as usize;
+
+            let added = plot
+                .generation
+                .filter(|drawn| appendable && plot.extendable && drawn.edits == generation.edits)
+                .and_then(|drawn| generation.shifts.checked_sub(drawn.shifts))
+                .map(|added| added as usize)
+                .filter(|added| added + 2 < len.min(MAX_TAIL_POINTS));
+
+            let added = match added {
+                Some(added) => added,
+                None if plot.generation == Some(generation) => 0,
+                None => {
+                    let (line, skip) = self.plot_line(width, height, scale_factor, values);
+
+                    // The newest point is left to the tail, as long as the one before it is on
+                    // the line for the tail to start from
+                    plot.extendable = appendable && skip + 2 <= len;
+                    let prefix_len = if plot.extendable {
+                        line.len() - 1
+                    } else {
+                        line.len()
+                    };
+                    plot.prefix = record(|snapshot| {
+                        self.draw_line(snapshot, height, &line[..prefix_len], values, color)
+                    });
+                    plot.generation = Some(generation);
+                    plot.tail = Cached::default();
+
+                    0
+                }
+            };
 
-        pub fn try_increment_scroll(&self) {
-            if !self.scroll.get() {
-                return;
+            if !plot.extendable {
+                return added;
             }
 
-            self.scroll_offset
-                .set(self.scroll_offset.get().wrapping_add(1));
-        }
-    }
+            plot.tail.get_or_draw(generation, || {
+                let min = self.value_range_min.get();
+                let line = (len - 2 - added..len)
+                    .map(|i| self.point_at(width, height, len, i, values.data_set[i] - min))
+                    .collect::<Vec<_>>();
 
-    impl GraphWidget {
-        #[inline]
-        fn draw_outline(&self, snapshot: &Snapshot, bounds: &gsk::RoundedRect, color: &gdk::RGBA) {
-            let stroke_color = gdk::RGBA::new(color.red(), color.green(), color.blue(), 1.);
-            snapshot.append_border(&bounds, &[1.; 4], &[stroke_color.clone(); 4]);
+                record(|snapshot| self.draw_line(snapshot, height, &line, values, color))
+            });
+
+            added
         }
 
-        #[inline]
-        fn draw_grid(
+        /// Minimum and maximum as dashed lines across the graph, and the rolling average of the
+        /// data set the statistics are about
+        fn draw_statistics(
             &self,
             snapshot: &Snapshot,
             width: f32,
             height: f32,
-            scale_factor: f64,
-            data_point_count: usize,
-            color: &gdk::RGBA,
+            data_sets: &[DataSetDescriptor],
+            colors: &[(gdk::RGBA, gdk::RGBA)],
         ) {
-            let scale_factor = scale_factor as f32;
-            let color = gdk::RGBA::new(color.red(), color.green(), color.blue(), 51. / 256.);
-
-            let stroke = Stroke::new(1.);
-
-            // Draw horizontal lines
-            let horizontal_line_count = self.obj().horizontal_line_count() + 1;
-
-            let col_width = width - scale_factor;
-            let col_height = height / horizontal_line_count as f32;
-
-            for i in 1..horizontal_line_count {
-                let path_builder = PathBuilder::new();
-                path_builder.move_to(scale_factor / 2., col_height * i as f32);
-                path_builder.line_to(col_width, col_height * i as f32);
-                snapshot.append_stroke(&path_builder.to_path(), &stroke, &color);
+            // Stacked data sets don't start from the bottom, lines at their values would mislead
+            if !self.show_statistics.get() || self.stacked.get() {
+                return;
             }
 
-            // Draw vertical lines
-            let vertical_line_count = self.obj().vertical_line_count() + 1;
-
-            let animdist = if self.do_animation.get() {
-                width / (data_point_count - 2) as f32
-            } else {
-                width / (data_point_count - 1) as f32
+            let index = self.statistics_data_set.get() as usize;
+            let Some(values) = data_sets.get(index).filter(|values| values.visible) else {
+                return;
             };
+            let len = values.data_set.len();
+            let shown = &values.data_set[len.saturating_sub(self.data_points.get() as usize)..];
+            let Some(statistics) = Statistics::of(shown) else {
+                return;
+            };
+            if len < 3 {
+                return;
+            }
 
-            let col_width = width / vertical_line_count as f32;
-            let col_height = height - scale_factor;
-
-            let anim_offset = if self.obj().scroll() {
-                ((animdist)
-                    * (-(self.scroll_offset.get() as f32) + 1f32
-                        - self.animation_ticks.get().saturating_sub(1) as f32
-                            / self.expected_animation_ticks.get() as f32))
-                    .rem_euclid(col_width)
+            let (min, range) = if self.scaling.get() == NORMALIZED_SCALING {
+                let min = values
+                    .data_set
+                    .iter()
+                    .copied()
+                    .fold(self.value_range_min.get(), f32::min);
+                let max = values
+                    .data_set
+                    .iter()
+                    .copied()
+                    .fold(self.value_range_max.get(), f32::max);
+                (min, max - min)
             } else {
-                0.
+                (
+                    self.value_range_min.get(),
+                    self.value_range_max.get() - self.value_range_min.get(),
+                )
             };
+            let y = |value: f32| {
+                if range > 0. {
+                    height - ((value - min).clamp(0., range) / range) * height
+                } else {
+                    height
+                }
+            };
+
+            let (color, _) = colors[index];
+            let color = gdk::RGBA::new(color.red(), color.green(), color.blue(), 0.8);
 
-            for i in 0..vertical_line_count {
+            let stroke = Stroke::new(1.);
+            stroke.set_dash(&[2., 4.]);
+            for value in [statistics.min, statistics.max] {
                 let path_builder = PathBuilder::new();
-                path_builder.move_to(col_width * i as f32 + anim_offset, scale_factor / 2.);
-                path_builder.line_to(col_width * i as f32 + anim_offset, col_height);
+                path_builder.move_to(0., y(value));
+                path_builder.line_to(width, y(value));
                 snapshot.append_stroke(&path_builder.to_path(), &stroke, &color);
             }
+
+            let (spacing_x, start_x) = self.x_layout(width, len);
+            let points = values
+                .data_set
+                .iter()
+                .enumerate()
+                .map(|(i, _)| {
+                    let window =
+                        &values.data_set[(i + 1).saturating_sub(ROLLING_AVERAGE_POINTS)..=i];
+                    let average = window.iter().sum::<f32>() / window.len() as f32;
+                    (i as f32 * spacing_x + start_x, y(average))
+                })
+                .collect::<Vec<_>>();
+
+            let path_builder = PathBuilder::new();
+            trace(
+                &path_builder,
+                &points,
+                self.smooth_graphs.get() && crate::animations_enabled(),
+                true,
+            );
+            snapshot.append_stroke(&path_builder.to_path(), &Stroke::new(2.), &color);
         }
 
-        #[inline]
-        fn plot_values(
+        fn plot_stacked(
             &self,
             snapshot: &Snapshot,
             width: f32,
             height: f32,
-            scale_factor: f64,
-            data_points: &mut DataSetDescriptor,
-            color: &gdk::RGBA,
+            data_sets: &[DataSetDescriptor],
+            colors: &[(gdk::RGBA, gdk::RGBA)],
         ) {
-            let scale_factor = scale_factor as f32;
-            let stroke_color = gdk::RGBA::new(color.red(), color.green(), color.blue(), 1.);
-            let fill_color = gdk::RGBA::new(color.red(), color.green(), color.blue(), 100. / 256.);
-
-            let stroke = Stroke::new(1.);
-
-            let val_max = self.value_range_max.get() - self.value_range_min.get();
-            let val_min = 0.;
+            let bands = stacked_bands(data_sets, self.value_range_min.get());
+            let len = bands.first().map_or(0, |band| band.upper.len());
+            if len < 3 {
+                return;
+            }
 
-            let spacing_x = if self.do_animation.get() {
-                width / (data_points.data_set.len() - 2) as f32
+            let range = if self.scaling.get() == NORMALIZED_SCALING {
+                // Normalized graphs span up to the highest total instead
+                bands
+                    .last()
+                    .map_or(0., |band| band.upper.iter().copied().fold(0., f32::max))
             } else {
-                width / (data_points.data_set.len() - 1) as f32
+                self.value_range_max.get() - self.value_range_min.get()
             };
 
-            let mut points: Vec<(f32, f32)> = if self.scaling.get() != NORMALIZED_SCALING {
-                (0..)
-                    .map(|x| x as f32)
-                    .zip(
-                        data_points
-                            .data_set
-                            .iter()
-                            .map(|x| *x - self.value_range_min.get()),
-                    )
-                    .skip_while(|(_, y)| *y <= scale_factor)
-                    .collect()
-            } else {
-                let mut min = self.value_range_min.get();
-                let mut max = self.value_range_max.get();
-                for value in data_points.data_set.iter() {
-                    if *value < min {
-                        min = *value;
-                    }
-                    if *value > max {
-                        max = *value;
-                    }
-                }
+            let (spacing_x, start_x) = self.resting_x_layout(width, len);
+            let point = |i: usize, value: f32| {
+                let x = i as f32 * spacing_x + start_x;
+                let y = if range > 0. {
+                    height - (value.clamp(0., range) / range) * height
+                } else {
+                    height
+                };
+                (x, y)
+            };
 
-                if data_points.max_all_time < max {
-                    data_points.max_all_time = max;
+            let smooth = self.smooth_graphs.get() && crate::animations_enabled();
+
+            // Downsampled along the total, so that the bands keep sharing their edges
+            let kept = match bands.last() {
+                Some(top) if len > width as usize => {
+                    let total = top
+                        .upper
+                        .iter()
+                        .enumerate()
+                        .map(|(i, value)| point(i, *value))
+                        .collect::<Vec<_>>();
+                    largest_triangle_three_buckets(&total, width as usize)
                 }
+                _ => (0..len).collect(),
+            };
 
-                if self.only_scale_up.get() {
-                    max = data_points.max_all_time;
+            let mut edges = vec![];
+            for band in &bands {
+                let values = &data_sets[band.index];
+                let (stroke_color, fill_color) = colors[band.index];
+
+                let upper = kept
+                    .iter()
+                    .map(|i| point(*i, band.upper[*i]))
+                    .collect::<Vec<_>>();
+
+                if values.fill {
+                    let lower = kept
+                        .iter()
+                        .rev()
+                        .map(|i| point(*i, band.lower[*i]))
+                        .collect::<Vec<_>>();
+
+                    let path_builder = PathBuilder::new();
+                    trace(&path_builder, &upper, smooth, true);
+                    trace(&path_builder, &lower, smooth, false);
+                    path_builder.close();
+                    snapshot.append_fill(&path_builder.to_path(), FillRule::Winding, &fill_color);
                 }
 
-                (0..)
-                    .map(|x| x as f32)
-                    .zip(data_points.data_set.iter().map(|x| {
-                        let downscale_factor = max - min;
-                        if downscale_factor == 0. {
-                            0.
-                        } else {
-                            (*x - min) / downscale_factor
-                        }
-                    }))
-                    .collect()
-            };
+                let path_builder = PathBuilder::new();
+                trace(&path_builder, &upper, smooth, true);
+                edges.push((path_builder.to_path(), values.dashed, stroke_color));
+            }
 
-            for (x, y) in &mut points {
-                *x = *x * spacing_x;
-                if self.do_animation.get() {
-                    *x -= spacing_x;
+            // Over every fill, so the band above doesn't cover the top edge of the one below
+            for (path, dashed, color) in edges {
+                let stroke = Stroke::new(1.);
+                if dashed {
+                    stroke.set_dash(&[5., 5.]);
                 }
-                *y = height - ((y.clamp(val_min, val_max) / val_max) * (height));
+                snapshot.append_stroke(&path, &stroke, &color);
             }
+        }
 
-            if !points.is_empty() {
-                let anim_offset = if self.do_animation.get() {
-                    -spacing_x
-                        * (1f32
-                            - self.animation_ticks.get().saturating_sub(1) as f32
-                                / self.expected_animation_ticks.get() as f32)
-                } else {
-                    0.
-                };
+        /// The labels of the visible data sets, in the order they are drawn in from the top
+        fn draw_legend(
+            &self,
+            snapshot: &Snapshot,
+            data_sets: &[DataSetDescriptor],
+            colors: &[(gdk::RGBA, gdk::RGBA)],
+            color: &gdk::RGBA,
+        ) {
+            const SWATCH_SIZE: f32 = 8.;
+
+            let mut entries = data_sets
+                .iter()
+                .zip(colors)
+                .filter(|(values, _)| values.visible)
+                .filter_map(|(values, (_, fill_color))| Some((values.label.as_ref()?, fill_color)))
+                .collect::<Vec<_>>();
+            if entries.is_empty() {
+                return;
+            }
+            if self.stacked.get() {
+                entries.reverse();
+            }
 
-                let startindex;
-                let (mut x, mut y);
-                let pointlen = points.len();
+            let text_color = gdk::RGBA::new(color.red(), color.green(), color.blue(), 0.8);
+
+            let mut x = 6.;
+            let y = 4.;
+            for (label, swatch_color) in entries {
+                let layout = self.small_text_layout(label);
+                let (text_width, text_height) = layout.pixel_size();
+
+                snapshot.append_color(
+                    swatch_color,
+                    &graphene::Rect::new(
+                        x,
+                        y + (text_height as f32 - SWATCH_SIZE) / 2.,
+                        SWATCH_SIZE,
+                        SWATCH_SIZE,
+                    ),
+                );
 
-                if pointlen < data_points.data_set.len() {
-                    (x, y) = (
-                        ((data_points.data_set.len() as f32) - (pointlen + 2) as f32) * spacing_x,
-                        height,
-                    );
-                    startindex = 0;
-                } else {
-                    (x, y) = points[0];
-                    startindex = 1;
-                }
+                snapshot.save();
+                snapshot.translate(&graphene::Point::new(x + SWATCH_SIZE + 4., y));
+                snapshot.append_layout(&layout, &text_color);
+                snapshot.restore();
 
-                x -= anim_offset;
+                x += SWATCH_SIZE + 4. + text_width as f32 + 12.;
+            }
+        }
 
-                let path_builder = PathBuilder::new();
-                path_builder.move_to(x, y);
+        fn draw_annotations(
+            &self,
+            snapshot: &Snapshot,
+            width: f32,
+            height: f32,
+            color: &gdk::RGBA,
+        ) {
+            let annotations = self.visible_annotations();
+            if annotations.is_empty() {
+                return;
+            }
 
-                let smooth = self.smooth_graphs.get();
+            let spacing_x = width / (self.data_points.get() - 1) as f32;
+            let stroke_color = gdk::RGBA::new(color.red(), color.green(), color.blue(), 0.8);
+            let stroke = Stroke::new(1.);
 
-                for i in startindex..pointlen {
-                    (x, y) = points[i];
+            for (index, text) in annotations {
+                let x = index as f32 * spacing_x;
 
-                    x -= anim_offset;
-                    if smooth {
-                        let (mut lastx, lasty);
-                        if i > 0 {
-                            (lastx, lasty) = points[i - 1];
-                        } else {
-                            (lastx, lasty) = (x - spacing_x + anim_offset, height);
-                        }
+                let path_builder = PathBuilder::new();
+                path_builder.move_to(x, 0.);
+                path_builder.line_to(x, height);
+                snapshot.append_stroke(&path_builder.to_path(), &stroke, &stroke_color);
 
-                        lastx -= anim_offset;
+                let layout = self.small_text_layout(&text);
 
-                        path_builder.cubic_to(
-                            lastx + spacing_x / 2f32,
-                            lasty,
-                            lastx + spacing_x / 2f32,
-                            y,
-                            x,
-                            y,
-                        );
-                    } else {
-                        path_builder.line_to(x, y);
-                    }
-                }
+                // Kept inside the graph when the line is close to its right edge
+                let text_width = layout.pixel_size().0 as f32;
+                let text_x = if x + 4. + text_width > width {
+                    x - 4. - text_width
+                } else {
+                    x + 4.
+                };
 
-                // Make sure to close out the path
-                path_builder.line_to(points[pointlen - 1].0, height);
-                path_builder.line_to(points[0].0, height);
-                path_builder.close();
+                snapshot.save();
+                snapshot.translate(&graphene::Point::new(text_x, 2.));
+                snapshot.append_layout(&layout, &stroke_color);
+                snapshot.restore();
+            }
+        }
 
-                let path = path_builder.to_path();
+        #[inline]
+        fn draw_crosshair(&self, snapshot: &Snapshot, width: f32, height: f32, color: &gdk::RGBA) {
+            let Some(index) = self.hover_x.get().and_then(|x| self.data_point_at(x)) else {
+                return;
+            };
 
-                if data_points.fill {
-                    snapshot.append_fill(&path, FillRule::Winding, &fill_color);
-                }
+            let spacing_x = width / (self.data_points.get() - 1) as f32;
+            let x = index as f32 * spacing_x;
 
-                if data_points.dashed {
-                    stroke.set_dash(&[5., 5.]);
-                }
+            let stroke_color = gdk::RGBA::new(color.red(), color.green(), color.blue(), 1.);
+            let stroke = Stroke::new(1.);
+            stroke.set_dash(&[3., 3.]);
 
-                snapshot.append_stroke(&path, &stroke, &stroke_color);
-            }
+            let path_builder = PathBuilder::new();
+            path_builder.move_to(x, 0.);
+            path_builder.line_to(x, height);
+            snapshot.append_stroke(&path_builder.to_path(), &stroke, &stroke_color);
         }
 
         fn render(&self, snapshot: &Snapshot, width: f32, height: f32, scale_factor: f64) {
@@ -521,15 +1752,23 @@ mod imp {
             }
 
             let mut data_sets = self.data_sets.take();
-            for values in &mut data_sets {
-                if !values.visible {
-                    continue;
-                }
-
-                self.plot_values(snapshot, width, height, scale_factor, values, &base_color);
-            }
+            let colors = self.data_set_colors(&data_sets, &base_color);
+            self.draw_data_sets(
+                snapshot,
+                width,
+                height,
+                scale_factor,
+                &mut data_sets,
+                &colors,
+            );
+            self.draw_statistics(snapshot, width, height, &data_sets, &colors);
+            self.draw_axis_labels(snapshot, width, height);
+            self.draw_legend(snapshot, &data_sets, &colors, &base_color);
             self.data_sets.set(data_sets);
 
+            self.draw_annotations(snapshot, width, height, &base_color);
+            self.draw_crosshair(snapshot, width, height, &base_color);
+
             snapshot.pop();
 
             self.draw_outline(snapshot, &bounds, &base_color);
@@ -561,6 +1800,12 @@ mod imp {
         fn property(&self, id: usize, pspec: &ParamSpec) -> Value {
             self.derived_property(id, pspec)
         }
+
+        fn constructed(&self) {
+            self.parent_constructed();
+
+            self.configure_hover();
+        }
     }
 
     impl WidgetImpl for GraphWidget {
@@ -637,16 +1882,59 @@ impl GraphWidget {
         self.imp().data_sets.set(data);
     }
 
-    pub fn add_data_point(&self, index: usize, mut value: f32) {
+    /// Draw the data set at `index` in `color` instead of the base color of the graph
+    pub fn set_data_color(&self, index: usize, color: Option<gdk::RGBA>) {
         let mut data = self.imp().data_sets.take();
+        if index < data.len() {
+            data[index].color = color;
+        }
+        self.imp().data_sets.set(data);
+        self.queue_draw();
+    }
 
-        self.set_animation_ticks(0);
-
-        if index == 0 {
-            self.imp().try_increment_scroll();
+    /// Name the data set at `index`; named data sets are listed in a legend on the graph
+    pub fn set_data_label(&self, index: usize, label: Option<&str>) {
+        let mut data = self.imp().data_sets.take();
+        if index < data.len() {
+            data[index].label = label.map(|label| label.to_owned());
         }
+        self.imp().data_sets.set(data);
+        self.queue_draw();
+    }
+
+    /// Format the values shown when hovering the graph, e.g. to add units; `index` is that of
+    /// the data set the value belongs to
+    pub fn set_value_formatter(&self, formatter: impl Fn(usize, f32) -> String + 'static) {
+        self.imp()
+            .value_formatter
+            .replace(Some(Box::new(formatter)));
+    }
+
+    /// Mark something that happened just now, e.g. a mode being switched on; the mark moves
+    /// along with the data and is dropped once it scrolls off
+    pub fn add_annotation(&self, text: &str) {
+        self.imp()
+            .annotations
+            .borrow_mut()
+            .push((glib::monotonic_time(), text.to_owned()));
+        self.queue_draw();
+    }
+
+    /// The graph keeps the averaged history for each of `factors`, so that `downsample` can be
+    /// switched between them without losing data
+    pub fn set_resolutions(&self, factors: &[u32]) {
+        self.imp().set_resolution_factors(factors.to_vec());
+    }
+
+    pub fn add_data_point(&self, index: usize, mut value: f32) {
+        let mut data = self.imp().data_sets.take();
 
         if index >= data.len() {
+            self.set_animation_ticks(0);
+            if index == 0 {
+                self.imp().try_increment_scroll();
+            }
+
             self.imp().data_sets.set(data);
             return;
         }
@@ -665,8 +1953,29 @@ impl GraphWidget {
             value = value.max(self.value_range_min());
         }
 
-        data[index].data_set.push(value);
-        data[index].data_set.remove(0);
+        let downsample = self.downsample();
+        let values = &mut data[index];
+
+        let mut new_point = true;
+        for resolution in values.resolutions.iter_mut() {
+            let started = resolution.push(value);
+            if resolution.factor == downsample {
+                new_point = started;
+                values.data_set.clone_from(&resolution.values);
+            }
+        }
+
+        if new_point {
+            values.generation.shifts += 1;
+            self.set_animation_ticks(0);
+            if index == 0 {
+                self.imp().try_increment_scroll();
+            }
+        } else {
+            // Only the last point changed, nothing to slide
+            values.generation.updates += 1;
+            self.queue_draw();
+        }
 
         if self.scaling() == AUTO_SCALING || self.scaling() == AUTO_POW2_SCALING {
             self.scale(&mut data, value);
@@ -677,7 +1986,7 @@ impl GraphWidget {
 
     pub fn update_animation(&self) -> bool {
         if self.is_visible() {
-            if self.do_animation() {
+            if self.imp().animate() {
                 self.set_animation_ticks(
                     (self.animation_ticks() + 1).min(self.expected_animation_ticks()),
                 );
@@ -707,6 +2016,211 @@ impl GraphWidget {
         result
     }
 
+    /// The points of the data set at `index` that are on screen, oldest first, each averaging
+    /// `downsample()` samples
+    pub fn displayed_data(&self, index: usize) -> Option<Vec<f32>> {
+        let mut data = self.data(index)?;
+
+        let data_points = self.data_points() as usize;
+        if data.len() > data_points {
+            data.drain(..data.len() - data_points);
+        }
+
+        Some(data)
+    }
+
+    /// Format the labels of the grid lines, e.g. to add units; they are plain numbers otherwise
+    pub fn set_axis_formatter(&self, formatter: impl Fn(f32) -> String + 'static) {
+        self.imp().axis_formatter.replace(Some(Box::new(formatter)));
+    }
+
+    /// The statistics of the data set picked with `statistics-data-set` over the points on
+    /// screen, whether or not they are shown on the graph
+    pub fn statistics(&self) -> Option<Statistics> {
+        Statistics::of(&self.displayed_data(self.statistics_data_set() as usize)?)
+    }
+
+    /// `value` as the tooltip shows it for the data set at `index`
+    pub fn format_value(&self, index: usize, value: f32) -> String {
+        match self.imp().value_formatter.borrow().as_ref() {
+            Some(formatter) => formatter(index, value),
+            None => format!("{:.1}", value),
+        }
+    }
+
+    /// Draw what is on screen as SVG elements, in a `width` by `height` box with its top left
+    /// corner at the origin; animations and smoothing are left out
+    pub fn to_svg_elements(&self, width: f32, height: f32) -> String {
+        let color = svg_color(&self.base_color());
+
+        let mut svg = String::new();
+
+        if self.grid_visible() {
+            let rows = self.horizontal_line_count() + 1;
+            for i in 1..rows {
+                let y = height * i as f32 / rows as f32;
+                let _ = writeln!(
+                    svg,
+                    r#"<line x1="0" y1="{y:.1}" x2="{width:.1}" y2="{y:.1}" stroke="{color}" stroke-opacity="0.2"/>"#
+                );
+            }
+
+            let columns = self.vertical_line_count() + 1;
+            for i in 1..columns {
+                let x = width * i as f32 / columns as f32;
+                let _ = writeln!(
+                    svg,
+                    r#"<line x1="{x:.1}" y1="0" x2="{x:.1}" y2="{height:.1}" stroke="{color}" stroke-opacity="0.2"/>"#
+                );
+            }
+        }
+
+        let imp = self.imp();
+        let range_min = self.value_range_min();
+        let range = self.value_range_max() - range_min;
+        let normalized = self.scaling() == NORMALIZED_SCALING;
+
+        let data_sets = imp.data_sets.take();
+        let colors = imp.data_set_colors(&data_sets, &self.base_color());
+        if self.stacked() {
+            self.stacked_svg_elements(&mut svg, width, height, &data_sets, &colors);
+        } else {
+            for (data_set, (stroke_color, _)) in data_sets
+                .iter()
+                .zip(&colors)
+                .filter(|(data_set, _)| data_set.visible)
+            {
+                let color = svg_color(stroke_color);
+                let values = &data_set.data_set;
+                if values.len() < 2 {
+                    continue;
+                }
+
+                // Same as on screen, normalized graphs span from their smallest to their largest value
+                let (min, range) = if normalized {
+                    let min = values.iter().copied().fold(range_min, f32::min);
+                    let max = values
+                        .iter()
+                        .copied()
+                        .fold(self.value_range_max(), f32::max);
+                    (min, max - min)
+                } else {
+                    (range_min, range)
+                };
+
+                let spacing_x = width / (values.len() - 1) as f32;
+                let mut path = format!("M0 {:.1}", height);
+                for (i, value) in values.iter().enumerate() {
+                    let y = if range > 0. {
+                        height - ((value - min).clamp(0., range) / range) * height
+                    } else {
+                        height
+                    };
+                    let _ = write!(path, " L{:.1} {:.1}", i as f32 * spacing_x, y);
+                }
+                let _ = write!(path, " L{:.1} {:.1} Z", width, height);
+
+                let fill = if data_set.fill {
+                    format!(r#"fill="{color}" fill-opacity="0.39""#)
+                } else {
+                    r#"fill="none""#.to_owned()
+                };
+                let dash = if data_set.dashed {
+                    r#" stroke-dasharray="5 5""#
+                } else {
+                    ""
+                };
+                let _ = writeln!(svg, r#"<path d="{path}" {fill} stroke="{color}"{dash}/>"#);
+            }
+        }
+        imp.data_sets.set(data_sets);
+
+        let _ = writeln!(
+            svg,
+            r#"<rect x="0.5" y="0.5" width="{:.1}" height="{:.1}" rx="{GRAPH_RADIUS}" fill="none" stroke="{color}"/>"#,
+            width - 1.,
+            height - 1.,
+        );
+
+        svg
+    }
+
+    fn stacked_svg_elements(
+        &self,
+        svg: &mut String,
+        width: f32,
+        height: f32,
+        data_sets: &[DataSetDescriptor],
+        colors: &[(gdk::RGBA, gdk::RGBA)],
+    ) {
+        let bands = stacked_bands(data_sets, self.value_range_min());
+        let len = bands.first().map_or(0, |band| band.upper.len());
+        if len < 2 {
+            return;
+        }
+
+        let range = if self.scaling() == NORMALIZED_SCALING {
+            bands
+                .last()
+                .map_or(0., |band| band.upper.iter().copied().fold(0., f32::max))
+        } else {
+            self.value_range_max() - self.value_range_min()
+        };
+
+        let spacing_x = width / (len - 1) as f32;
+        let point = |(i, value): (usize, &f32)| {
+            let y = if range > 0. {
+                height - (value.clamp(0., range) / range) * height
+            } else {
+                height
+            };
+            format!("{:.1} {:.1}", i as f32 * spacing_x, y)
+        };
+
+        let mut edges = String::new();
+        for band in &bands {
+            let data_set = &data_sets[band.index];
+            let (stroke_color, fill_color) = &colors[band.index];
+
+            let upper = band
+                .upper
+                .iter()
+                .enumerate()
+                .map(point)
+                .collect::<Vec<_>>()
+                .join(" L");
+
+            if data_set.fill {
+                let lower = band
+                    .lower
+                    .iter()
+                    .enumerate()
+                    .rev()
+                    .map(point)
+                    .collect::<Vec<_>>()
+                    .join(" L");
+                let _ = writeln!(
+                    svg,
+                    r#"<path d="M{upper} L{lower} Z" fill="{}" fill-opacity="{:.2}" stroke="none"/>"#,
+                    svg_color(fill_color),
+                    fill_color.alpha()
+                );
+            }
+
+            let dash = if data_set.dashed {
+                r#" stroke-dasharray="5 5""#
+            } else {
+                ""
+            };
+            let _ = writeln!(
+                edges,
+                r#"<path d="M{upper}" fill="none" stroke="{}"{dash}/>"#,
+                svg_color(stroke_color)
+            );
+        }
+        svg.push_str(&edges);
+    }
+
     pub fn set_data(&self, index: usize, mut values: Vec<f32>) {
         let imp = self.imp();
         let mut data = imp.data_sets.take();
@@ -714,6 +2228,7 @@ impl GraphWidget {
         if index < data.len() {
             values.truncate(data[index].data_set.len());
             data[index].data_set = values;
+            data[index].generation.edits += 1;
 
             for x in &mut data[index].data_set {
                 if x.is_infinite() || x.is_nan() {
@@ -731,6 +2246,16 @@ impl GraphWidget {
                 }
             }
 
+            let downsample = self.downsample();
+            let values = &mut data[index];
+            if let Some(resolution) = values
+                .resolutions
+                .iter_mut()
+                .find(|resolution| resolution.factor == downsample)
+            {
+                resolution.reset(&values.data_set);
+            }
+
             if self.scaling() == AUTO_SCALING || self.scaling() == AUTO_POW2_SCALING {
                 if let Some(max) = data[index]
                     .data_set
@@ -783,10 +2308,17 @@ impl GraphWidget {
         let mut max_y = value.max(max_norm);
 
         let mut value_max = value;
-        for data_set in data.iter() {
-            for value in data_set.data_set.iter() {
-                if value_max < (*value - min_value) {
-                    value_max = *value;
+        if self.stacked() {
+            // What has to fit is the total of all data sets
+            if let Some(top) = stacked_bands(data, min_value).last() {
+                value_max = top.upper.iter().copied().fold(value_max, f32::max);
+            }
+        } else {
+            for data_set in data.iter() {
+                for value in data_set.data_set.iter() {
+                    if value_max < (*value - min_value) {
+                        value_max = *value;
+                    }
                 }
             }
         }
@@ -0,0 +1,370 @@
+/* performance_page/widgets/history_dialog.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::cell::{Cell, RefCell};
+
+use adw::{prelude::*, subclass::prelude::*};
+use gtk::{gdk, glib};
+
+use super::GraphWidget;
+use crate::history::{self, Metric, Sample, SAMPLE_PERIOD_MS};
+use crate::{i18n::*, settings, DataType};
+
+/// Selectable time ranges, in the order of the range drop down
+const RANGES_MS: [i64; 4] = [
+    60 * 60 * 1000,
+    6 * 60 * 60 * 1000,
+    24 * 60 * 60 * 1000,
+    7 * 24 * 60 * 60 * 1000,
+];
+const MAX_GRAPH_POINTS: i64 = 360;
+
+/// Which page the history is shown for
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum HistoryKind {
+    Cpu,
+    Memory,
+    Disk,
+    Network,
+    Gpu,
+}
+
+impl HistoryKind {
+    fn title(&self) -> String {
+        match self {
+            HistoryKind::Cpu => i18n("CPU History"),
+            HistoryKind::Memory => i18n("Memory History"),
+            HistoryKind::Disk => i18n("Drive History"),
+            HistoryKind::Network => i18n("Network History"),
+            HistoryKind::Gpu => i18n("GPU History"),
+        }
+    }
+
+    /// One data set each; the second one is drawn dashed
+    fn metrics(&self) -> &'static [Metric] {
+        match self {
+            HistoryKind::Cpu => &[Metric::CpuUsage],
+            HistoryKind::Memory => &[Metric::MemoryUsed],
+            HistoryKind::Disk => &[Metric::DiskRead, Metric::DiskWrite],
+            HistoryKind::Network => &[Metric::NetworkReceive, Metric::NetworkSend],
+            HistoryKind::Gpu => &[Metric::GpuUsage],
+        }
+    }
+
+    fn metric_name(metric: Metric) -> String {
+        match metric {
+            Metric::CpuUsage | Metric::GpuUsage | Metric::DiskBusy => i18n("Utilization"),
+            Metric::MemoryUsed => i18n("In use"),
+            Metric::MemoryTotal => i18n("Total"),
+            Metric::DiskRead => i18n("Read"),
+            Metric::DiskWrite => i18n("Write"),
+            Metric::NetworkReceive => i18n("Receive"),
+            Metric::NetworkSend => i18n("Send"),
+        }
+    }
+
+    fn format_value(&self, value: f32) -> String {
+        let data_type = match self {
+            HistoryKind::Cpu | HistoryKind::Gpu => {
+                return format!("{}%", value.round() as u32);
+            }
+            HistoryKind::Memory => DataType::MemoryBytes,
+            HistoryKind::Disk => DataType::DriveBytesPerSecond,
+            HistoryKind::Network => DataType::NetworkBytesPerSecond,
+        };
+
+        crate::to_human_readable_nice(value, &data_type, &settings!())
+    }
+}
+
+mod imp {
+    use super::*;
+
+    #[derive(gtk::CompositeTemplate)]
+    #[template(resource = "/io/missioncenter/MissionCenter/ui/performance_page/history_dialog.ui")]
+    pub struct HistoryDialog {
+        #[template_child]
+        pub range: TemplateChild<gtk::DropDown>,
+        #[template_child]
+        pub stack: TemplateChild<gtk::Stack>,
+        #[template_child]
+        pub empty_status: TemplateChild<adw::StatusPage>,
+        #[template_child]
+        pub start_label: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub end_label: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub graph: TemplateChild<GraphWidget>,
+        #[template_child]
+        pub position: TemplateChild<gtk::Scale>,
+        #[template_child]
+        pub summary: TemplateChild<gtk::Label>,
+
+        pub kind: Cell<Option<HistoryKind>>,
+        // Loaded once when the dialog is opened, the recorder only adds a sample a minute
+        pub samples: RefCell<Vec<Sample>>,
+    }
+
+    impl Default for HistoryDialog {
+        fn default() -> Self {
+            Self {
+                range: TemplateChild::default(),
+                stack: TemplateChild::default(),
+                empty_status: TemplateChild::default(),
+                start_label: TemplateChild::default(),
+                end_label: TemplateChild::default(),
+                graph: TemplateChild::default(),
+                position: TemplateChild::default(),
+                summary: TemplateChild::default(),
+
+                kind: Cell::new(None),
+                samples: RefCell::new(vec![]),
+            }
+        }
+    }
+
+    impl HistoryDialog {
+        pub fn range_ms(&self) -> i64 {
+            RANGES_MS[(self.range.selected() as usize).min(RANGES_MS.len() - 1)]
+        }
+
+        /// The scale picks where the shown range ends, anywhere between the oldest sample and
+        /// now
+        pub fn update_position_range(&self) {
+            let samples = self.samples.borrow();
+            let (Some(first), Some(last)) = (samples.first(), samples.last()) else {
+                return;
+            };
+
+            let range_ms = self.range_ms();
+            let newest = (last.timestamp_ms + SAMPLE_PERIOD_MS) as f64;
+            let oldest = ((first.timestamp_ms + range_ms) as f64).min(newest);
+
+            let adjustment = self.position.adjustment();
+            let at_end = adjustment.value() >= adjustment.upper();
+            adjustment.configure(
+                if at_end {
+                    newest
+                } else {
+                    adjustment.value().clamp(oldest, newest)
+                },
+                oldest,
+                newest,
+                range_ms as f64 / 10.,
+                range_ms as f64,
+                0.,
+            );
+            self.position.set_sensitive(newest > oldest);
+        }
+
+        pub fn update_graph(&self) {
+            let Some(kind) = self.kind.get() else {
+                return;
+            };
+            let samples = self.samples.borrow();
+
+            let range_ms = self.range_ms();
+            let end_ms = self.position.value() as i64;
+            let start_ms = end_ms - range_ms;
+
+            let points = (range_ms / SAMPLE_PERIOD_MS).min(MAX_GRAPH_POINTS);
+            let bin_ms = range_ms / points;
+
+            let first = samples.partition_point(|sample| sample.timestamp_ms < start_ms);
+            let last = samples.partition_point(|sample| sample.timestamp_ms < end_ms);
+            let window = &samples[first..last];
+
+            let graph = &self.graph;
+            graph.set_data_points(points as u32);
+
+            let mut summary = vec![];
+            for (index, metric) in kind.metrics().iter().enumerate() {
+                let mut sums = vec![0_f32; points as usize];
+                let mut counts = vec![0_u32; points as usize];
+                for sample in window {
+                    let Some(value) = sample.value(*metric) else {
+                        continue;
+                    };
+
+                    let bin = ((sample.timestamp_ms - start_ms) / bin_ms) as usize;
+                    if bin < sums.len() {
+                        sums[bin] += value;
+                        counts[bin] += 1;
+                    }
+                }
+
+                let values = sums
+                    .iter()
+                    .zip(counts.iter())
+                    .map(|(sum, count)| if *count > 0 { sum / *count as f32 } else { 0. })
+                    .collect::<Vec<_>>();
+                graph.set_data(index, values);
+
+                let recorded = window
+                    .iter()
+                    .filter_map(|sample| sample.value(*metric))
+                    .collect::<Vec<_>>();
+                if !recorded.is_empty() {
+                    let average = recorded.iter().sum::<f32>() / recorded.len() as f32;
+                    let peak = recorded.iter().copied().fold(0., f32::max);
+                    summary.push(i18n_f(
+                        "{}: {} average, {} peak",
+                        &[
+                            &HistoryKind::metric_name(*metric),
+                            &kind.format_value(average),
+                            &kind.format_value(peak),
+                        ],
+                    ));
+                }
+            }
+
+            if kind == HistoryKind::Memory {
+                let total = window
+                    .iter()
+                    .filter_map(|sample| sample.value(Metric::MemoryTotal))
+                    .fold(0., f32::max);
+                if total > 0. {
+                    graph.set_value_range_max(total);
+                }
+            }
+
+            if summary.is_empty() {
+                self.summary
+                    .set_text(&i18n("Nothing was recorded in this range"));
+            } else {
+                self.summary.set_text(&summary.join("\n"));
+            }
+            self.start_label.set_text(&format_time(start_ms));
+            self.end_label.set_text(&format_time(end_ms));
+        }
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for HistoryDialog {
+        const NAME: &'static str = "HistoryDialog";
+        type Type = super::HistoryDialog;
+        type ParentType = adw::Dialog;
+
+        fn class_init(klass: &mut Self::Class) {
+            GraphWidget::ensure_type();
+
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for HistoryDialog {
+        fn constructed(&self) {
+            self.parent_constructed();
+
+            self.range.connect_selected_notify({
+                let this = self.obj().downgrade();
+                move |_| {
+                    if let Some(this) = this.upgrade() {
+                        let this = this.imp();
+                        this.update_position_range();
+                        this.update_graph();
+                    }
+                }
+            });
+
+            self.position.connect_value_changed({
+                let this = self.obj().downgrade();
+                move |_| {
+                    if let Some(this) = this.upgrade() {
+                        this.imp().update_graph();
+                    }
+                }
+            });
+        }
+    }
+
+    impl WidgetImpl for HistoryDialog {}
+
+    impl AdwDialogImpl for HistoryDialog {}
+}
+
+fn format_time(timestamp_ms: i64) -> String {
+    glib::DateTime::from_unix_local(timestamp_ms / 1000)
+        .and_then(|time| time.format("%x %R"))
+        .map(|time| time.to_string())
+        .unwrap_or_default()
+}
+
+glib::wrapper! {
+    pub struct HistoryDialog(ObjectSubclass<imp::HistoryDialog>)
+        @extends adw::Dialog, gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl HistoryDialog {
+    pub fn new(kind: HistoryKind, base_color: gdk::RGBA) -> Self {
+        let this: Self = glib::Object::builder()
+            .property("title", kind.title())
+            .build();
+
+        let imp = this.imp();
+        imp.kind.set(Some(kind));
+
+        let graph = &imp.graph;
+        graph.set_base_color(base_color);
+        graph.set_data_sets(kind.metrics().len() as u32);
+        graph.set_value_formatter(move |_, value| kind.format_value(value));
+        match kind {
+            HistoryKind::Disk | HistoryKind::Network => {
+                graph.set_dashed(1, true);
+                graph.set_filled(1, false);
+                graph.set_scaling(GraphWidget::auto_pow2_scaling());
+            }
+            HistoryKind::Cpu | HistoryKind::Gpu => graph.set_value_range_max(100.),
+            HistoryKind::Memory => {}
+        }
+
+        let samples = history::load(0);
+        if samples.is_empty() {
+            let status = &imp.empty_status;
+            if settings!().boolean("history-enabled") {
+                status.set_title(&i18n("No History Yet"));
+                status.set_description(Some(&i18n(
+                    "Usage is recorded once a minute, check back in a little while",
+                )));
+            } else {
+                status.set_title(&i18n("History Is Off"));
+                status.set_description(Some(&i18n(
+                    "Turn on “Keep History” in the preferences to start recording usage",
+                )));
+            }
+            imp.stack.set_visible_child_name("empty");
+            imp.range.set_sensitive(false);
+
+            return this;
+        }
+
+        imp.samples.replace(samples);
+        imp.stack.set_visible_child_name("graph");
+        imp.update_position_range();
+        imp.update_graph();
+
+        this
+    }
+}
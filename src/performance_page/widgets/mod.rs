@@ -19,22 +19,32 @@
  */
 
 pub use eject_failure_dialog::EjectFailureDialog;
+pub use fan_correlation_widget::FanCorrelationWidget;
+pub use fan_curve_widget::FanCurveWidget;
+pub use gauge_widget::GaugeWidget;
 pub use graph_widget::GraphWidget;
+pub use history_dialog::{HistoryDialog, HistoryKind};
 pub use mem_composition_widget::MemoryCompositionWidget;
 pub use sidebar_drop_hint::SidebarDropHint;
 pub use smart_data_dialog::SmartDataDialog;
 pub use smart_failure_dialog::SmartFailureDialog;
 pub use smart_nvme_dialog_row::SmartNvmeDialogRow;
 pub use smart_sata_dialog_row::SmartSataDialogRow;
+pub use top_processes::TopProcesses;
 
 const GRAPH_RADIUS: f32 = 7.;
 
 mod eject_failure_dialog;
 mod eject_failure_row;
+mod fan_correlation_widget;
+mod fan_curve_widget;
+mod gauge_widget;
 mod graph_widget;
+mod history_dialog;
 mod mem_composition_widget;
 mod sidebar_drop_hint;
 mod smart_data_dialog;
 mod smart_failure_dialog;
 mod smart_nvme_dialog_row;
 mod smart_sata_dialog_row;
+mod top_processes;
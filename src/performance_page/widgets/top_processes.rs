@@ -0,0 +1,198 @@
+/* performance_page/widgets/top_processes.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use glib::{ParamSpec, Properties, Value};
+use gtk::{
+    glib::{self, subclass::prelude::*},
+    pango,
+    prelude::*,
+    subclass::prelude::*,
+};
+
+use crate::{app, i18n::*, magpie_client::Process};
+
+const ROW_COUNT: usize = 5;
+
+mod imp {
+    use super::*;
+
+    #[derive(Properties)]
+    #[properties(wrapper_type = super::TopProcesses)]
+    pub struct TopProcesses {
+        #[property(get, set = Self::set_title)]
+        title: RefCell<String>,
+
+        title_label: gtk::Label,
+        /// (button, name, value) for each row, shown only while there is a process for it
+        pub(crate) rows: RefCell<Vec<(gtk::Button, gtk::Label, gtk::Label)>>,
+        /// The process each visible row stands for, in the same order
+        pub(crate) pids: RefCell<Vec<u32>>,
+    }
+
+    impl Default for TopProcesses {
+        fn default() -> Self {
+            Self {
+                title: RefCell::new(String::new()),
+
+                title_label: gtk::Label::new(None),
+                rows: RefCell::new(vec![]),
+                pids: RefCell::new(vec![]),
+            }
+        }
+    }
+
+    impl TopProcesses {
+        fn set_title(&self, title: String) {
+            self.title_label.set_label(&title);
+            self.title.replace(title);
+        }
+
+        fn show_process(&self, index: usize) {
+            let Some(pid) = self.pids.borrow().get(index).copied() else {
+                return;
+            };
+
+            if let Some(window) = app!().window() {
+                window.show_process(pid);
+            }
+        }
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for TopProcesses {
+        const NAME: &'static str = "TopProcesses";
+        type Type = super::TopProcesses;
+        type ParentType = gtk::Box;
+    }
+
+    impl ObjectImpl for TopProcesses {
+        fn properties() -> &'static [ParamSpec] {
+            Self::derived_properties()
+        }
+
+        fn set_property(&self, id: usize, value: &Value, pspec: &ParamSpec) {
+            self.derived_set_property(id, value, pspec);
+        }
+
+        fn property(&self, id: usize, pspec: &ParamSpec) -> Value {
+            self.derived_property(id, pspec)
+        }
+
+        fn constructed(&self) {
+            self.parent_constructed();
+
+            let obj = self.obj();
+            obj.set_orientation(gtk::Orientation::Vertical);
+            obj.set_spacing(3);
+
+            self.title_label.add_css_class("caption");
+            self.title_label.set_halign(gtk::Align::Start);
+            obj.append(&self.title_label);
+
+            let mut rows = Vec::with_capacity(ROW_COUNT);
+            for index in 0..ROW_COUNT {
+                let name = gtk::Label::new(None);
+                name.set_halign(gtk::Align::Start);
+                name.set_hexpand(true);
+                name.set_ellipsize(pango::EllipsizeMode::End);
+
+                let value = gtk::Label::new(None);
+                value.add_css_class("numeric");
+                value.set_halign(gtk::Align::End);
+
+                let content = gtk::Box::new(gtk::Orientation::Horizontal, 10);
+                content.append(&name);
+                content.append(&value);
+
+                let button = gtk::Button::new();
+                button.add_css_class("flat");
+                button.set_child(Some(&content));
+                button.set_tooltip_text(Some(&i18n("Show in Apps")));
+                button.set_visible(false);
+                button.connect_clicked({
+                    let this = obj.downgrade();
+                    move |_| {
+                        if let Some(this) = this.upgrade() {
+                            this.imp().show_process(index);
+                        }
+                    }
+                });
+
+                obj.append(&button);
+                rows.push((button, name, value));
+            }
+            self.rows.replace(rows);
+
+            obj.set_visible(false);
+        }
+    }
+
+    impl WidgetImpl for TopProcesses {}
+
+    impl BoxImpl for TopProcesses {}
+}
+
+glib::wrapper! {
+    pub struct TopProcesses(ObjectSubclass<imp::TopProcesses>)
+        @extends gtk::Box, gtk::Widget,
+        @implements gtk::Orientable, gtk::Buildable;
+}
+
+impl TopProcesses {
+    pub fn new() -> Self {
+        glib::Object::new()
+    }
+
+    /// Show the processes with the highest `usage`, labelled with `describe`; processes that
+    /// use nothing are left out, and the whole section is hidden if that leaves none
+    pub fn update(
+        &self,
+        processes: &HashMap<u32, Process>,
+        usage: impl Fn(&Process) -> f32,
+        describe: impl Fn(f32) -> String,
+    ) {
+        let mut top = processes
+            .values()
+            .map(|process| (process, usage(process)))
+            .filter(|(_, usage)| *usage > 0.)
+            .collect::<Vec<_>>();
+        top.sort_unstable_by(|(_, a), (_, b)| b.total_cmp(a));
+        top.truncate(ROW_COUNT);
+
+        let imp = self.imp();
+        for (i, (button, name, value)) in imp.rows.borrow().iter().enumerate() {
+            match top.get(i) {
+                Some((process, usage)) => {
+                    name.set_text(&process.name);
+                    value.set_text(&describe(*usage));
+                    button.set_visible(true);
+                }
+                None => button.set_visible(false),
+            }
+        }
+        imp.pids
+            .replace(top.iter().map(|(process, _)| process.pid).collect());
+
+        self.set_visible(!top.is_empty());
+    }
+}
@@ -19,9 +19,21 @@
  */
 
 use adw::{prelude::*, subclass::prelude::*, SpinRow, SwitchRow};
+use std::cell::RefCell;
+
 use gtk::{gio, glib, Scale};
 
-use crate::settings;
+use crate::application::{collector_intervals, disabled_collectors, set_disabled_collectors};
+use crate::i18n::*;
+use crate::magpie_client::{
+    foreground_boost_supported, Availability, Collector, DataSource, RemoteHost,
+};
+use crate::performance_page::{
+    category_color, reset_graph_colors, save_graph_color, GRAPH_COLOR_CATEGORIES,
+};
+use crate::profiles::{remove_profile, save_profile, saved_profiles, Profile};
+use crate::widgets::MINI_MONITOR_GRAPHS;
+use crate::{app, settings};
 
 const MAX_INTERVAL_TICKS: u64 = 200;
 const MIN_INTERVAL_TICKS: u64 = 10;
@@ -29,6 +41,15 @@ const MIN_INTERVAL_TICKS: u64 = 10;
 const MAX_POINTS: i32 = 600;
 const MIN_POINTS: i32 = 10;
 
+const STARTUP_PAGES: [&str; 4] = [
+    "last-used",
+    "performance-page",
+    "apps-page",
+    "services-page",
+];
+
+const STREAM_FORMATS: [&str; 2] = ["json-lines", "otlp"];
+
 macro_rules! connect_switch_to_setting {
     ($this: expr, $switch_row: expr, $setting: literal) => {
         $switch_row.connect_active_notify({
@@ -71,12 +92,97 @@ macro_rules! connect_toggle_pair_to_setting {
     };
 }
 
+fn save_collector_interval(collector: Collector, seconds: u64) {
+    let settings = settings!();
+    let current = settings.string("collector-intervals");
+
+    let mut entries = current
+        .split(';')
+        .filter(|entry| !entry.is_empty())
+        .filter(|entry| entry.split_once('=').map(|(id, _)| id) != Some(collector.id()))
+        .map(|entry| entry.to_owned())
+        .collect::<Vec<_>>();
+    if seconds > 0 {
+        entries.push(format!("{}={}", collector.id(), seconds));
+    }
+
+    if let Err(e) = settings.set_string("collector-intervals", &entries.join(";")) {
+        gtk::glib::g_critical!(
+            "MissionCenter::Preferences",
+            "Failed to set collector-intervals setting: {}",
+            e
+        );
+    }
+}
+
+fn save_collector_enabled(collector: Collector, enabled: bool) {
+    let settings = settings!();
+
+    let mut disabled = disabled_collectors(&settings);
+    if enabled {
+        disabled.remove(&collector);
+    } else {
+        disabled.insert(collector);
+    }
+
+    if let Err(e) = set_disabled_collectors(&settings, &disabled) {
+        gtk::glib::g_critical!(
+            "MissionCenter::Preferences",
+            "Failed to set disabled-collectors setting: {}",
+            e
+        );
+    }
+}
+
+fn data_source_name(source: DataSource) -> String {
+    match source {
+        DataSource::Hwmon => i18n("Hardware Sensors"),
+        DataSource::UDisks2 => i18n("UDisks"),
+        DataSource::Nvml => i18n("NVIDIA Management Library"),
+        DataSource::Systemd => i18n("systemd"),
+    }
+}
+
+fn data_source_explanation(source: DataSource, availability: Availability) -> String {
+    match (source, availability) {
+        (DataSource::Hwmon, Availability::Missing) => i18n(
+            "No fan reports its speed; the driver for the sensor chip of the motherboard may not be loaded, so fans can't be shown or controlled",
+        ),
+        (DataSource::Hwmon, _) => i18n("Fan speeds, and the controls for them"),
+        (DataSource::UDisks2, Availability::Missing) => i18n(
+            "The UDisks service is not installed, so SMART data can't be read and drives can't be ejected",
+        ),
+        (DataSource::UDisks2, _) => i18n("SMART data of the drives, and ejecting them"),
+        (DataSource::Nvml, Availability::Missing) => i18n(
+            "The NVIDIA driver is loaded but its management library, libnvidia-ml, is not installed; GPU usage is only shown if nvidia-smi is",
+        ),
+        (DataSource::Nvml, Availability::NotNeeded) => {
+            i18n("No NVIDIA driver is loaded, other GPUs are read without it")
+        }
+        (DataSource::Nvml, _) => i18n("Usage, memory and temperature of NVIDIA GPUs"),
+        (DataSource::Systemd, Availability::Missing) => i18n(
+            "The system is not managed by systemd, so the Services page, service logs and the boot analysis are unavailable",
+        ),
+        (DataSource::Systemd, _) => i18n("Services and their logs, and the boot analysis"),
+    }
+}
+
 mod imp {
     use super::*;
 
     #[derive(gtk::CompositeTemplate, Default)]
     #[template(resource = "/io/missioncenter/MissionCenter/ui/preferences/page.ui")]
     pub struct PreferencesPage {
+        #[template_child]
+        pub startup_page: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub low_memory_warning: TemplateChild<SwitchRow>,
+        #[template_child]
+        pub status_icon: TemplateChild<SwitchRow>,
+        #[template_child]
+        pub reduce_motion: TemplateChild<SwitchRow>,
+        #[template_child]
+        pub gatherer_idle_priority: TemplateChild<SwitchRow>,
         #[template_child]
         pub update_interval: TemplateChild<SpinRow>,
         #[template_child]
@@ -87,6 +193,12 @@ mod imp {
         #[template_child]
         pub sliding_graphs: TemplateChild<SwitchRow>,
         #[template_child]
+        pub graph_axis_labels: TemplateChild<SwitchRow>,
+        #[template_child]
+        pub graph_statistics: TemplateChild<SwitchRow>,
+        #[template_child]
+        pub gauges: TemplateChild<SwitchRow>,
+        #[template_child]
         pub network_dynamic_scaling: TemplateChild<SwitchRow>,
         #[template_child]
         pub show_cpu: TemplateChild<SwitchRow>,
@@ -101,6 +213,39 @@ mod imp {
         #[template_child]
         pub show_fans: TemplateChild<SwitchRow>,
 
+        #[template_child]
+        pub graph_colors: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        pub reset_graph_colors: TemplateChild<gtk::Button>,
+
+        #[template_child]
+        pub keep_history: TemplateChild<SwitchRow>,
+        #[template_child]
+        pub history_retention: TemplateChild<SpinRow>,
+        #[template_child]
+        pub run_in_background: TemplateChild<SwitchRow>,
+        #[template_child]
+        pub background_interval: TemplateChild<SpinRow>,
+
+        #[template_child]
+        pub collector_intervals: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        pub data_sources: TemplateChild<adw::PreferencesGroup>,
+
+        #[template_child]
+        pub stream_metrics: TemplateChild<SwitchRow>,
+        #[template_child]
+        pub stream_format: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub stream_target: TemplateChild<adw::EntryRow>,
+        #[template_child]
+        pub stream_interval: TemplateChild<SpinRow>,
+
+        #[template_child]
+        pub mini_monitor_first_graph: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub mini_monitor_second_graph: TemplateChild<adw::ComboRow>,
+
         #[template_child]
         pub merged_process_stats: TemplateChild<SwitchRow>,
         #[template_child]
@@ -110,7 +255,16 @@ mod imp {
         #[template_child]
         pub core_count_affects_percentages: TemplateChild<SwitchRow>,
         #[template_child]
+        pub smooth_cpu_usage: TemplateChild<SwitchRow>,
+        #[template_child]
         pub show_column_separators: TemplateChild<SwitchRow>,
+        #[template_child]
+        pub foreground_boost: TemplateChild<SwitchRow>,
+
+        #[template_child]
+        pub remote_hosts: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        pub add_remote_host: TemplateChild<gtk::Button>,
 
         #[template_child]
         pub toggle_group_memory_unit: TemplateChild<adw::ToggleGroup>,
@@ -148,9 +302,525 @@ mod imp {
         pub toggle_net_base_2: TemplateChild<adw::Toggle>,
         #[template_child]
         pub toggle_net_base_10: TemplateChild<adw::Toggle>,
+        #[template_child]
+        pub decimal_places: TemplateChild<SpinRow>,
+        #[template_child]
+        pub compact_numbers: TemplateChild<SwitchRow>,
+
+        #[template_child]
+        pub profiles: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        pub import_profile: TemplateChild<gtk::Button>,
+        #[template_child]
+        pub save_profile: TemplateChild<gtk::Button>,
+
+        pub remote_host_rows: RefCell<Vec<adw::ActionRow>>,
+        pub graph_color_rows: RefCell<Vec<adw::ActionRow>>,
+        pub profile_rows: RefCell<Vec<adw::ActionRow>>,
+        pub data_source_rows: RefCell<Vec<adw::ActionRow>>,
     }
 
     impl PreferencesPage {
+        pub fn populate_collector_intervals(&self) {
+            let settings = settings!();
+            let intervals = collector_intervals(&settings);
+            let disabled = disabled_collectors(&settings);
+
+            for collector in Collector::ALL {
+                let title = match collector {
+                    Collector::Processes => i18n("Processes and Apps"),
+                    Collector::Disks => i18n("Drives"),
+                    Collector::Network => i18n("Network"),
+                    Collector::Gpus => i18n("GPUs"),
+                    Collector::Fans => i18n("Fans"),
+                    Collector::Services => i18n("Services"),
+                };
+
+                let row = adw::ExpanderRow::builder()
+                    .title(title)
+                    .show_enable_switch(true)
+                    .enable_expansion(!disabled.contains(&collector))
+                    .build();
+                row.connect_enable_expansion_notify(move |row| {
+                    save_collector_enabled(collector, row.enables_expansion());
+                });
+
+                let interval_row = SpinRow::builder()
+                    .title(i18n("Refresh Every"))
+                    .subtitle(i18n("In seconds, 0 refreshes on every update"))
+                    .adjustment(&gtk::Adjustment::new(0., 0., 600., 1., 10., 0.))
+                    .digits(0)
+                    .build();
+                interval_row.set_value(
+                    intervals
+                        .get(&collector)
+                        .map_or(0., |interval| interval.as_secs() as f64),
+                );
+                interval_row.connect_value_notify(move |spin_row| {
+                    save_collector_interval(collector, spin_row.value() as u64);
+                });
+                row.add_row(&interval_row);
+
+                self.collector_intervals.add(&row);
+            }
+        }
+
+        pub fn refresh_data_sources(&self) {
+            for row in self.data_source_rows.take() {
+                self.data_sources.remove(&row);
+            }
+
+            let capabilities = match app!().sys_info().map(|sys_info| sys_info.capabilities()) {
+                Ok(Ok(capabilities)) => capabilities,
+                Ok(Err(e)) => {
+                    let row = adw::ActionRow::builder()
+                        .title(i18n("Unavailable"))
+                        .subtitle(glib::markup_escape_text(&e))
+                        .build();
+                    self.data_sources.add(&row);
+                    self.data_source_rows.replace(vec![row]);
+                    return;
+                }
+                Err(e) => {
+                    glib::g_warning!(
+                        "MissionCenter::Preferences",
+                        "Failed to get `sys_info`: {}",
+                        e
+                    );
+                    return;
+                }
+            };
+
+            let mut rows = vec![];
+            for capability in capabilities {
+                let (icon, status) = match capability.availability {
+                    Availability::Available => ("emblem-ok-symbolic", i18n("Available")),
+                    Availability::NotNeeded => ("action-unavailable-symbolic", i18n("Not needed")),
+                    Availability::Missing => ("dialog-warning-symbolic", i18n("Missing")),
+                };
+
+                let row = adw::ActionRow::builder()
+                    .title(data_source_name(capability.source))
+                    .subtitle(data_source_explanation(
+                        capability.source,
+                        capability.availability,
+                    ))
+                    .build();
+
+                let image = gtk::Image::from_icon_name(icon);
+                image.set_tooltip_text(Some(&status));
+                if capability.availability == Availability::Missing {
+                    image.add_css_class("warning");
+                }
+                row.add_suffix(&image);
+
+                self.data_sources.add(&row);
+                rows.push(row);
+            }
+
+            self.data_source_rows.replace(rows);
+        }
+
+        pub fn refresh_graph_colors(&self) {
+            for row in self.graph_color_rows.take() {
+                self.graph_colors.remove(&row);
+            }
+
+            let mut rows = vec![];
+            for (category, _) in GRAPH_COLOR_CATEGORIES {
+                let title = match category {
+                    "cpu" => i18n("CPU"),
+                    "memory" => i18n("Memory"),
+                    "disk" => i18n("Drives"),
+                    "network" => i18n("Network"),
+                    "gpu" => i18n("GPU"),
+                    "fan" => i18n("Fans"),
+                    _ => i18n("UPS"),
+                };
+                let row = adw::ActionRow::builder().title(title).build();
+
+                let button = gtk::ColorDialogButton::builder()
+                    .dialog(&gtk::ColorDialog::builder().with_alpha(false).build())
+                    .rgba(&category_color(category))
+                    .valign(gtk::Align::Center)
+                    .build();
+                button.connect_rgba_notify(move |button| {
+                    save_graph_color(category, Some(&button.rgba()));
+                });
+                row.add_suffix(&button);
+                row.set_activatable_widget(Some(&button));
+
+                self.graph_colors.add(&row);
+                rows.push(row);
+            }
+
+            self.graph_color_rows.replace(rows);
+        }
+
+        pub fn refresh_remote_hosts(&self) {
+            for row in self.remote_host_rows.take() {
+                self.remote_hosts.remove(&row);
+            }
+
+            let current = app!().remote_host();
+
+            let mut rows = vec![];
+            for host in app!().remote_hosts() {
+                let row = adw::ActionRow::builder()
+                    .title(glib::markup_escape_text(&host.name))
+                    .subtitle(glib::markup_escape_text(&host.destination()))
+                    .build();
+
+                if current.as_ref() == Some(&host) {
+                    let connected = gtk::Image::from_icon_name("network-transmit-receive-symbolic");
+                    connected.set_tooltip_text(Some(&i18n("Connected")));
+                    row.add_suffix(&connected);
+                }
+
+                let remove = gtk::Button::builder()
+                    .icon_name("user-trash-symbolic")
+                    .tooltip_text(i18n("Remove"))
+                    .valign(gtk::Align::Center)
+                    .css_classes(["flat"])
+                    .build();
+                remove.connect_clicked({
+                    let this = self.obj().downgrade();
+                    move |_| {
+                        let Some(this) = this.upgrade() else {
+                            return;
+                        };
+
+                        let app = app!();
+                        let mut hosts = app.remote_hosts();
+                        hosts.retain(|h| h.name != host.name);
+                        app.set_remote_hosts(&hosts);
+
+                        this.imp().refresh_remote_hosts();
+                    }
+                });
+                row.add_suffix(&remove);
+
+                self.remote_hosts.add(&row);
+                rows.push(row);
+            }
+
+            self.remote_host_rows.replace(rows);
+        }
+
+        fn show_add_remote_host_dialog(&self) {
+            let name = adw::EntryRow::builder().title(i18n("Name")).build();
+            let destination = adw::EntryRow::builder()
+                .title(i18n(
+                    "Host, as user@hostname:port, tcp://hostname:port or tls+tcp://hostname:port",
+                ))
+                .build();
+            let identity_file = adw::EntryRow::builder()
+                .title(i18n("Private Key or CA Certificate File (Optional)"))
+                .build();
+
+            let fields = gtk::ListBox::builder()
+                .selection_mode(gtk::SelectionMode::None)
+                .css_classes(["boxed-list"])
+                .build();
+            fields.append(&name);
+            fields.append(&destination);
+            fields.append(&identity_file);
+
+            let dialog = adw::AlertDialog::builder()
+                .heading(i18n("Add Remote Host"))
+                .body(i18n(
+                    "Hosts are reached with the SSH client and configuration of this computer, unless a tcp:// or tls+tcp:// address of a Gatherer that is already listening is given; TLS connections are made with socat, checked against the CA certificate if one is given",
+                ))
+                .extra_child(&fields)
+                .build();
+            dialog.add_responses(&[("cancel", &i18n("_Cancel")), ("add", &i18n("_Add"))]);
+            dialog.set_response_appearance("add", adw::ResponseAppearance::Suggested);
+            dialog.set_response_enabled("add", false);
+            dialog.set_default_response(Some("add"));
+            dialog.set_close_response("cancel");
+
+            let parse = {
+                let name = name.downgrade();
+                let destination = destination.downgrade();
+                let identity_file = identity_file.downgrade();
+                move || {
+                    let name = name.upgrade()?;
+                    let destination = destination.upgrade()?;
+                    let identity_file = identity_file.upgrade()?;
+
+                    let host = RemoteHost::new(
+                        name.text().as_str(),
+                        destination.text().as_str(),
+                        identity_file.text().as_str(),
+                    )?;
+                    // Hosts are picked by name, on the command line too
+                    if app!().remote_hosts().iter().any(|h| h.name == host.name) {
+                        return None;
+                    }
+
+                    Some(host)
+                }
+            };
+
+            for entry in [&name, &destination, &identity_file] {
+                entry.connect_changed({
+                    let dialog = dialog.downgrade();
+                    let parse = parse.clone();
+                    move |_| {
+                        if let Some(dialog) = dialog.upgrade() {
+                            dialog.set_response_enabled("add", parse().is_some());
+                        }
+                    }
+                });
+            }
+
+            dialog.connect_response(Some("add"), {
+                let this = self.obj().downgrade();
+                move |_, _| {
+                    let Some(this) = this.upgrade() else {
+                        return;
+                    };
+                    let Some(host) = parse() else {
+                        return;
+                    };
+
+                    let app = app!();
+                    let mut hosts = app.remote_hosts();
+                    hosts.push(host);
+                    app.set_remote_hosts(&hosts);
+
+                    this.imp().refresh_remote_hosts();
+                }
+            });
+
+            dialog.present(Some(&*self.obj()));
+        }
+
+        pub fn refresh_profiles(&self) {
+            for row in self.profile_rows.take() {
+                self.profiles.remove(&row);
+            }
+
+            let mut rows = vec![];
+            for profile in saved_profiles(&settings!()) {
+                let row = adw::ActionRow::builder()
+                    .title(glib::markup_escape_text(&profile.name))
+                    .build();
+
+                let apply = gtk::Button::builder()
+                    .label(i18n("Apply"))
+                    .valign(gtk::Align::Center)
+                    .build();
+                apply.connect_clicked({
+                    let this = self.obj().downgrade();
+                    let profile = profile.clone();
+                    move |_| {
+                        profile.apply(&settings!());
+
+                        // The rows of this page still show the preferences from before
+                        if let Some(dialog) = this
+                            .upgrade()
+                            .and_then(|this| this.ancestor(adw::Dialog::static_type()))
+                            .and_downcast::<adw::Dialog>()
+                        {
+                            dialog.close();
+                        }
+                    }
+                });
+                row.add_suffix(&apply);
+
+                let export = gtk::Button::builder()
+                    .icon_name("document-save-symbolic")
+                    .tooltip_text(i18n("Export"))
+                    .valign(gtk::Align::Center)
+                    .css_classes(["flat"])
+                    .build();
+                export.connect_clicked({
+                    let this = self.obj().downgrade();
+                    let profile = profile.clone();
+                    move |_| {
+                        if let Some(this) = this.upgrade() {
+                            this.imp().export_profile(&profile);
+                        }
+                    }
+                });
+                row.add_suffix(&export);
+
+                let remove = gtk::Button::builder()
+                    .icon_name("user-trash-symbolic")
+                    .tooltip_text(i18n("Remove"))
+                    .valign(gtk::Align::Center)
+                    .css_classes(["flat"])
+                    .build();
+                remove.connect_clicked({
+                    let this = self.obj().downgrade();
+                    let name = profile.name.clone();
+                    move |_| {
+                        let Some(this) = this.upgrade() else {
+                            return;
+                        };
+
+                        if let Err(e) = remove_profile(&settings!(), &name) {
+                            glib::g_critical!(
+                                "MissionCenter::Preferences",
+                                "Failed to remove profile {}: {}",
+                                name,
+                                e
+                            );
+                        }
+
+                        this.imp().refresh_profiles();
+                    }
+                });
+                row.add_suffix(&remove);
+
+                self.profiles.add(&row);
+                rows.push(row);
+            }
+
+            self.profile_rows.replace(rows);
+        }
+
+        fn show_save_profile_dialog(&self) {
+            let name = adw::EntryRow::builder().title(i18n("Name")).build();
+
+            let fields = gtk::ListBox::builder()
+                .selection_mode(gtk::SelectionMode::None)
+                .css_classes(["boxed-list"])
+                .build();
+            fields.append(&name);
+
+            let dialog = adw::AlertDialog::builder()
+                .heading(i18n("Save Profile"))
+                .body(i18n(
+                    "A profile with the same name is replaced by the current preferences",
+                ))
+                .extra_child(&fields)
+                .build();
+            dialog.add_responses(&[("cancel", &i18n("_Cancel")), ("save", &i18n("_Save"))]);
+            dialog.set_response_appearance("save", adw::ResponseAppearance::Suggested);
+            dialog.set_response_enabled("save", false);
+            dialog.set_default_response(Some("save"));
+            dialog.set_close_response("cancel");
+
+            name.connect_changed({
+                let dialog = dialog.downgrade();
+                move |name| {
+                    if let Some(dialog) = dialog.upgrade() {
+                        dialog.set_response_enabled("save", !name.text().trim().is_empty());
+                    }
+                }
+            });
+
+            dialog.connect_response(Some("save"), {
+                let this = self.obj().downgrade();
+                let name = name.downgrade();
+                move |_, _| {
+                    let (Some(this), Some(name)) = (this.upgrade(), name.upgrade()) else {
+                        return;
+                    };
+
+                    let settings = settings!();
+                    let profile = Profile::capture(name.text().trim(), &settings);
+                    if let Err(e) = save_profile(&settings, &profile) {
+                        glib::g_critical!(
+                            "MissionCenter::Preferences",
+                            "Failed to save profile {}: {}",
+                            profile.name,
+                            e
+                        );
+                    }
+
+                    this.imp().refresh_profiles();
+                }
+            });
+
+            dialog.present(Some(&*self.obj()));
+        }
+
+        fn profile_file_filters() -> gio::ListStore {
+            let filter = gtk::FileFilter::new();
+            filter.set_name(Some(&i18n("Mission Center Profile")));
+            filter.add_suffix("json");
+
+            let filters = gio::ListStore::new::<gtk::FileFilter>();
+            filters.append(&filter);
+
+            filters
+        }
+
+        fn export_profile(&self, profile: &Profile) {
+            let dialog = gtk::FileDialog::builder()
+                .title(i18n("Export Profile"))
+                .initial_name(format!("{}.json", profile.name.replace('/', "-")))
+                .filters(&Self::profile_file_filters())
+                .modal(true)
+                .build();
+
+            let json = profile.to_json();
+            let window = self.obj().root().and_downcast::<gtk::Window>();
+            dialog.save(window.as_ref(), None::<&gio::Cancellable>, move |result| {
+                let Some(path) = result.ok().and_then(|file| file.path()) else {
+                    return;
+                };
+
+                if let Err(e) = std::fs::write(&path, json.as_bytes()) {
+                    glib::g_warning!(
+                        "MissionCenter::Preferences",
+                        "Failed to export profile to {}: {}",
+                        path.display(),
+                        e
+                    );
+                }
+            });
+        }
+
+        fn import_profile(&self) {
+            let dialog = gtk::FileDialog::builder()
+                .title(i18n("Import Profile"))
+                .filters(&Self::profile_file_filters())
+                .modal(true)
+                .build();
+
+            let window = self.obj().root().and_downcast::<gtk::Window>();
+            dialog.open(window.as_ref(), None::<&gio::Cancellable>, {
+                let this = self.obj().downgrade();
+                move |result| {
+                    let Some(this) = this.upgrade() else {
+                        return;
+                    };
+                    let Some(path) = result.ok().and_then(|file| file.path()) else {
+                        return;
+                    };
+
+                    let profile = std::fs::read_to_string(&path)
+                        .map_err(|e| e.to_string())
+                        .and_then(|json| Profile::from_json(&json));
+                    match profile {
+                        Ok(profile) => {
+                            if let Err(e) = save_profile(&settings!(), &profile) {
+                                glib::g_critical!(
+                                    "MissionCenter::Preferences",
+                                    "Failed to save profile {}: {}",
+                                    profile.name,
+                                    e
+                                );
+                            }
+                            this.imp().refresh_profiles();
+                        }
+                        Err(e) => {
+                            let dialog = adw::AlertDialog::builder()
+                                .heading(i18n("Could Not Import Profile"))
+                                .body(e)
+                                .build();
+                            dialog.add_response("close", &i18n("_Close"));
+                            dialog.present(Some(&this));
+                        }
+                    }
+                }
+            });
+        }
+
         pub fn configure_update_speed(&self) {
             use crate::application::INTERVAL_STEP;
             use glib::g_critical;
@@ -217,6 +887,17 @@ mod imp {
         fn constructed(&self) {
             self.parent_constructed();
 
+            // Checked when shown, the readings have to be running for the gatherer to answer
+            self.data_sources.connect_map({
+                let this = self.obj().downgrade();
+                move |_| {
+                    let Some(this) = this.upgrade() else {
+                        return;
+                    };
+                    this.imp().refresh_data_sources();
+                }
+            });
+
             self.data_points
                 .downcast_ref::<Scale>()
                 .unwrap()
@@ -241,8 +922,36 @@ mod imp {
                     }
                 });
 
+            self.startup_page.connect_selected_notify(|combo_row| {
+                let Some(page) = STARTUP_PAGES.get(combo_row.selected() as usize) else {
+                    return;
+                };
+                if let Err(e) = settings!().set_string("window-startup-page", page) {
+                    gtk::glib::g_critical!(
+                        "MissionCenter::Preferences",
+                        "Failed to set window-startup-page setting: {}",
+                        e
+                    );
+                }
+            });
+
+            connect_switch_to_setting!(self, self.low_memory_warning, "window-low-memory-warning");
+            connect_switch_to_setting!(self, self.status_icon, "status-icon-enabled");
+            connect_switch_to_setting!(self, self.reduce_motion, "window-reduce-motion");
+            connect_switch_to_setting!(self, self.gatherer_idle_priority, "gatherer-idle-priority");
             connect_switch_to_setting!(self, self.smooth_graphs, "performance-smooth-graphs");
             connect_switch_to_setting!(self, self.sliding_graphs, "performance-sliding-graphs");
+            connect_switch_to_setting!(
+                self,
+                self.graph_axis_labels,
+                "performance-page-graph-axis-labels"
+            );
+            connect_switch_to_setting!(
+                self,
+                self.graph_statistics,
+                "performance-page-graph-statistics"
+            );
+            connect_switch_to_setting!(self, self.gauges, "performance-page-gauges");
             connect_switch_to_setting!(
                 self,
                 self.network_dynamic_scaling,
@@ -255,6 +964,100 @@ mod imp {
             connect_switch_to_setting!(self, self.show_gpus, "performance-show-gpus");
             connect_switch_to_setting!(self, self.show_fans, "performance-show-fans");
 
+            connect_switch_to_setting!(self, self.keep_history, "history-enabled");
+            self.history_retention.connect_value_notify(|spin_row| {
+                if let Err(e) =
+                    settings!().set_int("history-retention-days", spin_row.value() as i32)
+                {
+                    gtk::glib::g_critical!(
+                        "MissionCenter::Preferences",
+                        "Failed to set history-retention-days setting: {}",
+                        e
+                    );
+                }
+            });
+
+            connect_switch_to_setting!(self, self.run_in_background, "run-in-background");
+            self.background_interval.connect_value_notify(|spin_row| {
+                if let Err(e) =
+                    settings!().set_int("background-update-interval", spin_row.value() as i32)
+                {
+                    gtk::glib::g_critical!(
+                        "MissionCenter::Preferences",
+                        "Failed to set background-update-interval setting: {}",
+                        e
+                    );
+                }
+            });
+
+            connect_switch_to_setting!(self, self.stream_metrics, "metrics-stream-enabled");
+            self.stream_format.connect_selected_notify(|combo_row| {
+                let Some(format) = STREAM_FORMATS.get(combo_row.selected() as usize) else {
+                    return;
+                };
+                if let Err(e) = settings!().set_string("metrics-stream-format", format) {
+                    gtk::glib::g_critical!(
+                        "MissionCenter::Preferences",
+                        "Failed to set metrics-stream-format setting: {}",
+                        e
+                    );
+                }
+            });
+            self.stream_target.connect_apply(|entry_row| {
+                if let Err(e) =
+                    settings!().set_string("metrics-stream-target", entry_row.text().trim())
+                {
+                    gtk::glib::g_critical!(
+                        "MissionCenter::Preferences",
+                        "Failed to set metrics-stream-target setting: {}",
+                        e
+                    );
+                }
+            });
+            self.stream_interval.connect_value_notify(|spin_row| {
+                if let Err(e) =
+                    settings!().set_int("metrics-stream-interval", spin_row.value() as i32)
+                {
+                    gtk::glib::g_critical!(
+                        "MissionCenter::Preferences",
+                        "Failed to set metrics-stream-interval setting: {}",
+                        e
+                    );
+                }
+            });
+
+            self.mini_monitor_first_graph
+                .connect_selected_notify(|combo_row| {
+                    let Some(graph) = MINI_MONITOR_GRAPHS.get(combo_row.selected() as usize) else {
+                        return;
+                    };
+                    if let Err(e) = settings!().set_string("mini-monitor-first-graph", graph) {
+                        gtk::glib::g_critical!(
+                            "MissionCenter::Preferences",
+                            "Failed to set mini-monitor-first-graph setting: {}",
+                            e
+                        );
+                    }
+                });
+            self.mini_monitor_second_graph
+                .connect_selected_notify(|combo_row| {
+                    // "None" comes first
+                    let graph = match combo_row.selected() {
+                        0 => "none",
+                        selected => match MINI_MONITOR_GRAPHS.get(selected as usize - 1) {
+                            Some(graph) => graph,
+                            None => return,
+                        },
+                    };
+                    if let Err(e) = settings!().set_string("mini-monitor-second-graph", graph) {
+                        gtk::glib::g_critical!(
+                            "MissionCenter::Preferences",
+                            "Failed to set mini-monitor-second-graph setting: {}",
+                            e
+                        );
+                    }
+                });
+
             connect_switch_to_setting!(
                 self,
                 self.merged_process_stats,
@@ -271,11 +1074,50 @@ mod imp {
                 self.core_count_affects_percentages,
                 "apps-page-core-count-affects-percentages"
             );
+            connect_switch_to_setting!(self, self.smooth_cpu_usage, "apps-page-smooth-cpu-usage");
             connect_switch_to_setting!(
                 self,
                 self.show_column_separators,
                 "apps-page-show-column-separators"
             );
+            connect_switch_to_setting!(self, self.foreground_boost, "apps-page-foreground-boost");
+
+            self.reset_graph_colors.connect_clicked({
+                let this = self.obj().downgrade();
+                move |_| {
+                    reset_graph_colors();
+                    if let Some(this) = this.upgrade() {
+                        this.imp().refresh_graph_colors();
+                    }
+                }
+            });
+
+            self.add_remote_host.connect_clicked({
+                let this = self.obj().downgrade();
+                move |_| {
+                    if let Some(this) = this.upgrade() {
+                        this.imp().show_add_remote_host_dialog();
+                    }
+                }
+            });
+
+            self.save_profile.connect_clicked({
+                let this = self.obj().downgrade();
+                move |_| {
+                    if let Some(this) = this.upgrade() {
+                        this.imp().show_save_profile_dialog();
+                    }
+                }
+            });
+
+            self.import_profile.connect_clicked({
+                let this = self.obj().downgrade();
+                move |_| {
+                    if let Some(this) = this.upgrade() {
+                        this.imp().import_profile();
+                    }
+                }
+            });
 
             connect_toggle_pair_to_setting!(
                 self,
@@ -313,6 +1155,19 @@ mod imp {
                 self.toggle_net_base_2,
                 "performance-page-network-use-base2"
             );
+
+            self.decimal_places.connect_value_notify(|spin_row| {
+                if let Err(e) =
+                    settings!().set_int("number-decimal-places", spin_row.value() as i32)
+                {
+                    gtk::glib::g_critical!(
+                        "MissionCenter::Preferences",
+                        "Failed to set number-decimal-places setting: {}",
+                        e
+                    );
+                }
+            });
+            connect_switch_to_setting!(self, self.compact_numbers, "number-compact");
         }
     }
 
@@ -336,10 +1191,33 @@ impl PreferencesPage {
         let imp = this.imp();
         let settings = settings!();
 
+        let startup_page = settings.string("window-startup-page");
+        imp.startup_page.set_selected(
+            STARTUP_PAGES
+                .iter()
+                .position(|page| *page == startup_page.as_str())
+                .unwrap_or(0) as u32,
+        );
+
+        imp.low_memory_warning
+            .set_active(settings.boolean("window-low-memory-warning"));
+        imp.status_icon
+            .set_active(settings.boolean("status-icon-enabled"));
+        imp.reduce_motion
+            .set_active(settings.boolean("window-reduce-motion"));
+        imp.gatherer_idle_priority
+            .set_active(settings.boolean("gatherer-idle-priority"));
+
         imp.smooth_graphs
             .set_active(settings.boolean("performance-smooth-graphs"));
         imp.sliding_graphs
             .set_active(settings.boolean("performance-sliding-graphs"));
+        imp.graph_axis_labels
+            .set_active(settings.boolean("performance-page-graph-axis-labels"));
+        imp.graph_statistics
+            .set_active(settings.boolean("performance-page-graph-statistics"));
+        imp.gauges
+            .set_active(settings.boolean("performance-page-gauges"));
         imp.network_dynamic_scaling
             .set_active(settings.boolean("performance-page-network-dynamic-scaling"));
         imp.show_cpu
@@ -355,6 +1233,50 @@ impl PreferencesPage {
         imp.show_fans
             .set_active(settings.boolean("performance-show-fans"));
 
+        imp.refresh_graph_colors();
+
+        imp.keep_history
+            .set_active(settings.boolean("history-enabled"));
+        imp.history_retention
+            .set_value(settings.int("history-retention-days") as f64);
+
+        imp.run_in_background
+            .set_active(settings.boolean("run-in-background"));
+        imp.background_interval
+            .set_value(settings.int("background-update-interval") as f64);
+
+        imp.populate_collector_intervals();
+
+        imp.stream_metrics
+            .set_active(settings.boolean("metrics-stream-enabled"));
+        let stream_format = settings.string("metrics-stream-format");
+        imp.stream_format.set_selected(
+            STREAM_FORMATS
+                .iter()
+                .position(|format| *format == stream_format.as_str())
+                .unwrap_or(0) as u32,
+        );
+        imp.stream_target
+            .set_text(&settings.string("metrics-stream-target"));
+        imp.stream_interval
+            .set_value(settings.int("metrics-stream-interval") as f64);
+
+        let first_graph = settings.string("mini-monitor-first-graph");
+        imp.mini_monitor_first_graph.set_selected(
+            MINI_MONITOR_GRAPHS
+                .iter()
+                .position(|graph| *graph == first_graph.as_str())
+                .unwrap_or(0) as u32,
+        );
+        let second_graph = settings.string("mini-monitor-second-graph");
+        imp.mini_monitor_second_graph.set_selected(
+            MINI_MONITOR_GRAPHS
+                .iter()
+                .position(|graph| *graph == second_graph.as_str())
+                .map(|position| position + 1)
+                .unwrap_or(0) as u32,
+        );
+
         imp.merged_process_stats
             .set_active(settings.boolean("apps-page-merged-process-stats"));
         imp.remember_sorting
@@ -363,8 +1285,21 @@ impl PreferencesPage {
             .set_active(settings.boolean("apps-page-remember-column-order"));
         imp.core_count_affects_percentages
             .set_active(settings.boolean("apps-page-core-count-affects-percentages"));
+        imp.smooth_cpu_usage
+            .set_active(settings.boolean("apps-page-smooth-cpu-usage"));
         imp.show_column_separators
             .set_active(settings.boolean("apps-page-show-column-separators"));
+        imp.foreground_boost
+            .set_active(settings.boolean("apps-page-foreground-boost"));
+        if !foreground_boost_supported() {
+            imp.foreground_boost.set_sensitive(false);
+            imp.foreground_boost.set_subtitle(&i18n(
+                "Not available in this session, the focused window can only be detected in X11 sessions",
+            ));
+        }
+
+        imp.refresh_remote_hosts();
+        imp.refresh_profiles();
 
         imp.toggle_group_memory_unit
             .set_active(!settings.boolean("performance-page-memory2-use-bytes") as u32);
@@ -379,6 +1314,11 @@ impl PreferencesPage {
         imp.toggle_group_net_base
             .set_active(settings.boolean("performance-page-network-use-base2") as u32);
 
+        imp.decimal_places
+            .set_value(settings.int("number-decimal-places") as f64);
+        imp.compact_numbers
+            .set_active(settings.boolean("number-compact"));
+
         this
     }
 
@@ -0,0 +1,424 @@
+/* profiles.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+//! Named sets of settings, which can be switched between and shared with other machines.
+//!
+//! A profile holds every setting except those that only make sense on the machine they were
+//! made on, such as the window size, the remote hosts or the fan curves. Profiles are stored,
+//! and exported, as a single JSON object; values are in the GVariant text format, so they keep
+//! their exact type:
+//!
+//! ```text
+//! {"format":"missioncenter-settings","version":1,"name":"Laptop",
+//!  "settings":{"app-update-interval-u64":"uint64 20","performance-smooth-graphs":"true"}}
+//! ```
+//!
+//! Settings a profile doesn't mention are left alone when it is applied, as are those that
+//! don't exist anymore or hold a value this version doesn't accept.
+
+use std::collections::HashMap;
+use std::iter::Peekable;
+use std::str::Chars;
+
+use gtk::{gio, glib, prelude::*};
+
+use crate::export::json_string;
+use crate::i18n::*;
+
+const FORMAT_NAME: &str = "missioncenter-settings";
+const FORMAT_VERSION: u32 = 1;
+
+/// Where the profiles are kept, by name
+const PROFILES_KEY: &str = "settings-profiles";
+
+/// Settings that describe this machine or the state of the window rather than preferences
+const EXCLUDED_KEYS: [&str; 11] = [
+    "window-width",
+    "window-height",
+    "is-maximized",
+    "window-selected-page",
+    "performance-selected-page",
+    "remote-hosts",
+    "metrics-stream-target",
+    "performance-page-fan-curves",
+    "performance-sidebar-labels",
+    "services-page-favorites",
+    PROFILES_KEY,
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Profile {
+    pub name: String,
+    /// Setting names and their values in the GVariant text format
+    values: Vec<(String, String)>,
+}
+
+impl Profile {
+    /// The current value of every setting that goes into a profile
+    pub fn capture(name: &str, settings: &gio::Settings) -> Self {
+        let mut keys = settings
+            .settings_schema()
+            .map(|schema| schema.list_keys())
+            .unwrap_or_default();
+        keys.sort();
+
+        Self {
+            name: name.to_owned(),
+            values: keys
+                .into_iter()
+                .filter(|key| !EXCLUDED_KEYS.contains(&key.as_str()))
+                .map(|key| {
+                    let value = settings.value(&key).print(true).to_string();
+                    (key.to_string(), value)
+                })
+                .collect(),
+        }
+    }
+
+    /// Change the settings all at once, so whatever listens to them sees a consistent set
+    pub fn apply(&self, settings: &gio::Settings) {
+        let Some(schema) = settings.settings_schema() else {
+            return;
+        };
+
+        settings.delay();
+        for (key, value) in &self.values {
+            if EXCLUDED_KEYS.contains(&key.as_str()) || !schema.has_key(key) {
+                continue;
+            }
+
+            let schema_key = schema.key(key);
+            let value = match glib::Variant::parse(Some(&schema_key.value_type()), value) {
+                Ok(value) if schema_key.range_check(&value) => value,
+                _ => {
+                    glib::g_warning!(
+                        "MissionCenter::Profiles",
+                        "Ignoring invalid value {} for {} in profile {}",
+                        value,
+                        key,
+                        self.name
+                    );
+                    continue;
+                }
+            };
+
+            if let Err(e) = settings.set_value(key, &value) {
+                glib::g_warning!(
+                    "MissionCenter::Profiles",
+                    "Failed to set {} from profile {}: {}",
+                    key,
+                    self.name,
+                    e
+                );
+            }
+        }
+        settings.apply();
+    }
+
+    pub fn to_json(&self) -> String {
+        let values = self
+            .values
+            .iter()
+            .map(|(key, value)| format!("{}:{}", json_string(key), json_string(value)))
+            .collect::<Vec<_>>();
+
+        format!(
+            "{{\"format\":{},\"version\":{},\"name\":{},\"settings\":{{{}}}}}\n",
+            json_string(FORMAT_NAME),
+            FORMAT_VERSION,
+            json_string(&self.name),
+            values.join(",")
+        )
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let mut reader = JsonReader::new(json);
+        let document = reader.value()?;
+        reader.end()?;
+
+        let Json::Object(mut document) = document else {
+            return Err(i18n("The file does not hold a settings profile"));
+        };
+        if document.remove("format") != Some(Json::String(FORMAT_NAME.to_owned())) {
+            return Err(i18n("The file does not hold a settings profile"));
+        }
+        match document.remove("version") {
+            Some(Json::Number(version)) if version <= FORMAT_VERSION as f64 => {}
+            _ => {
+                return Err(i18n(
+                    "The profile was made by a newer version of Mission Center",
+                ))
+            }
+        }
+
+        let Some(Json::String(name)) = document.remove("name") else {
+            return Err(i18n("The profile has no name"));
+        };
+        if name.trim().is_empty() {
+            return Err(i18n("The profile has no name"));
+        }
+
+        let Some(Json::Object(settings)) = document.remove("settings") else {
+            return Err(i18n("The profile holds no settings"));
+        };
+        let mut values = settings
+            .into_iter()
+            .map(|(key, value)| match value {
+                Json::String(value) => Ok((key, value)),
+                _ => Err(i18n_f("The value of {} is not a string", &[&key])),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        values.sort();
+
+        Ok(Self {
+            name: name.trim().to_owned(),
+            values,
+        })
+    }
+}
+
+/// The saved profiles, sorted by name
+pub fn saved_profiles(settings: &gio::Settings) -> Vec<Profile> {
+    let mut profiles = settings
+        .value(PROFILES_KEY)
+        .get::<HashMap<String, String>>()
+        .unwrap_or_default()
+        .into_values()
+        .filter_map(|json| match Profile::from_json(&json) {
+            Ok(profile) => Some(profile),
+            Err(e) => {
+                glib::g_warning!(
+                    "MissionCenter::Profiles",
+                    "Ignoring invalid saved profile: {}",
+                    e
+                );
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+    profiles.sort_by(|p1, p2| p1.name.to_lowercase().cmp(&p2.name.to_lowercase()));
+
+    profiles
+}
+
+/// Save `profile`, replacing the one with the same name if there is one
+pub fn save_profile(settings: &gio::Settings, profile: &Profile) -> Result<(), glib::BoolError> {
+    let mut profiles = settings
+        .value(PROFILES_KEY)
+        .get::<HashMap<String, String>>()
+        .unwrap_or_default();
+    profiles.insert(profile.name.clone(), profile.to_json());
+
+    settings.set_value(PROFILES_KEY, &profiles.to_variant())
+}
+
+pub fn remove_profile(settings: &gio::Settings, name: &str) -> Result<(), glib::BoolError> {
+    let mut profiles = settings
+        .value(PROFILES_KEY)
+        .get::<HashMap<String, String>>()
+        .unwrap_or_default();
+    profiles.remove(name);
+
+    settings.set_value(PROFILES_KEY, &profiles.to_variant())
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Json {
+    Number(f64),
+    String(String),
+    Object(HashMap<String, Json>),
+    /// Arrays, booleans and `null`, which profiles don't use
+    Other,
+}
+
+/// Just enough of JSON to read profiles back, including those edited by hand
+struct JsonReader<'a> {
+    chars: Peekable<Chars<'a>>,
+}
+
+impl<'a> JsonReader<'a> {
+    fn new(json: &'a str) -> Self {
+        Self {
+            chars: json.chars().peekable(),
+        }
+    }
+
+    fn end(&mut self) -> Result<(), String> {
+        self.skip_whitespace();
+        match self.chars.next() {
+            None => Ok(()),
+            Some(c) => Err(format!("Unexpected '{}' after the end of the profile", c)),
+        }
+    }
+
+    fn value(&mut self) -> Result<Json, String> {
+        self.skip_whitespace();
+        match self.chars.peek() {
+            Some('{') => self.object(),
+            Some('[') => self.array(),
+            Some('"') => self.string().map(Json::String),
+            Some('t') => self.literal("true"),
+            Some('f') => self.literal("false"),
+            Some('n') => self.literal("null"),
+            Some(c) if *c == '-' || c.is_ascii_digit() => self.number(),
+            Some(c) => Err(format!("Unexpected '{}'", c)),
+            None => Err("Unexpected end of the profile".to_owned()),
+        }
+    }
+
+    fn object(&mut self) -> Result<Json, String> {
+        self.expect('{')?;
+
+        let mut result = HashMap::new();
+        self.skip_whitespace();
+        if self.chars.next_if_eq(&'}').is_some() {
+            return Ok(Json::Object(result));
+        }
+
+        loop {
+            self.skip_whitespace();
+            let key = self.string()?;
+            self.skip_whitespace();
+            self.expect(':')?;
+            let value = self.value()?;
+            result.insert(key, value);
+
+            self.skip_whitespace();
+            match self.chars.next() {
+                Some(',') => {}
+                Some('}') => return Ok(Json::Object(result)),
+                _ => return Err("Expected ',' or '}'".to_owned()),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<Json, String> {
+        self.expect('[')?;
+
+        self.skip_whitespace();
+        if self.chars.next_if_eq(&']').is_some() {
+            return Ok(Json::Other);
+        }
+
+        loop {
+            self.value()?;
+
+            self.skip_whitespace();
+            match self.chars.next() {
+                Some(',') => {}
+                Some(']') => return Ok(Json::Other),
+                _ => return Err("Expected ',' or ']'".to_owned()),
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        self.expect('"')?;
+
+        let mut result = String::new();
+        loop {
+            match self.chars.next() {
+                Some('"') => return Ok(result),
+                Some('\\') => match self.chars.next() {
+                    Some('"') => result.push('"'),
+                    Some('\\') => result.push('\\'),
+                    Some('/') => result.push('/'),
+                    Some('b') => result.push('\u{8}'),
+                    Some('f') => result.push('\u{c}'),
+                    Some('n') => result.push('\n'),
+                    Some('r') => result.push('\r'),
+                    Some('t') => result.push('\t'),
+                    Some('u') => result.push(self.unicode_escape()?),
+                    _ => return Err("Invalid escape sequence".to_owned()),
+                },
+                Some(c) => result.push(c),
+                None => return Err("Unterminated string".to_owned()),
+            }
+        }
+    }
+
+    /// The code point after `\u`, which takes two escapes outside the Basic Multilingual Plane
+    fn unicode_escape(&mut self) -> Result<char, String> {
+        let high = self.hex4()?;
+        if !(0xD800..0xDC00).contains(&high) {
+            return char::from_u32(high).ok_or_else(|| "Invalid escape sequence".to_owned());
+        }
+
+        if self.chars.next() != Some('\\') || self.chars.next() != Some('u') {
+            return Err("Invalid escape sequence".to_owned());
+        }
+        let low = self.hex4()?;
+        if !(0xDC00..0xE000).contains(&low) {
+            return Err("Invalid escape sequence".to_owned());
+        }
+
+        char::from_u32(0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00))
+            .ok_or_else(|| "Invalid escape sequence".to_owned())
+    }
+
+    fn hex4(&mut self) -> Result<u32, String> {
+        let mut result = 0;
+        for _ in 0..4 {
+            let digit = self
+                .chars
+                .next()
+                .and_then(|c| c.to_digit(16))
+                .ok_or_else(|| "Invalid escape sequence".to_owned())?;
+            result = result * 16 + digit;
+        }
+
+        Ok(result)
+    }
+
+    fn number(&mut self) -> Result<Json, String> {
+        let mut number = String::new();
+        while let Some(c) = self
+            .chars
+            .next_if(|c| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E'))
+        {
+            number.push(c);
+        }
+
+        number
+            .parse::<f64>()
+            .map(Json::Number)
+            .map_err(|_| format!("Invalid number {}", number))
+    }
+
+    fn literal(&mut self, literal: &str) -> Result<Json, String> {
+        for expected in literal.chars() {
+            self.expect(expected)?;
+        }
+
+        Ok(Json::Other)
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        match self.chars.next() {
+            Some(c) if c == expected => Ok(()),
+            Some(c) => Err(format!("Expected '{}', found '{}'", expected, c)),
+            None => Err("Unexpected end of the profile".to_owned()),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|c| c.is_ascii_whitespace()).is_some() {}
+    }
+}
@@ -0,0 +1,419 @@
+/* report.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+//! A single document describing the machine and what it is doing, to attach to support requests.
+//!
+//! Like the Markdown copy of the system information, the report is in English whatever the
+//! language of the UI, since it usually ends up in front of someone else; values are formatted
+//! with the units picked in the preferences.
+
+use gtk::{gio, glib, prelude::*};
+
+use crate::magpie_client::Readings;
+use crate::widgets::system_info_sections;
+use crate::{app, i18n::*, settings, DataType};
+
+/// How many of the busiest processes are listed
+const TOP_PROCESS_COUNT: usize = 10;
+
+enum Block {
+    Properties(Vec<(String, String)>),
+    Table {
+        header: Vec<&'static str>,
+        rows: Vec<Vec<String>>,
+    },
+    Text(String),
+}
+
+struct Section {
+    title: &'static str,
+    blocks: Vec<Block>,
+}
+
+pub struct Report {
+    generated: String,
+    sections: Vec<Section>,
+}
+
+impl Report {
+    /// Collect everything from `readings`; the SMART data of every drive is requested on the spot,
+    /// which takes a moment
+    pub fn new(readings: &Readings) -> Self {
+        let mut sections = system_info_sections(readings)
+            .into_iter()
+            .map(|(title, entries)| Section {
+                title,
+                blocks: vec![Block::Properties(
+                    entries
+                        .into_iter()
+                        .map(|(label, value)| (label.to_owned(), value))
+                        .collect(),
+                )],
+            })
+            .collect::<Vec<_>>();
+
+        sections.push(current_usage(readings));
+        sections.push(drive_health(readings));
+        sections.push(top_processes(readings));
+        sections.push(services(readings));
+
+        Self {
+            generated: glib::DateTime::now_local()
+                .and_then(|now| now.format("%F %T %Z"))
+                .map(|now| now.to_string())
+                .unwrap_or_default(),
+            sections,
+        }
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut result = format!("# System Report\n\nGenerated {}\n", self.generated);
+
+        for section in &self.sections {
+            result.push_str(&format!("\n## {}\n", section.title));
+
+            for block in &section.blocks {
+                result.push('\n');
+                match block {
+                    Block::Properties(properties) => {
+                        for (label, value) in properties {
+                            result.push_str(&format!("- **{}:** {}\n", label, value));
+                        }
+                    }
+                    Block::Table { header, rows } => {
+                        result.push_str(&format!("| {} |\n", header.join(" | ")));
+                        result.push_str(&format!("|{}\n", " --- |".repeat(header.len())));
+                        for row in rows {
+                            let cells = row
+                                .iter()
+                                .map(|cell| cell.replace('|', "\\|"))
+                                .collect::<Vec<_>>();
+                            result.push_str(&format!("| {} |\n", cells.join(" | ")));
+                        }
+                    }
+                    Block::Text(text) => {
+                        result.push_str(text);
+                        result.push('\n');
+                    }
+                }
+            }
+        }
+
+        result
+    }
+
+    pub fn to_html(&self) -> String {
+        let escape = |text: &str| glib::markup_escape_text(text).to_string();
+
+        let mut result = String::from(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+             <title>System Report</title>\n<style>\n\
+             body { font-family: sans-serif; margin: 2em; }\n\
+             table { border-collapse: collapse; }\n\
+             th, td { border: 1px solid #ccc; padding: 0.25em 0.75em; text-align: left; }\n\
+             </style>\n</head>\n<body>\n<h1>System Report</h1>\n",
+        );
+        result.push_str(&format!("<p>Generated {}</p>\n", escape(&self.generated)));
+
+        for section in &self.sections {
+            result.push_str(&format!("<h2>{}</h2>\n", escape(section.title)));
+
+            for block in &section.blocks {
+                match block {
+                    Block::Properties(properties) => {
+                        result.push_str("<ul>\n");
+                        for (label, value) in properties {
+                            result.push_str(&format!(
+                                "<li><strong>{}:</strong> {}</li>\n",
+                                escape(label),
+                                escape(value)
+                            ));
+                        }
+                        result.push_str("</ul>\n");
+                    }
+                    Block::Table { header, rows } => {
+                        result.push_str("<table>\n<tr>");
+                        for cell in header {
+                            result.push_str(&format!("<th>{}</th>", escape(cell)));
+                        }
+                        result.push_str("</tr>\n");
+                        for row in rows {
+                            result.push_str("<tr>");
+                            for cell in row {
+                                result.push_str(&format!("<td>{}</td>", escape(cell)));
+                            }
+                            result.push_str("</tr>\n");
+                        }
+                        result.push_str("</table>\n");
+                    }
+                    Block::Text(text) => {
+                        result.push_str(&format!("<p>{}</p>\n", escape(text)));
+                    }
+                }
+            }
+        }
+
+        result.push_str("</body>\n</html>\n");
+
+        result
+    }
+
+    /// Ask where to save the report, as HTML or as Markdown depending on the file name
+    pub fn save(self, parent: &impl IsA<gtk::Window>) {
+        let html_filter = gtk::FileFilter::new();
+        html_filter.set_name(Some(&i18n("HTML Document")));
+        html_filter.add_suffix("html");
+        let markdown_filter = gtk::FileFilter::new();
+        markdown_filter.set_name(Some(&i18n("Markdown Document")));
+        markdown_filter.add_suffix("md");
+
+        let filters = gio::ListStore::new::<gtk::FileFilter>();
+        filters.append(&html_filter);
+        filters.append(&markdown_filter);
+
+        let dialog = gtk::FileDialog::builder()
+            .title(i18n("Generate Report"))
+            .initial_name(format!("system-report-{}.html", glib::host_name()))
+            .filters(&filters)
+            .default_filter(&html_filter)
+            .modal(true)
+            .build();
+        dialog.save(Some(parent), None::<&gio::Cancellable>, move |result| {
+            let Some(path) = result.ok().and_then(|file| file.path()) else {
+                return;
+            };
+
+            let is_markdown = path.extension().is_some_and(|extension| {
+                extension.eq_ignore_ascii_case("md") || extension.eq_ignore_ascii_case("markdown")
+            });
+            let content = if is_markdown {
+                self.to_markdown()
+            } else {
+                self.to_html()
+            };
+
+            if let Err(e) = std::fs::write(&path, content.as_bytes()) {
+                glib::g_warning!(
+                    "MissionCenter::Report",
+                    "Failed to save the report to {}: {}",
+                    path.display(),
+                    e
+                );
+            }
+        });
+    }
+}
+
+fn current_usage(readings: &Readings) -> Section {
+    let settings = settings!();
+    let bytes =
+        |value: u64| crate::to_human_readable_nice(value as f32, &DataType::MemoryBytes, &settings);
+
+    let mem_info = &readings.mem_info;
+    let mut properties = vec![
+        (
+            "CPU".to_owned(),
+            format!("{:.1}%", readings.cpu.total_usage_percent),
+        ),
+        (
+            "Memory".to_owned(),
+            format!(
+                "{} of {}",
+                bytes(mem_info.mem_total.saturating_sub(mem_info.mem_available)),
+                bytes(mem_info.mem_total)
+            ),
+        ),
+        (
+            "Swap".to_owned(),
+            format!(
+                "{} of {}",
+                bytes(mem_info.swap_total.saturating_sub(mem_info.swap_free)),
+                bytes(mem_info.swap_total)
+            ),
+        ),
+    ];
+
+    let mut gpus = readings.gpus.values().collect::<Vec<_>>();
+    gpus.sort_by(|g1, g2| g1.id.cmp(&g2.id));
+    for gpu in gpus {
+        if let Some(utilization) = gpu.utilization_percent {
+            properties.push((
+                format!("GPU {}", gpu.device_name.as_ref().unwrap_or(&gpu.id)),
+                format!("{:.1}%", utilization),
+            ));
+        }
+    }
+
+    let drive_speed = |value: f32| {
+        crate::to_human_readable_nice(value, &DataType::DriveBytesPerSecond, &settings)
+    };
+    let drives = Block::Table {
+        header: vec!["Drive", "Busy", "Read", "Write"],
+        rows: readings
+            .disks_info
+            .iter()
+            .map(|disk| {
+                vec![
+                    disk.id.clone(),
+                    format!("{:.0}%", disk.busy_percent),
+                    drive_speed(disk.rx_speed_bytes_ps as f32),
+                    drive_speed(disk.tx_speed_bytes_ps as f32),
+                ]
+            })
+            .collect(),
+    };
+
+    let network_speed = |value: f32| {
+        crate::to_human_readable_nice(value, &DataType::NetworkBytesPerSecond, &settings)
+    };
+    let network = Block::Table {
+        header: vec!["Network", "Receive", "Send"],
+        rows: readings
+            .network_connections
+            .iter()
+            .map(|connection| {
+                vec![
+                    connection.id.clone(),
+                    network_speed(connection.rx_rate_bytes_ps),
+                    network_speed(connection.tx_rate_bytes_ps),
+                ]
+            })
+            .collect(),
+    };
+
+    Section {
+        title: "Current Usage",
+        blocks: vec![Block::Properties(properties), drives, network],
+    }
+}
+
+fn drive_health(readings: &Readings) -> Section {
+    let app = app!();
+    let magpie = app.sys_info().ok();
+
+    let rows = readings
+        .disks_info
+        .iter()
+        .filter(|disk| disk.smart_interface.is_some())
+        .map(|disk| {
+            match magpie
+                .as_ref()
+                .and_then(|magpie| magpie.smart_data(disk.id.clone()))
+            {
+                Some(smart_data) => vec![
+                    disk.id.clone(),
+                    format!("{:?}", smart_data.test_result()),
+                    crate::to_long_human_readable_time(smart_data.powered_on_seconds),
+                ],
+                None => vec![disk.id.clone(), "Unavailable".to_owned(), String::new()],
+            }
+        })
+        .collect::<Vec<_>>();
+
+    Section {
+        title: "Drive Health",
+        blocks: vec![if rows.is_empty() {
+            Block::Text("No drive reports SMART data".to_owned())
+        } else {
+            Block::Table {
+                header: vec!["Drive", "SMART Status", "Powered On"],
+                rows,
+            }
+        }],
+    }
+}
+
+fn top_processes(readings: &Readings) -> Section {
+    let settings = settings!();
+
+    let mut processes = readings.running_processes.values().collect::<Vec<_>>();
+    processes.sort_unstable_by(|p1, p2| {
+        p2.usage_stats
+            .cpu_usage
+            .total_cmp(&p1.usage_stats.cpu_usage)
+            .then(
+                p2.usage_stats
+                    .memory_usage
+                    .cmp(&p1.usage_stats.memory_usage),
+            )
+    });
+
+    Section {
+        title: "Top Processes",
+        blocks: vec![Block::Table {
+            header: vec!["Name", "PID", "CPU", "Memory"],
+            rows: processes
+                .into_iter()
+                .take(TOP_PROCESS_COUNT)
+                .map(|process| {
+                    vec![
+                        process.name.clone(),
+                        process.pid.to_string(),
+                        format!("{:.1}%", process.usage_stats.cpu_usage),
+                        crate::to_human_readable_nice(
+                            process.usage_stats.memory_usage as f32,
+                            &DataType::MemoryBytes,
+                            &settings,
+                        ),
+                    ]
+                })
+                .collect(),
+        }],
+    }
+}
+
+fn services(readings: &Readings) -> Section {
+    let running = readings
+        .services
+        .values()
+        .filter(|service| service.running)
+        .count();
+
+    let mut failed = readings
+        .services
+        .values()
+        .filter(|service| service.failed)
+        .collect::<Vec<_>>();
+    failed.sort_by(|s1, s2| s1.id.cmp(&s2.id));
+
+    let mut blocks = vec![Block::Properties(vec![
+        ("Running".to_owned(), running.to_string()),
+        ("Failed".to_owned(), failed.len().to_string()),
+    ])];
+    if !failed.is_empty() {
+        blocks.push(Block::Table {
+            header: vec!["Failed Service", "Description"],
+            rows: failed
+                .into_iter()
+                .map(|service| {
+                    vec![
+                        service.id.clone(),
+                        service.description.clone().unwrap_or_default(),
+                    ]
+                })
+                .collect(),
+        });
+    }
+
+    Section {
+        title: "Services",
+        blocks,
+    }
+}
@@ -18,16 +18,31 @@
  * SPDX-License-Identifier: GPL-3.0-or-later
  */
 
-use std::cell::{Cell, OnceCell};
+use std::cell::{Cell, OnceCell, RefCell};
+use std::collections::HashMap;
 use std::num::NonZeroU32;
 
 use adw::{prelude::*, subclass::prelude::*};
 use gtk::glib::{self, g_warning, ParamSpec, Properties, SignalHandlerId, Value};
 
-use crate::{app, i18n::*};
+use crate::{
+    app,
+    i18n::*,
+    magpie_client::{
+        Pid, SandboxKind, ServiceLogEntry, ServiceProcess, StopOutcome, PRIORITY_DEBUG,
+        PRIORITY_ERROR, PRIORITY_WARNING,
+    },
+};
 
 use super::services_list_item::ServicesListItem;
 
+/// Syslog priority of informational messages, the least severe ones short of debug output
+const PRIORITY_INFO: u8 = 6;
+/// Enough to go back a while for a chatty service without slowing the dialog down
+const LOG_LINES: u32 = 1000;
+/// How far each level of the process tree is indented
+const PROCESS_TREE_INDENT: i32 = 24;
+
 mod imp {
     use super::*;
 
@@ -60,12 +75,33 @@ mod imp {
         #[template_child]
         label_group: TemplateChild<gtk::Label>,
 
+        #[template_child]
+        group_processes: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        refresh_processes: TemplateChild<gtk::Button>,
+
         #[template_child]
         logs_overlay: TemplateChild<gtk::Overlay>,
         #[template_child]
         logs_expander: TemplateChild<gtk::Expander>,
         #[template_child]
         logs_buffer: TemplateChild<gtk::TextBuffer>,
+        #[template_child]
+        logs_view: TemplateChild<gtk::TextView>,
+        #[template_child]
+        logs_severity: TemplateChild<gtk::Box>,
+        #[template_child]
+        logs_errors: TemplateChild<gtk::ToggleButton>,
+        #[template_child]
+        logs_warnings: TemplateChild<gtk::ToggleButton>,
+        #[template_child]
+        logs_info: TemplateChild<gtk::ToggleButton>,
+        #[template_child]
+        logs_all: TemplateChild<gtk::ToggleButton>,
+        #[template_child]
+        logs_search: TemplateChild<gtk::SearchEntry>,
+        #[template_child]
+        logs_empty: TemplateChild<gtk::Label>,
 
         pub list_item: OnceCell<ServicesListItem>,
 
@@ -73,6 +109,9 @@ mod imp {
         pub enabled: Cell<bool>,
 
         copy_logs_button: gtk::Button,
+        /// The least severe messages shown in the logs
+        log_priority: Cell<u8>,
+        process_rows: RefCell<Vec<adw::ActionRow>>,
 
         list_item_running_notify: Cell<u64>,
         list_item_enabled_notify: Cell<u64>,
@@ -95,15 +134,28 @@ mod imp {
                 label_user: TemplateChild::default(),
                 label_group: TemplateChild::default(),
 
+                group_processes: TemplateChild::default(),
+                refresh_processes: TemplateChild::default(),
+
                 logs_overlay: TemplateChild::default(),
                 logs_expander: TemplateChild::default(),
                 logs_buffer: TemplateChild::default(),
+                logs_view: TemplateChild::default(),
+                logs_severity: TemplateChild::default(),
+                logs_errors: TemplateChild::default(),
+                logs_warnings: TemplateChild::default(),
+                logs_info: TemplateChild::default(),
+                logs_all: TemplateChild::default(),
+                logs_search: TemplateChild::default(),
+                logs_empty: TemplateChild::default(),
 
                 list_item: OnceCell::new(),
 
                 enabled: Cell::new(false),
 
                 copy_logs_button: gtk::Button::new(),
+                log_priority: Cell::new(PRIORITY_DEBUG),
+                process_rows: RefCell::new(vec![]),
 
                 list_item_running_notify: Cell::new(0),
                 list_item_enabled_notify: Cell::new(0),
@@ -116,6 +168,281 @@ mod imp {
         fn list_item(&self) -> ServicesListItem {
             unsafe { self.list_item.get().unwrap_unchecked().clone() }
         }
+
+        fn load_processes(&self) {
+            let list_item = self.list_item();
+
+            let processes = match app!()
+                .sys_info()
+                .map(|sys_info| sys_info.service_processes(list_item.name().to_string()))
+            {
+                Ok(processes) => processes,
+                Err(e) => {
+                    g_warning!(
+                        "MissionCenter::DetailsDialog",
+                        "Failed to get `sys_info`: {}",
+                        e
+                    );
+                    return;
+                }
+            };
+
+            for row in self.process_rows.take() {
+                self.group_processes.remove(&row);
+            }
+
+            let processes = match processes {
+                Ok(processes) => processes,
+                Err(e) => {
+                    g_warning!(
+                        "MissionCenter::DetailsDialog",
+                        "Failed to list the processes of {}: {}",
+                        list_item.name(),
+                        e
+                    );
+                    self.group_processes.set_visible(false);
+                    return;
+                }
+            };
+
+            let mut children = HashMap::<Option<Pid>, Vec<&ServiceProcess>>::new();
+            for process in &processes {
+                children.entry(process.parent).or_default().push(process);
+            }
+            for processes in children.values_mut() {
+                processes.sort_by_key(|process| process.pid);
+            }
+
+            let mut rows = vec![];
+            let mut pending = children
+                .get(&None)
+                .map(|roots| roots.iter().rev().map(|process| (*process, 0)).collect())
+                .unwrap_or_else(Vec::new);
+            while let Some((process, depth)) = pending.pop() {
+                rows.push(self.process_row(process, depth));
+                if let Some(processes) = children.get(&Some(process.pid)) {
+                    pending.extend(processes.iter().rev().map(|process| (*process, depth + 1)));
+                }
+            }
+
+            for row in &rows {
+                self.group_processes.add(row);
+            }
+            self.group_processes.set_visible(!rows.is_empty());
+            self.process_rows.replace(rows);
+        }
+
+        fn process_row(&self, process: &ServiceProcess, depth: i32) -> adw::ActionRow {
+            let row = adw::ActionRow::builder()
+                .title(&process.command_line)
+                .title_lines(1)
+                .subtitle(i18n_f("PID {}", &[&process.pid.to_string()]))
+                .use_markup(false)
+                .build();
+
+            if depth > 0 {
+                let indent = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+                indent.set_width_request(depth * PROCESS_TREE_INDENT);
+                row.add_prefix(&indent);
+            }
+
+            let terminate = gtk::Button::from_icon_name("media-playback-stop-symbolic");
+            terminate.add_css_class("flat");
+            terminate.set_valign(gtk::Align::Center);
+            terminate.set_tooltip_text(Some(&i18n("Terminate process")));
+            terminate.connect_clicked({
+                let this = self.obj().downgrade();
+                let pid = process.pid;
+                move |_| {
+                    if let Some(this) = this.upgrade() {
+                        this.imp().terminate(pid);
+                    }
+                }
+            });
+            row.add_suffix(&terminate);
+
+            row
+        }
+
+        fn terminate(&self, pid: Pid) {
+            let sys_info = match app!().sys_info() {
+                Ok(sys_info) => sys_info,
+                Err(e) => {
+                    g_warning!(
+                        "MissionCenter::DetailsDialog",
+                        "Failed to get `sys_info`: {}",
+                        e
+                    );
+                    return;
+                }
+            };
+
+            let this = glib::SendWeakRef::from(self.obj().downgrade());
+            sys_info.stop_processes(vec![pid], false, move |outcomes| {
+                let Some(this) = this.upgrade() else {
+                    return;
+                };
+
+                if let Some((_, StopOutcome::Failed(e))) = outcomes.into_iter().next() {
+                    let dialog = adw::AlertDialog::new(
+                        Some(&i18n_f("Can't Terminate Process {}", &[&pid.to_string()])),
+                        Some(&e),
+                    );
+                    dialog.add_responses(&[("close", &i18n("_Close"))]);
+                    dialog.present(Some(&this));
+                }
+
+                this.imp().load_processes();
+            });
+        }
+
+        fn load_logs(&self) {
+            let list_item = self.list_item();
+
+            let logs = match app!().sys_info().map(|sys_info| {
+                sys_info.service_logs(
+                    list_item.name().to_string(),
+                    NonZeroU32::new(list_item.pid().parse::<u32>().unwrap_or(0)),
+                    self.log_priority.get(),
+                    LOG_LINES,
+                )
+            }) {
+                Ok(logs) => logs,
+                Err(e) => {
+                    g_warning!(
+                        "MissionCenter::DetailsDialog",
+                        "Failed to get `sys_info`: {}",
+                        e
+                    );
+                    return;
+                }
+            };
+
+            match logs {
+                Ok(entries) if entries.is_empty() => {
+                    self.logs_buffer.set_text("");
+                    self.show_logs_message(&i18n("No log messages match the selected severity"));
+                }
+                Ok(entries) => {
+                    self.show_entries(&entries);
+                    self.logs_empty.set_visible(false);
+                    self.logs_overlay.set_visible(true);
+                    // Logs that only came as text can't be told apart by severity
+                    self.logs_severity
+                        .set_sensitive(entries.iter().all(|entry| entry.priority.is_some()));
+                    self.logs_expander.set_visible(true);
+
+                    self.highlight_matches();
+                }
+                Err(e) => {
+                    g_warning!(
+                        "MissionCenter::DetailsDialog",
+                        "Failed to read the logs of {}: {}",
+                        list_item.name(),
+                        e
+                    );
+                    self.logs_buffer.set_text("");
+                    self.show_logs_message(&e);
+                }
+            }
+        }
+
+        fn show_logs_message(&self, message: &str) {
+            self.logs_empty.set_text(message);
+            self.logs_empty.set_visible(true);
+            self.logs_overlay.set_visible(false);
+        }
+
+        fn show_entries(&self, entries: &[ServiceLogEntry]) {
+            let buffer = &*self.logs_buffer;
+            buffer.set_text("");
+
+            let mut end = buffer.end_iter();
+            for entry in entries {
+                if let Some(timestamp) = entry.timestamp.and_then(|timestamp| {
+                    glib::DateTime::from_unix_local(timestamp as i64 / 1_000_000)
+                        .and_then(|dt| dt.format("%b %d %H:%M:%S"))
+                        .ok()
+                }) {
+                    buffer.insert_with_tags_by_name(&mut end, &timestamp, &["timestamp"]);
+                    buffer.insert(&mut end, " ");
+                }
+
+                let tag = match entry.priority {
+                    Some(priority) if priority <= PRIORITY_ERROR => "error",
+                    Some(PRIORITY_WARNING) => "warning",
+                    _ => "",
+                };
+                let line = format!("{}\n", entry.message);
+                if tag.is_empty() {
+                    buffer.insert(&mut end, &line);
+                } else {
+                    buffer.insert_with_tags_by_name(&mut end, &line, &[tag]);
+                }
+            }
+        }
+
+        /// Mark every match of the search text and go to the first one
+        fn highlight_matches(&self) {
+            let buffer = &*self.logs_buffer;
+            let (start, end) = buffer.bounds();
+            buffer.remove_tag_by_name("match", &start, &end);
+            self.logs_search.remove_css_class("error");
+
+            let text = self.logs_search.text();
+            if text.is_empty() {
+                return;
+            }
+
+            let mut first = None;
+            let mut from = start;
+            while let Some((match_start, match_end)) =
+                from.forward_search(&text, gtk::TextSearchFlags::CASE_INSENSITIVE, None)
+            {
+                buffer.apply_tag_by_name("match", &match_start, &match_end);
+                if first.is_none() {
+                    first = Some((match_start, match_end.clone()));
+                }
+                from = match_end;
+            }
+
+            match first {
+                Some((mut match_start, match_end)) => {
+                    buffer.select_range(&match_start, &match_end);
+                    self.logs_view
+                        .scroll_to_iter(&mut match_start, 0.1, false, 0., 0.);
+                }
+                None => self.logs_search.add_css_class("error"),
+            }
+        }
+
+        /// Go to the match after, or before, the current one, wrapping around at the ends
+        fn select_match(&self, forward: bool) {
+            let text = self.logs_search.text();
+            if text.is_empty() {
+                return;
+            }
+
+            let buffer = &*self.logs_buffer;
+            let flags = gtk::TextSearchFlags::CASE_INSENSITIVE;
+            let mut cursor = buffer.iter_at_mark(&buffer.get_insert());
+            let found = if forward {
+                cursor.forward_char();
+                cursor
+                    .forward_search(&text, flags, None)
+                    .or_else(|| buffer.start_iter().forward_search(&text, flags, None))
+            } else {
+                cursor
+                    .backward_search(&text, flags, None)
+                    .or_else(|| buffer.end_iter().backward_search(&text, flags, None))
+            };
+
+            if let Some((mut match_start, match_end)) = found {
+                buffer.select_range(&match_start, &match_end);
+                self.logs_view
+                    .scroll_to_iter(&mut match_start, 0.1, false, 0., 0.);
+            }
+        }
     }
 
     #[glib::object_subclass]
@@ -189,7 +516,7 @@ mod imp {
                 }
             });
 
-            self.copy_logs_button.set_margin_top(14);
+            self.copy_logs_button.set_margin_top(2);
             self.copy_logs_button.set_margin_end(2);
             self.copy_logs_button.set_valign(gtk::Align::Start);
             self.copy_logs_button.set_halign(gtk::Align::End);
@@ -213,6 +540,84 @@ mod imp {
             });
 
             self.logs_overlay.add_overlay(&self.copy_logs_button);
+
+            self.refresh_processes.connect_clicked({
+                let this = self.obj().downgrade();
+                move |_| {
+                    if let Some(this) = this.upgrade() {
+                        this.imp().load_processes();
+                    }
+                }
+            });
+
+            self.logs_buffer
+                .create_tag(Some("timestamp"), &[("foreground", &"#77767b")]);
+            self.logs_buffer.create_tag(
+                Some("error"),
+                &[("foreground", &"#e01b24"), ("weight", &700)],
+            );
+            self.logs_buffer
+                .create_tag(Some("warning"), &[("foreground", &"#c88800")]);
+            self.logs_buffer.create_tag(
+                Some("match"),
+                &[("background", &"#f6d32d"), ("foreground", &"#000000")],
+            );
+
+            self.logs_all.set_active(true);
+            for (button, priority) in [
+                (&*self.logs_errors, PRIORITY_ERROR),
+                (&*self.logs_warnings, PRIORITY_WARNING),
+                (&*self.logs_info, PRIORITY_INFO),
+                (&*self.logs_all, PRIORITY_DEBUG),
+            ] {
+                button.connect_toggled({
+                    let this = self.obj().downgrade();
+                    move |button| {
+                        // Also emitted for the button that was let go of
+                        if !button.is_active() {
+                            return;
+                        }
+                        if let Some(this) = this.upgrade() {
+                            let this = this.imp();
+                            this.log_priority.set(priority);
+                            this.load_logs();
+                        }
+                    }
+                });
+            }
+
+            self.logs_search.connect_search_changed({
+                let this = self.obj().downgrade();
+                move |_| {
+                    if let Some(this) = this.upgrade() {
+                        this.imp().highlight_matches();
+                    }
+                }
+            });
+            self.logs_search.connect_activate({
+                let this = self.obj().downgrade();
+                move |_| {
+                    if let Some(this) = this.upgrade() {
+                        this.imp().select_match(true);
+                    }
+                }
+            });
+            self.logs_search.connect_next_match({
+                let this = self.obj().downgrade();
+                move |_| {
+                    if let Some(this) = this.upgrade() {
+                        this.imp().select_match(true);
+                    }
+                }
+            });
+            self.logs_search.connect_previous_match({
+                let this = self.obj().downgrade();
+                move |_| {
+                    if let Some(this) = this.upgrade() {
+                        this.imp().select_match(false);
+                    }
+                }
+            });
         }
     }
 
@@ -241,6 +646,15 @@ mod imp {
             self.label_running.set_text(&running);
             self.switch_enabled.set_active(list_item.enabled());
 
+            // Background Flatpak apps are listed with the services, but have no unit to act on
+            if list_item.source().as_str()
+                == crate::services_page::source(Some(SandboxKind::Flatpak))
+            {
+                self.switch_enabled.set_visible(false);
+                self.box_buttons.set_visible(false);
+                self.restart.set_visible(false);
+            }
+
             let mut group_empty = true;
             let pid = list_item.pid();
             if !pid.is_empty() {
@@ -272,28 +686,8 @@ mod imp {
                 self.group_process.set_visible(true);
             }
 
-            let logs = app!().sys_info().and_then(|sys_info| {
-                Ok(sys_info.service_logs(
-                    list_item.name().to_string(),
-                    NonZeroU32::new(pid.parse::<u32>().unwrap_or(0)),
-                ))
-            });
-
-            match logs {
-                Ok(logs) => {
-                    if !logs.is_empty() {
-                        self.logs_buffer.set_text(&logs);
-                        self.logs_expander.set_visible(true);
-                    }
-                }
-                Err(e) => {
-                    g_warning!(
-                        "MissionCenter::DetailsDialog",
-                        "Failed to get `sys_info`: {}",
-                        e
-                    );
-                }
-            }
+            self.load_processes();
+            self.load_logs();
 
             let notify = list_item.connect_running_notify({
                 let this = self.obj().downgrade();
@@ -308,6 +702,8 @@ mod imp {
                             i18n("Stopped")
                         };
                         this.label_running.set_text(&text);
+
+                        this.load_processes();
                     }
                 }
             });
@@ -20,7 +20,7 @@
 
 use std::cell::Cell;
 
-use adw::prelude::AdwDialogExt;
+use adw::prelude::{AdwDialogExt, AlertDialogExt};
 use gtk::{
     gdk, gio,
     glib::{
@@ -33,16 +33,21 @@ use gtk::{
 };
 
 use details_dialog::DetailsDialog;
+use override_dialog::OverrideDialog;
 use services_list_item::{ServicesListItem, ServicesListItemBuilder};
+use start_type_cell::StartTypeCell;
 
 use crate::{
     app,
     i18n::*,
-    magpie_client::{MagpieClient, Readings},
+    magpie_client::{MagpieClient, Readings, SandboxKind, Service, StartType},
+    window::RecentItem,
 };
 
 mod details_dialog;
+mod override_dialog;
 mod services_list_item;
+mod start_type_cell;
 
 mod imp {
     use super::*;
@@ -114,12 +119,17 @@ mod imp {
         #[template_child]
         name_column: TemplateChild<gtk::ColumnViewColumn>,
         #[template_child]
+        source_column: TemplateChild<gtk::ColumnViewColumn>,
+        #[template_child]
         description_column: TemplateChild<gtk::ColumnViewColumn>,
         #[template_child]
+        favorites: TemplateChild<gtk::MenuButton>,
+        #[template_child]
         context_menu: TemplateChild<gtk::PopoverMenu>,
 
         pub model: gio::ListStore,
         pub actions: Cell<Actions>,
+        favorite_action: gio::SimpleAction,
     }
 
     impl Default for ServicesPage {
@@ -136,7 +146,9 @@ mod imp {
                 restart_label: TemplateChild::default(),
                 details_label: TemplateChild::default(),
                 name_column: TemplateChild::default(),
+                source_column: TemplateChild::default(),
                 description_column: TemplateChild::default(),
+                favorites: TemplateChild::default(),
                 context_menu: TemplateChild::default(),
 
                 model: gio::ListStore::new::<ServicesListItem>(),
@@ -145,6 +157,11 @@ mod imp {
                     stop: gio::SimpleAction::new("selected-svc-stop", None),
                     restart: gio::SimpleAction::new("selected-svc-restart", None),
                 }),
+                favorite_action: gio::SimpleAction::new_stateful(
+                    "favorite",
+                    None,
+                    &false.to_variant(),
+                ),
             }
         }
     }
@@ -153,6 +170,27 @@ mod imp {
         pub fn actions(&self) -> &Actions {
             unsafe { &*self.actions.as_ptr() }
         }
+
+        pub fn update_favorite_action(&self, selected: Option<&ServicesListItem>) {
+            let is_favorite = selected.is_some_and(|item| {
+                app!()
+                    .favorite_services()
+                    .iter()
+                    .any(|service| service.as_str() == item.name().as_str())
+            });
+            self.favorite_action.set_enabled(selected.is_some());
+            self.favorite_action.set_state(&is_favorite.to_variant());
+        }
+
+        pub fn update_service_actions(&self, selected: &ServicesListItem) {
+            // Background Flatpak apps aren't units, there is nothing systemd can do with them
+            let is_unit = selected.source().as_str() != source(Some(SandboxKind::Flatpak));
+            let running = selected.running();
+
+            self.actions().stop.set_enabled(is_unit && running);
+            self.actions().start.set_enabled(is_unit && !running);
+            self.actions().restart.set_enabled(is_unit && running);
+        }
     }
 
     impl ServicesPage {
@@ -169,6 +207,7 @@ mod imp {
             self.name_column.set_fixed_width(1);
             self.name_column.set_expand(true);
             self.name_column.set_resizable(false);
+            self.source_column.set_visible(false);
             self.description_column.set_visible(false);
         }
 
@@ -185,6 +224,7 @@ mod imp {
             self.name_column.set_fixed_width(400);
             self.name_column.set_expand(false);
             self.name_column.set_resizable(true);
+            self.source_column.set_visible(true);
             self.description_column.set_visible(true);
         }
 
@@ -344,6 +384,11 @@ mod imp {
                 move |_action, _| {
                     match find_selected_item(this.clone()) {
                         Some((this, item)) => {
+                            if let Some(window) = app!().window() {
+                                window
+                                    .add_recent_item(RecentItem::Service(item.name().to_string()));
+                            }
+
                             let dialog = DetailsDialog::new(item);
                             dialog.present(Some(&this));
                         }
@@ -358,6 +403,54 @@ mod imp {
                 }
             });
             actions.add_action(&action);
+
+            let action = gio::SimpleAction::new("edit-override", None);
+            // Drop-ins can't be written from inside the Snap
+            action.set_enabled(std::env::var_os("SNAP_CONTEXT").is_none());
+            action.connect_activate({
+                let this = this.downgrade();
+                move |_action, _| {
+                    let Some((this, item)) = find_selected_item(this.clone()) else {
+                        g_critical!(
+                            "MissionCenter::ServicesPage",
+                            "Failed to get selected item for action"
+                        );
+                        return;
+                    };
+
+                    match OverrideDialog::new(item.name().as_str()) {
+                        Ok(dialog) => dialog.present(Some(&this)),
+                        Err(e) => {
+                            let dialog = adw::AlertDialog::new(
+                                Some(&i18n_f(
+                                    "Can't Edit the Override of {}",
+                                    &[item.name().as_str()],
+                                )),
+                                Some(&e),
+                            );
+                            dialog.add_responses(&[("close", &i18n("_Close"))]);
+                            dialog.present(Some(&this));
+                        }
+                    }
+                }
+            });
+            actions.add_action(&action);
+
+            self.favorite_action.connect_change_state({
+                let this = this.downgrade();
+                move |action, state| {
+                    let Some(favorite) = state.and_then(|s| s.get::<bool>()) else {
+                        return;
+                    };
+                    let Some((_, item)) = find_selected_item(this.clone()) else {
+                        return;
+                    };
+
+                    app!().set_favorite_service(item.name().as_str(), favorite);
+                    action.set_state(&favorite.to_variant());
+                }
+            });
+            actions.add_action(&self.favorite_action);
         }
 
         pub fn set_up_filter_model(&self, model: gio::ListModel) -> gtk::FilterListModel {
@@ -461,6 +554,8 @@ mod imp {
                                 .unwrap_or_default(),
                         );
                         item.set_enabled(service.enabled);
+                        item.set_start_type(start_type(readings, &service).as_str());
+                        item.set_source(source(readings.service_sources.get(&service.id).copied()));
                         item.set_running(service.running);
                         item.set_failed(service.failed);
                         if let Some(pid) = service.pid {
@@ -499,6 +594,8 @@ mod imp {
                             .unwrap_or_default(),
                     )
                     .enabled(service.enabled)
+                    .start_type(&start_type(readings, service))
+                    .source(source(readings.service_sources.get(&service.id).copied()))
                     .running(service.running)
                     .failed(service.failed)
                     .pid(service.pid);
@@ -547,14 +644,10 @@ mod imp {
                         .selected_item()
                         .and_then(|i| i.downcast_ref::<ServicesListItem>().cloned());
 
-                    if selected_item.map(|it| it.running()).unwrap_or(false) {
-                        self.actions().stop.set_enabled(true);
-                        self.actions().start.set_enabled(false);
-                        self.actions().restart.set_enabled(true);
-                    } else {
-                        self.actions().stop.set_enabled(false);
-                        self.actions().start.set_enabled(true);
-                        self.actions().restart.set_enabled(false);
+                    self.update_favorite_action(selected_item.as_ref());
+
+                    if let Some(selected_item) = &selected_item {
+                        self.update_service_actions(selected_item);
                     }
                 }
             }
@@ -569,6 +662,7 @@ mod imp {
 
         fn class_init(klass: &mut Self::Class) {
             ServicesListItem::ensure_type();
+            StartTypeCell::ensure_type();
             DetailsDialog::ensure_type();
 
             klass.bind_template();
@@ -587,6 +681,7 @@ mod imp {
                 self.start.set_visible(false);
                 self.stop.set_visible(false);
                 self.restart.set_visible(false);
+                self.favorites.set_visible(false);
 
                 let menu = gio::Menu::new();
                 menu.append(Some(&i18n("Details")), Some("services-page.details"));
@@ -597,6 +692,21 @@ mod imp {
 
             self.configure_actions();
 
+            // Shared with everywhere else quick actions are offered, so it stays up to date on its own
+            let favorites_menu = app!().favorite_services_menu();
+            if std::env::var_os("SNAP_CONTEXT").is_none() {
+                self.favorites.set_visible(favorites_menu.n_items() > 0);
+                favorites_menu.connect_items_changed({
+                    let favorites = self.favorites.downgrade();
+                    move |menu, _, _, _| {
+                        if let Some(favorites) = favorites.upgrade() {
+                            favorites.set_visible(menu.n_items() > 0);
+                        }
+                    }
+                });
+            }
+            self.favorites.set_menu_model(Some(&favorites_menu));
+
             if let Some(header) = self.column_view.first_child() {
                 // Add 10px padding to the left of the first column header to align it with the content
                 if let Some(first_column) = header
@@ -716,6 +826,29 @@ mod imp {
     impl BoxImpl for ServicesPage {}
 }
 
+/// Remote hosts only tell whether a service is enabled
+fn start_type(readings: &Readings, service: &Service) -> String {
+    // Without a unit file there is no start type to show
+    if readings.service_sources.get(&service.id) == Some(&SandboxKind::Flatpak) {
+        return String::new();
+    }
+
+    match readings.service_start_types.get(&service.id) {
+        Some(start_type) => start_type.state().to_owned(),
+        None if service.enabled => StartType::Enabled.state().to_owned(),
+        None => StartType::Disabled.state().to_owned(),
+    }
+}
+
+/// Names of products, not translated
+fn source(sandbox: Option<SandboxKind>) -> &'static str {
+    match sandbox {
+        None => "systemd",
+        Some(SandboxKind::Snap) => "Snap",
+        Some(SandboxKind::Flatpak) => "Flatpak",
+    }
+}
+
 glib::wrapper! {
     pub struct ServicesPage(ObjectSubclass<imp::ServicesPage>)
         @extends gtk::Box, gtk::Widget,
@@ -753,15 +886,8 @@ impl ServicesPage {
                 };
                 let this = this.imp();
 
-                if selected.running() {
-                    this.actions().stop.set_enabled(true);
-                    this.actions().start.set_enabled(false);
-                    this.actions().restart.set_enabled(true);
-                } else {
-                    this.actions().stop.set_enabled(false);
-                    this.actions().start.set_enabled(true);
-                    this.actions().restart.set_enabled(false);
-                }
+                this.update_favorite_action(Some(&selected));
+                this.update_service_actions(&selected);
             }
         });
 
@@ -780,6 +906,42 @@ impl ServicesPage {
         self.imp().expand();
     }
 
+    /// Select the service called `name`, if it is in the list
+    pub fn select_service(&self, name: &str) -> bool {
+        let imp = self.imp();
+
+        let Some(selection_model) = imp
+            .column_view
+            .model()
+            .and_then(|model| model.downcast::<gtk::SingleSelection>().ok())
+        else {
+            return false;
+        };
+
+        for i in 0..selection_model.n_items() {
+            let Some(item) = selection_model
+                .item(i)
+                .and_then(|item| item.downcast::<ServicesListItem>().ok())
+            else {
+                continue;
+            };
+
+            if item.name() != name {
+                continue;
+            }
+
+            imp.column_view.scroll_to(
+                i,
+                None,
+                gtk::ListScrollFlags::FOCUS | gtk::ListScrollFlags::SELECT,
+                None,
+            );
+            return true;
+        }
+
+        false
+    }
+
     pub fn update_readings(&self, readings: &mut Readings) -> bool {
         self.imp().update_model(readings);
 
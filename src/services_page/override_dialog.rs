@@ -0,0 +1,331 @@
+/* services_page/override_dialog.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::cell::{OnceCell, RefCell};
+
+use adw::{prelude::*, subclass::prelude::*};
+use gtk::glib::{self, g_warning};
+
+use crate::{
+    app,
+    i18n::*,
+    magpie_client::{ServiceOverride, RESTART_POLICIES},
+};
+
+const MIB: u64 = 1024 * 1024;
+
+mod imp {
+    use super::*;
+
+    #[derive(gtk::CompositeTemplate)]
+    #[template(resource = "/io/missioncenter/MissionCenter/ui/services_page/override_dialog.ui")]
+    pub struct OverrideDialog {
+        #[template_child]
+        pub cancel: TemplateChild<gtk::Button>,
+        #[template_child]
+        pub save: TemplateChild<gtk::Button>,
+        #[template_child]
+        pub restart: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub cpu_quota_row: TemplateChild<adw::ExpanderRow>,
+        #[template_child]
+        pub cpu_quota: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub memory_max_row: TemplateChild<adw::ExpanderRow>,
+        #[template_child]
+        pub memory_max: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub environment: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        pub add_variable: TemplateChild<gtk::Button>,
+
+        pub service_id: OnceCell<String>,
+        pub variable_rows: RefCell<Vec<adw::EntryRow>>,
+    }
+
+    impl Default for OverrideDialog {
+        fn default() -> Self {
+            Self {
+                cancel: TemplateChild::default(),
+                save: TemplateChild::default(),
+                restart: TemplateChild::default(),
+                cpu_quota_row: TemplateChild::default(),
+                cpu_quota: TemplateChild::default(),
+                memory_max_row: TemplateChild::default(),
+                memory_max: TemplateChild::default(),
+                environment: TemplateChild::default(),
+                add_variable: TemplateChild::default(),
+
+                service_id: OnceCell::new(),
+                variable_rows: RefCell::new(vec![]),
+            }
+        }
+    }
+
+    impl OverrideDialog {
+        fn service_id(&self) -> String {
+            self.service_id.get().cloned().unwrap_or_default()
+        }
+
+        pub fn show_override(&self, service_override: &ServiceOverride) {
+            let restart = service_override
+                .restart
+                .as_ref()
+                .and_then(|restart| {
+                    RESTART_POLICIES
+                        .iter()
+                        .position(|policy| *policy == restart.as_str())
+                })
+                // The first choice keeps the unit's own
+                .map_or(0, |index| index + 1);
+            self.restart.set_selected(restart as u32);
+
+            self.cpu_quota_row
+                .set_enable_expansion(service_override.cpu_quota.is_some());
+            if let Some(cpu_quota) = service_override.cpu_quota {
+                self.cpu_quota.set_value(cpu_quota as f64);
+            }
+
+            self.memory_max_row
+                .set_enable_expansion(service_override.memory_max.is_some());
+            if let Some(memory_max) = service_override.memory_max {
+                self.memory_max.set_value(memory_max.div_ceil(MIB) as f64);
+            }
+
+            for (name, value) in &service_override.environment {
+                self.add_variable_row(&format!("{}={}", name, value));
+            }
+        }
+
+        fn add_variable_row(&self, text: &str) -> adw::EntryRow {
+            let row = adw::EntryRow::builder()
+                .title(i18n("Variable"))
+                .text(text)
+                .build();
+
+            let remove = gtk::Button::from_icon_name("user-trash-symbolic");
+            remove.add_css_class("flat");
+            remove.set_valign(gtk::Align::Center);
+            remove.set_tooltip_text(Some(&i18n("Remove variable")));
+            remove.connect_clicked({
+                let this = self.obj().downgrade();
+                let row = row.downgrade();
+                move |_| {
+                    let (Some(this), Some(row)) = (this.upgrade(), row.upgrade()) else {
+                        return;
+                    };
+                    let this = this.imp();
+                    this.environment.remove(&row);
+                    this.variable_rows.borrow_mut().retain(|r| *r != row);
+                }
+            });
+            row.add_suffix(&remove);
+
+            self.environment.add(&row);
+            self.variable_rows.borrow_mut().push(row.clone());
+
+            row
+        }
+
+        fn edited_override(&self) -> Result<ServiceOverride, String> {
+            let mut environment = vec![];
+            for row in self.variable_rows.borrow().iter() {
+                let text = row.text();
+                let text = text.trim();
+                if text.is_empty() {
+                    continue;
+                }
+
+                match text.split_once('=') {
+                    Some((name, value)) if !name.trim().is_empty() => {
+                        environment.push((name.trim().to_owned(), value.to_owned()))
+                    }
+                    _ => {
+                        return Err(i18n_f(
+                            "“{}” is not a variable, they are written as NAME=value.",
+                            &[text],
+                        ))
+                    }
+                }
+            }
+
+            let restart = match self.restart.selected() {
+                0 => None,
+                index => RESTART_POLICIES
+                    .get(index as usize - 1)
+                    .map(|policy| policy.to_string()),
+            };
+
+            Ok(ServiceOverride {
+                environment,
+                restart,
+                cpu_quota: self
+                    .cpu_quota_row
+                    .enables_expansion()
+                    .then(|| self.cpu_quota.value() as u32),
+                memory_max: self
+                    .memory_max_row
+                    .enables_expansion()
+                    .then(|| self.memory_max.value() as u64 * MIB),
+            })
+        }
+
+        fn save(&self) {
+            let service_override = match self.edited_override() {
+                Ok(service_override) => service_override,
+                Err(e) => {
+                    self.show_error(&e);
+                    return;
+                }
+            };
+
+            let sys_info = match app!().sys_info() {
+                Ok(sys_info) => sys_info,
+                Err(e) => {
+                    g_warning!(
+                        "MissionCenter::OverrideDialog",
+                        "Failed to get `sys_info`: {}",
+                        e
+                    );
+                    self.show_error(&e.to_string());
+                    return;
+                }
+            };
+
+            // Asking for authorization can take a while, don't let it be saved twice meanwhile
+            self.save.set_sensitive(false);
+
+            let this = glib::SendWeakRef::from(self.obj().downgrade());
+            sys_info.set_service_override(self.service_id(), service_override, move |result| {
+                let Some(this) = this.upgrade() else {
+                    return;
+                };
+
+                match result {
+                    Ok(()) => {
+                        this.close();
+                    }
+                    Err(e) => {
+                        this.imp().save.set_sensitive(true);
+                        this.imp().show_error(&e);
+                    }
+                }
+            });
+        }
+
+        fn show_error(&self, e: &str) {
+            let dialog = adw::AlertDialog::new(
+                Some(&i18n_f(
+                    "Can't Save the Override of {}",
+                    &[self.service_id().as_str()],
+                )),
+                Some(e),
+            );
+            dialog.add_responses(&[("close", &i18n("_Close"))]);
+            dialog.present(Some(&*self.obj()));
+        }
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for OverrideDialog {
+        const NAME: &'static str = "OverrideDialog";
+        type Type = super::OverrideDialog;
+        type ParentType = adw::Dialog;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for OverrideDialog {
+        fn constructed(&self) {
+            self.parent_constructed();
+
+            let mut restart_policies = vec![i18n("Unit Default")];
+            restart_policies.extend(RESTART_POLICIES.iter().map(|policy| policy.to_string()));
+            let restart_policies = restart_policies
+                .iter()
+                .map(|policy| policy.as_str())
+                .collect::<Vec<_>>();
+            self.restart
+                .set_model(Some(&gtk::StringList::new(&restart_policies)));
+
+            self.add_variable.connect_clicked({
+                let this = self.obj().downgrade();
+                move |_| {
+                    if let Some(this) = this.upgrade() {
+                        let row = this.imp().add_variable_row("");
+                        row.grab_focus();
+                    }
+                }
+            });
+
+            self.cancel.connect_clicked({
+                let this = self.obj().downgrade();
+                move |_| {
+                    if let Some(this) = this.upgrade() {
+                        this.close();
+                    }
+                }
+            });
+
+            self.save.connect_clicked({
+                let this = self.obj().downgrade();
+                move |_| {
+                    if let Some(this) = this.upgrade() {
+                        this.imp().save();
+                    }
+                }
+            });
+        }
+    }
+
+    impl WidgetImpl for OverrideDialog {}
+
+    impl AdwDialogImpl for OverrideDialog {}
+}
+
+glib::wrapper! {
+    pub struct OverrideDialog(ObjectSubclass<imp::OverrideDialog>)
+        @extends adw::Dialog, gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl OverrideDialog {
+    /// Fails if the override of `service_id` can't be read, like on a remote host
+    pub fn new(service_id: &str) -> Result<Self, String> {
+        let service_override = match app!().sys_info() {
+            Ok(sys_info) => sys_info.service_override(service_id.to_owned())?,
+            Err(e) => return Err(e.to_string()),
+        };
+
+        let this: Self = glib::Object::builder()
+            .property("title", i18n_f("Override of {}", &[service_id]))
+            .build();
+        let _ = this.imp().service_id.set(service_id.to_owned());
+        this.imp().show_override(&service_override);
+
+        Ok(this)
+    }
+}
@@ -34,6 +34,12 @@ mod imp {
         pub description: Cell<glib::GString>,
         #[property(get, set = Self::set_enabled)]
         pub enabled: Cell<bool>,
+        /// The state of the unit file, like `enabled`, `static` or `masked`
+        #[property(get = Self::start_type, set = Self::set_start_type, type = glib::GString)]
+        pub start_type: Cell<glib::GString>,
+        /// Where the service comes from: systemd, a Snap or a Flatpak
+        #[property(get = Self::source, set = Self::set_source, type = glib::GString)]
+        pub source: Cell<glib::GString>,
         #[property(get, set = Self::set_running)]
         pub running: Cell<bool>,
         #[property(get, set = Self::set_failed)]
@@ -54,6 +60,8 @@ mod imp {
                 name: Cell::new(glib::GString::default()),
                 description: Cell::new(glib::GString::default()),
                 enabled: Cell::new(false),
+                start_type: Cell::new(glib::GString::default()),
+                source: Cell::new(glib::GString::default()),
                 running: Cell::new(false),
                 failed: Cell::new(false),
                 icon_name: Cell::new("service-disabled".into()),
@@ -129,6 +137,42 @@ mod imp {
             self.update_icon();
         }
 
+        pub fn start_type(&self) -> glib::GString {
+            let start_type = self.start_type.take();
+            let result = start_type.clone();
+            self.start_type.set(start_type);
+
+            result
+        }
+
+        pub fn set_start_type(&self, start_type: &str) {
+            let current_start_type = self.start_type.take();
+            if current_start_type == start_type {
+                self.start_type.set(current_start_type);
+                return;
+            }
+
+            self.start_type.set(glib::GString::from(start_type));
+        }
+
+        pub fn source(&self) -> glib::GString {
+            let source = self.source.take();
+            let result = source.clone();
+            self.source.set(source);
+
+            result
+        }
+
+        pub fn set_source(&self, source: &str) {
+            let current_source = self.source.take();
+            if current_source == source {
+                self.source.set(current_source);
+                return;
+            }
+
+            self.source.set(glib::GString::from(source));
+        }
+
         pub fn set_running(&self, running: bool) {
             let current_running = self.running.get();
             if current_running == running {
@@ -243,6 +287,8 @@ pub struct ServicesListItemBuilder {
     name: glib::GString,
     description: glib::GString,
     enabled: bool,
+    start_type: glib::GString,
+    source: glib::GString,
     running: bool,
     failed: bool,
     pid: Option<NonZeroU32>,
@@ -256,6 +302,8 @@ impl ServicesListItemBuilder {
             name: "".into(),
             description: "".into(),
             enabled: false,
+            start_type: "".into(),
+            source: "".into(),
             running: false,
             failed: false,
             pid: None,
@@ -279,6 +327,16 @@ impl ServicesListItemBuilder {
         self
     }
 
+    pub fn start_type(mut self, start_type: &str) -> Self {
+        self.start_type = start_type.into();
+        self
+    }
+
+    pub fn source(mut self, source: &str) -> Self {
+        self.source = source.into();
+        self
+    }
+
     pub fn running(mut self, running: bool) -> Self {
         self.running = running;
         self
@@ -312,6 +370,8 @@ impl ServicesListItemBuilder {
             this.name.set(self.name);
             this.description.set(self.description);
             this.set_enabled(self.enabled);
+            this.start_type.set(self.start_type);
+            this.source.set(self.source);
             this.set_running(self.running);
             this.set_failed(self.failed);
             this.pid.set(
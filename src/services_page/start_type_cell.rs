@@ -0,0 +1,273 @@
+/* services_page/start_type_cell.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::cell::{Cell, RefCell};
+
+use adw::prelude::*;
+use gtk::glib::{self, g_warning, ParamSpec, Properties, Value};
+use gtk::subclass::prelude::*;
+
+use crate::{app, i18n::*, magpie_client::StartType};
+
+mod imp {
+    use super::*;
+
+    #[derive(Properties)]
+    #[properties(wrapper_type = super::StartTypeCell)]
+    pub struct StartTypeCell {
+        #[property(get = Self::unit, set = Self::set_unit, type = glib::GString)]
+        pub unit: RefCell<glib::GString>,
+        #[property(get = Self::start_type, set = Self::set_start_type, type = glib::GString)]
+        pub start_type: RefCell<glib::GString>,
+
+        pub drop_down: gtk::DropDown,
+        pub label: gtk::Label,
+        /// What the drop down offers, in order
+        pub options: RefCell<Vec<StartType>>,
+        /// Set while the selection is changed to match the unit, which is not a choice to act on
+        pub updating: Cell<bool>,
+        /// A change is being made, whatever the readings say until then is out of date
+        pub pending: Cell<bool>,
+    }
+
+    impl Default for StartTypeCell {
+        fn default() -> Self {
+            Self {
+                unit: RefCell::new(glib::GString::default()),
+                start_type: RefCell::new(glib::GString::default()),
+
+                drop_down: gtk::DropDown::default(),
+                label: gtk::Label::new(None),
+                options: RefCell::new(vec![]),
+                updating: Cell::new(false),
+                pending: Cell::new(false),
+            }
+        }
+    }
+
+    impl StartTypeCell {
+        fn unit(&self) -> glib::GString {
+            self.unit.borrow().clone()
+        }
+
+        fn set_unit(&self, unit: &str) {
+            // Cells are reused for other units as the list scrolls
+            if self.unit.borrow().as_str() != unit {
+                self.pending.set(false);
+            }
+            *self.unit.borrow_mut() = unit.into();
+        }
+
+        fn start_type(&self) -> glib::GString {
+            self.start_type.borrow().clone()
+        }
+
+        fn set_start_type(&self, start_type: &str) {
+            if self.pending.get() {
+                return;
+            }
+
+            *self.start_type.borrow_mut() = start_type.into();
+            self.show(&StartType::from_state(start_type));
+        }
+
+        pub fn show(&self, start_type: &StartType) {
+            let options = match start_type {
+                StartType::Other(state) => {
+                    self.label.set_label(state);
+                    self.label.set_visible(true);
+                    self.drop_down.set_visible(false);
+                    return;
+                }
+                // There is nothing to enable, it can only be kept from starting
+                StartType::Static => vec![StartType::Static, StartType::Masked],
+                _ => vec![StartType::Enabled, StartType::Disabled, StartType::Masked],
+            };
+
+            self.label.set_visible(false);
+            self.drop_down.set_visible(true);
+
+            self.updating.set(true);
+            if *self.options.borrow() != options {
+                let names = options.iter().map(name).collect::<Vec<_>>();
+                let names = names.iter().map(|name| name.as_str()).collect::<Vec<_>>();
+                self.drop_down
+                    .set_model(Some(&gtk::StringList::new(&names)));
+                self.options.replace(options);
+            }
+            if let Some(position) = self
+                .options
+                .borrow()
+                .iter()
+                .position(|option| option == start_type)
+            {
+                self.drop_down.set_selected(position as u32);
+            }
+            self.updating.set(false);
+        }
+
+        fn selection_changed(&self) {
+            if self.updating.get() {
+                return;
+            }
+
+            let Some(selected) = self
+                .options
+                .borrow()
+                .get(self.drop_down.selected() as usize)
+                .cloned()
+            else {
+                return;
+            };
+            let current = StartType::from_state(&self.start_type.borrow());
+            if selected == current {
+                return;
+            }
+
+            self.change(current, selected);
+        }
+
+        fn change(&self, current: StartType, start_type: StartType) {
+            let unit = self.unit().to_string();
+
+            if app!().remote_host().is_some() {
+                let Ok(sys_info) = app!().sys_info() else {
+                    g_warning!(
+                        "MissionCenter::ServicesPage",
+                        "Failed to get `sys_info` to change the start type of {}",
+                        unit
+                    );
+                    return;
+                };
+                match start_type {
+                    StartType::Enabled => sys_info.enable_service(unit),
+                    StartType::Disabled => sys_info.disable_service(unit),
+                    _ => {
+                        drop(sys_info);
+                        self.show(&current);
+                        show_error(
+                            &unit,
+                            &i18n("Only enabling and disabling is available for a remote host."),
+                        );
+                    }
+                }
+                return;
+            }
+
+            self.pending.set(true);
+            *self.start_type.borrow_mut() = start_type.state().into();
+
+            let this = glib::SendWeakRef::from(self.obj().downgrade());
+            crate::magpie_client::set_service_start_type(&unit, current.clone(), start_type, {
+                move |result| {
+                    if let Some(this) = this.upgrade().filter(|this| this.unit() == unit) {
+                        let this = this.imp();
+                        this.pending.set(false);
+                        if result.is_err() {
+                            *this.start_type.borrow_mut() = current.state().into();
+                            this.show(&current);
+                        }
+                    }
+
+                    if let Err(e) = result {
+                        show_error(&unit, &e);
+                    }
+                }
+            });
+        }
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for StartTypeCell {
+        const NAME: &'static str = "StartTypeCell";
+        type Type = super::StartTypeCell;
+        type ParentType = gtk::Box;
+    }
+
+    impl ObjectImpl for StartTypeCell {
+        fn properties() -> &'static [ParamSpec] {
+            Self::derived_properties()
+        }
+
+        fn set_property(&self, id: usize, value: &Value, pspec: &ParamSpec) {
+            self.derived_set_property(id, value, pspec)
+        }
+
+        fn property(&self, id: usize, pspec: &ParamSpec) -> Value {
+            self.derived_property(id, pspec)
+        }
+
+        fn constructed(&self) {
+            self.parent_constructed();
+
+            let this = self.obj();
+            this.set_valign(gtk::Align::Center);
+
+            self.drop_down.add_css_class("flat");
+            self.drop_down.set_visible(false);
+            // Services can't be changed from inside the Snap
+            self.drop_down
+                .set_sensitive(std::env::var_os("SNAP_CONTEXT").is_none());
+            self.drop_down.connect_selected_notify({
+                let this = this.downgrade();
+                move |_| {
+                    if let Some(this) = this.upgrade() {
+                        this.imp().selection_changed();
+                    }
+                }
+            });
+            this.append(&self.drop_down);
+
+            self.label.add_css_class("dim-label");
+            self.label.set_margin_start(10);
+            self.label.set_visible(false);
+            this.append(&self.label);
+        }
+    }
+
+    impl WidgetImpl for StartTypeCell {}
+
+    impl BoxImpl for StartTypeCell {}
+}
+
+fn name(start_type: &StartType) -> String {
+    match start_type {
+        StartType::Enabled => i18n("Enabled"),
+        StartType::Disabled => i18n("Disabled"),
+        StartType::Static => i18n("Static"),
+        StartType::Masked => i18n("Masked"),
+        StartType::Other(state) => state.clone(),
+    }
+}
+
+fn show_error(unit: &str, error: &str) {
+    let dialog = adw::AlertDialog::new(
+        Some(&i18n_f("Can't Change the Start Type of {}", &[unit])),
+        Some(error),
+    );
+    dialog.add_responses(&[("close", &i18n("_Close"))]);
+    dialog.present(app!().window().as_ref());
+}
+
+glib::wrapper! {
+    pub struct StartTypeCell(ObjectSubclass<imp::StartTypeCell>)
+        @extends gtk::Box, gtk::Widget,
+        @implements gtk::Buildable;
+}
@@ -0,0 +1,599 @@
+/* status_icon.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+//! An icon in the system tray, through the StatusNotifierItem protocol that the KDE, Cinnamon,
+//! XFCE and budgie panels implement, and GNOME with the AppIndicator extension.
+//!
+//! The icon draws CPU and memory usage as two bars, with the numbers in its tooltip. Its menu is
+//! a `gio::MenuModel`, handed over to the panel as a `com.canonical.dbusmenu`.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use gtk::gio;
+use gtk::glib::{self, g_warning, prelude::*, variant::ObjectPath};
+
+use crate::history::Metric;
+use crate::i18n::*;
+use crate::magpie_client::Readings;
+use crate::{app, settings, DataType};
+
+const ITEM_PATH: &str = "/StatusNotifierItem";
+const MENU_PATH: &str = "/MenuBar";
+const WATCHER_NAME: &str = "org.kde.StatusNotifierWatcher";
+
+const ICON_SIZE: usize = 32;
+const CPU_COLOR: [u8; 3] = [0x35, 0x84, 0xe4];
+const MEMORY_COLOR: [u8; 3] = [0x91, 0x41, 0xac];
+
+const INTROSPECTION_XML: &str = r#"
+<node>
+  <interface name="org.kde.StatusNotifierItem">
+    <property name="Category" type="s" access="read"/>
+    <property name="Id" type="s" access="read"/>
+    <property name="Title" type="s" access="read"/>
+    <property name="Status" type="s" access="read"/>
+    <property name="IconName" type="s" access="read"/>
+    <property name="IconPixmap" type="a(iiay)" access="read"/>
+    <property name="ToolTip" type="(sa(iiay)ss)" access="read"/>
+    <property name="ItemIsMenu" type="b" access="read"/>
+    <property name="Menu" type="o" access="read"/>
+    <method name="Activate">
+      <arg name="x" type="i" direction="in"/>
+      <arg name="y" type="i" direction="in"/>
+    </method>
+    <method name="SecondaryActivate">
+      <arg name="x" type="i" direction="in"/>
+      <arg name="y" type="i" direction="in"/>
+    </method>
+    <method name="ContextMenu">
+      <arg name="x" type="i" direction="in"/>
+      <arg name="y" type="i" direction="in"/>
+    </method>
+    <method name="Scroll">
+      <arg name="delta" type="i" direction="in"/>
+      <arg name="orientation" type="s" direction="in"/>
+    </method>
+    <signal name="NewIcon"/>
+    <signal name="NewToolTip"/>
+  </interface>
+  <interface name="com.canonical.dbusmenu">
+    <property name="Version" type="u" access="read"/>
+    <property name="TextDirection" type="s" access="read"/>
+    <property name="Status" type="s" access="read"/>
+    <property name="IconThemePath" type="as" access="read"/>
+    <method name="GetLayout">
+      <arg name="parentId" type="i" direction="in"/>
+      <arg name="recursionDepth" type="i" direction="in"/>
+      <arg name="propertyNames" type="as" direction="in"/>
+      <arg name="revision" type="u" direction="out"/>
+      <arg name="layout" type="(ia{sv}av)" direction="out"/>
+    </method>
+    <method name="GetGroupProperties">
+      <arg name="ids" type="ai" direction="in"/>
+      <arg name="propertyNames" type="as" direction="in"/>
+      <arg name="properties" type="a(ia{sv})" direction="out"/>
+    </method>
+    <method name="GetProperty">
+      <arg name="id" type="i" direction="in"/>
+      <arg name="name" type="s" direction="in"/>
+      <arg name="value" type="v" direction="out"/>
+    </method>
+    <method name="Event">
+      <arg name="id" type="i" direction="in"/>
+      <arg name="eventId" type="s" direction="in"/>
+      <arg name="data" type="v" direction="in"/>
+      <arg name="timestamp" type="u" direction="in"/>
+    </method>
+    <method name="EventGroup">
+      <arg name="events" type="a(isvu)" direction="in"/>
+      <arg name="idErrors" type="ai" direction="out"/>
+    </method>
+    <method name="AboutToShow">
+      <arg name="id" type="i" direction="in"/>
+      <arg name="needUpdate" type="b" direction="out"/>
+    </method>
+    <method name="AboutToShowGroup">
+      <arg name="ids" type="ai" direction="in"/>
+      <arg name="updatesNeeded" type="ai" direction="out"/>
+      <arg name="idErrors" type="ai" direction="out"/>
+    </method>
+    <signal name="LayoutUpdated">
+      <arg name="revision" type="u"/>
+      <arg name="parent" type="i"/>
+    </signal>
+  </interface>
+</node>
+"#;
+
+/// A menu item, as the panel knows it; its id is its index in the list plus one, 0 being the
+/// root of the menu
+struct MenuEntry {
+    parent: i32,
+    label: String,
+    action: Option<String>,
+    target: Option<glib::Variant>,
+    separator: bool,
+}
+
+#[derive(Default)]
+struct State {
+    /// Rounded, so the panel is only bothered when the icon would look different
+    cpu_percent: u32,
+    memory_percent: u32,
+    tooltip: String,
+
+    menu: Vec<MenuEntry>,
+    menu_revision: u32,
+
+    /// Whether a panel took the icon; the window is only hidden on close when there is a way
+    /// back to it
+    registered: bool,
+}
+
+pub struct StatusIcon {
+    connection: gio::DBusConnection,
+    registrations: Vec<gio::RegistrationId>,
+    watcher: Option<gio::WatcherId>,
+    menu_model: gio::MenuModel,
+    state: Rc<RefCell<State>>,
+}
+
+impl Drop for StatusIcon {
+    fn drop(&mut self) {
+        if let Some(watcher) = self.watcher.take() {
+            gio::bus_unwatch_name(watcher);
+        }
+        for registration in self.registrations.drain(..) {
+            let _ = self.connection.unregister_object(registration);
+        }
+    }
+}
+
+impl StatusIcon {
+    /// Export the icon and register it with the panel, once there is one
+    pub fn new(menu: gio::MenuModel) -> Result<Self, String> {
+        let connection = gio::bus_get_sync(gio::BusType::Session, gio::Cancellable::NONE)
+            .map_err(|e| e.to_string())?;
+        let node = gio::DBusNodeInfo::for_xml(INTROSPECTION_XML).map_err(|e| e.to_string())?;
+
+        let state = Rc::new(RefCell::new(State {
+            menu: menu_entries(&menu),
+            ..Default::default()
+        }));
+
+        let mut this = Self {
+            connection: connection.clone(),
+            registrations: vec![],
+            watcher: None,
+            menu_model: menu.clone(),
+            state: state.clone(),
+        };
+
+        let Some(item_interface) = node.lookup_interface("org.kde.StatusNotifierItem") else {
+            return Err("Missing StatusNotifierItem interface".to_owned());
+        };
+        this.registrations.push(
+            connection
+                .register_object(ITEM_PATH, &item_interface)
+                .method_call(|_, _, _, _, method, _, invocation| {
+                    // A click on the icon itself; the menu is shown by the panel
+                    if method == "Activate" {
+                        app!().activate();
+                    }
+                    invocation.return_value(None);
+                })
+                .get_property({
+                    let state = state.clone();
+                    move |_, _, _, _, property| item_property(&state.borrow(), property)
+                })
+                .build()
+                .map_err(|e| e.to_string())?,
+        );
+
+        let Some(menu_interface) = node.lookup_interface("com.canonical.dbusmenu") else {
+            return Err("Missing dbusmenu interface".to_owned());
+        };
+        this.registrations.push(
+            connection
+                .register_object(MENU_PATH, &menu_interface)
+                .method_call({
+                    let state = state.clone();
+                    move |_, _, _, _, method, parameters, invocation| {
+                        let reply = menu_method(&state, method, &parameters);
+                        invocation.return_value(reply.as_ref());
+                    }
+                })
+                .get_property(|_, _, _, _, property| match property {
+                    "Version" => 3_u32.to_variant(),
+                    "TextDirection" => "ltr".to_variant(),
+                    "Status" => "normal".to_variant(),
+                    _ => Vec::<String>::new().to_variant(),
+                })
+                .build()
+                .map_err(|e| e.to_string())?,
+        );
+
+        // Panels come and go with the session, or get restarted, the icon is registered again
+        // every time
+        this.watcher = Some(gio::bus_watch_name_on_connection(
+            &connection,
+            WATCHER_NAME,
+            gio::BusNameWatcherFlags::NONE,
+            {
+                let state = state.clone();
+                move |connection, _, _| register(&connection, &state)
+            },
+            {
+                let state = state.clone();
+                move |_, _| state.borrow_mut().registered = false
+            },
+        ));
+
+        Ok(this)
+    }
+
+    pub fn is_registered(&self) -> bool {
+        self.state.borrow().registered
+    }
+
+    /// Hand the panel the menu again, after it changed
+    pub fn update_menu(&self) {
+        let revision = {
+            let mut state = self.state.borrow_mut();
+            state.menu = menu_entries(&self.menu_model);
+            state.menu_revision += 1;
+            state.menu_revision
+        };
+        emit(
+            &self.connection,
+            MENU_PATH,
+            "com.canonical.dbusmenu",
+            "LayoutUpdated",
+            Some(&(revision, 0_i32).to_variant()),
+        );
+    }
+
+    pub fn update(&self, readings: &Readings) {
+        let cpu = Metric::CpuUsage.current_value(readings);
+        let memory_used = Metric::MemoryUsed.current_value(readings);
+        let memory_total = Metric::MemoryTotal.current_value(readings);
+        let memory = if memory_total > 0. {
+            memory_used / memory_total * 100.
+        } else {
+            0.
+        };
+
+        let cpu_percent = cpu.round().clamp(0., 100.) as u32;
+        let memory_percent = memory.round().clamp(0., 100.) as u32;
+
+        let settings = settings!();
+        let tooltip = format!(
+            "{}\n{}",
+            i18n_f("CPU: {}%", &[&cpu_percent.to_string()]),
+            i18n_f(
+                "Memory: {}% ({} of {})",
+                &[
+                    &memory_percent.to_string(),
+                    &crate::to_human_readable_nice(memory_used, &DataType::MemoryBytes, &settings),
+                    &crate::to_human_readable_nice(memory_total, &DataType::MemoryBytes, &settings),
+                ],
+            )
+        );
+
+        let mut state = self.state.borrow_mut();
+        if state.tooltip != tooltip {
+            state.tooltip = tooltip;
+            emit(
+                &self.connection,
+                ITEM_PATH,
+                "org.kde.StatusNotifierItem",
+                "NewToolTip",
+                None,
+            );
+        }
+        if state.cpu_percent != cpu_percent || state.memory_percent != memory_percent {
+            state.cpu_percent = cpu_percent;
+            state.memory_percent = memory_percent;
+            emit(
+                &self.connection,
+                ITEM_PATH,
+                "org.kde.StatusNotifierItem",
+                "NewIcon",
+                None,
+            );
+        }
+    }
+}
+
+fn register(connection: &gio::DBusConnection, state: &Rc<RefCell<State>>) {
+    let Some(name) = connection.unique_name() else {
+        return;
+    };
+
+    // Registering with the unique name, the panel looks for the item at `ITEM_PATH`
+    connection.call(
+        Some(WATCHER_NAME),
+        "/StatusNotifierWatcher",
+        WATCHER_NAME,
+        "RegisterStatusNotifierItem",
+        Some(&(name.as_str(),).to_variant()),
+        None,
+        gio::DBusCallFlags::NONE,
+        -1,
+        gio::Cancellable::NONE,
+        {
+            let state = state.clone();
+            move |result| match result {
+                Ok(_) => state.borrow_mut().registered = true,
+                Err(e) => {
+                    g_warning!(
+                        "MissionCenter::StatusIcon",
+                        "Failed to register the status icon: {}",
+                        e
+                    );
+                }
+            }
+        },
+    );
+}
+
+fn emit(
+    connection: &gio::DBusConnection,
+    path: &str,
+    interface: &str,
+    signal: &str,
+    parameters: Option<&glib::Variant>,
+) {
+    if let Err(e) = connection.emit_signal(None, path, interface, signal, parameters) {
+        g_warning!(
+            "MissionCenter::StatusIcon",
+            "Failed to emit {}: {}",
+            signal,
+            e
+        );
+    }
+}
+
+fn item_property(state: &State, property: &str) -> glib::Variant {
+    match property {
+        "Category" => "SystemServices".to_variant(),
+        "Id" => "missioncenter".to_variant(),
+        "Title" => i18n("Mission Center").to_variant(),
+        "Status" => "Active".to_variant(),
+        // Left empty, panels prefer a named icon over the pixmap
+        "IconName" => "".to_variant(),
+        "IconPixmap" => icon_pixmap(state).to_variant(),
+        "ToolTip" => (
+            String::new(),
+            Vec::<(i32, i32, Vec<u8>)>::new(),
+            i18n("Mission Center"),
+            state.tooltip.clone(),
+        )
+            .to_variant(),
+        "ItemIsMenu" => false.to_variant(),
+        "Menu" => ObjectPath::try_from(MENU_PATH)
+            .map(|path| path.to_variant())
+            .unwrap_or_else(|_| "/".to_variant()),
+        _ => "".to_variant(),
+    }
+}
+
+/// Two bars, CPU and memory, filling up from the bottom; ARGB32 in network byte order
+fn icon_pixmap(state: &State) -> Vec<(i32, i32, Vec<u8>)> {
+    const MARGIN: usize = 2;
+    const BAR_WIDTH: usize = (ICON_SIZE - 3 * MARGIN) / 2;
+    const TRACK_ALPHA: u8 = 0x40;
+
+    let mut pixels = vec![0_u8; ICON_SIZE * ICON_SIZE * 4];
+    let bar_height = ICON_SIZE - 2 * MARGIN;
+
+    for (index, (percent, color)) in [
+        (state.cpu_percent, CPU_COLOR),
+        (state.memory_percent, MEMORY_COLOR),
+    ]
+    .iter()
+    .enumerate()
+    {
+        let left = MARGIN + index * (BAR_WIDTH + MARGIN);
+        let filled = (bar_height as u32 * percent / 100) as usize;
+
+        for y in MARGIN..ICON_SIZE - MARGIN {
+            let is_filled = ICON_SIZE - MARGIN - y <= filled;
+            for x in left..left + BAR_WIDTH {
+                let offset = (y * ICON_SIZE + x) * 4;
+                let pixel = if is_filled {
+                    [0xff, color[0], color[1], color[2]]
+                } else {
+                    [TRACK_ALPHA, 0xff, 0xff, 0xff]
+                };
+                pixels[offset..offset + 4].copy_from_slice(&pixel);
+            }
+        }
+    }
+
+    vec![(ICON_SIZE as i32, ICON_SIZE as i32, pixels)]
+}
+
+fn menu_method(
+    state: &Rc<RefCell<State>>,
+    method: &str,
+    parameters: &glib::Variant,
+) -> Option<glib::Variant> {
+    match method {
+        "GetLayout" => {
+            let (parent, _, _) = parameters.get::<(i32, i32, Vec<String>)>()?;
+            let state = state.borrow();
+            Some(glib::Variant::tuple_from_iter([
+                state.menu_revision.to_variant(),
+                menu_layout(&state.menu, parent),
+            ]))
+        }
+        "GetGroupProperties" => {
+            let (ids, _) = parameters.get::<(Vec<i32>, Vec<String>)>()?;
+            let state = state.borrow();
+            let properties = ids
+                .into_iter()
+                .filter_map(|id| Some((id, menu_properties(&state.menu, id)?)))
+                .collect::<Vec<_>>();
+            Some((properties,).to_variant())
+        }
+        "GetProperty" => {
+            let (id, name) = parameters.get::<(i32, String)>()?;
+            let state = state.borrow();
+            let value = menu_properties(&state.menu, id)?.remove(&name)?;
+            Some((value,).to_variant())
+        }
+        "Event" => {
+            let (id, event, _, _) = parameters.get::<(i32, String, glib::Variant, u32)>()?;
+            if event == "clicked" {
+                activate(state, id);
+            }
+            None
+        }
+        "EventGroup" => {
+            let (events,) = parameters.get::<(Vec<(i32, String, glib::Variant, u32)>,)>()?;
+            for (id, event, _, _) in events {
+                if event == "clicked" {
+                    activate(state, id);
+                }
+            }
+            Some((Vec::<i32>::new(),).to_variant())
+        }
+        "AboutToShow" => Some((false,).to_variant()),
+        "AboutToShowGroup" => Some((Vec::<i32>::new(), Vec::<i32>::new()).to_variant()),
+        _ => None,
+    }
+}
+
+fn activate(state: &Rc<RefCell<State>>, id: i32) {
+    // Taken out first, the action may well change the menu
+    let action = {
+        let state = state.borrow();
+        let Some(entry) = id
+            .checked_sub(1)
+            .and_then(|index| state.menu.get(index as usize))
+        else {
+            return;
+        };
+        entry
+            .action
+            .clone()
+            .map(|action| (action, entry.target.clone()))
+    };
+
+    let Some((action, target)) = action else {
+        return;
+    };
+    let Some(action) = action.strip_prefix("app.").map(|action| action.to_owned()) else {
+        return;
+    };
+
+    glib::idle_add_local_once(move || {
+        app!().activate_action(&action, target.as_ref());
+    });
+}
+
+fn menu_layout(menu: &[MenuEntry], id: i32) -> glib::Variant {
+    let children = menu
+        .iter()
+        .enumerate()
+        .filter(|(_, entry)| entry.parent == id)
+        .map(|(index, _)| menu_layout(menu, index as i32 + 1))
+        .collect::<Vec<_>>();
+
+    let mut properties = menu_properties(menu, id).unwrap_or_default();
+    if !children.is_empty() {
+        properties.insert("children-display".to_owned(), "submenu".to_variant());
+    }
+
+    (id, properties, children).to_variant()
+}
+
+fn menu_properties(menu: &[MenuEntry], id: i32) -> Option<HashMap<String, glib::Variant>> {
+    let mut properties = HashMap::new();
+    if id == 0 {
+        return Some(properties);
+    }
+
+    let entry = menu.get(id.checked_sub(1)? as usize)?;
+    if entry.separator {
+        properties.insert("type".to_owned(), "separator".to_variant());
+    } else {
+        properties.insert("label".to_owned(), entry.label.to_variant());
+    }
+
+    Some(properties)
+}
+
+fn menu_entries(model: &gio::MenuModel) -> Vec<MenuEntry> {
+    let mut entries = vec![];
+    add_menu_entries(model, 0, &mut entries);
+    entries
+}
+
+/// Sections become separators, and submenus that turn out empty are left out
+fn add_menu_entries(model: &gio::MenuModel, parent: i32, entries: &mut Vec<MenuEntry>) {
+    for i in 0..model.n_items() {
+        if let Some(section) = model.item_link(i, gio::MENU_LINK_SECTION) {
+            if entries.iter().any(|entry| entry.parent == parent) {
+                entries.push(MenuEntry {
+                    parent,
+                    label: String::new(),
+                    action: None,
+                    target: None,
+                    separator: true,
+                });
+            }
+            add_menu_entries(&section, parent, entries);
+            continue;
+        }
+
+        let attribute = |name: &str| {
+            model
+                .item_attribute_value(i, name, Some(glib::VariantTy::STRING))
+                .and_then(|value| value.get::<String>())
+        };
+        entries.push(MenuEntry {
+            parent,
+            label: attribute(gio::MENU_ATTRIBUTE_LABEL).unwrap_or_default(),
+            action: attribute(gio::MENU_ATTRIBUTE_ACTION),
+            target: model.item_attribute_value(i, gio::MENU_ATTRIBUTE_TARGET, None),
+            separator: false,
+        });
+
+        if let Some(submenu) = model.item_link(i, gio::MENU_LINK_SUBMENU) {
+            let id = entries.len() as i32;
+            add_menu_entries(&submenu, id, entries);
+            if entries.len() as i32 == id {
+                entries.pop();
+            }
+        }
+    }
+
+    // A section that ended up empty leaves a separator behind
+    if entries
+        .last()
+        .is_some_and(|entry| entry.parent == parent && entry.separator)
+    {
+        entries.pop();
+    }
+}
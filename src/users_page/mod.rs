@@ -0,0 +1,399 @@
+/* users_page/mod.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use adw::prelude::*;
+use gtk::{gio, glib, subclass::prelude::*};
+
+use crate::{
+    i18n::*,
+    magpie_client::{self, Readings, Session, SliceLimits, User, UserSlice},
+    settings, DataType,
+};
+
+mod imp {
+    use super::*;
+
+    #[derive(gtk::CompositeTemplate)]
+    #[template(resource = "/io/missioncenter/MissionCenter/ui/users_page/page.ui")]
+    pub struct UsersPage {
+        #[template_child]
+        pub h1: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub h2: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub users_list: TemplateChild<gtk::ListBox>,
+
+        // The rows are only rebuilt when users log in or out, or sessions change
+        pub user_rows: RefCell<Vec<adw::ExpanderRow>>,
+        pub layout: RefCell<Vec<(u32, Vec<String>)>>,
+        // What the limits dialog starts from, by UID
+        pub slices: RefCell<HashMap<u32, UserSlice>>,
+    }
+
+    impl Default for UsersPage {
+        fn default() -> Self {
+            Self {
+                h1: TemplateChild::default(),
+                h2: TemplateChild::default(),
+                users_list: TemplateChild::default(),
+
+                user_rows: RefCell::new(vec![]),
+                layout: RefCell::new(vec![]),
+                slices: RefCell::new(HashMap::new()),
+            }
+        }
+    }
+
+    impl UsersPage {
+        fn rebuild_rows(&self, users: &[User]) {
+            self.users_list.remove_all();
+
+            let mut user_rows = Vec::with_capacity(users.len());
+            for user in users {
+                let row = adw::ExpanderRow::new();
+                row.set_title(&glib::markup_escape_text(&user.name));
+                row.set_subtitle_lines(2);
+                row.add_prefix(&gtk::Image::from_icon_name("avatar-default-symbolic"));
+
+                let edit_limits = gtk::Button::from_icon_name("document-edit-symbolic");
+                edit_limits.set_tooltip_text(Some(&i18n("Edit Resource Limits")));
+                edit_limits.set_valign(gtk::Align::Center);
+                edit_limits.add_css_class("flat");
+                edit_limits.connect_clicked({
+                    let this = self.obj().downgrade();
+                    let uid = user.uid;
+                    let user_name = user.name.clone();
+                    move |_| {
+                        if let Some(this) = this.upgrade() {
+                            this.imp().edit_limits(uid, &user_name);
+                        }
+                    }
+                });
+                row.add_suffix(&edit_limits);
+
+                for session in &user.sessions {
+                    row.add_row(&self.session_row(user, session));
+                }
+
+                self.users_list.append(&row);
+                user_rows.push(row);
+            }
+
+            self.user_rows.replace(user_rows);
+        }
+
+        fn session_row(&self, user: &User, session: &Session) -> adw::ActionRow {
+            let row = adw::ActionRow::new();
+            row.set_title(&i18n_f("Session {}", &[&session.id]));
+            row.set_subtitle(&glib::markup_escape_text(&session_description(session)));
+
+            let graphical = session.kind == "x11" || session.kind == "wayland";
+            if graphical {
+                let lock = gtk::Button::from_icon_name("system-lock-screen-symbolic");
+                lock.set_tooltip_text(Some(&i18n("Lock Session")));
+                lock.set_valign(gtk::Align::Center);
+                lock.add_css_class("flat");
+                lock.connect_clicked({
+                    let session_id = session.id.clone();
+                    move |_| magpie_client::lock_session(&session_id)
+                });
+                row.add_suffix(&lock);
+            }
+
+            let terminate = gtk::Button::from_icon_name("system-log-out-symbolic");
+            terminate.set_tooltip_text(Some(&i18n("End Session")));
+            terminate.set_valign(gtk::Align::Center);
+            terminate.add_css_class("flat");
+            terminate.connect_clicked({
+                let this = self.obj().downgrade();
+                let session_id = session.id.clone();
+                let user_name = user.name.clone();
+                move |_| {
+                    let Some(this) = this.upgrade() else {
+                        return;
+                    };
+
+                    let dialog = adw::AlertDialog::new(
+                        Some(&i18n_f("End Session {} of {}?", &[&session_id, &user_name])),
+                        Some(&i18n(
+                            "All applications running in the session will be closed and unsaved work will be lost.",
+                        )),
+                    );
+                    dialog.add_responses(&[
+                        ("cancel", &i18n("_Cancel")),
+                        ("end", &i18n("_End Session")),
+                    ]);
+                    dialog.set_response_appearance("end", adw::ResponseAppearance::Destructive);
+                    dialog.set_default_response(Some("cancel"));
+                    dialog.set_close_response("cancel");
+                    dialog.connect_response(Some("end"), {
+                        let session_id = session_id.clone();
+                        move |_, _| magpie_client::terminate_session(&session_id)
+                    });
+                    dialog.present(Some(&this));
+                }
+            });
+            row.add_suffix(&terminate);
+
+            row
+        }
+
+        // The limits go on the user's slice, so they apply to all of their processes together and
+        // persist across logins
+        fn edit_limits(&self, uid: u32, user_name: &str) {
+            const MIB: u64 = 1024 * 1024;
+
+            let current = self
+                .slices
+                .borrow()
+                .get(&uid)
+                .map(SliceLimits::from)
+                .unwrap_or_default();
+
+            let memory = adw::SpinRow::with_range(0., (1024 * 1024) as f64, 256.);
+            memory.set_title(&i18n("Memory (MiB)"));
+            memory.set_value(current.memory_max.map_or(0., |max| (max / MIB) as f64));
+
+            let cpu_quota = adw::SpinRow::with_range(0., 100_000., 10.);
+            cpu_quota.set_title(&i18n("CPU Quota (%)"));
+            cpu_quota.set_subtitle(&i18n("100% is one whole CPU"));
+            cpu_quota.set_value(current.cpu_quota_percent.unwrap_or(0) as f64);
+
+            let tasks = adw::SpinRow::with_range(0., 4_194_304., 100.);
+            tasks.set_title(&i18n("Tasks"));
+            tasks.set_value(current.tasks_max.unwrap_or(0) as f64);
+
+            let list = gtk::ListBox::builder()
+                .selection_mode(gtk::SelectionMode::None)
+                .css_classes(["boxed-list"])
+                .build();
+            list.append(&memory);
+            list.append(&cpu_quota);
+            list.append(&tasks);
+
+            let dialog = adw::AlertDialog::new(
+                Some(&i18n_f("Resource Limits of {}", &[user_name])),
+                Some(&i18n(
+                    "Shared by all processes of the user. 0 means no limit.",
+                )),
+            );
+            dialog.set_extra_child(Some(&list));
+            dialog.add_responses(&[("cancel", &i18n("_Cancel")), ("save", &i18n("_Save"))]);
+            dialog.set_response_appearance("save", adw::ResponseAppearance::Suggested);
+            dialog.set_default_response(Some("save"));
+            dialog.set_close_response("cancel");
+            dialog.connect_response(Some("save"), {
+                let this = self.obj().downgrade();
+                let user_name = user_name.to_owned();
+                move |_, _| {
+                    let limits = SliceLimits {
+                        memory_max: Some(memory.value() as u64 * MIB).filter(|max| *max > 0),
+                        cpu_quota_percent: Some(cpu_quota.value() as u32)
+                            .filter(|quota| *quota > 0),
+                        tasks_max: Some(tasks.value() as u64).filter(|max| *max > 0),
+                    };
+
+                    let this = glib::SendWeakRef::from(this.clone());
+                    let user_name = user_name.clone();
+                    magpie_client::set_slice_limits(uid, limits, move |result| {
+                        let (Err(e), Some(this)) = (result, this.upgrade()) else {
+                            return;
+                        };
+
+                        let dialog = adw::AlertDialog::new(
+                            Some(&i18n_f("Can't Change the Limits of {}", &[&user_name])),
+                            Some(&e),
+                        );
+                        dialog.add_responses(&[("close", &i18n("_Close"))]);
+                        dialog.present(Some(&this));
+                    });
+                }
+            });
+            dialog.present(Some(&*self.obj()));
+        }
+
+        pub fn update_readings(&self, readings: &Readings) {
+            let users = &readings.users;
+            self.slices
+                .replace(users.iter().map(|user| (user.uid, user.slice)).collect());
+
+            let layout = users
+                .iter()
+                .map(|user| {
+                    (
+                        user.uid,
+                        user.sessions.iter().map(|s| s.id.clone()).collect(),
+                    )
+                })
+                .collect::<Vec<_>>();
+            if *self.layout.borrow() != layout {
+                self.rebuild_rows(users);
+                self.layout.replace(layout);
+            }
+
+            for (user, row) in users.iter().zip(self.user_rows.borrow().iter()) {
+                row.set_subtitle(&glib::markup_escape_text(&user_summary(user)));
+            }
+
+            let session_count = users.iter().map(|u| u.sessions.len()).sum::<usize>();
+
+            self.h1.set_text(&ni18n_f(
+                "{} User Logged In",
+                "{} Users Logged In",
+                users.len() as u32,
+                &[&users.len().to_string()],
+            ));
+            self.h2.set_text(&ni18n_f(
+                "{} active session",
+                "{} active sessions",
+                session_count as u32,
+                &[&session_count.to_string()],
+            ));
+        }
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for UsersPage {
+        const NAME: &'static str = "UsersPage";
+        type Type = super::UsersPage;
+        type ParentType = gtk::Box;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for UsersPage {}
+
+    impl WidgetImpl for UsersPage {}
+
+    impl BoxImpl for UsersPage {}
+}
+
+fn session_description(session: &Session) -> String {
+    let mut parts = vec![];
+
+    parts.push(match session.kind.as_str() {
+        "wayland" => "Wayland".to_owned(),
+        "x11" => "X11".to_owned(),
+        "tty" => i18n("Terminal"),
+        _ => i18n("Other"),
+    });
+
+    if let Some(seat) = session.seat.as_ref().or(session.tty.as_ref()) {
+        parts.push(seat.clone());
+    }
+
+    if let Some(remote_host) = &session.remote_host {
+        parts.push(i18n_f("from {}", &[remote_host]));
+    }
+
+    parts.push(match session.state.as_str() {
+        "active" => i18n("Active"),
+        "online" => i18n("Idle"),
+        "closing" => i18n("Closing"),
+        state => state.to_owned(),
+    });
+
+    if let Ok(start_time) = glib::DateTime::from_unix_local(session.start_time as i64) {
+        if let Ok(start_time) = start_time.format("%c") {
+            parts.push(i18n_f("since {}", &[&start_time]));
+        }
+    }
+
+    parts.join(" · ")
+}
+
+fn user_summary(user: &User) -> String {
+    let settings = settings!();
+
+    let mut parts = vec![
+        i18n_f("CPU {}%", &[&format!("{:.1}", user.cpu_usage)]),
+        i18n_f(
+            "Memory {}",
+            &[&crate::to_human_readable_nice(
+                user.memory_usage as f32,
+                &DataType::MemoryBytes,
+                &settings,
+            )],
+        ),
+        ni18n_f(
+            "{} process",
+            "{} processes",
+            user.process_count as u32,
+            &[&user.process_count.to_string()],
+        ),
+    ];
+
+    // Limits set on the user's slice, e.g. through `systemctl set-property user-UID.slice`
+    let mut limits = vec![];
+    if let Some(memory_max) = user.slice.memory_max {
+        limits.push(i18n_f(
+            "memory {} of {}",
+            &[
+                &crate::to_human_readable_nice(
+                    user.slice.memory_current.unwrap_or(0) as f32,
+                    &DataType::MemoryBytes,
+                    &settings,
+                ),
+                &crate::to_human_readable_nice(
+                    memory_max as f32,
+                    &DataType::MemoryBytes,
+                    &settings,
+                ),
+            ],
+        ));
+    }
+    if let Some(cpu_quota_percent) = user.slice.cpu_quota_percent {
+        limits.push(i18n_f(
+            "CPU quota {}%",
+            &[&format!("{:.0}", cpu_quota_percent)],
+        ));
+    }
+    if let Some(tasks_max) = user.slice.tasks_max {
+        limits.push(i18n_f("at most {} tasks", &[&tasks_max.to_string()]));
+    }
+    if !limits.is_empty() {
+        parts.push(i18n_f("Limits: {}", &[&limits.join(", ")]));
+    }
+
+    parts.join(" · ")
+}
+
+glib::wrapper! {
+    pub struct UsersPage(ObjectSubclass<imp::UsersPage>)
+        @extends gtk::Box, gtk::Widget,
+        @implements gio::ActionGroup, gio::ActionMap;
+}
+
+impl UsersPage {
+    pub fn update_readings(&self, readings: &Readings) -> bool {
+        self.imp().update_readings(readings);
+
+        true
+    }
+}
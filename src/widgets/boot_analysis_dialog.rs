@@ -0,0 +1,315 @@
+/* widgets/boot_analysis_dialog.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::cell::RefCell;
+
+use adw::{prelude::*, subclass::prelude::*};
+use gtk::glib::{self, g_warning};
+
+use crate::{
+    app,
+    i18n::*,
+    magpie_client::{BootAnalysis, BootStages, UnitBootTime},
+};
+
+/// Only the slowest units are worth looking at, the long tail starts in milliseconds
+const MAX_UNITS: usize = 25;
+
+mod imp {
+    use super::*;
+
+    #[derive(gtk::CompositeTemplate)]
+    #[template(resource = "/io/missioncenter/MissionCenter/ui/widgets/boot_analysis_dialog.ui")]
+    pub struct BootAnalysisDialog {
+        #[template_child]
+        pub toast_overlay: TemplateChild<adw::ToastOverlay>,
+        #[template_child]
+        pub refresh: TemplateChild<gtk::Button>,
+        #[template_child]
+        pub stack: TemplateChild<gtk::Stack>,
+        #[template_child]
+        pub page: TemplateChild<adw::PreferencesPage>,
+        #[template_child]
+        pub status: TemplateChild<adw::StatusPage>,
+
+        pub groups: RefCell<Vec<adw::PreferencesGroup>>,
+    }
+
+    impl Default for BootAnalysisDialog {
+        fn default() -> Self {
+            Self {
+                toast_overlay: TemplateChild::default(),
+                refresh: TemplateChild::default(),
+                stack: TemplateChild::default(),
+                page: TemplateChild::default(),
+                status: TemplateChild::default(),
+
+                groups: RefCell::new(vec![]),
+            }
+        }
+    }
+
+    impl BootAnalysisDialog {
+        pub fn reload(&self) {
+            let boot_analysis = match app!().sys_info().map(|sys_info| sys_info.boot_analysis()) {
+                Ok(boot_analysis) => boot_analysis,
+                Err(e) => {
+                    g_warning!(
+                        "MissionCenter::BootAnalysisDialog",
+                        "Failed to get `sys_info`: {}",
+                        e
+                    );
+                    return;
+                }
+            };
+
+            for group in self.groups.take() {
+                self.page.remove(&group);
+            }
+
+            match boot_analysis {
+                Ok(boot_analysis) => {
+                    self.show_analysis(&boot_analysis);
+                    self.stack.set_visible_child_name("analysis");
+                }
+                Err(e) => {
+                    self.status.set_title(&i18n("Boot Analysis Unavailable"));
+                    self.status
+                        .set_description(Some(&glib::markup_escape_text(&e)));
+                    self.stack.set_visible_child_name("empty");
+                }
+            }
+        }
+
+        fn show_analysis(&self, boot_analysis: &BootAnalysis) {
+            let stages = adw::PreferencesGroup::new();
+            stages.set_title(&i18n("Startup Time"));
+            stages.set_description(Some(&i18n_f(
+                "The system took {} to start",
+                &[&format_duration(boot_analysis.stages.total)],
+            )));
+            for (title, duration) in stage_rows(&boot_analysis.stages) {
+                let row = adw::ActionRow::new();
+                row.set_title(&title);
+                row.add_suffix(&duration_label(duration));
+                stages.add(&row);
+            }
+
+            let units = adw::PreferencesGroup::new();
+            units.set_title(&i18n("Slowest Units"));
+            units.set_description(Some(&i18n(
+                "Units on the critical chain delayed the boot, others started in parallel",
+            )));
+            let slowest = boot_analysis
+                .units
+                .first()
+                .map(|u| u.duration)
+                .unwrap_or(0.);
+            for unit in boot_analysis.units.iter().take(MAX_UNITS) {
+                units.add(&self.unit_row(unit, slowest));
+            }
+
+            self.page.add(&stages);
+            self.page.add(&units);
+            self.groups.replace(vec![stages, units]);
+        }
+
+        fn unit_row(&self, unit: &UnitBootTime, slowest: f32) -> adw::ActionRow {
+            let row = adw::ActionRow::new();
+            row.set_title(&glib::markup_escape_text(&unit.unit));
+            if let Some(activated_at) = unit.activated_at {
+                row.set_subtitle(&i18n_f(
+                    "On the critical chain, active after {}",
+                    &[&format_duration(activated_at)],
+                ));
+            }
+
+            let bar = gtk::LevelBar::new();
+            bar.set_valign(gtk::Align::Center);
+            bar.set_width_request(120);
+            bar.set_value(if slowest > 0. {
+                (unit.duration / slowest) as f64
+            } else {
+                0.
+            });
+            // The default offsets color short bars as a warning, which is backwards here
+            bar.remove_offset_value(Some("low"));
+            bar.remove_offset_value(Some("high"));
+            bar.remove_offset_value(Some("full"));
+            row.add_suffix(&bar);
+            row.add_suffix(&duration_label(Some(unit.duration)));
+
+            if unit.unit.ends_with(".service") {
+                let disable = gtk::Button::with_label(&i18n("Disable"));
+                disable.set_valign(gtk::Align::Center);
+                disable.add_css_class("flat");
+                disable.connect_clicked({
+                    let this = self.obj().downgrade();
+                    let unit = unit.unit.clone();
+                    move |_| {
+                        let Some(this) = this.upgrade() else {
+                            return;
+                        };
+                        this.imp().confirm_disable(&unit);
+                    }
+                });
+                row.add_suffix(&disable);
+            }
+
+            row
+        }
+
+        fn confirm_disable(&self, unit: &str) {
+            let dialog = adw::AlertDialog::new(
+                Some(&i18n_f("Disable {}?", &[unit])),
+                Some(&i18n(
+                    "The service will no longer be started at boot. Other units that depend on it may fail. It can be enabled again from the Services page.",
+                )),
+            );
+            dialog.add_responses(&[("cancel", &i18n("_Cancel")), ("disable", &i18n("_Disable"))]);
+            dialog.set_response_appearance("disable", adw::ResponseAppearance::Destructive);
+            dialog.set_default_response(Some("cancel"));
+            dialog.set_close_response("cancel");
+            dialog.connect_response(Some("disable"), {
+                let this = self.obj().downgrade();
+                let unit = unit.to_owned();
+                move |_, _| {
+                    let Some(this) = this.upgrade() else {
+                        return;
+                    };
+
+                    match app!().sys_info() {
+                        Ok(sys_info) => sys_info.disable_service(unit.clone()),
+                        Err(e) => {
+                            g_warning!(
+                                "MissionCenter::BootAnalysisDialog",
+                                "Failed to get `sys_info`: {}",
+                                e
+                            );
+                            return;
+                        }
+                    }
+
+                    this.imp()
+                        .toast_overlay
+                        .add_toast(adw::Toast::new(&i18n_f("Disabled {}", &[&unit])));
+                }
+            });
+            dialog.present(Some(&*self.obj()));
+        }
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for BootAnalysisDialog {
+        const NAME: &'static str = "BootAnalysisDialog";
+        type Type = super::BootAnalysisDialog;
+        type ParentType = adw::Dialog;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for BootAnalysisDialog {
+        fn constructed(&self) {
+            self.parent_constructed();
+
+            self.refresh.connect_clicked({
+                let this = self.obj().downgrade();
+                move |_| {
+                    let Some(this) = this.upgrade() else {
+                        return;
+                    };
+                    this.imp().reload();
+                }
+            });
+        }
+    }
+
+    impl WidgetImpl for BootAnalysisDialog {}
+
+    impl AdwDialogImpl for BootAnalysisDialog {}
+}
+
+fn stage_rows(stages: &BootStages) -> Vec<(String, Option<f32>)> {
+    let mut result = vec![];
+
+    if stages.firmware.is_some() {
+        result.push((i18n("Firmware"), stages.firmware));
+    }
+    if stages.loader.is_some() {
+        result.push((i18n("Boot Loader"), stages.loader));
+    }
+    result.push((i18n("Kernel"), stages.kernel));
+    if stages.initrd.is_some() {
+        result.push((i18n("Initial RAM Disk"), stages.initrd));
+    }
+    result.push((i18n("Userspace"), stages.userspace));
+
+    result
+}
+
+fn format_duration(seconds: f32) -> String {
+    if seconds < 1. {
+        i18n_f("{} ms", &[&format!("{:.0}", seconds * 1000.)])
+    } else if seconds < 60. {
+        i18n_f("{} s", &[&format!("{:.2}", seconds)])
+    } else {
+        i18n_f(
+            "{} min {} s",
+            &[
+                &format!("{}", (seconds / 60.) as u32),
+                &format!("{:.0}", seconds % 60.),
+            ],
+        )
+    }
+}
+
+fn duration_label(duration: Option<f32>) -> gtk::Label {
+    let label = gtk::Label::new(Some(
+        &duration
+            .map(format_duration)
+            .unwrap_or_else(|| i18n("Unknown")),
+    ));
+    label.add_css_class("numeric");
+    label.set_width_chars(9);
+    label.set_xalign(1.);
+
+    label
+}
+
+glib::wrapper! {
+    pub struct BootAnalysisDialog(ObjectSubclass<imp::BootAnalysisDialog>)
+        @extends adw::Dialog, gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl BootAnalysisDialog {
+    pub fn new() -> Self {
+        let this: Self = glib::Object::builder().build();
+        this.imp().reload();
+
+        this
+    }
+}
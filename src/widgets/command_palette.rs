@@ -0,0 +1,279 @@
+/* widgets/command_palette.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use adw::{prelude::*, subclass::prelude::*};
+use gtk::{gdk, glib};
+
+use crate::{app, MissionCenterWindow};
+
+/// Something the palette can take the user to or do, listed under `category`
+pub struct Command {
+    pub title: String,
+    pub category: String,
+    pub run: Rc<dyn Fn(&MissionCenterWindow)>,
+}
+
+impl Command {
+    pub fn new(
+        title: impl Into<String>,
+        category: impl Into<String>,
+        run: impl Fn(&MissionCenterWindow) + 'static,
+    ) -> Self {
+        Self {
+            title: title.into(),
+            category: category.into(),
+            run: Rc::new(run),
+        }
+    }
+}
+
+mod imp {
+    use super::*;
+
+    #[derive(gtk::CompositeTemplate)]
+    #[template(resource = "/io/missioncenter/MissionCenter/ui/widgets/command_palette.ui")]
+    pub struct CommandPalette {
+        #[template_child]
+        pub search_entry: TemplateChild<gtk::SearchEntry>,
+        #[template_child]
+        pub stack: TemplateChild<gtk::Stack>,
+        #[template_child]
+        pub commands: TemplateChild<gtk::ListBox>,
+
+        // In the same order as the rows of `commands`
+        pub entries: RefCell<Vec<Command>>,
+    }
+
+    impl Default for CommandPalette {
+        fn default() -> Self {
+            Self {
+                search_entry: TemplateChild::default(),
+                stack: TemplateChild::default(),
+                commands: TemplateChild::default(),
+
+                entries: RefCell::new(vec![]),
+            }
+        }
+    }
+
+    impl CommandPalette {
+        pub fn filter(&self) {
+            let query = self.search_entry.text().to_lowercase();
+
+            let entries = self.entries.borrow();
+            let mut first_visible = None;
+            for (index, command) in entries.iter().enumerate() {
+                let Some(row) = self.commands.row_at_index(index as i32) else {
+                    continue;
+                };
+
+                let visible = query.is_empty()
+                    || command.title.to_lowercase().contains(&query)
+                    || command.category.to_lowercase().contains(&query);
+                row.set_visible(visible);
+                if visible && first_visible.is_none() {
+                    first_visible = Some(row);
+                }
+            }
+
+            self.commands.select_row(first_visible.as_ref());
+            self.stack
+                .set_visible_child_name(if first_visible.is_some() {
+                    "commands"
+                } else {
+                    "empty"
+                });
+        }
+
+        /// Move the selection `offset` visible rows up or down, without leaving the search entry
+        pub fn move_selection(&self, offset: i32) {
+            let mut index = self
+                .commands
+                .selected_row()
+                .map(|row| row.index())
+                .unwrap_or(-1);
+
+            loop {
+                index += offset;
+                let Some(row) = self.commands.row_at_index(index) else {
+                    return;
+                };
+                if row.is_visible() {
+                    self.commands.select_row(Some(&row));
+                    // Focusing the row scrolls it into view, typing keeps going to the entry
+                    row.grab_focus();
+                    self.search_entry.grab_focus_without_selecting();
+                    return;
+                }
+            }
+        }
+
+        pub fn run(&self, index: i32) {
+            let Some(run) = self
+                .entries
+                .borrow()
+                .get(index as usize)
+                .map(|command| command.run.clone())
+            else {
+                return;
+            };
+
+            self.obj().close();
+            if let Some(window) = app!().window() {
+                run(&window);
+            }
+        }
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for CommandPalette {
+        const NAME: &'static str = "CommandPalette";
+        type Type = super::CommandPalette;
+        type ParentType = adw::Dialog;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for CommandPalette {
+        fn constructed(&self) {
+            self.parent_constructed();
+
+            self.search_entry.set_key_capture_widget(Some(&*self.obj()));
+
+            self.search_entry.connect_search_changed({
+                let this = self.obj().downgrade();
+                move |_| {
+                    if let Some(this) = this.upgrade() {
+                        this.imp().filter();
+                    }
+                }
+            });
+
+            self.search_entry.connect_activate({
+                let this = self.obj().downgrade();
+                move |_| {
+                    let Some(this) = this.upgrade() else {
+                        return;
+                    };
+                    let imp = this.imp();
+
+                    if let Some(row) = imp.commands.selected_row() {
+                        imp.run(row.index());
+                    }
+                }
+            });
+
+            self.search_entry.connect_stop_search({
+                let this = self.obj().downgrade();
+                move |_| {
+                    if let Some(this) = this.upgrade() {
+                        this.close();
+                    }
+                }
+            });
+
+            let key_controller = gtk::EventControllerKey::new();
+            key_controller.connect_key_pressed({
+                let this = self.obj().downgrade();
+                move |_, key, _, _| {
+                    let Some(this) = this.upgrade() else {
+                        return glib::Propagation::Proceed;
+                    };
+
+                    match key {
+                        gdk::Key::Down | gdk::Key::KP_Down => this.imp().move_selection(1),
+                        gdk::Key::Up | gdk::Key::KP_Up => this.imp().move_selection(-1),
+                        _ => return glib::Propagation::Proceed,
+                    }
+
+                    glib::Propagation::Stop
+                }
+            });
+            self.search_entry.add_controller(key_controller);
+
+            self.commands.connect_row_activated({
+                let this = self.obj().downgrade();
+                move |_, row| {
+                    if let Some(this) = this.upgrade() {
+                        this.imp().run(row.index());
+                    }
+                }
+            });
+        }
+    }
+
+    impl WidgetImpl for CommandPalette {}
+
+    impl AdwDialogImpl for CommandPalette {}
+}
+
+glib::wrapper! {
+    pub struct CommandPalette(ObjectSubclass<imp::CommandPalette>)
+        @extends adw::Dialog, gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl CommandPalette {
+    pub fn new(commands: Vec<Command>) -> Self {
+        let this: Self = glib::Object::builder().build();
+        let imp = this.imp();
+
+        for command in &commands {
+            imp.commands.append(&command_row(command));
+        }
+        imp.entries.replace(commands);
+        imp.filter();
+
+        this.set_focus(Some(&*imp.search_entry));
+
+        this
+    }
+}
+
+fn command_row(command: &Command) -> gtk::ListBoxRow {
+    let content = gtk::Box::new(gtk::Orientation::Horizontal, 12);
+    content.set_margin_top(3);
+    content.set_margin_bottom(3);
+
+    let title = gtk::Label::new(Some(&command.title));
+    title.set_hexpand(true);
+    title.set_xalign(0.);
+    title.set_ellipsize(gtk::pango::EllipsizeMode::End);
+    content.append(&title);
+
+    let category = gtk::Label::new(Some(&command.category));
+    category.add_css_class("caption");
+    category.add_css_class("dim-label");
+    content.append(&category);
+
+    let row = gtk::ListBoxRow::new();
+    row.set_child(Some(&content));
+
+    row
+}
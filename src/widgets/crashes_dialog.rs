@@ -0,0 +1,253 @@
+/* widgets/crashes_dialog.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use adw::{prelude::*, subclass::prelude::*};
+use gtk::{
+    gio,
+    glib::{self, g_warning},
+};
+
+use crate::{app, i18n::*, magpie_client::Coredump, settings, DataType};
+
+mod imp {
+    use super::*;
+
+    #[derive(gtk::CompositeTemplate)]
+    #[template(resource = "/io/missioncenter/MissionCenter/ui/widgets/crashes_dialog.ui")]
+    pub struct CrashesDialog {
+        #[template_child]
+        pub toast_overlay: TemplateChild<adw::ToastOverlay>,
+        #[template_child]
+        pub refresh: TemplateChild<gtk::Button>,
+        #[template_child]
+        pub stack: TemplateChild<gtk::Stack>,
+        #[template_child]
+        pub crash_list: TemplateChild<gtk::ListBox>,
+        #[template_child]
+        pub status: TemplateChild<adw::StatusPage>,
+    }
+
+    impl Default for CrashesDialog {
+        fn default() -> Self {
+            Self {
+                toast_overlay: TemplateChild::default(),
+                refresh: TemplateChild::default(),
+                stack: TemplateChild::default(),
+                crash_list: TemplateChild::default(),
+                status: TemplateChild::default(),
+            }
+        }
+    }
+
+    impl CrashesDialog {
+        pub fn reload(&self) {
+            let coredumps = match app!().sys_info().map(|sys_info| sys_info.coredumps()) {
+                Ok(coredumps) => coredumps,
+                Err(e) => {
+                    g_warning!(
+                        "MissionCenter::CrashesDialog",
+                        "Failed to get `sys_info`: {}",
+                        e
+                    );
+                    return;
+                }
+            };
+
+            self.crash_list.remove_all();
+
+            match coredumps {
+                Ok(coredumps) if coredumps.is_empty() => {
+                    self.status.set_title(&i18n("No Crashes"));
+                    self.status.set_description(Some(&i18n(
+                        "No crashes were recorded by systemd-coredump",
+                    )));
+                    self.stack.set_visible_child_name("empty");
+                }
+                Ok(coredumps) => {
+                    for coredump in &coredumps {
+                        self.crash_list.append(&self.crash_row(coredump));
+                    }
+                    self.stack.set_visible_child_name("crashes");
+                }
+                Err(e) => {
+                    self.status.set_title(&i18n("Unable to List Crashes"));
+                    self.status
+                        .set_description(Some(&glib::markup_escape_text(&e)));
+                    self.stack.set_visible_child_name("empty");
+                }
+            }
+        }
+
+        fn crash_row(&self, coredump: &Coredump) -> adw::ExpanderRow {
+            let row = adw::ExpanderRow::new();
+
+            let name = coredump
+                .exe
+                .rsplit('/')
+                .next()
+                .filter(|n| !n.is_empty())
+                .unwrap_or(&coredump.name);
+            row.set_title(&glib::markup_escape_text(name));
+            row.set_subtitle(&glib::markup_escape_text(&crash_description(coredump)));
+            row.add_prefix(&gtk::Image::from_icon_name("computer-fail-symbolic"));
+
+            if let Some(filename) = coredump.filename.clone() {
+                let open_location = gtk::Button::from_icon_name("folder-open-symbolic");
+                open_location.set_tooltip_text(Some(&i18n("Open Dump Location")));
+                open_location.set_valign(gtk::Align::Center);
+                open_location.add_css_class("flat");
+                open_location.connect_clicked({
+                    let this = self.obj().downgrade();
+                    move |_| {
+                        let Some(this) = this.upgrade() else {
+                            return;
+                        };
+
+                        let window = this.root().and_downcast::<gtk::Window>();
+                        gtk::FileLauncher::new(Some(&gio::File::for_path(&filename)))
+                            .open_containing_folder(
+                                window.as_ref(),
+                                gio::Cancellable::NONE,
+                                |result| {
+                                    if let Err(e) = result {
+                                        g_warning!(
+                                            "MissionCenter::CrashesDialog",
+                                            "Failed to open dump location: {}",
+                                            e
+                                        );
+                                    }
+                                },
+                            );
+                    }
+                });
+                row.add_suffix(&open_location);
+            }
+
+            let copy_summary = gtk::Button::from_icon_name("edit-copy-symbolic");
+            copy_summary.set_tooltip_text(Some(&i18n("Copy Backtrace Summary")));
+            copy_summary.set_valign(gtk::Align::Center);
+            copy_summary.add_css_class("flat");
+            copy_summary.connect_clicked({
+                let this = self.obj().downgrade();
+                let summary = coredump.summary.clone();
+                move |_| {
+                    let Some(this) = this.upgrade() else {
+                        return;
+                    };
+
+                    this.clipboard().set_text(&summary);
+                    this.imp()
+                        .toast_overlay
+                        .add_toast(adw::Toast::new(&i18n("Copied to clipboard")));
+                }
+            });
+            row.add_suffix(&copy_summary);
+
+            let summary = gtk::Label::new(Some(&coredump.summary));
+            summary.set_selectable(true);
+            summary.set_wrap(true);
+            summary.set_wrap_mode(gtk::pango::WrapMode::WordChar);
+            summary.set_xalign(0.);
+            summary.set_margin_top(12);
+            summary.set_margin_bottom(12);
+            summary.set_margin_start(12);
+            summary.set_margin_end(12);
+            summary.add_css_class("monospace");
+            row.add_row(&summary);
+
+            row
+        }
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for CrashesDialog {
+        const NAME: &'static str = "CrashesDialog";
+        type Type = super::CrashesDialog;
+        type ParentType = adw::Dialog;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for CrashesDialog {
+        fn constructed(&self) {
+            self.parent_constructed();
+
+            self.refresh.connect_clicked({
+                let this = self.obj().downgrade();
+                move |_| {
+                    let Some(this) = this.upgrade() else {
+                        return;
+                    };
+                    this.imp().reload();
+                }
+            });
+        }
+    }
+
+    impl WidgetImpl for CrashesDialog {}
+
+    impl AdwDialogImpl for CrashesDialog {}
+}
+
+fn crash_description(coredump: &Coredump) -> String {
+    let mut parts = vec![
+        coredump.signal.clone(),
+        i18n_f("PID {}", &[&coredump.pid.to_string()]),
+    ];
+
+    if let Ok(timestamp) = glib::DateTime::from_unix_local(coredump.timestamp as i64 / 1_000_000) {
+        if let Ok(timestamp) = timestamp.format("%c") {
+            parts.push(timestamp.to_string());
+        }
+    }
+
+    match coredump.size {
+        Some(size) => parts.push(crate::to_human_readable_nice(
+            size as f32,
+            &DataType::MemoryBytes,
+            &settings!(),
+        )),
+        None if coredump.filename.is_none() => parts.push(i18n("Dump not stored")),
+        None => {}
+    }
+
+    parts.join(" · ")
+}
+
+glib::wrapper! {
+    pub struct CrashesDialog(ObjectSubclass<imp::CrashesDialog>)
+        @extends adw::Dialog, gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl CrashesDialog {
+    pub fn new() -> Self {
+        let this: Self = glib::Object::builder().build();
+        this.imp().reload();
+
+        this
+    }
+}
@@ -0,0 +1,228 @@
+/* widgets/gatherer_stats_dialog.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::cell::RefCell;
+use std::time::Duration;
+
+use adw::{prelude::*, subclass::prelude::*};
+use gtk::glib::{self, g_warning};
+
+use crate::{
+    app,
+    i18n::*,
+    magpie_client::{GathererStats, Reading},
+    settings, DataType,
+};
+
+const REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+mod imp {
+    use super::*;
+
+    #[derive(gtk::CompositeTemplate)]
+    #[template(resource = "/io/missioncenter/MissionCenter/ui/widgets/gatherer_stats_dialog.ui")]
+    pub struct GathererStatsDialog {
+        #[template_child]
+        pub pid: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub cpu_usage: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub cpu_time: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub memory: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub requests: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub requests_per_second: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub durations: TemplateChild<adw::PreferencesGroup>,
+
+        pub duration_labels: RefCell<Vec<(Reading, gtk::Label)>>,
+    }
+
+    impl Default for GathererStatsDialog {
+        fn default() -> Self {
+            Self {
+                pid: TemplateChild::default(),
+                cpu_usage: TemplateChild::default(),
+                cpu_time: TemplateChild::default(),
+                memory: TemplateChild::default(),
+                requests: TemplateChild::default(),
+                requests_per_second: TemplateChild::default(),
+                durations: TemplateChild::default(),
+
+                duration_labels: RefCell::new(vec![]),
+            }
+        }
+    }
+
+    impl GathererStatsDialog {
+        pub fn refresh(&self) {
+            let stats = match app!().sys_info() {
+                Ok(sys_info) => sys_info.gatherer_stats(),
+                Err(e) => {
+                    g_warning!(
+                        "MissionCenter::GathererStatsDialog",
+                        "Failed to get `sys_info`: {}",
+                        e
+                    );
+                    return;
+                }
+            };
+
+            if let Some(stats) = stats {
+                self.show_stats(&stats);
+            }
+        }
+
+        fn show_stats(&self, stats: &GathererStats) {
+            let unknown = i18n("Unknown");
+
+            self.pid.set_text(
+                &stats
+                    .pid
+                    .map(|pid| pid.to_string())
+                    .unwrap_or_else(|| unknown.clone()),
+            );
+            self.cpu_usage.set_text(
+                &stats
+                    .cpu_usage
+                    .map(|usage| format!("{:.1}%", usage))
+                    .unwrap_or_else(|| unknown.clone()),
+            );
+            self.cpu_time.set_text(
+                &stats
+                    .cpu_time
+                    .map(|time| i18n_f("{} s", &[&format!("{:.2}", time.as_secs_f32())]))
+                    .unwrap_or_else(|| unknown.clone()),
+            );
+            self.memory.set_text(
+                &stats
+                    .memory_bytes
+                    .map(|bytes| {
+                        crate::to_human_readable_nice(
+                            bytes as f32,
+                            &DataType::MemoryBytes,
+                            &settings!(),
+                        )
+                    })
+                    .unwrap_or_else(|| unknown.clone()),
+            );
+            self.requests.set_text(&stats.request_count.to_string());
+            self.requests_per_second
+                .set_text(&format!("{:.1}", stats.requests_per_second));
+
+            for (reading, label) in self.duration_labels.borrow().iter() {
+                label.set_text(
+                    &stats
+                        .read_durations
+                        .get(reading)
+                        .map(|duration| {
+                            i18n_f(
+                                "{} ms",
+                                &[&format!("{:.1}", duration.as_secs_f64() * 1000.)],
+                            )
+                        })
+                        .unwrap_or_else(|| i18n("Not read yet")),
+                );
+            }
+        }
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for GathererStatsDialog {
+        const NAME: &'static str = "GathererStatsDialog";
+        type Type = super::GathererStatsDialog;
+        type ParentType = adw::Dialog;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for GathererStatsDialog {
+        fn constructed(&self) {
+            self.parent_constructed();
+
+            let mut duration_labels = vec![];
+            for reading in Reading::ALL {
+                let row = adw::ActionRow::new();
+                row.set_title(&reading_name(reading));
+
+                let label = gtk::Label::new(None);
+                label.add_css_class("numeric");
+                row.add_suffix(&label);
+
+                self.durations.add(&row);
+                duration_labels.push((reading, label));
+            }
+            self.duration_labels.replace(duration_labels);
+
+            glib::timeout_add_local(REFRESH_INTERVAL, {
+                let this = self.obj().downgrade();
+                move || {
+                    let Some(this) = this.upgrade() else {
+                        return glib::ControlFlow::Break;
+                    };
+                    this.imp().refresh();
+
+                    glib::ControlFlow::Continue
+                }
+            });
+        }
+    }
+
+    impl WidgetImpl for GathererStatsDialog {}
+
+    impl AdwDialogImpl for GathererStatsDialog {}
+}
+
+fn reading_name(reading: Reading) -> String {
+    match reading {
+        Reading::Processes => i18n("Processes"),
+        Reading::Apps => i18n("Apps"),
+        Reading::Cpu => i18n("CPU"),
+        Reading::Memory => i18n("Memory"),
+        Reading::Disks => i18n("Disks"),
+        Reading::Network => i18n("Network"),
+        Reading::Gpus => i18n("GPUs"),
+        Reading::Fans => i18n("Fans"),
+        Reading::Services => i18n("Services"),
+    }
+}
+
+glib::wrapper! {
+    pub struct GathererStatsDialog(ObjectSubclass<imp::GathererStatsDialog>)
+        @extends adw::Dialog, gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl GathererStatsDialog {
+    pub fn new() -> Self {
+        let this: Self = glib::Object::builder().build();
+        this.imp().refresh();
+
+        this
+    }
+}
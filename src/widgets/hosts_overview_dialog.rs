@@ -0,0 +1,350 @@
+/* widgets/hosts_overview_dialog.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::cell::RefCell;
+use std::time::Duration;
+
+use adw::{prelude::*, subclass::prelude::*};
+use gtk::glib;
+
+use crate::application::INTERVAL_STEP;
+use crate::magpie_client::{HostMonitor, HostSummary, RemoteHost};
+use crate::performance_page::{category_color, GraphWidget};
+use crate::{app, i18n::*, settings, DataType};
+
+/// Usage, in percent, above which a host gets flagged
+const ALERT_THRESHOLD: f32 = 90.;
+const SPARKLINE_POINTS: u32 = 30;
+
+struct HostTile {
+    /// `None` for this computer
+    host: Option<RemoteHost>,
+    /// Not set for the machine shown in the main window, its readings are handed over as they
+    /// come in instead
+    monitor: Option<HostMonitor>,
+
+    status: gtk::Label,
+    badges: gtk::Box,
+    // CPU, memory, disk and network, in that order
+    graphs: [GraphWidget; 4],
+    values: [gtk::Label; 4],
+}
+
+mod imp {
+    use super::*;
+
+    #[derive(gtk::CompositeTemplate)]
+    #[template(resource = "/io/missioncenter/MissionCenter/ui/widgets/hosts_overview_dialog.ui")]
+    pub struct HostsOverviewDialog {
+        #[template_child]
+        pub tiles: TemplateChild<gtk::FlowBox>,
+
+        pub host_tiles: RefCell<Vec<HostTile>>,
+    }
+
+    impl Default for HostsOverviewDialog {
+        fn default() -> Self {
+            Self {
+                tiles: TemplateChild::default(),
+
+                host_tiles: RefCell::new(vec![]),
+            }
+        }
+    }
+
+    impl HostsOverviewDialog {
+        pub fn populate(&self) {
+            let app = app!();
+            let current = app.remote_host();
+
+            // The machine shown in the main window comes first, followed by everything else that
+            // can be switched to
+            let mut hosts = vec![current.clone()];
+            if current.is_some() {
+                hosts.push(None);
+            }
+            hosts.extend(
+                app.remote_hosts()
+                    .into_iter()
+                    .filter(|host| Some(host) != current.as_ref())
+                    .map(Some),
+            );
+
+            let settings = settings!();
+            let interval = Duration::from_secs_f64(
+                settings.uint64("app-update-interval-u64") as f64 * INTERVAL_STEP,
+            );
+
+            let mut host_tiles = vec![];
+            for (index, host) in hosts.into_iter().enumerate() {
+                let monitor = if index == 0 {
+                    None
+                } else {
+                    Some(HostMonitor::new(host.clone(), interval))
+                };
+
+                let (widget, tile) = host_tile(host, monitor, index == 0);
+                self.tiles.append(&widget);
+                host_tiles.push(tile);
+            }
+            self.host_tiles.replace(host_tiles);
+
+            glib::timeout_add_local(interval, {
+                let this = self.obj().downgrade();
+                move || {
+                    let Some(this) = this.upgrade() else {
+                        return glib::ControlFlow::Break;
+                    };
+
+                    for tile in this.imp().host_tiles.borrow().iter() {
+                        if let Some(summary) = tile.monitor.as_ref().and_then(|m| m.latest()) {
+                            update_tile(tile, &summary);
+                        }
+                    }
+
+                    glib::ControlFlow::Continue
+                }
+            });
+        }
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for HostsOverviewDialog {
+        const NAME: &'static str = "HostsOverviewDialog";
+        type Type = super::HostsOverviewDialog;
+        type ParentType = adw::Dialog;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for HostsOverviewDialog {
+        fn constructed(&self) {
+            self.parent_constructed();
+
+            self.tiles.connect_child_activated({
+                let this = self.obj().downgrade();
+                move |_, child| {
+                    let Some(this) = this.upgrade() else {
+                        return;
+                    };
+
+                    let index = child.index();
+                    // Its pages are the ones already on screen
+                    if index <= 0 {
+                        this.close();
+                        return;
+                    }
+
+                    let host = this
+                        .imp()
+                        .host_tiles
+                        .borrow()
+                        .get(index as usize)
+                        .map(|tile| tile.host.clone());
+                    if let Some(host) = host {
+                        app!().switch_remote_host(host.as_ref());
+                    }
+                }
+            });
+        }
+    }
+
+    impl WidgetImpl for HostsOverviewDialog {}
+
+    impl AdwDialogImpl for HostsOverviewDialog {}
+}
+
+fn host_tile(
+    host: Option<RemoteHost>,
+    monitor: Option<HostMonitor>,
+    current: bool,
+) -> (gtk::FlowBoxChild, HostTile) {
+    let content = gtk::Box::new(gtk::Orientation::Vertical, 6);
+    content.add_css_class("card");
+    let inner = gtk::Box::new(gtk::Orientation::Vertical, 6);
+    inner.set_margin_start(12);
+    inner.set_margin_end(12);
+    inner.set_margin_top(12);
+    inner.set_margin_bottom(12);
+    content.append(&inner);
+
+    let header = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+    let name = gtk::Label::new(Some(
+        host.as_ref()
+            .map(|host| host.name.clone())
+            .unwrap_or_else(|| i18n("This Computer"))
+            .as_str(),
+    ));
+    name.add_css_class("heading");
+    name.set_halign(gtk::Align::Start);
+    name.set_hexpand(true);
+    name.set_ellipsize(gtk::pango::EllipsizeMode::End);
+    header.append(&name);
+
+    let badges = gtk::Box::new(gtk::Orientation::Horizontal, 3);
+    header.append(&badges);
+    inner.append(&header);
+
+    let status = gtk::Label::new(Some(&if current {
+        i18n("Shown in the main window")
+    } else {
+        i18n("Connecting…")
+    }));
+    status.add_css_class("caption");
+    status.add_css_class("dim-label");
+    status.set_halign(gtk::Align::Start);
+    status.set_ellipsize(gtk::pango::EllipsizeMode::End);
+    if let Some(host) = &host {
+        status.set_tooltip_text(Some(&host.destination()));
+    }
+    inner.append(&status);
+
+    let grid = gtk::Grid::new();
+    grid.set_column_spacing(6);
+    grid.set_row_spacing(3);
+
+    let rows = [
+        (i18n("CPU"), category_color("cpu")),
+        (i18n("Memory"), category_color("memory")),
+        (i18n("Disk"), category_color("disk")),
+        (i18n("Network"), category_color("network")),
+    ];
+    let graphs: [GraphWidget; 4] = std::array::from_fn(|_| GraphWidget::new());
+    let values: [gtk::Label; 4] = std::array::from_fn(|_| gtk::Label::new(Some("-")));
+    for (row, ((title, color), (graph, value))) in rows
+        .iter()
+        .zip(graphs.iter().zip(values.iter()))
+        .enumerate()
+    {
+        let title = gtk::Label::new(Some(title));
+        title.add_css_class("caption");
+        title.set_halign(gtk::Align::Start);
+        grid.attach(&title, 0, row as i32, 1, 1);
+
+        graph.set_data_points(SPARKLINE_POINTS);
+        graph.set_grid_visible(false);
+        graph.set_hexpand(true);
+        graph.set_size_request(80, 24);
+        graph.set_base_color(*color);
+        grid.attach(graph, 1, row as i32, 1, 1);
+
+        value.add_css_class("caption");
+        value.add_css_class("numeric");
+        value.set_halign(gtk::Align::End);
+        value.set_width_chars(9);
+        value.set_xalign(1.);
+        grid.attach(value, 2, row as i32, 1, 1);
+    }
+    graphs[3].set_scaling(GraphWidget::auto_pow2_scaling());
+    inner.append(&grid);
+
+    let child = gtk::FlowBoxChild::new();
+    child.set_child(Some(&content));
+    child.set_tooltip_text(Some(&if current {
+        i18n("Close the overview")
+    } else {
+        i18n("Switch to this host")
+    }));
+
+    (
+        child,
+        HostTile {
+            host,
+            monitor,
+            status,
+            badges,
+            graphs,
+            values,
+        },
+    )
+}
+
+fn update_tile(tile: &HostTile, summary: &HostSummary) {
+    while let Some(badge) = tile.badges.first_child() {
+        tile.badges.remove(&badge);
+    }
+
+    if !summary.reachable {
+        tile.status.set_text(&i18n("Unreachable, retrying shortly"));
+        tile.badges.append(&badge(&i18n("Offline"), "error"));
+        return;
+    }
+
+    if tile.monitor.is_some() {
+        tile.status.set_text(&i18n("Connected"));
+    }
+
+    let percentages = [
+        (summary.cpu_usage_percent, i18n("CPU")),
+        (summary.memory_used_percent, i18n("Memory")),
+        (summary.disk_busy_percent, i18n("Disk")),
+    ];
+    for (index, (value, name)) in percentages.iter().enumerate() {
+        tile.graphs[index].add_data_point(0, *value);
+        tile.values[index].set_text(&format!("{}%", value.round() as u32));
+
+        if *value >= ALERT_THRESHOLD {
+            tile.badges.append(&badge(name, "warning"));
+        }
+    }
+
+    tile.graphs[3].add_data_point(0, summary.network_bytes_ps);
+    tile.values[3].set_text(&crate::to_human_readable_nice(
+        summary.network_bytes_ps,
+        &DataType::NetworkBytesPerSecond,
+        &settings!(),
+    ));
+}
+
+fn badge(text: &str, style: &str) -> gtk::Label {
+    let badge = gtk::Label::new(Some(text));
+    badge.add_css_class("caption-heading");
+    badge.add_css_class(style);
+
+    badge
+}
+
+glib::wrapper! {
+    pub struct HostsOverviewDialog(ObjectSubclass<imp::HostsOverviewDialog>)
+        @extends adw::Dialog, gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl HostsOverviewDialog {
+    pub fn new() -> Self {
+        let this: Self = glib::Object::builder().build();
+        this.imp().populate();
+
+        this
+    }
+
+    /// Readings of the machine shown in the main window, which aren't collected twice
+    pub fn update_current(&self, summary: &HostSummary) {
+        if let Some(tile) = self.imp().host_tiles.borrow().first() {
+            update_tile(tile, summary);
+        }
+    }
+}
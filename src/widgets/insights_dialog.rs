@@ -0,0 +1,133 @@
+/* widgets/insights_dialog.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::cell::RefCell;
+
+use adw::{prelude::*, subclass::prelude::*};
+use gtk::glib;
+
+use crate::app;
+use crate::insights::Insight;
+
+mod imp {
+    use super::*;
+
+    #[derive(gtk::CompositeTemplate)]
+    #[template(resource = "/io/missioncenter/MissionCenter/ui/widgets/insights_dialog.ui")]
+    pub struct InsightsDialog {
+        #[template_child]
+        pub stack: TemplateChild<gtk::Stack>,
+        #[template_child]
+        pub insights: TemplateChild<adw::PreferencesGroup>,
+
+        pub rows: RefCell<Vec<adw::ActionRow>>,
+    }
+
+    impl Default for InsightsDialog {
+        fn default() -> Self {
+            Self {
+                stack: TemplateChild::default(),
+                insights: TemplateChild::default(),
+
+                rows: RefCell::new(vec![]),
+            }
+        }
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for InsightsDialog {
+        const NAME: &'static str = "InsightsDialog";
+        type Type = super::InsightsDialog;
+        type ParentType = adw::Dialog;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for InsightsDialog {}
+
+    impl WidgetImpl for InsightsDialog {}
+
+    impl AdwDialogImpl for InsightsDialog {}
+}
+
+glib::wrapper! {
+    pub struct InsightsDialog(ObjectSubclass<imp::InsightsDialog>)
+        @extends adw::Dialog, gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl InsightsDialog {
+    pub fn new(insights: &[Insight]) -> Self {
+        let this: Self = glib::Object::builder().build();
+        this.update(insights);
+
+        this
+    }
+
+    pub fn update(&self, insights: &[Insight]) {
+        let imp = self.imp();
+
+        for row in imp.rows.take() {
+            imp.insights.remove(&row);
+        }
+
+        if insights.is_empty() {
+            imp.stack.set_visible_child_name("empty");
+            return;
+        }
+
+        let mut rows = Vec::with_capacity(insights.len());
+        for insight in insights {
+            let row = adw::ActionRow::new();
+            row.set_title(&insight.title);
+            row.set_subtitle(&insight.explanation);
+            row.set_title_selectable(true);
+
+            let button = gtk::Button::with_label(&insight.link_label);
+            button.set_valign(gtk::Align::Center);
+            button.connect_clicked({
+                let this = self.downgrade();
+                let link = insight.link.clone();
+                move |_| {
+                    let Some(this) = this.upgrade() else {
+                        return;
+                    };
+
+                    this.close();
+                    if let Some(window) = app!().window() {
+                        window.open_deep_link(link.clone());
+                    }
+                }
+            });
+            row.add_suffix(&button);
+
+            imp.insights.add(&row);
+            rows.push(row);
+        }
+        imp.rows.replace(rows);
+        imp.stack.set_visible_child_name("insights");
+    }
+}
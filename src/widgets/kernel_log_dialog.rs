@@ -0,0 +1,222 @@
+/* widgets/kernel_log_dialog.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use adw::{prelude::*, subclass::prelude::*};
+use gtk::glib::{self, g_warning};
+
+use crate::{
+    app,
+    i18n::*,
+    magpie_client::{KernelLogEntry, PRIORITY_ERROR, PRIORITY_WARNING},
+};
+
+mod imp {
+    use super::*;
+
+    #[derive(gtk::CompositeTemplate)]
+    #[template(resource = "/io/missioncenter/MissionCenter/ui/widgets/kernel_log_dialog.ui")]
+    pub struct KernelLogDialog {
+        #[template_child]
+        pub toast_overlay: TemplateChild<adw::ToastOverlay>,
+        #[template_child]
+        pub severity: TemplateChild<gtk::DropDown>,
+        #[template_child]
+        pub copy_log: TemplateChild<gtk::Button>,
+        #[template_child]
+        pub refresh: TemplateChild<gtk::Button>,
+        #[template_child]
+        pub stack: TemplateChild<gtk::Stack>,
+        #[template_child]
+        pub log_buffer: TemplateChild<gtk::TextBuffer>,
+        #[template_child]
+        pub status: TemplateChild<adw::StatusPage>,
+    }
+
+    impl Default for KernelLogDialog {
+        fn default() -> Self {
+            Self {
+                toast_overlay: TemplateChild::default(),
+                severity: TemplateChild::default(),
+                copy_log: TemplateChild::default(),
+                refresh: TemplateChild::default(),
+                stack: TemplateChild::default(),
+                log_buffer: TemplateChild::default(),
+                status: TemplateChild::default(),
+            }
+        }
+    }
+
+    impl KernelLogDialog {
+        pub fn reload(&self) {
+            let max_priority = self.severity.selected() as u8;
+
+            let entries = match app!()
+                .sys_info()
+                .map(|sys_info| sys_info.kernel_log(max_priority))
+            {
+                Ok(entries) => entries,
+                Err(e) => {
+                    g_warning!(
+                        "MissionCenter::KernelLogDialog",
+                        "Failed to get `sys_info`: {}",
+                        e
+                    );
+                    return;
+                }
+            };
+
+            match entries {
+                Ok(entries) if entries.is_empty() => {
+                    self.log_buffer.set_text("");
+                    self.status.set_title(&i18n("No Kernel Messages"));
+                    self.status
+                        .set_description(Some(&i18n("No messages match the selected severity")));
+                    self.stack.set_visible_child_name("empty");
+                }
+                Ok(entries) => {
+                    self.show_entries(&entries);
+                    self.stack.set_visible_child_name("log");
+                }
+                Err(e) => {
+                    self.log_buffer.set_text("");
+                    self.status
+                        .set_title(&i18n("Unable to Read the Kernel Log"));
+                    self.status
+                        .set_description(Some(&glib::markup_escape_text(&e)));
+                    self.stack.set_visible_child_name("empty");
+                }
+            }
+        }
+
+        fn show_entries(&self, entries: &[KernelLogEntry]) {
+            let buffer = &*self.log_buffer;
+            buffer.set_text("");
+
+            let mut end = buffer.end_iter();
+            for entry in entries {
+                let timestamp = glib::DateTime::from_unix_local(entry.timestamp as i64 / 1_000_000)
+                    .and_then(|dt| dt.format("%b %d %H:%M:%S"))
+                    .map(|ts| ts.to_string())
+                    .unwrap_or_default();
+                buffer.insert_with_tags_by_name(&mut end, &timestamp, &["timestamp"]);
+                buffer.insert(&mut end, " ");
+
+                let tag = if entry.priority <= PRIORITY_ERROR {
+                    "error"
+                } else if entry.priority == PRIORITY_WARNING {
+                    "warning"
+                } else {
+                    ""
+                };
+                let line = format!("{}\n", entry.message);
+                if tag.is_empty() {
+                    buffer.insert(&mut end, &line);
+                } else {
+                    buffer.insert_with_tags_by_name(&mut end, &line, &[tag]);
+                }
+            }
+        }
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for KernelLogDialog {
+        const NAME: &'static str = "KernelLogDialog";
+        type Type = super::KernelLogDialog;
+        type ParentType = adw::Dialog;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for KernelLogDialog {
+        fn constructed(&self) {
+            self.parent_constructed();
+
+            self.log_buffer
+                .create_tag(Some("timestamp"), &[("foreground", &"#77767b")]);
+            self.log_buffer.create_tag(
+                Some("error"),
+                &[("foreground", &"#e01b24"), ("weight", &700)],
+            );
+            self.log_buffer
+                .create_tag(Some("warning"), &[("foreground", &"#c88800")]);
+
+            self.severity.set_selected(PRIORITY_WARNING as u32);
+            self.severity.connect_selected_notify({
+                let this = self.obj().downgrade();
+                move |_| {
+                    let Some(this) = this.upgrade() else {
+                        return;
+                    };
+                    this.imp().reload();
+                }
+            });
+
+            self.refresh.connect_clicked({
+                let this = self.obj().downgrade();
+                move |_| {
+                    let Some(this) = this.upgrade() else {
+                        return;
+                    };
+                    this.imp().reload();
+                }
+            });
+
+            self.copy_log.connect_clicked({
+                let this = self.obj().downgrade();
+                move |_| {
+                    let Some(this) = this.upgrade() else {
+                        return;
+                    };
+                    let this = this.imp();
+
+                    let log = this.log_buffer.property::<glib::GString>("text");
+                    this.obj().clipboard().set_text(log.as_str());
+                    this.toast_overlay
+                        .add_toast(adw::Toast::new(&i18n("Copied to clipboard")));
+                }
+            });
+        }
+    }
+
+    impl WidgetImpl for KernelLogDialog {}
+
+    impl AdwDialogImpl for KernelLogDialog {}
+}
+
+glib::wrapper! {
+    pub struct KernelLogDialog(ObjectSubclass<imp::KernelLogDialog>)
+        @extends adw::Dialog, gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl KernelLogDialog {
+    pub fn new() -> Self {
+        let this: Self = glib::Object::builder().build();
+        this.imp().reload();
+
+        this
+    }
+}
@@ -0,0 +1,277 @@
+/* widgets/mini_monitor.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::cell::RefCell;
+
+use adw::{prelude::*, subclass::prelude::*};
+use gtk::{gdk, glib};
+
+use crate::i18n::*;
+use crate::magpie_client::Readings;
+use crate::performance_page::{category_color, GraphWidget};
+use crate::{settings, DataType};
+
+/// The values of `mini-monitor-first-graph`, `mini-monitor-second-graph` also allows `none`
+pub const MINI_MONITOR_GRAPHS: [&str; 5] = ["cpu", "memory", "disk", "network", "gpu"];
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Kind {
+    Cpu,
+    Memory,
+    /// Of the busiest drive
+    Disk,
+    /// Received and sent, over all connections
+    Network,
+    /// Of the busiest GPU
+    Gpu,
+}
+
+impl Kind {
+    fn from_setting(value: &str) -> Option<Self> {
+        match value {
+            "cpu" => Some(Self::Cpu),
+            "memory" => Some(Self::Memory),
+            "disk" => Some(Self::Disk),
+            "network" => Some(Self::Network),
+            "gpu" => Some(Self::Gpu),
+            _ => None,
+        }
+    }
+
+    fn title(&self) -> String {
+        match self {
+            Self::Cpu => i18n("CPU"),
+            Self::Memory => i18n("Memory"),
+            Self::Disk => i18n("Disk"),
+            Self::Network => i18n("Network"),
+            Self::Gpu => i18n("GPU"),
+        }
+    }
+
+    fn color(&self) -> gdk::RGBA {
+        category_color(match self {
+            Self::Cpu => "cpu",
+            Self::Memory => "memory",
+            Self::Disk => "disk",
+            Self::Network => "network",
+            Self::Gpu => "gpu",
+        })
+    }
+
+    fn value(&self, readings: &Readings) -> f32 {
+        match self {
+            Self::Cpu => readings.cpu.total_usage_percent,
+            Self::Memory => {
+                let mem_info = &readings.mem_info;
+                if mem_info.mem_total == 0 {
+                    return 0.;
+                }
+
+                mem_info.mem_total.saturating_sub(mem_info.mem_available) as f32
+                    / mem_info.mem_total as f32
+                    * 100.
+            }
+            Self::Disk => readings
+                .disks_info
+                .iter()
+                .map(|disk| disk.busy_percent)
+                .fold(0., f32::max),
+            Self::Network => readings
+                .network_connections
+                .iter()
+                .map(|connection| connection.rx_rate_bytes_ps + connection.tx_rate_bytes_ps)
+                .sum(),
+            Self::Gpu => readings
+                .gpus
+                .values()
+                .filter_map(|gpu| gpu.utilization_percent)
+                .fold(0., f32::max),
+        }
+    }
+
+    fn label(&self, value: f32) -> String {
+        match self {
+            Self::Network => {
+                crate::to_human_readable_nice(value, &DataType::NetworkBytesPerSecond, &settings!())
+            }
+            _ => format!("{}%", value.round() as u32),
+        }
+    }
+}
+
+struct MiniGraph {
+    kind: Kind,
+    graph: GraphWidget,
+    value: gtk::Label,
+}
+
+mod imp {
+    use super::*;
+
+    #[derive(gtk::CompositeTemplate)]
+    #[template(resource = "/io/missioncenter/MissionCenter/ui/widgets/mini_monitor.ui")]
+    pub struct MiniMonitor {
+        #[template_child]
+        pub graphs: TemplateChild<gtk::Box>,
+
+        pub mini_graphs: RefCell<Vec<MiniGraph>>,
+        pub settings_changed: RefCell<Vec<glib::SignalHandlerId>>,
+    }
+
+    impl Default for MiniMonitor {
+        fn default() -> Self {
+            Self {
+                graphs: TemplateChild::default(),
+
+                mini_graphs: RefCell::new(vec![]),
+                settings_changed: RefCell::new(vec![]),
+            }
+        }
+    }
+
+    impl MiniMonitor {
+        pub fn populate(&self) {
+            while let Some(child) = self.graphs.first_child() {
+                self.graphs.remove(&child);
+            }
+
+            let settings = settings!();
+            let data_points = settings.int("performance-page-data-points") as u32;
+
+            let mut kinds = vec![];
+            kinds.extend(Kind::from_setting(
+                &settings.string("mini-monitor-first-graph"),
+            ));
+            kinds.extend(
+                Kind::from_setting(&settings.string("mini-monitor-second-graph"))
+                    .filter(|kind| !kinds.contains(kind)),
+            );
+
+            let mut mini_graphs = vec![];
+            for kind in kinds {
+                let content = gtk::Box::new(gtk::Orientation::Vertical, 3);
+
+                let header = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+                let title = gtk::Label::new(Some(&kind.title()));
+                title.add_css_class("caption-heading");
+                title.set_halign(gtk::Align::Start);
+                title.set_hexpand(true);
+                header.append(&title);
+
+                let value = gtk::Label::new(Some("-"));
+                value.add_css_class("caption");
+                value.add_css_class("numeric");
+                value.set_halign(gtk::Align::End);
+                header.append(&value);
+                content.append(&header);
+
+                let graph = GraphWidget::new();
+                graph.set_data_points(data_points);
+                graph.set_hexpand(true);
+                graph.set_vexpand(true);
+                graph.set_size_request(120, 40);
+                graph.set_base_color(kind.color());
+                if kind == Kind::Network {
+                    graph.set_scaling(GraphWidget::auto_pow2_scaling());
+                }
+                content.append(&graph);
+
+                self.graphs.append(&content);
+                mini_graphs.push(MiniGraph { kind, graph, value });
+            }
+            self.mini_graphs.replace(mini_graphs);
+        }
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for MiniMonitor {
+        const NAME: &'static str = "MiniMonitor";
+        type Type = super::MiniMonitor;
+        type ParentType = adw::Window;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for MiniMonitor {
+        fn constructed(&self) {
+            self.parent_constructed();
+
+            self.populate();
+
+            let settings = settings!();
+            let handlers = [
+                "mini-monitor-first-graph",
+                "mini-monitor-second-graph",
+                "performance-page-colors",
+            ]
+            .map(|key| {
+                settings.connect_changed(Some(key), {
+                    let this = self.obj().downgrade();
+                    move |_, _| {
+                        if let Some(this) = this.upgrade() {
+                            this.imp().populate();
+                        }
+                    }
+                })
+            });
+            self.settings_changed.replace(handlers.into());
+        }
+
+        fn dispose(&self) {
+            let settings = settings!();
+            for handler in self.settings_changed.take() {
+                settings.disconnect(handler);
+            }
+        }
+    }
+
+    impl WidgetImpl for MiniMonitor {}
+
+    impl WindowImpl for MiniMonitor {}
+
+    impl AdwWindowImpl for MiniMonitor {}
+}
+
+glib::wrapper! {
+    pub struct MiniMonitor(ObjectSubclass<imp::MiniMonitor>)
+        @extends gtk::Widget, gtk::Window, adw::Window,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::Native,
+            gtk::Root, gtk::ShortcutManager;
+}
+
+impl MiniMonitor {
+    pub fn new() -> Self {
+        glib::Object::builder().build()
+    }
+
+    pub fn update_readings(&self, readings: &Readings) {
+        for mini_graph in self.imp().mini_graphs.borrow().iter() {
+            let value = mini_graph.kind.value(readings);
+            mini_graph.graph.add_data_point(0, value);
+            mini_graph.value.set_text(&mini_graph.kind.label(value));
+        }
+    }
+}
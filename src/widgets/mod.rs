@@ -1,5 +1,23 @@
+mod boot_analysis_dialog;
+mod command_palette;
+mod crashes_dialog;
+mod gatherer_stats_dialog;
+mod hosts_overview_dialog;
+mod insights_dialog;
+mod kernel_log_dialog;
 mod list_cell;
+mod mini_monitor;
+mod system_info_dialog;
 mod theme_selector;
 
+pub use boot_analysis_dialog::BootAnalysisDialog;
+pub use command_palette::{Command, CommandPalette};
+pub use crashes_dialog::CrashesDialog;
+pub use gatherer_stats_dialog::GathererStatsDialog;
+pub use hosts_overview_dialog::HostsOverviewDialog;
+pub use insights_dialog::InsightsDialog;
+pub use kernel_log_dialog::KernelLogDialog;
 pub use list_cell::ListCell;
+pub use mini_monitor::{MiniMonitor, MINI_MONITOR_GRAPHS};
+pub use system_info_dialog::{system_info_sections, SystemInfoDialog};
 pub use theme_selector::ThemeSelector;
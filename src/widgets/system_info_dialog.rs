@@ -0,0 +1,325 @@
+/* widgets/system_info_dialog.rs
+ *
+ * Copyright 2025 Mission Center Developers
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ * SPDX-License-Identifier: GPL-3.0-or-later
+ */
+
+use std::cell::RefCell;
+
+use adw::{prelude::*, subclass::prelude::*};
+use gtk::glib;
+
+use crate::{
+    config::VERSION, i18n::*, is_flatpak, magpie_client::Readings, settings,
+    to_long_human_readable_time, DataType,
+};
+
+/// One line of the summary; the untranslated label is used for the Markdown copy, which usually
+/// ends up in bug reports
+struct Entry {
+    label: String,
+    label_en: &'static str,
+    value: String,
+}
+
+struct Section {
+    title: String,
+    title_en: &'static str,
+    entries: Vec<Entry>,
+}
+
+mod imp {
+    use super::*;
+
+    #[derive(gtk::CompositeTemplate)]
+    #[template(resource = "/io/missioncenter/MissionCenter/ui/widgets/system_info_dialog.ui")]
+    pub struct SystemInfoDialog {
+        #[template_child]
+        pub toast_overlay: TemplateChild<adw::ToastOverlay>,
+        #[template_child]
+        pub copy_markdown: TemplateChild<gtk::Button>,
+        #[template_child]
+        pub page: TemplateChild<adw::PreferencesPage>,
+
+        pub markdown: RefCell<String>,
+    }
+
+    impl Default for SystemInfoDialog {
+        fn default() -> Self {
+            Self {
+                toast_overlay: TemplateChild::default(),
+                copy_markdown: TemplateChild::default(),
+                page: TemplateChild::default(),
+
+                markdown: RefCell::new(String::new()),
+            }
+        }
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for SystemInfoDialog {
+        const NAME: &'static str = "SystemInfoDialog";
+        type Type = super::SystemInfoDialog;
+        type ParentType = adw::Dialog;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for SystemInfoDialog {
+        fn constructed(&self) {
+            self.parent_constructed();
+
+            self.copy_markdown.connect_clicked({
+                let this = self.obj().downgrade();
+                move |_| {
+                    let Some(this) = this.upgrade() else {
+                        return;
+                    };
+                    let this = this.imp();
+
+                    this.obj()
+                        .clipboard()
+                        .set_text(this.markdown.borrow().as_str());
+                    this.toast_overlay
+                        .add_toast(adw::Toast::new(&i18n("Copied to clipboard")));
+                }
+            });
+        }
+    }
+
+    impl WidgetImpl for SystemInfoDialog {}
+
+    impl AdwDialogImpl for SystemInfoDialog {}
+}
+
+glib::wrapper! {
+    pub struct SystemInfoDialog(ObjectSubclass<imp::SystemInfoDialog>)
+        @extends adw::Dialog, gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl SystemInfoDialog {
+    pub fn new(readings: &Readings) -> Self {
+        let this: Self = glib::Object::builder().build();
+
+        let sections = collect(readings);
+
+        let imp = this.imp();
+        for section in &sections {
+            let group = adw::PreferencesGroup::new();
+            group.set_title(&section.title);
+
+            for entry in &section.entries {
+                let row = adw::ActionRow::new();
+                row.set_title(&entry.label);
+                row.set_subtitle(&glib::markup_escape_text(&entry.value));
+                row.set_subtitle_selectable(true);
+                row.add_css_class("property");
+                group.add(&row);
+            }
+
+            imp.page.add(&group);
+        }
+
+        imp.markdown.replace(to_markdown(&sections));
+
+        this
+    }
+}
+
+/// The summary, with untranslated labels, for the system report
+pub fn system_info_sections(
+    readings: &Readings,
+) -> Vec<(&'static str, Vec<(&'static str, String)>)> {
+    collect(readings)
+        .into_iter()
+        .map(|section| {
+            (
+                section.title_en,
+                section
+                    .entries
+                    .into_iter()
+                    .map(|entry| (entry.label_en, entry.value))
+                    .collect(),
+            )
+        })
+        .collect()
+}
+
+fn collect(readings: &Readings) -> Vec<Section> {
+    let settings = settings!();
+    let unknown = i18n("Unknown");
+
+    let mut system = vec![];
+    system.push(Entry {
+        label: i18n("Hostname"),
+        label_en: "Hostname",
+        value: glib::host_name().to_string(),
+    });
+    system.push(Entry {
+        label: i18n("Operating System"),
+        label_en: "Operating System",
+        value: os_pretty_name().unwrap_or_else(|| unknown.clone()),
+    });
+    system.push(Entry {
+        label: i18n("Kernel"),
+        label_en: "Kernel",
+        value: std::fs::read_to_string("/proc/sys/kernel/osrelease")
+            .map(|release| release.trim().to_owned())
+            .unwrap_or_else(|_| unknown.clone()),
+    });
+    system.push(Entry {
+        label: i18n("Desktop"),
+        label_en: "Desktop",
+        value: desktop().unwrap_or_else(|| unknown.clone()),
+    });
+    system.push(Entry {
+        label: i18n("Uptime"),
+        label_en: "Uptime",
+        value: to_long_human_readable_time(readings.cpu.uptime_seconds),
+    });
+    system.push(Entry {
+        label: i18n("Mission Center"),
+        label_en: "Mission Center",
+        value: if is_flatpak() {
+            format!("{} (Flatpak)", VERSION)
+        } else {
+            VERSION.to_owned()
+        },
+    });
+
+    let mut hardware = vec![];
+    hardware.push(Entry {
+        label: i18n("Processor"),
+        label_en: "Processor",
+        value: format!(
+            "{} ({})",
+            readings.cpu.name.as_ref().unwrap_or(&unknown),
+            ni18n_f(
+                "{} logical processor",
+                "{} logical processors",
+                readings.cpu.core_usage_percent.len() as u32,
+                &[&readings.cpu.core_usage_percent.len().to_string()],
+            )
+        ),
+    });
+    hardware.push(Entry {
+        label: i18n("Memory"),
+        label_en: "Memory",
+        value: crate::to_human_readable_nice(
+            readings.mem_info.mem_total as f32,
+            &DataType::MemoryBytes,
+            &settings,
+        ),
+    });
+
+    let mut gpus = readings.gpus.values().collect::<Vec<_>>();
+    gpus.sort_by(|g1, g2| g1.id.cmp(&g2.id));
+    for gpu in gpus {
+        hardware.push(Entry {
+            label: i18n("Graphics"),
+            label_en: "Graphics",
+            value: gpu.device_name.as_ref().unwrap_or(&unknown).clone(),
+        });
+    }
+
+    for disk in &readings.disks_info {
+        let capacity = if disk.capacity_bytes > 0 {
+            crate::to_human_readable_nice(
+                disk.capacity_bytes as f32,
+                &DataType::MemoryBytes,
+                &settings,
+            )
+        } else {
+            unknown.clone()
+        };
+
+        hardware.push(Entry {
+            label: i18n("Drive"),
+            label_en: "Drive",
+            value: format!(
+                "{} ({}, {})",
+                disk.model.as_ref().unwrap_or(&disk.id),
+                disk.id,
+                capacity
+            ),
+        });
+    }
+
+    vec![
+        Section {
+            title: i18n("System"),
+            title_en: "System",
+            entries: system,
+        },
+        Section {
+            title: i18n("Hardware"),
+            title_en: "Hardware",
+            entries: hardware,
+        },
+    ]
+}
+
+fn to_markdown(sections: &[Section]) -> String {
+    let mut result = String::from("## System Information\n");
+
+    for section in sections {
+        result.push_str(&format!("\n### {}\n\n", section.title_en));
+        for entry in &section.entries {
+            result.push_str(&format!("- **{}:** {}\n", entry.label_en, entry.value));
+        }
+    }
+
+    result
+}
+
+fn os_pretty_name() -> Option<String> {
+    // Inside the sandbox /etc/os-release describes the Flatpak runtime, not the host
+    let path = if is_flatpak() {
+        "/run/host/os-release"
+    } else {
+        "/etc/os-release"
+    };
+
+    let content = std::fs::read_to_string(path)
+        .or_else(|_| std::fs::read_to_string("/usr/lib/os-release"))
+        .ok()?;
+
+    content.lines().find_map(|line| {
+        line.strip_prefix("PRETTY_NAME=")
+            .map(|name| name.trim_matches('"').to_owned())
+    })
+}
+
+fn desktop() -> Option<String> {
+    let desktop = std::env::var("XDG_CURRENT_DESKTOP")
+        .ok()
+        .filter(|d| !d.is_empty())?;
+    let desktop = desktop.replace(':', ", ");
+
+    match std::env::var("XDG_SESSION_TYPE") {
+        Ok(session_type) if session_type == "wayland" => Some(format!("{} (Wayland)", desktop)),
+        Ok(session_type) if session_type == "x11" => Some(format!("{} (X11)", desktop)),
+        _ => Some(desktop),
+    }
+}
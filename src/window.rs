@@ -18,8 +18,8 @@
  * SPDX-License-Identifier: GPL-3.0-or-later
  */
 
-use std::cell::Cell;
-use std::collections::HashMap;
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
 use std::sync::OnceLock;
 use std::time::Duration;
 
@@ -28,9 +28,21 @@ use glib::{g_critical, idle_add_local_once, ParamSpec, Propagation, Properties,
 use gtk::glib::ControlFlow;
 use gtk::{gdk, gio, glib};
 
-use crate::widgets::ListCell;
+use crate::i18n::*;
 use crate::widgets::ThemeSelector;
-use crate::{app, magpie_client::Readings, settings};
+use crate::widgets::{
+    BootAnalysisDialog, Command, CommandPalette, CrashesDialog, GathererStatsDialog,
+    HostsOverviewDialog, InsightsDialog, KernelLogDialog, ListCell, MiniMonitor, SystemInfoDialog,
+};
+use crate::{
+    app,
+    application::DeepLink,
+    history::HistoryRecorder,
+    insights::Insights,
+    magpie_client::{HostSummary, Readings, RemoteHost},
+    report::Report,
+    settings, DataType,
+};
 
 fn special_shortcuts(
 ) -> &'static HashMap<gdk::ModifierType, HashMap<gdk::Key, fn(&MissionCenterWindow) -> bool>> {
@@ -221,6 +233,16 @@ fn special_shortcuts(
     })
 }
 
+/// How many processes and services the command palette offers to go back to
+const MAX_RECENT_ITEMS: usize = 5;
+
+/// A process or service the user looked at, by name since PIDs don't survive restarts
+#[derive(Debug, Clone, PartialEq)]
+pub enum RecentItem {
+    Process(String),
+    Service(String),
+}
+
 mod imp {
     use super::*;
 
@@ -246,10 +268,16 @@ mod imp {
         #[template_child]
         pub sidebar_edit_mode_reset: TemplateChild<gtk::Button>,
         #[template_child]
+        pub toggle_sidebar_edit_mode_button: TemplateChild<gtk::ToggleButton>,
+        #[template_child]
         pub toggle_sidebar_button: TemplateChild<gtk::ToggleButton>,
         #[template_child]
         pub sidebar: TemplateChild<gtk::ListBox>,
         #[template_child]
+        pub host_switcher_box: TemplateChild<gtk::Box>,
+        #[template_child]
+        pub host_switcher: TemplateChild<gtk::DropDown>,
+        #[template_child]
         pub performance_page: TemplateChild<crate::performance_page::PerformancePage>,
         #[template_child]
         pub apps_page: TemplateChild<crate::apps_page::AppsPage>,
@@ -258,6 +286,10 @@ mod imp {
         #[template_child]
         pub services_page: TemplateChild<crate::services_page::ServicesPage>,
         #[template_child]
+        pub users_stack_page: TemplateChild<adw::ViewStackPage>,
+        #[template_child]
+        pub users_page: TemplateChild<crate::users_page::UsersPage>,
+        #[template_child]
         pub header_bar: TemplateChild<adw::HeaderBar>,
         #[template_child]
         pub header_stack: TemplateChild<gtk::Stack>,
@@ -268,6 +300,16 @@ mod imp {
         #[template_child]
         pub search_button: TemplateChild<gtk::ToggleButton>,
         #[template_child]
+        pub pause_button: TemplateChild<gtk::ToggleButton>,
+        #[template_child]
+        pub foreground_boost_indicator: TemplateChild<gtk::Image>,
+        #[template_child]
+        pub insights_button: TemplateChild<gtk::Button>,
+        #[template_child]
+        pub low_memory_banner: TemplateChild<adw::Banner>,
+        #[template_child]
+        pub gatherer_banner: TemplateChild<adw::Banner>,
+        #[template_child]
         pub loading_box: TemplateChild<gtk::Box>,
         #[template_child]
         pub loading_spinner: TemplateChild<adw::Spinner>,
@@ -281,6 +323,8 @@ mod imp {
         #[property(get)]
         services_page_active: Cell<bool>,
         #[property(get)]
+        users_page_active: Cell<bool>,
+        #[property(get)]
         user_hid_sidebar: Cell<bool>,
 
         #[property(name = "info-button-visible", get = Self::info_button_visible, type = bool)]
@@ -292,6 +336,27 @@ mod imp {
         summary_mode: Cell<bool>,
         #[property(get, set)]
         collapse_threshold: Cell<i32>,
+
+        pub pending_deep_link: RefCell<Option<DeepLink>>,
+        // Name and processes of what the low memory banner offers to end
+        pub low_memory_target: RefCell<Option<(String, Vec<u32>)>>,
+        // The system information dialog is built from the next readings that come in
+        pub system_info_requested: Cell<bool>,
+        // So is the report, which also needs the services before the services page takes them
+        pub report_requested: Cell<bool>,
+        // Readings keep being collected while paused, they just aren't shown
+        pub paused: Cell<bool>,
+        // The hosts listed in the host switcher, after "This Computer"
+        pub switchable_hosts: RefCell<Vec<RemoteHost>>,
+        pub updating_host_switcher: Cell<bool>,
+        pub hosts_overview: glib::WeakRef<HostsOverviewDialog>,
+        pub insights: RefCell<Insights>,
+        pub insights_dialog: glib::WeakRef<InsightsDialog>,
+        pub mini_monitor: glib::WeakRef<MiniMonitor>,
+        // Most recent first
+        pub recent_items: RefCell<Vec<RecentItem>>,
+        // Only around while the history is turned on
+        pub history_recorder: RefCell<Option<HistoryRecorder>>,
     }
 
     impl Default for MissionCenterWindow {
@@ -305,17 +370,27 @@ mod imp {
                 sidebar_edit_mode_enable_all: TemplateChild::default(),
                 sidebar_edit_mode_disable_all: TemplateChild::default(),
                 sidebar_edit_mode_reset: TemplateChild::default(),
+                toggle_sidebar_edit_mode_button: TemplateChild::default(),
                 toggle_sidebar_button: TemplateChild::default(),
                 sidebar: TemplateChild::default(),
+                host_switcher_box: TemplateChild::default(),
+                host_switcher: TemplateChild::default(),
                 performance_page: TemplateChild::default(),
                 apps_page: TemplateChild::default(),
                 services_stack_page: TemplateChild::default(),
                 services_page: TemplateChild::default(),
+                users_stack_page: TemplateChild::default(),
+                users_page: TemplateChild::default(),
                 header_bar: TemplateChild::default(),
                 header_stack: TemplateChild::default(),
                 header_tabs: TemplateChild::default(),
                 header_search_entry: TemplateChild::default(),
                 search_button: TemplateChild::default(),
+                pause_button: TemplateChild::default(),
+                foreground_boost_indicator: TemplateChild::default(),
+                insights_button: TemplateChild::default(),
+                low_memory_banner: TemplateChild::default(),
+                gatherer_banner: TemplateChild::default(),
                 loading_box: TemplateChild::default(),
                 loading_spinner: TemplateChild::default(),
                 stack: TemplateChild::default(),
@@ -323,6 +398,7 @@ mod imp {
                 performance_page_active: Cell::new(true),
                 apps_page_active: Cell::new(false),
                 services_page_active: Cell::new(false),
+                users_page_active: Cell::new(false),
                 user_hid_sidebar: Cell::new(false),
 
                 _info_button_visible: [0; 0],
@@ -330,6 +406,20 @@ mod imp {
 
                 summary_mode: Cell::new(false),
                 collapse_threshold: Cell::new(0),
+
+                pending_deep_link: RefCell::new(None),
+                low_memory_target: RefCell::new(None),
+                system_info_requested: Cell::new(false),
+                report_requested: Cell::new(false),
+                paused: Cell::new(false),
+                switchable_hosts: RefCell::new(vec![]),
+                updating_host_switcher: Cell::new(false),
+                hosts_overview: glib::WeakRef::new(),
+                insights: RefCell::new(Insights::new()),
+                insights_dialog: glib::WeakRef::new(),
+                mini_monitor: glib::WeakRef::new(),
+                recent_items: RefCell::new(vec![]),
+                history_recorder: RefCell::new(None),
             }
         }
     }
@@ -367,6 +457,9 @@ mod imp {
 
                 self.services_page_active.set(false);
                 self.obj().notify_services_page_active();
+
+                self.users_page_active.set(false);
+                self.obj().notify_users_page_active();
             }
             if visible_child_name == "apps-page" {
                 if self.apps_page_active.get() {
@@ -381,6 +474,9 @@ mod imp {
 
                 self.services_page_active.set(false);
                 self.obj().notify_services_page_active();
+
+                self.users_page_active.set(false);
+                self.obj().notify_users_page_active();
             } else if visible_child_name == "services-page" {
                 if self.services_page_active.get() {
                     return;
@@ -394,6 +490,25 @@ mod imp {
 
                 self.services_page_active.set(true);
                 self.obj().notify_services_page_active();
+
+                self.users_page_active.set(false);
+                self.obj().notify_users_page_active();
+            } else if visible_child_name == "users-page" {
+                if self.users_page_active.get() {
+                    return;
+                }
+
+                self.performance_page_active.set(false);
+                self.obj().notify_performance_page_active();
+
+                self.apps_page_active.set(false);
+                self.obj().notify_apps_page_active();
+
+                self.services_page_active.set(false);
+                self.obj().notify_services_page_active();
+
+                self.users_page_active.set(true);
+                self.obj().notify_users_page_active();
             }
 
             self.obj().notify_info_button_visible();
@@ -535,6 +650,160 @@ mod imp {
             self.obj().add_action(&action);
             app.set_accels_for_action("win.select-tab-services", &["<Control>3"]);
 
+            let action = gio::SimpleAction::new("select-tab-users", None);
+            action.connect_activate({
+                let this = self.obj().downgrade();
+                move |_, _| {
+                    let this = match this.upgrade() {
+                        Some(this) => this,
+                        None => return,
+                    };
+                    let imp = this.imp();
+                    if imp.summary_mode.get() || !imp.users_stack_page.is_visible() {
+                        return;
+                    }
+                    imp.stack.set_visible_child_name("users-page");
+                }
+            });
+            self.obj().add_action(&action);
+            app.set_accels_for_action("win.select-tab-users", &["<Control>4"]);
+
+            let action = gio::SimpleAction::new("system-info", None);
+            action.connect_activate({
+                let this = self.obj().downgrade();
+                move |_, _| {
+                    let Some(this) = this.upgrade() else {
+                        return;
+                    };
+                    this.imp().system_info_requested.set(true);
+                }
+            });
+            self.obj().add_action(&action);
+
+            let action = gio::SimpleAction::new("generate-report", None);
+            action.connect_activate({
+                let this = self.obj().downgrade();
+                move |_, _| {
+                    let Some(this) = this.upgrade() else {
+                        return;
+                    };
+                    this.imp().report_requested.set(true);
+                }
+            });
+            self.obj().add_action(&action);
+
+            let action = gio::SimpleAction::new("kernel-log", None);
+            action.connect_activate({
+                let this = self.obj().downgrade();
+                move |_, _| {
+                    let Some(this) = this.upgrade() else {
+                        return;
+                    };
+                    KernelLogDialog::new().present(Some(&this));
+                }
+            });
+            self.obj().add_action(&action);
+
+            let action = gio::SimpleAction::new("hosts-overview", None);
+            action.connect_activate({
+                let this = self.obj().downgrade();
+                move |_, _| {
+                    let Some(this) = this.upgrade() else {
+                        return;
+                    };
+
+                    let dialog = HostsOverviewDialog::new();
+                    this.imp().hosts_overview.set(Some(&dialog));
+                    dialog.present(Some(&this));
+                }
+            });
+            self.obj().add_action(&action);
+
+            let action = gio::SimpleAction::new("insights", None);
+            action.connect_activate({
+                let this = self.obj().downgrade();
+                move |_, _| {
+                    let Some(this) = this.upgrade() else {
+                        return;
+                    };
+
+                    let dialog = InsightsDialog::new(this.imp().insights.borrow().current());
+                    this.imp().insights_dialog.set(Some(&dialog));
+                    dialog.present(Some(&this));
+                }
+            });
+            self.obj().add_action(&action);
+
+            let action = gio::SimpleAction::new("mini-monitor", None);
+            action.connect_activate({
+                let this = self.obj().downgrade();
+                move |_, _| {
+                    let Some(this) = this.upgrade() else {
+                        return;
+                    };
+
+                    // Not tied to the application, so it doesn't keep it running once the main
+                    // window is closed
+                    let mini_monitor = this.imp().mini_monitor.upgrade().unwrap_or_else(|| {
+                        let mini_monitor = MiniMonitor::new();
+                        this.imp().mini_monitor.set(Some(&mini_monitor));
+                        mini_monitor
+                    });
+                    mini_monitor.present();
+                }
+            });
+            self.obj().add_action(&action);
+
+            let action = gio::SimpleAction::new("command-palette", None);
+            action.connect_activate({
+                let this = self.obj().downgrade();
+                move |_, _| {
+                    let Some(this) = this.upgrade() else {
+                        return;
+                    };
+
+                    CommandPalette::new(this.palette_commands()).present(Some(&this));
+                }
+            });
+            self.obj().add_action(&action);
+            app.set_accels_for_action("win.command-palette", &["<Control>K"]);
+
+            let action = gio::SimpleAction::new("crashes", None);
+            action.connect_activate({
+                let this = self.obj().downgrade();
+                move |_, _| {
+                    let Some(this) = this.upgrade() else {
+                        return;
+                    };
+                    CrashesDialog::new().present(Some(&this));
+                }
+            });
+            self.obj().add_action(&action);
+
+            let action = gio::SimpleAction::new("boot-analysis", None);
+            action.connect_activate({
+                let this = self.obj().downgrade();
+                move |_, _| {
+                    let Some(this) = this.upgrade() else {
+                        return;
+                    };
+                    BootAnalysisDialog::new().present(Some(&this));
+                }
+            });
+            self.obj().add_action(&action);
+
+            let action = gio::SimpleAction::new("gatherer-stats", None);
+            action.connect_activate({
+                let this = self.obj().downgrade();
+                move |_, _| {
+                    let Some(this) = this.upgrade() else {
+                        return;
+                    };
+                    GathererStatsDialog::new().present(Some(&this));
+                }
+            });
+            self.obj().add_action(&action);
+
             let action =
                 gio::SimpleAction::new_stateful("toggle-sidebar", None, &true.to_variant());
             action.connect_activate({
@@ -574,6 +843,35 @@ mod imp {
             self.obj().add_action(&action);
             app.set_accels_for_action("win.toggle-sidebar", &["<Control>T"]);
 
+            let action = gio::SimpleAction::new_stateful("toggle-pause", None, &false.to_variant());
+            action.connect_activate({
+                let this = self.obj().downgrade();
+                move |action, _| {
+                    let Some(this) = this.upgrade() else {
+                        return;
+                    };
+                    let imp = this.imp();
+
+                    let paused = !imp.paused.get();
+                    imp.paused.set(paused);
+                    action.set_state(&paused.to_variant());
+
+                    if paused {
+                        imp.pause_button
+                            .set_icon_name("media-playback-start-symbolic");
+                        imp.pause_button
+                            .set_tooltip_text(Some(&i18n("Resume Updates")));
+                    } else {
+                        imp.pause_button
+                            .set_icon_name("media-playback-pause-symbolic");
+                        imp.pause_button
+                            .set_tooltip_text(Some(&i18n("Pause Updates")));
+                    }
+                }
+            });
+            self.obj().add_action(&action);
+            app.set_accels_for_action("win.toggle-pause", &["<Control>P"]);
+
             let action = gio::SimpleAction::new("close", None);
             action.connect_activate({
                 let this = self.obj().downgrade();
@@ -656,6 +954,100 @@ mod imp {
             }
         }
 
+        fn update_host_switcher(&self) {
+            let app = app!();
+
+            let mut hosts = app.remote_hosts();
+            let current = app.remote_host();
+            // Removing the host being monitored only takes effect once switching away from it
+            if let Some(current) = &current {
+                if !hosts.contains(current) {
+                    hosts.push(current.clone());
+                }
+            }
+
+            let mut names = vec![i18n("This Computer")];
+            names.extend(hosts.iter().map(|host| host.name.clone()));
+            let selected = current
+                .and_then(|current| hosts.iter().position(|host| *host == current))
+                .map(|index| index + 1)
+                .unwrap_or(0);
+
+            self.updating_host_switcher.set(true);
+            self.host_switcher.set_model(Some(&gtk::StringList::new(
+                &names.iter().map(String::as_str).collect::<Vec<_>>(),
+            )));
+            self.host_switcher.set_selected(selected as u32);
+            self.updating_host_switcher.set(false);
+
+            self.host_switcher_box.set_visible(!hosts.is_empty());
+            self.switchable_hosts.replace(hosts);
+        }
+
+        fn configure_host_switcher(&self) {
+            self.host_switcher.connect_selected_notify({
+                let this = self.obj().downgrade();
+                move |host_switcher| {
+                    let Some(this) = this.upgrade() else {
+                        return;
+                    };
+                    let this = this.imp();
+                    if this.updating_host_switcher.get() {
+                        return;
+                    }
+
+                    let host = match host_switcher.selected() {
+                        0 | gtk::INVALID_LIST_POSITION => None,
+                        index => this
+                            .switchable_hosts
+                            .borrow()
+                            .get(index as usize - 1)
+                            .cloned(),
+                    };
+
+                    let app = app!();
+                    if host != app.remote_host() {
+                        app.switch_remote_host(host.as_ref());
+                    }
+                }
+            });
+
+            settings!().connect_changed(Some("remote-hosts"), {
+                let this = self.obj().downgrade();
+                move |_, _| {
+                    if let Some(this) = this.upgrade() {
+                        this.imp().update_host_switcher();
+                    }
+                }
+            });
+            self.update_host_switcher();
+        }
+
+        fn configure_history(&self) {
+            fn update_recorder(this: &super::MissionCenterWindow, settings: &gio::Settings) {
+                let this = this.imp();
+
+                let enabled = settings.boolean("history-enabled");
+                let mut recorder = this.history_recorder.borrow_mut();
+                if enabled && recorder.is_none() {
+                    *recorder = Some(HistoryRecorder::new());
+                } else if !enabled {
+                    *recorder = None;
+                }
+            }
+
+            let settings = settings!();
+            settings.connect_changed(Some("history-enabled"), {
+                let this = self.obj().downgrade();
+                move |settings, _| {
+                    if let Some(this) = this.upgrade() {
+                        update_recorder(&this, settings);
+                    }
+                }
+            });
+            update_recorder(&self.obj(), &settings);
+        }
+
         #[inline]
         fn window_width_below_threshold(&self) -> bool {
             let window_width =
@@ -686,7 +1078,8 @@ mod imp {
 
         fn class_init(klass: &mut Self::Class) {
             use crate::{
-                apps_page::AppsPage, performance_page::PerformancePage, services_page::ServicesPage,
+                apps_page::AppsPage, performance_page::PerformancePage,
+                services_page::ServicesPage, users_page::UsersPage,
             };
 
             ListCell::ensure_type();
@@ -694,6 +1087,7 @@ mod imp {
             PerformancePage::ensure_type();
             AppsPage::ensure_type();
             ServicesPage::ensure_type();
+            UsersPage::ensure_type();
 
             klass.bind_template();
         }
@@ -721,6 +1115,8 @@ mod imp {
 
             self.configure_actions();
             self.configure_theme_selection();
+            self.configure_host_switcher();
+            self.configure_history();
 
             idle_add_local_once({
                 let this = self.obj().downgrade();
@@ -732,6 +1128,15 @@ mod imp {
                 }
             });
 
+            self.low_memory_banner.connect_button_clicked({
+                let this = self.obj().downgrade();
+                move |_| {
+                    if let Some(this) = this.upgrade() {
+                        this.confirm_end_low_memory_target();
+                    }
+                }
+            });
+
             self.sidebar.connect_row_activated({
                 let this = self.obj().downgrade();
                 move |_, _| {
@@ -959,8 +1364,14 @@ mod imp {
         fn realize(&self) {
             self.parent_realize();
 
-            self.stack
-                .set_visible_child_name(settings!().string("window-selected-page").as_str());
+            let settings = settings!();
+            let startup_page = settings.string("window-startup-page");
+            if startup_page.as_str() == "last-used" {
+                self.stack
+                    .set_visible_child_name(settings.string("window-selected-page").as_str());
+            } else {
+                self.stack.set_visible_child_name(startup_page.as_str());
+            }
         }
     }
 
@@ -993,6 +1404,7 @@ impl MissionCenterWindow {
         sys_info.set_core_count_affects_percentages(
             settings.boolean("apps-page-core-count-affects-percentages"),
         );
+        sys_info.set_smooth_cpu_usage(settings.boolean("apps-page-smooth-cpu-usage"));
 
         settings.connect_changed(Some("app-update-interval-u64"), |settings, _| {
             let update_speed = settings.uint64("app-update-interval-u64");
@@ -1081,6 +1493,10 @@ impl MissionCenterWindow {
             .split_view
             .set_collapsed(self.imp().should_hide_sidebar());
 
+        if let Some(deep_link) = self.imp().pending_deep_link.take() {
+            self.open_deep_link(deep_link);
+        }
+
         if let Ok(sys_info) = app!().sys_info() {
             sys_info.continue_reading();
         } else {
@@ -1096,7 +1512,47 @@ impl MissionCenterWindow {
 
         let this = self.imp();
 
+        // Needs the process list, which is handed over to the apps page below
+        self.update_low_memory_warning(readings);
+        self.update_foreground_boost_indicator(readings);
+        self.update_insights(readings);
+
+        if this.system_info_requested.take() {
+            SystemInfoDialog::new(readings).present(Some(self));
+        }
+
+        if this.report_requested.take() {
+            Report::new(readings).save(self);
+        }
+
+        if let Some(hosts_overview) = this.hosts_overview.upgrade() {
+            hosts_overview.update_current(&HostSummary::from_readings(readings));
+        }
+
+        if let Some(mini_monitor) = this.mini_monitor.upgrade() {
+            mini_monitor.update_readings(readings);
+        }
+
+        // The history is about this computer, remote hosts would end up mixed into it
+        if let Some(recorder) = this.history_recorder.borrow_mut().as_mut() {
+            if app!().remote_host().is_none() {
+                recorder.record(readings);
+            }
+        }
+
+        // The low memory warning keeps working, everything else stays as it was when paused so
+        // spikes can be looked at without them scrolling away
+        if this.paused.get() {
+            return result;
+        }
+
         result &= this.performance_page.update_readings(readings);
+
+        // Running in the background, the readings leave out what only the other pages show
+        if !self.is_visible() {
+            return result;
+        }
+
         result &= this.apps_page.update_readings(readings);
 
         if !readings.services.is_empty() {
@@ -1106,6 +1562,13 @@ impl MissionCenterWindow {
             this.services_stack_page.set_visible(false);
         }
 
+        if !readings.users.is_empty() {
+            this.users_stack_page.set_visible(true);
+            result &= this.users_page.update_readings(readings);
+        } else {
+            this.users_stack_page.set_visible(false);
+        }
+
         result
     }
 
@@ -1118,4 +1581,409 @@ impl MissionCenterWindow {
 
         result
     }
+
+    pub fn open_deep_link(&self, deep_link: DeepLink) {
+        let imp = self.imp();
+
+        // Pages are only populated once the first readings arrive
+        if imp.loading_box.is_visible() {
+            imp.pending_deep_link.replace(Some(deep_link));
+            return;
+        }
+
+        if let Some(pid) = deep_link.pid {
+            self.show_process(pid);
+        } else if let Some(name) = deep_link.process.as_ref() {
+            self.find_process(name);
+        } else if let Some(page) = deep_link.page.as_ref() {
+            self.show_page(page);
+        }
+    }
+
+    pub fn show_page(&self, page: &str) -> bool {
+        use gtk::glib::*;
+
+        let imp = self.imp();
+
+        let stack_page = match page {
+            "performance" | "performance-page" => "performance-page",
+            "apps" | "apps-page" | "processes" => "apps-page",
+            "services" | "services-page" => "services-page",
+            "users" | "users-page" => "users-page",
+            device => {
+                if !imp.performance_page.select_page(device) {
+                    g_warning!(
+                        "MissionCenter",
+                        "Cannot open page '{}': no such page or device",
+                        device
+                    );
+                    return false;
+                }
+                "performance-page"
+            }
+        };
+
+        self.leave_summary_mode();
+        imp.stack.set_visible_child_name(stack_page);
+
+        true
+    }
+
+    pub fn show_process(&self, pid: u32) -> bool {
+        use gtk::glib::*;
+
+        let imp = self.imp();
+
+        self.leave_summary_mode();
+        imp.stack.set_visible_child_name("apps-page");
+
+        if !imp.apps_page.select_process(pid) {
+            g_warning!("MissionCenter", "Cannot select process {}: not found", pid);
+            return false;
+        }
+
+        true
+    }
+
+    /// Show that the readings are stale while the connection to the gatherer is being restored
+    pub fn set_gatherer_reconnecting(&self, reconnecting: bool) {
+        self.imp().gatherer_banner.set_revealed(reconnecting);
+    }
+
+    /// Search the Services page for `name` and select it
+    pub fn show_service(&self, name: &str) -> bool {
+        use gtk::glib::*;
+
+        let imp = self.imp();
+
+        self.leave_summary_mode();
+        imp.stack.set_visible_child_name("services-page");
+
+        if !imp.search_button.is_active() {
+            let _ = WidgetExt::activate_action(self, "win.toggle-search", None);
+        }
+        imp.header_search_entry.set_text(name);
+
+        if !imp.services_page.select_service(name) {
+            g_warning!("MissionCenter", "Cannot find service '{}'", name);
+            return false;
+        }
+
+        true
+    }
+
+    /// Remember a process or service the user looked at, for the command palette
+    pub fn add_recent_item(&self, item: RecentItem) {
+        let mut recent_items = self.imp().recent_items.borrow_mut();
+        recent_items.retain(|recent| *recent != item);
+        recent_items.insert(0, item);
+        recent_items.truncate(MAX_RECENT_ITEMS);
+    }
+
+    fn palette_commands(&self) -> Vec<Command> {
+        let imp = self.imp();
+
+        let mut commands = vec![];
+
+        for item in imp.recent_items.borrow().iter() {
+            commands.push(match item.clone() {
+                RecentItem::Process(name) => {
+                    Command::new(name.clone(), i18n("Recent Process"), move |window| {
+                        window.find_process(&name);
+                    })
+                }
+                RecentItem::Service(name) => {
+                    Command::new(name.clone(), i18n("Recent Service"), move |window| {
+                        window.show_service(&name);
+                    })
+                }
+            });
+        }
+
+        let pages = i18n("Pages");
+        commands.push(Command::new(i18n("Performance"), &pages, |window| {
+            window.show_page("performance");
+        }));
+        commands.push(Command::new(i18n("Apps"), &pages, |window| {
+            window.show_page("apps");
+        }));
+        if imp.services_stack_page.is_visible() {
+            commands.push(Command::new(i18n("Services"), &pages, |window| {
+                window.show_page("services");
+            }));
+        }
+        if imp.users_stack_page.is_visible() {
+            commands.push(Command::new(i18n("Users"), &pages, |window| {
+                window.show_page("users");
+            }));
+        }
+
+        let devices = i18n("Devices");
+        for (name, heading) in imp.performance_page.pages() {
+            commands.push(Command::new(heading, &devices, move |window| {
+                window.show_page(&name);
+            }));
+        }
+
+        let actions = i18n("Actions");
+        let action = |title: String, action_name: &'static str| {
+            Command::new(title, &actions, move |window| {
+                let _ = WidgetExt::activate_action(window, action_name, None);
+            })
+        };
+        commands.push(action(
+            if imp.paused.get() {
+                i18n("Resume Updates")
+            } else {
+                i18n("Pause Updates")
+            },
+            "win.toggle-pause",
+        ));
+        commands.push(Command::new(i18n("Edit Sidebar"), &actions, |window| {
+            window.show_page("performance");
+            window
+                .imp()
+                .toggle_sidebar_edit_mode_button
+                .set_active(true);
+        }));
+        commands.push(action(i18n("Search"), "win.toggle-search"));
+        commands.push(action(i18n("Insights"), "win.insights"));
+        commands.push(action(i18n("About This System"), "win.system-info"));
+        commands.push(action(i18n("Generate Report"), "win.generate-report"));
+        commands.push(action(i18n("Kernel Log"), "win.kernel-log"));
+        commands.push(action(i18n("Crashes"), "win.crashes"));
+        commands.push(action(i18n("Hosts Overview"), "win.hosts-overview"));
+        commands.push(action(i18n("Mini Monitor"), "win.mini-monitor"));
+        commands.push(action(i18n("Boot Analysis"), "win.boot-analysis"));
+        commands.push(action(i18n("Gatherer Diagnostics"), "win.gatherer-stats"));
+        commands.push(action(i18n("Preferences"), "app.preferences"));
+        commands.push(action(i18n("Keyboard Shortcuts"), "app.keyboard-shortcuts"));
+
+        commands
+    }
+
+    /// Search the Apps page for `name` and select the best match
+    pub fn find_process(&self, name: &str) -> bool {
+        use gtk::glib::*;
+
+        let imp = self.imp();
+
+        self.leave_summary_mode();
+        imp.stack.set_visible_child_name("apps-page");
+
+        // The search narrows the list down to all of the matches
+        if !imp.search_button.is_active() {
+            let _ = WidgetExt::activate_action(self, "win.toggle-search", None);
+        }
+        imp.header_search_entry.set_text(name);
+
+        if !imp.apps_page.select_process_by_name(name) {
+            g_warning!("MissionCenter", "Cannot find process '{}'", name);
+            return false;
+        }
+
+        true
+    }
+
+    fn update_foreground_boost_indicator(&self, readings: &Readings) {
+        let indicator = &self.imp().foreground_boost_indicator;
+
+        let Some(boosted) = &readings.foreground_boost else {
+            indicator.set_visible(false);
+            return;
+        };
+
+        let name = readings
+            .running_processes
+            .get(&boosted.pid)
+            .map(|process| process.name.as_str())
+            .unwrap_or(boosted.unit.as_str());
+        indicator.set_tooltip_text(Some(&i18n_f(
+            "{} has focus and gets more CPU time (weight {} instead of {})",
+            &[
+                name,
+                &boosted.cpu_weight.to_string(),
+                &boosted.original_cpu_weight.to_string(),
+            ],
+        )));
+        indicator.set_visible(true);
+    }
+
+    fn update_insights(&self, readings: &Readings) {
+        let imp = self.imp();
+
+        let mut insights = imp.insights.borrow_mut();
+        if !insights.update(readings) {
+            return;
+        }
+
+        let current = insights.current();
+        imp.insights_button.set_visible(!current.is_empty());
+        imp.insights_button.set_tooltip_text(Some(&ni18n_f(
+            "{} possible problem found",
+            "{} possible problems found",
+            current.len() as u32,
+            &[&current.len().to_string()],
+        )));
+
+        if let Some(dialog) = imp.insights_dialog.upgrade() {
+            dialog.update(current);
+        }
+    }
+
+    // Ending the biggest consumer loses whatever wasn't saved in it, make sure it's the one the
+    // user meant; the banner is updated while the dialog is up, so it holds on to the target
+    fn confirm_end_low_memory_target(&self) {
+        let Some((name, pids)) = self.imp().low_memory_target.borrow().clone() else {
+            return;
+        };
+
+        let dialog = adw::AlertDialog::new(
+            Some(&i18n_f("End {}?", &[&name])),
+            Some(&i18n("Unsaved work in it will be lost.")),
+        );
+        dialog.add_responses(&[("cancel", &i18n("_Cancel")), ("end", &i18n("_End"))]);
+        dialog.set_response_appearance("end", adw::ResponseAppearance::Destructive);
+        dialog.set_default_response(Some("cancel"));
+        dialog.set_close_response("cancel");
+        dialog.connect_response(Some("end"), {
+            let this = self.downgrade();
+            move |_, _| {
+                if let Ok(magpie_client) = app!().sys_info() {
+                    magpie_client.terminate_processes(pids.clone());
+                }
+                if let Some(this) = this.upgrade() {
+                    let imp = this.imp();
+                    imp.low_memory_target.replace(None);
+                    imp.low_memory_banner.set_revealed(false);
+                }
+            }
+        });
+        dialog.present(Some(self));
+    }
+
+    fn update_low_memory_warning(&self, readings: &Readings) {
+        // Percentage of time, over the last 10 seconds, in which all tasks were stalled waiting for
+        // memory; at this point the system is thrashing and the OOM killer is not far off
+        const SHOW_THRESHOLD: f32 = 10.;
+        const HIDE_THRESHOLD: f32 = 2.;
+        const MAX_LISTED_CONSUMERS: usize = 3;
+
+        let imp = self.imp();
+        let banner = &imp.low_memory_banner;
+
+        let full_avg10 = readings
+            .mem_pressure
+            .and_then(|pressure| pressure.full)
+            .map(|full| full.avg10)
+            .unwrap_or(0.);
+
+        let show = settings!().boolean("window-low-memory-warning")
+            && if banner.is_revealed() {
+                full_avg10 >= HIDE_THRESHOLD
+            } else {
+                full_avg10 >= SHOW_THRESHOLD
+            };
+        if !show {
+            if banner.is_revealed() {
+                app!().withdraw_notification("low-memory");
+            }
+            banner.set_revealed(false);
+            imp.low_memory_target.replace(None);
+            return;
+        }
+
+        let processes = &readings.running_processes;
+        let app_pids = readings
+            .running_apps
+            .values()
+            .flat_map(|app| app.pids.iter().copied())
+            .collect::<HashSet<_>>();
+
+        // Group processes by app where possible, that's what users recognize and what "End Task"
+        // on the Apps page acts on
+        let mut consumers = readings
+            .running_apps
+            .values()
+            .map(|app| {
+                let memory = app
+                    .pids
+                    .iter()
+                    .filter_map(|pid| processes.get(pid))
+                    .map(|process| process.usage_stats.memory_usage)
+                    .sum::<u64>();
+                (app.name.clone(), app.pids.clone(), memory)
+            })
+            .collect::<Vec<_>>();
+        consumers.extend(
+            processes
+                .values()
+                .filter(|process| !app_pids.contains(&process.pid))
+                .map(|process| {
+                    (
+                        process.name.clone(),
+                        vec![process.pid],
+                        process.usage_stats.memory_usage,
+                    )
+                }),
+        );
+        consumers.sort_unstable_by(|(_, _, m1), (_, _, m2)| m2.cmp(m1));
+        consumers.truncate(MAX_LISTED_CONSUMERS);
+
+        let Some((top_name, top_pids, _)) = consumers.first() else {
+            banner.set_revealed(false);
+            return;
+        };
+
+        let settings = settings!();
+        let consumer_list = consumers
+            .iter()
+            .map(|(name, _, memory)| {
+                format!(
+                    "{} ({})",
+                    name,
+                    crate::to_human_readable_nice(
+                        *memory as f32,
+                        &DataType::MemoryBytes,
+                        &settings
+                    )
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        banner.set_title(&i18n_f(
+            "The system is running out of memory. Biggest consumers: {}",
+            &[&consumer_list],
+        ));
+        banner.set_button_label(Some(&i18n_f("End {}", &[top_name])));
+        imp.low_memory_target
+            .replace(Some((top_name.clone(), top_pids.clone())));
+
+        if !banner.is_revealed() {
+            glib::g_warning!(
+                "MissionCenter",
+                "Memory pressure is at {:.1}%, the system is about to run out of memory",
+                full_avg10
+            );
+            banner.set_revealed(true);
+
+            // Nobody sees the banner while running in the background
+            if !self.is_visible() {
+                let notification =
+                    gio::Notification::new(&i18n("The system is running out of memory"));
+                notification.set_body(Some(&i18n_f("Biggest consumers: {}", &[&consumer_list])));
+                notification.set_priority(gio::NotificationPriority::Urgent);
+                notification.set_default_action("app.show-window");
+                app!().send_notification(Some("low-memory"), &notification);
+            }
+        }
+    }
+
+    fn leave_summary_mode(&self) {
+        let imp = self.imp();
+        if imp.performance_page.summary_mode() {
+            let _ = WidgetExt::activate_action(&*imp.performance_page, "graph.summary", None);
+        }
+    }
 }